@@ -0,0 +1,186 @@
+//! Append-only JSONL record of every tool execution.
+//!
+//! Unlike tracing, which is for debugging and may be filtered or
+//! rotated away, the audit log is a durable, parseable account of what
+//! an agent did: one [`ToolAuditEntry`] line per call to
+//! [`crate::Agent`]'s tool executor, written once the call returns.
+//! Sub-agents inherit their parent's log, so a session's file covers
+//! the whole hierarchy.
+//!
+//! Each line is one `write` on a file opened in append mode, so
+//! concurrent calls (and concurrent `aj` processes sharing the file)
+//! never interleave partial lines. The file is opened per entry rather
+//! than held open, which keeps an agent that never runs a tool from
+//! creating it and lets the file be moved or rotated between calls.
+//! Writing is synchronous: one small append is noise next to the tool
+//! call it records. A failed write is logged and otherwise ignored —
+//! auditing never fails a turn.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use aj_models::types::UserContent;
+use serde::Serialize;
+
+use crate::events::AgentId;
+use crate::tool::ToolOutcome;
+
+/// Handle on a tool-audit log file. Cheap to clone; clones append to
+/// the same file.
+#[derive(Clone, Debug)]
+pub struct ToolAuditLog {
+    path: Arc<PathBuf>,
+    /// Serializes this process's appends. `O_APPEND` already keeps a
+    /// single write whole; the lock additionally keeps the lines of
+    /// parallel tool calls in completion order.
+    write_lock: Arc<StdMutex<()>>,
+}
+
+/// One line of the audit log.
+#[derive(Debug, Serialize)]
+pub struct ToolAuditEntry<'a> {
+    /// When the call finished, RFC 3339 in UTC.
+    pub timestamp: String,
+    pub agent_id: AgentId,
+    pub call_id: &'a str,
+    pub tool: &'a str,
+    pub input: &'a serde_json::Value,
+    /// Bytes of text returned to the model, plus the base64 length of
+    /// any image attachments. `0` when the call failed outright.
+    pub result_bytes: usize,
+    pub duration_ms: u64,
+    /// Whether the result was reported to the model as an error; `true`
+    /// as well when the tool failed without producing a result.
+    pub is_error: bool,
+    /// The failure of a call that produced no result at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ToolAuditLog {
+    /// A log appending to `path`. Nothing is created until the first
+    /// entry; missing parent directories are created then.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Arc::new(path.into()),
+            write_lock: Arc::new(StdMutex::new(())),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append the entry for one finished tool call.
+    pub(crate) fn record<E: std::fmt::Display>(
+        &self,
+        agent_id: AgentId,
+        call_id: &str,
+        tool: &str,
+        input: &serde_json::Value,
+        duration: Duration,
+        result: Result<&ToolOutcome, &E>,
+    ) {
+        let entry = ToolAuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            agent_id,
+            call_id,
+            tool,
+            input,
+            result_bytes: result.map_or(0, |outcome| result_bytes(&outcome.content)),
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            is_error: result.map_or(true, |outcome| outcome.is_error),
+            error: result.err().map(ToString::to_string),
+        };
+        if let Err(err) = self.append(&entry) {
+            tracing::warn!(path = %self.path.display(), %err, "failed to write tool audit entry");
+        }
+    }
+
+    fn append(&self, entry: &ToolAuditEntry<'_>) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&*self.path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+fn result_bytes(content: &[UserContent]) -> usize {
+    content
+        .iter()
+        .map(|block| match block {
+            UserContent::Text(text) => text.text.len(),
+            UserContent::Image(image) => image.data.len(),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::ToolDetails;
+
+    #[test]
+    fn appends_one_json_line_per_call() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit").join("tools.jsonl");
+        let log = ToolAuditLog::new(&path);
+        let input = serde_json::json!({"command": "ls"});
+        let outcome = ToolOutcome {
+            content: vec![UserContent::text("a\nb")],
+            details: ToolDetails::Text {
+                summary: String::new(),
+                body: String::new(),
+            },
+            is_error: false,
+        };
+        log.record::<String>(
+            AgentId::Main,
+            "call_1",
+            "bash",
+            &input,
+            Duration::from_millis(12),
+            Ok(&outcome),
+        );
+        log.record(
+            AgentId::Sub(2),
+            "call_2",
+            "read_file",
+            &input,
+            Duration::ZERO,
+            Err(&"tool not found!".to_string()),
+        );
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["agent_id"], "main");
+        assert_eq!(lines[0]["tool"], "bash");
+        assert_eq!(lines[0]["input"]["command"], "ls");
+        assert_eq!(lines[0]["result_bytes"], 3);
+        assert_eq!(lines[0]["duration_ms"], 12);
+        assert_eq!(lines[0]["is_error"], false);
+        assert!(lines[0].get("error").is_none());
+        assert_eq!(lines[1]["agent_id"]["sub"], 2);
+        assert_eq!(lines[1]["is_error"], true);
+        assert_eq!(lines[1]["error"], "tool not found!");
+    }
+}
@@ -0,0 +1,184 @@
+//! What a session did on disk: the files its tools wrote and how many
+//! commands it ran through `bash` and `shell_session`.
+//!
+//! The agent records every finished (not cancelled) tool call into the
+//! session's [`ChangeLog`]. A call to a tool that isn't read-only and
+//! whose result is a [`ToolDetails::Diff`] counts as a write of that
+//! diff's path: a creation when there was no prior content, an edit
+//! otherwise. A `bash` or `shell_session` call counts as one command,
+//! whatever its exit status. Sub-agents share their parent's log, so
+//! the main agent's [`crate::Agent::change_log`] covers the whole
+//! session.
+//!
+//! Deletions and writes made from inside a shell are invisible here;
+//! the log only knows what the file tools reported.
+
+use crate::tool::{ToolDetails, ToolOutcome};
+
+/// How a write changed its file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileOperation {
+    /// The file had no prior content.
+    Created,
+    /// The file existed and was rewritten or edited.
+    Edited,
+}
+
+/// One recorded write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileChange {
+    /// The path as the tool displayed it (relative to the working
+    /// directory when inside it).
+    pub path: String,
+    pub operation: FileOperation,
+    /// The tool that made the write.
+    pub tool: String,
+    pub lines_before: usize,
+    pub lines_after: usize,
+}
+
+/// Every write and shell command of a session, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChangeLog {
+    pub files: Vec<FileChange>,
+    pub bash_commands: usize,
+}
+
+/// All writes to one path, folded; see [`ChangeLog::by_file`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSummary {
+    pub path: String,
+    /// The first write's operation, so a file created and then edited
+    /// reads as created.
+    pub operation: FileOperation,
+    pub writes: usize,
+    /// Line count before the first write.
+    pub lines_before: usize,
+    /// Line count after the last write.
+    pub lines_after: usize,
+}
+
+impl ChangeLog {
+    /// Whether nothing was written and no command was run.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.bash_commands == 0
+    }
+
+    /// Record a finished call to `tool`.
+    pub(crate) fn record(&mut self, tool: &str, read_only: bool, outcome: &ToolOutcome) {
+        if tool == "bash" || tool == "shell_session" {
+            self.bash_commands += 1;
+            return;
+        }
+        if read_only || outcome.is_error {
+            return;
+        }
+        if let ToolDetails::Diff {
+            path,
+            before,
+            after,
+        } = &outcome.details
+        {
+            self.files.push(FileChange {
+                path: path.clone(),
+                operation: if before.is_empty() {
+                    FileOperation::Created
+                } else {
+                    FileOperation::Edited
+                },
+                tool: tool.to_string(),
+                lines_before: before.lines().count(),
+                lines_after: after.lines().count(),
+            });
+        }
+    }
+
+    /// The writes folded per path, in order of each path's first
+    /// write.
+    pub fn by_file(&self) -> Vec<FileSummary> {
+        let mut summaries: Vec<FileSummary> = Vec::new();
+        for change in &self.files {
+            match summaries.iter_mut().find(|s| s.path == change.path) {
+                Some(summary) => {
+                    summary.writes += 1;
+                    summary.lines_after = change.lines_after;
+                }
+                None => summaries.push(FileSummary {
+                    path: change.path.clone(),
+                    operation: change.operation,
+                    writes: 1,
+                    lines_before: change.lines_before,
+                    lines_after: change.lines_after,
+                }),
+            }
+        }
+        summaries
+    }
+}
+
+impl FileSummary {
+    /// One line for a changelog, e.g. `created (42 lines)` or
+    /// `edited 3 times (120 -> 134 lines)`.
+    pub fn describe(&self) -> String {
+        let times = match self.writes {
+            1 => String::new(),
+            n => format!(" {n} times"),
+        };
+        match self.operation {
+            FileOperation::Created => format!(
+                "created{times} ({} {})",
+                self.lines_after,
+                plural(self.lines_after)
+            ),
+            FileOperation::Edited => format!(
+                "edited{times} ({} -> {} {})",
+                self.lines_before,
+                self.lines_after,
+                plural(self.lines_after)
+            ),
+        }
+    }
+}
+
+fn plural(lines: usize) -> &'static str {
+    if lines == 1 { "line" } else { "lines" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(path: &str, before: &str, after: &str) -> ToolOutcome {
+        ToolOutcome {
+            content: Vec::new(),
+            details: ToolDetails::Diff {
+                path: path.to_string(),
+                before: before.to_string(),
+                after: after.to_string(),
+            },
+            is_error: false,
+        }
+    }
+
+    #[test]
+    fn records_writes_and_commands_and_folds_per_file() {
+        let mut log = ChangeLog::default();
+        log.record("write_file", false, &diff("a.rs", "", "x\ny\n"));
+        log.record("bash", false, &diff("ignored", "", ""));
+        log.record("shell_session", false, &diff("ignored", "", ""));
+        log.record("edit_file", false, &diff("b.rs", "1\n", "1\n2\n"));
+        log.record("edit_file", false, &diff("a.rs", "x\ny\n", "x\n"));
+        // Read-only previews and failed writes change nothing.
+        log.record("preview_edit", true, &diff("c.rs", "1\n", "2\n"));
+        let mut failed = diff("d.rs", "1\n", "2\n");
+        failed.is_error = true;
+        log.record("edit_file", false, &failed);
+
+        assert_eq!(log.bash_commands, 2);
+        let files = log.by_file();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.rs");
+        assert_eq!(files[0].describe(), "created 2 times (1 line)");
+        assert_eq!(files[1].describe(), "edited (1 -> 2 lines)");
+    }
+}
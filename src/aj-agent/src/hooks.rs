@@ -1,8 +1,12 @@
 //! Single-slot hook surface for agent runtime extension.
 //!
-//! Three hooks bracket the per-turn / per-tool flow inside
+//! Four hooks bracket the per-turn / per-tool flow inside
 //! [`crate::Agent::execute_turn`]:
 //!
+//! - [`BeforeToolBatchHook`] — fires once per assistant message that
+//!   requested tools, before any call in the batch starts. Sees every
+//!   call at once and can short-circuit any subset of them (batch
+//!   review / approval).
 //! - [`BeforeToolCallHook`] — fires after [`crate::events::AgentEvent::ToolExecutionStart`]
 //!   and before the tool's `execute` closure runs. Can mutate the
 //!   args (e.g. redaction) or short-circuit the call with a
@@ -17,7 +21,7 @@
 //!   `true` ends the turn with no follow-up call (e.g. budget /
 //!   context-window guard).
 //!
-//! All three are stored as `Option<Box<dyn Fn... + Send + Sync>>` —
+//! All four are stored as `Option<Box<dyn Fn... + Send + Sync>>` —
 //! one slot per hook, replacing on `set_*`. No registry, no
 //! priority order; if a host wants to chain multiple effects it
 //! composes them into a single closure.
@@ -28,6 +32,7 @@
 //! next agent step. Hosts that need fire-and-forget side effects
 //! should spawn a task inside the hook body.
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -51,6 +56,33 @@ pub struct ToolCallContext<'a> {
     pub tool_name: &'a str,
 }
 
+/// One call of the batch passed to [`BeforeToolBatchHook`], in the
+/// order the model requested it.
+#[derive(Debug, Clone)]
+pub struct ToolBatchCall<'a> {
+    pub call_id: &'a str,
+    pub tool_name: &'a str,
+    pub args: &'a Value,
+}
+
+/// Decision returned by [`BeforeToolBatchHook`].
+pub enum BeforeToolBatchOutcome {
+    /// Run the batch normally.
+    Proceed,
+    /// Skip the listed calls, keyed by call id; each is finalized
+    /// with its pre-baked outcome exactly as a
+    /// [`BeforeToolCallOutcome::ShortCircuit`] would be. Calls not
+    /// listed run normally.
+    ShortCircuit {
+        outcomes: HashMap<String, ToolOutcome>,
+    },
+}
+
+/// Closure invoked once per tool batch, before any of its calls run.
+pub type BeforeToolBatchHook = Arc<
+    dyn for<'a> Fn(&'a [ToolBatchCall<'a>]) -> HookFuture<'a, BeforeToolBatchOutcome> + Send + Sync,
+>;
+
 /// Decision returned by [`BeforeToolCallHook`].
 ///
 /// `Proceed` lets the tool's own `execute` run with the (possibly
@@ -5,19 +5,24 @@
 // [`bus::EventBus`]; the binary subscribes a renderer listener and a
 // persistence listener (the latter lives in `aj-session`) and owns
 // the readline loop, log management, and history display.
+pub mod audit;
 pub mod bus;
+pub mod changes;
 pub mod error;
 pub mod events;
 pub mod hooks;
 pub mod message;
+pub mod output_cache;
+pub mod preload;
 pub mod projection;
 pub mod queue;
+pub mod recent_calls;
 pub mod tool;
 pub mod types;
 
 pub use error::BoxError;
 
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Mutex as StdMutex;
 use std::time::{Duration, Instant};
@@ -27,20 +32,25 @@ use aj_models::provider::Provider;
 use aj_models::registry::ModelInfo;
 use aj_models::streaming::{AssistantMessageEvent, AssistantMessageEventStream};
 use aj_models::types::{
-    AssistantContent, AssistantMessage, Context, ErrorCategory, Message, SimpleStreamOptions,
-    Speed, StopReason, StreamOptions, ThinkingLevel, ToolCall,
+    AssistantContent, AssistantMessage, CacheRetention, Context, ErrorCategory, Message,
+    SimpleStreamOptions, Speed, StopReason, StreamOptions, ThinkingLevel, ToolCall,
     ToolDefinition as UnifiedToolDefinition, ToolResultMessage, Usage, UserContent, UserMessage,
 };
 
+use crate::audit::ToolAuditLog;
 use crate::bus::{EventBus, Listener, SubscriptionHandle};
+use crate::changes::ChangeLog;
 use crate::events::{AgentEvent, AgentId, AgentSettings};
 use crate::message::AgentMessage;
+use crate::output_cache::OutputCache;
+use crate::preload::{PRELOAD_INTRO, Preload};
 use crate::projection::transcript_to_messages;
 use crate::queue::{MessageQueues, PendingKind};
+use crate::recent_calls::RecentCalls;
 use crate::tool::{
     ErasedToolDefinition, ExecutionMode, SpawnMode, SpawnResult, SpawnedAgent, StartedTask,
     TaskEventSink, TaskId, TaskKind, TaskNotice, TaskOutputSource, TaskRead, TaskStatus, TodoItem,
-    ToolContext, ToolDetails, ToolOutcome,
+    ToolContext, ToolDetails, ToolInterrupt, ToolOutcome,
 };
 use crate::types::TokenUsage;
 use futures::StreamExt;
@@ -90,6 +100,19 @@ pub struct AgentSeed {
     pub sub_agent_counter: usize,
 }
 
+/// A model an overloaded request falls back to. Built by the host the
+/// same way as the primary's handle (see [`Agent::with_provider`]) and
+/// installed with [`Agent::set_fallback_models`].
+#[derive(Clone)]
+pub struct FallbackModel {
+    pub provider: Arc<dyn Provider>,
+    pub model_info: Arc<ModelInfo>,
+    /// Baseline options for this model. The prompt-cache key is taken
+    /// from the agent's own options at inference time, so the host
+    /// need not stamp one here.
+    pub stream_options: StreamOptions,
+}
+
 pub struct Agent {
     /// The fully-assembled system prompt for the current run.
     /// Populated by [`Agent::seed_session`] (resume path or fresh
@@ -98,12 +121,24 @@ pub struct Agent {
     /// (none sends an empty system block or errors), so an unseeded
     /// agent degrades to a promptless turn rather than panicking.
     assembled_system_prompt: String,
+    /// Ephemeral steering text appended to the system prompt, in its
+    /// own `<turn-context>` block, for every inference of the next
+    /// top-level run only. Set by [`Agent::set_turn_system_addendum`]
+    /// and cleared when that run ends; never persisted and never
+    /// inherited by sub-agents.
+    turn_system_addendum: Option<String>,
     tool_definitions: HashMap<String, ErasedToolDefinition>,
     tools: Vec<UnifiedToolDefinition>,
     /// Names of builtin tools to exclude when spawning subagents.
     /// Mirrors the filter applied to the top-level agent so
     /// subagents inherit the same tool restrictions.
     disabled_tools: Vec<String>,
+    /// Tools switched off for the running session by
+    /// [`Agent::set_session_disabled_tools`]. Unlike `disabled_tools`
+    /// they stay registered: they are only left out of the tool list
+    /// sent to the model and refused if called, so switching one back
+    /// on needs no rebuild.
+    session_disabled_tools: BTreeSet<String>,
     /// Unified provider handle used by the inference loop. Supplied
     /// directly by [`Agent::with_provider`] / [`Agent::set_provider`].
     /// The inference loop only ever reaches for this field; sub-agents
@@ -122,6 +157,10 @@ pub struct Agent {
     /// top inside `run_inference_streaming`; everything else flows
     /// through verbatim.
     stream_options: StreamOptions,
+    /// Models tried in order when the active one stays overloaded
+    /// after its retries. Set via [`Agent::set_fallback_models`];
+    /// empty (the default) surfaces the overload as an error.
+    fallback_models: Vec<FallbackModel>,
     session_state: SessionState,
     default_thinking: Option<ThinkingConfig>,
     /// Inference speed mode reported on sub-agent spawn events and
@@ -161,6 +200,14 @@ pub struct Agent {
     /// hook" — the tool's own `execute` runs unconditionally with
     /// the model-supplied arguments.
     before_tool_call: Option<hooks::BeforeToolCallHook>,
+    /// Optional hook fired once per tool batch, before any of its
+    /// calls run. Set via [`Agent::set_before_tool_batch`]; can
+    /// short-circuit any subset of the batch.
+    before_tool_batch: Option<hooks::BeforeToolBatchHook>,
+    /// Stops the running tool group without ending the turn. A fresh
+    /// handle by default; a frontend swaps in its own clone via
+    /// [`Agent::set_tool_interrupt`].
+    tool_interrupt: ToolInterrupt,
     /// Optional hook fired after every tool call returns. Set via
     /// [`Agent::set_after_tool_call`]; can rewrite the outcome's
     /// `content`, `details`, or `is_error` before the bus event
@@ -179,6 +226,24 @@ pub struct Agent {
     /// later in the same thread restores image visibility for
     /// future turns. Set via [`Agent::set_block_images`].
     block_images: bool,
+    /// Where every tool execution is recorded, if anywhere. Set via
+    /// [`Agent::set_tool_audit_log`].
+    tool_audit: Option<ToolAuditLog>,
+    /// What to do when an inference finishes with neither text nor
+    /// tool calls: `false` ends the run with a notice saying so,
+    /// `true` asks the model to continue, once per run. Set via
+    /// [`Agent::set_nudge_empty_responses`].
+    nudge_empty_responses: bool,
+    /// What to do when a stream fails with a retryable error after
+    /// content already arrived: `false` stops the run with the
+    /// partial response kept, `true` restarts the request like a
+    /// failure before any content. Set via
+    /// [`Agent::set_retry_partial_streams`].
+    retry_partial_streams: bool,
+    /// Whether a top-level prompt identical to the previous user
+    /// message is dropped instead of sent. Set via
+    /// [`Agent::set_skip_repeated_prompts`].
+    skip_repeated_prompts: bool,
     /// Shared registry into which this agent inserts each sub-agent it
     /// spawns, keyed by `Sub(n)` index, so the handle outlives the
     /// initial `agent` tool call. Default-empty; the binary injects a
@@ -263,12 +328,15 @@ impl Agent {
 
         Self {
             assembled_system_prompt: String::new(),
+            turn_system_addendum: None,
             tool_definitions,
             tools: api_tools,
             disabled_tools,
+            session_disabled_tools: BTreeSet::new(),
             provider,
             model_info,
             stream_options,
+            fallback_models: Vec::new(),
             session_state,
             default_thinking,
             speed: None,
@@ -277,9 +345,15 @@ impl Agent {
             cancellation: CancellationToken::new(),
             transcript: Vec::new(),
             before_tool_call: None,
+            before_tool_batch: None,
+            tool_interrupt: ToolInterrupt::new(),
             after_tool_call: None,
             should_stop_after_turn: None,
             block_images: false,
+            tool_audit: None,
+            nudge_empty_responses: false,
+            retry_partial_streams: false,
+            skip_repeated_prompts: false,
             sub_agent_registry: SubAgentRegistry::default(),
             task_registry: TaskRegistry::default(),
             message_queues: MessageQueues::default(),
@@ -343,6 +417,83 @@ impl Agent {
         self.block_images = block;
     }
 
+    /// Append a line per tool execution to `log` (see
+    /// [`crate::audit`]), or stop auditing with `None`. Sub-agents
+    /// inherit the parent's log at spawn time.
+    pub fn set_tool_audit_log(&mut self, log: Option<ToolAuditLog>) {
+        self.tool_audit = log;
+    }
+
+    /// Names of the tools this agent was built with, in registration
+    /// order, including any switched off by
+    /// [`Agent::set_session_disabled_tools`].
+    pub fn tool_names(&self) -> Vec<String> {
+        self.tools.iter().map(|tool| tool.name.clone()).collect()
+    }
+
+    /// Switch the named tools off for the running session, replacing
+    /// the previous set. Takes effect at the next inference: the tools
+    /// are left out of the list sent to the model, a call to one
+    /// anyway gets an `is_error` result, and sub-agents spawned from
+    /// here on don't get them. Names this agent doesn't have are
+    /// ignored.
+    pub fn set_session_disabled_tools(&mut self, names: BTreeSet<String>) {
+        self.session_disabled_tools = names;
+    }
+
+    /// Choose how an empty assistant response (no text, no tool
+    /// calls) is handled. By default the run ends and a
+    /// [`AgentEvent::Notice`] tells the user the model returned
+    /// nothing; with `nudge` set the agent instead appends a short
+    /// user message asking the model to continue and runs one more
+    /// inference. Only one nudge is sent per run, so a model that
+    /// keeps answering with nothing still ends with the notice.
+    pub fn set_nudge_empty_responses(&mut self, nudge: bool) {
+        self.nudge_empty_responses = nudge;
+    }
+
+    /// Choose how a stream that fails with a retryable error
+    /// (transient drop, overload) is recovered. A failure before any
+    /// content always restarts the request with backoff. By default a
+    /// failure after content arrived does not: the partial assistant
+    /// message is kept on the transcript (errored turns are never
+    /// replayed to the model), an [`AgentEvent::Warning`] says so, and
+    /// the run ends with [`TurnError::Recoverable`] so the user can
+    /// decide how to go on. With `restart` set such a failure restarts
+    /// the request too, discarding the partial.
+    pub fn set_retry_partial_streams(&mut self, restart: bool) {
+        self.retry_partial_streams = restart;
+    }
+
+    /// Drop a top-level prompt whose text repeats the previous user
+    /// message (see [`Agent::repeats_last_prompt`]) instead of sending
+    /// it again. A skipped prompt runs no inference and leaves the
+    /// transcript untouched; an [`AgentEvent::Warning`] says so. Meant
+    /// for unattended runs, where there is nobody to ask; an
+    /// interactive host should check [`Agent::repeats_last_prompt`]
+    /// itself and confirm with the user instead.
+    pub fn set_skip_repeated_prompts(&mut self, skip: bool) {
+        self.skip_repeated_prompts = skip;
+    }
+
+    /// Whether `text` repeats the most recent user message on the
+    /// transcript, comparing its leading text block with surrounding
+    /// whitespace trimmed. Attachments are not compared, so a repeated
+    /// prompt that `@`-mentions a file that has since changed still
+    /// counts as a repeat.
+    pub fn repeats_last_prompt(&self, text: &str) -> bool {
+        let last = self
+            .transcript
+            .iter()
+            .rev()
+            .find_map(|m| match m.as_wire() {
+                Some(Message::User(user)) => Some(user),
+                _ => None,
+            });
+        last.and_then(|user| prompt_text(&user.content))
+            .is_some_and(|last| last.trim() == text.trim())
+    }
+
     /// Inject the shared sub-agent registry.
     ///
     /// The binary calls this on the main agent so the agent and the
@@ -456,6 +607,13 @@ impl Agent {
         self.session_state.sub_agent_usage()
     }
 
+    /// Snapshot of the files written and `bash` commands run so far
+    /// in this session, sub-agents included. Backs the binary's
+    /// `/summary` command and end-of-session summary.
+    pub fn change_log(&self) -> ChangeLog {
+        self.session_state.change_log()
+    }
+
     /// Borrow the agent's in-memory transcript. The binary uses
     /// this on shutdown to decide whether to print the resume hint
     /// (only when the agent observed at least one message) and on
@@ -503,6 +661,7 @@ impl Agent {
     /// before it is shared or drives its first turn.
     pub fn seed_session(&mut self, seed: AgentSeed) {
         self.transcript = seed.transcript;
+        self.session_state.clear_recent_calls();
         if let Some(prompt) = seed.assembled_system_prompt {
             self.assembled_system_prompt = prompt;
         }
@@ -520,6 +679,9 @@ impl Agent {
     /// sub-agent counter are untouched.
     pub fn reseed_transcript(&mut self, transcript: Vec<AgentMessage>) {
         self.transcript = transcript;
+        // Earlier results may have been compacted away, so don't
+        // point back at them.
+        self.session_state.clear_recent_calls();
     }
 
     /// Install a hook fired before every tool call, replacing any
@@ -533,6 +695,22 @@ impl Agent {
         self.before_tool_call = hook;
     }
 
+    /// Install a hook fired once per tool batch, replacing any
+    /// previous hook. It sees every call the assistant message
+    /// requested before any of them starts, so a host can review or
+    /// approve the batch as a whole. See
+    /// [`hooks::BeforeToolBatchHook`]; short-circuited calls skip the
+    /// per-call before hook and the tool itself.
+    pub fn set_before_tool_batch(&mut self, hook: Option<hooks::BeforeToolBatchHook>) {
+        self.before_tool_batch = hook;
+    }
+
+    /// Share `interrupt` with the agent so the holder of a clone can
+    /// stop the tools this agent runs. See [`ToolInterrupt`].
+    pub fn set_tool_interrupt(&mut self, interrupt: ToolInterrupt) {
+        self.tool_interrupt = interrupt;
+    }
+
     /// Install a hook fired after every tool call returns, replacing
     /// any previous hook. The hook may mutate the [`ToolOutcome`] in
     /// place before [`crate::events::AgentEvent::ToolExecutionEnd`]
@@ -558,6 +736,36 @@ impl Agent {
         &self.assembled_system_prompt
     }
 
+    /// Attach transient system context (e.g. "focus on the auth
+    /// module") to the next top-level run, replacing any addendum not
+    /// yet consumed. Every inference of that run sees it after the
+    /// assembled prompt in a `<turn-context>` block; the run's end
+    /// clears it, so it neither persists across turns nor lands in the
+    /// transcript. `None` drops a pending addendum.
+    pub fn set_turn_system_addendum(&mut self, addendum: Option<String>) {
+        self.turn_system_addendum = addendum;
+    }
+
+    /// The tool list sent with the next inference: every registered
+    /// tool except those switched off for the session.
+    fn inference_tools(&self) -> Vec<UnifiedToolDefinition> {
+        self.tools
+            .iter()
+            .filter(|tool| !self.session_disabled_tools.contains(&tool.name))
+            .cloned()
+            .collect()
+    }
+
+    /// The system prompt sent with the next inference: the assembled
+    /// prompt plus the pending turn addendum, if any.
+    fn inference_system_prompt(&self) -> String {
+        let mut prompt = self.assembled_system_prompt.clone();
+        if let Some(addendum) = &self.turn_system_addendum {
+            prompt.push_str(&format!("\n\n<turn-context>\n{addendum}\n</turn-context>"));
+        }
+        prompt
+    }
+
     /// Run a single, bus-silent completion against the agent's provider
     /// and return the concatenated assistant text. Does not touch the
     /// transcript, emits no events, and does not accumulate usage.
@@ -621,6 +829,73 @@ impl Agent {
         }
     }
 
+    /// Send a minimal request carrying the system prompt and tool list,
+    /// so the provider's prompt cache holds that prefix before the
+    /// first real turn and the turn reads it instead of writing it.
+    ///
+    /// The request asks for a single output token with thinking off
+    /// and is not added to the transcript. Its usage is reported like
+    /// a turn's: one [`AgentEvent::UsageUpdate`] and a share of
+    /// [`Agent::accumulated_usage`]. Returns `Ok(false)` without a
+    /// request when caching is off ([`CacheRetention::None`]) or there
+    /// is no system prompt to cache. Honors `cancel`.
+    pub async fn prewarm_cache(&self, cancel: CancellationToken) -> Result<bool, TurnError> {
+        let system_prompt = self.inference_system_prompt();
+        if self.stream_options.cache_retention == CacheRetention::None || system_prompt.is_empty() {
+            return Ok(false);
+        }
+        let context = Context {
+            system_prompt: Some(system_prompt),
+            messages: vec![Message::User(UserMessage::text(PREWARM_PROMPT.to_string()))],
+            tools: self.inference_tools(),
+        };
+        let mut base = self.stream_options.clone();
+        base.cancel = Some(cancel);
+        base.max_tokens = Some(1);
+        let options = SimpleStreamOptions {
+            base,
+            reasoning: None,
+        };
+
+        let mut stream = self
+            .provider
+            .stream_simple(&self.model_info, &context, &options);
+        while stream.next().await.is_some() {}
+        let message = stream.result().await;
+        match message.stop_reason {
+            StopReason::Aborted => return Err(TurnError::Aborted),
+            StopReason::Error => {
+                let detail = message
+                    .error
+                    .map(|e| e.message)
+                    .unwrap_or_else(|| "cache prewarm failed".to_string());
+                return Err(TurnError::Recoverable(detail.into()));
+            }
+            _ => {}
+        }
+
+        let accumulated = self.session_state.accumulated_usage();
+        let usage = &message.usage;
+        self.bus
+            .emit(AgentEvent::UsageUpdate {
+                agent_id: self.agent_id,
+                usage: TokenUsage {
+                    accumulated_input: accumulated.input,
+                    turn_input: usage.input,
+                    accumulated_output: accumulated.output,
+                    turn_output: usage.output,
+                    accumulated_cache_write: accumulated.cache_write,
+                    turn_cache_write: usage.cache_write,
+                    accumulated_cache_read: accumulated.cache_read,
+                    turn_cache_read: usage.cache_read,
+                },
+            })
+            .await
+            .map_err(TurnError::Fatal)?;
+        self.session_state.accumulate_usage(usage);
+        Ok(true)
+    }
+
     /// Borrow the registry-resolved [`ModelInfo`] this agent is
     /// currently running against.
     ///
@@ -662,6 +937,25 @@ impl Agent {
         self.stream_options = stream_options;
     }
 
+    /// Replace the fallback chain: the models an inference moves to,
+    /// in order, when the active model still answers `overloaded`
+    /// after the retry budget is spent. The switch holds for the rest
+    /// of the run (the prompt and its tool continuations); the next
+    /// run starts on the primary again. Each switch is announced with
+    /// an [`AgentEvent::Warning`]. Sub-agents spawned afterwards
+    /// inherit the chain.
+    ///
+    /// The transcript is replayed as-is, so conversation state limits
+    /// what a switch keeps: thinking-block signatures only stay valid
+    /// within one provider, and
+    /// [`aj_models::transform::transform_messages`] demotes earlier
+    /// thinking to plain text when the fallback belongs to another
+    /// provider. A fallback with a smaller context window can also
+    /// reject a long transcript outright.
+    pub fn set_fallback_models(&mut self, models: Vec<FallbackModel>) {
+        self.fallback_models = models;
+    }
+
     /// Borrow the agent's current default thinking configuration.
     ///
     /// `None` means "no extended thinking". The selector overlays in
@@ -746,6 +1040,118 @@ impl Agent {
             .await
     }
 
+    /// Append preloaded context and synthetic tool results to the
+    /// transcript without running an inference; see [`crate::preload`]
+    /// for the message shape. Each message is announced with a
+    /// `MessageStart` / `MessageEnd` pair, and each tool result also
+    /// with a `ToolExecutionStart` / `ToolExecutionEnd` bracket, exactly
+    /// as a live turn would.
+    ///
+    /// Everything is checked before anything is appended: a tool this
+    /// agent doesn't have is [`TurnError::Recoverable`] and leaves the
+    /// transcript untouched. An empty `entries` is a no-op.
+    pub async fn preload(&mut self, entries: Vec<Preload>) -> Result<(), TurnError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let tool_names = self.tool_names();
+        let mut context = vec![PRELOAD_INTRO.to_string()];
+        let mut results = Vec::new();
+        for entry in entries {
+            match entry {
+                Preload::Context(text) => context.push(text),
+                Preload::ToolResult {
+                    tool,
+                    input,
+                    output,
+                } => {
+                    if !tool_names.contains(&tool) {
+                        return Err(TurnError::Recoverable(
+                            format!("cannot preload a result for unknown tool '{tool}'").into(),
+                        ));
+                    }
+                    results.push((tool, serde_json::Value::Object(input), output));
+                }
+            }
+        }
+
+        let intro = AgentMessage::wire(Message::User(UserMessage::text(context.join("\n\n"))));
+        self.push_and_announce(intro).await?;
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let stamp = chrono::Utc::now().timestamp_millis();
+        let calls: Vec<ToolCall> = results
+            .iter()
+            .enumerate()
+            .map(|(n, (tool, input, _))| ToolCall {
+                id: format!("preload_{stamp}_{n}"),
+                name: tool.clone(),
+                arguments: input.clone(),
+            })
+            .collect();
+        let assistant = AssistantMessage {
+            content: calls
+                .iter()
+                .cloned()
+                .map(AssistantContent::ToolCall)
+                .collect(),
+            api: self.model_info.api.clone(),
+            provider: self.model_info.provider.clone(),
+            model: self.model_info.id.clone(),
+            response_id: None,
+            usage: Usage::default(),
+            stop_reason: StopReason::ToolUse,
+            error: None,
+            timestamp: 0,
+        };
+        self.push_and_announce(AgentMessage::wire(Message::Assistant(assistant)))
+            .await?;
+
+        for (call, (tool, _, output)) in calls.into_iter().zip(results) {
+            self.bus
+                .emit(AgentEvent::ToolExecutionStart {
+                    agent_id: self.agent_id,
+                    call_id: call.id.clone(),
+                    tool: tool.clone(),
+                    args: call.arguments,
+                })
+                .await
+                .map_err(TurnError::Fatal)?;
+            let outcome = ToolOutcome {
+                content: vec![UserContent::text(output.clone())],
+                details: ToolDetails::Text {
+                    summary: format!("{tool} (preloaded)"),
+                    body: output,
+                },
+                is_error: false,
+            };
+            self.finalize_tool_result(&call.id, &tool, outcome).await?;
+        }
+        Ok(())
+    }
+
+    /// Push `message` onto the transcript and emit its `MessageStart` /
+    /// `MessageEnd` pair.
+    async fn push_and_announce(&mut self, message: AgentMessage) -> Result<(), TurnError> {
+        self.transcript.push(message.clone());
+        self.bus
+            .emit(AgentEvent::MessageStart {
+                agent_id: self.agent_id,
+                message: message.clone(),
+            })
+            .await
+            .map_err(TurnError::Fatal)?;
+        self.bus
+            .emit(AgentEvent::MessageEnd {
+                agent_id: self.agent_id,
+                message,
+            })
+            .await
+            .map_err(TurnError::Fatal)
+    }
+
     /// Wake the agent on pending work it can react to without a fresh
     /// user prompt: queued task-completion notices and messages the
     /// user queued while the agent was busy (steering / follow-up).
@@ -824,6 +1230,8 @@ impl Agent {
             .map_err(TurnError::Fatal)?;
 
         let outcome = self.run_top_level_turn_inner(prompt).await;
+        // The addendum is scoped to this run, whatever its outcome.
+        self.turn_system_addendum = None;
 
         self.bus
             .emit(AgentEvent::AgentEnd {
@@ -840,6 +1248,22 @@ impl Agent {
         &mut self,
         prompt: Option<UserMessage>,
     ) -> Result<(), TurnError> {
+        // Checked before any queued work is drained, so a skipped
+        // prompt leaves pending notices for the next run to deliver.
+        if self.skip_repeated_prompts
+            && let Some(text) = prompt.as_ref().and_then(|user| prompt_text(&user.content))
+            && self.repeats_last_prompt(text)
+        {
+            self.bus
+                .emit(AgentEvent::Warning {
+                    agent_id: self.agent_id,
+                    text: REPEATED_PROMPT_SKIPPED.to_string(),
+                })
+                .await
+                .map_err(TurnError::Fatal)?;
+            return Ok(());
+        }
+
         // Notices that arrived while the agent was idle land before
         // the user's new message, in arrival order.
         self.drain_task_notices().await?;
@@ -1004,6 +1428,11 @@ impl Agent {
         let mut retry_attempt: u32 = 0;
         let mut retry_strategy = None;
 
+        // Position in `fallback_models` of the model this run has moved
+        // to after the primary stayed overloaded; `None` runs on the
+        // primary. Local to the run, so the next prompt starts over.
+        let mut fallback: Option<usize> = None;
+
         // A turn is one inference plus the tool batch it triggers,
         // bracketed by `TurnStart` / `TurnEnd`. A transient-error
         // retry re-enters the loop for the *same* turn, so it must not
@@ -1012,6 +1441,14 @@ impl Agent {
         // first, or a tool-continuation) emits it.
         let mut retrying = false;
 
+        // Whether this run already nudged the model after an empty
+        // response (see [`Agent::set_nudge_empty_responses`]).
+        let mut nudged_empty = false;
+
+        // `PauseTurn` continuations since the last inference that
+        // stopped any other way (see [`MAX_PAUSE_CONTINUATIONS`]).
+        let mut pauses_in_a_row = 0;
+
         'outer: loop {
             // Pre-iteration cancel check (cheap atomic). Lets us
             // skip an inference when cancel fired between turns
@@ -1030,7 +1467,8 @@ impl Agent {
             }
             retrying = false;
 
-            let mut response_stream = self.run_inference_streaming();
+            let route = fallback.map(|n| &self.fallback_models[n]);
+            let mut response_stream = self.run_inference_streaming(route);
             // Cheap clone — `CancellationToken` is `Arc`-backed and
             // the same handle is shared with the provider task via
             // `run_inference_streaming`'s `options.cancel`.
@@ -1046,7 +1484,9 @@ impl Agent {
             self.bus
                 .emit(AgentEvent::MessageStart {
                     agent_id: self.agent_id,
-                    message: AgentMessage::wire(Message::Assistant(self.empty_assistant_message())),
+                    message: AgentMessage::wire(Message::Assistant(
+                        self.empty_assistant_message(route),
+                    )),
                 })
                 .await
                 .map_err(TurnError::Fatal)?;
@@ -1062,8 +1502,13 @@ impl Agent {
             // synthesize the aborted terminal when our local
             // `select!` wins the cancel race against the provider's
             // own abort path.
-            let mut latest_partial = self.empty_assistant_message();
+            let mut latest_partial = self.empty_assistant_message(route);
             let mut aborted_during_stream = false;
+            // Whether any content block arrived before the stream
+            // ended; decides between restarting a failed request and
+            // keeping its partial (see
+            // [`Agent::set_retry_partial_streams`]).
+            let mut saw_content = false;
 
             loop {
                 tokio::select! {
@@ -1097,6 +1542,7 @@ impl Agent {
                             _ => {}
                         }
                         latest_partial = event.partial().clone();
+                        saw_content |= !latest_partial.content.is_empty();
 
                         // Forward the provider event as a `MessageUpdate` on
                         // the bus. Renderers consume the inner
@@ -1159,6 +1605,15 @@ impl Agent {
             };
             drop(response_stream);
 
+            let mut final_message = final_message;
+            for (original, replacement) in disambiguate_tool_call_ids(&mut final_message) {
+                tracing::warn!(
+                    original,
+                    replacement,
+                    "model emitted a duplicate tool_use id; renamed the repeat"
+                );
+            }
+
             // Emit `MessageEnd` so renderers can finalize their
             // assistant slot (close in-flight blocks, mark the turn
             // complete). Fires for success, error, and abort
@@ -1212,7 +1667,9 @@ impl Agent {
                 // backoff. `Transient` covers a stream that dropped
                 // before its terminal frame (a truncated turn):
                 // retrying re-issues the turn instead of surfacing a
-                // cut-off answer as final.
+                // cut-off answer as final. Once content has arrived
+                // the partial is kept instead, unless
+                // `retry_partial_streams` asks for a restart.
                 // `Overloaded` (provider 529/503) retries for the same
                 // reason. `RateLimit` is also retryable but
                 // must honour `retry_after_ms`, which this fixed backoff
@@ -1223,6 +1680,29 @@ impl Agent {
                         ErrorCategory::Overloaded | ErrorCategory::Transient
                     )
                 });
+                if is_retryable && saw_content && !self.retry_partial_streams {
+                    // Restarting would throw away what the user already
+                    // watched stream in. Keep the partial on the
+                    // transcript (the wire transform skips errored
+                    // turns, so the model never sees it) and stop.
+                    let detail = assistant_err
+                        .map(|e| e.message)
+                        .unwrap_or_else(|| "model stream failed".to_string());
+                    let text = format!(
+                        "The response failed partway through ({detail}). The partial response \
+                         was kept; send a message to continue or ask again to retry."
+                    );
+                    self.transcript
+                        .push(AgentMessage::wire(Message::Assistant(final_message)));
+                    self.bus
+                        .emit(AgentEvent::Warning {
+                            agent_id: self.agent_id,
+                            text: text.clone(),
+                        })
+                        .await
+                        .map_err(TurnError::Fatal)?;
+                    return Err(TurnError::Recoverable(text.into()));
+                }
                 if is_retryable {
                     if retry_strategy.is_none() {
                         retry_strategy = Some(Self::create_retry_strategy());
@@ -1261,6 +1741,35 @@ impl Agent {
                     }
                 }
 
+                // Retries spent on an overloaded model: move to the
+                // next model in the fallback chain, with a fresh retry
+                // budget, instead of giving up.
+                let overloaded = assistant_err
+                    .as_ref()
+                    .is_some_and(|e| e.category == ErrorCategory::Overloaded);
+                let next = fallback.map_or(0, |n| n.saturating_add(1));
+                if overloaded && next < self.fallback_models.len() {
+                    let from =
+                        fallback.map_or(&self.model_info, |n| &self.fallback_models[n].model_info);
+                    let to = &self.fallback_models[next].model_info;
+                    let text = format!(
+                        "{}/{} is overloaded; switching to {}/{} for this request.",
+                        from.provider, from.id, to.provider, to.id
+                    );
+                    self.bus
+                        .emit(AgentEvent::Warning {
+                            agent_id: self.agent_id,
+                            text,
+                        })
+                        .await
+                        .map_err(TurnError::Fatal)?;
+                    fallback = Some(next);
+                    retry_strategy = None;
+                    retry_attempt = 0;
+                    retrying = true;
+                    continue 'outer;
+                }
+
                 // Non-retryable / retry-exhausted: surface a
                 // recoverable turn error so the binary keeps the
                 // session alive and the user can re-prompt.
@@ -1292,6 +1801,11 @@ impl Agent {
                 })
                 .collect();
             let has_tool_use = !tool_calls.is_empty();
+            if response.stop_reason == StopReason::PauseTurn {
+                pauses_in_a_row += 1;
+            } else {
+                pauses_in_a_row = 0;
+            }
 
             // Append the finalized assistant message to the
             // transcript. Persistence listeners subscribe to the
@@ -1325,6 +1839,27 @@ impl Agent {
 
             // Execute tool calls if any
             if has_tool_use {
+                // The batch hook sees the whole batch before any call
+                // starts; the calls it short-circuits carry their
+                // pre-baked outcome into `run_tool_call` below.
+                let mut batch_outcomes = match self.before_tool_batch.clone() {
+                    Some(hook) => {
+                        let calls: Vec<hooks::ToolBatchCall<'_>> = tool_calls
+                            .iter()
+                            .map(|(call_id, tool_name, args)| hooks::ToolBatchCall {
+                                call_id,
+                                tool_name,
+                                args,
+                            })
+                            .collect();
+                        match hook(&calls).await {
+                            hooks::BeforeToolBatchOutcome::Proceed => HashMap::new(),
+                            hooks::BeforeToolBatchOutcome::ShortCircuit { outcomes } => outcomes,
+                        }
+                    }
+                    None => HashMap::new(),
+                };
+
                 // Partition the batch into contiguous concurrency
                 // groups and run each group through a bounded pool.
                 // `Sequential` tools (bash, file edits) are singleton
@@ -1379,9 +1914,18 @@ impl Agent {
                     // finalization below stays deterministic. Each
                     // future only borrows `&self`, so the whole group
                     // can be in flight at once.
+                    let interrupt = self.tool_interrupt.arm();
                     let results: Vec<Result<RunToolResult, TurnError>> = futures::stream::iter(
                         group.into_iter().map(|(call_id, tool_name, args)| {
-                            self.run_tool_call(call_id, tool_name, args, cancel.clone())
+                            let preset = batch_outcomes.remove(&call_id);
+                            self.run_tool_call(
+                                call_id,
+                                tool_name,
+                                args,
+                                preset,
+                                cancel.clone(),
+                                interrupt.clone(),
+                            )
                         }),
                     )
                     .buffered(cap)
@@ -1396,9 +1940,17 @@ impl Agent {
                         let RunToolResult {
                             call_id,
                             tool_name,
-                            outcome,
+                            args,
+                            mut outcome,
                             aborted: call_aborted,
                         } = result?;
+                        // Deduplicate here rather than in
+                        // `run_tool_call` so "see above" holds: the
+                        // earlier result is already in the transcript.
+                        if !call_aborted {
+                            self.record_change(&tool_name, &outcome);
+                            self.dedupe_tool_result(&tool_name, &args, &mut outcome);
+                        }
                         let tool_result = self
                             .finalize_tool_result(&call_id, &tool_name, outcome)
                             .await?;
@@ -1451,12 +2003,51 @@ impl Agent {
                 // Continue the conversation loop to get the model's
                 // response to tool results.
                 continue;
+            } else if response.stop_reason == StopReason::PauseTurn {
+                // The provider paused a long-running turn. It is not
+                // finished: close this inference's turn, tell the user
+                // why another inference follows, and re-send the
+                // transcript (which now ends in the paused assistant
+                // message) so the model continues where it stopped.
+                self.bus
+                    .emit(AgentEvent::TurnEnd {
+                        agent_id: self.agent_id,
+                        message: response,
+                        tool_results: Vec::new(),
+                    })
+                    .await
+                    .map_err(TurnError::Fatal)?;
+                // A provider that never stops pausing would otherwise
+                // keep this loop going forever.
+                if pauses_in_a_row > MAX_PAUSE_CONTINUATIONS {
+                    let text = format!(
+                        "The model paused its turn {pauses_in_a_row} times in a row; \
+                         stopped continuing it."
+                    );
+                    self.bus
+                        .emit(AgentEvent::Warning {
+                            agent_id: self.agent_id,
+                            text: text.clone(),
+                        })
+                        .await
+                        .map_err(TurnError::Fatal)?;
+                    return Err(TurnError::Recoverable(text.into()));
+                }
+                self.bus
+                    .emit(AgentEvent::Notice {
+                        agent_id: self.agent_id,
+                        text: "Model paused its turn; continuing…".to_string(),
+                    })
+                    .await
+                    .map_err(TurnError::Fatal)?;
+                continue;
             } else {
                 // We are now ready to finish this turn. Every message
                 // event that belongs to it has already been emitted
                 // individually; there is no per-turn save. `TurnEnd`
                 // closes the turn with the finalized assistant message
                 // and no tool results (this turn called none).
+                let empty = is_empty_response(&response);
                 self.bus
                     .emit(AgentEvent::TurnEnd {
                         agent_id: self.agent_id,
@@ -1465,6 +2056,60 @@ impl Agent {
                     })
                     .await
                     .map_err(TurnError::Fatal)?;
+
+                // A response with nothing to show would otherwise end
+                // the run silently. Either nudge the model once or
+                // tell the user what happened.
+                if empty {
+                    if self.nudge_empty_responses && !nudged_empty {
+                        nudged_empty = true;
+                        self.bus
+                            .emit(AgentEvent::Notice {
+                                agent_id: self.agent_id,
+                                text: "Model returned an empty response; asking it to continue…"
+                                    .to_string(),
+                            })
+                            .await
+                            .map_err(TurnError::Fatal)?;
+                        // Providers reject an assistant turn without
+                        // content, so the empty answer must not be
+                        // replayed ahead of the nudge. It is still the
+                        // last transcript entry: nothing was appended
+                        // after it in this branch.
+                        if matches!(
+                            self.transcript.last().and_then(AgentMessage::as_wire),
+                            Some(Message::Assistant(a)) if is_empty_response(a)
+                        ) {
+                            self.transcript.pop();
+                        }
+                        let message = AgentMessage::wire(Message::User(UserMessage::text(
+                            EMPTY_RESPONSE_NUDGE.to_string(),
+                        )));
+                        self.transcript.push(message.clone());
+                        self.bus
+                            .emit(AgentEvent::MessageStart {
+                                agent_id: self.agent_id,
+                                message: message.clone(),
+                            })
+                            .await
+                            .map_err(TurnError::Fatal)?;
+                        self.bus
+                            .emit(AgentEvent::MessageEnd {
+                                agent_id: self.agent_id,
+                                message,
+                            })
+                            .await
+                            .map_err(TurnError::Fatal)?;
+                        continue;
+                    }
+                    self.bus
+                        .emit(AgentEvent::Notice {
+                            agent_id: self.agent_id,
+                            text: "The model returned an empty response.".to_string(),
+                        })
+                        .await
+                        .map_err(TurnError::Fatal)?;
+                }
                 break;
             }
         }
@@ -1502,21 +2147,7 @@ impl Agent {
                 crate::tool::TASK_NOTIFICATION_CLOSE_TAG,
             );
             let message = AgentMessage::wire(Message::User(UserMessage::text(text)));
-            self.transcript.push(message.clone());
-            self.bus
-                .emit(AgentEvent::MessageStart {
-                    agent_id: self.agent_id,
-                    message: message.clone(),
-                })
-                .await
-                .map_err(TurnError::Fatal)?;
-            self.bus
-                .emit(AgentEvent::MessageEnd {
-                    agent_id: self.agent_id,
-                    message,
-                })
-                .await
-                .map_err(TurnError::Fatal)?;
+            self.push_and_announce(message).await?;
         }
         Ok(())
     }
@@ -1656,12 +2287,13 @@ impl Agent {
     /// `MessageStart` payload before any provider event arrives, so
     /// renderers can open their assistant slot with a structurally
     /// complete message even though the content is empty.
-    fn empty_assistant_message(&self) -> AssistantMessage {
+    fn empty_assistant_message(&self, route: Option<&FallbackModel>) -> AssistantMessage {
+        let model_info = route.map_or(&self.model_info, |m| &m.model_info);
         AssistantMessage {
             content: Vec::new(),
-            api: self.model_info.api.clone(),
-            provider: self.model_info.provider.clone(),
-            model: self.model_info.id.clone(),
+            api: model_info.api.clone(),
+            provider: model_info.provider.clone(),
+            model: model_info.id.clone(),
             response_id: None,
             usage: aj_models::types::Usage::default(),
             stop_reason: StopReason::Stop,
@@ -1681,12 +2313,19 @@ impl Agent {
     /// to [`Provider::stream_simple`]. The agent does not block
     /// on the stream here: it's returned to the caller, which
     /// polls it inside [`Self::execute_turn`]'s outer retry loop.
-    fn run_inference_streaming(&self) -> AssistantMessageEventStream {
+    ///
+    /// `route` overrides the agent's own provider, model, and
+    /// options with an entry of the fallback chain (see
+    /// [`Agent::set_fallback_models`]); `None` runs on the primary.
+    fn run_inference_streaming(
+        &self,
+        route: Option<&FallbackModel>,
+    ) -> AssistantMessageEventStream {
         let thinking = self.default_thinking.clone();
 
         tracing::debug!(?thinking, "thinking effort");
 
-        let system_prompt = self.assembled_system_prompt.clone();
+        let system_prompt = self.inference_system_prompt();
 
         let messages = transcript_to_messages(&self.transcript);
         // Defense-in-depth `image_block` gate: scrub image bytes
@@ -1704,7 +2343,7 @@ impl Agent {
         } else {
             messages
         };
-        let tools = self.tools.clone();
+        let tools = self.inference_tools();
 
         let context = Context {
             system_prompt: Some(system_prompt),
@@ -1721,7 +2360,18 @@ impl Agent {
         // The same token is also `select!`-ed in `execute_turn` so
         // the agent stops polling the moment cancel fires, regardless
         // of how quickly the provider task winds down.
-        let mut base = self.stream_options.clone();
+        let (provider, model_info, mut base) = match route {
+            Some(m) => {
+                let mut base = m.stream_options.clone();
+                base.session_id = self.stream_options.session_id.clone();
+                (&m.provider, &m.model_info, base)
+            }
+            None => (
+                &self.provider,
+                &self.model_info,
+                self.stream_options.clone(),
+            ),
+        };
         base.cancel = Some(self.cancellation.clone());
 
         let options = SimpleStreamOptions {
@@ -1729,13 +2379,15 @@ impl Agent {
             reasoning: thinking.as_ref().map(thinking_config_to_level),
         };
 
-        self.provider
-            .stream_simple(&self.model_info, &context, &options)
+        provider.stream_simple(model_info, &context, &options)
     }
 
     /// Run one tool call up to (but not including) result
     /// finalization: emit `ToolExecutionStart`, consult the
-    /// before/after hooks, and race the tool against cancellation.
+    /// before/after hooks, and race the tool against cancellation
+    /// and the group's [`ToolInterrupt`] token. A `preset` outcome
+    /// (from the batch hook) stands in for both the before hook and
+    /// the tool.
     ///
     /// Takes `&self` so a batch of these can run concurrently within a
     /// turn. Appending the result to the transcript and emitting the
@@ -1748,7 +2400,9 @@ impl Agent {
         call_id: String,
         tool_name: String,
         tool_input: serde_json::Value,
+        preset: Option<ToolOutcome>,
         cancel: CancellationToken,
+        interrupt: CancellationToken,
     ) -> Result<RunToolResult, TurnError> {
         // Mirror the start of every tool invocation on the bus before
         // any work — listeners that render a "running…" placeholder
@@ -1767,9 +2421,17 @@ impl Agent {
         // short-circuit the call with a pre-baked outcome (permission
         // denial, policy block). We clone the `Arc` so the borrow
         // doesn't conflict with the `execute_tool` call below.
+        // A tool switched off for the session is refused here, before
+        // the hook gets a chance to act on it.
+        let preset = preset.or_else(|| {
+            self.session_disabled_tools
+                .contains(&tool_name)
+                .then(|| disabled_tool_outcome(&tool_name))
+        });
         let before_hook = self.before_tool_call.clone();
-        let (tool_input, short_circuit_outcome) = match before_hook {
-            Some(hook) => {
+        let (tool_input, short_circuit_outcome) = match (preset, before_hook) {
+            (Some(outcome), _) => (tool_input, Some(outcome)),
+            (None, Some(hook)) => {
                 let ctx = hooks::ToolCallContext {
                     call_id: &call_id,
                     tool_name: &tool_name,
@@ -1781,14 +2443,16 @@ impl Agent {
                     }
                 }
             }
-            None => (tool_input, None),
+            (None, None) => (tool_input, None),
         };
 
         // Run the tool unless the before-hook short-circuited it,
         // racing against cancel. On cancel we drop the tool future
         // (bash tears down its process tree; other tools just exit)
         // and synthesize a cancelled outcome so the transcript still
-        // pairs `tool_use` with `tool_result`.
+        // pairs `tool_use` with `tool_result`. An interrupt drops the
+        // tool the same way but lands as an ordinary error result, so
+        // the turn continues.
         //
         // Tool-input parse failures surface as a `ToolCall` with
         // `arguments == Value::Null`; the tool's own deserializer
@@ -1802,6 +2466,7 @@ impl Agent {
             tokio::select! {
                 biased;
                 _ = cancel.cancelled() => None,
+                _ = interrupt.cancelled() => Some(interrupted_tool_outcome(&tool_name)),
                 res = self.execute_tool(&call_id, &tool_name, tool_input.clone()) => {
                     Some(match res {
                         Ok(outcome) => outcome,
@@ -1838,16 +2503,78 @@ impl Agent {
         Ok(RunToolResult {
             call_id,
             tool_name,
+            args: tool_input,
             outcome,
             aborted,
         })
     }
 
+    /// Replace the wire content of a read-only tool's result with a
+    /// pointer at the previous result when the identical call returned
+    /// the same content last time. `details` stay intact for the UI.
+    /// Add a finished call to the session's [`ChangeLog`].
+    fn record_change(&self, tool_name: &str, outcome: &ToolOutcome) {
+        let read_only = self
+            .tool_definitions
+            .get(tool_name)
+            .is_some_and(|def| def.read_only);
+        self.session_state
+            .record_change(tool_name, read_only, outcome);
+    }
+
+    fn dedupe_tool_result(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+        outcome: &mut ToolOutcome,
+    ) {
+        let read_only = self
+            .tool_definitions
+            .get(tool_name)
+            .is_some_and(|def| def.read_only);
+        if !read_only || outcome.is_error {
+            return;
+        }
+        if self
+            .session_state
+            .record_call(tool_name, args, &outcome.content)
+        {
+            outcome.content = vec![UserContent::text(format!(
+                "Identical to the previous result of this {tool_name} call (see above)."
+            ))];
+        }
+    }
+
+    /// Run one tool call and, when a [`ToolAuditLog`] is set, append
+    /// its audit entry.
     async fn execute_tool(
         &self,
         call_id: &str,
         tool_name: &str,
         tool_input: serde_json::Value,
+    ) -> Result<ToolOutcome, BoxError> {
+        let Some(audit) = &self.tool_audit else {
+            return self.dispatch_tool(call_id, tool_name, tool_input).await;
+        };
+        let input = tool_input.clone();
+        let started = Instant::now();
+        let result = self.dispatch_tool(call_id, tool_name, tool_input).await;
+        audit.record(
+            self.agent_id,
+            call_id,
+            tool_name,
+            &input,
+            started.elapsed(),
+            result.as_ref(),
+        );
+        result
+    }
+
+    async fn dispatch_tool(
+        &self,
+        call_id: &str,
+        tool_name: &str,
+        tool_input: serde_json::Value,
     ) -> Result<ToolOutcome, BoxError> {
         let tool_def = if let Some(tool_def) = self.tool_definitions.get(tool_name) {
             tool_def
@@ -1858,8 +2585,12 @@ impl Agent {
         // Build the sub-agent tool template now (cheap clone: every
         // `ErasedToolDefinition` field is `Clone`, with the closure
         // sitting behind an `Arc`).
-        let sub_agent_tools: Vec<ErasedToolDefinition> =
-            self.tool_definitions.values().cloned().collect();
+        let sub_agent_tools: Vec<ErasedToolDefinition> = self
+            .tool_definitions
+            .values()
+            .filter(|tool| !self.session_disabled_tools.contains(&tool.name))
+            .cloned()
+            .collect();
 
         // Build the [`ToolContext`] the tool sees: working
         // directory, todos, sub-agent spawn, cancellation token,
@@ -1874,16 +2605,19 @@ impl Agent {
             provider: Arc::clone(&self.provider),
             model_info: Arc::clone(&self.model_info),
             stream_options: self.stream_options.clone(),
+            fallback_models: self.fallback_models.clone(),
             sub_agent_tools,
             parent_bus: self.bus.clone(),
             parent_agent_id: self.agent_id,
             cancellation: self.cancellation.child_token(),
             block_images: self.block_images,
+            tool_audit: self.tool_audit.clone(),
             default_thinking: self.default_thinking.clone(),
             speed: self.speed,
             sub_agent_registry: self.sub_agent_registry.clone(),
             task_registry: self.task_registry.clone(),
             message_queues: self.message_queues.clone(),
+            before_tool_batch: self.before_tool_batch.clone(),
             call_id: call_id.to_string(),
             tool_name: tool_name.to_string(),
             tool_args: tool_input.clone(),
@@ -2254,6 +2988,9 @@ impl TaskRegistry {
 #[derive(Clone)]
 pub(crate) struct SessionState {
     inner: Arc<StdMutex<SessionStateInner>>,
+    /// Kept outside `inner` so a sub-agent's state can point at its
+    /// parent's log; see [`SessionState::share_change_log`].
+    changes: Arc<StdMutex<ChangeLog>>,
 }
 
 #[derive(Debug)]
@@ -2264,6 +3001,8 @@ struct SessionStateInner {
     accumulated_usage: Usage,
     sub_agent_counter: usize,
     sub_agent_usage: HashMap<usize, Usage>,
+    output_cache: OutputCache,
+    recent_calls: RecentCalls,
 }
 
 impl SessionState {
@@ -2276,7 +3015,10 @@ impl SessionState {
                 accumulated_usage: Usage::default(),
                 sub_agent_counter: 0,
                 sub_agent_usage: HashMap::new(),
+                output_cache: OutputCache::default(),
+                recent_calls: RecentCalls::default(),
             })),
+            changes: Arc::new(StdMutex::new(ChangeLog::default())),
         }
     }
 
@@ -2296,6 +3038,22 @@ impl SessionState {
         self.lock().todo_list = todos;
     }
 
+    fn stash_output(&self, output: String) -> String {
+        self.lock().output_cache.insert(output)
+    }
+
+    fn stashed_output(&self, token: &str) -> Option<Arc<str>> {
+        self.lock().output_cache.get(token)
+    }
+
+    fn record_call(&self, tool: &str, input: &serde_json::Value, content: &[UserContent]) -> bool {
+        self.lock().recent_calls.record(tool, input, content)
+    }
+
+    fn clear_recent_calls(&self) {
+        self.lock().recent_calls.clear();
+    }
+
     pub(crate) fn turn_counter(&self) -> usize {
         self.lock().turn_counter
     }
@@ -2333,6 +3091,24 @@ impl SessionState {
     fn sub_agent_usage(&self) -> HashMap<usize, Usage> {
         self.lock().sub_agent_usage.clone()
     }
+
+    fn changes(&self) -> std::sync::MutexGuard<'_, ChangeLog> {
+        self.changes.lock().expect("change log mutex poisoned")
+    }
+
+    fn record_change(&self, tool: &str, read_only: bool, outcome: &ToolOutcome) {
+        self.changes().record(tool, read_only, outcome);
+    }
+
+    fn change_log(&self) -> ChangeLog {
+        self.changes().clone()
+    }
+
+    /// Record this agent's writes into `parent`'s log, so a
+    /// sub-agent's edits show up in the session summary.
+    fn share_change_log(&mut self, parent: &SessionState) {
+        self.changes = Arc::clone(&parent.changes);
+    }
 }
 
 #[cfg(test)]
@@ -2608,6 +3384,8 @@ struct SessionContextWrapper<'a> {
     provider: Arc<dyn Provider>,
     model_info: Arc<ModelInfo>,
     stream_options: StreamOptions,
+    /// The parent's fallback chain, inherited by sub-agents.
+    fallback_models: Vec<FallbackModel>,
     /// Snapshot of the parent's tool list. Sub-agents inherit this
     /// minus the `agent` tool. Cloning per-spawn is cheap because
     /// every `ErasedToolDefinition` field is `Clone` and the
@@ -2634,6 +3412,9 @@ struct SessionContextWrapper<'a> {
     /// Parent's `image_block` setting; propagated to spawned
     /// sub-agents so the defense-in-depth gate stays uniform.
     block_images: bool,
+    /// Parent's tool-audit log; propagated to spawned sub-agents so
+    /// the audit covers the whole hierarchy.
+    tool_audit: Option<ToolAuditLog>,
     /// Parent's default thinking level; propagated to spawned
     /// sub-agents so they reason at the same effort as the parent
     /// (and so non-reasoning models never receive an explicit
@@ -2655,6 +3436,9 @@ struct SessionContextWrapper<'a> {
     /// parent so a spawned sub-agent drains the same per-agent slots
     /// the binary's TUI enqueues onto.
     message_queues: MessageQueues,
+    /// Parent's batch hook (e.g. edit approval); propagated to spawned
+    /// sub-agents so their tool batches are reviewed the same way.
+    before_tool_batch: Option<hooks::BeforeToolBatchHook>,
     /// Id of the tool call this wrapper was built for. Captured on the
     /// [`TaskEventSink`] so task lifecycle events correlate with the
     /// originating transcript cell.
@@ -2682,9 +3466,17 @@ impl<'a> ToolContext for SessionContextWrapper<'a> {
         self.session_state.set_todo_list(todos);
     }
 
-    fn spawn_agent<'b>(
-        &'b mut self,
-        task: String,
+    fn stash_output(&mut self, output: String) -> String {
+        self.session_state.stash_output(output)
+    }
+
+    fn stashed_output(&self, token: &str) -> Option<Arc<str>> {
+        self.session_state.stashed_output(token)
+    }
+
+    fn spawn_agent<'b>(
+        &'b mut self,
+        task: String,
         mode: SpawnMode,
     ) -> std::pin::Pin<
         Box<dyn std::future::Future<Output = Result<SpawnResult, BoxError>> + Send + 'b>,
@@ -2763,6 +3555,9 @@ impl<'a> ToolContext for SessionContextWrapper<'a> {
                 None,
             );
             sub_agent.set_agent_id(child_id);
+            sub_agent
+                .session_state
+                .share_change_log(&self.session_state);
             // Sub-agents inherit the parent's assembled system
             // prompt verbatim so the session has a single,
             // consistent prompt across the hierarchy. The transcript
@@ -2777,6 +3572,16 @@ impl<'a> ToolContext for SessionContextWrapper<'a> {
             // so the defense-in-depth gate stays uniform across the
             // hierarchy.
             sub_agent.set_block_images(self.block_images);
+            sub_agent.set_tool_audit_log(self.tool_audit.clone());
+            // The parent's batch hook is shared, not copied: its state
+            // (e.g. whether edits need confirming, which paths were
+            // approved for the session) lives behind the closure, so
+            // the sub-agent's edits go through the same review.
+            sub_agent.set_before_tool_batch(self.before_tool_batch.clone());
+            // Nobody is at hand to decide what to do with a sub-agent's
+            // cut-off response, so its failed streams always restart.
+            sub_agent.set_retry_partial_streams(true);
+            sub_agent.set_fallback_models(self.fallback_models.clone());
             // Sub-agents inherit the parent's thinking level so they
             // reason at the same effort and so a `None` default never
             // gets serialized as an explicit `disabled` for models
@@ -3208,6 +4013,113 @@ fn cancelled_tool_outcome(tool_name: &str) -> ToolOutcome {
     }
 }
 
+/// The `is_error: true` [`ToolOutcome`] for a call stopped through
+/// [`ToolInterrupt`]. Unlike [`cancelled_tool_outcome`] the turn goes
+/// on, so the text tells the model what happened.
+fn interrupted_tool_outcome(tool_name: &str) -> ToolOutcome {
+    let body = format!("{tool_name}: stopped by the user before it finished");
+    ToolOutcome {
+        content: vec![UserContent::text(body.clone())],
+        details: ToolDetails::Text {
+            summary: format!("{tool_name}: interrupted"),
+            body,
+        },
+        is_error: true,
+    }
+}
+
+/// The `is_error: true` [`ToolOutcome`] for a call to a tool switched
+/// off by [`Agent::set_session_disabled_tools`].
+fn disabled_tool_outcome(tool_name: &str) -> ToolOutcome {
+    let body = format!("{tool_name}: disabled by the user for this session; use another tool");
+    ToolOutcome {
+        content: vec![UserContent::text(body.clone())],
+        details: ToolDetails::Text {
+            summary: format!("{tool_name}: disabled"),
+            body,
+        },
+        is_error: true,
+    }
+}
+
+/// How many consecutive `PauseTurn` responses a run continues before
+/// ending the turn with an error.
+const MAX_PAUSE_CONTINUATIONS: usize = 8;
+
+/// User message appended when [`Agent::set_nudge_empty_responses`]
+/// is on and the model answered with nothing.
+const EMPTY_RESPONSE_NUDGE: &str =
+    "Your last response was empty. Continue with the task, or say what you are waiting for.";
+
+/// Warning emitted when [`Agent::set_skip_repeated_prompts`] drops a
+/// prompt.
+const REPEATED_PROMPT_SKIPPED: &str =
+    "Skipped a prompt identical to the previous message; nothing was sent.";
+
+/// The prompt text of a user message: its leading text block, which is
+/// what the user typed (attachments follow it).
+fn prompt_text(content: &[UserContent]) -> Option<&str> {
+    match content.first() {
+        Some(UserContent::Text(t)) => Some(&t.text),
+        _ => None,
+    }
+}
+
+/// User message of the [`Agent::prewarm_cache`] request. Only the
+/// prefix before it matters; it just has to be a valid turn.
+const PREWARM_PROMPT: &str = "Reply with OK.";
+
+/// Whether a finalized assistant message has nothing for the user:
+/// no tool calls and no text beyond whitespace. Thinking blocks don't
+/// count, since they are not an answer.
+fn is_empty_response(message: &AssistantMessage) -> bool {
+    message.content.iter().all(|block| match block {
+        AssistantContent::Text(t) => t.text.trim().is_empty(),
+        AssistantContent::Thinking(_) => true,
+        AssistantContent::ToolCall(_) => false,
+    })
+}
+
+/// Give every repeated tool-call id in `message` a fresh `<id>_<n>`
+/// suffix, leaving the first occurrence alone. Returns the renames as
+/// `(original, replacement)` pairs, empty in the normal case.
+///
+/// A streaming glitch can produce two `tool_use` blocks with one id;
+/// executing both would send two `tool_result`s for that id, which the
+/// provider rejects on the next request. Renaming before the message
+/// is announced keeps the transcript, the log and the results
+/// consistent, and both calls still run.
+fn disambiguate_tool_call_ids(message: &mut AssistantMessage) -> Vec<(String, String)> {
+    let mut seen: std::collections::HashSet<String> = message
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            AssistantContent::ToolCall(call) => Some(call.id.clone()),
+            _ => None,
+        })
+        .collect();
+    let mut first_seen = std::collections::HashSet::new();
+    let mut renames = Vec::new();
+    for block in &mut message.content {
+        let AssistantContent::ToolCall(call) = block else {
+            continue;
+        };
+        if first_seen.insert(call.id.clone()) {
+            continue;
+        }
+        let replacement = (2..)
+            .map(|n| format!("{}_{n}", call.id))
+            .find(|candidate| !seen.contains(candidate))
+            .expect("an unused suffix exists");
+        seen.insert(replacement.clone());
+        renames.push((
+            std::mem::replace(&mut call.id, replacement.clone()),
+            replacement,
+        ));
+    }
+    renames
+}
+
 /// One pending tool call from an assistant turn:
 /// `(call_id, tool_name, arguments)`.
 type PendingToolCall = (String, String, serde_json::Value);
@@ -3219,6 +4131,8 @@ type PendingToolCall = (String, String, serde_json::Value);
 struct RunToolResult {
     call_id: String,
     tool_name: String,
+    /// The input the tool ran with, after the before-hook.
+    args: serde_json::Value,
     outcome: ToolOutcome,
     aborted: bool,
 }
@@ -3465,6 +4379,7 @@ mod event_protocol_tests {
             StopReason::Stop => DoneReason::Stop,
             StopReason::Length => DoneReason::Length,
             StopReason::ToolUse => DoneReason::ToolUse,
+            StopReason::PauseTurn => DoneReason::PauseTurn,
             other => panic!(
                 "finalize_script: unexpected non-success stop reason {other:?}; \
                  use ScriptedProvider's error path for error/aborted cases"
@@ -3533,6 +4448,27 @@ mod event_protocol_tests {
         ]
     }
 
+    /// Like [`transient_error_script`], but the stream drops after a
+    /// text block carrying `text` has started: the shape of a
+    /// response cut off mid-content.
+    fn transient_error_after_text_script(text: &str) -> Vec<AssistantMessageEvent> {
+        let mut events = transient_error_script();
+        let AssistantMessageEvent::Error { error, .. } = &mut events[1] else {
+            unreachable!("transient_error_script ends with Error");
+        };
+        error.content = vec![AssistantContent::Text(TextContent::new(text))];
+        let partial = error.clone();
+        events.insert(
+            1,
+            AssistantMessageEvent::TextDelta {
+                content_index: 0,
+                delta: text.to_string(),
+                partial,
+            },
+        );
+        events
+    }
+
     /// Build a script whose terminal event is a non-retryable
     /// context-overflow `Error`. The agent surfaces it after a single
     /// inference (no retry), so a turn driven against it errors out
@@ -4336,6 +5272,134 @@ mod event_protocol_tests {
         assert_eq!(agent.messages().len(), 2);
     }
 
+    #[tokio::test]
+    async fn preload_appends_valid_tool_pairs_without_inference() {
+        // Only the follow-up prompt is scripted: the strict provider
+        // panics if `preload` itself ran an inference.
+        let scripts = vec![finalize_script(finalize_text("done"))];
+        let mut agent = build_agent(scripts, vec![PingTool.into()]);
+
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
+
+        agent
+            .preload(vec![
+                crate::preload::Preload::Context("the repo is a Rust workspace".to_string()),
+                crate::preload::Preload::ToolResult {
+                    tool: "ping".to_string(),
+                    input: serde_json::Map::new(),
+                    output: "pong (cached)".to_string(),
+                },
+            ])
+            .await
+            .expect("preload");
+
+        let messages: Vec<Message> = agent
+            .messages()
+            .iter()
+            .filter_map(|m| m.as_wire().cloned())
+            .collect();
+        let [
+            Message::User(user),
+            Message::Assistant(assistant),
+            Message::ToolResult(result),
+        ] = &messages[..]
+        else {
+            panic!("unexpected transcript: {messages:#?}");
+        };
+        assert!(
+            user.content
+                .iter()
+                .any(|c| matches!(c, aj_models::types::UserContent::Text(t)
+                    if t.text.contains("Rust workspace")))
+        );
+        assert_eq!(assistant.stop_reason, StopReason::ToolUse);
+        let [AssistantContent::ToolCall(call)] = &assistant.content[..] else {
+            panic!("expected one tool call: {assistant:#?}");
+        };
+        assert_eq!(call.name, "ping");
+        assert!(call.id.starts_with("preload_"));
+        assert_eq!(result.tool_call_id, call.id);
+        assert!(!result.is_error);
+
+        let events = recorded.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![
+                EventLabel::Message {
+                    agent_id: AgentId::Main,
+                    phase: "start",
+                    kind: "User",
+                },
+                EventLabel::Message {
+                    agent_id: AgentId::Main,
+                    phase: "end",
+                    kind: "User",
+                },
+                EventLabel::Message {
+                    agent_id: AgentId::Main,
+                    phase: "start",
+                    kind: "Assistant",
+                },
+                EventLabel::Message {
+                    agent_id: AgentId::Main,
+                    phase: "end",
+                    kind: "Assistant",
+                },
+                EventLabel::ToolExecutionStart {
+                    agent_id: AgentId::Main,
+                    call_id: call.id.clone(),
+                    tool: "ping".to_string(),
+                },
+                EventLabel::Message {
+                    agent_id: AgentId::Main,
+                    phase: "start",
+                    kind: "ToolResult",
+                },
+                EventLabel::Message {
+                    agent_id: AgentId::Main,
+                    phase: "end",
+                    kind: "ToolResult",
+                },
+                EventLabel::ToolExecutionEnd {
+                    agent_id: AgentId::Main,
+                    call_id: call.id.clone(),
+                    tool: "ping".to_string(),
+                    summary: "ping (preloaded)".to_string(),
+                    body: "pong (cached)".to_string(),
+                    is_error: false,
+                },
+            ],
+            "unexpected event sequence: {events:#?}"
+        );
+
+        // The preloaded sequence is complete, so a regular prompt can
+        // follow it.
+        agent
+            .prompt("go on".to_string(), CancellationToken::new())
+            .await
+            .expect("prompt");
+        assert_eq!(agent.messages().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn preload_rejects_unknown_tools_without_appending() {
+        let mut agent = build_agent(Vec::new(), Vec::new());
+        let err = agent
+            .preload(vec![crate::preload::Preload::ToolResult {
+                tool: "nope".to_string(),
+                input: serde_json::Map::new(),
+                output: String::new(),
+            }])
+            .await
+            .expect_err("unknown tool");
+        assert!(matches!(err, crate::TurnError::Recoverable(_)), "{err}");
+        assert!(agent.messages().is_empty());
+    }
+
     #[tokio::test]
     async fn prompt_on_unseeded_agent_runs_with_empty_system_prompt() {
         // The assembled system prompt defaults to the empty string, so
@@ -4679,8 +5743,8 @@ mod event_protocol_tests {
 
     #[tokio::test]
     async fn truncated_turn_is_retried_then_succeeds() {
-        // A provider stream that drops before its terminal frame
-        // surfaces as a transient `Error` (via
+        // A provider stream that drops before its terminal frame, and
+        // before any content, surfaces as a transient `Error` (via
         // `AssistantMessageEvent::truncated`). The agent's retry layer
         // must re-issue the turn rather than accept the truncated turn
         // as final. Strict-mode provider: exactly two inferences are
@@ -4746,84 +5810,595 @@ mod event_protocol_tests {
         assert_eq!(last_assistant.stop_reason, StopReason::Stop);
     }
 
-    /// `last_assistant` exposes the terminal success message right after
-    /// a turn so the host's post-turn policy can classify it.
+    #[tokio::test(start_paused = true)]
+    async fn overloaded_primary_falls_back_to_the_next_model() {
+        use aj_models::types::ErrorCategory;
+
+        // The first attempt plus every retry the backoff allows, all
+        // overloaded. Strict mode: one more inference on the primary
+        // would panic.
+        let overloaded = || {
+            let mut events = transient_error_script();
+            for event in &mut events {
+                let (AssistantMessageEvent::Start { partial: m }
+                | AssistantMessageEvent::Error { error: m, .. }) = event
+                else {
+                    unreachable!("transient_error_script is Start then Error");
+                };
+                if let Some(err) = &mut m.error {
+                    err.category = ErrorCategory::Overloaded;
+                }
+            }
+            events
+        };
+        let attempts = Agent::create_retry_strategy().count() + 1;
+        let mut agent = build_agent(vec![overloaded(); attempts], Vec::new());
+
+        let backup = ProviderScript::from_events(finalize_script(finalize_text("from backup")));
+        let mut backup_info = scripted_model_info();
+        backup_info.id = "backup-model".to_string();
+        agent.set_fallback_models(vec![crate::FallbackModel {
+            provider: Arc::new(
+                ScriptedProvider::new(vec![backup]).on_exhausted(ExhaustedBehavior::Panic),
+            ),
+            model_info: Arc::new(backup_info),
+            stream_options: StreamOptions::default(),
+        }]);
+
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
+
+        let final_text = agent
+            .run_single_turn("hello".to_string())
+            .await
+            .expect("the fallback model answers");
+        assert_eq!(final_text, "from backup");
+        assert!(
+            recorded.lock().unwrap().iter().any(|l| matches!(
+                l,
+                EventLabel::Warning(_, text) if text.contains("switching to scripted/backup-model")
+            )),
+            "expected the switch to be announced: {:?}",
+            recorded.lock().unwrap()
+        );
+    }
+
     #[tokio::test]
-    async fn last_assistant_reflects_terminal_message() {
-        let scripts = vec![finalize_script(finalize_text("hello world"))];
-        let mut agent = build_agent(scripts, Vec::new());
+    async fn prewarm_cache_reports_usage_without_touching_the_transcript() {
+        let mut reply = finalize_text("OK");
+        reply.usage.input = 5;
+        reply.usage.output = 1;
+        reply.usage.cache_write = 1_200;
+        let agent = build_agent(vec![finalize_script(reply)], Vec::new());
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
 
-        let text = agent
-            .run_single_turn("hi".to_string())
+        let sent = agent
+            .prewarm_cache(CancellationToken::new())
             .await
-            .expect("scripted success turn");
-        assert_eq!(text, "hello world");
+            .expect("prewarm succeeds");
+        assert!(sent);
+        assert!(agent.messages().is_empty());
+        assert_eq!(agent.accumulated_usage().cache_write, 1_200);
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            vec![EventLabel::UsageUpdate(AgentId::Main)]
+        );
 
-        let last = agent.last_assistant().expect("terminal message retained");
-        assert_eq!(last.stop_reason, StopReason::Stop);
-        let body: String = last
-            .content
-            .iter()
-            .filter_map(|c| match c {
-                AssistantContent::Text(t) => Some(t.text.as_str()),
-                _ => None,
-            })
-            .collect();
-        assert!(body.contains("hello world"));
+        // With caching off there is nothing to warm: no request (the
+        // strict provider has no script left) and no usage.
+        let mut agent = build_agent(Vec::new(), Vec::new());
+        agent.stream_options.cache_retention = aj_models::types::CacheRetention::None;
+        let sent = agent
+            .prewarm_cache(CancellationToken::new())
+            .await
+            .expect("skipped");
+        assert!(!sent);
+        assert_eq!(agent.accumulated_usage().cache_write, 0);
     }
 
-    /// `last_assistant` retains a non-retryable error terminal even
-    /// though the turn surfaces as an error and the failed message is
-    /// not pushed onto the transcript.
     #[tokio::test]
-    async fn last_assistant_reflects_error_terminal() {
-        let mut agent = build_agent(vec![overflow_error_script()], Vec::new());
+    async fn stream_failing_mid_content_keeps_the_partial_and_stops() {
+        // Strict-mode provider with a single script: a restart would
+        // panic on the missing second inference.
+        let scripts = vec![transient_error_after_text_script("half an ans")];
+        let mut agent = build_agent(scripts, Vec::new());
 
-        let result = agent.run_single_turn("hi".to_string()).await;
-        assert!(result.is_err(), "non-retryable overflow surfaces as error");
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
 
-        let last = agent.last_assistant().expect("terminal message retained");
-        assert_eq!(last.stop_reason, StopReason::Error);
-        assert_eq!(
-            last.error.as_ref().expect("error retained").category,
-            aj_models::types::ErrorCategory::ContextOverflow
+        let err = agent
+            .prompt("hello".to_string(), CancellationToken::new())
+            .await
+            .expect_err("a mid-content failure ends the run");
+        assert!(
+            matches!(&err, crate::TurnError::Recoverable(e) if e.to_string().contains("partial response was kept")),
+            "expected a Recoverable naming the kept partial, got: {err:?}"
         );
-    }
 
-    #[test]
-    fn scan_dangling_tool_uses_finds_unmatched_ids() {
-        // Sanity check on the test-only helper: a transcript
-        // ending in an assistant tool_call without a matching
-        // tool_result reports the dangling id.
-        use aj_models::types::ToolResultMessage;
+        let recorded = recorded.lock().unwrap();
+        assert!(
+            !recorded
+                .iter()
+                .any(|l| matches!(l, EventLabel::StreamRetry(..))),
+            "a mid-content failure must not restart: {recorded:?}"
+        );
+        assert!(
+            recorded
+                .iter()
+                .any(|l| matches!(l, EventLabel::Warning(_, text) if text.contains("failed partway through"))),
+            "expected the in-band warning: {recorded:?}"
+        );
+        drop(recorded);
 
-        let transcript = vec![
-            AgentMessage::wire(Message::User(UserMessage::text("hi"))),
-            AgentMessage::wire(Message::Assistant(AssistantMessage {
-                content: vec![AssistantContent::ToolCall(ToolCall {
-                    id: "tu-1".to_string(),
-                    name: "ping".to_string(),
-                    arguments: serde_json::json!({}),
-                })],
-                ..AssistantMessage::empty()
-            })),
+        let messages = agent.messages();
+        let Some(Message::Assistant(partial)) = messages.last().and_then(|m| m.as_wire()) else {
+            panic!("expected the partial assistant message to be kept");
+        };
+        assert_eq!(partial.stop_reason, StopReason::Error);
+        assert!(
+            matches!(&partial.content[..], [AssistantContent::Text(t)] if t.text == "half an ans")
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_failing_mid_content_restarts_when_configured() {
+        let scripts = vec![
+            transient_error_after_text_script("half an ans"),
+            finalize_script(finalize_text("whole answer")),
         ];
-        let dangling = super::scan_dangling_tool_uses(&transcript);
-        assert!(dangling.contains("tu-1"));
+        let mut agent = build_agent(scripts, Vec::new());
+        agent.set_retry_partial_streams(true);
 
-        let mut transcript_with_resolution = transcript.clone();
-        transcript_with_resolution.push(AgentMessage::wire(Message::ToolResult(
-            ToolResultMessage::text("tu-1", "ping", "done", false),
-        )));
-        assert!(super::scan_dangling_tool_uses(&transcript_with_resolution).is_empty());
+        let final_text = agent
+            .run_single_turn("hello".to_string())
+            .await
+            .expect("the restart succeeds");
+        assert_eq!(final_text, "whole answer");
+        // The discarded partial never reached the transcript.
+        let assistants = agent
+            .messages()
+            .iter()
+            .filter(|m| matches!(m.as_wire(), Some(Message::Assistant(_))))
+            .count();
+        assert_eq!(assistants, 1);
     }
 
     #[tokio::test]
-    async fn before_tool_call_hook_can_mutate_tool_input() {
-        // The hook flips the tool's input from `{}` to `{"flag": true}`;
-        // a tool that records its input in its outcome body proves the
-        // mutated args reached `execute`.
-        use std::sync::Arc;
+    async fn pause_turn_continues_until_terminal_stop() {
+        // A `PauseTurn` terminal means the provider wants to keep
+        // going: the agent must re-send the conversation rather than
+        // treat the turn as finished. Strict-mode provider: exactly two
+        // inferences are scripted, so a missing continuation leaves the
+        // paused text as the answer and an extra one panics.
+        let mut paused = finalize_text("searching… ");
+        paused.stop_reason = StopReason::PauseTurn;
+        let scripts = vec![
+            finalize_script(paused),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, Vec::new());
+
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
+
+        let final_text = agent
+            .run_single_turn("hello".to_string())
+            .await
+            .expect("paused turn should continue into a completed one");
+        assert_eq!(final_text, "done");
+
+        let recorded = recorded.lock().unwrap();
+        assert!(
+            recorded.contains(&EventLabel::Notice(
+                AgentId::Main,
+                "Model paused its turn; continuing…".to_string()
+            )),
+            "continuation should be announced: {recorded:?}"
+        );
+        let turn_starts = recorded
+            .iter()
+            .filter(|l| matches!(l, EventLabel::TurnStart(_)))
+            .count();
+        assert_eq!(turn_starts, 2, "each inference is its own turn");
+        drop(recorded);
+
+        // Both the paused and the continued assistant messages are on
+        // the transcript, in order, after the user prompt.
+        let stop_reasons: Vec<StopReason> = agent
+            .messages()
+            .iter()
+            .filter_map(|m| match m.as_wire() {
+                Some(Message::Assistant(a)) => Some(a.stop_reason.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stop_reasons, vec![StopReason::PauseTurn, StopReason::Stop]);
+    }
+
+    #[tokio::test]
+    async fn endless_pause_turns_end_the_turn_with_an_error() {
+        // Strict-mode provider: one script per allowed continuation
+        // plus the initial inference, so continuing past the cap
+        // panics instead of looping.
+        let scripts = (0..=super::MAX_PAUSE_CONTINUATIONS)
+            .map(|_| {
+                let mut paused = finalize_text("still going… ");
+                paused.stop_reason = StopReason::PauseTurn;
+                finalize_script(paused)
+            })
+            .collect();
+        let mut agent = build_agent(scripts, Vec::new());
+
+        let err = agent
+            .prompt("hello".to_string(), CancellationToken::new())
+            .await
+            .expect_err("the run gives up on endless pauses");
+        assert!(
+            matches!(&err, crate::TurnError::Recoverable(e) if e.to_string().contains("paused its turn")),
+            "{err}"
+        );
+        let paused = agent
+            .messages()
+            .iter()
+            .filter(|m| matches!(m.as_wire(), Some(Message::Assistant(_))))
+            .count();
+        assert_eq!(paused, super::MAX_PAUSE_CONTINUATIONS + 1);
+    }
+
+    #[tokio::test]
+    async fn empty_response_ends_the_run_with_a_notice() {
+        // Default policy: no continuation (strict provider, one
+        // script), and the user is told why nothing appeared.
+        let scripts = vec![finalize_script(finalize_text(""))];
+        let mut agent = build_agent(scripts, Vec::new());
+
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
+
+        agent
+            .run_single_turn("hello".to_string())
+            .await
+            .expect("an empty response is not an error");
+        assert!(
+            recorded.lock().unwrap().contains(&EventLabel::Notice(
+                AgentId::Main,
+                "The model returned an empty response.".to_string()
+            )),
+            "{:?}",
+            recorded.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_prompt_is_skipped_when_enabled() {
+        // Only two scripts: the repeat must not reach the provider,
+        // while a different prompt afterwards still runs.
+        let scripts = vec![
+            finalize_script(finalize_text("first")),
+            finalize_script(finalize_text("second")),
+        ];
+        let mut agent = build_agent(scripts, Vec::new());
+        agent.set_skip_repeated_prompts(true);
+
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
+
+        agent
+            .prompt("hello".to_string(), CancellationToken::new())
+            .await
+            .expect("first prompt runs");
+        assert!(agent.repeats_last_prompt("  hello\n"));
+        let len = agent.messages().len();
+        agent
+            .prompt("hello ".to_string(), CancellationToken::new())
+            .await
+            .expect("a skipped repeat is not an error");
+        assert_eq!(
+            agent.messages().len(),
+            len,
+            "the repeat must not be appended"
+        );
+        assert!(recorded.lock().unwrap().iter().any(
+            |l| matches!(l, EventLabel::Warning(_, text) if text == super::REPEATED_PROMPT_SKIPPED)
+        ));
+
+        agent
+            .prompt("something else".to_string(), CancellationToken::new())
+            .await
+            .expect("a new prompt still runs");
+        assert!(agent.messages().len() > len);
+    }
+
+    #[tokio::test]
+    async fn empty_response_nudges_the_model_once_when_enabled() {
+        // Two empty answers then text: the first is nudged, the second
+        // ends the run with the notice, so the third script is never
+        // reached.
+        let scripts = vec![
+            finalize_script(finalize_text("")),
+            finalize_script(finalize_text("  \n")),
+            finalize_script(finalize_text("unreachable")),
+        ];
+        let mut agent = build_agent(scripts, Vec::new());
+        agent.set_nudge_empty_responses(true);
+
+        let recorded: Arc<Mutex<Vec<EventLabel>>> = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+        let _handle = agent.subscribe(listener_from_sync(move |event| {
+            recorded_clone.lock().unwrap().push(label(event));
+        }));
+
+        agent
+            .run_single_turn("hello".to_string())
+            .await
+            .expect("an empty response is not an error");
+
+        let users: Vec<String> = agent
+            .messages()
+            .iter()
+            .filter_map(|m| match m.as_wire() {
+                Some(Message::User(u)) => match &u.content[..] {
+                    [aj_models::types::UserContent::Text(t)] => Some(t.text.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            users,
+            vec!["hello".to_string(), super::EMPTY_RESPONSE_NUDGE.to_string()]
+        );
+        let recorded = recorded.lock().unwrap();
+        let notices: Vec<&EventLabel> = recorded
+            .iter()
+            .filter(|l| matches!(l, EventLabel::Notice(..)))
+            .collect();
+        assert_eq!(notices.len(), 2, "{recorded:?}");
+        assert!(
+            matches!(notices[1], EventLabel::Notice(_, text) if text == "The model returned an empty response."),
+            "{recorded:?}"
+        );
+    }
+
+    /// Delegates to a [`ScriptedProvider`] and records the messages of
+    /// every request, so tests can check what was actually sent.
+    struct RecordingProvider {
+        inner: ScriptedProvider,
+        requests: Arc<Mutex<Vec<Vec<Message>>>>,
+    }
+
+    impl Provider for RecordingProvider {
+        fn stream(
+            &self,
+            model: &ModelInfo,
+            context: &aj_models::types::Context,
+            options: &StreamOptions,
+        ) -> aj_models::streaming::AssistantMessageEventStream {
+            self.requests.lock().unwrap().push(context.messages.clone());
+            self.inner.stream(model, context, options)
+        }
+
+        fn stream_simple(
+            &self,
+            model: &ModelInfo,
+            context: &aj_models::types::Context,
+            options: &aj_models::types::SimpleStreamOptions,
+        ) -> aj_models::streaming::AssistantMessageEventStream {
+            self.stream(model, context, &options.base)
+        }
+    }
+
+    #[tokio::test]
+    async fn nudged_request_leaves_out_the_empty_answer() {
+        let mut empty = finalize_text("");
+        empty.content.clear();
+        let scripts = vec![
+            ProviderScript::from_events(finalize_script(empty)),
+            ProviderScript::from_events(finalize_script(finalize_text("done"))),
+        ];
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let provider: Arc<dyn Provider> = Arc::new(RecordingProvider {
+            inner: ScriptedProvider::new(scripts).on_exhausted(ExhaustedBehavior::Panic),
+            requests: Arc::clone(&requests),
+        });
+        let mut agent = Agent::with_provider(
+            std::env::temp_dir(),
+            Vec::new(),
+            Vec::new(),
+            provider,
+            Arc::new(scripted_model_info()),
+            StreamOptions::default(),
+            None,
+        );
+        agent.seed_session(AgentSeed {
+            assembled_system_prompt: Some("test system prompt".to_string()),
+            ..AgentSeed::default()
+        });
+        agent.set_nudge_empty_responses(true);
+
+        let final_text = agent
+            .run_single_turn("hello".to_string())
+            .await
+            .expect("the nudged model answers");
+        assert_eq!(final_text, "done");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(
+            requests[1]
+                .iter()
+                .all(|m| !matches!(m, Message::Assistant(a) if a.content.is_empty())),
+            "{:?}",
+            requests[1]
+        );
+        assert!(matches!(
+            requests[1].last(),
+            Some(Message::User(u)) if matches!(
+                &u.content[..],
+                [aj_models::types::UserContent::Text(t)] if t.text == super::EMPTY_RESPONSE_NUDGE
+            )
+        ));
+    }
+
+    /// A turn addendum rides after the assembled prompt in its own
+    /// block for the next top-level run, then is cleared so the
+    /// following run sees the plain prompt again.
+    #[tokio::test]
+    async fn turn_system_addendum_applies_to_one_run() {
+        let scripts = vec![finalize_script(finalize_text("ok"))];
+        let mut agent = build_agent(scripts, Vec::new());
+        agent.seed_session(AgentSeed {
+            assembled_system_prompt: Some("base".to_string()),
+            ..AgentSeed::default()
+        });
+
+        agent.set_turn_system_addendum(Some("focus on auth".to_string()));
+        assert_eq!(
+            agent.inference_system_prompt(),
+            "base\n\n<turn-context>\nfocus on auth\n</turn-context>"
+        );
+
+        agent
+            .prompt("hello".to_string(), CancellationToken::new())
+            .await
+            .expect("turn completes");
+        assert_eq!(agent.inference_system_prompt(), "base");
+        assert_eq!(agent.assembled_system_prompt(), "base");
+    }
+
+    /// A tool switched off for the session leaves the wire tool list,
+    /// and a call the model makes to it anyway is refused without
+    /// running it. Switching it back on restores it.
+    #[tokio::test]
+    async fn session_disabled_tools_are_hidden_and_refused() {
+        let scripts = vec![
+            finalize_script(finalize_tool_use("tu-1", "ping")),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![PingTool.into(), ProgressTool.into()]);
+        assert_eq!(agent.tool_names(), vec!["ping", "progress"]);
+
+        agent.set_session_disabled_tools(std::collections::BTreeSet::from(["ping".to_string()]));
+        let names: Vec<String> = agent
+            .inference_tools()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["progress"]);
+
+        agent
+            .run_single_turn("ping please".to_string())
+            .await
+            .expect("run_single_turn");
+        let refused = agent.transcript.iter().find_map(|m| match m.as_wire() {
+            Some(Message::ToolResult(r)) => Some(r.clone()),
+            _ => None,
+        });
+        let refused = refused.expect("tool result recorded");
+        assert!(refused.is_error);
+        assert!(format!("{:?}", refused.content).contains("disabled by the user"));
+
+        agent.set_session_disabled_tools(Default::default());
+        assert_eq!(agent.inference_tools().len(), 2);
+    }
+
+    /// `last_assistant` exposes the terminal success message right after
+    /// a turn so the host's post-turn policy can classify it.
+    #[tokio::test]
+    async fn last_assistant_reflects_terminal_message() {
+        let scripts = vec![finalize_script(finalize_text("hello world"))];
+        let mut agent = build_agent(scripts, Vec::new());
+
+        let text = agent
+            .run_single_turn("hi".to_string())
+            .await
+            .expect("scripted success turn");
+        assert_eq!(text, "hello world");
+
+        let last = agent.last_assistant().expect("terminal message retained");
+        assert_eq!(last.stop_reason, StopReason::Stop);
+        let body: String = last
+            .content
+            .iter()
+            .filter_map(|c| match c {
+                AssistantContent::Text(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(body.contains("hello world"));
+    }
+
+    /// `last_assistant` retains a non-retryable error terminal even
+    /// though the turn surfaces as an error and the failed message is
+    /// not pushed onto the transcript.
+    #[tokio::test]
+    async fn last_assistant_reflects_error_terminal() {
+        let mut agent = build_agent(vec![overflow_error_script()], Vec::new());
+
+        let result = agent.run_single_turn("hi".to_string()).await;
+        assert!(result.is_err(), "non-retryable overflow surfaces as error");
+
+        let last = agent.last_assistant().expect("terminal message retained");
+        assert_eq!(last.stop_reason, StopReason::Error);
+        assert_eq!(
+            last.error.as_ref().expect("error retained").category,
+            aj_models::types::ErrorCategory::ContextOverflow
+        );
+    }
+
+    #[test]
+    fn scan_dangling_tool_uses_finds_unmatched_ids() {
+        // Sanity check on the test-only helper: a transcript
+        // ending in an assistant tool_call without a matching
+        // tool_result reports the dangling id.
+        use aj_models::types::ToolResultMessage;
+
+        let transcript = vec![
+            AgentMessage::wire(Message::User(UserMessage::text("hi"))),
+            AgentMessage::wire(Message::Assistant(AssistantMessage {
+                content: vec![AssistantContent::ToolCall(ToolCall {
+                    id: "tu-1".to_string(),
+                    name: "ping".to_string(),
+                    arguments: serde_json::json!({}),
+                })],
+                ..AssistantMessage::empty()
+            })),
+        ];
+        let dangling = super::scan_dangling_tool_uses(&transcript);
+        assert!(dangling.contains("tu-1"));
+
+        let mut transcript_with_resolution = transcript.clone();
+        transcript_with_resolution.push(AgentMessage::wire(Message::ToolResult(
+            ToolResultMessage::text("tu-1", "ping", "done", false),
+        )));
+        assert!(super::scan_dangling_tool_uses(&transcript_with_resolution).is_empty());
+    }
+
+    #[tokio::test]
+    async fn before_tool_call_hook_can_mutate_tool_input() {
+        // The hook flips the tool's input from `{}` to `{"flag": true}`;
+        // a tool that records its input in its outcome body proves the
+        // mutated args reached `execute`.
+        use std::sync::Arc;
 
         use crate::hooks::{BeforeToolCallHook, BeforeToolCallOutcome, ToolCallContext};
 
@@ -5197,6 +6772,48 @@ mod event_protocol_tests {
         );
     }
 
+    /// A spawned sub-agent's tool batches go through the parent's
+    /// batch hook, so a review such as edit approval covers them too.
+    #[tokio::test]
+    async fn sub_agent_tool_batches_go_through_the_parents_hook() {
+        use crate::hooks::{BeforeToolBatchHook, BeforeToolBatchOutcome, ToolBatchCall};
+
+        // Shared provider, consumed in run order: the parent's spawn,
+        // the sub-agent's `ping` call and report, the parent's answer.
+        let scripts = vec![
+            finalize_script(finalize_tool_use("tu-1", "agent")),
+            finalize_script(finalize_tool_use("sub-c0", "ping")),
+            finalize_script(finalize_text("sub report")),
+            finalize_script(finalize_text("parent done")),
+        ];
+        let mut agent = build_agent(scripts, vec![SpawnTool::blocking().into(), PingTool.into()]);
+
+        let seen: Arc<Mutex<Vec<(String, String)>>> = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_hook = Arc::clone(&seen);
+        let hook: BeforeToolBatchHook = Arc::new(move |calls: &[ToolBatchCall<'_>]| {
+            seen_in_hook.lock().unwrap().extend(
+                calls
+                    .iter()
+                    .map(|c| (c.call_id.to_string(), c.tool_name.to_string())),
+            );
+            Box::pin(async { BeforeToolBatchOutcome::Proceed })
+        });
+        agent.set_before_tool_batch(Some(hook));
+
+        agent
+            .run_single_turn("delegate work".to_string())
+            .await
+            .expect("run_single_turn");
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                ("tu-1".to_string(), "agent".to_string()),
+                ("sub-c0".to_string(), "ping".to_string()),
+            ]
+        );
+    }
+
     /// A sub-agent retained in the registry is live and re-promptable:
     /// re-prompting its handle directly (the capability the binary
     /// exercises for steering) appends the new user message and the
@@ -6243,6 +7860,314 @@ mod event_protocol_tests {
         assert_eq!(*state.max_active.lock().unwrap(), 2);
     }
 
+    /// A repeated `tool_use` id is renamed before the assistant message
+    /// lands, so both calls run and every result answers a distinct id
+    /// that the persisted assistant message also carries.
+    #[tokio::test]
+    async fn duplicate_tool_use_ids_are_disambiguated() {
+        let state = Arc::new(ProbeState::default());
+        let probe = ProbeTool {
+            name: "probe",
+            mode: ExecutionMode::Parallel,
+            state: Arc::clone(&state),
+            cancel_on_start: None,
+        };
+        let scripts = vec![
+            finalize_script(finalize_tool_uses(&[
+                ("dup", "probe", serde_json::json!({"id": "a"})),
+                ("dup_2", "probe", serde_json::json!({"id": "b"})),
+                ("dup", "probe", serde_json::json!({"id": "c"})),
+            ])),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![probe.into()]);
+        agent.run_single_turn("go".to_string()).await.expect("turn");
+
+        assert_eq!(tool_result_ids(&agent), vec!["dup", "dup_2", "dup_3"]);
+        let call_ids: Vec<String> = agent
+            .messages()
+            .iter()
+            .filter_map(|m| match m.as_wire() {
+                Some(Message::Assistant(a)) => Some(a.content.clone()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|block| match block {
+                AssistantContent::ToolCall(call) => Some(call.id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(call_ids, vec!["dup", "dup_2", "dup_3"]);
+        assert_eq!(state.finish_order.lock().unwrap().len(), 3);
+    }
+
+    /// With an audit log set, every executed call appends one line,
+    /// in completion order.
+    #[tokio::test]
+    async fn tool_audit_log_records_each_execution() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let probe = ProbeTool {
+            name: "probe",
+            mode: ExecutionMode::Sequential,
+            state: Arc::new(ProbeState::default()),
+            cancel_on_start: None,
+        };
+        let scripts = vec![
+            finalize_script(finalize_tool_uses(&[
+                ("c0", "probe", serde_json::json!({"id": "a"})),
+                ("c1", "probe", serde_json::json!({"id": "b"})),
+            ])),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![probe.into()]);
+        agent.set_tool_audit_log(Some(crate::audit::ToolAuditLog::new(&path)));
+        agent.run_single_turn("go".to_string()).await.expect("turn");
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2, "{text}");
+        assert_eq!(lines[0]["call_id"], "c0");
+        assert_eq!(lines[0]["tool"], "probe");
+        assert_eq!(lines[0]["input"]["id"], "a");
+        assert_eq!(lines[0]["agent_id"], "main");
+        assert_eq!(lines[1]["call_id"], "c1");
+    }
+
+    /// The batch hook sees every call before any runs; the calls it
+    /// short-circuits never execute but still land in call order with
+    /// their pre-baked outcome.
+    #[tokio::test]
+    async fn before_tool_batch_hook_short_circuits_selected_calls() {
+        use crate::hooks::{BeforeToolBatchHook, BeforeToolBatchOutcome, ToolBatchCall};
+
+        let state = Arc::new(ProbeState::default());
+        let probe = ProbeTool {
+            name: "probe",
+            mode: ExecutionMode::Parallel,
+            state: Arc::clone(&state),
+            cancel_on_start: None,
+        };
+        let scripts = vec![
+            finalize_script(finalize_tool_uses(&[
+                ("c0", "probe", serde_json::json!({"id": "c0"})),
+                ("c1", "probe", serde_json::json!({"id": "c1"})),
+            ])),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![probe.into()]);
+
+        let seen: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_hook = Arc::clone(&seen);
+        let hook: BeforeToolBatchHook = Arc::new(move |calls: &[ToolBatchCall<'_>]| {
+            seen_in_hook
+                .lock()
+                .unwrap()
+                .extend(calls.iter().map(|c| c.call_id.to_string()));
+            let denied = ToolOutcome {
+                content: vec![aj_models::types::UserContent::text("denied")],
+                details: ToolDetails::Text {
+                    summary: "denied".to_string(),
+                    body: "denied".to_string(),
+                },
+                is_error: true,
+            };
+            Box::pin(async move {
+                BeforeToolBatchOutcome::ShortCircuit {
+                    outcomes: std::collections::HashMap::from([("c1".to_string(), denied)]),
+                }
+            })
+        });
+        agent.set_before_tool_batch(Some(hook));
+        agent.run_single_turn("go".to_string()).await.expect("turn");
+
+        assert_eq!(*seen.lock().unwrap(), vec!["c0", "c1"]);
+        assert_eq!(*state.finish_order.lock().unwrap(), vec!["c0".to_string()]);
+        assert_eq!(tool_result_ids(&agent), vec!["c0", "c1"]);
+        let denied = agent
+            .messages()
+            .iter()
+            .find_map(|m| match m.as_wire() {
+                Some(Message::ToolResult(r)) if r.tool_call_id == "c1" => Some(r.is_error),
+                _ => None,
+            })
+            .expect("c1 result");
+        assert!(denied);
+    }
+
+    /// A [`ToolInterrupt`] stops the running tool without ending the
+    /// turn: the call lands as an error result and the model's next
+    /// reply still arrives.
+    #[tokio::test(start_paused = true)]
+    async fn tool_interrupt_stops_the_tool_but_not_the_turn() {
+        let state = Arc::new(ProbeState::default());
+        let probe = ProbeTool {
+            name: "probe",
+            mode: ExecutionMode::Sequential,
+            state: Arc::clone(&state),
+            cancel_on_start: None,
+        };
+        let scripts = vec![
+            finalize_script(finalize_tool_uses(&[(
+                "c0",
+                "probe",
+                serde_json::json!({"id": "c0", "delay_ms": 3_600_000}),
+            )])),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![probe.into()]);
+        let interrupt = crate::tool::ToolInterrupt::new();
+        agent.set_tool_interrupt(interrupt.clone());
+        let stopper = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            interrupt.interrupt();
+        });
+
+        agent.run_single_turn("go".to_string()).await.expect("turn");
+        stopper.await.unwrap();
+
+        assert!(state.finish_order.lock().unwrap().is_empty());
+        let result = agent
+            .messages()
+            .iter()
+            .find_map(|m| match m.as_wire() {
+                Some(Message::ToolResult(r)) if r.tool_call_id == "c0" => Some(r.clone()),
+                _ => None,
+            })
+            .expect("c0 result");
+        assert!(result.is_error);
+        let last = agent.messages().last().and_then(|m| m.as_wire());
+        assert!(
+            matches!(last, Some(Message::Assistant(_))),
+            "the turn continued to the model's reply"
+        );
+    }
+
+    /// A closure-backed tool from `ErasedToolDefinition::from_fn`
+    /// receives the model's raw arguments and the working directory,
+    /// and its outcome lands as the tool result.
+    #[tokio::test]
+    async fn closure_tools_run_like_trait_tools() {
+        let greet = ErasedToolDefinition::from_fn(
+            "greet",
+            "Greet someone",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"who": {"type": "string"}},
+                "required": ["who"],
+            }),
+            |ctx, args| async move {
+                let who = args["who"].as_str().unwrap_or("nobody").to_string();
+                let text = format!("hello {who} from {}", ctx.working_directory.display());
+                Ok(ToolOutcome {
+                    content: vec![aj_models::types::UserContent::text(text.clone())],
+                    details: ToolDetails::Text {
+                        summary: who,
+                        body: text,
+                    },
+                    is_error: false,
+                })
+            },
+        )
+        .with_execution_mode(ExecutionMode::Sequential);
+        assert_eq!(greet.execution_mode, ExecutionMode::Sequential);
+
+        let scripts = vec![
+            finalize_script(finalize_tool_uses(&[(
+                "c0",
+                "greet",
+                serde_json::json!({"who": "world"}),
+            )])),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![greet]);
+        agent.run_single_turn("go".to_string()).await.expect("turn");
+
+        let result = agent
+            .messages()
+            .iter()
+            .find_map(|m| match m.as_wire() {
+                Some(Message::ToolResult(r)) if r.tool_call_id == "c0" => Some(r.clone()),
+                _ => None,
+            })
+            .expect("c0 result");
+        assert!(!result.is_error);
+        let text = match &result.content[..] {
+            [aj_models::types::UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        };
+        assert!(text.starts_with("hello world from "), "{text}");
+    }
+
+    /// A read-only tool returning the same large result for the same
+    /// input is sent as a pointer the second time; a tool not marked
+    /// read-only always sends its full result.
+    #[tokio::test]
+    async fn repeated_read_only_results_are_deduplicated() {
+        let big = "x".repeat(crate::recent_calls::DEDUPE_MIN_BYTES);
+        let tool = |name: &'static str, read_only: bool| {
+            let big = big.clone();
+            let mut tool = ErasedToolDefinition::from_fn(
+                name,
+                "Read something",
+                serde_json::json!({"type": "object", "properties": {}}),
+                move |_ctx, _args| {
+                    let big = big.clone();
+                    async move {
+                        Ok(ToolOutcome {
+                            content: vec![aj_models::types::UserContent::text(big.clone())],
+                            details: ToolDetails::Text {
+                                summary: name.to_string(),
+                                body: big,
+                            },
+                            is_error: false,
+                        })
+                    }
+                },
+            );
+            tool.read_only = read_only;
+            tool
+        };
+        let args = serde_json::json!({"path": "/a.rs"});
+        let scripts = vec![
+            finalize_script(finalize_tool_uses(&[
+                ("c0", "look", args.clone()),
+                ("c1", "poke", args.clone()),
+            ])),
+            finalize_script(finalize_tool_uses(&[
+                ("c2", "look", args.clone()),
+                ("c3", "poke", args.clone()),
+            ])),
+            finalize_script(finalize_text("done")),
+        ];
+        let mut agent = build_agent(scripts, vec![tool("look", true), tool("poke", false)]);
+        agent.run_single_turn("go".to_string()).await.expect("turn");
+
+        let texts: std::collections::HashMap<String, String> = agent
+            .messages()
+            .iter()
+            .filter_map(|m| match m.as_wire() {
+                Some(Message::ToolResult(r)) => match &r.content[..] {
+                    [aj_models::types::UserContent::Text(t)] => {
+                        Some((r.tool_call_id.clone(), t.text.clone()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts["c0"], big);
+        assert_eq!(texts["c1"], big);
+        assert_eq!(
+            texts["c2"],
+            "Identical to the previous result of this look call (see above)."
+        );
+        assert_eq!(texts["c3"], big);
+    }
+
     /// Serial tools are singleton barrier groups: two calls to a
     /// `Sequential` tool never overlap.
     #[tokio::test]
@@ -0,0 +1,85 @@
+//! Full tool outputs kept behind truncated results.
+//!
+//! A tool that cuts its result down for the model can stash the full
+//! text here through [`crate::tool::ToolContext::stash_output`] and
+//! name the returned token in its truncation footer; the `read_more`
+//! builtin then pages through it on request. The cache lives on the
+//! agent's session state, so tokens are scoped to one agent and die
+//! with the process.
+//!
+//! Eviction is oldest-first: at most [`OUTPUT_CACHE_MAX_ENTRIES`]
+//! outputs and [`OUTPUT_CACHE_MAX_BYTES`] in total are kept, except
+//! that the newest output always stays, however large.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Most outputs kept at once.
+pub const OUTPUT_CACHE_MAX_ENTRIES: usize = 16;
+/// Total size of the kept outputs before older ones are evicted.
+pub const OUTPUT_CACHE_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// Stashed outputs in insertion order, keyed by token.
+#[derive(Debug, Default)]
+pub struct OutputCache {
+    entries: VecDeque<(String, Arc<str>)>,
+    bytes: usize,
+    next_id: usize,
+}
+
+impl OutputCache {
+    /// Keep `output` and return its token (`out-1`, `out-2`, ...),
+    /// evicting the oldest outputs over the limits.
+    pub fn insert(&mut self, output: String) -> String {
+        self.next_id += 1;
+        let token = format!("out-{}", self.next_id);
+        self.bytes += output.len();
+        self.entries.push_back((token.clone(), Arc::from(output)));
+        while self.entries.len() > OUTPUT_CACHE_MAX_ENTRIES
+            || (self.bytes > OUTPUT_CACHE_MAX_BYTES && self.entries.len() > 1)
+        {
+            if let Some((_, evicted)) = self.entries.pop_front() {
+                self.bytes -= evicted.len();
+            }
+        }
+        token
+    }
+
+    /// The output stashed under `token`, `None` for an unknown or
+    /// evicted token.
+    pub fn get(&self, token: &str) -> Option<Arc<str>> {
+        self.entries
+            .iter()
+            .find(|(t, _)| t == token)
+            .map(|(_, output)| Arc::clone(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_oldest_entries_first() {
+        let mut cache = OutputCache::default();
+        let first = cache.insert("first".to_string());
+        let tokens: Vec<String> = (0..OUTPUT_CACHE_MAX_ENTRIES)
+            .map(|i| cache.insert(i.to_string()))
+            .collect();
+        assert_eq!(cache.get(&first), None);
+        assert_eq!(cache.get(&tokens[0]).as_deref(), Some("0"));
+        assert_eq!(cache.get("out-999"), None);
+    }
+
+    #[test]
+    fn keeps_the_newest_entry_even_over_the_byte_limit() {
+        let mut cache = OutputCache::default();
+        let small = cache.insert("small".to_string());
+        let big = cache.insert("x".repeat(OUTPUT_CACHE_MAX_BYTES + 1));
+        assert_eq!(cache.get(&small), None);
+        assert_eq!(
+            cache.get(&big).map(|o| o.len()),
+            Some(OUTPUT_CACHE_MAX_BYTES + 1)
+        );
+    }
+}
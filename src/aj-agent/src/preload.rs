@@ -0,0 +1,49 @@
+//! Preloaded context: transcript entries injected without an inference.
+//!
+//! A scripted or reproducible session often wants the model to start
+//! out already "knowing" something — a file it would otherwise read, a
+//! command's output it would otherwise run — without paying an API
+//! round trip (and a model decision) to get there. [`Preload`] entries
+//! describe that material; [`crate::Agent::preload`] turns them into
+//! ordinary transcript messages:
+//!
+//! - one user message carrying [`PRELOAD_INTRO`] followed by every
+//!   [`Preload::Context`] text, so the transcript leads with a user
+//!   turn as every provider requires;
+//! - when there are [`Preload::ToolResult`] entries, one assistant
+//!   message with a `tool_use` block per entry (ids
+//!   `preload_<millis>_<n>`, unique across repeated preloads in one
+//!   session) and `stop_reason: ToolUse`;
+//! - one tool-result message per call, in the same order.
+//!
+//! The sequence is well-formed on its own, so the next user prompt can
+//! follow it directly. The messages go through the regular
+//! `MessageStart` / `MessageEnd` events, so persistence and renderers
+//! treat them like any other turn.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Opening text of the user message that carries preloaded material.
+pub const PRELOAD_INTRO: &str =
+    "The following context was preloaded for this session; no reply is needed yet.";
+
+/// One piece of preloaded material.
+///
+/// Deserializes from either a bare string (context) or an object
+/// `{"tool": ..., "input": {...}, "output": ...}` (a tool result), which
+/// is the shape of the binary's `--preload` file.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Preload {
+    /// Free-form text handed to the model as user-supplied context.
+    Context(String),
+    /// A call to `tool` with `input`, answered with `output` as if the
+    /// tool had run. `tool` must be one of the agent's tools.
+    ToolResult {
+        tool: String,
+        #[serde(default)]
+        input: Map<String, Value>,
+        output: String,
+    },
+}
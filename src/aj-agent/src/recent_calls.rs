@@ -0,0 +1,110 @@
+//! Recent read-only tool calls, for deduplicating repeated results.
+//!
+//! In iterative loops a model often re-runs the exact same read or
+//! search and gets the same large result back. The agent still runs
+//! the call (the file may have changed since), but when a read-only
+//! tool's result matches the one the same call returned last time,
+//! the wire content is replaced with a short pointer at the earlier
+//! result; the structured `details` the UI renders are left alone.
+//!
+//! Entries are keyed by tool name plus a hash of the input and hold a
+//! hash of the result content. At most [`RECENT_CALLS_MAX_ENTRIES`]
+//! calls are remembered, oldest evicted first, and the agent clears
+//! the cache whenever its transcript is replaced (compaction, resume),
+//! since the earlier result may no longer be in context.
+
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use aj_models::types::UserContent;
+use serde_json::Value;
+
+/// Most distinct calls remembered at once.
+pub const RECENT_CALLS_MAX_ENTRIES: usize = 32;
+
+/// Results smaller than this many bytes of serialized content are
+/// sent in full: the pointer wouldn't save anything.
+pub const DEDUPE_MIN_BYTES: usize = 1024;
+
+/// `(call key, content hash)` pairs, oldest first.
+#[derive(Debug, Default)]
+pub struct RecentCalls {
+    entries: VecDeque<(u64, u64)>,
+}
+
+impl RecentCalls {
+    /// Record `content` as the latest result of `tool` with `input`
+    /// and report whether it is identical to the previous result of
+    /// that same call (and large enough to be worth deduplicating).
+    pub fn record(&mut self, tool: &str, input: &Value, content: &[UserContent]) -> bool {
+        let Ok(serialized) = serde_json::to_string(content) else {
+            return false;
+        };
+        let key = hash(&(tool, input.to_string()));
+        let content_hash = hash(&serialized);
+        let previous = self
+            .entries
+            .iter()
+            .position(|(k, _)| *k == key)
+            .and_then(|i| self.entries.remove(i));
+        self.entries.push_back((key, content_hash));
+        while self.entries.len() > RECENT_CALLS_MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        serialized.len() >= DEDUPE_MIN_BYTES && previous.is_some_and(|(_, h)| h == content_hash)
+    }
+
+    /// Forget every call.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn big(text: &str) -> Vec<UserContent> {
+        vec![UserContent::text(text.repeat(DEDUPE_MIN_BYTES))]
+    }
+
+    #[test]
+    fn flags_only_repeated_identical_results_of_the_same_call() {
+        let mut calls = RecentCalls::default();
+        let input = json!({"path": "/a.rs"});
+        assert!(!calls.record("read_file", &input, &big("a")));
+        assert!(calls.record("read_file", &input, &big("a")));
+        // Changed content, a different input, or a different tool is
+        // a fresh result.
+        assert!(!calls.record("read_file", &input, &big("b")));
+        assert!(!calls.record("read_file", &json!({"path": "/b.rs"}), &big("b")));
+        assert!(!calls.record("hash", &input, &big("b")));
+        // Small results are always sent in full.
+        let small = vec![UserContent::text("ok".to_string())];
+        calls.record("count_lines", &input, &small);
+        assert!(!calls.record("count_lines", &input, &small));
+    }
+
+    #[test]
+    fn evicts_the_oldest_calls_and_clears() {
+        let mut calls = RecentCalls::default();
+        let first = json!({"n": "first"});
+        calls.record("read_file", &first, &big("x"));
+        for i in 0..RECENT_CALLS_MAX_ENTRIES {
+            calls.record("read_file", &json!({ "n": i }), &big("x"));
+        }
+        assert!(!calls.record("read_file", &first, &big("x")));
+
+        calls.clear();
+        assert!(!calls.record("read_file", &first, &big("x")));
+    }
+}
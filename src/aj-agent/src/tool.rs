@@ -9,7 +9,7 @@
 use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use aj_models::types::UserContent;
 use schemars::JsonSchema;
@@ -56,6 +56,46 @@ impl Default for ExecutionMode {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Tool interrupt
+// ---------------------------------------------------------------------------
+
+/// Handle that stops the tool calls an agent is currently running
+/// without cancelling its turn.
+///
+/// The agent arms a fresh token for every concurrency group of a tool
+/// batch; [`ToolInterrupt::interrupt`] fires the armed one. Each call
+/// in the group is dropped (bash tears down its process group) and
+/// lands as an `is_error: true` result telling the model the user
+/// stopped it, after which the turn carries on as if the tool had
+/// failed. Calls in later groups are unaffected. An interrupt while
+/// no tool is running is a no-op.
+///
+/// Clones share one slot, so a frontend keeps a clone and installs
+/// another on the agent via `Agent::set_tool_interrupt`.
+#[derive(Clone, Default)]
+pub struct ToolInterrupt(Arc<Mutex<CancellationToken>>);
+
+impl ToolInterrupt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the currently running tool calls, if any.
+    pub fn interrupt(&self) {
+        self.0.lock().expect("tool interrupt poisoned").cancel();
+    }
+
+    /// Replace the armed token with a fresh one and return it. Called
+    /// by the agent as each tool group starts, so an interrupt that
+    /// landed between groups doesn't leak into the next one.
+    pub(crate) fn arm(&self) -> CancellationToken {
+        let fresh = CancellationToken::new();
+        *self.0.lock().expect("tool interrupt poisoned") = fresh.clone();
+        fresh
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tool details — closed enum keyed by rendering shape
 // ---------------------------------------------------------------------------
@@ -619,6 +659,14 @@ pub trait ToolDefinition: Send + Sync {
         ExecutionMode::default()
     }
 
+    /// Whether the tool only reads state. A read-only tool's result
+    /// that repeats the previous result of the identical call is sent
+    /// to the model as a pointer at the earlier one (see
+    /// [`crate::recent_calls`]). Default `false`.
+    fn read_only(&self) -> bool {
+        false
+    }
+
     /// Run the tool. Errors should be surfaced as `is_error: true`
     /// outcomes when the model can recover; bubbling up an `Err`
     /// causes the agent to synthesize a generic error tool_result
@@ -650,16 +698,88 @@ pub type ErasedToolFn = Arc<
 /// collections of tools (the agent stores `Vec<ErasedToolDefinition>`).
 ///
 /// Convert from a [`ToolDefinition`] via the blanket
-/// `From<T> for ErasedToolDefinition` impl.
+/// `From<T> for ErasedToolDefinition` impl, or build one from a plain
+/// closure and a hand-written schema with [`Self::from_fn`].
 #[derive(Clone)]
 pub struct ErasedToolDefinition {
     pub name: String,
     pub description: String,
     pub input_schema: Value,
     pub execution_mode: ExecutionMode,
+    /// [`ToolDefinition::read_only`]; `false` for [`Self::from_fn`]
+    /// tools unless set.
+    pub read_only: bool,
     pub func: ErasedToolFn,
 }
 
+/// The slice of [`ToolContext`] handed to a [`ErasedToolDefinition::from_fn`]
+/// closure. Owned, so the closure's future can outlive the call's
+/// borrow of the context.
+#[derive(Debug, Clone)]
+pub struct ToolFnContext {
+    /// [`ToolContext::working_directory`] at call time.
+    pub working_directory: PathBuf,
+    /// [`ToolContext::cancellation`]; long-running work should stop
+    /// when it fires.
+    pub cancellation: CancellationToken,
+}
+
+impl ErasedToolDefinition {
+    /// Build a tool from a closure, for embedders registering ad-hoc
+    /// tools (e.g. backed by their own systems) without implementing
+    /// [`ToolDefinition`].
+    ///
+    /// `input_schema` is the JSON Schema advertised to the model: an
+    /// object schema with `properties` (and usually `required`), as
+    /// [`derive_schema`] produces. The closure has the signature
+    ///
+    /// ```text
+    /// Fn(ToolFnContext, serde_json::Value) -> impl Future<Output = Result<ToolOutcome, BoxError>>
+    /// ```
+    ///
+    /// It receives the model's raw JSON arguments, unvalidated against
+    /// the schema, and returns a `Send + 'static` future: clone
+    /// whatever the future needs out of the closure's captures
+    /// (typically `Arc`s) before the `async move` block. As with
+    /// [`ToolDefinition::execute`], report recoverable failures as
+    /// `is_error: true` outcomes; an `Err` becomes a generic error
+    /// result.
+    ///
+    /// The tool runs in [`ExecutionMode::Parallel`]; use
+    /// [`Self::with_execution_mode`] for one with side effects.
+    pub fn from_fn<F, Fut>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        input_schema: Value,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(ToolFnContext, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ToolOutcome, BoxError>> + Send + 'static,
+    {
+        ErasedToolDefinition {
+            name: name.into(),
+            description: description.into(),
+            input_schema,
+            execution_mode: ExecutionMode::default(),
+            read_only: false,
+            func: Arc::new(move |ctx, raw_input| {
+                let fn_ctx = ToolFnContext {
+                    working_directory: ctx.working_directory(),
+                    cancellation: ctx.cancellation(),
+                };
+                Box::pin(f(fn_ctx, raw_input))
+            }),
+        }
+    }
+
+    /// Replace the execution mode (default [`ExecutionMode::Parallel`]).
+    pub fn with_execution_mode(mut self, execution_mode: ExecutionMode) -> Self {
+        self.execution_mode = execution_mode;
+        self
+    }
+}
+
 impl<T> From<T> for ErasedToolDefinition
 where
     T: ToolDefinition + Clone + 'static,
@@ -669,11 +789,13 @@ where
         let description = tool.description().to_string();
         let input_schema = tool.input_schema();
         let execution_mode = tool.execution_mode();
+        let read_only = tool.read_only();
         ErasedToolDefinition {
             name,
             description,
             input_schema,
             execution_mode,
+            read_only,
             func: Arc::new(move |ctx, raw_input| {
                 let parsed: Result<T::Input, _> = serde_json::from_value(raw_input);
                 let tool = tool.clone();
@@ -705,6 +827,15 @@ pub trait ToolContext: Send {
     /// Replace the session's todo list.
     fn set_todo_list(&mut self, todos: Vec<TodoItem>);
 
+    /// Keep the full text behind a truncated result in the session's
+    /// [`crate::output_cache::OutputCache`] and return the token the
+    /// `read_more` tool pages it with.
+    fn stash_output(&mut self, output: String) -> String;
+
+    /// An output kept by [`Self::stash_output`], `None` once evicted
+    /// or for an unknown token.
+    fn stashed_output(&self, token: &str) -> Option<Arc<str>>;
+
     /// Spawn a sub-agent on the current bus.
     ///
     /// The child shares the parent's event bus tagged with a fresh
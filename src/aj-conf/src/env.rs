@@ -1,6 +1,7 @@
-//! The agent's working environment: working directory, git root, OS, date,
-//! the base system prompt, and the user/project `AGENTS.md`/`CLAUDE.md`
-//! context files stitched into the prompt.
+//! The agent's working environment: working directory, git root,
+//! detected project types, OS, date, the base system prompt, and the
+//! user/project `AGENTS.md`/`CLAUDE.md` context files stitched into the
+//! prompt.
 
 use std::env;
 use std::fmt;
@@ -8,6 +9,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::paths::{find_git_root, home_dir, project_dirs_upward};
+use crate::schema::{Config, ConfigStyleTerseness};
 use crate::skills::{self, Skill, SkillDiagnostic};
 
 /// Prefix for project-level AGENTS.md instructions injected into the system
@@ -38,8 +40,34 @@ pub struct ContextFile {
     /// What kind of context file this is. Used to pick the right framing when
     /// stitching the file into the system prompt and to label it in the UI.
     pub kind: ContextFileKind,
-    /// Contents of the file.
+    /// Contents of the file, cut short by [`AgentEnv::cap_context_files`]
+    /// when the file exceeds the size cap.
     pub content: String,
+    /// Size in bytes of the file on disk when its content was truncated;
+    /// `None` when the whole file is included.
+    pub truncated_from: Option<usize>,
+}
+
+impl ContextFile {
+    /// Startup warning for a truncated file, `None` when it was
+    /// included whole.
+    pub fn truncation_warning(&self) -> Option<String> {
+        let original = self.truncated_from?;
+        Some(format!(
+            "{} is {original} bytes and was truncated in the system prompt \
+             (raise max_context_file_bytes to include more)",
+            self.path.display(),
+        ))
+    }
+}
+
+/// Marker appended to a truncated context file so the model knows the
+/// instructions it sees are incomplete and where to find the rest.
+fn truncation_marker(path: &Path, original: usize) -> String {
+    format!(
+        "\n\n[... truncated: {} is {original} bytes; read the file for the rest ...]\n",
+        path.display()
+    )
 }
 
 /// Kind of a [ContextFile]. Determines the prefix text used when injecting the
@@ -75,6 +103,28 @@ impl ContextFileKind {
     }
 }
 
+/// Build-manifest files that identify a project type, as `(file,
+/// language, tool)`. Checked in order; a language is reported once, by
+/// its first marker found, so a Python project with both
+/// `pyproject.toml` and `requirements.txt` reads as one type.
+const PROJECT_MARKERS: &[(&str, &str, &str)] = &[
+    ("Cargo.toml", "Rust", "Cargo"),
+    ("package.json", "JavaScript/TypeScript", "npm"),
+    ("deno.json", "JavaScript/TypeScript", "Deno"),
+    ("pyproject.toml", "Python", "pyproject.toml"),
+    ("setup.py", "Python", "setup.py"),
+    ("requirements.txt", "Python", "pip"),
+    ("go.mod", "Go", "Go modules"),
+    ("pom.xml", "Java", "Maven"),
+    ("build.gradle", "Java/Kotlin", "Gradle"),
+    ("build.gradle.kts", "Java/Kotlin", "Gradle"),
+    ("Gemfile", "Ruby", "Bundler"),
+    ("composer.json", "PHP", "Composer"),
+    ("mix.exs", "Elixir", "Mix"),
+    ("Package.swift", "Swift", "SwiftPM"),
+    ("CMakeLists.txt", "C/C++", "CMake"),
+];
+
 /// The agent's base system prompt and where it came from.
 #[derive(Debug, Clone)]
 pub struct SystemPrompt {
@@ -100,12 +150,37 @@ impl SystemPromptSource {
     }
 }
 
+/// The `style_*` config values, rendered into a `<style>` block of the
+/// system prompt. Kept apart from the context files so the user's tone
+/// preferences never read as project instructions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptStyle {
+    pub terseness: Option<ConfigStyleTerseness>,
+    pub explain_first: bool,
+    pub language: Option<String>,
+}
+
+impl PromptStyle {
+    /// Pick the style values out of `config`.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            terseness: config.style_terseness,
+            explain_first: config.style_explain_first,
+            language: config.style_language.clone(),
+        }
+    }
+}
+
 /// The working environment of the agent, includes configuration, the system
 /// prompt, working directories, etc.
 #[derive(Debug, Clone)]
 pub struct AgentEnv {
     pub working_directory: PathBuf,
     pub git_root_directory: Option<PathBuf>,
+    /// Project types detected from build manifests in the working
+    /// directory and the git root, e.g. `Rust (Cargo)`. Empty when none
+    /// was recognized.
+    pub project_types: Vec<String>,
     pub operating_system: String,
     pub today_date: String,
     /// The base system prompt: the builtin one or an override file from the
@@ -123,6 +198,9 @@ pub struct AgentEnv {
     /// Non-fatal problems hit while discovering skills, for the binary to
     /// surface alongside its other startup diagnostics.
     pub skill_diagnostics: Vec<SkillDiagnostic>,
+    /// Tone preferences from the config; empty (the default) adds no
+    /// `<style>` block. Set by the binary after discovery.
+    pub style: PromptStyle,
 }
 
 impl AgentEnv {
@@ -159,6 +237,8 @@ impl AgentEnv {
         disabled_skills: &[String],
     ) -> Self {
         let git_root_directory = find_git_root(&working_directory);
+        let project_types =
+            Self::detect_project_types(&working_directory, git_root_directory.as_deref());
         let operating_system = env::consts::OS.to_string();
         let system_prompt = Self::resolve_system_prompt(home, builtin_system_prompt);
 
@@ -181,15 +261,64 @@ impl AgentEnv {
         AgentEnv {
             working_directory,
             git_root_directory,
+            project_types,
             operating_system,
             today_date,
             system_prompt,
             context_files,
             skills,
             skill_diagnostics,
+            style: PromptStyle::default(),
+        }
+    }
+
+    /// Cut every context file longer than `max_bytes` down to its first
+    /// `max_bytes` (at a char boundary) and append a marker saying so.
+    /// Truncated files record their original size in
+    /// [`ContextFile::truncated_from`] for the startup warning. `0`
+    /// disables the cap.
+    pub fn cap_context_files(&mut self, max_bytes: u64) {
+        let Ok(max_bytes) = usize::try_from(max_bytes) else {
+            return;
+        };
+        if max_bytes == 0 {
+            return;
+        }
+        for file in &mut self.context_files {
+            let original = file.content.len();
+            if original <= max_bytes {
+                continue;
+            }
+            let mut end = max_bytes;
+            while !file.content.is_char_boundary(end) {
+                end -= 1;
+            }
+            file.content.truncate(end);
+            file.content
+                .push_str(&truncation_marker(&file.path, original));
+            file.truncated_from = Some(original);
         }
     }
 
+    /// Detect project types from the [`PROJECT_MARKERS`] present in the
+    /// working directory and, when different, the git root. Every
+    /// recognized language is listed, working directory first.
+    fn detect_project_types(working_directory: &Path, git_root: Option<&Path>) -> Vec<String> {
+        let mut dirs = vec![working_directory];
+        dirs.extend(git_root.filter(|root| *root != working_directory));
+        let mut languages = Vec::new();
+        let mut types = Vec::new();
+        for dir in dirs {
+            for (file, language, tool) in PROJECT_MARKERS {
+                if !languages.contains(language) && dir.join(file).is_file() {
+                    languages.push(*language);
+                    types.push(format!("{language} ({tool})"));
+                }
+            }
+        }
+        types
+    }
+
     /// Resolve the base system prompt: an override file under `home` fully
     /// replaces the builtin prompt. Falls back to `builtin` when `home` is
     /// `None` or no override file exists.
@@ -235,6 +364,7 @@ impl AgentEnv {
                     path,
                     kind: ContextFileKind::UserInstructions,
                     content,
+                    truncated_from: None,
                 });
             }
         }
@@ -275,6 +405,7 @@ impl AgentEnv {
                     path,
                     kind: ContextFileKind::ProjectInstructions,
                     content,
+                    truncated_from: None,
                 });
             }
         }
@@ -289,6 +420,9 @@ impl fmt::Display for AgentEnv {
             Some(git_root) => writeln!(f, "Git root directory: {}", git_root.display())?,
             None => writeln!(f, "Git root directory: None")?,
         }
+        if !self.project_types.is_empty() {
+            writeln!(f, "Project type: {}", self.project_types.join(", "))?;
+        }
         writeln!(f, "Operating system: {}", self.operating_system)?;
         write!(f, "Today's date: {}", self.today_date)
     }
@@ -363,6 +497,43 @@ mod tests {
         assert!(display_output.contains("Git root directory: None"));
         assert!(display_output.contains("Operating system:"));
         assert!(display_output.contains("Today's date: 2026-01-02"));
+        assert!(!display_output.contains("Project type"));
+    }
+
+    #[test]
+    fn detects_every_project_type_across_cwd_and_git_root() {
+        let root = crate::test_temp_dir("project-types");
+        let cwd = root.join("web");
+        fs::create_dir_all(&cwd).unwrap();
+        fs::write(root.join("Cargo.toml"), "").unwrap();
+        fs::write(cwd.join("package.json"), "{}").unwrap();
+        fs::write(cwd.join("pyproject.toml"), "").unwrap();
+        fs::write(cwd.join("requirements.txt"), "").unwrap();
+
+        let types = AgentEnv::detect_project_types(&cwd, Some(&root));
+        assert_eq!(
+            types,
+            [
+                "JavaScript/TypeScript (npm)",
+                "Python (pyproject.toml)",
+                "Rust (Cargo)"
+            ]
+        );
+        assert!(AgentEnv::detect_project_types(&root.join("missing"), None).is_empty());
+
+        let mut env = AgentEnv::discover(
+            PathBuf::from("/work"),
+            None,
+            "2026-01-02".to_string(),
+            "builtin prompt",
+            &[],
+        );
+        env.project_types = types;
+        assert!(format!("{env}").contains(
+            "Project type: JavaScript/TypeScript (npm), Python (pyproject.toml), Rust (Cargo)"
+        ));
+
+        fs::remove_dir_all(&root).ok();
     }
 
     #[test]
@@ -451,6 +622,43 @@ mod tests {
         fs::remove_dir_all(&root).ok();
     }
 
+    #[test]
+    fn cap_context_files_truncates_at_a_char_boundary_with_a_marker() {
+        let mut env = AgentEnv::discover(
+            PathBuf::from("/work"),
+            None,
+            "2026-01-02".to_string(),
+            "builtin prompt",
+            &[],
+        );
+        let file = |content: &str| ContextFile {
+            path: PathBuf::from("/work/AGENTS.md"),
+            kind: ContextFileKind::ProjectInstructions,
+            content: content.to_string(),
+            truncated_from: None,
+        };
+        // "é" is two bytes, so a 4-byte cap falls mid-character and
+        // backs off to byte 3.
+        env.context_files = vec![file("abcé rest"), file("tiny")];
+        env.cap_context_files(4);
+
+        let big = &env.context_files[0];
+        assert!(
+            big.content
+                .starts_with("abc\n\n[... truncated: /work/AGENTS.md is 10 bytes")
+        );
+        assert_eq!(big.truncated_from, Some(10));
+        assert!(big.truncation_warning().unwrap().contains("is 10 bytes"));
+        let small = &env.context_files[1];
+        assert_eq!(small.content, "tiny");
+        assert!(small.truncation_warning().is_none());
+
+        // Zero disables the cap.
+        env.context_files = vec![file("abcé rest")];
+        env.cap_context_files(0);
+        assert_eq!(env.context_files[0].content, "abcé rest");
+    }
+
     #[test]
     fn test_resolve_system_prompt_prefers_agents_override() {
         let home = crate::test_temp_dir("sysprompt-prefers-agents");
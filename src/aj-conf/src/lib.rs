@@ -14,13 +14,14 @@ mod paths;
 mod schema;
 
 pub use env::{
-    AGENTS_MD_PREFIX, AgentEnv, ContextFile, ContextFileKind, SystemPrompt, SystemPromptSource,
-    USER_AGENTS_MD_PREFIX,
+    AGENTS_MD_PREFIX, AgentEnv, ContextFile, ContextFileKind, PromptStyle, SystemPrompt,
+    SystemPromptSource, USER_AGENTS_MD_PREFIX,
 };
 pub use paths::display_path;
 pub use schema::{
     Config, ConfigDiagnostic, ConfigError, ConfigLayer, ConfigOption, ConfigSpeed,
-    ConfigThinkingDisplay, ConfigThinkingLevel, ConfigVerbosity, Severity, ValueKind,
+    ConfigStyleTerseness, ConfigThinkingDisplay, ConfigThinkingLevel, ConfigVerbosity, Severity,
+    ValueKind,
 };
 
 /// Unique temp directory for tests that need real filesystem scratch
@@ -63,6 +63,24 @@ pub(crate) fn project_dirs_upward(
     dirs
 }
 
+/// [`Config::dotenv_file_paths`] for explicit inputs: `global` (the
+/// `~/.aj/.env` path) first, then the git root's and the working
+/// directory's `.env`, without duplicates.
+fn dotenv_layers(
+    global: Option<PathBuf>,
+    working_directory: &Path,
+    git_root: Option<&Path>,
+) -> Vec<PathBuf> {
+    let mut layers: Vec<PathBuf> = global.into_iter().collect();
+    for dir in git_root.into_iter().chain([working_directory]) {
+        let path = dir.join(".env");
+        if !layers.contains(&path) {
+            layers.push(path);
+        }
+    }
+    layers
+}
+
 /// Render `path` for display. If it lives under `$HOME`, abbreviate the home
 /// prefix to `~`.
 pub fn display_path(path: &Path) -> String {
@@ -151,6 +169,23 @@ impl Config {
         Ok(aj_dir.join(".env"))
     }
 
+    /// The `.env` layers for the current working directory, lowest
+    /// precedence first: `~/.aj/.env`, then `<git-root>/.env`, then
+    /// `<cwd>/.env`. A later layer overrides an earlier one.
+    ///
+    /// The files need not exist; callers skip missing ones. A layer that
+    /// can't be resolved (no `$HOME`, not in a git repository) is left
+    /// out, and the git-root layer is dropped when it is the working
+    /// directory.
+    pub fn dotenv_file_paths() -> Vec<PathBuf> {
+        let global = Self::get_dotenv_file_path().ok();
+        let Ok(working_directory) = env::current_dir() else {
+            return global.into_iter().collect();
+        };
+        let git_root = find_git_root(&working_directory);
+        dotenv_layers(global, &working_directory, git_root.as_deref())
+    }
+
     /// Get the sessions directory path for the current project. The sessions
     /// are stored in subdirectories based on the git root directory. For
     /// example, if the git root is /Users/user/Dev/project, the subdirectory
@@ -251,4 +286,35 @@ mod tests {
             vec![cwd.to_path_buf()]
         );
     }
+
+    #[test]
+    fn test_dotenv_layers_order() {
+        let global = PathBuf::from("/home/u/.aj/.env");
+        assert_eq!(
+            dotenv_layers(
+                Some(global.clone()),
+                Path::new("/repo/sub"),
+                Some(Path::new("/repo"))
+            ),
+            vec![
+                global.clone(),
+                PathBuf::from("/repo/.env"),
+                PathBuf::from("/repo/sub/.env"),
+            ]
+        );
+        // cwd == git root collapses to one project layer.
+        assert_eq!(
+            dotenv_layers(
+                Some(global.clone()),
+                Path::new("/repo"),
+                Some(Path::new("/repo"))
+            ),
+            vec![global, PathBuf::from("/repo/.env")]
+        );
+        // No `$HOME` and no repository: just the working directory.
+        assert_eq!(
+            dotenv_layers(None, Path::new("/tmp/x"), None),
+            vec![PathBuf::from("/tmp/x/.env")]
+        );
+    }
 }
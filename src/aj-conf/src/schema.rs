@@ -598,6 +598,19 @@ pub struct Config {
     /// capability. Independent of `image_block`: this only affects
     /// what the user sees, not what the model receives.
     pub image_show_in_terminal: bool,
+    /// Show each tool call's full JSON input, pretty-printed, between
+    /// its header and its result in the interactive TUI, for debugging
+    /// what the model actually sent. Defaults to `false` (the header's
+    /// one-line argument summary only); `--verbose` turns it on for a
+    /// single run.
+    pub show_tool_input: bool,
+    /// Put a dim rule numbering the turn above each prompt in the
+    /// interactive transcript, as a visual break when scrolling back.
+    /// Defaults to `false`.
+    pub turn_separator: bool,
+    /// Include the prompt's send time in the `turn_separator` rule.
+    /// Defaults to `false`.
+    pub turn_timestamps: bool,
     /// Defense-in-depth: when `true`, strip every
     /// [`aj_models::types::UserContent::Image`] block from outgoing
     /// wire messages (both user messages and tool result messages)
@@ -624,8 +637,200 @@ pub struct Config {
     /// summarized range depends only on how much recent context we want
     /// to retain, not on the model. Defaults to `20_000`.
     pub compact_keep_recent: u64,
+    /// Seconds of no user input after which the interactive TUI quits
+    /// on its own, printing the usual usage summary on the way out.
+    /// Only counts while no turn is running. `0` (the default)
+    /// disables the timeout.
+    pub idle_timeout_secs: u64,
+    /// Keep ANSI escape sequences and other terminal-control bytes in
+    /// `bash` output instead of stripping them, and don't ask child
+    /// processes to suppress color (`NO_COLOR`, `TERM=dumb`). Defaults
+    /// to `false`: escapes are noise to the model and cost tokens.
+    pub bash_preserve_ansi: bool,
+    /// Seconds a foreground `bash` call may run before it is killed,
+    /// when the call doesn't pass its own `timeout`. Precedence: the
+    /// per-call `timeout` > this value (project config over user
+    /// config) > the built-in 30 seconds. Must be between 1 and
+    /// [`MAX_BASH_TIMEOUT_SECS`].
+    pub bash_timeout_secs: u64,
+    /// Let the `env` tool return the values of variables the model
+    /// names explicitly. Off by default, so the tool only reports
+    /// whether variables are set; prefix listings never show values.
+    pub env_tool_values: bool,
+    /// Ask once before running an assistant message's file-editing
+    /// tool calls, previewing each pending change's diff; denying
+    /// (optionally with a reason) returns an error result for each so
+    /// the model knows nothing was applied. Interactive mode only, so
+    /// print-mode runs never block on it. Defaults to `false`.
+    pub confirm_edit_batches: bool,
+    /// Trim trailing whitespace from every line and end with exactly
+    /// one newline whenever `write_file` writes a text file. The edit
+    /// tools only trim the lines they insert, so untouched lines keep
+    /// their whitespace. The diff shown for the write reflects the
+    /// normalized content. Defaults to `false`: files are written
+    /// verbatim.
+    pub normalize_on_write: bool,
+    /// Apply the target's `.editorconfig` (`trim_trailing_whitespace`,
+    /// `insert_final_newline`, `indent_style`) whenever `write_file` or
+    /// an edit tool writes a file. The diff shown for the write reflects
+    /// the adjusted content. Defaults to `false`.
+    pub respect_editorconfig: bool,
+    /// After `write_file` or `edit_file` writes a file, append its
+    /// on-disk size and modification time and a short listing of its
+    /// directory to the result, so the model needn't run `ls` to check.
+    /// Defaults to `false`.
+    pub confirm_with_listing: bool,
+    /// When the model finishes a response with no text and no tool
+    /// calls, ask it once to continue instead of ending the run. With
+    /// the default `false` the run ends and a notice says the model
+    /// returned an empty response.
+    pub nudge_empty_responses: bool,
+    /// When a model stream fails with a retryable error after part of
+    /// the response arrived, restart the request instead of stopping.
+    /// With the default `false` the partial response is kept and the
+    /// run ends with an error so the user can decide how to go on; a
+    /// failure before any content is always retried.
+    pub retry_partial_streams: bool,
+    /// Guard against sending the same prompt twice in a row. When a new
+    /// prompt matches the previous user message, the interactive UI
+    /// asks for a second Enter before sending it and `--print` skips it
+    /// with a warning. Off by default, since repeating a prompt is
+    /// sometimes exactly what the user wants.
+    pub guard_repeated_prompts: bool,
+    /// Models to fall back to, in order, when the active model keeps
+    /// answering `overloaded` after its retries. Each entry is
+    /// `provider/model` or a bare model id of the primary's provider.
+    /// The switch lasts until the prompt is answered. Empty (the
+    /// default) surfaces the overload as an error.
+    pub fallback_models: Vec<String>,
+    /// Largest file, in bytes, that `read_file`, `write_file` and the
+    /// edit tools will load into memory; bigger files are refused with
+    /// a pointer at a bash range read. `0` disables the cap. Defaults
+    /// to 10 MB.
+    pub max_file_size: u64,
+    /// Glob patterns for files `write_file` and the edit tools refuse
+    /// to modify, whatever the permission mode: secrets, lockfiles, CI
+    /// config. A pattern without `/` matches a file name anywhere
+    /// (`.env`, `*.lock`); one with `/` matches the path relative to
+    /// the working directory (`.github/**`). Empty by default.
+    pub protected_paths: Vec<String>,
+    /// Refuse tool calls whose paths resolve outside `allowed_roots`.
+    /// Covers every builtin tool that takes a path (reads, searches,
+    /// writes, edits, diff, git history); `bash` is not covered.
+    /// Defaults to `false`.
+    pub confine_paths: bool,
+    /// Directories `confine_paths` keeps tools inside, relative to the
+    /// working directory unless absolute. Empty (the default) allows
+    /// the working directory and its git root; the directories of
+    /// enabled skills are always allowed.
+    pub allowed_roots: Vec<String>,
+    /// Before `write_file`, `format_file` or an edit tool overwrites a
+    /// file, copy its current content into a timestamped directory
+    /// under `trash_dir`, so the interactive `restore` command can put
+    /// it back. Defaults to `false`.
+    pub trash_writes: bool,
+    /// Where `trash_writes` keeps prior versions, relative to the
+    /// working directory unless absolute. The directory is created
+    /// with ignore files so git and searches skip it. Unset uses
+    /// `.aj-trash`.
+    pub trash_dir: Option<String>,
+    /// Append one JSON line per tool execution (tool, input, result
+    /// size, duration, success, timestamp) to this file, relative to
+    /// the working directory unless absolute. Sub-agents' calls are
+    /// included. Unset keeps no audit log.
+    pub tool_audit_log: Option<String>,
+    /// When the interactive TUI starts a fresh session with no launch
+    /// prompt, point at the project's most recent session and how to
+    /// resume it. Every session is already saved as it runs, so this
+    /// only controls the startup notice. Defaults to `true`.
+    pub offer_resume: bool,
+    /// When the interactive TUI starts a fresh session with no launch
+    /// prompt, send a one-token request carrying the system prompt
+    /// and tools so the provider caches that prefix before the first
+    /// real turn. Costs a cache write up front (shown in the usage
+    /// line) and is skipped when prompt caching is off. Defaults to
+    /// `false`.
+    pub prewarm_cache: bool,
+    /// Header text the interactive TUI shows when it starts a fresh
+    /// session. Unset shows `Chat with AJ — Ctrl+C to quit`.
+    pub greeting: Option<String>,
+    /// At interactive startup, print one line summarizing the setup:
+    /// model, working directory, how many AGENTS.md/CLAUDE.md files
+    /// were loaded, and whether edit batches need approval. Defaults
+    /// to `false`.
+    pub show_startup_summary: bool,
+    /// When `read_file` is given a path that doesn't exist and exactly
+    /// one similarly named file in the project is the best match, read
+    /// that file instead (noting the correction). With the default
+    /// `false` the error only lists the candidates.
+    pub read_fuzzy_paths: bool,
+    /// Most model requests in flight at once across the process,
+    /// including sub-agents; further requests wait for a slot. Useful
+    /// against tight provider rate limits. `0` (the default) means
+    /// unlimited.
+    pub max_concurrent_requests: u64,
+    /// When an Anthropic API response reports that a rate-limit
+    /// budget (requests or tokens per minute) is nearly spent, hold
+    /// the next request until that budget resets, up to a minute,
+    /// instead of running into a 429. Defaults to `false`.
+    pub rate_limit_throttle: bool,
+    /// Estimated size, in tokens, above which the interactive TUI
+    /// shows a warning under a tool result (e.g. `tool_result from
+    /// grep is ~8.2k tokens`), to flag context-bloating calls. `0`
+    /// disables the warning. Defaults to `10_000`.
+    pub tool_result_warn_tokens: u64,
+    /// `user-agent` header for Anthropic API-key requests. Unset sends
+    /// `aj/<version>`. OAuth requests always identify as the Claude
+    /// Code CLI, which the subscription endpoint requires.
+    pub user_agent: Option<String>,
+    /// Stable id sent as the request's `metadata.user_id` on Anthropic
+    /// requests, for attributing usage and abuse reports to an end
+    /// user. Should be an opaque id (a UUID or hash), not a name or
+    /// email. Unset sends no metadata.
+    pub metadata_user_id: Option<String>,
+    /// Largest size, in bytes, of each `AGENTS.md`/`CLAUDE.md` stitched
+    /// into the system prompt. A bigger file is truncated with a marker
+    /// pointing the model at the rest, and a warning is shown at
+    /// startup. `0` disables the cap. Defaults to 64 KiB.
+    pub max_context_file_bytes: u64,
+    /// Shell command the interactive TUI runs for the `open_in_editor`
+    /// tool, with `{path}` and `{line}` placeholders, e.g.
+    /// `code -g {path}:{line}`. It runs detached from the terminal, so
+    /// a terminal editor needs a wrapper such as
+    /// `tmux new-window nvim +{line} {path}`. Unset, the tool opens
+    /// nothing and reports an error.
+    pub editor_command: Option<String>,
+    /// How much the assistant says, rendered into the system prompt's
+    /// `<style>` block. Unset leaves the builtin prompt's own guidance
+    /// alone. See [`ConfigStyleTerseness`].
+    pub style_terseness: Option<ConfigStyleTerseness>,
+    /// Ask the assistant, via the `<style>` block, to say briefly what
+    /// it is about to do before it calls tools. Defaults to `false`.
+    pub style_explain_first: bool,
+    /// Natural language the assistant answers in (e.g. `German`),
+    /// rendered into the `<style>` block. Code, identifiers and commit
+    /// messages are left alone. Unset answers in the user's language.
+    pub style_language: Option<String>,
 }
 
+/// Default for [`Config::max_file_size`]: 10 MB. Mirrors
+/// `aj_tools::file_size::DEFAULT_MAX_FILE_SIZE`.
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Default for [`Config::bash_timeout_secs`]. Mirrors
+/// `aj_tools::tools::bash::DEFAULT_TIMEOUT_SECS`.
+const DEFAULT_BASH_TIMEOUT_SECS: u64 = 30;
+
+/// Upper bound for [`Config::bash_timeout_secs`]: one day. Longer work
+/// belongs in a background task.
+pub const MAX_BASH_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+
+/// Default for [`Config::tool_result_warn_tokens`].
+const DEFAULT_TOOL_RESULT_WARN_TOKENS: u64 = 10_000;
+
+/// Default for [`Config::max_context_file_bytes`]: 64 KiB.
+const DEFAULT_MAX_CONTEXT_FILE_BYTES: u64 = 64 * 1024;
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -644,11 +849,48 @@ impl Default for Config {
             // blocking is opt-in.
             image_auto_resize: true,
             image_show_in_terminal: true,
+            show_tool_input: false,
+            turn_separator: false,
+            turn_timestamps: false,
             image_block: false,
             syntax_highlighting: false,
             auto_compact: true,
             compact_threshold: 0.85,
             compact_keep_recent: 20_000,
+            idle_timeout_secs: 0,
+            bash_preserve_ansi: false,
+            bash_timeout_secs: DEFAULT_BASH_TIMEOUT_SECS,
+            env_tool_values: false,
+            confirm_edit_batches: false,
+            normalize_on_write: false,
+            respect_editorconfig: false,
+            confirm_with_listing: false,
+            nudge_empty_responses: false,
+            retry_partial_streams: false,
+            guard_repeated_prompts: false,
+            fallback_models: Vec::new(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: Vec::new(),
+            confine_paths: false,
+            allowed_roots: Vec::new(),
+            trash_writes: false,
+            trash_dir: None,
+            tool_audit_log: None,
+            greeting: None,
+            show_startup_summary: false,
+            offer_resume: true,
+            prewarm_cache: false,
+            read_fuzzy_paths: false,
+            max_concurrent_requests: 0,
+            rate_limit_throttle: false,
+            tool_result_warn_tokens: DEFAULT_TOOL_RESULT_WARN_TOKENS,
+            user_agent: None,
+            metadata_user_id: None,
+            max_context_file_bytes: DEFAULT_MAX_CONTEXT_FILE_BYTES,
+            editor_command: None,
+            style_terseness: None,
+            style_explain_first: false,
+            style_language: None,
         }
     }
 }
@@ -722,6 +964,41 @@ impl FromStr for ConfigVerbosity {
     }
 }
 
+/// Answer length asked for in the system prompt's `<style>` block.
+/// Unlike [`ConfigVerbosity`], which is a wire parameter only some
+/// models honour, this is plain prompt text and works everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigStyleTerseness {
+    /// Short answers; no preamble or recap.
+    Terse,
+    /// Explain reasoning and trade-offs alongside the answer.
+    Detailed,
+}
+
+impl fmt::Display for ConfigStyleTerseness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigStyleTerseness::Terse => write!(f, "terse"),
+            ConfigStyleTerseness::Detailed => write!(f, "detailed"),
+        }
+    }
+}
+
+impl FromStr for ConfigStyleTerseness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "terse" => Ok(ConfigStyleTerseness::Terse),
+            "detailed" => Ok(ConfigStyleTerseness::Detailed),
+            _ => Err(format!(
+                "invalid style_terseness '{s}': expected terse or detailed"
+            )),
+        }
+    }
+}
+
 impl Config {
     /// Schema for every option this binary understands. The file
     /// parser, the unknown-key suggester, and the interactive
@@ -876,6 +1153,39 @@ impl Config {
             display_fn: |c| c.image_show_in_terminal.to_string(),
             to_toml_fn: |c| bool_item(c.image_show_in_terminal, true),
         },
+        ConfigOption {
+            name: "show_tool_input",
+            description: "Show each tool call's full JSON input above its result in the TUI.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.show_tool_input = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.show_tool_input.to_string(),
+            to_toml_fn: |c| bool_item(c.show_tool_input, false),
+        },
+        ConfigOption {
+            name: "turn_separator",
+            description: "Put a rule numbering the turn above each prompt in the transcript.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.turn_separator = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.turn_separator.to_string(),
+            to_toml_fn: |c| bool_item(c.turn_separator, false),
+        },
+        ConfigOption {
+            name: "turn_timestamps",
+            description: "Show the prompt's send time in the turn separator.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.turn_timestamps = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.turn_timestamps.to_string(),
+            to_toml_fn: |c| bool_item(c.turn_timestamps, false),
+        },
         ConfigOption {
             name: "image_block",
             description: "Strip image attachments from outgoing wire messages (defense-in-depth).",
@@ -970,6 +1280,500 @@ impl Config {
             display_fn: |c| c.compact_keep_recent.to_string(),
             to_toml_fn: |c| int_item(c.compact_keep_recent, 20_000),
         },
+        ConfigOption {
+            name: "idle_timeout_secs",
+            description: "Quit the interactive TUI after this many seconds without input (0 disables).",
+            kind: ValueKind::Number,
+            apply_toml_fn: |v, c| {
+                // Same integer-or-float acceptance as
+                // `compact_keep_recent`, but zero is allowed: it is the
+                // "disabled" sentinel.
+                #[allow(clippy::as_conversions)]
+                let n: i64 = match v {
+                    toml::Value::Integer(i) => i,
+                    toml::Value::Float(f) => f as i64,
+                    _ => {
+                        return Err(<toml::de::Error as serde::de::Error>::custom(
+                            "idle_timeout_secs must be a number",
+                        ));
+                    }
+                };
+                if n < 0 {
+                    return Err(<toml::de::Error as serde::de::Error>::custom(
+                        "idle_timeout_secs must be zero or a positive number of seconds",
+                    ));
+                }
+                #[allow(clippy::as_conversions)]
+                {
+                    c.idle_timeout_secs = n as u64;
+                }
+                Ok(())
+            },
+            display_fn: |c| c.idle_timeout_secs.to_string(),
+            to_toml_fn: |c| int_item(c.idle_timeout_secs, 0),
+        },
+        ConfigOption {
+            name: "bash_preserve_ansi",
+            description: "Keep ANSI color/escape codes in bash output sent to the model.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.bash_preserve_ansi = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.bash_preserve_ansi.to_string(),
+            to_toml_fn: |c| bool_item(c.bash_preserve_ansi, false),
+        },
+        ConfigOption {
+            name: "bash_timeout_secs",
+            description: "Default seconds before a bash command is killed, when the model doesn't set a timeout.",
+            kind: ValueKind::Number,
+            apply_toml_fn: |v, c| {
+                // Same integer-or-float acceptance as
+                // `compact_keep_recent`, bounded to a sane range.
+                #[allow(clippy::as_conversions)]
+                let n: i64 = match v {
+                    toml::Value::Integer(i) => i,
+                    toml::Value::Float(f) => f as i64,
+                    _ => {
+                        return Err(<toml::de::Error as serde::de::Error>::custom(
+                            "bash_timeout_secs must be a number",
+                        ));
+                    }
+                };
+                match u64::try_from(n) {
+                    Ok(secs) if (1..=MAX_BASH_TIMEOUT_SECS).contains(&secs) => {
+                        c.bash_timeout_secs = secs;
+                        Ok(())
+                    }
+                    _ => Err(<toml::de::Error as serde::de::Error>::custom(format!(
+                        "bash_timeout_secs must be between 1 and {MAX_BASH_TIMEOUT_SECS} seconds"
+                    ))),
+                }
+            },
+            display_fn: |c| c.bash_timeout_secs.to_string(),
+            to_toml_fn: |c| int_item(c.bash_timeout_secs, DEFAULT_BASH_TIMEOUT_SECS),
+        },
+        ConfigOption {
+            name: "env_tool_values",
+            description: "Let the env tool return the values of variables it is asked about by name.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.env_tool_values = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.env_tool_values.to_string(),
+            to_toml_fn: |c| bool_item(c.env_tool_values, false),
+        },
+        ConfigOption {
+            name: "confirm_edit_batches",
+            description: "Preview and approve each batch of file edits the model requests before it is applied.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.confirm_edit_batches = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.confirm_edit_batches.to_string(),
+            to_toml_fn: |c| bool_item(c.confirm_edit_batches, false),
+        },
+        ConfigOption {
+            name: "normalize_on_write",
+            description: "Trim trailing whitespace (and fix the final newline in write_file) when tools write files.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.normalize_on_write = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.normalize_on_write.to_string(),
+            to_toml_fn: |c| bool_item(c.normalize_on_write, false),
+        },
+        ConfigOption {
+            name: "respect_editorconfig",
+            description: "Apply the project's .editorconfig (final newline, trailing whitespace, indentation) when tools write files.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.respect_editorconfig = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.respect_editorconfig.to_string(),
+            to_toml_fn: |c| bool_item(c.respect_editorconfig, false),
+        },
+        ConfigOption {
+            name: "confirm_with_listing",
+            description: "Follow each write_file/edit_file result with the file's size and a listing of its directory.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.confirm_with_listing = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.confirm_with_listing.to_string(),
+            to_toml_fn: |c| bool_item(c.confirm_with_listing, false),
+        },
+        ConfigOption {
+            name: "nudge_empty_responses",
+            description: "Ask the model to continue once when it returns an empty response, instead of only noting it.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.nudge_empty_responses = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.nudge_empty_responses.to_string(),
+            to_toml_fn: |c| bool_item(c.nudge_empty_responses, false),
+        },
+        ConfigOption {
+            name: "retry_partial_streams",
+            description: "Restart a model request that fails after part of the response arrived, instead of keeping the partial response.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.retry_partial_streams = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.retry_partial_streams.to_string(),
+            to_toml_fn: |c| bool_item(c.retry_partial_streams, false),
+        },
+        ConfigOption {
+            name: "guard_repeated_prompts",
+            description: "Ask before sending a prompt identical to the previous message (skipped in --print).",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.guard_repeated_prompts = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.guard_repeated_prompts.to_string(),
+            to_toml_fn: |c| bool_item(c.guard_repeated_prompts, false),
+        },
+        ConfigOption {
+            name: "fallback_models",
+            description: "Models (provider/model or a model id) to switch to, in order, when the model stays overloaded.",
+            kind: ValueKind::StringList,
+            apply_toml_fn: |v, c| {
+                c.fallback_models = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_string_list(&c.fallback_models),
+            to_toml_fn: |c| string_list_item(&c.fallback_models),
+        },
+        ConfigOption {
+            name: "max_file_size",
+            description: "Largest file in bytes that read_file, write_file and the edit tools will load (0 disables).",
+            kind: ValueKind::Number,
+            apply_toml_fn: |v, c| {
+                // Same integer-or-float acceptance as
+                // `idle_timeout_secs`; zero disables the cap.
+                #[allow(clippy::as_conversions)]
+                let n: i64 = match v {
+                    toml::Value::Integer(i) => i,
+                    toml::Value::Float(f) => f as i64,
+                    _ => {
+                        return Err(<toml::de::Error as serde::de::Error>::custom(
+                            "max_file_size must be a number",
+                        ));
+                    }
+                };
+                if n < 0 {
+                    return Err(<toml::de::Error as serde::de::Error>::custom(
+                        "max_file_size must be zero or a positive number of bytes",
+                    ));
+                }
+                #[allow(clippy::as_conversions)]
+                {
+                    c.max_file_size = n as u64;
+                }
+                Ok(())
+            },
+            display_fn: |c| c.max_file_size.to_string(),
+            to_toml_fn: |c| int_item(c.max_file_size, DEFAULT_MAX_FILE_SIZE),
+        },
+        ConfigOption {
+            name: "protected_paths",
+            description: "Glob patterns of files write_file and the edit tools refuse to modify.",
+            kind: ValueKind::StringList,
+            apply_toml_fn: |v, c| {
+                c.protected_paths = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_string_list(&c.protected_paths),
+            to_toml_fn: |c| string_list_item(&c.protected_paths),
+        },
+        ConfigOption {
+            name: "confine_paths",
+            description: "Refuse tool paths outside allowed_roots (default: the working directory and its git root).",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.confine_paths = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.confine_paths.to_string(),
+            to_toml_fn: |c| bool_item(c.confine_paths, false),
+        },
+        ConfigOption {
+            name: "allowed_roots",
+            description: "Directories confine_paths keeps tools inside.",
+            kind: ValueKind::StringList,
+            apply_toml_fn: |v, c| {
+                c.allowed_roots = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_string_list(&c.allowed_roots),
+            to_toml_fn: |c| string_list_item(&c.allowed_roots),
+        },
+        ConfigOption {
+            name: "trash_writes",
+            description: "Copy a file's prior version into trash_dir before write_file or an edit tool overwrites it.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.trash_writes = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.trash_writes.to_string(),
+            to_toml_fn: |c| bool_item(c.trash_writes, false),
+        },
+        ConfigOption {
+            name: "trash_dir",
+            description: "Directory trash_writes keeps prior versions in (default .aj-trash).",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.trash_dir = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.trash_dir),
+            to_toml_fn: |c| opt_value_item(&c.trash_dir),
+        },
+        ConfigOption {
+            name: "tool_audit_log",
+            description: "JSONL file every tool execution is appended to, for auditing (unset: none).",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.tool_audit_log = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.tool_audit_log),
+            to_toml_fn: |c| opt_value_item(&c.tool_audit_log),
+        },
+        ConfigOption {
+            name: "offer_resume",
+            description: "On a fresh start, point at the project's most recent session to resume.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.offer_resume = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.offer_resume.to_string(),
+            to_toml_fn: |c| bool_item(c.offer_resume, true),
+        },
+        ConfigOption {
+            name: "prewarm_cache",
+            description: "On a fresh start, prime the prompt cache with the system prompt and tools.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.prewarm_cache = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.prewarm_cache.to_string(),
+            to_toml_fn: |c| bool_item(c.prewarm_cache, false),
+        },
+        ConfigOption {
+            name: "greeting",
+            description: "Header text shown when the interactive TUI starts a fresh session.",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.greeting = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.greeting),
+            to_toml_fn: |c| opt_value_item(&c.greeting),
+        },
+        ConfigOption {
+            name: "show_startup_summary",
+            description: "Print a line with the model, working directory, loaded AGENTS.md files and edit approval at startup.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.show_startup_summary = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.show_startup_summary.to_string(),
+            to_toml_fn: |c| bool_item(c.show_startup_summary, false),
+        },
+        ConfigOption {
+            name: "read_fuzzy_paths",
+            description: "Let read_file read the unique best match when given a path that doesn't exist.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.read_fuzzy_paths = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.read_fuzzy_paths.to_string(),
+            to_toml_fn: |c| bool_item(c.read_fuzzy_paths, false),
+        },
+        ConfigOption {
+            name: "max_concurrent_requests",
+            description: "Most model requests in flight at once, sub-agents included (0 means unlimited).",
+            kind: ValueKind::Number,
+            apply_toml_fn: |v, c| {
+                // Same integer-or-float acceptance as
+                // `idle_timeout_secs`; zero lifts the limit.
+                #[allow(clippy::as_conversions)]
+                let n: i64 = match v {
+                    toml::Value::Integer(i) => i,
+                    toml::Value::Float(f) => f as i64,
+                    _ => {
+                        return Err(<toml::de::Error as serde::de::Error>::custom(
+                            "max_concurrent_requests must be a number",
+                        ));
+                    }
+                };
+                if n < 0 {
+                    return Err(<toml::de::Error as serde::de::Error>::custom(
+                        "max_concurrent_requests must be zero or a positive number",
+                    ));
+                }
+                #[allow(clippy::as_conversions)]
+                {
+                    c.max_concurrent_requests = n as u64;
+                }
+                Ok(())
+            },
+            display_fn: |c| c.max_concurrent_requests.to_string(),
+            to_toml_fn: |c| int_item(c.max_concurrent_requests, 0),
+        },
+        ConfigOption {
+            name: "rate_limit_throttle",
+            description: "Delay Anthropic requests until a nearly spent rate-limit budget resets.",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.rate_limit_throttle = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.rate_limit_throttle.to_string(),
+            to_toml_fn: |c| bool_item(c.rate_limit_throttle, false),
+        },
+        ConfigOption {
+            name: "tool_result_warn_tokens",
+            description: "Warn when a tool result is estimated above this many tokens (0 disables).",
+            kind: ValueKind::Number,
+            apply_toml_fn: |v, c| {
+                // Same integer-or-float acceptance as
+                // `idle_timeout_secs`; zero disables the warning.
+                #[allow(clippy::as_conversions)]
+                let n: i64 = match v {
+                    toml::Value::Integer(i) => i,
+                    toml::Value::Float(f) => f as i64,
+                    _ => {
+                        return Err(<toml::de::Error as serde::de::Error>::custom(
+                            "tool_result_warn_tokens must be a number",
+                        ));
+                    }
+                };
+                if n < 0 {
+                    return Err(<toml::de::Error as serde::de::Error>::custom(
+                        "tool_result_warn_tokens must be zero or a positive number of tokens",
+                    ));
+                }
+                #[allow(clippy::as_conversions)]
+                {
+                    c.tool_result_warn_tokens = n as u64;
+                }
+                Ok(())
+            },
+            display_fn: |c| c.tool_result_warn_tokens.to_string(),
+            to_toml_fn: |c| int_item(c.tool_result_warn_tokens, DEFAULT_TOOL_RESULT_WARN_TOKENS),
+        },
+        ConfigOption {
+            name: "user_agent",
+            description: "user-agent header for Anthropic API-key requests (default aj/<version>).",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.user_agent = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.user_agent),
+            to_toml_fn: |c| opt_value_item(&c.user_agent),
+        },
+        ConfigOption {
+            name: "metadata_user_id",
+            description: "Opaque id sent as metadata.user_id on Anthropic requests.",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.metadata_user_id = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.metadata_user_id),
+            to_toml_fn: |c| opt_value_item(&c.metadata_user_id),
+        },
+        ConfigOption {
+            name: "max_context_file_bytes",
+            description: "Largest AGENTS.md/CLAUDE.md in bytes included whole in the system prompt (0 disables).",
+            kind: ValueKind::Number,
+            apply_toml_fn: |v, c| {
+                // Same integer-or-float acceptance as
+                // `idle_timeout_secs`; zero disables the cap.
+                #[allow(clippy::as_conversions)]
+                let n: i64 = match v {
+                    toml::Value::Integer(i) => i,
+                    toml::Value::Float(f) => f as i64,
+                    _ => {
+                        return Err(<toml::de::Error as serde::de::Error>::custom(
+                            "max_context_file_bytes must be a number",
+                        ));
+                    }
+                };
+                if n < 0 {
+                    return Err(<toml::de::Error as serde::de::Error>::custom(
+                        "max_context_file_bytes must be zero or a positive number of bytes",
+                    ));
+                }
+                #[allow(clippy::as_conversions)]
+                {
+                    c.max_context_file_bytes = n as u64;
+                }
+                Ok(())
+            },
+            display_fn: |c| c.max_context_file_bytes.to_string(),
+            to_toml_fn: |c| int_item(c.max_context_file_bytes, DEFAULT_MAX_CONTEXT_FILE_BYTES),
+        },
+        ConfigOption {
+            name: "editor_command",
+            description: "Command open_in_editor runs, with {path} and {line} placeholders (unset disables it).",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.editor_command = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.editor_command),
+            to_toml_fn: |c| opt_value_item(&c.editor_command),
+        },
+        ConfigOption {
+            name: "style_terseness",
+            description: "Ask for terse or detailed answers in the system prompt (new sessions).",
+            kind: ValueKind::Enum(&["terse", "detailed"]),
+            apply_toml_fn: |v, c| {
+                c.style_terseness = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.style_terseness),
+            to_toml_fn: |c| opt_value_item(&c.style_terseness),
+        },
+        ConfigOption {
+            name: "style_explain_first",
+            description: "Ask the assistant to say what it is about to do before calling tools (new sessions).",
+            kind: ValueKind::Bool,
+            apply_toml_fn: |v, c| {
+                c.style_explain_first = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| c.style_explain_first.to_string(),
+            to_toml_fn: |c| bool_item(c.style_explain_first, false),
+        },
+        ConfigOption {
+            name: "style_language",
+            description: "Language the assistant answers in, e.g. \"German\" (new sessions).",
+            kind: ValueKind::String,
+            apply_toml_fn: |v, c| {
+                c.style_language = v.try_into()?;
+                Ok(())
+            },
+            display_fn: |c| display_opt(&c.style_language),
+            to_toml_fn: |c| opt_value_item(&c.style_language),
+        },
     ];
 
     /// Look up an option by its config key, if any. Returns `None`
@@ -1921,6 +2725,76 @@ hide_thinking_block = true
         );
     }
 
+    #[test]
+    fn idle_timeout_secs_defaults_off_and_accepts_zero() {
+        let opt = Config::option("idle_timeout_secs").unwrap();
+
+        // Disabled by default, and the default never reaches disk.
+        assert_eq!(Config::default().idle_timeout_secs, 0);
+        assert!(opt.to_toml(&Config::default()).is_none());
+
+        // A positive integer is accepted and stored.
+        let mut config = Config::default();
+        assert!(
+            opt.apply_toml(toml::Value::Integer(900), &mut config)
+                .is_ok()
+        );
+        assert_eq!(config.idle_timeout_secs, 900);
+
+        // Zero re-disables; negatives and non-numbers are rejected.
+        assert!(opt.apply_toml(toml::Value::Integer(0), &mut config).is_ok());
+        assert_eq!(config.idle_timeout_secs, 0);
+        assert!(
+            opt.apply_toml(toml::Value::Integer(-5), &mut config)
+                .is_err()
+        );
+        assert!(
+            opt.apply_toml(toml::Value::String("soon".into()), &mut config)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn bash_timeout_secs_defaults_to_thirty_and_is_bounded() {
+        let opt = Config::option("bash_timeout_secs").unwrap();
+        assert_eq!(Config::default().bash_timeout_secs, 30);
+        assert!(opt.to_toml(&Config::default()).is_none());
+
+        let mut config = Config::default();
+        assert!(
+            opt.apply_toml(toml::Value::Integer(300), &mut config)
+                .is_ok()
+        );
+        assert_eq!(config.bash_timeout_secs, 300);
+
+        for bad in [0, -1, i64::try_from(MAX_BASH_TIMEOUT_SECS).unwrap() + 1] {
+            assert!(
+                opt.apply_toml(toml::Value::Integer(bad), &mut config)
+                    .is_err(),
+                "{bad} accepted"
+            );
+        }
+        assert_eq!(config.bash_timeout_secs, 300);
+    }
+
+    #[test]
+    fn max_file_size_defaults_to_ten_megabytes() {
+        let opt = Config::option("max_file_size").unwrap();
+        assert_eq!(Config::default().max_file_size, 10 * 1024 * 1024);
+
+        let mut config = Config::default();
+        assert!(
+            opt.apply_toml(toml::Value::Integer(1_000_000), &mut config)
+                .is_ok()
+        );
+        assert_eq!(config.max_file_size, 1_000_000);
+        assert!(opt.apply_toml(toml::Value::Integer(0), &mut config).is_ok());
+        assert!(
+            opt.apply_toml(toml::Value::Integer(-1), &mut config)
+                .is_err()
+        );
+    }
+
     #[test]
     fn number_item_emits_only_when_changed() {
         // At default the key is dropped; off-default it round-trips.
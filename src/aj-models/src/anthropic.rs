@@ -1,8 +1,12 @@
 //! Anthropic provider integration.
 //!
 //! Hosts the unified [`Provider`](crate::provider::Provider)
-//! implementation in [`provider`].
+//! implementation in [`provider`], and re-exports the SDK's
+//! rate-limit tracking so hosts can set
+//! [`StreamOptions::rate_limits`](crate::types::StreamOptions::rate_limits)
+//! and read the reported budgets without depending on the SDK.
 
 pub mod provider;
 
+pub use anthropic_sdk::rate_limit::{RateLimitBucket, RateLimitTracker, RateLimits};
 pub use provider::AnthropicProvider;
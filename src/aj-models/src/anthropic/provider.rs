@@ -7,6 +7,8 @@
 //! derived from the per-call [`ModelInfo`] and [`StreamOptions`] so the
 //! same instance can serve any number of concurrent requests.
 
+use std::sync::Arc;
+
 use anthropic_sdk::client::{Client, ClientError};
 use anthropic_sdk::messages::{
     CacheControl, ContentBlock as AContentBlock, ContentBlockDelta as AContentBlockDelta,
@@ -263,6 +265,15 @@ fn build_client(
     if options.speed == Some(Speed::Fast) {
         client = client.with_beta(FAST_MODE_BETA);
     }
+    if let Some(limit) = &options.request_limiter {
+        client = client.with_concurrency_limit(Arc::clone(limit));
+    }
+    if let Some(user_agent) = &options.user_agent {
+        client = client.with_user_agent(user_agent.clone());
+    }
+    if let Some(tracker) = &options.rate_limits {
+        client = client.with_rate_limit_tracker(tracker.clone());
+    }
     client
 }
 
@@ -1292,15 +1303,14 @@ impl StreamState {
         finalize_usage(&mut self.partial.usage, &self.cost);
 
         let (stop_reason, done_reason) = match self.stop_reason {
+            Some(AStopReason::EndTurn) | Some(AStopReason::StopSequence) | None => {
+                (StopReason::Stop, Some(DoneReason::Stop))
+            }
             // `PauseTurn` ("server wants to keep going", e.g. a long
-            // server-side tool call) collapses to a plain `Stop`. We run
-            // tools in the agent loop rather than using server-side tools,
-            // so pause-turn doesn't arise in practice, and treating it as
-            // a completed turn is the safe default.
-            Some(AStopReason::EndTurn)
-            | Some(AStopReason::PauseTurn)
-            | Some(AStopReason::StopSequence)
-            | None => (StopReason::Stop, Some(DoneReason::Stop)),
+            // server-side tool call) is surfaced as-is: the turn is not
+            // finished, and the agent loop re-sends the conversation so
+            // the model can continue.
+            Some(AStopReason::PauseTurn) => (StopReason::PauseTurn, Some(DoneReason::PauseTurn)),
             Some(AStopReason::MaxTokens) => (StopReason::Length, Some(DoneReason::Length)),
             Some(AStopReason::ToolUse) => (StopReason::ToolUse, Some(DoneReason::ToolUse)),
             // Refusal / sensitive / context-window exceeded / compaction
@@ -2131,7 +2141,7 @@ mod tests {
             (Some(AStopReason::EndTurn), StopReason::Stop),
             (Some(AStopReason::MaxTokens), StopReason::Length),
             (Some(AStopReason::ToolUse), StopReason::ToolUse),
-            (Some(AStopReason::PauseTurn), StopReason::Stop),
+            (Some(AStopReason::PauseTurn), StopReason::PauseTurn),
             (Some(AStopReason::StopSequence), StopReason::Stop),
             (None, StopReason::Stop),
         ] {
@@ -30,6 +30,8 @@
 //! round-trip, composite tool-call IDs, usage parsing, stop-reason
 //! mapping — is shared with [`super::responses`].
 
+use std::sync::Arc;
+
 use futures::StreamExt;
 use openai_sdk::client::{Client, ClientError};
 use openai_sdk::types::common::ServiceTier as OpenAIServiceTier;
@@ -220,6 +222,9 @@ async fn run_stream_inner(
             .with_extra_header("session_id", sid)
             .with_extra_header("x-client-request-id", sid);
     }
+    if let Some(limit) = &options.request_limiter {
+        client = client.with_concurrency_limit(Arc::clone(limit));
+    }
 
     let request = build_request(model, context, options, reasoning);
 
@@ -17,6 +17,7 @@
 //! provider instead.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use futures::StreamExt;
 use openai_sdk::client::Client;
@@ -172,7 +173,10 @@ async fn run_stream_inner(
     }
 
     let base_url = (!model.base_url.is_empty()).then(|| model.base_url.clone());
-    let client = Client::new(base_url, api_key);
+    let mut client = Client::new(base_url, api_key);
+    if let Some(limit) = &options.request_limiter {
+        client = client.with_concurrency_limit(Arc::clone(limit));
+    }
     let request = build_request(model, context, options, reasoning);
 
     if let Some(cb) = options.on_payload.as_ref() {
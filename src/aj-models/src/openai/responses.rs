@@ -16,6 +16,7 @@
 //! [`TextSignatureV1`] envelope on `text_signature`.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use futures::StreamExt;
 use openai_sdk::client::Client;
@@ -268,6 +269,9 @@ async fn run_stream_inner(
                 .with_extra_header("x-client-request-id", sid);
         }
     }
+    if let Some(limit) = &options.request_limiter {
+        client = client.with_concurrency_limit(Arc::clone(limit));
+    }
 
     let request = build_request(model, context, options, reasoning);
 
@@ -621,7 +621,8 @@ impl ScriptBuilder {
     /// The terminal message captures the current partial plus the chosen
     /// stop reason. Per the unified spec, `Done`'s `reason` is the
     /// successful subset of [`StopReason`] ([`DoneReason::Stop`],
-    /// [`DoneReason::Length`], [`DoneReason::ToolUse`]); the message's
+    /// [`DoneReason::Length`], [`DoneReason::ToolUse`],
+    /// [`DoneReason::PauseTurn`]); the message's
     /// `stop_reason` field is set to match.
     pub fn done(mut self, reason: DoneReason) -> ProviderScript {
         let mut message = self.partial.clone();
@@ -715,6 +716,7 @@ pub fn script_from_message(
         StopReason::Stop => builder.done(DoneReason::Stop),
         StopReason::Length => builder.done(DoneReason::Length),
         StopReason::ToolUse => builder.done(DoneReason::ToolUse),
+        StopReason::PauseTurn => builder.done(DoneReason::PauseTurn),
         StopReason::Error => builder.error(
             ErrorReason::Error,
             message.error.unwrap_or_else(|| {
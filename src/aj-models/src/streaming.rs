@@ -25,13 +25,14 @@ use crate::types::{AssistantError, AssistantMessage, ErrorCategory, StopReason,
 /// Subset of [`StopReason`] valid on an [`AssistantMessageEvent::Done`] event.
 ///
 /// Mirrors the constraint that successful terminations are limited to
-/// `Stop`, `Length`, or `ToolUse`.
+/// `Stop`, `Length`, `ToolUse`, or `PauseTurn`.
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum DoneReason {
     Stop,
     Length,
     ToolUse,
+    PauseTurn,
 }
 
 impl From<DoneReason> for StopReason {
@@ -40,6 +41,7 @@ impl From<DoneReason> for StopReason {
             DoneReason::Stop => StopReason::Stop,
             DoneReason::Length => StopReason::Length,
             DoneReason::ToolUse => StopReason::ToolUse,
+            DoneReason::PauseTurn => StopReason::PauseTurn,
         }
     }
 }
@@ -578,6 +580,10 @@ mod tests {
         assert_eq!(StopReason::from(DoneReason::Stop), StopReason::Stop);
         assert_eq!(StopReason::from(DoneReason::Length), StopReason::Length);
         assert_eq!(StopReason::from(DoneReason::ToolUse), StopReason::ToolUse);
+        assert_eq!(
+            StopReason::from(DoneReason::PauseTurn),
+            StopReason::PauseTurn
+        );
     }
 
     #[test]
@@ -5,8 +5,9 @@
 //! rewritten so the target API accepts them: encrypted reasoning tied to
 //! the source model is dropped or demoted, tool-call IDs are coerced into
 //! the target's character class and length limits, orphaned tool calls
-//! get synthetic error results, and incomplete (errored/aborted) turns
-//! are skipped along with their dangling tool results.
+//! get synthetic error results, incomplete (errored/aborted) turns
+//! are skipped along with their dangling tool results, and assistant
+//! turns without any content are skipped, since providers reject them.
 //!
 //! Capability downgrade follows the same call: images on a
 //! non-vision target collapse into a fixed placeholder string.
@@ -319,6 +320,7 @@ fn short_hash(s: &str) -> String {
 /// Walk pass-1 output to:
 ///   - rewrite `ToolResultMessage.tool_call_id` via the id map,
 ///   - skip errored/aborted assistants and drop their tool results,
+///   - skip assistants with no content blocks,
 ///   - emit synthetic error results for orphaned tool calls when the
 ///     assistant turn closes (next non-tool-result message or EOF).
 fn align_tool_results(messages: Vec<Message>, id_map: &HashMap<String, String>) -> Vec<Message> {
@@ -349,6 +351,11 @@ fn align_tool_results(messages: Vec<Message>, id_map: &HashMap<String, String>)
                     }
                     continue;
                 }
+                if a.content.is_empty() {
+                    // An empty answer (e.g. one the agent nudged the
+                    // model past) has no tool calls to account for.
+                    continue;
+                }
                 pending = a
                     .content
                     .iter()
@@ -1006,6 +1013,20 @@ mod tests {
         assert!(matches!(out[0], Message::User(_)));
     }
 
+    #[test]
+    fn assistant_without_content_is_dropped() {
+        let target = model("anthropic", "anthropic-messages", "claude-x", false);
+        let empty = assistant("anthropic", "anthropic-messages", "claude-x", Vec::new());
+        let messages = vec![
+            Message::User(UserMessage::text("hello")),
+            Message::Assistant(empty),
+            Message::User(UserMessage::text("continue")),
+        ];
+        let out = transform_messages(&messages, &target);
+        assert_eq!(out.len(), 2);
+        assert!(out.iter().all(|m| matches!(m, Message::User(_))));
+    }
+
     // -- Two-pass interaction (rule 5 + cross-model id rewrite) ------------
 
     #[test]
@@ -165,6 +165,11 @@ pub enum StopReason {
     Length,
     /// Model requested tool use.
     ToolUse,
+    /// The provider paused a long-running turn (Anthropic's
+    /// `pause_turn`). The turn is not finished: sending the
+    /// conversation back as-is lets the model pick up where it left
+    /// off.
+    PauseTurn,
     /// An error occurred during generation.
     Error,
     /// Client-synthesized: the request was cancelled locally (e.g. the
@@ -600,6 +605,11 @@ pub struct StreamOptions {
     /// Metadata fields (e.g. Anthropic user_id for rate limiting).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, Value>>,
+    /// Anthropic-only: `user-agent` header for API-key requests.
+    /// OAuth requests always identify as the Claude Code CLI. Ignored
+    /// by other providers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
     /// Optional debug callback invoked with the outgoing request body
     /// just before it's sent. Skipped in serde — callbacks aren't
     /// serializable, and persisting them would be meaningless.
@@ -656,6 +666,20 @@ pub struct StreamOptions {
     /// `Clone` like the rest of `StreamOptions`.
     #[serde(skip)]
     pub cancel: Option<CancellationToken>,
+    /// Shared cap on in-flight provider requests. Providers hand it to
+    /// the SDK client, which waits for a permit before sending and
+    /// holds it until the response stream ends. Clones share the one
+    /// semaphore, so a session and its sub-agents draw from the same
+    /// pool. `None` means unlimited. Skipped in serde like
+    /// [`Self::cancel`].
+    #[serde(skip)]
+    pub request_limiter: Option<Arc<tokio::sync::Semaphore>>,
+    /// Anthropic-only: shared record of the API's rate-limit headers,
+    /// optionally throttling requests when a budget is nearly spent.
+    /// Clones share one snapshot, like [`Self::request_limiter`].
+    /// Ignored by other providers. Skipped in serde.
+    #[serde(skip)]
+    pub rate_limits: Option<anthropic_sdk::rate_limit::RateLimitTracker>,
 }
 
 /// Higher-level options that include reasoning control.
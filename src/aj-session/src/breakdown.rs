@@ -0,0 +1,238 @@
+//! Estimated context tokens broken out by content type: the listing
+//! behind the interactive `breakdown` overlay.
+//!
+//! [`context_breakdown`] walks the projection the model actually
+//! receives ([`Conversation::indexed_messages`]) and attributes each
+//! block to a category — system prompt, user text, assistant text,
+//! thinking, tool calls, tool results, images, or the compaction
+//! summary — using the same `chars / 4` heuristic as
+//! [`crate::compaction::estimate_message_tokens`]. Tool results are
+//! additionally totalled per tool, since a handful of giant results is
+//! the usual reason a context is bloated.
+//!
+//! These are estimates of what the context holds now, not of spend:
+//! provider-reported usage lives in [`crate::SessionStats`].
+
+use std::collections::HashMap;
+
+use aj_models::types::{AssistantContent, Message, UserContent};
+
+use crate::compaction::ESTIMATED_IMAGE_TOKENS;
+use crate::log::Conversation;
+
+/// Estimated context tokens per content type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextBreakdown {
+    pub system_prompt: u64,
+    /// Text the user typed (and any other user-role text).
+    pub user_text: u64,
+    pub assistant_text: u64,
+    pub thinking: u64,
+    /// Tool names plus their JSON arguments.
+    pub tool_calls: u64,
+    /// Text of tool results, images excluded.
+    pub tool_results: u64,
+    /// Image blocks in user messages and tool results.
+    pub images: u64,
+    /// The compaction summary standing in for earlier history.
+    pub summary: u64,
+    /// `tool_results` (images included) per tool name, largest first,
+    /// ties broken by name, with the number of results.
+    pub by_tool: Vec<ToolResultShare>,
+}
+
+/// One tool's share of the tool-result tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolResultShare {
+    pub tool_name: String,
+    pub results: usize,
+    pub tokens: u64,
+}
+
+impl ContextBreakdown {
+    /// Sum of every category.
+    pub fn total(&self) -> u64 {
+        self.system_prompt
+            + self.user_text
+            + self.assistant_text
+            + self.thinking
+            + self.tool_calls
+            + self.tool_results
+            + self.images
+            + self.summary
+    }
+}
+
+/// Break down the context `conversation` projects, plus
+/// `system_prompt` when the caller has one.
+#[allow(clippy::as_conversions)]
+pub fn context_breakdown(
+    conversation: &Conversation,
+    system_prompt: Option<&str>,
+) -> ContextBreakdown {
+    let tokens = |s: &str| (s.chars().count() as u64).div_ceil(4);
+    let mut breakdown = ContextBreakdown {
+        system_prompt: system_prompt.map_or(0, tokens),
+        ..Default::default()
+    };
+    let mut by_tool: HashMap<String, (usize, u64)> = HashMap::new();
+
+    for (id, message) in conversation.indexed_messages() {
+        let Some(wire) = message.as_wire() else {
+            continue;
+        };
+        match wire {
+            Message::User(u) => {
+                let (text, images) = user_content_tokens(&u.content);
+                // The synthetic compaction summary is the one user
+                // message without an entry of its own.
+                if id.is_none() {
+                    breakdown.summary += text;
+                } else {
+                    breakdown.user_text += text;
+                }
+                breakdown.images += images;
+            }
+            Message::Assistant(a) => {
+                for block in &a.content {
+                    match block {
+                        AssistantContent::Text(t) => breakdown.assistant_text += tokens(&t.text),
+                        AssistantContent::Thinking(th) => {
+                            breakdown.thinking += tokens(&th.thinking)
+                        }
+                        AssistantContent::ToolCall(tc) => {
+                            let arguments =
+                                serde_json::to_string(&tc.arguments).unwrap_or_default();
+                            breakdown.tool_calls += tokens(&tc.name) + tokens(&arguments);
+                        }
+                    }
+                }
+            }
+            Message::ToolResult(tr) => {
+                let (text, images) = user_content_tokens(&tr.content);
+                breakdown.tool_results += text;
+                breakdown.images += images;
+                let share = by_tool.entry(tr.tool_name.clone()).or_default();
+                share.0 += 1;
+                share.1 += text + images;
+            }
+        }
+    }
+
+    breakdown.by_tool = by_tool
+        .into_iter()
+        .map(|(tool_name, (results, tokens))| ToolResultShare {
+            tool_name,
+            results,
+            tokens,
+        })
+        .collect();
+    breakdown.by_tool.sort_by(|a, b| {
+        b.tokens
+            .cmp(&a.tokens)
+            .then_with(|| a.tool_name.cmp(&b.tool_name))
+    });
+    breakdown
+}
+
+/// Estimated tokens of the text and of the images in `content`.
+#[allow(clippy::as_conversions)]
+fn user_content_tokens(content: &[UserContent]) -> (u64, u64) {
+    let mut chars: u64 = 0;
+    let mut images: u64 = 0;
+    for block in content {
+        match block {
+            UserContent::Text(t) => chars += t.text.chars().count() as u64,
+            UserContent::Image(_) => images += ESTIMATED_IMAGE_TOKENS,
+        }
+    }
+    (chars.div_ceil(4), images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{ConversationLog, ConversationView, ThreadFilter};
+    use crate::persistence::ConversationPersistence;
+    use aj_agent::message::AgentMessage;
+    use aj_models::types::{
+        AssistantMessage, TextContent, ThinkingContent, ToolCall, ToolResultMessage, UserMessage,
+    };
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn wire(message: Message) -> AgentMessage {
+        AgentMessage::wire(message)
+    }
+
+    #[test]
+    fn attributes_each_block_to_its_category() {
+        let dir = TempDir::new().expect("temp dir");
+        let persistence = ConversationPersistence::new(dir.path().join("sessions"));
+        let mut log = ConversationLog::create(&persistence).expect("create log");
+        log.set_system_prompt("s".repeat(40)).expect("sp");
+        {
+            let mut view = ConversationView::user(&mut log, None);
+            view.add_message(wire(Message::User(UserMessage::text(&"u".repeat(8)))))
+                .expect("u");
+            view.add_message(wire(Message::Assistant(AssistantMessage {
+                content: vec![
+                    AssistantContent::Thinking(ThinkingContent {
+                        thinking: "t".repeat(12),
+                        thinking_signature: None,
+                        redacted: false,
+                    }),
+                    AssistantContent::Text(TextContent {
+                        text: "a".repeat(4),
+                        text_signature: None,
+                    }),
+                    AssistantContent::ToolCall(ToolCall {
+                        id: "c1".to_string(),
+                        name: "bash".to_string(),
+                        arguments: json!({}),
+                    }),
+                    AssistantContent::ToolCall(ToolCall {
+                        id: "c2".to_string(),
+                        name: "read_file".to_string(),
+                        arguments: json!({}),
+                    }),
+                ],
+                ..AssistantMessage::empty()
+            })))
+            .expect("a");
+            view.add_message(wire(Message::ToolResult(ToolResultMessage::text(
+                "c1",
+                "bash",
+                &"x".repeat(400),
+                false,
+            ))))
+            .expect("r1");
+            view.add_message(wire(Message::ToolResult(ToolResultMessage::text(
+                "c2",
+                "read_file",
+                &"y".repeat(4000),
+                false,
+            ))))
+            .expect("r2");
+        }
+        let head = log.latest_leaf(ThreadFilter::USER).expect("head");
+        let conversation = log.linearize(&head, ThreadFilter::USER);
+
+        let breakdown = context_breakdown(&conversation, log.system_prompt());
+        assert_eq!(breakdown.system_prompt, 10);
+        assert_eq!(breakdown.user_text, 2);
+        assert_eq!(breakdown.thinking, 3);
+        assert_eq!(breakdown.assistant_text, 1);
+        // "bash" + "{}" and "read_file" + "{}".
+        assert_eq!(breakdown.tool_calls, 1 + 1 + 3 + 1);
+        assert_eq!(breakdown.tool_results, 1100);
+        assert_eq!(breakdown.summary, 0);
+        assert_eq!(breakdown.total(), 10 + 2 + 3 + 1 + 6 + 1100);
+        let by_tool: Vec<(&str, usize, u64)> = breakdown
+            .by_tool
+            .iter()
+            .map(|s| (s.tool_name.as_str(), s.results, s.tokens))
+            .collect();
+        assert_eq!(by_tool, [("read_file", 1, 1000), ("bash", 1, 100)]);
+    }
+}
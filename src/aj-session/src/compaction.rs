@@ -13,7 +13,9 @@ use serde::{Deserialize, Serialize};
 use aj_agent::message::AgentMessage;
 use aj_models::types::{AssistantContent, Message, UserContent, UserMessage};
 
-use crate::log::{Conversation, ConversationEntry, ConversationEntryKind, EntryId};
+use crate::log::{
+    Conversation, ConversationEntry, ConversationEntryKind, EntryId, project_message,
+};
 
 /// Files touched in a summarized range, surfaced so the model knows
 /// what was read or modified without parsing the summary prose. The
@@ -69,13 +71,20 @@ pub const COMPACTION_SUMMARY_SUFFIX: &str = "\n</summary>";
 /// Heuristic token charge for one image block. The character-based
 /// estimator divides accumulated characters by 4, so an image is
 /// charged `ESTIMATED_IMAGE_TOKENS * 4` characters.
-const ESTIMATED_IMAGE_TOKENS: u64 = 1200; // ~4800 chars / 4
+pub(crate) const ESTIMATED_IMAGE_TOKENS: u64 = 1200; // ~4800 chars / 4
 
 /// Builtin tool names that read a file at `arguments["path"]`.
-const READ_TOOLS: &[&str] = &["read_file"];
+const READ_TOOLS: &[&str] = &["read_file", "preview_edit", "search_in_file"];
 
 /// Builtin tool names that modify a file at `arguments["path"]`.
-const EDIT_TOOLS: &[&str] = &["edit_file", "edit_file_multi", "write_file"];
+const EDIT_TOOLS: &[&str] = &[
+    "edit_file",
+    "edit_file_multi",
+    "format_file",
+    "regex_replace",
+    "write_file",
+    "write_range",
+];
 
 /// Upper bound on the characters of a single tool result embedded in the
 /// summarizer transcript. A large result (a file dump, a long command
@@ -188,6 +197,12 @@ fn user_content_chars(content: &[UserContent]) -> u64 {
     chars
 }
 
+/// Estimate the context tokens user/tool-result content occupies, by
+/// the same heuristic as [`estimate_message_tokens`].
+pub fn estimate_content_tokens(content: &[UserContent]) -> u64 {
+    user_content_chars(content).div_ceil(4)
+}
+
 /// Estimate context tokens for a single wire message via the
 /// character heuristic (`ceil(chars / 4)`), charging a fixed amount
 /// per image block.
@@ -252,7 +267,8 @@ pub fn estimate_context_tokens(messages: &[Message]) -> ContextEstimate {
 /// Whether the most-recent-assistant-`usage` anchor would over-report
 /// occupancy for this entry path.
 ///
-/// The anchor is stale exactly when a `Compaction` is the most recent
+/// The anchor is stale exactly when a `Compaction` (or a `Prune`,
+/// which shrinks the projection the same way) is the most recent
 /// entry among {compaction, assistant message}: every retained
 /// assistant message then predates the summary, so its `usage` still
 /// reflects the old, pre-compaction prompt — the full summarized prefix
@@ -263,7 +279,9 @@ pub fn estimate_context_tokens(messages: &[Message]) -> ContextEstimate {
 fn usage_anchor_is_stale(entries: &[ConversationEntry]) -> bool {
     for entry in entries.iter().rev() {
         match &entry.entry {
-            ConversationEntryKind::Compaction { .. } => return true,
+            ConversationEntryKind::Compaction { .. } | ConversationEntryKind::Prune { .. } => {
+                return true;
+            }
             ConversationEntryKind::Message { message }
                 if matches!(message.as_wire(), Some(Message::Assistant(_))) =>
             {
@@ -633,12 +651,11 @@ pub fn prepare_compaction(
     let cut = find_cut_point(entries, boundary_start, keep_recent_tokens)?;
     let history_end = cut.turn_start_index.unwrap_or(cut.first_kept_index);
 
+    // Messages the user dropped stay out of the summary too.
+    let pruned = conversation.pruned();
     let messages_to_summarize: Vec<Message> = entries[boundary_start..history_end]
         .iter()
-        .filter_map(|e| match &e.entry {
-            ConversationEntryKind::Message { message } => message.as_wire().cloned(),
-            _ => None,
-        })
+        .filter_map(|e| project_message(e, &pruned)?.as_wire().cloned())
         .collect();
 
     // Nothing accumulated before the cut: the session is too small to
@@ -650,10 +667,7 @@ pub fn prepare_compaction(
     let turn_prefix_messages: Vec<Message> = match cut.turn_start_index {
         Some(turn_start) => entries[turn_start..cut.first_kept_index]
             .iter()
-            .filter_map(|e| match &e.entry {
-                ConversationEntryKind::Message { message } => message.as_wire().cloned(),
-                _ => None,
-            })
+            .filter_map(|e| project_message(e, &pruned)?.as_wire().cloned())
             .collect(),
         None => Vec::new(),
     };
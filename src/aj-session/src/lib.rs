@@ -18,15 +18,22 @@
 //! - [`compaction`] is the pure planning library for context
 //!   compaction: token estimation, cut-point selection, summary
 //!   prompt templates, and file-op extraction over log entries.
+//! - [`prune`] lists the projected messages and plans manual drops
+//!   from the context, keeping tool calls and results paired.
+//! - [`breakdown`] estimates how the projected context splits across
+//!   content types (tool results, thinking, text, system prompt).
 
+pub mod breakdown;
 pub mod compaction;
 pub mod listener;
 pub mod log;
 pub mod persistence;
+pub mod prune;
 pub mod repair;
 pub mod replay;
 pub mod stats;
 
+pub use breakdown::{ContextBreakdown, ToolResultShare, context_breakdown};
 pub use compaction::{
     CompactionDetails, CompactionPlan, ContextEstimate, estimate_context_tokens,
     prepare_compaction, should_compact,
@@ -37,6 +44,7 @@ pub use log::{
     EntryId, SessionSettings, ThreadFilter, ThreadKind,
 };
 pub use persistence::{ConversationPersistence, SessionMetadata, SessionPreview};
+pub use prune::{MessageRow, drop_notice, message_rows, plan_drop};
 pub use repair::repair_interrupted_tool_uses;
 pub use replay::replay;
 pub use stats::SessionStats;
@@ -23,10 +23,10 @@
 
 use aj_agent::events::AgentSettings;
 use aj_agent::message::AgentMessage;
-use aj_models::types::Message;
+use aj_models::types::{AssistantContent, Message, ToolResultMessage};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{
     fs::{self, File, OpenOptions},
     io::{BufRead, BufReader, Write},
@@ -181,6 +181,16 @@ pub enum ConversationEntryKind {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         details: Option<crate::compaction::CompactionDetails>,
     },
+    /// The user dropped messages from the model's context (`drop`
+    /// command). Projection skips the listed message entries; a
+    /// dropped tool result is replaced by a short stub instead, so its
+    /// `tool_call` stays answered on the wire. Like `Compaction` this
+    /// changes only the projection; the dropped lines stay on disk and
+    /// in the replayed scrollback.
+    Prune {
+        /// Message entries to leave out of the projection.
+        entry_ids: Vec<EntryId>,
+    },
 }
 
 impl ConversationEntryKind {
@@ -199,12 +209,12 @@ impl ConversationEntryKind {
     /// submitting anything leaves no file on disk; the system prompt
     /// alone is not enough to materialize one.
     ///
-    /// A `Compaction` checkpoint is likewise punctuation: it must be
-    /// durable on its own so that resuming a compacted-then-abandoned
-    /// session still sees the reduced context.
+    /// A `Compaction` checkpoint (and a `Prune`) is likewise
+    /// punctuation: it must be durable on its own so that resuming a
+    /// compacted-then-abandoned session still sees the reduced context.
     pub fn is_punctuation(&self) -> bool {
         match self {
-            Self::Message { .. } | Self::Compaction { .. } => true,
+            Self::Message { .. } | Self::Compaction { .. } | Self::Prune { .. } => true,
             Self::SystemPrompt { .. }
             | Self::ModelChange { .. }
             | Self::ThinkingChange { .. }
@@ -335,15 +345,70 @@ impl Conversation {
         self.projected_agent_messages()
     }
 
+    /// The projection [`Self::agent_messages`] returns, each message
+    /// paired with the id of the entry it came from. The synthetic
+    /// compaction summary has no entry of its own and carries `None`.
+    pub fn indexed_messages(&self) -> Vec<(Option<EntryId>, AgentMessage)> {
+        self.projected()
+    }
+
+    /// Ids of every message entry a [`ConversationEntryKind::Prune`]
+    /// on this path dropped.
+    pub fn pruned_entry_ids(&self) -> HashSet<EntryId> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.entry {
+                ConversationEntryKind::Prune { entry_ids } => Some(entry_ids.iter().cloned()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// What the `Prune` entries on this path remove; see
+    /// [`project_message`].
+    pub(crate) fn pruned(&self) -> Pruned {
+        let entry_ids = self.pruned_entry_ids();
+        let call_ids = self
+            .entries
+            .iter()
+            .filter(|entry| entry_ids.contains(&entry.id))
+            .filter_map(|entry| match &entry.entry {
+                ConversationEntryKind::Message { message } => match message.as_wire() {
+                    Some(Message::Assistant(a)) => Some(a.content.iter().filter_map(|c| match c {
+                        AssistantContent::ToolCall(tc) => Some(tc.id.clone()),
+                        _ => None,
+                    })),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        Pruned {
+            entry_ids,
+            call_ids,
+        }
+    }
+
+    fn projected_agent_messages(&self) -> Vec<AgentMessage> {
+        self.projected()
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect()
+    }
+
     /// Project entries to the agent transcript, honoring the latest
     /// compaction: everything before its `first_kept_entry_id` is
-    /// replaced by a single synthetic summary message.
+    /// replaced by a single synthetic summary message. Pruned entries
+    /// are left out (see [`project_message`]).
     ///
     /// The last compaction wins — its summary already folds in any
     /// earlier compaction and its `first_kept_entry_id` points past the
     /// earlier boundary, so the latest summary plus its retained tail
     /// reconstruct the full reduced context.
-    fn projected_agent_messages(&self) -> Vec<AgentMessage> {
+    fn projected(&self) -> Vec<(Option<EntryId>, AgentMessage)> {
+        let pruned = self.pruned();
         let last_compaction = self
             .entries
             .iter()
@@ -362,9 +427,8 @@ impl Conversation {
             return self
                 .entries
                 .iter()
-                .filter_map(|entry| match &entry.entry {
-                    ConversationEntryKind::Message { message } => Some(message.clone()),
-                    _ => None,
+                .filter_map(|entry| {
+                    project_message(entry, &pruned).map(|message| (Some(entry.id.clone()), message))
                 })
                 .collect();
         };
@@ -384,11 +448,11 @@ impl Conversation {
                 c
             });
 
-        let mut out: Vec<AgentMessage> = Vec::new();
-        out.push(crate::compaction::summary_message(&summary));
+        let mut out: Vec<(Option<EntryId>, AgentMessage)> = Vec::new();
+        out.push((None, crate::compaction::summary_message(&summary)));
         for entry in &self.entries[k..] {
-            if let ConversationEntryKind::Message { message } = &entry.entry {
-                out.push(message.clone());
+            if let Some(message) = project_message(entry, &pruned) {
+                out.push((Some(entry.id.clone()), message));
             }
         }
         out
@@ -436,8 +500,8 @@ impl Conversation {
                 // Compaction does not change settings; the retained tail
                 // still carries the last assistant model, and any
                 // settings entries before the boundary remain on the
-                // path.
-                ConversationEntryKind::Compaction { .. } => {}
+                // path. Neither does dropping messages.
+                ConversationEntryKind::Compaction { .. } | ConversationEntryKind::Prune { .. } => {}
             }
         }
         settings
@@ -455,6 +519,40 @@ impl Conversation {
     }
 }
 
+/// Text a dropped tool result is projected as.
+const PRUNED_TOOL_RESULT: &str = "[Tool result removed from the context by the user.]";
+
+/// The pruned message entries on one linearized path, plus the tool
+/// call ids carried by the pruned assistant messages among them.
+pub(crate) struct Pruned {
+    entry_ids: HashSet<EntryId>,
+    call_ids: HashSet<String>,
+}
+
+/// The message `entry` contributes to a projection: `None` for
+/// non-message entries and pruned messages. A pruned tool result whose
+/// call is still in the projection becomes a [`PRUNED_TOOL_RESULT`]
+/// stub instead, so that call stays answered on the wire.
+pub(crate) fn project_message(entry: &ConversationEntry, pruned: &Pruned) -> Option<AgentMessage> {
+    let ConversationEntryKind::Message { message } = &entry.entry else {
+        return None;
+    };
+    if !pruned.entry_ids.contains(&entry.id) {
+        return Some(message.clone());
+    }
+    match message.as_wire() {
+        Some(Message::ToolResult(tr)) if !pruned.call_ids.contains(&tr.tool_call_id) => Some(
+            AgentMessage::wire(Message::ToolResult(ToolResultMessage::text(
+                tr.tool_call_id.clone(),
+                tr.tool_name.clone(),
+                PRUNED_TOOL_RESULT,
+                tr.is_error,
+            ))),
+        ),
+        _ => None,
+    }
+}
+
 /// An append-only, event-sourced log of a conversation and all its subagent
 /// and branch offshoots, held in memory and mirrored to a single JSONL file
 /// on disk.
@@ -991,6 +1089,42 @@ impl ConversationLog {
         )
     }
 
+    /// Record that the user dropped `entry_ids` from `filter`'s
+    /// thread, anchored at the thread's current leaf. Punctuation:
+    /// flushes immediately. Every id must name an existing message
+    /// entry; the caller (see [`crate::prune::plan_drop`]) is
+    /// responsible for keeping tool calls and results paired.
+    pub fn append_prune(
+        &mut self,
+        filter: ThreadFilter,
+        entry_ids: Vec<EntryId>,
+    ) -> Result<EntryId, ConversationError> {
+        if entry_ids.is_empty() {
+            return Err(ConversationError::InvalidAppend(
+                "prune must name at least one entry".to_string(),
+            ));
+        }
+        for id in &entry_ids {
+            match self.entries.get(id).map(|e| &e.entry) {
+                Some(ConversationEntryKind::Message { .. }) => {}
+                _ => {
+                    return Err(ConversationError::InvalidAppend(format!(
+                        "prune entry {id} is not a message in the log"
+                    )));
+                }
+            }
+        }
+        let parent = self
+            .latest_leaf(filter)
+            .or_else(|| self.system_prompt_id().cloned());
+        self.append(
+            parent,
+            filter.thread,
+            filter.agent_id,
+            ConversationEntryKind::Prune { entry_ids },
+        )
+    }
+
     /// Seed sub-agent `agent_id`'s thread with its
     /// [`ConversationEntryKind::SubAgentSpawn`] root, anchored at
     /// `parent_head` (the parent thread's head at spawn time — the
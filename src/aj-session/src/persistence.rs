@@ -132,6 +132,16 @@ impl ConversationPersistence {
         Ok(sessions.first().map(|t| t.session_id.clone()))
     }
 
+    /// Preview of the most recent session, if any. Stops at the first
+    /// file that yields a preview rather than walking the whole
+    /// directory like [`Self::list_session_previews`].
+    pub fn latest_session_preview(&self) -> Option<SessionPreview> {
+        let candidates = self.preview_candidates().ok()?;
+        candidates
+            .into_iter()
+            .find_map(|(session_id, path)| read_preview(session_id, &path))
+    }
+
     /// List sessions with rich per-session previews — first user
     /// message, message count, modified time, file size.
     ///
@@ -593,6 +603,20 @@ mod tests {
         assert_eq!(streamed, batched);
     }
 
+    #[test]
+    fn latest_session_preview_is_the_newest_session() {
+        let (_dir, persistence) = fixture();
+        assert!(persistence.latest_session_preview().is_none());
+        for i in 0..2 {
+            let mut log = ConversationLog::create(&persistence).expect("create");
+            append_user_then_assistant(&mut log, &format!("prompt {i}"), &format!("reply {i}"));
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let latest = persistence.latest_session_preview().expect("a session");
+        assert_eq!(latest.first_user_message.as_deref(), Some("prompt 1"));
+    }
+
     #[test]
     fn list_session_previews_streaming_missing_dir_emits_nothing() {
         let dir = TempDir::new().unwrap();
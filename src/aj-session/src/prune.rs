@@ -0,0 +1,321 @@
+//! Manual context pruning: the listing behind the interactive
+//! `context` overlay and the planning behind `drop <n>`.
+//!
+//! Both work on the projection the model actually receives
+//! ([`Conversation::indexed_messages`]), numbered from 1. A compaction
+//! summary shows up as message 1 and can't be dropped; everything
+//! after it can. [`plan_drop`] keeps `tool_call` / `tool_result`
+//! pairing intact: dropping an assistant message drops the results of
+//! its tool calls with it, while dropping a single tool result leaves
+//! a stub in its place (see [`crate::log::ConversationEntryKind::Prune`]).
+//! The host records the plan with
+//! [`crate::ConversationLog::append_prune`] and reseeds the agent from
+//! the new projection.
+
+use std::collections::HashSet;
+
+use aj_models::types::{AssistantContent, Message, UserContent};
+
+use crate::compaction::estimate_message_tokens;
+use crate::log::{Conversation, EntryId};
+
+/// Characters of text kept in [`MessageRow::preview`].
+const PREVIEW_CHARS: usize = 60;
+
+/// One projected message, summarized for the listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageRow {
+    /// 1-based position in the projection; the number `drop` takes.
+    pub index: usize,
+    /// `user`, `assistant`, `tool_result (<tool>)`, or `summary` for
+    /// a compaction summary.
+    pub role: String,
+    /// Block kinds in order, repeats collapsed (`thinking, text,
+    /// 2× tool_call`).
+    pub blocks: String,
+    /// Estimated context tokens (the compaction heuristic).
+    pub tokens: u64,
+    /// The start of the message's first text, on one line.
+    pub preview: String,
+}
+
+/// Summarize every message of `conversation`'s projection.
+pub fn message_rows(conversation: &Conversation) -> Vec<MessageRow> {
+    conversation
+        .indexed_messages()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (id, message))| {
+            let wire = message.as_wire()?;
+            let (role, kinds, text) = match wire {
+                Message::User(u) => {
+                    let role = if id.is_none() { "summary" } else { "user" };
+                    (
+                        role.to_string(),
+                        user_kinds(&u.content),
+                        first_text(&u.content),
+                    )
+                }
+                Message::Assistant(a) => {
+                    let kinds = a
+                        .content
+                        .iter()
+                        .map(|c| match c {
+                            AssistantContent::Text(_) => "text",
+                            AssistantContent::Thinking(_) => "thinking",
+                            AssistantContent::ToolCall(_) => "tool_call",
+                        })
+                        .collect();
+                    let text = a
+                        .content
+                        .iter()
+                        .find_map(|c| match c {
+                            AssistantContent::Text(t) => Some(t.text.as_str()),
+                            AssistantContent::ToolCall(tc) => Some(tc.name.as_str()),
+                            AssistantContent::Thinking(_) => None,
+                        })
+                        .unwrap_or_default()
+                        .to_string();
+                    ("assistant".to_string(), kinds, text)
+                }
+                Message::ToolResult(tr) => (
+                    format!("tool_result ({})", tr.tool_name),
+                    user_kinds(&tr.content),
+                    first_text(&tr.content),
+                ),
+            };
+            Some(MessageRow {
+                index: i + 1,
+                role,
+                blocks: collapse_kinds(&kinds),
+                tokens: estimate_message_tokens(wire),
+                preview: preview(&text),
+            })
+        })
+        .collect()
+}
+
+/// Entry ids to prune for `drop <index>` (1-based, as listed by
+/// [`message_rows`]), with tool pairing repaired. Errors are
+/// user-facing sentences.
+pub fn plan_drop(conversation: &Conversation, index: usize) -> Result<Vec<EntryId>, String> {
+    let messages = conversation.indexed_messages();
+    let Some((id, message)) = index.checked_sub(1).and_then(|i| messages.get(i)) else {
+        return Err(format!(
+            "There is no message #{index}; the context has {} messages.",
+            messages.len()
+        ));
+    };
+    let Some(id) = id else {
+        return Err("The compaction summary can't be dropped.".to_string());
+    };
+    if conversation.pruned_entry_ids().contains(id) {
+        return Err(format!("Message #{index} was already dropped."));
+    }
+
+    let mut ids = vec![id.clone()];
+    if let Some(Message::Assistant(a)) = message.as_wire() {
+        let calls: HashSet<&str> = a
+            .content
+            .iter()
+            .filter_map(|c| match c {
+                AssistantContent::ToolCall(tc) => Some(tc.id.as_str()),
+                _ => None,
+            })
+            .collect();
+        ids.extend(messages.iter().filter_map(|(id, m)| match m.as_wire() {
+            Some(Message::ToolResult(tr)) if calls.contains(tr.tool_call_id.as_str()) => id.clone(),
+            _ => None,
+        }));
+    }
+    Ok(ids)
+}
+
+/// The notice shown when `count` message entries were dropped, live
+/// and on replay.
+pub fn drop_notice(count: usize) -> String {
+    match count {
+        1 => "Dropped 1 message from the context.".to_string(),
+        n => format!("Dropped {n} messages from the context."),
+    }
+}
+
+fn user_kinds(content: &[UserContent]) -> Vec<&'static str> {
+    content
+        .iter()
+        .map(|c| match c {
+            UserContent::Text(_) => "text",
+            UserContent::Image(_) => "image",
+        })
+        .collect()
+}
+
+fn first_text(content: &[UserContent]) -> String {
+    content
+        .iter()
+        .find_map(|c| match c {
+            UserContent::Text(t) => Some(t.text.clone()),
+            UserContent::Image(_) => None,
+        })
+        .unwrap_or_default()
+}
+
+/// `["thinking", "text", "tool_call", "tool_call"]` → `thinking,
+/// text, 2× tool_call`.
+fn collapse_kinds(kinds: &[&str]) -> String {
+    let mut runs: Vec<(&str, usize)> = Vec::new();
+    for kind in kinds {
+        match runs.last_mut() {
+            Some((last, n)) if last == kind => *n += 1,
+            _ => runs.push((kind, 1)),
+        }
+    }
+    runs.iter()
+        .map(|(kind, n)| match n {
+            1 => (*kind).to_string(),
+            n => format!("{n}× {kind}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= PREVIEW_CHARS {
+        return flat;
+    }
+    let head: String = flat.chars().take(PREVIEW_CHARS - 1).collect();
+    format!("{head}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{ConversationLog, ConversationView, ThreadFilter};
+    use crate::persistence::ConversationPersistence;
+    use aj_agent::message::AgentMessage;
+    use aj_models::types::{
+        AssistantMessage, TextContent, ToolCall, ToolResultMessage, UserMessage,
+    };
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn user(text: &str) -> AgentMessage {
+        AgentMessage::wire(Message::User(UserMessage::text(text)))
+    }
+
+    fn assistant(text: &str, calls: &[&str]) -> AgentMessage {
+        let mut content = vec![AssistantContent::Text(TextContent {
+            text: text.to_string(),
+            text_signature: None,
+        })];
+        content.extend(calls.iter().map(|id| {
+            AssistantContent::ToolCall(ToolCall {
+                id: id.to_string(),
+                name: "bash".to_string(),
+                arguments: json!({}),
+            })
+        }));
+        AgentMessage::wire(Message::Assistant(AssistantMessage {
+            content,
+            ..AssistantMessage::empty()
+        }))
+    }
+
+    fn result(id: &str, body: &str) -> AgentMessage {
+        AgentMessage::wire(Message::ToolResult(ToolResultMessage::text(
+            id, "bash", body, false,
+        )))
+    }
+
+    /// A log holding: user, assistant calling two tools, both results,
+    /// assistant answer.
+    fn log_with_tools() -> (TempDir, ConversationLog) {
+        let dir = TempDir::new().expect("temp dir");
+        let persistence = ConversationPersistence::new(dir.path().join("sessions"));
+        let mut log = ConversationLog::create(&persistence).expect("create log");
+        log.set_system_prompt("p".to_string()).expect("sp");
+        {
+            let mut view = ConversationView::user(&mut log, None);
+            view.add_message(user("list files")).expect("u");
+            view.add_message(assistant("running", &["c1", "c2"]))
+                .expect("a");
+            view.add_message(result("c1", &"x".repeat(4000)))
+                .expect("r1");
+            view.add_message(result("c2", "small")).expect("r2");
+            view.add_message(assistant("done", &[])).expect("a2");
+        }
+        (dir, log)
+    }
+
+    fn conversation(log: &ConversationLog) -> Conversation {
+        let head = log.latest_leaf(ThreadFilter::USER).expect("head");
+        log.linearize(&head, ThreadFilter::USER)
+    }
+
+    fn wire_shape(conversation: &Conversation) -> Vec<String> {
+        conversation
+            .messages()
+            .iter()
+            .map(|m| match m {
+                Message::User(_) => "user".to_string(),
+                Message::Assistant(_) => "assistant".to_string(),
+                Message::ToolResult(tr) => format!("result {}", first_text(&tr.content)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rows_describe_each_projected_message() {
+        let (_dir, log) = log_with_tools();
+        let rows = message_rows(&conversation(&log));
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[1].role, "assistant");
+        assert_eq!(rows[1].blocks, "text, 2× tool_call");
+        assert_eq!(rows[2].role, "tool_result (bash)");
+        assert_eq!(rows[2].tokens, 1000);
+        assert!(rows[2].preview.ends_with('…'));
+    }
+
+    #[test]
+    fn dropping_a_tool_result_leaves_a_stub() {
+        let (_dir, mut log) = log_with_tools();
+        let ids = plan_drop(&conversation(&log), 3).expect("plan");
+        assert_eq!(ids.len(), 1);
+        log.append_prune(ThreadFilter::USER, ids).expect("prune");
+
+        let after = conversation(&log);
+        let shape = wire_shape(&after);
+        assert_eq!(shape.len(), 5);
+        assert!(shape[2].contains("removed from the context"), "{shape:?}");
+        assert_eq!(shape[3], "result small");
+        assert_eq!(
+            plan_drop(&after, 3).unwrap_err(),
+            "Message #3 was already dropped."
+        );
+    }
+
+    #[test]
+    fn dropping_an_assistant_message_takes_its_results_along() {
+        let (_dir, mut log) = log_with_tools();
+        let ids = plan_drop(&conversation(&log), 2).expect("plan");
+        assert_eq!(ids.len(), 3);
+        log.append_prune(ThreadFilter::USER, ids).expect("prune");
+
+        assert_eq!(
+            wire_shape(&conversation(&log)),
+            vec!["user".to_string(), "assistant".to_string()]
+        );
+    }
+
+    #[test]
+    fn out_of_range_index_is_an_error() {
+        let (_dir, log) = log_with_tools();
+        let convo = conversation(&log);
+        assert!(plan_drop(&convo, 0).is_err());
+        assert_eq!(
+            plan_drop(&convo, 9).unwrap_err(),
+            "There is no message #9; the context has 5 messages."
+        );
+    }
+}
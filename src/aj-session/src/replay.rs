@@ -63,6 +63,9 @@
 //!   stale). The summarized prefix entries still replay in order, so
 //!   the scrollback shows the full history even though the model
 //!   context (rebuilt via `agent_messages`) is the reduced projection.
+//! - [`ConversationEntryKind::Prune`]: one [`AgentEvent::Notice`]
+//!   repeating what the live `drop` command reported. As with
+//!   compaction, the dropped messages themselves still replay.
 //!
 //! Sub-agent runs are bracketed with synthesized
 //! [`AgentEvent::SubAgentStart`] / [`AgentEvent::SubAgentEnd`]
@@ -251,7 +254,8 @@ impl ReplayState {
             | ConversationEntryKind::SpeedChange { .. }
             | ConversationEntryKind::VerbosityChange { .. }
             | ConversationEntryKind::SystemPrompt { .. }
-            | ConversationEntryKind::Compaction { .. } => {}
+            | ConversationEntryKind::Compaction { .. }
+            | ConversationEntryKind::Prune { .. } => {}
         }
     }
 
@@ -347,6 +351,12 @@ impl ReplayState {
                     error: None,
                 });
             }
+            ConversationEntryKind::Prune { entry_ids } => {
+                out.push(AgentEvent::Notice {
+                    agent_id,
+                    text: crate::prune::drop_notice(entry_ids.len()),
+                });
+            }
             ConversationEntryKind::Message { message: agent_msg } => {
                 self.seen_message.insert(agent_id);
                 let Some(wire) = agent_msg.as_wire() else {
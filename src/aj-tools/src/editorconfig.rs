@@ -0,0 +1,381 @@
+//! `.editorconfig` support for the file-writing tools
+//! (`respect_editorconfig`).
+//!
+//! When enabled, `write_file`, `edit_file`, `edit_file_multi`,
+//! `write_range` and `regex_replace` pass the content they are about to
+//! write through [`apply_editorconfig`], after `normalize_on_write`.
+//! The settings for the target path are collected the way editors do:
+//! every `.editorconfig` from the file's directory upwards, stopping at
+//! one declaring `root = true`, with nearer files and later sections
+//! winning. Of those settings, these are applied:
+//!
+//! - `trim_trailing_whitespace = true` trims every line;
+//! - `insert_final_newline` adds (`true`) or removes (`false`) the
+//!   final line ending;
+//! - `indent_style` rewrites each line's leading whitespace to tabs or
+//!   spaces, using `indent_size` / `tab_width` for the width.
+//!
+//! Line endings are kept as they were. Content containing a NUL byte is
+//! treated as binary and left alone, as is empty content. Unreadable or
+//! malformed `.editorconfig` lines are skipped.
+
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+/// Appended to a tool's success message when `.editorconfig` changed
+/// what was written, so the model doesn't later match against the
+/// content it sent.
+pub(crate) const EDITORCONFIG_NOTE: &str =
+    "\nNote: the content was adjusted to the project's .editorconfig on write.";
+
+/// Columns a tab counts for when neither `tab_width` nor a numeric
+/// `indent_size` says otherwise.
+const DEFAULT_TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IndentStyle {
+    Tab,
+    Space,
+}
+
+/// The properties this module applies; `None` when unset.
+#[derive(Default, Debug, PartialEq)]
+struct Properties {
+    indent_style: Option<IndentStyle>,
+    indent_size: Option<usize>,
+    tab_width: Option<usize>,
+    insert_final_newline: Option<bool>,
+    trim_trailing_whitespace: Option<bool>,
+}
+
+/// Adjust `content`, about to be written to `path`, to the applicable
+/// `.editorconfig` settings when `enabled`. Returns the content to
+/// write and whether it changed.
+pub fn apply_editorconfig(enabled: bool, path: &Path, content: String) -> (String, bool) {
+    if !enabled || content.is_empty() || content.contains('\0') {
+        return (content, false);
+    }
+    let properties = lookup(path);
+    if properties == Properties::default() {
+        return (content, false);
+    }
+    let adjusted = apply(&properties, &content);
+    let changed = adjusted != content;
+    (adjusted, changed)
+}
+
+/// Collect the settings for `path` from its `.editorconfig` files.
+fn lookup(path: &Path) -> Properties {
+    let mut files = Vec::new();
+    for dir in path.ancestors().skip(1) {
+        let Ok(text) = fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let root = is_root(&text);
+        files.push((dir, text));
+        if root {
+            break;
+        }
+    }
+    let mut properties = Properties::default();
+    for (dir, text) in files.iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        apply_file(text, &relative.to_string_lossy(), &mut properties);
+    }
+    properties
+}
+
+/// Whether the preamble (the part before the first section) declares
+/// `root = true`.
+fn is_root(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(key_value)
+        .any(|(key, value)| key == "root" && value == "true")
+}
+
+/// Fold the sections of one `.editorconfig` that match `relative` (the
+/// target's path relative to the file's directory) into `properties`.
+fn apply_file(text: &str, relative: &str, properties: &mut Properties) {
+    let mut matching = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(pattern) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            matching = section_matches(pattern, relative);
+            continue;
+        }
+        if !matching {
+            continue;
+        }
+        let Some((key, value)) = key_value(line) else {
+            continue;
+        };
+        let flag = match value.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+        match key.as_str() {
+            "indent_style" => {
+                properties.indent_style = match value.as_str() {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ => None,
+                };
+            }
+            // `indent_size = tab` defers to `tab_width`, which `None`
+            // does as well.
+            "indent_size" => properties.indent_size = value.parse().ok(),
+            "tab_width" => properties.tab_width = value.parse().ok(),
+            "insert_final_newline" => properties.insert_final_newline = flag,
+            "trim_trailing_whitespace" => properties.trim_trailing_whitespace = flag,
+            _ => {}
+        }
+    }
+}
+
+/// A `key = value` line, both lowercased and trimmed.
+fn key_value(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once('=')?;
+    Some((
+        key.trim().to_ascii_lowercase(),
+        value.trim().to_ascii_lowercase(),
+    ))
+}
+
+/// Whether a section header `pattern` applies to `relative`. Patterns
+/// without a `/` match the file name at any depth; the rest are
+/// anchored at the `.editorconfig`'s directory.
+fn section_matches(pattern: &str, relative: &str) -> bool {
+    let anchored = pattern.contains('/');
+    let body = glob_to_regex(pattern.trim_start_matches('/'));
+    let regex = if anchored {
+        format!("^{body}$")
+    } else {
+        format!("^(?:.*/)?{body}$")
+    };
+    Regex::new(&regex).is_ok_and(|re| re.is_match(relative))
+}
+
+/// Translate an editorconfig glob into a regex: `*` stays within a
+/// path component, `**` crosses them, `?` is one character, `[...]`
+/// (`[!...]` negated) is a character class and `{a,b}` an alternation.
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::new();
+    let mut braces = 0usize;
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                out.push_str(".*");
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                let class: String = chars.clone().take_while(|&c| c != ']').collect();
+                let len = class.chars().count();
+                if chars.clone().nth(len) == Some(']') {
+                    chars.nth(len);
+                    let (negate, class) = match class.strip_prefix('!') {
+                        Some(rest) => ("^", rest.to_string()),
+                        None => ("", class),
+                    };
+                    out.push_str(&format!(
+                        "[{negate}{}]",
+                        class.replace('\\', "\\\\").replace('[', "\\[")
+                    ));
+                } else {
+                    out.push_str("\\[");
+                }
+            }
+            '{' => {
+                braces += 1;
+                out.push_str("(?:");
+            }
+            '}' if braces > 0 => {
+                braces -= 1;
+                out.push(')');
+            }
+            ',' if braces > 0 => out.push('|'),
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    out.push_str(&regex::escape(&next.to_string()));
+                }
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    // An unclosed `{` matches literally in editors; closing it keeps
+    // the regex valid instead.
+    out.push_str(&")".repeat(braces));
+    out
+}
+
+/// Rewrite `content` line by line according to `properties`.
+fn apply(properties: &Properties, content: &str) -> String {
+    let tab_width = properties
+        .tab_width
+        .or(properties.indent_size)
+        .unwrap_or(DEFAULT_TAB_WIDTH)
+        .max(1);
+    let trim = properties.trim_trailing_whitespace == Some(true);
+
+    let mut out = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        let (body, ending) = match line.strip_suffix("\r\n") {
+            Some(body) => (body, "\r\n"),
+            None => match line.strip_suffix('\n') {
+                Some(body) => (body, "\n"),
+                None => (line, ""),
+            },
+        };
+        let body = if trim { body.trim_end() } else { body };
+        match properties.indent_style {
+            Some(style) => {
+                let rest = body.trim_start_matches([' ', '\t']);
+                let leading = &body[..body.len() - rest.len()];
+                out.push_str(&reindent(leading, style, tab_width));
+                out.push_str(rest);
+            }
+            None => out.push_str(body),
+        }
+        out.push_str(ending);
+    }
+
+    match properties.insert_final_newline {
+        Some(true) if !out.ends_with('\n') => {
+            out.push_str(if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            });
+        }
+        Some(false) => {
+            if let Some(stripped) = out.strip_suffix('\n') {
+                let end = stripped.strip_suffix('\r').unwrap_or(stripped).len();
+                out.truncate(end);
+            }
+        }
+        _ => {}
+    }
+    out
+}
+
+/// Leading whitespace `leading` rewritten in `style`, keeping its
+/// visual width.
+fn reindent(leading: &str, style: IndentStyle, tab_width: usize) -> String {
+    let width = leading.chars().fold(0, |col, c| match c {
+        '\t' => (col / tab_width + 1) * tab_width,
+        _ => col + 1,
+    });
+    match style {
+        IndentStyle::Space => " ".repeat(width),
+        IndentStyle::Tab => {
+            // Whole tab stops become tabs; a remainder (alignment)
+            // stays as spaces.
+            format!(
+                "{}{}",
+                "\t".repeat(width / tab_width),
+                " ".repeat(width % tab_width)
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, text: &str) {
+        std::fs::create_dir_all(dir.join(name).parent().unwrap()).unwrap();
+        std::fs::write(dir.join(name), text).unwrap();
+    }
+
+    #[test]
+    fn nearer_files_and_later_sections_win() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            ".editorconfig",
+            "root = true\n\n[*]\ninsert_final_newline = true\ntrim_trailing_whitespace = true\n\n\
+             [*.md]\ntrim_trailing_whitespace = false\n",
+        );
+        write(
+            dir.path(),
+            "sub/.editorconfig",
+            "[*.rs]\nindent_style = space\nindent_size = 4\n",
+        );
+
+        let rs = dir.path().join("sub/main.rs");
+        let (out, changed) = apply_editorconfig(true, &rs, "fn f() {\n\tx();  \n}".to_string());
+        assert!(changed);
+        assert_eq!(out, "fn f() {\n    x();\n}\n");
+
+        let md = dir.path().join("README.md");
+        let (out, _) = apply_editorconfig(true, &md, "a  \nb".to_string());
+        assert_eq!(out, "a  \nb\n");
+    }
+
+    #[test]
+    fn path_sections_are_anchored_and_root_stops_the_walk() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            ".editorconfig",
+            "[*]\ninsert_final_newline = false\n",
+        );
+        write(
+            dir.path(),
+            "proj/.editorconfig",
+            "root = true\n[src/**.go]\nindent_style = tab\ntab_width = 4\n",
+        );
+
+        let go = dir.path().join("proj/src/x/main.go");
+        let (out, _) = apply_editorconfig(true, &go, "func f() {\n        x()\n}\n".to_string());
+        assert_eq!(out, "func f() {\n\t\tx()\n}\n");
+
+        let other = dir.path().join("proj/main.go");
+        let (out, changed) = apply_editorconfig(true, &other, "  x\n".to_string());
+        assert_eq!((out.as_str(), changed), ("  x\n", false));
+    }
+
+    #[test]
+    fn final_newline_can_be_removed_and_crlf_is_kept() {
+        let properties = Properties {
+            insert_final_newline: Some(false),
+            ..Properties::default()
+        };
+        assert_eq!(apply(&properties, "a\r\nb\r\n"), "a\r\nb");
+        let properties = Properties {
+            insert_final_newline: Some(true),
+            ..Properties::default()
+        };
+        assert_eq!(apply(&properties, "a\r\nb"), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn disabled_or_unconfigured_writes_are_untouched() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            ".editorconfig",
+            "root = true\n[*]\ninsert_final_newline = true\n",
+        );
+        let path = dir.path().join("a.txt");
+        let (out, changed) = apply_editorconfig(false, &path, "a".to_string());
+        assert_eq!((out.as_str(), changed), ("a", false));
+
+        let bare = TempDir::new().unwrap();
+        let (out, changed) = apply_editorconfig(true, &bare.path().join("a.txt"), "a".to_string());
+        assert_eq!((out.as_str(), changed), ("a", false));
+    }
+}
@@ -0,0 +1,58 @@
+//! Size cap for the tools that load a whole file into memory
+//! (`max_file_size`).
+//!
+//! `read_file`, `read_multi`, `search_in_file`, `syntax_check`,
+//! `write_file` (for its before/after diff), `edit_file`,
+//! `edit_file_multi`, `write_range`, `regex_replace` and `format_file`
+//! call [`check_file_size`] before reading, so a multi-hundred-megabyte
+//! file is refused with a pointer at a bash range read instead of being
+//! pulled in with `read_to_string`.
+
+use std::fs;
+use std::path::Path;
+
+/// Default for `max_file_size`: 10 MB.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Refuse `path` when it is larger than `max_bytes`; `0` disables the
+/// check. `hint` is appended to the error to say what to do instead.
+/// Paths whose metadata can't be read pass, so the caller's own read
+/// reports the real problem.
+pub fn check_file_size(path: &Path, max_bytes: u64, hint: &str) -> Result<(), String> {
+    if max_bytes == 0 {
+        return Ok(());
+    }
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+    if !metadata.is_file() || metadata.len() <= max_bytes {
+        return Ok(());
+    }
+    Err(format!(
+        "File '{}' is too large ({} bytes, limit {max_bytes}). {hint}",
+        path.display(),
+        metadata.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn refuses_files_over_the_cap() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("big.log");
+        fs::write(&file, "x".repeat(100)).unwrap();
+
+        let err = check_file_size(&file, 10, "Use bash.").unwrap_err();
+        assert!(
+            err.ends_with("is too large (100 bytes, limit 10). Use bash."),
+            "{err}"
+        );
+        assert!(check_file_size(&file, 100, "").is_ok());
+        assert!(check_file_size(&file, 0, "").is_ok());
+        assert!(check_file_size(&dir.path().join("missing"), 10, "").is_ok());
+    }
+}
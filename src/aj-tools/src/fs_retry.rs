@@ -0,0 +1,106 @@
+//! Retry for transient filesystem failures in the file-mutating tools.
+//!
+//! Network filesystems and on-access scanners occasionally fail a
+//! write with `PermissionDenied` or `ResourceBusy` while something
+//! else briefly holds the file. [`retry_transient`] reruns such an
+//! operation a couple of times with a short exponential backoff before
+//! giving up; every other error kind (missing parent, invalid path,
+//! ...) is returned on the first attempt.
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use tokio_retry2::strategy::ExponentialBackoff;
+
+/// Retries after the first attempt.
+const TRANSIENT_RETRIES: usize = 2;
+
+/// Backoff between attempts: 20ms, then 200ms.
+fn retry_delays() -> impl Iterator<Item = Duration> {
+    ExponentialBackoff::from_millis(10)
+        .factor(2)
+        .take(TRANSIENT_RETRIES)
+}
+
+/// Error kinds that are worth another attempt.
+fn is_transient(kind: io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        io::ErrorKind::PermissionDenied | io::ErrorKind::ResourceBusy
+    )
+}
+
+/// Run `op`, rerunning it after a backoff while it fails with a
+/// transient error. The last error is returned once retries run out.
+pub(crate) async fn retry_transient<T>(mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut delays = retry_delays();
+    loop {
+        match op() {
+            Err(e) if is_transient(e.kind()) => match delays.next() {
+                Some(delay) => {
+                    tracing::debug!(error = %e, ?delay, "transient filesystem error; retrying");
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(e),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// [`std::fs::write`] under [`retry_transient`].
+pub(crate) async fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    retry_transient(|| std::fs::write(path, contents.as_ref())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn transient_failure_then_success_retries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        let attempts = Cell::new(0);
+        retry_transient(|| {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() == 1 {
+                return Err(io::Error::from(io::ErrorKind::ResourceBusy));
+            }
+            std::fs::write(&file, "landed")
+        })
+        .await
+        .expect("second attempt succeeds");
+        assert_eq!(attempts.get(), 2);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "landed");
+    }
+
+    #[tokio::test]
+    async fn persistent_transient_failure_gives_up() {
+        let attempts = Cell::new(0);
+        let err = retry_transient(|| -> io::Result<()> {
+            attempts.set(attempts.get() + 1);
+            Err(io::Error::from(io::ErrorKind::PermissionDenied))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(attempts.get(), TRANSIENT_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn missing_parent_fails_fast() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let attempts = Cell::new(0);
+        let err = retry_transient(|| {
+            attempts.set(attempts.get() + 1);
+            std::fs::write(dir.path().join("no/such/dir/f.txt"), "x")
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(attempts.get(), 1);
+    }
+}
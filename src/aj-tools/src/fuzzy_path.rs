@@ -0,0 +1,161 @@
+//! Near-miss lookup for a path that doesn't exist (`read_fuzzy_paths`).
+//!
+//! The model often guesses a path slightly wrong: `src/main.rs` for
+//! `src/bin/main.rs`, or the right name under the wrong directory.
+//! [`resolve`] walks the working directory (honouring `.gitignore`)
+//! for files with the same name, falling back to the same stem with a
+//! different extension, and ranks them by how many of the wanted
+//! path's directories they share.
+//!
+//! `read_file` always lists the candidates in its not-found error; with
+//! `read_fuzzy_paths` on it reads a unique best match instead. Only a
+//! same-name match can be unique: a different extension is a different
+//! file, so it is only ever suggested.
+
+use std::path::{Component, Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+/// Most walk entries looked at before the search gives up.
+const WALK_FILES_MAX: usize = 20_000;
+/// Most candidates returned.
+pub(crate) const CANDIDATES_MAX: usize = 5;
+
+/// Outcome of [`resolve`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Resolution {
+    /// The single best same-name match, when one outranks the rest.
+    pub(crate) unique: Option<PathBuf>,
+    /// Plausible matches, best first, at most [`CANDIDATES_MAX`].
+    pub(crate) candidates: Vec<PathBuf>,
+}
+
+/// Look under `root` for the file `wanted` (an absolute path that
+/// doesn't exist) probably meant.
+pub(crate) fn resolve(wanted: &Path, root: &Path) -> Resolution {
+    let Some(name) = wanted.file_name().and_then(|n| n.to_str()) else {
+        return Resolution::default();
+    };
+    let name = name.to_lowercase();
+    let wanted_stem = stem(&name);
+    let wanted_dirs = dir_names(wanted.parent().unwrap_or(wanted), root);
+
+    // (same name, shared directories, path)
+    let mut scored: Vec<(bool, usize, PathBuf)> = Vec::new();
+    let walk = WalkBuilder::new(root).build().flatten();
+    for entry in walk.take(WALK_FILES_MAX) {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        let Some(candidate) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let candidate = candidate.to_lowercase();
+        let same_name = candidate == name;
+        if !same_name && stem(&candidate) != wanted_stem {
+            continue;
+        }
+        let dirs = dir_names(path.parent().unwrap_or(path), root);
+        let shared = wanted_dirs.iter().filter(|d| dirs.contains(d)).count();
+        scored.push((same_name, shared, path.to_path_buf()));
+    }
+
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then(b.1.cmp(&a.1))
+            .then_with(|| a.2.components().count().cmp(&b.2.components().count()))
+            .then_with(|| a.2.cmp(&b.2))
+    });
+    let unique = match scored.as_slice() {
+        [(true, _, path)] => Some(path.clone()),
+        [(true, best, path), (same_name, next, _), ..] if !same_name || best > next => {
+            Some(path.clone())
+        }
+        _ => None,
+    };
+    Resolution {
+        unique,
+        candidates: scored
+            .into_iter()
+            .take(CANDIDATES_MAX)
+            .map(|(_, _, path)| path)
+            .collect(),
+    }
+}
+
+/// `name` up to its last `.`, or all of it.
+fn stem(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => name,
+    }
+}
+
+/// Lowercased directory names of `dir`, relative to `root` when it is
+/// inside it.
+fn dir_names(dir: &Path, root: &Path) -> Vec<String> {
+    dir.strip_prefix(root)
+        .unwrap_or(dir)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str().map(str::to_lowercase),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn touch(root: &Path, rel: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "x").unwrap();
+    }
+
+    #[test]
+    fn prefers_the_same_name_sharing_the_most_directories() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        touch(root, "src/bin/main.rs");
+        touch(root, "tests/main.rs");
+        touch(root, "src/main.ts");
+
+        let resolution = resolve(&root.join("src/main.rs"), root);
+        assert_eq!(resolution.unique, Some(root.join("src/bin/main.rs")));
+        assert_eq!(
+            resolution.candidates,
+            vec![
+                root.join("src/bin/main.rs"),
+                root.join("tests/main.rs"),
+                root.join("src/main.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_and_other_extensions_are_only_suggested() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        touch(root, "a/lib.rs");
+        touch(root, "b/lib.rs");
+        touch(root, "web/app.tsx");
+
+        let resolution = resolve(&root.join("lib.rs"), root);
+        assert_eq!(resolution.unique, None);
+        assert_eq!(resolution.candidates.len(), 2);
+
+        let resolution = resolve(&root.join("web/app.ts"), root);
+        assert_eq!(resolution.unique, None);
+        assert_eq!(resolution.candidates, vec![root.join("web/app.tsx")]);
+
+        assert_eq!(
+            resolve(&root.join("nothing.rs"), root),
+            Resolution::default()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Shared plumbing for the git tools (`git_blame`, `git_file_history`,
+//! `git_files`, `git_stash`).
+//!
+//! Runs `git` as a child process with no terminal prompts and no
+//! pager, captures its output, and maps the common fatal errors
+//! (outside a repository, path unknown to git) onto messages a model
+//! can act on.
+
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+/// Run `git <args>` in `dir` and return its stdout. On a non-zero
+/// exit the error is a model-facing message built from stderr.
+pub(crate) async fn run_git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let stdout = run_git_bytes(dir, args).await?;
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+/// [`run_git`] returning stdout undecoded, for output that can carry
+/// non-UTF-8 file names.
+pub(crate) async fn run_git_bytes(dir: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_PAGER", "cat")
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if output.status.success() {
+        return Ok(output.stdout);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+    Err(if stderr.contains("not a git repository") {
+        format!("'{}' is not inside a git repository", dir.display())
+    } else {
+        let detail = stderr.strip_prefix("fatal: ").unwrap_or(stderr);
+        format!(
+            "git {} failed: {detail}",
+            args.first().copied().unwrap_or("")
+        )
+    })
+}
+
+/// Split an absolute file path into the directory git should run in
+/// and the file name to pass it, after checking the file exists.
+pub(crate) fn split_file_path(path: &Path) -> Result<(&Path, &str), String> {
+    if !path.is_file() {
+        return Err(if path.exists() {
+            format!("'{}' is not a regular file", path.display())
+        } else {
+            format!("File '{}' does not exist", path.display())
+        });
+    }
+    let dir = path.parent().unwrap_or_else(|| Path::new("/"));
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("'{}' has no usable file name", path.display()))?;
+    Ok((dir, name))
+}
+
+/// `s` cut to `max` characters, with a trailing `…` when cut.
+pub(crate) fn clip(s: &str, max: usize) -> String {
+    let mut chars = s.chars();
+    let head: String = chars.by_ref().take(max).collect();
+    if chars.next().is_some() {
+        format!("{head}…")
+    } else {
+        head
+    }
+}
@@ -0,0 +1,116 @@
+//! Transparent gzip decompression for the text-reading tools.
+//!
+//! `read_file` and `read_multi` read through [`read_text_file`]: a file
+//! with a `.gz` extension or the gzip magic bytes is decompressed on the
+//! fly (concatenated members included, as logrotate produces), and the
+//! tools then apply their usual line slicing to the decompressed text.
+//! Decompression stops at [`GZIP_MAX_DECOMPRESSED_BYTES`] so a small
+//! archive can't expand without bound; past that the read fails with a
+//! pointer at `zcat`.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+
+use crate::truncate::format_size;
+
+/// Most decompressed bytes accepted from one gzip file.
+pub(crate) const GZIP_MAX_DECOMPRESSED_BYTES: usize = 32 * 1024 * 1024;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Read `path` as UTF-8 text, decompressing it first when it is
+/// gzip-compressed. Errors carry the same `io::Error` kinds as
+/// [`fs::read_to_string`], plus `InvalidData` when the decompressed
+/// content exceeds the cap.
+pub(crate) fn read_text_file(path: &Path) -> io::Result<String> {
+    if !is_gzip(path)? {
+        return fs::read_to_string(path);
+    }
+    read_gzip(path, GZIP_MAX_DECOMPRESSED_BYTES)
+}
+
+fn is_gzip(path: &Path) -> io::Result<bool> {
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        return Ok(true);
+    }
+    let mut magic = [0u8; 2];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == GZIP_MAGIC),
+        // Shorter than the magic, a directory, and the like: let
+        // `read_to_string` handle (or report) them.
+        Err(_) => Ok(false),
+    }
+}
+
+fn read_gzip(path: &Path, max_bytes: usize) -> io::Result<String> {
+    let decoder = MultiGzDecoder::new(File::open(path)?);
+    let mut bytes = Vec::new();
+    let limit = u64::try_from(max_bytes)
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
+    decoder.take(limit).read_to_end(&mut bytes)?;
+    if bytes.len() > max_bytes {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "decompressed content exceeds {}; use bash (zcat {} | sed -n '<start>,<end>p')",
+                format_size(max_bytes),
+                path.display()
+            ),
+        ));
+    }
+    String::from_utf8(bytes).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "decompressed content is not valid UTF-8",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn gzip(text: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decompresses_by_extension_or_magic() {
+        let dir = TempDir::new().unwrap();
+        let by_ext = dir.path().join("app.log.gz");
+        let by_magic = dir.path().join("app.log.1");
+        let plain = dir.path().join("app.log");
+        fs::write(&by_ext, gzip("one\ntwo\n")).unwrap();
+        fs::write(&by_magic, [gzip("a\n"), gzip("b\n")].concat()).unwrap();
+        fs::write(&plain, "plain\n").unwrap();
+
+        assert_eq!(read_text_file(&by_ext).unwrap(), "one\ntwo\n");
+        assert_eq!(read_text_file(&by_magic).unwrap(), "a\nb\n");
+        assert_eq!(read_text_file(&plain).unwrap(), "plain\n");
+    }
+
+    #[test]
+    fn oversized_or_corrupt_archives_are_errors() {
+        let dir = TempDir::new().unwrap();
+        let big = dir.path().join("big.gz");
+        fs::write(&big, gzip(&"x".repeat(2048))).unwrap();
+        let err = read_gzip(&big, 1024).unwrap_err();
+        assert!(err.to_string().contains("exceeds 1.0KB"), "{err}");
+        assert!(read_gzip(&big, 2048).is_ok());
+
+        let bogus = dir.path().join("bogus.gz");
+        fs::write(&bogus, "not gzip").unwrap();
+        assert!(read_text_file(&bogus).is_err());
+    }
+}
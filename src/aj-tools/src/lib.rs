@@ -10,8 +10,20 @@
 //! ([`AgentEvent::ToolExecutionEnd`](aj_agent::events::AgentEvent::ToolExecutionEnd)
 //! carries the structured result); `aj-tools` is wire-only.
 
+pub mod editorconfig;
+pub mod file_size;
+mod fs_retry;
+mod fuzzy_path;
+mod git;
+mod gzip;
 pub mod image;
+mod listing;
+pub mod normalize;
+pub mod protected;
+mod resolve;
+pub mod roots;
 pub mod sanitize;
+pub mod schema_check;
 /// Test-only [`aj_agent::tool::ToolContext`] doubles for exercising tools
 /// without a live agent runtime. Gated behind `cfg(test)` plus the `testing`
 /// feature so it never ships in the production public API. Other crates'
@@ -19,6 +31,7 @@ pub mod sanitize;
 #[cfg(any(test, feature = "testing"))]
 pub mod testing;
 pub mod tools;
+pub mod trash;
 pub mod truncate;
 
 pub use sanitize::sanitize_terminal_output;
@@ -26,27 +39,107 @@ pub use sanitize::sanitize_terminal_output;
 use aj_agent::tool::ErasedToolDefinition;
 
 pub use tools::agent::AgentTool;
+pub use tools::archive_list::ArchiveListTool;
 pub use tools::bash::BashTool;
+pub use tools::count_lines::CountLinesTool;
+pub use tools::diff::DiffTool;
 pub use tools::edit_file::EditFileTool;
 pub use tools::edit_file_multi::EditFileMultiTool;
+pub use tools::env::EnvTool;
+pub use tools::format_file::FormatFileTool;
+pub use tools::git_blame::GitBlameTool;
+pub use tools::git_file_history::GitFileHistoryTool;
+pub use tools::git_files::GitFilesTool;
+pub use tools::git_stash::GitStashTool;
+pub use tools::hash::HashTool;
+pub use tools::help::HelpTool;
+pub use tools::hotspots::HotspotsTool;
+pub use tools::open_in_editor::OpenInEditorTool;
+pub use tools::preview_edit::PreviewEditTool;
 pub use tools::read_file::ReadFileTool;
+pub use tools::read_more::ReadMoreTool;
+pub use tools::read_multi::ReadMultiTool;
+pub use tools::regex_replace::RegexReplaceTool;
+pub use tools::run_tests::RunTestsTool;
+pub use tools::search_in_file::SearchInFileTool;
+pub use tools::shell_session::ShellSessionTool;
+pub use tools::structural_search::StructuralSearchTool;
+pub use tools::syntax_check::SyntaxCheckTool;
 pub use tools::task::{TaskOutputTool, TaskStopTool};
 pub use tools::todo::{TodoReadTool, TodoWriteTool};
 pub use tools::write_file::WriteFileTool;
+pub use tools::write_range::WriteRangeTool;
 
 /// Cross-cutting settings the binary feeds into builtin tool
-/// construction. Currently image-related flags only.
+/// construction.
 #[derive(Clone)]
 pub struct BuiltinToolOptions {
     /// Forwarded to [`ReadFileTool::with_auto_resize`]. Default
     /// `true`; flip via `image_auto_resize` in `~/.aj/config.toml`.
     pub image_auto_resize: bool,
+    /// Forwarded to [`BashTool::with_preserve_ansi`]. Default `false`;
+    /// flip via `bash_preserve_ansi` in `~/.aj/config.toml`.
+    pub bash_preserve_ansi: bool,
+    /// Forwarded to [`BashTool::with_default_timeout`] and
+    /// [`ShellSessionTool::with_default_timeout`]. Default
+    /// [`tools::bash::DEFAULT_TIMEOUT_SECS`]; set via `bash_timeout_secs`
+    /// in `~/.aj/config.toml` (or a project's `.aj/config.toml`).
+    pub bash_timeout_secs: u64,
+    /// Forwarded to [`EnvTool::with_show_values`]. Default `false`;
+    /// flip via `env_tool_values` in `~/.aj/config.toml`.
+    pub env_tool_values: bool,
+    /// Forwarded to the `with_normalize_on_write` constructors of the
+    /// file-writing tools. Default `false`; flip via
+    /// `normalize_on_write` in `~/.aj/config.toml`.
+    pub normalize_on_write: bool,
+    /// Forwarded to the `with_confirm_listing` builders of `write_file`
+    /// and `edit_file`. Default `false`; flip via `confirm_with_listing`
+    /// in `~/.aj/config.toml`.
+    pub confirm_with_listing: bool,
+    /// Forwarded to the `with_editorconfig` builders of the
+    /// file-writing tools. Default `false`; flip via
+    /// `respect_editorconfig` in `~/.aj/config.toml`.
+    pub respect_editorconfig: bool,
+    /// Forwarded to the `with_max_file_size` builders of `read_file`,
+    /// `read_multi`, `search_in_file`, `syntax_check`, `write_file`,
+    /// `format_file`, `diff` and the edit tools. Default
+    /// [`file_size::DEFAULT_MAX_FILE_SIZE`]; set via `max_file_size` in
+    /// `~/.aj/config.toml`.
+    pub max_file_size: u64,
+    /// Forwarded to the `with_protected_paths` builders of
+    /// `write_file`, `format_file`, `git_stash` and the edit tools.
+    /// Default protects nothing; set via `protected_paths` in
+    /// `~/.aj/config.toml`.
+    pub protected_paths: protected::ProtectedPaths,
+    /// Forwarded to [`ReadFileTool::with_fuzzy_paths`]. Default
+    /// `false`; flip via `read_fuzzy_paths` in `~/.aj/config.toml`.
+    pub read_fuzzy_paths: bool,
+    /// Forwarded to the `with_trash` builders of `write_file`,
+    /// `format_file` and the edit tools. Default `None` keeps nothing;
+    /// enable via `trash_writes` (and `trash_dir`) in
+    /// `~/.aj/config.toml`.
+    pub trash: Option<trash::Trash>,
+    /// Forwarded to the `with_allowed_roots` builders of every tool
+    /// that takes a path. Default allows every path; confine via
+    /// `confine_paths` (and `allowed_roots`) in `~/.aj/config.toml`.
+    pub allowed_roots: roots::AllowedRoots,
 }
 
 impl Default for BuiltinToolOptions {
     fn default() -> Self {
         Self {
             image_auto_resize: true,
+            bash_preserve_ansi: false,
+            bash_timeout_secs: tools::bash::DEFAULT_TIMEOUT_SECS,
+            env_tool_values: false,
+            normalize_on_write: false,
+            confirm_with_listing: false,
+            respect_editorconfig: false,
+            max_file_size: file_size::DEFAULT_MAX_FILE_SIZE,
+            protected_paths: protected::ProtectedPaths::default(),
+            read_fuzzy_paths: false,
+            trash: None,
+            allowed_roots: roots::AllowedRoots::default(),
         }
     }
 }
@@ -59,15 +152,117 @@ impl Default for BuiltinToolOptions {
 pub fn get_builtin_tools(options: &BuiltinToolOptions) -> Vec<ErasedToolDefinition> {
     vec![
         AgentTool.into(),
-        BashTool.into(),
-        ReadFileTool::with_auto_resize(options.image_auto_resize).into(),
-        WriteFileTool.into(),
-        EditFileTool.into(),
-        EditFileMultiTool.into(),
+        BashTool::with_preserve_ansi(options.bash_preserve_ansi)
+            .with_default_timeout(options.bash_timeout_secs)
+            .into(),
+        ShellSessionTool::default()
+            .with_default_timeout(options.bash_timeout_secs)
+            .into(),
+        ReadFileTool::with_auto_resize(options.image_auto_resize)
+            .with_max_file_size(options.max_file_size)
+            .with_fuzzy_paths(options.read_fuzzy_paths)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        ReadMultiTool::default()
+            .with_max_file_size(options.max_file_size)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        ReadMoreTool.into(),
+        SearchInFileTool::default()
+            .with_max_file_size(options.max_file_size)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        StructuralSearchTool::default()
+            .with_max_file_size(options.max_file_size)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        WriteFileTool::with_normalize_on_write(options.normalize_on_write)
+            .with_editorconfig(options.respect_editorconfig)
+            .with_max_file_size(options.max_file_size)
+            .with_protected_paths(options.protected_paths.clone())
+            .with_trash(options.trash.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .with_confirm_listing(options.confirm_with_listing)
+            .into(),
+        EditFileTool::with_normalize_on_write(options.normalize_on_write)
+            .with_editorconfig(options.respect_editorconfig)
+            .with_max_file_size(options.max_file_size)
+            .with_protected_paths(options.protected_paths.clone())
+            .with_trash(options.trash.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .with_confirm_listing(options.confirm_with_listing)
+            .into(),
+        EditFileMultiTool::with_normalize_on_write(options.normalize_on_write)
+            .with_editorconfig(options.respect_editorconfig)
+            .with_max_file_size(options.max_file_size)
+            .with_protected_paths(options.protected_paths.clone())
+            .with_trash(options.trash.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        WriteRangeTool::with_normalize_on_write(options.normalize_on_write)
+            .with_editorconfig(options.respect_editorconfig)
+            .with_max_file_size(options.max_file_size)
+            .with_protected_paths(options.protected_paths.clone())
+            .with_trash(options.trash.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        RegexReplaceTool::with_normalize_on_write(options.normalize_on_write)
+            .with_editorconfig(options.respect_editorconfig)
+            .with_max_file_size(options.max_file_size)
+            .with_protected_paths(options.protected_paths.clone())
+            .with_trash(options.trash.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        FormatFileTool::default()
+            .with_max_file_size(options.max_file_size)
+            .with_protected_paths(options.protected_paths.clone())
+            .with_trash(options.trash.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        SyntaxCheckTool::default()
+            .with_max_file_size(options.max_file_size)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        PreviewEditTool::with_normalize_on_write(options.normalize_on_write)
+            .with_editorconfig(options.respect_editorconfig)
+            .with_max_file_size(options.max_file_size)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        HashTool::default()
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        ArchiveListTool::default()
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        DiffTool::default()
+            .with_max_file_size(options.max_file_size)
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        GitBlameTool::default()
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        GitFileHistoryTool::default()
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        GitFilesTool.into(),
+        GitStashTool::default()
+            .with_protected_paths(options.protected_paths.clone())
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        HelpTool.into(),
+        EnvTool::with_show_values(options.env_tool_values).into(),
+        CountLinesTool::default()
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        HotspotsTool::default()
+            .with_allowed_roots(options.allowed_roots.clone())
+            .into(),
+        RunTestsTool.into(),
         TaskOutputTool.into(),
         TaskStopTool.into(),
         TodoReadTool.into(),
         TodoWriteTool.into(),
+        OpenInEditorTool.into(),
     ]
 }
 
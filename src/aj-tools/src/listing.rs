@@ -0,0 +1,120 @@
+//! Post-write confirmation for the file-writing tools
+//! (`confirm_with_listing`).
+//!
+//! When enabled, `write_file` and `edit_file` append
+//! [`write_confirmation`] to their success message: the written file's
+//! size and modification time as read back from disk, and a short
+//! listing of its directory. It saves the model the `ls` it would
+//! otherwise run to check the write landed. Everything here is
+//! best-effort; a metadata or listing failure just leaves that part
+//! out.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::truncate::format_size;
+
+/// Most directory entries listed before the rest are counted.
+const LISTING_ENTRIES_MAX: usize = 30;
+
+/// The confirmation appended after a successful write of `path`, or an
+/// empty string when the file can't be stat'ed.
+pub(crate) fn write_confirmation(path: &Path) -> String {
+    let Ok(metadata) = fs::metadata(path) else {
+        return String::new();
+    };
+    let mut out = format!("\nOn disk: {}", format_len(metadata.len()));
+    if let Ok(modified) = metadata.modified() {
+        let modified: DateTime<Utc> = modified.into();
+        out.push_str(&format!(
+            ", modified {}",
+            modified.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+    }
+    out.push('.');
+    if let Some(dir) = path.parent()
+        && let Some(listing) = list_dir(dir)
+    {
+        out.push_str(&listing);
+    }
+    out
+}
+
+/// `dir`'s entries sorted by name, directories marked with a trailing
+/// `/` and files followed by their size, capped at
+/// [`LISTING_ENTRIES_MAX`].
+fn list_dir(dir: &Path) -> Option<String> {
+    let mut entries: Vec<(String, Option<u64>)> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            match entry.metadata() {
+                Ok(m) if m.is_dir() => (format!("{name}/"), None),
+                Ok(m) => (name, Some(m.len())),
+                Err(_) => (name, None),
+            }
+        })
+        .collect();
+    entries.sort();
+
+    let mut out = format!("\nDirectory {} ({} entries):", dir.display(), entries.len());
+    for (name, size) in entries.iter().take(LISTING_ENTRIES_MAX) {
+        match size {
+            Some(size) => out.push_str(&format!("\n  {name}  {}", format_len(*size))),
+            None => out.push_str(&format!("\n  {name}")),
+        }
+    }
+    if entries.len() > LISTING_ENTRIES_MAX {
+        out.push_str(&format!(
+            "\n  ... and {} more",
+            entries.len() - LISTING_ENTRIES_MAX
+        ));
+    }
+    Some(out)
+}
+
+fn format_len(len: u64) -> String {
+    format_size(usize::try_from(len).unwrap_or(usize::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn confirms_size_and_lists_the_directory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("b.txt"), "x").unwrap();
+        let target = dir.path().join("a.txt");
+        std::fs::write(&target, "hello\n").unwrap();
+
+        let out = write_confirmation(&target);
+        assert!(out.starts_with("\nOn disk: 6B, modified "), "{out}");
+        assert!(
+            out.contains("(3 entries):\n  a.txt  6B\n  b.txt  1B\n  sub/"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn long_directories_are_capped() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..=LISTING_ENTRIES_MAX {
+            std::fs::write(dir.path().join(format!("f{i:02}")), "").unwrap();
+        }
+        let out = write_confirmation(&dir.path().join("f00"));
+        assert!(out.contains("(31 entries)"), "{out}");
+        assert!(out.ends_with("\n  ... and 1 more"), "{out}");
+    }
+
+    #[test]
+    fn missing_file_adds_nothing() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(write_confirmation(&dir.path().join("gone")), "");
+    }
+}
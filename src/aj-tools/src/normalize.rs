@@ -0,0 +1,190 @@
+//! Whitespace normalization for the file-writing tools
+//! (`normalize_on_write`).
+//!
+//! When enabled, `write_file` passes the content it is about to write
+//! through [`normalize_for_write`]: trailing whitespace is trimmed from
+//! every line and the file ends with exactly one newline. Line endings
+//! are kept as they were; the added final newline is `\r\n` when the
+//! content already uses CRLF. Content containing a NUL byte is treated
+//! as binary and left alone, as is empty content.
+//!
+//! The editing tools (`edit_file`, `edit_file_multi`, `write_range`
+//! and `regex_replace`) only normalize the text they splice in, through
+//! [`normalize_inserted`], so lines the edit never touched keep their
+//! whitespace and the diff stays limited to the change.
+
+/// Appended to a tool's success message when normalization changed
+/// what was written, so the model doesn't later match against the
+/// whitespace it sent.
+pub(crate) const NORMALIZED_NOTE: &str =
+    "\nNote: trailing whitespace was trimmed and the final newline normalized on write.";
+
+/// Like [`NORMALIZED_NOTE`], for an edit that only normalized the text
+/// it inserted.
+pub(crate) const INSERTED_NORMALIZED_NOTE: &str =
+    "\nNote: trailing whitespace was trimmed from the inserted lines on write.";
+
+/// Normalize `content` when `enabled`. Returns the content to write
+/// and whether normalization changed it.
+pub fn normalize_for_write(enabled: bool, content: String) -> (String, bool) {
+    if !enabled {
+        return (content, false);
+    }
+    let normalized = normalize_whitespace(&content);
+    let changed = normalized != content;
+    (normalized, changed)
+}
+
+/// Normalize `text` an edit splices into a file when `enabled`:
+/// trailing whitespace is trimmed from every line of it that ends in a
+/// newline, and from its last line too when `whole_lines` says the text
+/// ends at a line end in the file. A last line that runs on into the
+/// rest of an existing line is left alone. Returns the text and whether
+/// normalization changed it.
+pub fn normalize_inserted(enabled: bool, text: &str, whole_lines: bool) -> (String, bool) {
+    if !enabled || text.contains('\0') {
+        return (text.to_string(), false);
+    }
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let (body, ending) = split_line_ending(line);
+        if ending.is_empty() && !whole_lines {
+            out.push_str(body);
+        } else {
+            out.push_str(body.trim_end());
+        }
+        out.push_str(ending);
+    }
+    let changed = out != text;
+    (out, changed)
+}
+
+/// Replace every occurrence of `old` in `content` with `new`, normalized
+/// per occurrence when `enabled` (see [`normalize_inserted`]): its last
+/// line is trimmed too where the occurrence ends a line. Returns the
+/// content and whether normalization changed any replacement.
+pub fn replace_normalized(enabled: bool, content: &str, old: &str, new: &str) -> (String, bool) {
+    if !enabled {
+        return (content.replace(old, new), false);
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut normalized = false;
+    let mut last = 0;
+    for (start, _) in content.match_indices(old) {
+        out.push_str(&content[last..start]);
+        let end = start + old.len();
+        let (text, changed) = normalize_inserted(true, new, ends_line(content, end));
+        normalized |= changed;
+        out.push_str(&text);
+        last = end;
+    }
+    out.push_str(&content[last..]);
+    (out, normalized)
+}
+
+/// Whether text replacing `content` up to byte `end` finishes its line:
+/// a line ending or the end of `content` follows.
+pub(crate) fn ends_line(content: &str, end: usize) -> bool {
+    let rest = &content[end..];
+    rest.is_empty() || rest.starts_with('\n') || rest.starts_with("\r\n")
+}
+
+/// Split a line from `split_inclusive('\n')` into its body and its
+/// line ending (`\r\n`, `\n`, or empty for a last line without one).
+fn split_line_ending(line: &str) -> (&str, &str) {
+    match line.strip_suffix("\r\n") {
+        Some(body) => (body, "\r\n"),
+        None => match line.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (line, ""),
+        },
+    }
+}
+
+/// Trim each line's trailing whitespace and collapse the end of the
+/// text to a single newline.
+fn normalize_whitespace(content: &str) -> String {
+    if content.is_empty() || content.contains('\0') {
+        return content.to_string();
+    }
+    let mut out = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        let (body, ending) = split_line_ending(line);
+        out.push_str(body.trim_end());
+        out.push_str(ending);
+    }
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let end = out.trim_end_matches(['\r', '\n']).len();
+    out.truncate(end);
+    out.push_str(newline);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_lines_and_fixes_the_final_newline() {
+        assert_eq!(normalize_whitespace("a  \nb\t\nc"), "a\nb\nc\n");
+        assert_eq!(normalize_whitespace("a\n\n\n"), "a\n");
+        assert_eq!(normalize_whitespace("a\r\nb  \r\n\r\n"), "a\r\nb\r\n");
+        assert_eq!(normalize_whitespace("  \n"), "\n");
+    }
+
+    #[test]
+    fn inserted_text_keeps_a_run_on_last_line() {
+        assert_eq!(
+            normalize_inserted(true, "a  \nb ", false),
+            ("a\nb ".to_string(), true)
+        );
+        assert_eq!(
+            normalize_inserted(true, "a  \r\nb ", true),
+            ("a\r\nb".to_string(), true)
+        );
+        assert_eq!(
+            normalize_inserted(false, "a  \n", true),
+            ("a  \n".to_string(), false)
+        );
+        assert_eq!(
+            normalize_inserted(true, "x", false),
+            ("x".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn replacements_are_trimmed_where_they_end_a_line() {
+        assert_eq!(
+            replace_normalized(true, "foo\nfoo bar\r\nfoo", "foo", "x  "),
+            ("x\nx   bar\r\nx".to_string(), true)
+        );
+        assert_eq!(
+            replace_normalized(false, "foo\n", "foo", "x  "),
+            ("x  \n".to_string(), false)
+        );
+        assert_eq!(
+            replace_normalized(true, "foo\n", "foo", "x"),
+            ("x\n".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn leaves_empty_and_binary_content_alone() {
+        assert_eq!(normalize_whitespace(""), "");
+        assert_eq!(normalize_whitespace("a \0 "), "a \0 ");
+    }
+
+    #[test]
+    fn disabled_or_clean_content_reports_no_change() {
+        let (out, changed) = normalize_for_write(false, "a  ".to_string());
+        assert_eq!((out.as_str(), changed), ("a  ", false));
+        let (out, changed) = normalize_for_write(true, "a\n".to_string());
+        assert_eq!((out.as_str(), changed), ("a\n", false));
+        let (out, changed) = normalize_for_write(true, "a  ".to_string());
+        assert_eq!((out.as_str(), changed), ("a\n", true));
+    }
+}
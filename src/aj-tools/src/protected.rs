@@ -0,0 +1,149 @@
+//! Paths the file-writing tools refuse to touch (`protected_paths`).
+//!
+//! `write_file`, `edit_file`, `edit_file_multi`, `write_range`,
+//! `regex_replace` and `format_file` call [`ProtectedPaths::check`]
+//! before anything else, so a matching path is refused whatever the
+//! permission mode or edit approval says. `git_stash` checks every
+//! path a `push` would stash away or a `pop` would write back.
+//! There is no move or delete tool; those go through `bash`, which this
+//! guardrail doesn't cover.
+//!
+//! Patterns are globs. One without a `/` matches a file name anywhere
+//! (`.env`, `*.lock`); one with a `/` matches the whole path, relative
+//! to the working directory unless the pattern is absolute
+//! (`.github/**`, `/etc/**`).
+
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::resolve::{canonicalize, normalize};
+
+/// A compiled `protected_paths` list. The default protects nothing.
+#[derive(Clone, Debug, Default)]
+pub struct ProtectedPaths {
+    globs: Vec<(String, GlobMatcher)>,
+}
+
+impl ProtectedPaths {
+    /// Compile `patterns`. An invalid pattern is logged and skipped
+    /// rather than failing startup.
+    pub fn new(patterns: &[String]) -> Self {
+        let globs = patterns
+            .iter()
+            .filter_map(|pattern| {
+                match GlobBuilder::new(pattern).literal_separator(true).build() {
+                    Ok(glob) => Some((pattern.clone(), glob.compile_matcher())),
+                    Err(e) => {
+                        tracing::warn!(%pattern, error = %e, "ignoring invalid protected_paths pattern");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { globs }
+    }
+
+    /// Refuse `path` when it matches a protected pattern. Relative
+    /// paths are resolved against `working_directory`. Both the path as
+    /// written (with `.` and `..` resolved) and its symlink-resolved
+    /// form are checked, so neither `src/../.env` nor a link to a
+    /// protected file slips past.
+    pub(crate) fn check(&self, path: &Path, working_directory: &Path) -> Result<(), String> {
+        if self.globs.is_empty() {
+            return Ok(());
+        }
+        let absolute = working_directory.join(path);
+        let hit = self
+            .matching(&normalize(&absolute), &normalize(working_directory))
+            .or_else(|| self.matching(&canonicalize(&absolute), &canonicalize(working_directory)));
+        match hit {
+            Some(pattern) => Err(format!(
+                "Path '{}' is protected by config (matches protected_paths pattern '{pattern}') and can't be modified.",
+                path.display()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The first pattern matching the resolved `absolute` path.
+    fn matching(&self, absolute: &Path, working_directory: &Path) -> Option<&str> {
+        let relative = absolute.strip_prefix(working_directory).ok();
+        let file_name = absolute.file_name().map(Path::new);
+        self.globs
+            .iter()
+            .find(|(pattern, glob)| {
+                if !pattern.contains('/') {
+                    file_name.is_some_and(|name| glob.is_match(name))
+                } else if Path::new(pattern).is_absolute() {
+                    glob.is_match(absolute)
+                } else {
+                    relative.is_some_and(|rel| glob.is_match(rel))
+                }
+            })
+            .map(|(pattern, _)| pattern.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protected(patterns: &[&str]) -> ProtectedPaths {
+        ProtectedPaths::new(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn matches_names_anywhere_and_paths_from_the_working_directory() {
+        let paths = protected(&[".env", "*.lock", ".github/**", "/etc/**"]);
+        let cwd = Path::new("/repo");
+        for blocked in [
+            "/repo/.env",
+            "/repo/sub/.env",
+            "/repo/Cargo.lock",
+            "/repo/.github/workflows/ci.yml",
+            "/repo/src/../.env",
+            "/etc/hosts",
+        ] {
+            let err = paths.check(Path::new(blocked), cwd).unwrap_err();
+            assert!(err.contains("is protected by config"), "{err}");
+        }
+        for allowed in [
+            "/repo/.env.example",
+            "/repo/src/main.rs",
+            "/repo/sub/.github/x.yml",
+            "/other/.github/x.yml",
+        ] {
+            assert!(paths.check(Path::new(allowed), cwd).is_ok(), "{allowed}");
+        }
+    }
+
+    #[test]
+    fn invalid_patterns_are_skipped() {
+        let paths = protected(&["[", ".env"]);
+        assert!(
+            paths
+                .check(Path::new("/repo/.env"), Path::new("/repo"))
+                .is_err()
+        );
+        assert!(
+            ProtectedPaths::default()
+                .check(Path::new("/repo/.env"), Path::new("/repo"))
+                .is_ok()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_to_protected_files_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = dir.path();
+        std::fs::write(cwd.join(".env"), "SECRET=1").unwrap();
+        std::os::unix::fs::symlink(cwd.join(".env"), cwd.join("config.txt")).unwrap();
+
+        let err = protected(&[".env"])
+            .check(&cwd.join("config.txt"), cwd)
+            .unwrap_err();
+        assert!(err.contains("'.env'"), "{err}");
+    }
+}
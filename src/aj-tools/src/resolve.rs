@@ -0,0 +1,76 @@
+//! Path resolution shared by the path guards ([`crate::roots`],
+//! [`crate::protected`]).
+//!
+//! Tools only require paths to be absolute, which lets `..` and
+//! symlinks point anywhere. [`normalize`] resolves `.` and `..`
+//! lexically; [`canonicalize`] additionally follows symlinks through
+//! the longest prefix that exists, so it also works for a file that
+//! is about to be created.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Resolve `.` and `..` lexically. Symlinks are not followed.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Normalize `path` lexically, then canonicalize its longest existing
+/// prefix and re-append the rest.
+pub(crate) fn canonicalize(path: &Path) -> PathBuf {
+    let normalized = normalize(path);
+    let mut existing = normalized.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest.into_iter().rev().fold(canonical, |acc, c| acc.join(c));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return normalized,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn resolves_dots_and_symlinks_for_new_and_existing_paths() {
+        assert_eq!(
+            normalize(Path::new("/repo/src/../.env")),
+            PathBuf::from("/repo/.env")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("real")).unwrap();
+        assert_eq!(
+            canonicalize(&root.join("real/../real/new/file.txt")),
+            root.join("real/new/file.txt")
+        );
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("real"), root.join("link")).unwrap();
+            assert_eq!(
+                canonicalize(&root.join("link/new.txt")),
+                root.join("real/new.txt")
+            );
+        }
+    }
+}
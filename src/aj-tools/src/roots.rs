@@ -0,0 +1,120 @@
+//! Directories the path-taking tools are confined to (`confine_paths`).
+//!
+//! Every builtin tool that takes a file or directory path calls
+//! [`AllowedRoots::check`] right after validating that the path is
+//! absolute, so a path outside the allowed roots is refused before
+//! anything is read or written. The check resolves `.` and `..` and
+//! follows symlinks through the longest existing prefix of the path,
+//! so neither `/repo/../etc/passwd` nor a link pointing out of the
+//! tree gets past it. A path that doesn't exist yet (a file about to
+//! be created) is checked through its nearest existing ancestor.
+//!
+//! `bash` and the commands it runs are not covered; neither are
+//! `git_files` and `run_tests`, which only ever work in the working
+//! directory, nor `open_in_editor`, which hands the path to the user's
+//! editor without reading it.
+
+use std::path::{Path, PathBuf};
+
+use crate::resolve::canonicalize;
+
+/// A resolved `allowed_roots` list. The default allows every path.
+#[derive(Clone, Debug, Default)]
+pub struct AllowedRoots {
+    roots: Option<Vec<PathBuf>>,
+}
+
+impl AllowedRoots {
+    /// Confine paths to `roots`. Each root is canonicalized up front;
+    /// one that doesn't exist is kept as given (lexically normalized).
+    pub fn new(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut resolved: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            let root = canonicalize(&root);
+            if !resolved.contains(&root) {
+                resolved.push(root);
+            }
+        }
+        Self {
+            roots: Some(resolved),
+        }
+    }
+
+    /// Refuse `path` when it resolves outside every allowed root.
+    /// Relative paths are resolved against `working_directory`.
+    pub(crate) fn check(&self, path: &Path, working_directory: &Path) -> Result<(), String> {
+        let Some(roots) = &self.roots else {
+            return Ok(());
+        };
+        let resolved = canonicalize(&working_directory.join(path));
+        if roots.iter().any(|root| resolved.starts_with(root)) {
+            return Ok(());
+        }
+        let allowed = roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(format!(
+            "Path '{}' is outside the allowed directories ({allowed}). Add a directory to allowed_roots in ~/.aj/config.toml to give tools access to it.",
+            path.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn allows_paths_under_a_root_and_refuses_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let other = dir.path().join("other");
+        fs::create_dir_all(repo.join("src")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("secret.txt"), "x").unwrap();
+        let roots = AllowedRoots::new([repo.clone()]);
+
+        for allowed in [
+            repo.join("src/main.rs"),
+            repo.join("new/dir/file.rs"),
+            repo.join("src/../Cargo.toml"),
+        ] {
+            assert!(
+                roots.check(&allowed, &repo).is_ok(),
+                "{}",
+                allowed.display()
+            );
+        }
+        for refused in [
+            other.join("secret.txt"),
+            repo.join("../other/secret.txt"),
+            repo.join("src/../../other/new.txt"),
+        ] {
+            let err = roots.check(&refused, &repo).unwrap_err();
+            assert!(err.contains("outside the allowed directories"), "{err}");
+        }
+        assert!(
+            AllowedRoots::default()
+                .check(&other.join("secret.txt"), &repo)
+                .is_ok()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_a_root_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let other = dir.path().join("other");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        std::os::unix::fs::symlink(&other, repo.join("link")).unwrap();
+        let roots = AllowedRoots::new([repo.clone()]);
+
+        assert!(roots.check(&repo.join("link/file.txt"), &repo).is_err());
+    }
+}
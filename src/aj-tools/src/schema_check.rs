@@ -0,0 +1,224 @@
+//! Self-check of tool definitions as the model will see them.
+//!
+//! [`check_tool_schemas`] verifies that every tool's `input_schema`
+//! keeps the shape [`aj_agent::tool::derive_schema`] promises — a
+//! root object schema with `properties` and `required`, no `title` or
+//! meta-schema link — and that it is well formed: `required` names
+//! only existing properties, every property is documented, and every
+//! `$ref` resolves. Tool names must be unique and valid for the
+//! providers' function-name rules, and every tool needs a description.
+//!
+//! The builtin catalog is checked by a unit test and by the binary's
+//! hidden `--check-tools` flag, so a schema regression from a tool
+//! change (a lost doc comment, a renamed field left in `required`, a
+//! `schemars` upgrade reintroducing `title`) fails loudly instead of
+//! reaching the model.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use aj_agent::tool::ErasedToolDefinition;
+use serde_json::Value;
+
+/// Longest tool name the providers accept.
+const MAX_NAME_LEN: usize = 64;
+
+/// One thing wrong with one tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaProblem {
+    pub tool: String,
+    pub problem: String,
+}
+
+impl fmt::Display for SchemaProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.tool, self.problem)
+    }
+}
+
+/// Every problem found across `tools`, in tool order. Empty when the
+/// catalog is sound.
+pub fn check_tool_schemas(tools: &[ErasedToolDefinition]) -> Vec<SchemaProblem> {
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    for tool in tools {
+        let mut report = |problem: String| {
+            problems.push(SchemaProblem {
+                tool: tool.name.clone(),
+                problem,
+            });
+        };
+        if !seen.insert(tool.name.as_str()) {
+            report("name is registered more than once".to_string());
+        }
+        if !valid_name(&tool.name) {
+            report(format!(
+                "name must be 1-{MAX_NAME_LEN} ASCII letters, digits, '_' or '-'"
+            ));
+        }
+        if tool.description.trim().is_empty() {
+            report("description is empty".to_string());
+        }
+        check_input_schema(&tool.input_schema, &mut report);
+    }
+    problems
+}
+
+fn valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LEN).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn check_input_schema(schema: &Value, report: &mut impl FnMut(String)) {
+    let Some(root) = schema.as_object() else {
+        report("input_schema is not a JSON object".to_string());
+        return;
+    };
+    if root.get("type").and_then(Value::as_str) != Some("object") {
+        report("input_schema must have \"type\": \"object\"".to_string());
+    }
+    for key in ["title", "$schema"] {
+        if root.contains_key(key) {
+            report(format!("input_schema has a root \"{key}\""));
+        }
+    }
+
+    let properties = match root.get("properties") {
+        Some(Value::Object(properties)) => properties,
+        Some(_) => {
+            report("\"properties\" is not an object".to_string());
+            return;
+        }
+        None => {
+            report("input_schema has no \"properties\"".to_string());
+            return;
+        }
+    };
+    for (name, property) in properties {
+        match property {
+            Value::Object(property) if !property.contains_key("description") => {
+                report(format!("property \"{name}\" has no description"));
+            }
+            Value::Object(_) => {}
+            _ => report(format!("property \"{name}\" is not a schema object")),
+        }
+    }
+
+    match root.get("required") {
+        Some(Value::Array(required)) => {
+            let mut listed = HashSet::new();
+            for entry in required {
+                match entry.as_str() {
+                    Some(name) if !properties.contains_key(name) => {
+                        report(format!("required \"{name}\" is not a property"));
+                    }
+                    Some(name) if !listed.insert(name) => {
+                        report(format!("required \"{name}\" is listed twice"));
+                    }
+                    Some(_) => {}
+                    None => report(format!("required entry {entry} is not a string")),
+                }
+            }
+        }
+        Some(_) => report("\"required\" is not an array".to_string()),
+        None => report("input_schema has no \"required\"".to_string()),
+    }
+
+    let defs = root.get("$defs").and_then(Value::as_object);
+    let mut refs = Vec::new();
+    collect_refs(schema, &mut refs);
+    for reference in refs {
+        let resolved = reference
+            .strip_prefix("#/$defs/")
+            .is_some_and(|name| defs.is_some_and(|defs| defs.contains_key(name)));
+        if !resolved {
+            report(format!("$ref \"{reference}\" does not resolve"));
+        }
+    }
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                refs.push(reference);
+            }
+            for child in map.values() {
+                collect_refs(child, refs);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_refs(item, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuiltinToolOptions, get_builtin_tools};
+    use serde_json::json;
+
+    fn tool(name: &str, input_schema: Value) -> ErasedToolDefinition {
+        let mut tool = get_builtin_tools(&BuiltinToolOptions::default()).remove(0);
+        tool.name = name.to_string();
+        tool.input_schema = input_schema;
+        tool
+    }
+
+    #[test]
+    fn builtin_tool_schemas_pass() {
+        let problems = check_tool_schemas(&get_builtin_tools(&BuiltinToolOptions::default()));
+        let report: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        assert!(report.is_empty(), "{}", report.join("\n"));
+    }
+
+    #[test]
+    fn reports_each_kind_of_problem() {
+        let tools = [
+            tool(
+                "ok",
+                json!({
+                    "type": "object",
+                    "properties": {"path": {"type": "string", "description": "Where."}},
+                    "required": ["path"],
+                }),
+            ),
+            tool(
+                "ok",
+                json!({
+                    "type": "object",
+                    "title": "Input",
+                    "properties": {
+                        "mode": {"$ref": "#/$defs/Mode", "description": "How."},
+                        "count": {"type": "integer"},
+                    },
+                    "required": ["path", "mode", "mode"],
+                }),
+            ),
+            tool("bad name", json!({"type": "object", "properties": {}})),
+        ];
+        let problems: Vec<String> = check_tool_schemas(&tools)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                "ok: name is registered more than once",
+                "ok: input_schema has a root \"title\"",
+                "ok: property \"count\" has no description",
+                "ok: required \"path\" is not a property",
+                "ok: required \"mode\" is listed twice",
+                "ok: $ref \"#/$defs/Mode\" does not resolve",
+                "bad name: name must be 1-64 ASCII letters, digits, '_' or '-'",
+                "bad name: input_schema has no \"required\"",
+            ]
+        );
+    }
+}
@@ -2,18 +2,22 @@
 //!
 //! Provides a no-op [`ToolContext`] implementation so individual
 //! tools can be exercised from CLI bins and integration tests
-//! without standing up a full agent runtime.
+//! without standing up a full agent runtime, plus the git repository
+//! fixtures the git-backed tools' tests share.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::sync::Arc;
 
 use aj_agent::TaskRegistry;
 use aj_agent::bus::EventBus;
 use aj_agent::events::AgentId;
+use aj_agent::output_cache::OutputCache;
 use aj_agent::tool::{
     SpawnMode, SpawnResult, StartedTask, TaskEventSink, TaskKind, TaskOutputSource, TodoItem,
     ToolContext, ToolDetails,
 };
+use tempfile::TempDir;
 use tokio_util::sync::CancellationToken;
 
 /// No-op [`ToolContext`] for exercising new-shape
@@ -39,6 +43,9 @@ pub struct DummyToolContext {
     /// Bus the task event sinks emit on. Fresh (no subscribers) by
     /// default; tests can subscribe to observe task events.
     pub bus: EventBus,
+    /// Backing storage for [`ToolContext::stash_output`] /
+    /// [`ToolContext::stashed_output`].
+    pub output_cache: OutputCache,
 }
 
 impl Default for DummyToolContext {
@@ -49,6 +56,7 @@ impl Default for DummyToolContext {
             cancellation: CancellationToken::new(),
             task_registry: TaskRegistry::default(),
             bus: EventBus::new(),
+            output_cache: OutputCache::default(),
         }
     }
 }
@@ -66,6 +74,14 @@ impl ToolContext for DummyToolContext {
         self.todos = todos;
     }
 
+    fn stash_output(&mut self, output: String) -> String {
+        self.output_cache.insert(output)
+    }
+
+    fn stashed_output(&self, token: &str) -> Option<Arc<str>> {
+        self.output_cache.get(token)
+    }
+
     fn spawn_agent<'a>(
         &'a mut self,
         _task: String,
@@ -113,3 +129,40 @@ impl ToolContext for DummyToolContext {
         StartedTask { id, cancel, events }
     }
 }
+
+/// Run `git` with `args` in `dir`, panicking if it fails.
+pub fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A fresh, empty git repository in a temporary directory. A committer
+/// identity is configured in the repository itself, so commits made by
+/// a test and by the tool under test both succeed.
+pub fn git_repo() -> TempDir {
+    let dir = TempDir::new().expect("create temp dir");
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["config", "user.name", "Ada"]);
+    git(dir.path(), &["config", "user.email", "ada@example.com"]);
+    dir
+}
+
+/// Write each `(path, contents)` of `files` under `dir`, creating parent
+/// directories, and commit them with `message`.
+pub fn git_commit(dir: &Path, files: &[(&str, &str)], message: &str) {
+    for (path, contents) in files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create parent dirs");
+        }
+        std::fs::write(path, contents).expect("write file");
+    }
+    let mut args = vec!["add", "--"];
+    args.extend(files.iter().map(|(path, _)| *path));
+    git(dir, &args);
+    git(dir, &["commit", "-q", "-m", message]);
+}
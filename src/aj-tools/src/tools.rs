@@ -1,10 +1,33 @@
 //! Built-in tools for AJ.
 
 pub mod agent;
+pub mod archive_list;
 pub mod bash;
+pub mod count_lines;
+pub mod diff;
 pub mod edit_file;
 pub mod edit_file_multi;
+pub mod env;
+pub mod format_file;
+pub mod git_blame;
+pub mod git_file_history;
+pub mod git_files;
+pub mod git_stash;
+pub mod hash;
+pub mod help;
+pub mod hotspots;
+pub mod open_in_editor;
+pub mod preview_edit;
 pub mod read_file;
+pub mod read_more;
+pub mod read_multi;
+pub mod regex_replace;
+pub mod run_tests;
+pub mod search_in_file;
+pub mod shell_session;
+pub mod structural_search;
+pub mod syntax_check;
 pub mod task;
 pub mod todo;
 pub mod write_file;
+pub mod write_range;
@@ -195,6 +195,14 @@ mod tests {
 
         fn set_todo_list(&mut self, _todos: Vec<TodoItem>) {}
 
+        fn stash_output(&mut self, output: String) -> String {
+            self.tasks.stash_output(output)
+        }
+
+        fn stashed_output(&self, token: &str) -> Option<Arc<str>> {
+            self.tasks.stashed_output(token)
+        }
+
         fn spawn_agent<'a>(
             &'a mut self,
             task: String,
@@ -255,6 +263,14 @@ mod tests {
 
         fn set_todo_list(&mut self, _todos: Vec<TodoItem>) {}
 
+        fn stash_output(&mut self, output: String) -> String {
+            self.tasks.stash_output(output)
+        }
+
+        fn stashed_output(&self, token: &str) -> Option<Arc<str>> {
+            self.tasks.stashed_output(token)
+        }
+
         fn spawn_agent<'a>(
             &'a mut self,
             _task: String,
@@ -0,0 +1,473 @@
+//! `archive_list` builtin — lists the entries of a zip or tar archive.
+//!
+//! Lets the model answer "what's in this jar?" without extracting
+//! anything to disk. The format is sniffed from the leading bytes, not
+//! the extension: `PK` is a zip (`.jar`, `.war` and `.whl` are zips),
+//! the gzip magic is a gzip-compressed tar, and `ustar` at offset 257
+//! is a plain tar. Anything else is refused with an error naming the
+//! supported formats.
+//!
+//! The listing is one `<size>  <name>` row per entry, directories
+//! marked with a trailing `/`, stopped after [`ENTRIES_MAX`] rows with
+//! a note giving the total. With `extract_entry`, the named entry's
+//! text is returned instead, read through [`ENTRY_MAX_BYTES`] and then
+//! cut to the `read_file` budgets by [`truncate_head`].
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the path (plus the
+//! entry when extracting). Missing paths, corrupt archives, unknown
+//! entries and binary entries come back as recoverable
+//! `is_error: true` outcomes.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use flate2::read::GzDecoder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::roots::AllowedRoots;
+use crate::truncate::{READ_MAX_BYTES, READ_MAX_LINES, format_size, truncate_head};
+
+const DESCRIPTION: &str = r#"
+List the entries of a zip or tar archive, or read one text entry from it, without extracting.
+
+Usage:
+
+- The path parameter must be an absolute path to the archive
+- Supported formats: zip (including .jar, .war, .whl), .tar, and gzip-compressed
+  tar (.tar.gz, .tgz)
+- Without extract_entry, the result lists each entry's size and name
+  (directories end in "/"); long listings are cut off with a count of the rest
+- extract_entry is the full name of one entry exactly as listed; its content is
+  returned as text (binary entries are refused, long content is truncated)
+"#;
+
+/// Most entries listed in one call.
+const ENTRIES_MAX: usize = 1_000;
+/// Most decompressed bytes read from one extracted entry.
+const ENTRY_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZIP_MAGIC: [u8; 2] = *b"PK";
+/// Offset of the `ustar` magic in a POSIX tar header.
+const TAR_MAGIC_OFFSET: usize = 257;
+/// Leading bytes read to sniff the format: through the `ustar` magic.
+const SNIFF_BYTES: u64 = 262;
+
+#[derive(Clone, Default)]
+pub struct ArchiveListTool {
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl ArchiveListTool {
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct ArchiveListInput {
+    /// The absolute path to the archive.
+    pub path: String,
+    /// Name of one entry, exactly as listed, whose text content to
+    /// return instead of the listing.
+    #[serde(default)]
+    pub extract_entry: Option<String>,
+}
+
+impl ToolDefinition for ArchiveListTool {
+    type Input = ArchiveListInput;
+
+    fn name(&self) -> &'static str {
+        "archive_list"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = PathBuf::from(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(&path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let entry = input.extract_entry.clone().filter(|e| !e.is_empty());
+        let summary = match &entry {
+            Some(entry) => format!("{} → {entry}", input.path),
+            None => input.path.clone(),
+        };
+        let result = tokio::task::spawn_blocking(move || match entry {
+            Some(entry) => extract(&path, &entry),
+            None => list(&path),
+        })
+        .await?;
+
+        match result {
+            Ok(body) => Ok(ToolOutcome {
+                content: vec![UserContent::text(body.clone())],
+                details: ToolDetails::Text { summary, body },
+                is_error: false,
+            }),
+            Err(message) => Ok(error_outcome(&summary, message)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl Format {
+    fn as_str(self) -> &'static str {
+        match self {
+            Format::Zip => "zip",
+            Format::Tar => "tar",
+            Format::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// One listed entry.
+struct Entry {
+    name: String,
+    size: u64,
+    is_dir: bool,
+}
+
+/// Sniff the archive format of `path`. Errors are the model-facing
+/// message.
+fn detect(path: &Path) -> Result<Format, String> {
+    let mut file = File::open(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => format!("Path does not exist: {}", path.display()),
+        _ => format!("Failed to open '{}': {e}", path.display()),
+    })?;
+    let mut head = Vec::new();
+    (&mut file)
+        .take(SNIFF_BYTES)
+        .read_to_end(&mut head)
+        .map_err(|e| format!("Failed to read '{}': {e}", path.display()))?;
+    if head.starts_with(&ZIP_MAGIC) {
+        Ok(Format::Zip)
+    } else if head.starts_with(&GZIP_MAGIC) {
+        Ok(Format::TarGz)
+    } else if head.get(TAR_MAGIC_OFFSET..) == Some(b"ustar".as_slice()) {
+        Ok(Format::Tar)
+    } else {
+        Err(format!(
+            "'{}' is not a supported archive (zip, jar, tar, tar.gz)",
+            path.display()
+        ))
+    }
+}
+
+fn corrupt(path: &Path, format: Format, e: impl std::fmt::Display) -> String {
+    format!(
+        "Failed to read '{}' as {}: {e}",
+        path.display(),
+        format.as_str()
+    )
+}
+
+/// Render the listing of `path`, stopping at [`ENTRIES_MAX`] rows.
+fn list(path: &Path) -> Result<String, String> {
+    let format = detect(path)?;
+    let mut entries = Vec::new();
+    let total = for_each_entry(path, format, |entry, _| {
+        if entries.len() < ENTRIES_MAX {
+            entries.push(entry);
+        }
+        Ok(false)
+    })?;
+
+    let noun = if total == 1 { "entry" } else { "entries" };
+    let mut out = format!(
+        "{} ({} archive, {total} {noun})",
+        path.display(),
+        format.as_str()
+    );
+    for entry in &entries {
+        let slash = if entry.is_dir && !entry.name.ends_with('/') {
+            "/"
+        } else {
+            ""
+        };
+        let size = usize::try_from(entry.size).unwrap_or(usize::MAX);
+        let _ = write!(out, "\n{:>10}  {}{slash}", format_size(size), entry.name);
+    }
+    if total > entries.len() {
+        let _ = write!(
+            out,
+            "\n[Listing stopped after {ENTRIES_MAX} of {total} entries.]"
+        );
+    }
+    Ok(out)
+}
+
+/// Return the text of the entry named `name` in `path`.
+fn extract(path: &Path, name: &str) -> Result<String, String> {
+    let format = detect(path)?;
+    let mut bytes = None;
+    for_each_entry(path, format, |entry, reader| {
+        if entry.name != name {
+            return Ok(false);
+        }
+        if entry.is_dir {
+            return Err(format!("'{name}' is a directory entry"));
+        }
+        let mut buf = Vec::new();
+        reader
+            .take(ENTRY_MAX_BYTES)
+            .read_to_end(&mut buf)
+            .map_err(|e| corrupt(path, format, e))?;
+        bytes = Some((buf, entry.size));
+        Ok(true)
+    })?;
+    let Some((bytes, size)) = bytes else {
+        return Err(format!(
+            "No entry named '{name}' in '{}'; call archive_list without extract_entry to see the names",
+            path.display()
+        ));
+    };
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        // The byte cap can split a multi-byte character at the very
+        // end; that is a cut, not binary content.
+        Err(e) if e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+        Err(_) => return Err(format!("Entry '{name}' is binary or not valid UTF-8")),
+    };
+
+    let trunc = truncate_head(&text, READ_MAX_LINES, READ_MAX_BYTES);
+    let mut out = trunc.content;
+    if trunc.truncated || size > ENTRY_MAX_BYTES {
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        let _ = write!(
+            out,
+            "\n\n[Showing the first {} lines of '{name}' ({}).]",
+            trunc.output_lines,
+            format_size(size)
+        );
+    }
+    Ok(out)
+}
+
+/// Walk the entries of `path` in archive order, calling `f` with each
+/// entry and a reader over its content until `f` returns `Ok(true)`.
+/// Returns the number of entries seen.
+fn for_each_entry(
+    path: &Path,
+    format: Format,
+    mut f: impl FnMut(Entry, &mut dyn Read) -> Result<bool, String>,
+) -> Result<usize, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open '{}': {e}", path.display()))?;
+    let mut seen = 0;
+    match format {
+        Format::Zip => {
+            let mut archive = zip::ZipArchive::new(file).map_err(|e| corrupt(path, format, e))?;
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i).map_err(|e| corrupt(path, format, e))?;
+                seen += 1;
+                let entry = Entry {
+                    name: file.name().to_string(),
+                    size: file.size(),
+                    is_dir: file.is_dir(),
+                };
+                if f(entry, &mut file)? {
+                    break;
+                }
+            }
+        }
+        Format::Tar | Format::TarGz => {
+            let reader: Box<dyn Read> = if format == Format::TarGz {
+                Box::new(GzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            let mut archive = tar::Archive::new(reader);
+            let entries = archive.entries().map_err(|e| corrupt(path, format, e))?;
+            for file in entries {
+                let mut file = file.map_err(|e| corrupt(path, format, e))?;
+                seen += 1;
+                let entry = Entry {
+                    name: String::from_utf8_lossy(&file.path_bytes()).into_owned(),
+                    size: file.header().size().unwrap_or(0),
+                    is_dir: file.header().entry_type().is_dir(),
+                };
+                if f(entry, &mut file)? {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(seen)
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(summary: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: summary.to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    async fn run(path: &Path, extract_entry: Option<&str>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        ArchiveListTool::default()
+            .execute(
+                &mut ctx,
+                ArchiveListInput {
+                    path: path.display().to_string(),
+                    extract_entry: extract_entry.map(str::to_string),
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        zip.add_directory("META-INF/", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        for (name, data) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+        let gz = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(gz);
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(u64::try_from(data.len()).unwrap());
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, *data).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn lists_and_extracts_zip_entries() {
+        let dir = TempDir::new().unwrap();
+        let jar = dir.path().join("lib.jar");
+        write_zip(
+            &jar,
+            &[
+                ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n"),
+                ("a/B.class", &[0xca, 0xfe, 0xba, 0xbe, 0xff]),
+            ],
+        );
+
+        let listing = run(&jar, None).await;
+        assert!(!listing.is_error, "{}", text(&listing));
+        let body = text(&listing);
+        assert!(body.contains("(zip archive, 3 entries)"), "{body}");
+        assert!(body.contains("  META-INF/\n"), "{body}");
+        assert!(body.contains("  a/B.class"), "{body}");
+
+        let manifest = run(&jar, Some("META-INF/MANIFEST.MF")).await;
+        assert_eq!(text(&manifest), "Manifest-Version: 1.0\n");
+
+        let binary = run(&jar, Some("a/B.class")).await;
+        assert!(binary.is_error);
+        assert!(text(&binary).contains("binary"), "{}", text(&binary));
+
+        let missing = run(&jar, Some("nope")).await;
+        assert!(missing.is_error);
+        assert!(text(&missing).contains("No entry named 'nope'"));
+    }
+
+    #[tokio::test]
+    async fn lists_tar_gz_and_stops_at_the_entry_cap() {
+        let dir = TempDir::new().unwrap();
+        let tgz = dir.path().join("many.tgz");
+        let names: Vec<String> = (0..ENTRIES_MAX + 5).map(|i| format!("f{i}.txt")).collect();
+        let files: Vec<(&str, &[u8])> = names.iter().map(|n| (n.as_str(), &b"x"[..])).collect();
+        write_tar_gz(&tgz, &files);
+
+        let listing = run(&tgz, None).await;
+        assert!(!listing.is_error, "{}", text(&listing));
+        let body = text(&listing);
+        assert!(body.contains("(tar.gz archive, 1005 entries)"), "{body}");
+        assert!(
+            body.ends_with("[Listing stopped after 1000 of 1005 entries.]"),
+            "{body}"
+        );
+
+        let one = run(&tgz, Some("f1003.txt")).await;
+        assert_eq!(text(&one), "x");
+    }
+
+    #[tokio::test]
+    async fn unsupported_and_corrupt_archives_are_errors() {
+        let dir = TempDir::new().unwrap();
+        let plain = dir.path().join("notes.txt");
+        std::fs::write(&plain, b"just text").unwrap();
+        let outcome = run(&plain, None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("not a supported archive"));
+
+        let broken = dir.path().join("broken.zip");
+        std::fs::write(&broken, b"PK\x03\x04garbage").unwrap();
+        let outcome = run(&broken, None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("as zip"), "{}", text(&outcome));
+
+        let missing = run(&dir.path().join("gone.zip"), None).await;
+        assert!(missing.is_error);
+        assert!(text(&missing).contains("does not exist"));
+    }
+}
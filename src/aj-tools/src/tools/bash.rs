@@ -47,7 +47,14 @@
 //!   the immediate child and leaks any grandchildren the shell forked.
 //!   On Unix we `SIGTERM` the process group, give it a short grace
 //!   period, then escalate to `SIGKILL`; non-Unix builds fall back to
-//!   killing just the immediate child.
+//!   killing just the immediate child. The readers keep what arrived
+//!   before the kill, so a timed-out command still returns its output
+//!   ahead of the timeout line.
+//! - **Escapes.** Captured output is passed through
+//!   [`crate::sanitize::sanitize_terminal_output`] and the child runs
+//!   with `NO_COLOR=1` / `TERM=dumb`, so color codes neither reach the
+//!   model nor get emitted in the first place.
+//!   [`BashTool::with_preserve_ansi`] opts out of both.
 //! - **`Sequential` execution.** `bash` runs arbitrary commands, so it
 //!   runs in `Sequential` mode: a batch containing it serializes
 //!   around any other in-flight tool calls.
@@ -78,7 +85,9 @@ working directory of the agent session.
 
 - There are no permissions checks or sandboxing. You are free to run any command
   you consider reasonable and safe.
-- Commands have a configurable timeout to prevent hanging (default: 30s).
+- Commands have a timeout to prevent hanging. Omit `timeout` to use the
+  session default (30s unless the user configured another); set it for commands
+  you expect to take longer or want cut off sooner.
 - Output is truncated to the last 2000 lines or 50KB per stream (whichever
   fires first). When truncated, the full output is saved to a temp file and
   the marker points at it.
@@ -128,16 +137,56 @@ const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
 /// within its budget, even for a command that ignores `SIGTERM`.
 const KILL_GRACE: Duration = Duration::from_secs(2);
 
+/// Built-in foreground timeout, used when neither the call nor the
+/// config sets one.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
 #[derive(Clone)]
-pub struct BashTool;
+pub struct BashTool {
+    /// Keep ANSI escapes and other terminal-control bytes in the
+    /// captured output instead of sanitising them away, and leave the
+    /// child's color environment alone. Off by default.
+    preserve_ansi: bool,
+    /// Foreground timeout for calls that don't pass `timeout`.
+    default_timeout_secs: u64,
+}
+
+impl Default for BashTool {
+    fn default() -> Self {
+        Self {
+            preserve_ansi: false,
+            default_timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl BashTool {
+    /// Construct with an explicit escape policy. `true` passes output
+    /// through verbatim (after lossy UTF-8 decoding) and skips the
+    /// `NO_COLOR` / `TERM=dumb` hints; see [`decode_stream_output`].
+    pub fn with_preserve_ansi(preserve_ansi: bool) -> Self {
+        Self {
+            preserve_ansi,
+            ..Self::default()
+        }
+    }
+
+    /// Time out calls that don't pass their own `timeout` after
+    /// `secs` seconds. The per-call value always wins.
+    pub fn with_default_timeout(mut self, secs: u64) -> Self {
+        self.default_timeout_secs = secs;
+        self
+    }
+}
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
 pub struct BashInput {
     /// The command to execute in the shell.
     pub command: String,
-    /// Timeout in seconds after which the command will be cancelled (default: 30).
-    #[serde(default = "default_timeout")]
-    pub timeout: u64,
+    /// Timeout in seconds after which the command will be cancelled.
+    /// Omit it to use the session default (30 unless configured).
+    #[serde(default)]
+    pub timeout: Option<u64>,
     /// A description explaining what the command does and why you want to run it.
     pub description: String,
     /// Run the command in the background. The call returns immediately
@@ -148,10 +197,6 @@ pub struct BashInput {
     pub run_in_background: bool,
 }
 
-fn default_timeout() -> u64 {
-    30
-}
-
 impl ToolDefinition for BashTool {
     type Input = BashInput;
 
@@ -177,7 +222,8 @@ impl ToolDefinition for BashTool {
     ) -> Result<ToolOutcome, aj_agent::BoxError> {
         let working_dir = ctx.working_directory();
         let cancellation = ctx.cancellation();
-        let timeout = Duration::from_secs(input.timeout);
+        let timeout_secs = input.timeout.unwrap_or(self.default_timeout_secs);
+        let timeout = Duration::from_secs(timeout_secs);
         let command = input.command.clone();
 
         // Build the child. `process_group(0)` makes the child the
@@ -196,6 +242,12 @@ impl ToolDefinition for BashTool {
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        // Discourage color at the source: escapes are stripped from
+        // the capture anyway, but tools that honor these never emit
+        // them (and some, like progress bars, simplify their output).
+        if !self.preserve_ansi {
+            cmd.env("NO_COLOR", "1").env("TERM", "dumb");
+        }
         #[cfg(unix)]
         {
             cmd.process_group(0);
@@ -228,8 +280,9 @@ impl ToolDefinition for BashTool {
         // surface the path through the structured payload.
         let spill = Arc::new(Mutex::new(SpillState::new()?));
 
-        let stdout_state = Arc::new(Mutex::new(StreamState::new()));
-        let stderr_state = Arc::new(Mutex::new(StreamState::new()));
+        let sanitize = !self.preserve_ansi;
+        let stdout_state = Arc::new(Mutex::new(StreamState::new(sanitize)));
+        let stderr_state = Arc::new(Mutex::new(StreamState::new(sanitize)));
 
         let stdout_reader = tokio::spawn(read_stream(
             stdout,
@@ -370,6 +423,7 @@ impl ToolDefinition for BashTool {
         };
 
         let truncated = stdout_truncation.is_some() || stderr_truncation.is_some();
+        let lossy_streams = lossy_streams(&stdout_state, &stderr_state);
 
         // Persist the spill file iff we actually truncated; otherwise
         // drop it (NamedTempFile's Drop unlinks the file).
@@ -392,9 +446,10 @@ impl ToolDefinition for BashTool {
             &stderr_str,
             stdout_truncation.as_ref(),
             stderr_truncation.as_ref(),
+            &lossy_streams,
             &outcome_kind,
             exit_code,
-            input.timeout,
+            timeout_secs,
             full_output_path.as_deref(),
         );
 
@@ -463,10 +518,14 @@ struct StreamState {
     /// `true` so an empty stream is treated as ending on a (vacuous)
     /// boundary.
     ends_with_newline: bool,
+    /// Whether decoding the tail runs it through
+    /// [`crate::sanitize::sanitize_terminal_output`]. `false` only
+    /// when the tool was built with `preserve_ansi`.
+    sanitize: bool,
 }
 
 impl StreamState {
-    fn new() -> Self {
+    fn new(sanitize: bool) -> Self {
         Self {
             tail: Vec::new(),
             tail_starts_at_boundary: true,
@@ -474,6 +533,7 @@ impl StreamState {
             newlines_seen: 0,
             current_line_bytes: 0,
             ends_with_newline: true,
+            sanitize,
         }
     }
 
@@ -513,6 +573,32 @@ impl StreamState {
         }
     }
 
+    /// Whether the bytes that will be surfaced from the tail are not
+    /// valid UTF-8, i.e. decoding them replaces something with U+FFFD.
+    ///
+    /// Mirrors the finalizer's whole-line policy so a leading partial
+    /// line left by a mid-line trim (which may start mid-character)
+    /// doesn't read as corruption. When the tail holds no newline at
+    /// all, the leading continuation bytes of a split character are
+    /// skipped for the same reason.
+    fn tail_has_invalid_utf8(&self) -> bool {
+        let mut bytes = &self.tail[..];
+        if !self.tail_starts_at_boundary {
+            match bytes.iter().position(|&b| b == b'\n') {
+                Some(idx) => bytes = &bytes[idx + 1..],
+                None => {
+                    let split = bytes
+                        .iter()
+                        .take(3)
+                        .take_while(|&&b| (0x80..0xC0).contains(&b))
+                        .count();
+                    bytes = &bytes[split..];
+                }
+            }
+        }
+        std::str::from_utf8(bytes).is_err()
+    }
+
     fn trim_to(&mut self, target: usize) {
         if self.tail.len() <= target {
             return;
@@ -653,15 +739,16 @@ impl TaskOutputSource for BashTaskOutput {
 /// by a mid-line trim is dropped, except when the tail has no newline
 /// at all (a single huge line stays visible).
 fn tail_snapshot(state: &Arc<Mutex<StreamState>>) -> (String, u64) {
-    let (bytes, at_boundary, total) = {
+    let (bytes, at_boundary, total, sanitize) = {
         let s = state.lock().unwrap();
         (
             s.tail.clone(),
             s.tail_starts_at_boundary,
             s.total_bytes_seen,
+            s.sanitize,
         )
     };
-    let decoded = decode_stream_output(bytes);
+    let decoded = decode_stream_output(bytes, sanitize);
     let text = if at_boundary {
         decoded
     } else {
@@ -787,6 +874,9 @@ async fn drive_background_bash(task: BackgroundBash) {
         body.push('\n');
         body.push_str(&tail);
     }
+    for stream in lossy_streams(&stdout_state, &stderr_state) {
+        push_marker(&mut body, &invalid_utf8_note(stream));
+    }
     if !body.ends_with('\n') {
         body.push('\n');
     }
@@ -830,7 +920,7 @@ fn finalize_stream(state: &StreamState) -> (String, Option<BashStreamTruncation>
     let total_lines = state.total_lines();
     let total_bytes = state.total_bytes_seen;
 
-    let tail_decoded = decode_stream_output(state.tail.clone());
+    let tail_decoded = decode_stream_output(state.tail.clone(), state.sanitize);
 
     let overflowed = total_lines > BASH_MAX_LINES as u64 || total_bytes > BASH_MAX_BYTES as u64;
     if !overflowed {
@@ -902,8 +992,8 @@ fn snapshot_partial(
         || stderr_state.total_bytes_seen > BASH_MAX_BYTES as u64;
     ToolDetails::Bash {
         command: command.to_string(),
-        stdout: decode_stream_output(stdout_data),
-        stderr: decode_stream_output(stderr_data),
+        stdout: decode_stream_output(stdout_data, stdout_state.sanitize),
+        stderr: decode_stream_output(stderr_data, stderr_state.sanitize),
         exit_code: None,
         truncated,
         full_output_path: None,
@@ -916,14 +1006,16 @@ fn snapshot_partial(
 /// Build the wire content the model sees. Per-stream truncation
 /// markers (`[Showing lines X-Y of TOTAL ...]`) are inserted right
 /// after each affected stream's content so the model reads the
-/// elision context next to the truncated text. The trailing
-/// exit-status / cancel / timeout block stays last.
+/// elision context next to the truncated text, followed by an
+/// [`invalid_utf8_note`] for every stream in `lossy_streams`. The
+/// trailing exit-status / cancel / timeout block stays last.
 #[allow(clippy::too_many_arguments)]
 fn build_wire_content(
     stdout: &str,
     stderr: &str,
     stdout_truncation: Option<&BashStreamTruncation>,
     stderr_truncation: Option<&BashStreamTruncation>,
+    lossy_streams: &[&str],
     outcome: &ChildExit,
     exit_code: Option<i32>,
     timeout_secs: u64,
@@ -936,6 +1028,9 @@ fn build_wire_content(
         stderr_truncation,
         full_output_path,
     );
+    for stream in lossy_streams {
+        push_marker(&mut wire, &invalid_utf8_note(stream));
+    }
     match outcome {
         ChildExit::Exited(_) => {
             if let Some(code) = exit_code {
@@ -1057,6 +1152,29 @@ pub fn stream_marker(
     }
 }
 
+/// Names of the streams (`"stdout"`, `"stderr"`) whose surfaced tail
+/// was not valid UTF-8, in that order.
+fn lossy_streams(
+    stdout_state: &Arc<Mutex<StreamState>>,
+    stderr_state: &Arc<Mutex<StreamState>>,
+) -> Vec<&'static str> {
+    let mut streams = Vec::new();
+    if stdout_state.lock().unwrap().tail_has_invalid_utf8() {
+        streams.push("stdout");
+    }
+    if stderr_state.lock().unwrap().tail_has_invalid_utf8() {
+        streams.push("stderr");
+    }
+    streams
+}
+
+/// Note telling the model that `stream` held bytes that aren't valid
+/// UTF-8. [`decode_stream_output`] replaces them with U+FFFD, which
+/// would otherwise silently mangle binary-ish output.
+fn invalid_utf8_note(stream: &str) -> String {
+    format!("[{stream} contained invalid UTF-8; those bytes were replaced with U+FFFD]")
+}
+
 /// Build a recoverable-error outcome for the spawn-failure path.
 /// Surface the failure both as the wire content (so the model can see
 /// what went wrong) and as `stderr` in the structured payload (no
@@ -1080,17 +1198,25 @@ fn spawn_error_outcome(command: &str, error: String) -> ToolOutcome {
 }
 
 /// Decode subprocess output bytes to UTF-8 (lossy) and sanitise them
-/// before they leave the bash tool.
+/// before they leave the bash tool. Invalid sequences become U+FFFD;
+/// the callers that build model-facing text flag that with an
+/// [`invalid_utf8_note`].
 ///
 /// Sanitisation strips ANSI escape sequences, drops carriage returns,
 /// and removes other terminal-control bytes that would either corrupt
 /// the renderer's width math (so the tool-output bubble's right edge
 /// stays flush instead of breaking on overprints / erase-in-line) or
 /// waste tokens in the model's context. See [`crate::sanitize`] for
-/// the exact transform.
-fn decode_stream_output(bytes: Vec<u8>) -> String {
+/// the exact transform. With `sanitize: false` (the tool's
+/// `preserve_ansi` mode) the decoded text is returned verbatim; the
+/// TUI still sanitises it again before rendering.
+fn decode_stream_output(bytes: Vec<u8>, sanitize: bool) -> String {
     let lossy = String::from_utf8_lossy(&bytes);
-    crate::sanitize::sanitize_terminal_output(&lossy)
+    if sanitize {
+        crate::sanitize::sanitize_terminal_output(&lossy)
+    } else {
+        lossy.into_owned()
+    }
 }
 
 /// Terminate the child's whole process group and reap the child.
@@ -1101,7 +1227,7 @@ fn decode_stream_output(bytes: Vec<u8>) -> String {
 /// to an unconditional `SIGKILL`. Returns once the child has been
 /// reaped, so the handle never outlives the call.
 #[cfg(unix)]
-async fn terminate_process_group(child: &mut Child, pgid: i32) {
+pub(crate) async fn terminate_process_group(child: &mut Child, pgid: i32) {
     use nix::sys::signal::{Signal, killpg};
     use nix::unistd::Pid;
 
@@ -1129,7 +1255,7 @@ async fn terminate_process_group(child: &mut Child, pgid: i32) {
 }
 
 #[cfg(not(unix))]
-async fn terminate_process_group(child: &mut Child, _pgid: i32) {
+pub(crate) async fn terminate_process_group(child: &mut Child, _pgid: i32) {
     // Process-group semantics are Unix-only; elsewhere we kill just the
     // immediate child and accept that shell-forked grandchildren may
     // leak.
@@ -1177,6 +1303,14 @@ mod tests {
             self.inner.set_todo_list(todos);
         }
 
+        fn stash_output(&mut self, output: String) -> String {
+            self.inner.stash_output(output)
+        }
+
+        fn stashed_output(&self, token: &str) -> Option<Arc<str>> {
+            self.inner.stashed_output(token)
+        }
+
         fn spawn_agent<'a>(
             &'a mut self,
             task: String,
@@ -1234,7 +1368,10 @@ mod tests {
     /// call.
     #[test]
     fn execution_mode_is_sequential() {
-        assert_eq!(BashTool.execution_mode(), ExecutionMode::Sequential);
+        assert_eq!(
+            BashTool::default().execution_mode(),
+            ExecutionMode::Sequential
+        );
     }
 
     /// Successful command. Wire content carries stdout verbatim;
@@ -1243,12 +1380,12 @@ mod tests {
     #[tokio::test]
     async fn echo_returns_stdout_and_exit_zero() {
         let mut ctx = DummyToolContext::default();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "echo hello".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test echo".to_string(),
                     run_in_background: false,
                 },
@@ -1290,12 +1427,12 @@ mod tests {
     #[tokio::test]
     async fn nonzero_exit_code_is_not_marked_as_error() {
         let mut ctx = DummyToolContext::default();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "echo fail; exit 7".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test failing exit".to_string(),
                     run_in_background: false,
                 },
@@ -1318,17 +1455,94 @@ mod tests {
         }
     }
 
+    /// By default the child is asked not to color its output, and any
+    /// escapes it emits anyway are stripped before the model sees them.
+    #[tokio::test]
+    async fn ansi_escapes_are_stripped_and_color_discouraged() {
+        let mut ctx = DummyToolContext::default();
+        let outcome = BashTool::default()
+            .execute(
+                &mut ctx,
+                BashInput {
+                    command: r"printf '\033[31mred\033[0m %s %s\n' $NO_COLOR $TERM".to_string(),
+                    timeout: Some(30),
+                    description: "test escapes".to_string(),
+                    run_in_background: false,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert_eq!(extract_text(&outcome.content), "red 1 dumb\n");
+    }
+
+    /// `preserve_ansi` keeps the raw escapes and leaves the child's
+    /// color environment untouched.
+    #[tokio::test]
+    async fn preserve_ansi_keeps_escapes() {
+        let mut ctx = DummyToolContext::default();
+        let outcome = BashTool::with_preserve_ansi(true)
+            .execute(
+                &mut ctx,
+                BashInput {
+                    command: r"printf '\033[31mred\033[0m [%s]\n' $NO_COLOR".to_string(),
+                    timeout: Some(30),
+                    description: "test raw escapes".to_string(),
+                    run_in_background: false,
+                },
+            )
+            .await
+            .expect("execute");
+
+        let expected = format!(
+            "\x1b[31mred\x1b[0m [{}]\n",
+            std::env::var("NO_COLOR").unwrap_or_default()
+        );
+        assert_eq!(extract_text(&outcome.content), expected);
+    }
+
+    /// Bytes that aren't valid UTF-8 are still replaced with U+FFFD,
+    /// but the wire content now says so instead of mangling the
+    /// output silently. A clean stream carries no such note.
+    #[tokio::test]
+    async fn invalid_utf8_output_is_flagged() {
+        let mut ctx = DummyToolContext::default();
+        let outcome = BashTool::default()
+            .execute(
+                &mut ctx,
+                BashInput {
+                    command: r"printf 'ok\n\377\376\n'; echo clean >&2".to_string(),
+                    timeout: Some(30),
+                    description: "test invalid utf-8".to_string(),
+                    run_in_background: false,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        assert!(wire.contains('\u{FFFD}'), "wire: {wire:?}");
+        assert!(
+            wire.contains(
+                "[stdout contained invalid UTF-8; those bytes were replaced with U+FFFD]"
+            ),
+            "wire: {wire:?}"
+        );
+        assert!(!wire.contains("[stderr contained"), "wire: {wire:?}");
+    }
+
     /// stderr captures show up under a `STDERR:` header on the wire,
     /// and as a separate field in the structured payload.
     #[tokio::test]
     async fn stderr_is_captured_under_its_own_header() {
         let mut ctx = DummyToolContext::default();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "echo to-stdout; echo to-stderr 1>&2".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test stderr".to_string(),
                     run_in_background: false,
                 },
@@ -1363,12 +1577,12 @@ mod tests {
         // `yes` would be unbounded; bound it with `head -c` so the
         // command terminates naturally. Each "ABCDEFGH\n" is 9 bytes,
         // so 200 KB ≈ 22_756 lines — well over the 2000-line cap too.
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "yes ABCDEFGH | head -c 200000".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test truncation".to_string(),
                     run_in_background: false,
                 },
@@ -1439,12 +1653,12 @@ mod tests {
         // One ~120 KB line with no internal newlines, no trailing
         // newline. Exceeds the 50 KB byte cap; line cap is irrelevant
         // (one line total).
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "head -c 120000 /dev/zero | tr '\\0' 'x'".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test last_line_partial".to_string(),
                     run_in_background: false,
                 },
@@ -1496,12 +1710,12 @@ mod tests {
         });
 
         let start = Instant::now();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "sleep 30".to_string(),
-                    timeout: 60,
+                    timeout: Some(60),
                     description: "test cancellation".to_string(),
                     run_in_background: false,
                 },
@@ -1540,7 +1754,7 @@ mod tests {
         });
 
         let start = Instant::now();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
@@ -1548,7 +1762,7 @@ mod tests {
                     // loop keeps it (and thus the group) alive until the
                     // escalation SIGKILL lands.
                     command: "trap '' TERM; while true; do sleep 0.2; done".to_string(),
-                    timeout: 60,
+                    timeout: Some(60),
                     description: "test sigkill escalation".to_string(),
                     run_in_background: false,
                 },
@@ -1581,12 +1795,12 @@ mod tests {
     async fn timeout_kills_command_and_marks_error() {
         let mut ctx = DummyToolContext::default();
         let start = Instant::now();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "sleep 30".to_string(),
-                    timeout: 1,
+                    timeout: Some(1),
                     description: "test timeout".to_string(),
                     run_in_background: false,
                 },
@@ -1613,18 +1827,78 @@ mod tests {
         );
     }
 
+    /// Whatever the command printed before the timeout comes back
+    /// alongside the timeout error.
+    #[tokio::test]
+    async fn timeout_keeps_output_printed_before_it() {
+        let mut ctx = DummyToolContext::default();
+        let outcome = BashTool::default()
+            .execute(
+                &mut ctx,
+                BashInput {
+                    command: "echo 'FAIL parses dates'; echo oops >&2; sleep 30".to_string(),
+                    timeout: Some(1),
+                    description: "test partial output".to_string(),
+                    run_in_background: false,
+                },
+            )
+            .await
+            .expect("execute");
+        assert!(outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        assert!(wire.contains("FAIL parses dates"), "wire: {wire:?}");
+        assert!(wire.contains("oops"), "wire: {wire:?}");
+        assert!(
+            wire.ends_with("Command timed out after 1 seconds"),
+            "wire: {wire:?}"
+        );
+    }
+
+    /// A call without `timeout` uses the configured default; one that
+    /// passes its own overrides it.
+    #[tokio::test]
+    async fn configured_default_timeout_applies_unless_the_call_sets_one() {
+        let input: BashInput =
+            serde_json::from_value(serde_json::json!({"command": "sleep 30", "description": "d"}))
+                .expect("timeout is optional");
+        assert_eq!(input.timeout, None);
+
+        let tool = BashTool::default().with_default_timeout(1);
+        let mut ctx = DummyToolContext::default();
+        let outcome = tool.execute(&mut ctx, input).await.expect("execute");
+        let wire = extract_text(&outcome.content);
+        assert!(
+            wire.contains("Command timed out after 1 seconds"),
+            "wire: {wire:?}"
+        );
+
+        let outcome = tool
+            .execute(
+                &mut ctx,
+                BashInput {
+                    command: "sleep 2; echo done".to_string(),
+                    timeout: Some(10),
+                    description: "d".to_string(),
+                    run_in_background: false,
+                },
+            )
+            .await
+            .expect("execute");
+        assert!(!outcome.is_error, "{}", extract_text(&outcome.content));
+    }
+
     /// `emit_update` is invoked at least once during execution; the
     /// snapshot carries the same `command` the caller passed, no
     /// structured truncation summary, and an unset exit code.
     #[tokio::test]
     async fn emit_update_fires_during_execution() {
         let (mut ctx, updates) = RecordingCtx::new();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "echo hi; sleep 0.3; echo bye".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test progress".to_string(),
                     run_in_background: false,
                 },
@@ -1672,12 +1946,12 @@ mod tests {
     #[tokio::test]
     async fn missing_binary_surfaces_as_normal_failure() {
         let mut ctx = DummyToolContext::default();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "this-binary-does-not-exist-aj".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test missing binary".to_string(),
                     run_in_background: false,
                 },
@@ -1705,12 +1979,12 @@ mod tests {
             working_directory: dir.path().to_path_buf(),
             ..DummyToolContext::default()
         };
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "pwd".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test cwd".to_string(),
                     run_in_background: false,
                 },
@@ -1788,12 +2062,12 @@ mod tests {
         command: &str,
         timeout: u64,
     ) -> (aj_agent::tool::TaskId, PathBuf) {
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 ctx,
                 BashInput {
                     command: command.to_string(),
-                    timeout,
+                    timeout: Some(timeout),
                     description: "test background".to_string(),
                     run_in_background: true,
                 },
@@ -1842,12 +2116,12 @@ mod tests {
     #[tokio::test]
     async fn background_started_result_carries_id_and_spill_path() {
         let mut ctx = DummyToolContext::default();
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 &mut ctx,
                 BashInput {
                     command: "echo hello; sleep 0.2".to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test background start".to_string(),
                     run_in_background: true,
                 },
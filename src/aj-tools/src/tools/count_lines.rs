@@ -0,0 +1,520 @@
+//! `count_lines` builtin — cloc-style line counts for a file or tree.
+//!
+//! Walks `path` with the [`ignore`] crate, so `.gitignore`, `.ignore`
+//! and hidden-file rules apply the way they do for `git` and `rg`.
+//! Hidden files and directories are skipped unless `hidden` is set;
+//! `.git` itself is never walked. An
+//! optional `include` glob narrows the walk: a pattern without a `/`
+//! matches file names (`*.rs`), one with a `/` matches paths relative to
+//! `path` (`src/**/*.rs`). Binary files (a NUL byte in the first
+//! [`BINARY_SNIFF_BYTES`]) are skipped.
+//!
+//! Files are grouped by language, derived from the extension, and the
+//! result is a fixed-width table of files, lines, blank lines, words
+//! and bytes per language plus a total row. At most [`ROWS_MAX`]
+//! languages get their own row; the rest are folded into `(other)`.
+//! The walk stops after [`FILES_MAX`] files with a note saying so.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the path. The tool
+//! only reads, so it keeps the default parallel execution mode.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use globset::{Glob, GlobMatcher};
+use ignore::WalkBuilder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::roots::AllowedRoots;
+
+const DESCRIPTION: &str = r#"
+Count lines, blank lines, words and bytes per language for a file or directory.
+
+Usage:
+
+- The path parameter must be an absolute path to a file or directory
+- include is an optional glob: "*.rs" matches file names anywhere, while a
+  pattern containing a slash ("src/**/*.rs") matches paths relative to path
+- .gitignore rules are respected and binary files are skipped
+- Hidden files and directories (.github, .env, ...) are skipped by default;
+  set hidden to true to count them too
+- The result is a summary table with one row per language (by file extension)
+  and a total; use git_files or bash to list the individual files
+"#;
+
+/// Most files counted in one call; the walk stops there.
+const FILES_MAX: usize = 50_000;
+/// Most languages given their own table row.
+const ROWS_MAX: usize = 25;
+/// Leading bytes checked for a NUL to decide a file is binary.
+pub(crate) const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Clone, Default)]
+pub struct CountLinesTool {
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl CountLinesTool {
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct CountLinesInput {
+    /// The absolute path to the file or directory to count.
+    pub path: String,
+    /// Glob restricting which files are counted (e.g. `*.rs` or
+    /// `src/**/*.ts`). Counts every non-ignored file when omitted.
+    #[serde(default)]
+    pub include: Option<String>,
+    /// Also count hidden files and directories (names starting with
+    /// `.`). Defaults to false.
+    #[serde(default)]
+    pub hidden: Option<bool>,
+}
+
+impl ToolDefinition for CountLinesTool {
+    type Input = CountLinesInput;
+
+    fn name(&self) -> &'static str {
+        "count_lines"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = PathBuf::from(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(&path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if !path.exists() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path '{}' does not exist", input.path),
+            ));
+        }
+
+        let include = match input.include.as_deref().map(str::trim) {
+            Some(pattern) if !pattern.is_empty() => match Include::new(pattern) {
+                Ok(include) => Some(include),
+                Err(e) => {
+                    return Ok(error_outcome(
+                        &input.path,
+                        format!("Invalid include glob '{pattern}': {e}"),
+                    ));
+                }
+            },
+            _ => None,
+        };
+
+        let hidden = input.hidden.unwrap_or(false);
+        let counted =
+            tokio::task::spawn_blocking(move || count(&path, include.as_ref(), hidden)).await?;
+        let body = render(&counted, &input.path);
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text {
+                summary: PathBuf::from(&input.path).display().to_string(),
+                body,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// The `include` filter, matched against the file name or the path
+/// relative to the walk root depending on whether it contains a `/`.
+/// Shared with `hotspots`.
+pub(crate) struct Include {
+    matcher: GlobMatcher,
+    by_name: bool,
+}
+
+impl Include {
+    pub(crate) fn new(pattern: &str) -> Result<Self, globset::Error> {
+        Ok(Self {
+            matcher: Glob::new(pattern)?.compile_matcher(),
+            by_name: !pattern.contains('/'),
+        })
+    }
+
+    pub(crate) fn matches(&self, root: &Path, path: &Path) -> bool {
+        if self.by_name {
+            path.file_name()
+                .is_some_and(|name| self.matcher.is_match(name))
+        } else {
+            self.matcher
+                .is_match(path.strip_prefix(root).unwrap_or(path))
+        }
+    }
+}
+
+/// Walk `root` honouring ignore files. Hidden entries are skipped
+/// unless `hidden` is set, and `.git` is skipped either way. Shared
+/// with `hotspots` and `structural_search`.
+pub(crate) fn walk(root: &Path, hidden: bool) -> ignore::Walk {
+    WalkBuilder::new(root)
+        .hidden(!hidden)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+}
+
+/// Totals for one language (or for everything).
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+struct Counts {
+    files: usize,
+    lines: usize,
+    blank: usize,
+    words: usize,
+    bytes: usize,
+}
+
+impl Counts {
+    fn add(&mut self, other: &Counts) {
+        self.files += other.files;
+        self.lines += other.lines;
+        self.blank += other.blank;
+        self.words += other.words;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Result of a walk: per-language counts plus what was left out.
+#[derive(Default)]
+struct Counted {
+    by_language: HashMap<String, Counts>,
+    binary_skipped: usize,
+    unreadable: usize,
+    truncated: bool,
+}
+
+fn count(root: &Path, include: Option<&Include>, hidden: bool) -> Counted {
+    let mut counted = Counted::default();
+    let mut files = 0;
+    for entry in walk(root, hidden).flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        if include.is_some_and(|include| !include.matches(root, path)) {
+            continue;
+        }
+        if files == FILES_MAX {
+            counted.truncated = true;
+            break;
+        }
+        files += 1;
+
+        let Ok(bytes) = fs::read(path) else {
+            counted.unreadable += 1;
+            continue;
+        };
+        if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+            counted.binary_skipped += 1;
+            continue;
+        }
+        let file = count_file(&bytes);
+        counted
+            .by_language
+            .entry(language(path))
+            .or_default()
+            .add(&file);
+    }
+    counted
+}
+
+/// Counts for one text file's contents.
+fn count_file(bytes: &[u8]) -> Counts {
+    let text = String::from_utf8_lossy(bytes);
+    let mut counts = Counts {
+        files: 1,
+        bytes: bytes.len(),
+        ..Counts::default()
+    };
+    for line in text.lines() {
+        counts.lines += 1;
+        let words = line.split_whitespace().count();
+        if words == 0 {
+            counts.blank += 1;
+        }
+        counts.words += words;
+    }
+    counts
+}
+
+/// Language name for `path`, by extension (or well-known file name);
+/// unknown extensions are reported as `.ext`.
+fn language(path: &Path) -> String {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    match name {
+        "Makefile" | "makefile" | "GNUmakefile" => return "Makefile".to_string(),
+        "Dockerfile" => return "Dockerfile".to_string(),
+        _ => {}
+    }
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return "(no extension)".to_string();
+    };
+    let language = match ext.to_ascii_lowercase().as_str() {
+        "rs" => "Rust",
+        "py" | "pyi" => "Python",
+        "js" | "mjs" | "cjs" => "JavaScript",
+        "jsx" => "JSX",
+        "ts" | "mts" | "cts" => "TypeScript",
+        "tsx" => "TSX",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "scala" => "Scala",
+        "c" => "C",
+        "h" => "C header",
+        "cc" | "cpp" | "cxx" => "C++",
+        "hh" | "hpp" | "hxx" => "C++ header",
+        "cs" => "C#",
+        "swift" => "Swift",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "lua" => "Lua",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" | "sass" => "Sass",
+        "md" | "markdown" => "Markdown",
+        "json" => "JSON",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "xml" => "XML",
+        "proto" => "Protobuf",
+        "txt" => "Text",
+        _ => return format!(".{ext}"),
+    };
+    language.to_string()
+}
+
+/// Render the per-language table, largest languages by line count
+/// first, under a one-line header.
+fn render(counted: &Counted, path: &str) -> String {
+    let mut rows: Vec<(String, Counts)> = counted
+        .by_language
+        .iter()
+        .map(|(language, counts)| (language.clone(), counts.clone()))
+        .collect();
+    if rows.is_empty() {
+        return format!("No text files to count under '{path}'.");
+    }
+    rows.sort_by(|a, b| b.1.lines.cmp(&a.1.lines).then_with(|| a.0.cmp(&b.0)));
+    if rows.len() > ROWS_MAX {
+        let mut other = Counts::default();
+        for (_, counts) in rows.drain(ROWS_MAX - 1..) {
+            other.add(&counts);
+        }
+        rows.push(("(other)".to_string(), other));
+    }
+    let mut total = Counts::default();
+    for (_, counts) in &rows {
+        total.add(counts);
+    }
+
+    let width = rows
+        .iter()
+        .map(|(language, _)| language.chars().count())
+        .chain(["Language".len(), "Total".len()])
+        .max()
+        .unwrap_or(0);
+    let mut out = format!("Line counts for '{path}':\n\n");
+    let _ = writeln!(
+        out,
+        "{:<width$}  {:>7}  {:>9}  {:>8}  {:>10}  {:>12}",
+        "Language", "Files", "Lines", "Blank", "Words", "Bytes"
+    );
+    let rule = "-".repeat(width + 2 + 7 + 2 + 9 + 2 + 8 + 2 + 10 + 2 + 12);
+    let _ = writeln!(out, "{rule}");
+    for (language, counts) in &rows {
+        write_row(&mut out, language, counts, width);
+    }
+    let _ = writeln!(out, "{rule}");
+    write_row(&mut out, "Total", &total, width);
+
+    let mut notes = Vec::new();
+    if counted.binary_skipped > 0 {
+        notes.push(format!("{} binary files skipped", counted.binary_skipped));
+    }
+    if counted.unreadable > 0 {
+        notes.push(format!("{} unreadable files skipped", counted.unreadable));
+    }
+    if counted.truncated {
+        notes.push(format!(
+            "stopped after {FILES_MAX} files; narrow path or include for a complete count"
+        ));
+    }
+    if !notes.is_empty() {
+        let _ = write!(out, "\n[{}.]\n", notes.join("; "));
+    }
+    out
+}
+
+fn write_row(out: &mut String, language: &str, counts: &Counts, width: usize) {
+    let _ = writeln!(
+        out,
+        "{language:<width$}  {:>7}  {:>9}  {:>8}  {:>10}  {:>12}",
+        counts.files, counts.lines, counts.blank, counts.words, counts.bytes
+    );
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    async fn run(path: &Path, include: Option<&str>) -> ToolOutcome {
+        run_with(path, include, None).await
+    }
+
+    async fn run_with(path: &Path, include: Option<&str>, hidden: Option<bool>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        CountLinesTool::default()
+            .execute(
+                &mut ctx,
+                CountLinesInput {
+                    path: path.display().to_string(),
+                    include: include.map(str::to_string),
+                    hidden,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    /// A temp tree with a `.git` marker so `.gitignore` applies.
+    fn tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("target")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "fn a() {}\n\nfn b() {}\n").unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("README.md"), "# Title\n\nsome words here\n").unwrap();
+        fs::write(dir.path().join("target/out.rs"), "ignored\n").unwrap();
+        fs::write(dir.path().join("logo.png"), b"\x89PNG\0\0\0").unwrap();
+        dir
+    }
+
+    #[test]
+    fn counts_lines_blanks_and_words() {
+        assert_eq!(
+            count_file(b"fn a() {}\n\n  \nlet x = 1;"),
+            Counts {
+                files: 1,
+                lines: 4,
+                blank: 2,
+                words: 7,
+                bytes: 24,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn groups_by_language_and_respects_gitignore() {
+        let dir = tree();
+        let outcome = run(dir.path(), None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+
+        let rust = body
+            .lines()
+            .find(|l| l.starts_with("Rust"))
+            .expect("Rust row");
+        let cells: Vec<&str> = rust.split_whitespace().collect();
+        assert_eq!(cells[1..5], ["2", "4", "1", "9"], "{body}");
+        assert!(body.lines().any(|l| l.starts_with("Markdown")), "{body}");
+        assert!(body.contains("[1 binary files skipped.]"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn include_narrows_by_name_or_relative_path() {
+        let dir = tree();
+        let body = text(&run(dir.path(), Some("*.md")).await);
+        assert!(!body.contains("Rust"), "{body}");
+        assert!(body.contains("Markdown"), "{body}");
+
+        let body = text(&run(dir.path(), Some("src/main.rs")).await);
+        let total = body
+            .lines()
+            .find(|l| l.starts_with("Total"))
+            .expect("total");
+        assert_eq!(total.split_whitespace().nth(2), Some("1"), "{body}");
+
+        let body = text(&run(dir.path(), Some("*.go")).await);
+        assert!(body.starts_with("No text files to count"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn hidden_files_are_counted_only_on_request() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        fs::create_dir_all(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/build.rs"), "a\nb\n").unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".git/hook.rs"), "x\ny\nz\n").unwrap();
+        let total = |body: &str| {
+            body.lines()
+                .find(|l| l.starts_with("Total"))
+                .and_then(|l| l.split_whitespace().nth(2).map(str::to_string))
+        };
+
+        let body = text(&run(dir.path(), None).await);
+        assert_eq!(total(&body).as_deref(), Some("1"), "{body}");
+        let body = text(&run_with(dir.path(), None, Some(false)).await);
+        assert_eq!(total(&body).as_deref(), Some("1"), "{body}");
+
+        let body = text(&run_with(dir.path(), None, Some(true)).await);
+        assert_eq!(total(&body).as_deref(), Some("3"), "{body}");
+    }
+}
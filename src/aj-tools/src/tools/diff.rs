@@ -0,0 +1,560 @@
+//! `diff` builtin — unified diff between two files or two directories.
+//!
+//! Saves the model from reading a generated file and its expected
+//! fixture side by side. Two files are diffed line by line with
+//! `similar`; two directories are walked recursively (regular files
+//! only, like `hash`) and paired by relative path, each pair that
+//! differs contributing its own unified diff. Files present on one
+//! side only are listed, and a pair that isn't valid UTF-8 or is over
+//! `max_file_size` is reported as differing without a diff.
+//!
+//! The result opens with a summary line (changed / added / removed,
+//! with `left` as the old side). A diff over the `read_file` page caps
+//! is cut down and the full text stashed for `read_more` (see
+//! [`ToolContext::stash_output`]). Two files come back with
+//! [`ToolDetails::Diff`] so the user sees the usual diff rendering;
+//! directories come back as [`ToolDetails::Text`]. Missing paths and a
+//! file paired with a directory are recoverable `is_error: true`
+//! outcomes; differences are a normal result.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::file_size::DEFAULT_MAX_FILE_SIZE;
+use crate::roots::AllowedRoots;
+use crate::tools::hash::collect_files;
+use crate::truncate::{READ_MAX_BYTES, READ_MAX_LINES, truncate_head};
+
+const DESCRIPTION: &str = r#"
+Show a unified diff between two files, or between two directories.
+
+Usage:
+
+- left and right must be absolute paths, both files or both directories;
+  left is treated as the old side
+- Directories are compared recursively by relative path: the result lists
+  files only on one side and diffs every file that differs
+- Use this to compare generated output against an expected fixture instead
+  of reading both files
+- A long diff is cut down; the result then names a read_more token for the
+  rest
+"#;
+
+/// Lines of unchanged context around each hunk.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Clone)]
+pub struct DiffTool {
+    /// Files larger than this are compared by content but not diffed;
+    /// `0` disables the cap.
+    max_file_size: u64,
+    /// Directories both paths must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl Default for DiffTool {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            allowed_roots: AllowedRoots::default(),
+        }
+    }
+}
+
+impl DiffTool {
+    /// Replace the size above which files are not diffed (see
+    /// [`crate::file_size`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct DiffInput {
+    /// The absolute path to the old file or directory.
+    pub left: String,
+    /// The absolute path to the new file or directory.
+    pub right: String,
+}
+
+impl ToolDefinition for DiffTool {
+    type Input = DiffInput;
+
+    fn name(&self) -> &'static str {
+        "diff"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let label = format!("{} {}", input.left, input.right);
+        for path in [&input.left, &input.right] {
+            if !Path::new(path).is_absolute() {
+                return Ok(error_outcome(
+                    &label,
+                    format!("Path must be absolute, got: {path}"),
+                ));
+            }
+        }
+        let cwd = ctx.working_directory();
+        for path in [&input.left, &input.right] {
+            if let Err(message) = self.allowed_roots.check(Path::new(path), &cwd) {
+                return Ok(error_outcome(&label, message));
+            }
+        }
+        let left = PathBuf::from(&input.left);
+        let right = PathBuf::from(&input.right);
+        let summary = format!(
+            "{} {}",
+            display_relative(&left, &cwd),
+            display_relative(&right, &cwd)
+        );
+        let max_file_size = self.max_file_size;
+
+        let result =
+            tokio::task::spawn_blocking(move || diff_paths(&left, &right, max_file_size)).await;
+        let comparison = match result {
+            Ok(Ok(comparison)) => comparison,
+            Ok(Err(message)) => return Ok(error_outcome(&label, message)),
+            Err(e) => {
+                return Ok(error_outcome(&label, format!("Diffing failed: {e}")));
+            }
+        };
+
+        let full = comparison.render();
+        let trunc = truncate_head(&full, READ_MAX_LINES, READ_MAX_BYTES);
+        let text = if trunc.truncated {
+            let token = ctx.stash_output(full.clone());
+            format!(
+                "{}\n\n[Diff truncated: showing {} of {} lines. Use read_more with token \
+                 '{token}' and offset={} to continue.]",
+                trunc.content,
+                trunc.output_lines,
+                trunc.total_lines,
+                trunc.output_lines + 1
+            )
+        } else {
+            full
+        };
+
+        let details = match comparison.pair {
+            Some((before, after)) => ToolDetails::Diff {
+                path: summary,
+                before,
+                after,
+            },
+            None => ToolDetails::Text {
+                summary,
+                body: text.clone(),
+            },
+        };
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(text)],
+            details,
+            is_error: false,
+        })
+    }
+}
+
+/// What comparing two paths found, before rendering.
+#[derive(Debug, Default)]
+struct Comparison {
+    /// Relative paths (or, for two files, the right-hand path) that
+    /// differ, in order.
+    changed: Vec<String>,
+    /// Relative paths only under `right`.
+    added: Vec<String>,
+    /// Relative paths only under `left`.
+    removed: Vec<String>,
+    /// Files present in both directories with the same bytes.
+    identical: usize,
+    /// Symlinks and special files skipped during the walk.
+    skipped: usize,
+    /// Concatenated unified diffs of the changed text files, plus
+    /// one-line notes for changed files without a diff.
+    diff: String,
+    /// Both contents when two text files were compared, for the
+    /// structured diff rendering.
+    pair: Option<(String, String)>,
+}
+
+impl Comparison {
+    fn render(&self) -> String {
+        let mut out = if self.changed.is_empty() && self.added.is_empty() && self.removed.is_empty()
+        {
+            "No differences.".to_string()
+        } else {
+            format!(
+                "{} changed, {} added, {} removed",
+                self.changed.len(),
+                self.added.len(),
+                self.removed.len()
+            )
+        };
+        if self.identical > 0 {
+            let _ = write!(out, " ({} identical)", self.identical);
+        }
+        if self.skipped > 0 {
+            let _ = write!(
+                out,
+                "\nSkipped {} symlink(s) or special file(s).",
+                self.skipped
+            );
+        }
+        for rel in &self.removed {
+            let _ = write!(out, "\nOnly in left: {rel}");
+        }
+        for rel in &self.added {
+            let _ = write!(out, "\nOnly in right: {rel}");
+        }
+        if !self.diff.is_empty() {
+            out.push_str("\n\n");
+            out.push_str(self.diff.trim_end());
+        }
+        out
+    }
+}
+
+/// Compare `left` and `right` as two files or two directories. Errors
+/// are the model-facing message.
+fn diff_paths(left: &Path, right: &Path, max_file_size: u64) -> Result<Comparison, String> {
+    let left_meta = stat(left)?;
+    let right_meta = stat(right)?;
+    let mut comparison = Comparison::default();
+    match (left_meta.is_dir(), right_meta.is_dir()) {
+        (false, false) => {
+            let label = right.display().to_string();
+            let pair = diff_file_pair(
+                left,
+                right,
+                &left.display().to_string(),
+                &label,
+                max_file_size,
+                &mut comparison.diff,
+            )?;
+            match pair {
+                FilePair::Identical => {}
+                FilePair::Text { before, after } => {
+                    comparison.changed.push(label);
+                    comparison.pair = Some((before, after));
+                }
+                FilePair::Opaque => comparison.changed.push(label),
+            }
+        }
+        (true, true) => diff_directories(left, right, max_file_size, &mut comparison)?,
+        _ => {
+            return Err(format!(
+                "Cannot diff a file against a directory: '{}' and '{}'",
+                left.display(),
+                right.display()
+            ));
+        }
+    }
+    Ok(comparison)
+}
+
+/// Metadata for a path the caller named; a named symlink is resolved.
+fn stat(path: &Path) -> Result<fs::Metadata, String> {
+    let meta = fs::metadata(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => format!("Path does not exist: {}", path.display()),
+        _ => format!("Failed to stat '{}': {e}", path.display()),
+    })?;
+    if meta.is_dir() || meta.is_file() {
+        Ok(meta)
+    } else {
+        Err(format!(
+            "'{}' is neither a regular file nor a directory",
+            path.display()
+        ))
+    }
+}
+
+fn diff_directories(
+    left: &Path,
+    right: &Path,
+    max_file_size: u64,
+    comparison: &mut Comparison,
+) -> Result<(), String> {
+    let mut left_files = Vec::new();
+    let mut right_files = Vec::new();
+    collect_files(left, left, &mut left_files, &mut comparison.skipped)?;
+    collect_files(right, right, &mut right_files, &mut comparison.skipped)?;
+
+    // Pair by raw relative path; `None` marks a side without the file.
+    let mut paired: BTreeMap<Vec<u8>, (Option<PathBuf>, Option<PathBuf>)> = BTreeMap::new();
+    for (rel, full) in left_files {
+        paired.entry(rel).or_default().0 = Some(full);
+    }
+    for (rel, full) in right_files {
+        paired.entry(rel).or_default().1 = Some(full);
+    }
+
+    for (rel, sides) in paired {
+        let rel = String::from_utf8_lossy(&rel).into_owned();
+        match sides {
+            (Some(l), Some(r)) => {
+                let pair = diff_file_pair(
+                    &l,
+                    &r,
+                    &format!("left/{rel}"),
+                    &format!("right/{rel}"),
+                    max_file_size,
+                    &mut comparison.diff,
+                )?;
+                match pair {
+                    FilePair::Identical => comparison.identical += 1,
+                    FilePair::Text { .. } | FilePair::Opaque => comparison.changed.push(rel),
+                }
+            }
+            (Some(_), None) => comparison.removed.push(rel),
+            (None, Some(_)) => comparison.added.push(rel),
+            (None, None) => {}
+        }
+    }
+    Ok(())
+}
+
+/// How two files on both sides compare.
+enum FilePair {
+    Identical,
+    /// Both sides are text and differ; their diff went into the
+    /// output.
+    Text {
+        before: String,
+        after: String,
+    },
+    /// The files differ but aren't both text under the size cap.
+    Opaque,
+}
+
+/// Compare two files, appending their unified diff (headed by the
+/// given labels) or a one-line note to `out` when they differ.
+fn diff_file_pair(
+    left: &Path,
+    right: &Path,
+    left_label: &str,
+    right_label: &str,
+    max_file_size: u64,
+    out: &mut String,
+) -> Result<FilePair, String> {
+    let read = |path: &Path| {
+        fs::read(path).map_err(|e| format!("Failed to read file '{}': {e}", path.display()))
+    };
+    let too_large = |path: &Path| {
+        max_file_size > 0 && fs::metadata(path).is_ok_and(|m| m.len() > max_file_size)
+    };
+    if too_large(left) || too_large(right) {
+        // Still tell identical from different, just without a diff.
+        if read(left)? == read(right)? {
+            return Ok(FilePair::Identical);
+        }
+        let _ = writeln!(
+            out,
+            "Files {left_label} and {right_label} differ (over the {max_file_size}-byte diff limit)\n"
+        );
+        return Ok(FilePair::Opaque);
+    }
+    let before = read(left)?;
+    let after = read(right)?;
+    if before == after {
+        return Ok(FilePair::Identical);
+    }
+    match (String::from_utf8(before), String::from_utf8(after)) {
+        (Ok(before), Ok(after)) => {
+            let diff = TextDiff::from_lines(&before, &after)
+                .unified_diff()
+                .context_radius(DIFF_CONTEXT_LINES)
+                .header(left_label, right_label)
+                .to_string();
+            out.push_str(&diff);
+            if !diff.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push('\n');
+            Ok(FilePair::Text { before, after })
+        }
+        _ => {
+            let _ = writeln!(out, "Binary files {left_label} and {right_label} differ\n");
+            Ok(FilePair::Opaque)
+        }
+    }
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the path lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+fn error_outcome(label: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: label.to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    async fn diff(ctx: &mut DummyToolContext, left: &Path, right: &Path) -> ToolOutcome {
+        DiffTool::default()
+            .execute(
+                ctx,
+                DiffInput {
+                    left: left.display().to_string(),
+                    right: right.display().to_string(),
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn diffs_two_files() {
+        let dir = TempDir::new().unwrap();
+        let left = dir.path().join("expected.txt");
+        let right = dir.path().join("actual.txt");
+        fs::write(&left, "alpha\nbeta\ngamma\n").unwrap();
+        fs::write(&right, "alpha\nBETA\ngamma\n").unwrap();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = diff(&mut ctx, &left, &right).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        assert!(
+            body.starts_with("1 changed, 0 added, 0 removed\n"),
+            "{body}"
+        );
+        assert!(body.contains("-beta\n+BETA\n"), "{body}");
+        match &outcome.details {
+            ToolDetails::Diff { before, after, .. } => {
+                assert_eq!(before, "alpha\nbeta\ngamma\n");
+                assert_eq!(after, "alpha\nBETA\ngamma\n");
+            }
+            other => panic!("expected Diff details, got {other:?}"),
+        }
+
+        fs::write(&right, "alpha\nbeta\ngamma\n").unwrap();
+        let outcome = diff(&mut ctx, &left, &right).await;
+        assert_eq!(text(&outcome), "No differences.");
+    }
+
+    #[tokio::test]
+    async fn diffs_two_directories_recursively() {
+        let dir = TempDir::new().unwrap();
+        let left = dir.path().join("left");
+        let right = dir.path().join("right");
+        for root in [&left, &right] {
+            fs::create_dir_all(root.join("sub")).unwrap();
+            fs::write(root.join("same.txt"), "same\n").unwrap();
+        }
+        fs::write(left.join("sub/changed.txt"), "one\n").unwrap();
+        fs::write(right.join("sub/changed.txt"), "two\n").unwrap();
+        fs::write(left.join("gone.txt"), "x\n").unwrap();
+        fs::write(right.join("new.txt"), "y\n").unwrap();
+        fs::write(left.join("blob.bin"), [0xff, 0x00]).unwrap();
+        fs::write(right.join("blob.bin"), [0xfe, 0x00]).unwrap();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = diff(&mut ctx, &left, &right).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        assert!(
+            body.starts_with("2 changed, 1 added, 1 removed (1 identical)\n"),
+            "{body}"
+        );
+        assert!(body.contains("Only in left: gone.txt"), "{body}");
+        assert!(body.contains("Only in right: new.txt"), "{body}");
+        assert!(
+            body.contains("--- left/sub/changed.txt\n+++ right/sub/changed.txt\n"),
+            "{body}"
+        );
+        assert!(body.contains("-one\n+two"), "{body}");
+        assert!(
+            body.contains("Binary files left/blob.bin and right/blob.bin differ"),
+            "{body}"
+        );
+        assert!(matches!(outcome.details, ToolDetails::Text { .. }));
+    }
+
+    #[tokio::test]
+    async fn long_diffs_are_cut_down_and_stashed() {
+        let dir = TempDir::new().unwrap();
+        let left = dir.path().join("a.txt");
+        let right = dir.path().join("b.txt");
+        let lines = READ_MAX_LINES + 100;
+        fs::write(&left, "old\n".repeat(lines)).unwrap();
+        fs::write(&right, "new\n".repeat(lines)).unwrap();
+
+        let mut ctx = DummyToolContext::default();
+        let body = text(&diff(&mut ctx, &left, &right).await);
+        let token = body
+            .split("token '")
+            .nth(1)
+            .and_then(|rest| rest.split('\'').next())
+            .expect("read_more token in footer");
+        let full = ctx.stashed_output(token).expect("full diff stashed");
+        assert!(full.lines().count() > READ_MAX_LINES);
+        assert!(body.contains("[Diff truncated: showing 2000 of"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn rejects_mismatched_and_missing_paths() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        fs::write(&file, "x\n").unwrap();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = diff(&mut ctx, &file, dir.path()).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("file against a directory"));
+
+        let outcome = diff(&mut ctx, &file, &dir.path().join("missing")).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("Path does not exist"));
+
+        let outcome = diff(&mut ctx, Path::new("rel.txt"), &file).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("Path must be absolute"));
+    }
+}
@@ -24,6 +24,15 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::editorconfig::{EDITORCONFIG_NOTE, apply_editorconfig};
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fs_retry;
+use crate::listing::write_confirmation;
+use crate::normalize::{INSERTED_NORMALIZED_NOTE, replace_normalized};
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+use crate::trash::Trash;
+
 const DESCRIPTION: &str = r#"
 Edit files by doing exact string replacement.
 
@@ -37,7 +46,90 @@ Usage:
 "#;
 
 #[derive(Clone)]
-pub struct EditFileTool;
+pub struct EditFileTool {
+    /// Trim trailing whitespace from the inserted text before writing;
+    /// see [`crate::normalize`]. Off by default.
+    normalize_on_write: bool,
+    /// Adjust the content to the target's `.editorconfig` before
+    /// writing; see [`crate::editorconfig`]. Off by default.
+    editorconfig: bool,
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Paths refused outright, whatever the content; see
+    /// [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+    /// Where the prior version goes before a write, `None` to keep
+    /// nothing; see [`crate::trash`].
+    trash: Option<Trash>,
+    /// Append the file's size, mtime and a directory listing to the
+    /// result; see [`crate::listing`]. Off by default.
+    confirm_with_listing: bool,
+}
+
+impl EditFileTool {
+    /// Construct with an explicit `normalize_on_write` policy.
+    pub fn with_normalize_on_write(normalize_on_write: bool) -> Self {
+        Self {
+            normalize_on_write,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse to modify paths matching the `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Copy a file's prior version into `trash` before overwriting it.
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Adjust written content to the target's `.editorconfig`.
+    pub fn with_editorconfig(mut self, editorconfig: bool) -> Self {
+        self.editorconfig = editorconfig;
+        self
+    }
+
+    /// Confirm each write with the file's on-disk size and its
+    /// directory's listing.
+    pub fn with_confirm_listing(mut self, confirm_with_listing: bool) -> Self {
+        self.confirm_with_listing = confirm_with_listing;
+        self
+    }
+}
+
+impl Default for EditFileTool {
+    fn default() -> Self {
+        Self {
+            normalize_on_write: false,
+            editorconfig: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: ProtectedPaths::default(),
+            allowed_roots: AllowedRoots::default(),
+            trash: None,
+            confirm_with_listing: false,
+        }
+    }
+}
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
 pub struct EditFileInput {
@@ -77,70 +169,51 @@ impl ToolDefinition for EditFileTool {
         input: Self::Input,
     ) -> Result<ToolOutcome, aj_agent::BoxError> {
         let path = Path::new(&input.path);
-        if !path.is_absolute() {
-            return Ok(error_outcome(
-                &input.path,
-                format!("Path must be absolute, got: {}", input.path),
-            ));
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
         }
-
-        if !path.exists() {
-            return Ok(error_outcome(
-                &input.path,
-                format!("File '{}' does not exist", input.path),
-            ));
+        if let Err(message) = self.protected_paths.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
         }
 
-        let original_content = match fs::read_to_string(path) {
-            Ok(content) => content,
-            Err(e) => {
-                return Ok(error_outcome(
-                    &input.path,
-                    format!("Failed to read file '{}': {}", input.path, e),
-                ));
-            }
+        let PlannedEdit {
+            original_content,
+            new_content,
+            normalized,
+            editorconfig_applied,
+        } = match self.plan(&input) {
+            Ok(plan) => plan,
+            Err(message) => return Ok(error_outcome(&input.path, message)),
         };
 
-        // Count matches to enforce the "exactly one occurrence unless
-        // replace_all" contract before touching the disk. `match_indices`
-        // is non-overlapping, which matches the tool description.
-        let match_count = original_content.matches(&input.old_string).count();
-
-        if match_count == 0 {
-            return Ok(error_outcome(
-                &input.path,
-                format!(
-                    "No occurrences of '{}' found in file '{}'",
-                    input.old_string, input.path
-                ),
-            ));
-        }
+        let display_path = display_relative(path, &ctx.working_directory());
 
-        if match_count > 1 && !input.replace_all {
-            return Ok(error_outcome(
-                &input.path,
-                format!(
-                    "Found {} occurrences of '{}' in file '{}'. Exactly one occurrence is required for safe replacement. Set replace_all to true to replace all occurrences.",
-                    match_count, input.old_string, input.path
-                ),
-            ));
+        if let Some(trash) = &self.trash
+            && let Err(message) = trash.keep(path, &ctx.working_directory())
+        {
+            return Ok(error_outcome(&input.path, message));
         }
 
-        let new_content = original_content.replace(&input.old_string, &input.new_string);
-
-        let display_path = display_relative(path, &ctx.working_directory());
-
-        if let Err(e) = fs::write(path, &new_content) {
+        if let Err(e) = fs_retry::write(path, &new_content).await {
             return Ok(error_outcome(
                 &input.path,
                 format!("Failed to write file '{}': {}", input.path, e),
             ));
         }
 
-        let return_value = format!(
+        let mut return_value = format!(
             "Successfully replaced '{}' with '{}' in file '{}'",
             input.old_string, input.new_string, input.path
         );
+        if normalized {
+            return_value.push_str(INSERTED_NORMALIZED_NOTE);
+        }
+        if editorconfig_applied {
+            return_value.push_str(EDITORCONFIG_NOTE);
+        }
+        if self.confirm_with_listing {
+            return_value.push_str(&write_confirmation(path));
+        }
 
         Ok(ToolOutcome {
             content: vec![UserContent::text(return_value)],
@@ -154,6 +227,77 @@ impl ToolDefinition for EditFileTool {
     }
 }
 
+/// The result of applying an [`EditFileInput`] in memory.
+pub(crate) struct PlannedEdit {
+    pub(crate) original_content: String,
+    pub(crate) new_content: String,
+    /// Whether `normalize_on_write` changed `new_content`.
+    pub(crate) normalized: bool,
+    /// Whether `respect_editorconfig` changed `new_content`.
+    pub(crate) editorconfig_applied: bool,
+}
+
+impl EditFileTool {
+    /// Validate `input` and compute the edited content without touching
+    /// the disk. Shared with `preview_edit`, so a preview reports exactly
+    /// what `edit_file` would write. Errors are model-facing messages.
+    pub(crate) fn plan(&self, input: &EditFileInput) -> Result<PlannedEdit, String> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Err(format!("Path must be absolute, got: {}", input.path));
+        }
+
+        if !path.exists() {
+            return Err(format!("File '{}' does not exist", input.path));
+        }
+
+        check_file_size(
+            path,
+            self.max_file_size,
+            "Edit it with bash (e.g. sed -i) instead.",
+        )?;
+
+        let original_content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file '{}': {}", input.path, e))?;
+
+        // Count matches to enforce the "exactly one occurrence unless
+        // replace_all" contract before touching the disk. `match_indices`
+        // is non-overlapping, which matches the tool description.
+        let match_count = original_content.matches(&input.old_string).count();
+
+        if match_count == 0 {
+            return Err(format!(
+                "No occurrences of '{}' found in file '{}'",
+                input.old_string, input.path
+            ));
+        }
+
+        if match_count > 1 && !input.replace_all {
+            return Err(format!(
+                "Found {} occurrences of '{}' in file '{}'. Exactly one occurrence is required for safe replacement. Set replace_all to true to replace all occurrences.",
+                match_count, input.old_string, input.path
+            ));
+        }
+
+        // Only the replacement is normalized, so lines the edit never
+        // touched keep their whitespace.
+        let (new_content, normalized) = replace_normalized(
+            self.normalize_on_write,
+            &original_content,
+            &input.old_string,
+            &input.new_string,
+        );
+        let (new_content, editorconfig_applied) =
+            apply_editorconfig(self.editorconfig, path, new_content);
+        Ok(PlannedEdit {
+            original_content,
+            new_content,
+            normalized,
+            editorconfig_applied,
+        })
+    }
+}
+
 /// Resolve `path` against `cwd` for display, falling back to the raw
 /// path when stripping fails (e.g. the file lives outside the cwd).
 fn display_relative(path: &Path, cwd: &Path) -> String {
@@ -206,7 +350,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileTool
+        let outcome = EditFileTool::default()
             .execute(
                 &mut ctx,
                 EditFileInput {
@@ -241,6 +385,76 @@ mod tests {
         assert_eq!(on_disk, "alpha BETA gamma\n");
     }
 
+    /// A file larger than `max_file_size` is refused before it's read
+    /// into memory, and left untouched.
+    #[tokio::test]
+    async fn file_over_the_size_cap_is_refused_untouched() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        write!(file, "foo foo foo\n").unwrap();
+        let path = file.path().to_path_buf();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = EditFileTool::default()
+            .with_max_file_size(4)
+            .execute(
+                &mut ctx,
+                EditFileInput {
+                    path: path.display().to_string(),
+                    old_string: "foo".to_string(),
+                    new_string: "bar".to_string(),
+                    replace_all: true,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        assert!(
+            wire.contains("is too large (12 bytes, limit 4)"),
+            "wire: {wire:?}"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "foo foo foo\n");
+    }
+
+    /// A path matching a `protected_paths` pattern is refused before
+    /// the file is read, and left untouched.
+    #[tokio::test]
+    async fn protected_path_is_refused_untouched() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let path = dir.path().join(".env");
+        fs::write(&path, "SECRET=1\n").unwrap();
+
+        let mut ctx = DummyToolContext {
+            working_directory: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let outcome = EditFileTool::default()
+            .with_protected_paths(ProtectedPaths::new(&[
+                "*.lock".to_string(),
+                ".env".to_string(),
+            ]))
+            .execute(
+                &mut ctx,
+                EditFileInput {
+                    path: path.display().to_string(),
+                    old_string: "1".to_string(),
+                    new_string: "2".to_string(),
+                    replace_all: false,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        assert!(
+            wire.contains("is protected by config (matches protected_paths pattern '.env')"),
+            "wire: {wire:?}"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "SECRET=1\n");
+    }
+
     /// `replace_all: true` replaces every occurrence in a single
     /// invocation, even when the count is greater than one.
     #[tokio::test]
@@ -250,7 +464,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileTool
+        let outcome = EditFileTool::default()
             .execute(
                 &mut ctx,
                 EditFileInput {
@@ -281,7 +495,7 @@ mod tests {
     #[tokio::test]
     async fn relative_path_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileTool
+        let outcome = EditFileTool::default()
             .execute(
                 &mut ctx,
                 EditFileInput {
@@ -308,7 +522,7 @@ mod tests {
     #[tokio::test]
     async fn missing_file_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileTool
+        let outcome = EditFileTool::default()
             .execute(
                 &mut ctx,
                 EditFileInput {
@@ -339,7 +553,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileTool
+        let outcome = EditFileTool::default()
             .execute(
                 &mut ctx,
                 EditFileInput {
@@ -374,7 +588,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileTool
+        let outcome = EditFileTool::default()
             .execute(
                 &mut ctx,
                 EditFileInput {
@@ -401,10 +615,70 @@ mod tests {
         assert_eq!(on_disk, "foo foo foo\n");
     }
 
+    /// With `normalize_on_write`, only the inserted text is normalized:
+    /// untouched lines keep their trailing whitespace and no final
+    /// newline is added.
+    #[tokio::test]
+    async fn normalize_on_write_leaves_untouched_lines_alone() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        write!(file, "keep  \nold\nend\t").unwrap();
+        let path = file.path().to_path_buf();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = EditFileTool::with_normalize_on_write(true)
+            .execute(
+                &mut ctx,
+                EditFileInput {
+                    path: path.display().to_string(),
+                    old_string: "old\n".to_string(),
+                    new_string: "new   \nmore\t\n".to_string(),
+                    replace_all: false,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        assert!(extract_text(&outcome.content).contains("inserted lines"));
+        let on_disk = fs::read_to_string(&path).expect("read back");
+        assert_eq!(on_disk, "keep  \nnew\nmore\nend\t");
+    }
+
+    /// A single-line replacement that ends its line loses its trailing
+    /// whitespace; one that runs on into the rest of a line keeps it.
+    #[tokio::test]
+    async fn normalize_on_write_trims_a_single_line_edit() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        write!(file, "a\nfoo\nfoo = 1\n").unwrap();
+        let path = file.path().to_path_buf();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = EditFileTool::with_normalize_on_write(true)
+            .execute(
+                &mut ctx,
+                EditFileInput {
+                    path: path.display().to_string(),
+                    old_string: "foo".to_string(),
+                    new_string: "bar  ".to_string(),
+                    replace_all: true,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        assert!(extract_text(&outcome.content).contains("inserted lines"));
+        let on_disk = fs::read_to_string(&path).expect("read back");
+        assert_eq!(on_disk, "a\nbar\nbar   = 1\n");
+    }
+
     /// Locks in `Sequential` execution mode — the agent's batching
     /// logic relies on this to serialize filesystem mutations.
     #[test]
     fn execution_mode_is_sequential() {
-        assert_eq!(EditFileTool.execution_mode(), ExecutionMode::Sequential);
+        assert_eq!(
+            EditFileTool::default().execution_mode(),
+            ExecutionMode::Sequential
+        );
     }
 }
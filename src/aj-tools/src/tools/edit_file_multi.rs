@@ -7,13 +7,19 @@
 //! file's prior content, `after` is the content after every edit
 //! has been applied. The wire `content` keeps the
 //! `"Successfully applied N edits ..."` summary so the model still
-//! reads a deterministic confirmation.
+//! reads a deterministic confirmation, and follows each edit's line
+//! with a numbered before/after snippet of the lines it touched, so
+//! the sequential edits can be audited one by one.
 //!
 //! Edits run sequentially against an in-memory copy of the file —
 //! each edit's `old_string` is matched against the result of all
-//! prior edits. The single disk write happens only after every edit
-//! has validated, so a failure mid-batch leaves the file untouched:
-//! all edits apply together or none do. That all-or-nothing guarantee
+//! prior edits. Each anchor is also checked against the original
+//! content: one that only exists because an earlier edit created it
+//! is applied but carries a warning in the summary, and one an
+//! earlier edit destroyed fails with an error that says so. The
+//! single disk write happens only after every edit has validated, so
+//! a failure mid-batch leaves the file untouched: all edits apply
+//! together or none do. That all-or-nothing guarantee
 //! is about application order, not durability — the final write itself
 //! is an ordinary in-place `fs::write`, not a crash-atomic replace.
 //!
@@ -36,6 +42,16 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::editorconfig::{EDITORCONFIG_NOTE, apply_editorconfig};
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fs_retry;
+use crate::normalize::{
+    INSERTED_NORMALIZED_NOTE, ends_line, normalize_inserted, replace_normalized,
+};
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+use crate::trash::Trash;
+
 const DESCRIPTION: &str = r#"
 Edit files by doing multiple exact string replacements sequentially.
 
@@ -46,13 +62,85 @@ Usage:
 - Each edit's old_string must match exactly one occurrence in the file at the time it's applied, you can provide a larger string with more context to make it more unique, or use replace_all to replace all occurences
 - If there are zero matches or multiple matches for any edit, the operation will fail
 - If replace_all is set to true for an edit, all occurrences of that edit's old_string will be replaced with new_string
-- Edits are applied sequentially, so each subsequent edit works on the state of the file after the previous edit
+- Edits are applied sequentially, so each subsequent edit works on the state of the file after the previous edit. An edit whose old_string only exists because an earlier edit created it is applied but flagged with a warning; an edit whose old_string an earlier edit destroyed fails
 - Either every edit applies, or — if any edit fails to match — none are written to the file
 - Prefer this tool over edit_file if there are multiple changes to a file that can be batched together in one call to edit_file_multi
 "#;
 
 #[derive(Clone)]
-pub struct EditFileMultiTool;
+pub struct EditFileMultiTool {
+    /// Trim trailing whitespace from the inserted text before writing;
+    /// see [`crate::normalize`]. Off by default.
+    normalize_on_write: bool,
+    /// Adjust the content to the target's `.editorconfig` before
+    /// writing; see [`crate::editorconfig`]. Off by default.
+    editorconfig: bool,
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Paths refused outright, whatever the content; see
+    /// [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+    /// Where the prior version goes before a write, `None` to keep
+    /// nothing; see [`crate::trash`].
+    trash: Option<Trash>,
+}
+
+impl EditFileMultiTool {
+    /// Construct with an explicit `normalize_on_write` policy.
+    pub fn with_normalize_on_write(normalize_on_write: bool) -> Self {
+        Self {
+            normalize_on_write,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse to modify paths matching the `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Copy a file's prior version into `trash` before overwriting it.
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Adjust written content to the target's `.editorconfig`.
+    pub fn with_editorconfig(mut self, editorconfig: bool) -> Self {
+        self.editorconfig = editorconfig;
+        self
+    }
+}
+
+impl Default for EditFileMultiTool {
+    fn default() -> Self {
+        Self {
+            normalize_on_write: false,
+            editorconfig: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: ProtectedPaths::default(),
+            allowed_roots: AllowedRoots::default(),
+            trash: None,
+        }
+    }
+}
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
 pub struct EditOperation {
@@ -105,6 +193,13 @@ impl ToolDefinition for EditFileMultiTool {
             ));
         }
 
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if let Err(message) = self.protected_paths.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
         if !path.exists() {
             return Ok(error_outcome(
                 &input.path,
@@ -112,6 +207,14 @@ impl ToolDefinition for EditFileMultiTool {
             ));
         }
 
+        if let Err(message) = check_file_size(
+            path,
+            self.max_file_size,
+            "Edit it with bash (e.g. sed -i) instead.",
+        ) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
         let original_content = match fs::read_to_string(path) {
             Ok(content) => content,
             Err(e) => {
@@ -130,19 +233,29 @@ impl ToolDefinition for EditFileMultiTool {
         // occurrence" contract.
         let mut content = original_content.clone();
         let mut edit_results = Vec::with_capacity(input.edits.len());
+        let mut normalized = false;
         for (i, edit) in input.edits.iter().enumerate() {
             let match_count = content.matches(&edit.old_string).count();
 
+            // Each edit matches the evolving content, so an anchor can
+            // be destroyed (or created) by an earlier edit in the batch.
+            // Checking against the original content tells the model
+            // which of the two happened instead of a bare "no match".
+            let in_original = original_content.contains(&edit.old_string);
+
             if match_count == 0 {
-                return Ok(error_outcome(
-                    &input.path,
-                    format!(
-                        "Edit #{}: No occurrences of '{}' found in file '{}'",
-                        i + 1,
-                        edit.old_string,
-                        input.path
-                    ),
-                ));
+                let mut error = format!(
+                    "Edit #{}: No occurrences of '{}' found in file '{}'",
+                    i + 1,
+                    edit.old_string,
+                    input.path
+                );
+                if in_original {
+                    error.push_str(
+                        ". It is present in the original file, but an earlier edit in this batch removed or changed it.",
+                    );
+                }
+                return Ok(error_outcome(&input.path, error));
             }
 
             if match_count > 1 && !edit.replace_all {
@@ -158,30 +271,79 @@ impl ToolDefinition for EditFileMultiTool {
                 ));
             }
 
-            content = content.replace(&edit.old_string, &edit.new_string);
-            edit_results.push(format!(
+            // Everything ahead of the first match is untouched by the
+            // replacement, so the same offset anchors the snippet in
+            // both the pre- and post-edit content.
+            let offset = content
+                .find(&edit.old_string)
+                .expect("match_count > 0 guarantees a first match");
+            let (next, trimmed) = replace_normalized(
+                self.normalize_on_write,
+                &content,
+                &edit.old_string,
+                &edit.new_string,
+            );
+            normalized |= trimmed;
+            // The first occurrence's replacement, as written.
+            let (new_string, _) = normalize_inserted(
+                self.normalize_on_write,
+                &edit.new_string,
+                ends_line(&content, offset + edit.old_string.len()),
+            );
+            let mut result = format!(
                 "Edit #{}: replaced '{}' with '{}'",
                 i + 1,
                 edit.old_string,
-                edit.new_string
+                new_string
+            );
+            if match_count > 1 {
+                result.push_str(&format!(" ({match_count} occurrences, first shown)"));
+            }
+            if !in_original {
+                result.push_str(
+                    "\n Warning: this old_string does not appear in the original file; it only matched text created by an earlier edit in this batch.",
+                );
+            }
+            result.push('\n');
+            result.push_str(&edit_snippet(
+                &content,
+                &next,
+                offset,
+                edit.old_string.len(),
+                new_string.len(),
             ));
+            edit_results.push(result);
+            content = next;
         }
 
         let display_path = display_relative(path, &ctx.working_directory());
 
-        if let Err(e) = fs::write(path, &content) {
+        let (content, editorconfig_applied) = apply_editorconfig(self.editorconfig, path, content);
+        if let Some(trash) = &self.trash
+            && let Err(message) = trash.keep(path, &ctx.working_directory())
+        {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if let Err(e) = fs_retry::write(path, &content).await {
             return Ok(error_outcome(
                 &input.path,
                 format!("Failed to write file '{}': {}", input.path, e),
             ));
         }
 
-        let return_value = format!(
+        let mut return_value = format!(
             "Successfully applied {} edits to file '{}':\n{}",
             input.edits.len(),
             input.path,
             edit_results.join("\n")
         );
+        if normalized {
+            return_value.push_str(INSERTED_NORMALIZED_NOTE);
+        }
+        if editorconfig_applied {
+            return_value.push_str(EDITORCONFIG_NOTE);
+        }
 
         Ok(ToolOutcome {
             content: vec![UserContent::text(return_value)],
@@ -195,6 +357,44 @@ impl ToolDefinition for EditFileMultiTool {
     }
 }
 
+/// Lines of surrounding context shown on each side of an edit in the
+/// per-edit snippets of the model-facing summary.
+const SNIPPET_CONTEXT_LINES: usize = 2;
+
+/// Render the before/after snippet for one applied edit: the lines
+/// touched by the replacement at byte `offset` plus
+/// [`SNIPPET_CONTEXT_LINES`] on either side, numbered against each
+/// version of the content. Lets the model audit what each sequential
+/// edit did, which the single combined diff can't attribute.
+fn edit_snippet(
+    before: &str,
+    after: &str,
+    offset: usize,
+    old_len: usize,
+    new_len: usize,
+) -> String {
+    let start = before[..offset].matches('\n').count();
+    let first = start.saturating_sub(SNIPPET_CONTEXT_LINES);
+    let before_end = before[..offset + old_len].matches('\n').count() + SNIPPET_CONTEXT_LINES;
+    let after_end = after[..offset + new_len].matches('\n').count() + SNIPPET_CONTEXT_LINES;
+
+    let numbered = |text: &str, last: usize| -> String {
+        text.lines()
+            .enumerate()
+            .skip(first)
+            .take(last + 1 - first)
+            .map(|(n, line)| format!("  {:>4} | {line}", n + 1))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        " before:\n{}\n after:\n{}",
+        numbered(before, before_end),
+        numbered(after, after_end)
+    )
+}
+
 /// Resolve `path` against `cwd` for display, falling back to the raw
 /// path when stripping fails (e.g. the file lives outside the cwd).
 fn display_relative(path: &Path, cwd: &Path) -> String {
@@ -248,7 +448,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -291,6 +491,56 @@ mod tests {
         assert_eq!(on_disk, "ALPHA beta GAMMA\n");
     }
 
+    /// The wire summary attributes each change to its edit: every
+    /// `Edit #N` line is followed by a numbered before/after snippet
+    /// of the lines that edit touched, with surrounding context.
+    #[tokio::test]
+    async fn summary_includes_per_edit_snippets() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        write!(file, "one\ntwo\nthree\nfour\nfive\nsix\nseven\n").unwrap();
+        let path = file.path().to_path_buf();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = EditFileMultiTool::default()
+            .execute(
+                &mut ctx,
+                EditFileMultiInput {
+                    path: path.display().to_string(),
+                    edits: vec![
+                        EditOperation {
+                            old_string: "two".to_string(),
+                            new_string: "TWO".to_string(),
+                            replace_all: false,
+                        },
+                        EditOperation {
+                            old_string: "six\n".to_string(),
+                            new_string: "six\nsix-and-a-half\n".to_string(),
+                            replace_all: false,
+                        },
+                    ],
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        let (first, second) = wire.split_once("Edit #2").expect("two edit entries");
+
+        // Edit #1 shows line 2 changing, with context on both sides
+        // but nothing from as far down as edit #2.
+        assert!(first.contains("     2 | two"), "wire: {wire:?}");
+        assert!(first.contains("     2 | TWO"), "wire: {wire:?}");
+        assert!(first.contains("     4 | four"), "wire: {wire:?}");
+        assert!(!first.contains("six"), "wire: {wire:?}");
+
+        // Edit #2 is numbered against the content edit #1 produced and
+        // shows the inserted line.
+        assert!(second.contains("     6 | six"), "wire: {wire:?}");
+        assert!(second.contains("     7 | six-and-a-half"), "wire: {wire:?}");
+        assert!(!second.contains("TWO"), "wire: {wire:?}");
+    }
+
     /// Each subsequent edit sees the result of the previous one. Here
     /// the second edit only matches because the first edit produced
     /// the string it's looking for — exercises the "sequential
@@ -302,7 +552,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -332,6 +582,64 @@ mod tests {
             }
             other => panic!("expected Diff details, got {other:?}"),
         }
+
+        // The second anchor only existed post-transformation, which
+        // the summary flags on that edit alone.
+        let wire = extract_text(&outcome.content);
+        let (first, second) = wire.split_once("Edit #2").expect("two edit entries");
+        assert!(!first.contains("Warning"), "wire: {wire:?}");
+        assert!(
+            second.contains("does not appear in the original file"),
+            "wire: {wire:?}"
+        );
+    }
+
+    /// An earlier edit that destroys a later edit's anchor fails the
+    /// batch, and the error explains that the text was in the original
+    /// file rather than reporting a bare "no occurrences".
+    #[tokio::test]
+    async fn anchor_destroyed_by_earlier_edit_is_explained() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        write!(file, "let value = compute();\n").unwrap();
+        let path = file.path().to_path_buf();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = EditFileMultiTool::default()
+            .execute(
+                &mut ctx,
+                EditFileMultiInput {
+                    path: path.display().to_string(),
+                    edits: vec![
+                        EditOperation {
+                            old_string: "let value".to_string(),
+                            new_string: "let result".to_string(),
+                            replace_all: false,
+                        },
+                        EditOperation {
+                            old_string: "value = compute()".to_string(),
+                            new_string: "value = compute_fast()".to_string(),
+                            replace_all: false,
+                        },
+                    ],
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(outcome.is_error);
+        match &outcome.details {
+            ToolDetails::Text { body, .. } => {
+                assert!(body.contains("Edit #2"), "body: {body:?}");
+                assert!(
+                    body.contains("an earlier edit in this batch removed or changed it"),
+                    "body: {body:?}"
+                );
+            }
+            other => panic!("expected Text details, got {other:?}"),
+        }
+
+        let on_disk = fs::read_to_string(&path).expect("read back");
+        assert_eq!(on_disk, "let value = compute();\n");
     }
 
     /// `replace_all: true` on a single edit replaces every occurrence
@@ -343,7 +651,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -373,7 +681,7 @@ mod tests {
     #[tokio::test]
     async fn relative_path_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -402,7 +710,7 @@ mod tests {
     #[tokio::test]
     async fn missing_file_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -437,7 +745,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -484,7 +792,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = EditFileMultiTool
+        let outcome = EditFileMultiTool::default()
             .execute(
                 &mut ctx,
                 EditFileMultiInput {
@@ -517,7 +825,7 @@ mod tests {
     #[test]
     fn execution_mode_is_sequential() {
         assert_eq!(
-            EditFileMultiTool.execution_mode(),
+            EditFileMultiTool::default().execution_mode(),
             ExecutionMode::Sequential
         );
     }
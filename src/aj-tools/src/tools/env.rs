@@ -0,0 +1,239 @@
+//! `env` builtin — which environment variables are set, without
+//! dumping secrets into the transcript.
+//!
+//! Running `env` through bash puts every value (API keys, tokens,
+//! connection strings) into context. This tool answers the narrower
+//! questions the model actually has: is `DATABASE_URL` set, and which
+//! `AWS_*` variables exist. Explicitly named variables report set,
+//! set-but-empty, or not set; a prefix lists matching names only.
+//!
+//! Values of explicitly named variables are included only when the
+//! model asks for them *and* the user enabled `env_tool_values`
+//! ([`EnvTool::with_show_values`]); prefix listings never carry values.
+//! The variables are those of the aj process, which is also what
+//! `bash` commands inherit.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DESCRIPTION: &str = r#"
+Check which environment variables are set without exposing their values.
+
+Usage:
+
+- names lists exact variable names; each is reported as set, set but empty, or
+  not set
+- prefix lists the names of all variables starting with it (e.g. "AWS_");
+  values are never shown for a prefix
+- Pass values: true to also get the values of the named variables. This only
+  works when the user has enabled it; otherwise values stay hidden
+- Prefer this over running env or printenv with bash, which would put secrets
+  into the conversation
+"#;
+
+/// Most names a prefix listing returns.
+const PREFIX_MAX_NAMES: usize = 200;
+
+#[derive(Clone, Default)]
+pub struct EnvTool {
+    /// Whether `values: true` returns the values of named variables.
+    /// Off by default.
+    show_values: bool,
+}
+
+impl EnvTool {
+    /// Construct with an explicit policy for returning values.
+    pub fn with_show_values(show_values: bool) -> Self {
+        Self { show_values }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct EnvInput {
+    /// Exact variable names to check.
+    #[serde(default)]
+    pub names: Option<Vec<String>>,
+    /// List the names of all variables starting with this prefix.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Also return the values of the variables in `names`, if the user
+    /// allows it.
+    #[serde(default)]
+    pub values: bool,
+}
+
+impl ToolDefinition for EnvTool {
+    type Input = EnvInput;
+
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let vars: BTreeMap<String, String> = std::env::vars_os()
+            .map(|(k, v)| {
+                (
+                    k.to_string_lossy().into_owned(),
+                    v.to_string_lossy().into_owned(),
+                )
+            })
+            .collect();
+        Ok(render(&input, self.show_values, &vars))
+    }
+}
+
+/// Answer `input` against the `vars` snapshot.
+fn render(input: &EnvInput, show_values: bool, vars: &BTreeMap<String, String>) -> ToolOutcome {
+    let names = input.names.as_deref().unwrap_or_default();
+    let prefix = input.prefix.as_deref().filter(|p| !p.is_empty());
+    if names.is_empty() && prefix.is_none() {
+        return error_outcome("Pass names, a non-empty prefix, or both.".to_string());
+    }
+
+    let reveal = input.values && show_values;
+    let mut body = String::new();
+    for name in names {
+        let state = match vars.get(name) {
+            Some(value) if reveal => format!("{value:?}"),
+            Some(value) if value.is_empty() => "set (empty)".to_string(),
+            Some(_) => "set".to_string(),
+            None => "not set".to_string(),
+        };
+        let _ = writeln!(body, "{name}: {state}");
+    }
+    if input.values && !show_values && !names.is_empty() {
+        body.push_str(
+            "[Values are hidden: the user has not enabled env_tool_values in ~/.aj/config.toml.]\n",
+        );
+    }
+
+    if let Some(prefix) = prefix {
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        let matching: Vec<&String> = vars.keys().filter(|k| k.starts_with(prefix)).collect();
+        if matching.is_empty() {
+            let _ = writeln!(body, "No variables start with '{prefix}'.");
+        } else {
+            let _ = writeln!(
+                body,
+                "Variables starting with '{prefix}' (values redacted):"
+            );
+            for name in matching.iter().take(PREFIX_MAX_NAMES) {
+                let _ = writeln!(body, "{name}");
+            }
+            if matching.len() > PREFIX_MAX_NAMES {
+                let _ = writeln!(
+                    body,
+                    "[{} more not shown; use a longer prefix.]",
+                    matching.len() - PREFIX_MAX_NAMES
+                );
+            }
+        }
+    }
+
+    let body = body.trim_end().to_string();
+    let mut summary: Vec<String> = names.to_vec();
+    summary.extend(prefix.map(|p| format!("{p}*")));
+    ToolOutcome {
+        content: vec![UserContent::text(body.clone())],
+        details: ToolDetails::Text {
+            summary: summary.join(" "),
+            body,
+        },
+        is_error: false,
+    }
+}
+
+fn error_outcome(error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: "env".to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> BTreeMap<String, String> {
+        [
+            ("DATABASE_URL", "postgres://u:hunter2@db/app"),
+            ("AWS_REGION", "eu-west-1"),
+            ("AWS_SECRET_ACCESS_KEY", "abc123"),
+            ("EMPTY", ""),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    }
+
+    fn input(names: &[&str], prefix: Option<&str>, values: bool) -> EnvInput {
+        EnvInput {
+            names: Some(names.iter().map(|n| n.to_string()).collect()),
+            prefix: prefix.map(str::to_string),
+            values,
+        }
+    }
+
+    fn text(outcome: &ToolOutcome) -> &str {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => &t.text,
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_presence_and_redacts_prefix_listings() {
+        let outcome = render(
+            &input(&["DATABASE_URL", "EMPTY", "MISSING"], Some("AWS_"), false),
+            false,
+            &vars(),
+        );
+        assert!(!outcome.is_error);
+        assert_eq!(
+            text(&outcome),
+            "DATABASE_URL: set\nEMPTY: set (empty)\nMISSING: not set\n\n\
+             Variables starting with 'AWS_' (values redacted):\n\
+             AWS_REGION\nAWS_SECRET_ACCESS_KEY"
+        );
+    }
+
+    #[test]
+    fn values_need_both_the_request_and_the_setting() {
+        let hidden = render(&input(&["DATABASE_URL"], None, true), false, &vars());
+        assert!(!text(&hidden).contains("hunter2"));
+        assert!(text(&hidden).contains("env_tool_values"));
+
+        let shown = render(&input(&["DATABASE_URL"], Some("AWS_"), true), true, &vars());
+        assert!(text(&shown).contains("DATABASE_URL: \"postgres://u:hunter2@db/app\""));
+        assert!(!text(&shown).contains("abc123"));
+    }
+
+    #[test]
+    fn needs_names_or_a_prefix() {
+        let outcome = render(&input(&[], Some(""), false), true, &vars());
+        assert!(outcome.is_error);
+    }
+}
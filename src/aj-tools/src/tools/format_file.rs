@@ -0,0 +1,593 @@
+//! `format_file` builtin — run the project's formatter on one file.
+//!
+//! The formatter is picked by extension: `rustfmt` for `.rs`,
+//! `prettier` for JavaScript and TypeScript, `black` for Python. The
+//! file's content is piped through the formatter's stdin mode rather
+//! than formatted in place, so `rustfmt` doesn't follow `mod`
+//! declarations into other files and the tool sees both sides of the
+//! change. The formatter runs from the file's directory, so it picks up
+//! the project's `rustfmt.toml`, `.prettierrc` or `pyproject.toml` the
+//! same way it would from the command line. `prettier` is taken from
+//! the nearest `node_modules/.bin` before `PATH`.
+//!
+//! Returns a [`ToolOutcome`] whose `details` is [`ToolDetails::Diff`]
+//! and whose wire `content` carries a unified diff of what changed. An
+//! extension without a formatter, or a formatter that isn't installed,
+//! is not an error: the file is left alone and the result says why. A
+//! formatter that rejects the file (usually a syntax error) comes back
+//! as `is_error: true` with its stderr. Like the other file-writing
+//! tools, it honours `protected_paths` and `max_file_size` and runs
+//! `Sequential`.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use aj_agent::tool::{ExecutionMode, ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fs_retry;
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+use crate::tools::run_tests::on_path;
+use crate::trash::Trash;
+
+const DESCRIPTION: &str = r#"
+Format a file with the project's formatter and return the diff of what changed.
+
+Usage:
+
+- The path parameter must be an absolute path
+- The formatter is chosen by extension: rustfmt for .rs, prettier for
+  .js/.jsx/.mjs/.cjs/.ts/.tsx/.mts/.cts, black for .py/.pyi
+- The project's formatter configuration is respected
+- The file is rewritten in place; the result is a unified diff of the change
+- If no formatter is available for the file, it is left unchanged and the
+  result says so
+- Prefer this over running the formatter through bash
+"#;
+
+/// Seconds a formatter may run before it is killed.
+const FORMAT_TIMEOUT_SECS: u64 = 30;
+/// Lines of unchanged context around each hunk of the returned diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+/// Edition passed to `rustfmt` when no `Cargo.toml` above the file
+/// names one.
+const DEFAULT_RUST_EDITION: &str = "2021";
+
+#[derive(Clone)]
+pub struct FormatFileTool {
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Paths refused outright; see [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+    /// Where the prior version goes before a write, `None` to keep
+    /// nothing; see [`crate::trash`].
+    trash: Option<Trash>,
+}
+
+impl FormatFileTool {
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse to modify paths matching the `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Copy a file's prior version into `trash` before overwriting it.
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+}
+
+impl Default for FormatFileTool {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: ProtectedPaths::default(),
+            allowed_roots: AllowedRoots::default(),
+            trash: None,
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct FormatFileInput {
+    /// The absolute path to the file to format.
+    pub path: String,
+}
+
+/// The formatters the tool knows how to drive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Formatter {
+    Rustfmt,
+    Prettier,
+    Black,
+}
+
+impl Formatter {
+    /// The formatter for `path`'s extension, if any.
+    fn for_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension {
+            "rs" => Some(Formatter::Rustfmt),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => {
+                Some(Formatter::Prettier)
+            }
+            "py" | "pyi" => Some(Formatter::Black),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Formatter::Rustfmt => "rustfmt",
+            Formatter::Prettier => "prettier",
+            Formatter::Black => "black",
+        }
+    }
+
+    /// The program to run for `path`, or `None` when it isn't
+    /// installed.
+    fn program(self, path: &Path) -> Option<PathBuf> {
+        if self == Formatter::Prettier {
+            let local = path
+                .ancestors()
+                .skip(1)
+                .map(|dir| dir.join("node_modules/.bin/prettier"))
+                .find(|bin| bin.is_file());
+            if local.is_some() {
+                return local;
+            }
+        }
+        on_path(self.name()).then(|| PathBuf::from(self.name()))
+    }
+
+    /// Arguments that make the formatter read `path`'s content from
+    /// stdin and write the formatted content to stdout.
+    fn args(self, path: &Path) -> Vec<String> {
+        match self {
+            Formatter::Rustfmt => vec![
+                "--emit".to_string(),
+                "stdout".to_string(),
+                "--edition".to_string(),
+                rust_edition(path),
+            ],
+            Formatter::Prettier => vec!["--stdin-filepath".to_string(), path.display().to_string()],
+            Formatter::Black => vec![
+                "--quiet".to_string(),
+                "--stdin-filename".to_string(),
+                path.display().to_string(),
+                "-".to_string(),
+            ],
+        }
+    }
+}
+
+/// The edition from the nearest `Cargo.toml` above `path` that sets
+/// one, so `rustfmt` parses the file the way `cargo fmt` would. A
+/// workspace member that inherits its edition is skipped in favour of
+/// the workspace root.
+pub(crate) fn rust_edition(path: &Path) -> String {
+    static EDITION: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"(?m)^\s*edition\s*=\s*"(\d{4})""#).expect("valid edition regex")
+    });
+    path.ancestors()
+        .skip(1)
+        .filter_map(|dir| std::fs::read_to_string(dir.join("Cargo.toml")).ok())
+        .find_map(|manifest| EDITION.captures(&manifest).map(|caps| caps[1].to_string()))
+        .unwrap_or_else(|| DEFAULT_RUST_EDITION.to_string())
+}
+
+/// What running a formatter produced.
+enum FormatRun {
+    Formatted(String),
+    Failed(String),
+}
+
+/// Pipe `content` through `program`, killing it if it outlives
+/// [`FORMAT_TIMEOUT_SECS`] or the call is cancelled.
+async fn run_formatter(
+    cancellation: CancellationToken,
+    formatter: Formatter,
+    program: &Path,
+    path: &Path,
+    content: &str,
+) -> Result<FormatRun, aj_agent::BoxError> {
+    let name = formatter.name();
+    let mut cmd = Command::new(program);
+    cmd.args(formatter.args(path))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = path.parent() {
+        cmd.current_dir(dir);
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return Ok(FormatRun::Failed(format!("Failed to start {name}: {e}"))),
+    };
+
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let mut stdout = child.stdout.take().expect("stdout was piped");
+    let mut stderr = child.stderr.take().expect("stderr was piped");
+    let input = content.to_string();
+    let feed = tokio::spawn(async move {
+        let _ = stdin.write_all(input.as_bytes()).await;
+    });
+    let drain = async {
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let _ = tokio::join!(stdout.read_to_end(&mut out), stderr.read_to_end(&mut err));
+        let status = child.wait().await;
+        (status, out, err)
+    };
+
+    let (status, out, err) = tokio::select! {
+        result = drain => result,
+        _ = tokio::time::sleep(Duration::from_secs(FORMAT_TIMEOUT_SECS)) => {
+            return Ok(FormatRun::Failed(format!(
+                "{name} timed out after {FORMAT_TIMEOUT_SECS} seconds; the file was left unchanged."
+            )));
+        }
+        _ = cancellation.cancelled() => {
+            return Ok(FormatRun::Failed(format!("{name} was cancelled; the file was left unchanged.")));
+        }
+    };
+    feed.await?;
+
+    let status = status?;
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&err);
+        return Ok(FormatRun::Failed(format!(
+            "{name} failed ({status}); the file was left unchanged:\n{}",
+            stderr.trim_end()
+        )));
+    }
+    match String::from_utf8(out) {
+        Ok(formatted) => Ok(FormatRun::Formatted(formatted)),
+        Err(_) => Ok(FormatRun::Failed(format!(
+            "{name} produced output that is not valid UTF-8; the file was left unchanged."
+        ))),
+    }
+}
+
+impl ToolDefinition for FormatFileTool {
+    type Input = FormatFileInput;
+
+    fn name(&self) -> &'static str {
+        "format_file"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    /// `format_file` rewrites the file, so it runs in `Sequential`
+    /// mode like the other file-writing tools.
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if let Err(message) = self.protected_paths.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if let Err(message) = check_file_size(
+            path,
+            self.max_file_size,
+            "Run the formatter through bash instead.",
+        ) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let original_content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Failed to read file '{}': {e}", input.path),
+                ));
+            }
+        };
+
+        let Some(formatter) = Formatter::for_path(path) else {
+            return Ok(skipped_outcome(
+                &input.path,
+                format!(
+                    "No formatter is known for '{}'; the file was left unchanged. format_file handles .rs (rustfmt), JavaScript/TypeScript (prettier) and .py (black).",
+                    input.path
+                ),
+            ));
+        };
+        let Some(program) = formatter.program(path) else {
+            return Ok(skipped_outcome(
+                &input.path,
+                format!(
+                    "{} is not installed, so '{}' was left unchanged.",
+                    formatter.name(),
+                    input.path
+                ),
+            ));
+        };
+
+        let formatted = match run_formatter(
+            ctx.cancellation(),
+            formatter,
+            &program,
+            path,
+            &original_content,
+        )
+        .await?
+        {
+            FormatRun::Formatted(formatted) => formatted,
+            FormatRun::Failed(message) => return Ok(error_outcome(&input.path, message)),
+        };
+
+        let display_path = display_relative(path, &ctx.working_directory());
+        if formatted == original_content {
+            return Ok(skipped_outcome(
+                &input.path,
+                format!(
+                    "'{}' is already formatted ({}); nothing changed.",
+                    input.path,
+                    formatter.name()
+                ),
+            ));
+        }
+
+        if let Some(trash) = &self.trash
+            && let Err(message) = trash.keep(path, &ctx.working_directory())
+        {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if let Err(e) = fs_retry::write(path, &formatted).await {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Failed to write file '{}': {e}", input.path),
+            ));
+        }
+
+        let diff = TextDiff::from_lines(&original_content, &formatted)
+            .unified_diff()
+            .context_radius(DIFF_CONTEXT_LINES)
+            .header(&display_path, &display_path)
+            .to_string();
+        let return_value = format!(
+            "Formatted '{}' with {}:\n\n{diff}",
+            input.path,
+            formatter.name()
+        );
+
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(return_value)],
+            details: ToolDetails::Diff {
+                path: display_path,
+                before: original_content,
+                after: formatted,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// A successful call that left the file alone, with `message` saying
+/// why.
+fn skipped_outcome(path: &str, message: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(message.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: message,
+        },
+        is_error: false,
+    }
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use std::fs;
+    use tempfile::TempDir;
+
+    async fn format(path: &Path) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        FormatFileTool::default()
+            .execute(
+                &mut ctx,
+                FormatFileInput {
+                    path: path.display().to_string(),
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn picks_the_formatter_by_extension() {
+        for (file, expected) in [
+            ("a.rs", Some(Formatter::Rustfmt)),
+            ("a.tsx", Some(Formatter::Prettier)),
+            ("a.mjs", Some(Formatter::Prettier)),
+            ("a.py", Some(Formatter::Black)),
+            ("a.txt", None),
+            ("Makefile", None),
+        ] {
+            assert_eq!(Formatter::for_path(Path::new(file)), expected, "{file}");
+        }
+    }
+
+    #[test]
+    fn rust_edition_comes_from_the_nearest_manifest_that_sets_one() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace.package]\nedition = \"2024\"\n",
+        )
+        .unwrap();
+        let member = dir.path().join("member");
+        fs::create_dir_all(member.join("src")).unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nedition.workspace = true\n",
+        )
+        .unwrap();
+        assert_eq!(rust_edition(&member.join("src/lib.rs")), "2024");
+
+        let other = TempDir::new().unwrap();
+        assert_eq!(
+            rust_edition(&other.path().join("main.rs")),
+            DEFAULT_RUST_EDITION
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_extension_is_left_unchanged() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "  hello\n").unwrap();
+
+        let outcome = format(&path).await;
+        assert!(!outcome.is_error);
+        assert!(text(&outcome).contains("No formatter is known"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "  hello\n");
+    }
+
+    #[tokio::test]
+    async fn file_over_the_size_cap_is_refused_untouched() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("main.rs");
+        fs::write(&path, "fn main(){let x=1;}\n").unwrap();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = FormatFileTool::default()
+            .with_max_file_size(4)
+            .execute(
+                &mut ctx,
+                FormatFileInput {
+                    path: path.display().to_string(),
+                },
+            )
+            .await
+            .expect("execute");
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("is too large"),
+            "{}",
+            text(&outcome)
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn main(){let x=1;}\n");
+    }
+
+    #[tokio::test]
+    async fn formats_rust_and_returns_the_diff() {
+        if !on_path("rustfmt") {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "fn main(){let x=1;}\n").unwrap();
+
+        let outcome = format(&path).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let after = fs::read_to_string(&path).unwrap();
+        assert_eq!(after, "fn main() {\n    let x = 1;\n}\n");
+        assert!(
+            text(&outcome).contains("+    let x = 1;"),
+            "{}",
+            text(&outcome)
+        );
+
+        let outcome = format(&path).await;
+        assert!(!outcome.is_error);
+        assert!(text(&outcome).contains("already formatted"));
+    }
+
+    #[tokio::test]
+    async fn syntax_errors_leave_the_file_alone() {
+        if !on_path("rustfmt") {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "fn main( {\n").unwrap();
+
+        let outcome = format(&path).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("rustfmt failed"),
+            "{}",
+            text(&outcome)
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn main( {\n");
+    }
+}
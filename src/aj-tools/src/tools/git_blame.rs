@@ -0,0 +1,403 @@
+//! `git_blame` builtin — who last changed each line of a range, and
+//! when.
+//!
+//! Runs `git blame --porcelain` scoped to the requested lines and
+//! renders one row per line: short commit hash, author date, author,
+//! line number, and the line itself. A range is capped at
+//! [`BLAME_MAX_LINES`] lines and each line's text at
+//! [`BLAME_MAX_LINE_CHARS`] characters, so blaming a large file stays
+//! bounded; a footer tells the model how to continue when the cap
+//! bit. Lines not yet committed show as `uncommitted`.
+//!
+//! Returns [`ToolDetails::Text`] with the display path (and range) as
+//! the `summary`. Files outside a repository or unknown to git come
+//! back as recoverable `is_error: true` outcomes.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use chrono::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::git::{clip, run_git, split_file_path};
+use crate::roots::AllowedRoots;
+
+const DESCRIPTION: &str = r#"
+Show who last changed each line of a file, and in which commit (git blame).
+
+Usage:
+
+- The path parameter must be an absolute path to a file tracked by git
+- start_line / end_line (1-based, inclusive) limit the range; prefer a focused
+  range around the code you are investigating
+- At most 500 lines are blamed per call; the result says where to continue
+- Each row is: commit, date, author, line number, line. Follow up with bash
+  (git show <commit>) to read a commit
+"#;
+
+/// Most lines blamed in one call.
+const BLAME_MAX_LINES: usize = 500;
+
+/// Per-line character cap for the blamed text.
+const BLAME_MAX_LINE_CHARS: usize = 200;
+
+/// Author names are clipped to this many characters to keep rows aligned.
+const AUTHOR_MAX_CHARS: usize = 20;
+
+#[derive(Clone, Default)]
+pub struct GitBlameTool {
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl GitBlameTool {
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct GitBlameInput {
+    /// The absolute path to the file to blame.
+    pub path: String,
+    /// First line to blame (1-based). Defaults to 1.
+    #[serde(default)]
+    pub start_line: Option<usize>,
+    /// Last line to blame (1-based, inclusive). Defaults to the end of
+    /// the file, subject to the per-call line cap.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+}
+
+impl ToolDefinition for GitBlameTool {
+    type Input = GitBlameInput;
+
+    fn name(&self) -> &'static str {
+        "git_blame"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        let (dir, name) = match split_file_path(path) {
+            Ok(parts) => parts,
+            Err(message) => return Ok(error_outcome(&input.path, message)),
+        };
+
+        let start = input.start_line.unwrap_or(1);
+        if start == 0 {
+            return Ok(error_outcome(
+                &input.path,
+                "start_line is 1-based; use 1 for the first line".to_string(),
+            ));
+        }
+        if let Some(end) = input.end_line
+            && end < start
+        {
+            return Ok(error_outcome(
+                &input.path,
+                format!("end_line ({end}) is before start_line ({start})"),
+            ));
+        }
+        let line_count = match std::fs::read(path) {
+            Ok(bytes) => count_lines(&bytes),
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Failed to read file '{}': {e}", input.path),
+                ));
+            }
+        };
+        if start > line_count {
+            return Ok(error_outcome(
+                &input.path,
+                format!("start_line {start} is past the end of the file ({line_count} lines)"),
+            ));
+        }
+        let wanted_end = input.end_line.unwrap_or(line_count).min(line_count);
+        let end = wanted_end.min(start + BLAME_MAX_LINES - 1);
+
+        let range = format!("{start},{end}");
+        let porcelain =
+            match run_git(dir, &["blame", "--porcelain", "-L", &range, "--", name]).await {
+                Ok(out) => out,
+                Err(message) if message.contains("no such path") => {
+                    return Ok(error_outcome(
+                        &input.path,
+                        format!(
+                            "'{}' is not tracked by git (no committed history)",
+                            input.path
+                        ),
+                    ));
+                }
+                Err(message) => return Ok(error_outcome(&input.path, message)),
+            };
+
+        let mut body = render_blame(&parse_porcelain(&porcelain));
+        if end < wanted_end {
+            let _ = write!(
+                body,
+                "\n[Showing lines {start}-{end} of {wanted_end}. Use start_line={} to continue.]",
+                end + 1
+            );
+        }
+
+        let display = display_relative(path, &ctx.working_directory());
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text {
+                summary: format!("{display}:{start}-{end}"),
+                body,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// One blamed line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlameLine {
+    commit: String,
+    author: String,
+    /// Unix seconds of the authoring time.
+    author_time: i64,
+    line: usize,
+    text: String,
+}
+
+/// Parse `git blame --porcelain` output. Commit metadata only appears
+/// on a commit's first line, so it is remembered per hash.
+fn parse_porcelain(out: &str) -> Vec<BlameLine> {
+    let mut meta: HashMap<String, (String, i64)> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+    for row in out.lines() {
+        if let Some(text) = row.strip_prefix('\t') {
+            if let Some((commit, line)) = current.take() {
+                let (author, author_time) = meta.get(&commit).cloned().unwrap_or_default();
+                lines.push(BlameLine {
+                    commit,
+                    author,
+                    author_time,
+                    line,
+                    text: text.to_string(),
+                });
+            }
+        } else if current.is_none() {
+            // Header: `<hash> <orig-line> <final-line> [<group-size>]`.
+            let mut parts = row.split(' ');
+            let (Some(hash), Some(_), Some(final_line)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let Ok(line) = final_line.parse() else {
+                continue;
+            };
+            current = Some((hash.to_string(), line));
+        } else if let Some((commit, _)) = &current {
+            let entry = meta.entry(commit.clone()).or_default();
+            if let Some(author) = row.strip_prefix("author ") {
+                entry.0 = author.to_string();
+            } else if let Some(time) = row.strip_prefix("author-time ") {
+                entry.1 = time.parse().unwrap_or(0);
+            }
+        }
+    }
+    lines
+}
+
+fn render_blame(lines: &[BlameLine]) -> String {
+    let mut out = String::new();
+    for (i, l) in lines.iter().enumerate() {
+        let uncommitted = l.commit.bytes().all(|b| b == b'0');
+        let (commit, date, author) = if uncommitted {
+            ("uncommitted".to_string(), "-".repeat(10), String::new())
+        } else {
+            let date = DateTime::from_timestamp(l.author_time, 0)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "?".repeat(10));
+            (
+                l.commit.chars().take(8).collect(),
+                date,
+                clip(&l.author, AUTHOR_MAX_CHARS),
+            )
+        };
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = write!(
+            out,
+            "{commit:<11} {date} {author:<width$} {:>5} | {}",
+            l.line,
+            clip(&l.text, BLAME_MAX_LINE_CHARS),
+            width = AUTHOR_MAX_CHARS
+        );
+    }
+    out
+}
+
+/// Line count as git sees it: a trailing partial line counts.
+fn count_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    if bytes.last().is_some_and(|&b| b != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DummyToolContext, git_commit, git_repo};
+    use tempfile::TempDir;
+
+    /// A repo with one committed three-line file.
+    fn repo() -> TempDir {
+        let dir = git_repo();
+        git_commit(dir.path(), &[("f.txt", "one\ntwo\nthree\n")], "init");
+        dir
+    }
+
+    async fn blame(path: &Path, start: Option<usize>, end: Option<usize>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        GitBlameTool::default()
+            .execute(
+                &mut ctx,
+                GitBlameInput {
+                    path: path.display().to_string(),
+                    start_line: start,
+                    end_line: end,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn blames_the_requested_range() {
+        let dir = repo();
+        std::fs::write(dir.path().join("f.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+
+        let outcome = blame(&dir.path().join("f.txt"), Some(2), None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        let rows: Vec<&str> = body.lines().collect();
+        assert_eq!(rows.len(), 3, "{body}");
+        assert!(
+            rows[0].contains("Ada") && rows[0].ends_with("2 | two"),
+            "{body}"
+        );
+        assert!(
+            rows[2].starts_with("uncommitted") && rows[2].ends_with("4 | four"),
+            "{body}"
+        );
+    }
+
+    #[tokio::test]
+    async fn untracked_file_is_a_clear_error() {
+        let dir = repo();
+        let file = dir.path().join("new.txt");
+        std::fs::write(&file, "x\n").unwrap();
+
+        let outcome = blame(&file, None, None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not tracked by git"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[tokio::test]
+    async fn outside_a_repository_is_a_clear_error() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        std::fs::write(&file, "x\n").unwrap();
+
+        let outcome = blame(&file, None, None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not inside a git repository"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[tokio::test]
+    async fn range_past_the_end_is_rejected() {
+        let dir = repo();
+        let outcome = blame(&dir.path().join("f.txt"), Some(9), None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("past the end"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[test]
+    fn porcelain_metadata_carries_to_repeated_commits() {
+        let out =
+            "abc123 1 1 2\nauthor Ada\nauthor-time 0\nsummary s\n\tfirst\nabc123 2 2\n\tsecond\n";
+        let lines = parse_porcelain(out);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].author, "Ada");
+        assert_eq!(lines[1].line, 2);
+        assert_eq!(lines[1].text, "second");
+        assert!(render_blame(&lines).starts_with("abc123      1970-01-01 Ada"));
+    }
+}
@@ -0,0 +1,329 @@
+//! `git_file_history` builtin — the recent commits that touched a file.
+//!
+//! Runs `git log --follow` for one file and renders one row per
+//! commit: short hash, author date, author, and subject. At most
+//! [`HISTORY_MAX_LIMIT`] commits are listed per call and subjects are
+//! clipped to [`SUBJECT_MAX_CHARS`] characters, so a long-lived file
+//! stays bounded; a footer says when older commits were left out.
+//!
+//! Returns [`ToolDetails::Text`] with the display path as the
+//! `summary`. Files outside a repository or without committed history
+//! come back as recoverable `is_error: true` outcomes.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::git::{clip, run_git, split_file_path};
+use crate::roots::AllowedRoots;
+
+const DESCRIPTION: &str = r#"
+Show the recent commit history of a file (git log), newest first.
+
+Usage:
+
+- The path parameter must be an absolute path to a file tracked by git
+- limit caps how many commits are listed (default 20, at most 100)
+- Renames are followed, so history from before a move is included
+- Each row is: commit, date, author, subject. Follow up with bash
+  (git show <commit>) to read a commit, or git_blame for line-level history
+"#;
+
+/// Commits listed when the caller gives no `limit`.
+const HISTORY_DEFAULT_LIMIT: usize = 20;
+
+/// Most commits listed in one call.
+const HISTORY_MAX_LIMIT: usize = 100;
+
+/// Per-commit character cap for the subject line.
+const SUBJECT_MAX_CHARS: usize = 120;
+
+/// Author names are clipped to this many characters to keep rows aligned.
+const AUTHOR_MAX_CHARS: usize = 20;
+
+#[derive(Clone, Default)]
+pub struct GitFileHistoryTool {
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl GitFileHistoryTool {
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct GitFileHistoryInput {
+    /// The absolute path to the file whose history to show.
+    pub path: String,
+    /// Most commits to list, newest first. Defaults to 20; capped at 100.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ToolDefinition for GitFileHistoryTool {
+    type Input = GitFileHistoryInput;
+
+    fn name(&self) -> &'static str {
+        "git_file_history"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        let (dir, name) = match split_file_path(path) {
+            Ok(parts) => parts,
+            Err(message) => return Ok(error_outcome(&input.path, message)),
+        };
+        let limit = input.limit.unwrap_or(HISTORY_DEFAULT_LIMIT);
+        if limit == 0 {
+            return Ok(error_outcome(
+                &input.path,
+                "limit must be at least 1".to_string(),
+            ));
+        }
+        let limit = limit.min(HISTORY_MAX_LIMIT);
+
+        let count = limit.to_string();
+        let args = [
+            "log",
+            "--follow",
+            "-n",
+            &count,
+            "--date=short",
+            "--format=%h%x09%ad%x09%an%x09%s",
+            "--",
+            name,
+        ];
+        let log = match run_git(dir, &args).await {
+            Ok(out) => out,
+            Err(message) => return Ok(error_outcome(&input.path, message)),
+        };
+        let commits = parse_log(&log);
+        if commits.is_empty() {
+            return Ok(error_outcome(
+                &input.path,
+                format!(
+                    "'{}' is not tracked by git (no committed history)",
+                    input.path
+                ),
+            ));
+        }
+
+        let mut body = render_history(&commits);
+        if commits.len() == limit {
+            let _ = write!(
+                body,
+                "\n[Showing the {limit} most recent commits; older ones may exist.]"
+            );
+        }
+
+        let display = display_relative(path, &ctx.working_directory());
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text {
+                summary: display,
+                body,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// One commit in the file's history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HistoryCommit {
+    hash: String,
+    date: String,
+    author: String,
+    subject: String,
+}
+
+/// Parse the tab-separated `hash, date, author, subject` rows the
+/// `--format` above produces. Rows that don't split cleanly are
+/// skipped; the subject is last so tabs inside it survive.
+fn parse_log(out: &str) -> Vec<HistoryCommit> {
+    out.lines()
+        .filter_map(|row| {
+            let mut parts = row.splitn(4, '\t');
+            let (Some(hash), Some(date), Some(author), Some(subject)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return None;
+            };
+            Some(HistoryCommit {
+                hash: hash.to_string(),
+                date: date.to_string(),
+                author: author.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn render_history(commits: &[HistoryCommit]) -> String {
+    let mut out = String::new();
+    for (i, c) in commits.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = write!(
+            out,
+            "{:<11} {} {:<width$} {}",
+            c.hash,
+            c.date,
+            clip(&c.author, AUTHOR_MAX_CHARS),
+            clip(&c.subject, SUBJECT_MAX_CHARS),
+            width = AUTHOR_MAX_CHARS
+        );
+    }
+    out
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DummyToolContext, git_commit, git_repo};
+    use tempfile::TempDir;
+
+    /// A repo where `f.txt` was committed three times.
+    fn repo() -> TempDir {
+        let dir = git_repo();
+        for (i, subject) in ["add f", "grow f", "trim f"].iter().enumerate() {
+            git_commit(dir.path(), &[("f.txt", &"x\n".repeat(i + 1))], subject);
+        }
+        dir
+    }
+
+    async fn history(path: &Path, limit: Option<usize>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        GitFileHistoryTool::default()
+            .execute(
+                &mut ctx,
+                GitFileHistoryInput {
+                    path: path.display().to_string(),
+                    limit,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_commits_newest_first() {
+        let dir = repo();
+        let outcome = history(&dir.path().join("f.txt"), None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        let rows: Vec<&str> = body.lines().collect();
+        assert_eq!(rows.len(), 3, "{body}");
+        assert!(
+            rows[0].contains("Ada") && rows[0].ends_with("trim f"),
+            "{body}"
+        );
+        assert!(rows[2].ends_with("add f"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn limit_bounds_the_listing() {
+        let dir = repo();
+        let outcome = history(&dir.path().join("f.txt"), Some(2)).await;
+        let body = text(&outcome);
+        assert!(body.lines().next().unwrap().ends_with("trim f"), "{body}");
+        assert!(!body.contains("add f"), "{body}");
+        assert!(body.contains("2 most recent commits"), "{body}");
+
+        let outcome = history(&dir.path().join("f.txt"), Some(0)).await;
+        assert!(outcome.is_error);
+    }
+
+    #[tokio::test]
+    async fn untracked_file_is_a_clear_error() {
+        let dir = repo();
+        let file = dir.path().join("new.txt");
+        std::fs::write(&file, "x\n").unwrap();
+
+        let outcome = history(&file, None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not tracked by git"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[tokio::test]
+    async fn outside_a_repository_is_a_clear_error() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        std::fs::write(&file, "x\n").unwrap();
+
+        let outcome = history(&file, None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not inside a git repository"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[test]
+    fn subjects_keep_their_tabs() {
+        let commits = parse_log("abc1234\t2024-01-02\tAda\tfix:\tthing\nbogus\n");
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].subject, "fix:\tthing");
+    }
+}
@@ -0,0 +1,271 @@
+//! `git_files` builtin — the files git tracks, straight from the index.
+//!
+//! Runs `git ls-files` in the working directory, so the listing is
+//! git's notion of the project (untracked and ignored files are left
+//! out) and costs no filesystem walk. An optional `pattern` is passed
+//! through as a `:(glob)` pathspec, relative to the working directory.
+//! At most [`FILES_MAX`] paths are listed; a footer gives the full
+//! count when the rest were left out. Names that aren't valid UTF-8
+//! are never decoded lossily: they are left out and named, byte-escaped,
+//! in a footer.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the pattern (or
+//! `all tracked files`). Outside a repository the call is a
+//! recoverable `is_error: true` outcome.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use crate::git::run_git_bytes;
+
+const DESCRIPTION: &str = r#"
+List the files tracked by git in the working directory (git ls-files).
+
+Usage:
+
+- Faster than walking the filesystem, and leaves out untracked and ignored
+  files
+- pattern is an optional glob relative to the working directory, e.g.
+  "**/*.rs" or "src/*.toml"; omit it to list every tracked file
+- Paths are relative to the working directory, one per line
+- At most 1000 paths are listed; narrow the pattern when the listing is cut
+"#;
+
+/// Most paths listed in one call.
+const FILES_MAX: usize = 1000;
+
+#[derive(Clone)]
+pub struct GitFilesTool;
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct GitFilesInput {
+    /// Glob to filter the tracked files by, relative to the working
+    /// directory (e.g. `**/*.rs`). Lists every tracked file when omitted.
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+impl ToolDefinition for GitFilesTool {
+    type Input = GitFilesInput;
+
+    fn name(&self) -> &'static str {
+        "git_files"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let pattern = input.pattern.as_deref().map(str::trim).unwrap_or("");
+        let summary = if pattern.is_empty() {
+            "all tracked files".to_string()
+        } else {
+            pattern.to_string()
+        };
+
+        let pathspec = format!(":(glob){pattern}");
+        let mut args = vec!["ls-files", "-z"];
+        if !pattern.is_empty() {
+            args.extend(["--", pathspec.as_str()]);
+        }
+        let listing = match run_git_bytes(&ctx.working_directory(), &args).await {
+            Ok(out) => out,
+            Err(message) => return Ok(error_outcome(summary, message)),
+        };
+
+        // Names that aren't UTF-8 can't be passed back to the other
+        // tools as JSON strings, so rather than mangle them they are
+        // left out and named (byte-escaped) in a footer.
+        let mut files = Vec::new();
+        let mut non_utf8 = Vec::new();
+        for name in listing.split(|&b| b == 0).filter(|f| !f.is_empty()) {
+            match std::str::from_utf8(name) {
+                Ok(name) => files.push(name),
+                Err(_) => non_utf8.push(name.escape_ascii().to_string()),
+            }
+        }
+        let mut body = render_files(&files, pattern);
+        if !non_utf8.is_empty() {
+            let _ = write!(
+                body,
+                "\n\n[Left out {} tracked file(s) whose names are not valid UTF-8 (shown \
+                 byte-escaped): {}. Use bash to work with them.]",
+                non_utf8.len(),
+                non_utf8.join(", ")
+            );
+        }
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text { summary, body },
+            is_error: false,
+        })
+    }
+}
+
+fn render_files(files: &[&str], pattern: &str) -> String {
+    if files.is_empty() {
+        return if pattern.is_empty() {
+            "No tracked files.".to_string()
+        } else {
+            format!("No tracked files match '{pattern}'.")
+        };
+    }
+    let mut out = files
+        .iter()
+        .take(FILES_MAX)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if files.len() > FILES_MAX {
+        let _ = write!(
+            out,
+            "\n\n[Showing {FILES_MAX} of {} tracked files. Narrow the pattern to see the rest.]",
+            files.len()
+        );
+    }
+    out
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(summary: String, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary,
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DummyToolContext, git, git_repo};
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    /// A repo tracking `a.rs`, `src/b.rs` and `README.md`, with an
+    /// untracked `scratch.rs` beside them.
+    fn repo() -> TempDir {
+        let dir = git_repo();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        for file in ["a.rs", "src/b.rs", "README.md", "scratch.rs"] {
+            std::fs::write(dir.path().join(file), "x\n").unwrap();
+        }
+        git(dir.path(), &["add", "a.rs", "src/b.rs", "README.md"]);
+        dir
+    }
+
+    async fn list(dir: &Path, pattern: Option<&str>) -> ToolOutcome {
+        let mut ctx = DummyToolContext {
+            working_directory: dir.to_path_buf(),
+            ..Default::default()
+        };
+        GitFilesTool
+            .execute(
+                &mut ctx,
+                GitFilesInput {
+                    pattern: pattern.map(str::to_string),
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_tracked_files_only() {
+        let dir = repo();
+        let outcome = list(dir.path(), None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        assert_eq!(text(&outcome), "README.md\na.rs\nsrc/b.rs");
+    }
+
+    #[tokio::test]
+    async fn pattern_filters_by_glob() {
+        let dir = repo();
+        assert_eq!(
+            text(&list(dir.path(), Some("**/*.rs")).await),
+            "a.rs\nsrc/b.rs"
+        );
+        assert_eq!(text(&list(dir.path(), Some("*.rs")).await), "a.rs");
+        assert_eq!(
+            text(&list(dir.path(), Some("*.py")).await),
+            "No tracked files match '*.py'."
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn non_utf8_names_are_left_out_with_a_note() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = repo();
+        let name = OsStr::from_bytes(b"caf\xe9.rs");
+        if std::fs::write(dir.path().join(name), "x\n").is_err() {
+            // The filesystem only allows UTF-8 names (e.g. APFS).
+            return;
+        }
+        let status = Command::new("git")
+            .arg("add")
+            .arg(name)
+            .current_dir(dir.path())
+            .status()
+            .expect("run git");
+        assert!(status.success());
+
+        let outcome = list(dir.path(), None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        assert_eq!(
+            text(&outcome),
+            "README.md\na.rs\nsrc/b.rs\n\n[Left out 1 tracked file(s) whose names are not \
+             valid UTF-8 (shown byte-escaped): caf\\xe9.rs. Use bash to work with them.]"
+        );
+    }
+
+    #[tokio::test]
+    async fn outside_a_repository_is_a_clear_error() {
+        let dir = TempDir::new().unwrap();
+        let outcome = list(dir.path(), None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not inside a git repository"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[test]
+    fn long_listings_are_capped() {
+        let names: Vec<String> = (0..=FILES_MAX).map(|i| format!("f{i}")).collect();
+        let files: Vec<&str> = names.iter().map(String::as_str).collect();
+        let out = render_files(&files, "");
+        assert_eq!(
+            out.lines().filter(|l| l.starts_with('f')).count(),
+            FILES_MAX
+        );
+        assert!(out.contains("Showing 1000 of 1001"), "{out}");
+    }
+}
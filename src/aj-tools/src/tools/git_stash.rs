@@ -0,0 +1,468 @@
+//! `git_stash` builtin — a git-level checkpoint around risky changes.
+//!
+//! `push` runs `git stash push --include-untracked` in the repository
+//! root, so the whole working tree (untracked files included) goes back
+//! to `HEAD` and the previous state sits in a stash entry; `pop`
+//! restores the most recent entry; `list` shows them. Unlike the
+//! in-session undo this survives the session and also covers changes
+//! made through `bash`.
+//!
+//! Like the file-writing tools it honours `protected_paths` and
+//! `allowed_roots`: a `push` that would stash (and so remove from the
+//! working tree) a protected file or one outside the allowed roots is
+//! refused before git touches anything, and so is a `pop` of an entry
+//! that would write such a file back, whoever created the entry.
+//!
+//! "Nothing to stash" is a normal result; "no stash to pop" and a pop
+//! that fails (conflicts, local changes in the way) come back as
+//! `is_error: true`, with git's reason. Returns [`ToolDetails::Text`]
+//! whose `summary` is the action. `push` and `pop` rewrite the working
+//! tree, so the tool runs in `Sequential` mode.
+
+use aj_agent::tool::{ExecutionMode, ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::git::run_git;
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+
+const DESCRIPTION: &str = r#"
+Save or restore a git stash checkpoint of the working tree.
+
+Usage:
+
+- action "push" stashes all uncommitted changes, including untracked files,
+  leaving the working tree clean at HEAD; message labels the entry
+- action "pop" restores the most recent stash entry and drops it
+- action "list" shows the stash entries, most recent first
+- Use push before sweeping or risky changes to get a rollback point; to roll
+  back, discard the new changes (e.g. git checkout/clean via bash), then pop
+- Runs in the root of the git repository containing the working directory
+- A push that would stash a protected file, or a pop that would restore one,
+  is refused
+"#;
+
+/// Label used for a `push` without a message.
+const DEFAULT_MESSAGE: &str = "aj checkpoint";
+
+#[derive(Clone, Default)]
+pub struct GitStashTool {
+    /// Paths a `push` must not stash away and a `pop` must not
+    /// restore; see [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every stashed or restored path must resolve
+    /// inside; see [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl GitStashTool {
+    /// Refuse a `push` or `pop` that would touch a path matching the
+    /// `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse a `push` or `pop` that would touch a path outside
+    /// `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Check every path a `push` would stash, returning the first
+    /// refusal.
+    async fn check_stashable(&self, root: &Path, working_directory: &Path) -> Result<(), String> {
+        let status = run_git(
+            root,
+            &["status", "--porcelain", "--untracked-files=all", "-z"],
+        )
+        .await?;
+        self.check_paths(
+            root,
+            working_directory,
+            changed_paths(&status),
+            "Stashing would remove it from the working tree, so nothing was stashed.",
+        )
+    }
+
+    /// Check every path popping the latest stash entry would write
+    /// back, untracked files included, returning the first refusal.
+    async fn check_poppable(&self, root: &Path, working_directory: &Path) -> Result<(), String> {
+        let names = run_git(
+            root,
+            &[
+                "stash",
+                "show",
+                "--name-only",
+                "--include-untracked",
+                "-z",
+                "stash@{0}",
+            ],
+        )
+        .await?;
+        self.check_paths(
+            root,
+            working_directory,
+            names.split('\0').filter(|name| !name.is_empty()).collect(),
+            "Popping the stash would rewrite it, so nothing was restored.",
+        )
+    }
+
+    /// Run `paths` (relative to `root`) past the allowed roots and the
+    /// protected paths, adding `consequence` to a protected-path
+    /// refusal.
+    fn check_paths(
+        &self,
+        root: &Path,
+        working_directory: &Path,
+        paths: Vec<&str>,
+        consequence: &str,
+    ) -> Result<(), String> {
+        for path in paths {
+            let path = root.join(path);
+            self.allowed_roots.check(&path, working_directory)?;
+            self.protected_paths
+                .check(&path, working_directory)
+                .map_err(|message| format!("{message} {consequence}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitStashAction {
+    /// Stash all uncommitted changes, including untracked files.
+    Push,
+    /// Restore the most recent stash entry and drop it.
+    Pop,
+    /// List the stash entries.
+    List,
+}
+
+impl GitStashAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Push => "push",
+            Self::Pop => "pop",
+            Self::List => "list",
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct GitStashInput {
+    /// What to do: `push`, `pop` or `list`.
+    pub action: GitStashAction,
+    /// Label for the stash entry (`push` only). Defaults to
+    /// `aj checkpoint`.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl ToolDefinition for GitStashTool {
+    type Input = GitStashInput;
+
+    fn name(&self) -> &'static str {
+        "git_stash"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    /// `push` and `pop` rewrite the working tree, so this runs in
+    /// `Sequential` mode like the file-writing tools.
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let summary = format!("stash {}", input.action.as_str());
+        let root = match repo_root(&ctx.working_directory()).await {
+            Ok(root) => root,
+            Err(message) => return Ok(outcome(summary, message, true)),
+        };
+        let cwd = ctx.working_directory();
+        let checked = match input.action {
+            GitStashAction::Push => self.check_stashable(&root, &cwd).await,
+            // With no entry there is nothing to check; `pop` says so.
+            GitStashAction::Pop if latest_entry(&root).await.is_some() => {
+                self.check_poppable(&root, &cwd).await
+            }
+            GitStashAction::Pop | GitStashAction::List => Ok(()),
+        };
+        if let Err(message) = checked {
+            return Ok(outcome(summary, message, true));
+        }
+        let (body, is_error) = match input.action {
+            GitStashAction::Push => push(&root, input.message.as_deref()).await,
+            GitStashAction::Pop => pop(&root).await,
+            GitStashAction::List => list(&root).await,
+        };
+        Ok(outcome(summary, body, is_error))
+    }
+}
+
+/// The top-level directory of the repository containing `dir`.
+async fn repo_root(dir: &Path) -> Result<PathBuf, String> {
+    let out = run_git(dir, &["rev-parse", "--show-toplevel"]).await?;
+    Ok(PathBuf::from(out.trim_end()))
+}
+
+/// The paths in `git status --porcelain -z` output, relative to the
+/// repository root. A rename or copy contributes both its new and its
+/// original path.
+fn changed_paths(status: &str) -> Vec<&str> {
+    let mut paths = Vec::new();
+    let mut entries = status.split('\0').filter(|e| !e.is_empty());
+    while let Some(entry) = entries.next() {
+        let Some((code, path)) = entry.split_at_checked(3) else {
+            continue;
+        };
+        paths.push(path);
+        if code.contains(['R', 'C'])
+            && let Some(original) = entries.next()
+        {
+            paths.push(original);
+        }
+    }
+    paths
+}
+
+async fn push(root: &Path, message: Option<&str>) -> (String, bool) {
+    let message = message
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .unwrap_or(DEFAULT_MESSAGE);
+    let args = ["stash", "push", "--include-untracked", "--message", message];
+    match run_git(root, &["status", "--porcelain"]).await {
+        Ok(status) if status.trim().is_empty() => {
+            return (
+                "Nothing to stash: the working tree has no uncommitted changes.".to_string(),
+                false,
+            );
+        }
+        Ok(_) => {}
+        Err(message) => return (message, true),
+    }
+    match run_git(root, &args).await {
+        Ok(_) => match latest_entry(root).await {
+            Some(entry) => (format!("Saved {entry}"), false),
+            None => (
+                "Saved the working tree to a new stash entry.".to_string(),
+                false,
+            ),
+        },
+        Err(message) => (message, true),
+    }
+}
+
+async fn pop(root: &Path) -> (String, bool) {
+    let Some(entry) = latest_entry(root).await else {
+        return (
+            "No stash entries to pop; nothing was restored.".to_string(),
+            true,
+        );
+    };
+    match run_git(root, &["stash", "pop"]).await {
+        Ok(_) => (format!("Restored and dropped {entry}"), false),
+        Err(message) => (
+            format!("{message}\n\n{entry} was not dropped; resolve the problem and pop again."),
+            true,
+        ),
+    }
+}
+
+async fn list(root: &Path) -> (String, bool) {
+    match run_git(root, &["stash", "list"]).await {
+        Ok(out) if out.trim().is_empty() => ("No stash entries.".to_string(), false),
+        Ok(out) => (out.trim_end().to_string(), false),
+        Err(message) => (message, true),
+    }
+}
+
+/// The most recent stash entry as `stash@{0}: On <branch>: <message>`.
+async fn latest_entry(root: &Path) -> Option<String> {
+    let out = run_git(root, &["stash", "list", "--max-count=1"])
+        .await
+        .ok()?;
+    let entry = out.trim_end();
+    (!entry.is_empty()).then(|| entry.to_string())
+}
+
+fn outcome(summary: String, body: String, is_error: bool) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(body.clone())],
+        details: ToolDetails::Text { summary, body },
+        is_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DummyToolContext, git, git_commit, git_repo};
+    use tempfile::TempDir;
+
+    /// A repo with one commit of `a.txt`, worked on from `sub/`.
+    fn repo() -> TempDir {
+        let dir = git_repo();
+        git_commit(
+            dir.path(),
+            &[("a.txt", "one\n"), ("sub/b.txt", "b\n")],
+            "init",
+        );
+        dir
+    }
+
+    async fn stash(dir: &Path, action: GitStashAction, message: Option<&str>) -> ToolOutcome {
+        stash_with(GitStashTool::default(), dir, action, message).await
+    }
+
+    async fn stash_with(
+        tool: GitStashTool,
+        dir: &Path,
+        action: GitStashAction,
+        message: Option<&str>,
+    ) -> ToolOutcome {
+        let mut ctx = DummyToolContext {
+            working_directory: dir.join("sub"),
+            ..Default::default()
+        };
+        tool.execute(
+            &mut ctx,
+            GitStashInput {
+                action,
+                message: message.map(str::to_string),
+            },
+        )
+        .await
+        .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn push_list_and_pop_round_trip() {
+        let dir = repo();
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "new\n").unwrap();
+
+        let outcome = stash(dir.path(), GitStashAction::Push, Some("before refactor")).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        assert!(
+            text(&outcome).starts_with("Saved stash@{0}: On "),
+            "{}",
+            text(&outcome)
+        );
+        assert!(text(&outcome).ends_with(": before refactor"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "one\n"
+        );
+        assert!(!dir.path().join("new.txt").exists());
+
+        let outcome = stash(dir.path(), GitStashAction::List, None).await;
+        assert!(text(&outcome).contains("before refactor"));
+
+        let outcome = stash(dir.path(), GitStashAction::Pop, None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        assert!(text(&outcome).starts_with("Restored and dropped stash@{0}"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "two\n"
+        );
+        assert!(dir.path().join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn nothing_to_stash_and_nothing_to_pop() {
+        let dir = repo();
+
+        let outcome = stash(dir.path(), GitStashAction::Push, None).await;
+        assert!(!outcome.is_error);
+        assert!(text(&outcome).starts_with("Nothing to stash"));
+
+        let outcome = stash(dir.path(), GitStashAction::Pop, None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).starts_with("No stash entries to pop"));
+
+        let outcome = stash(dir.path(), GitStashAction::List, None).await;
+        assert_eq!(text(&outcome), "No stash entries.");
+    }
+
+    #[tokio::test]
+    async fn push_refuses_to_stash_a_protected_path() {
+        let dir = repo();
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(dir.path().join(".env"), "SECRET=1\n").unwrap();
+
+        let tool = GitStashTool::default()
+            .with_protected_paths(ProtectedPaths::new(&[".env".to_string()]));
+        let outcome = stash_with(tool, dir.path(), GitStashAction::Push, None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("protected"), "{}", text(&outcome));
+        assert!(text(&outcome).contains("nothing was stashed"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".env")).unwrap(),
+            "SECRET=1\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "two\n"
+        );
+        let outcome = stash(dir.path(), GitStashAction::List, None).await;
+        assert_eq!(text(&outcome), "No stash entries.");
+    }
+
+    #[tokio::test]
+    async fn pop_refuses_to_restore_a_protected_path() {
+        // An entry the user made before the session, holding an
+        // untracked secret.
+        let dir = repo();
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(dir.path().join(".env"), "SECRET=1\n").unwrap();
+        git(dir.path(), &["stash", "push", "-q", "--include-untracked"]);
+
+        let tool = GitStashTool::default()
+            .with_protected_paths(ProtectedPaths::new(&[".env".to_string()]));
+        let outcome = stash_with(tool, dir.path(), GitStashAction::Pop, None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("protected"), "{}", text(&outcome));
+        assert!(text(&outcome).contains("nothing was restored"));
+        assert!(!dir.path().join(".env").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "one\n"
+        );
+        let outcome = stash(dir.path(), GitStashAction::List, None).await;
+        assert!(
+            text(&outcome).starts_with("stash@{0}"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[test]
+    fn changed_paths_reads_renames_and_untracked_files() {
+        let status = " M a.txt\0R  new.txt\0old.txt\0?? sub/c.txt\0";
+        assert_eq!(
+            changed_paths(status),
+            vec!["a.txt", "new.txt", "old.txt", "sub/c.txt"]
+        );
+    }
+}
@@ -0,0 +1,524 @@
+//! `hash` builtin — digests a file or a directory tree.
+//!
+//! Lets the model assert "is this artifact unchanged?" without reading
+//! or diffing the contents. Files are streamed through the hasher in
+//! fixed-size chunks, so size is not bounded by memory; the whole walk
+//! runs on `tokio::task::spawn_blocking` since it is synchronous IO.
+//!
+//! A directory digest is the digest of a manifest with one
+//! `"<hex digest>  <relative path>\n"` line per regular file (the
+//! `sha256sum` output format), sorted by `/`-separated relative path.
+//! Paths go into the manifest as their raw bytes, so a non-UTF-8 file
+//! name is hashed (and read) as it is on disk rather than through a
+//! lossy conversion.
+//! It therefore changes when any file's bytes change and when files
+//! are added, removed, or renamed, but not when only metadata
+//! (mtimes, permissions) changes. Symlinks and other special files
+//! are not followed; they are skipped and counted in the result.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the display path
+//! and whose `body` matches the wire `content`. Missing paths and
+//! unreadable entries come back as recoverable `is_error: true`
+//! outcomes.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use md5::Md5;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::roots::AllowedRoots;
+use crate::truncate::format_size;
+
+const DESCRIPTION: &str = r#"
+Compute the digest of a file, or a combined digest of a directory's contents.
+
+Usage:
+
+- The path parameter must be an absolute path
+- algorithm is "sha256" (default) or "md5"
+- Use this to check whether a file or generated output is unchanged (compare
+  digests from before and after) instead of reading or diffing large contents
+- A directory digest covers every regular file under it, recursively: it
+  changes when any file's content changes or when files are added, removed or
+  renamed. Symlinks are not followed and are skipped
+"#;
+
+/// Read buffer for streaming file contents through the hasher.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Default)]
+pub struct HashTool {
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl HashTool {
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Md5,
+}
+
+impl HashAlgorithm {
+    fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Md5 => "md5",
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct HashInput {
+    /// The absolute path to the file or directory to hash.
+    pub path: String,
+    /// The digest algorithm: "sha256" (default) or "md5".
+    #[serde(default)]
+    pub algorithm: Option<HashAlgorithm>,
+}
+
+impl ToolDefinition for HashTool {
+    type Input = HashInput;
+
+    fn name(&self) -> &'static str {
+        "hash"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = PathBuf::from(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(&path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        let algorithm = input.algorithm.unwrap_or_default();
+        let display_path = display_relative(&path, &ctx.working_directory());
+
+        let result = tokio::task::spawn_blocking(move || hash_path(&path, algorithm)).await;
+        let text = match result {
+            Ok(Ok(digest)) => digest.render(algorithm, &input.path),
+            Ok(Err(message)) => return Ok(error_outcome(&input.path, message)),
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Hashing '{}' failed: {e}", input.path),
+                ));
+            }
+        };
+
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(text.clone())],
+            details: ToolDetails::Text {
+                summary: display_path,
+                body: text,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// What a successful hash produced, before rendering.
+#[derive(Debug)]
+enum PathDigest {
+    File {
+        hex: String,
+        bytes: usize,
+    },
+    Directory {
+        hex: String,
+        files: usize,
+        bytes: usize,
+        skipped: usize,
+    },
+}
+
+impl PathDigest {
+    fn render(&self, algorithm: HashAlgorithm, path: &str) -> String {
+        let name = algorithm.as_str();
+        match self {
+            PathDigest::File { hex, bytes } => {
+                format!("{name} {hex} {path} (file, {})", format_size(*bytes))
+            }
+            PathDigest::Directory {
+                hex,
+                files,
+                bytes,
+                skipped,
+            } => {
+                let noun = if *files == 1 { "file" } else { "files" };
+                let mut out = format!(
+                    "{name} {hex} {path} (directory, {files} {noun}, {})",
+                    format_size(*bytes)
+                );
+                if *skipped > 0 {
+                    let _ = write!(
+                        out,
+                        "\nSkipped {skipped} symlink(s) or special file(s); they are not part of the digest."
+                    );
+                }
+                out
+            }
+        }
+    }
+}
+
+/// Hash `path` as a file or directory. Errors are the model-facing
+/// message.
+fn hash_path(path: &Path, algorithm: HashAlgorithm) -> Result<PathDigest, String> {
+    let meta = fs::symlink_metadata(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => format!("Path does not exist: {}", path.display()),
+        _ => format!("Failed to stat '{}': {e}", path.display()),
+    })?;
+    // A symlink given directly is resolved: the caller named it on
+    // purpose. Only links found during a directory walk are skipped.
+    let meta = if meta.file_type().is_symlink() {
+        fs::metadata(path).map_err(|e| format!("Failed to resolve '{}': {e}", path.display()))?
+    } else {
+        meta
+    };
+
+    if meta.is_dir() {
+        hash_directory(path, algorithm)
+    } else if meta.is_file() {
+        let (hex, bytes) = hash_file(path, algorithm)
+            .map_err(|e| format!("Failed to read file '{}': {e}", path.display()))?;
+        Ok(PathDigest::File { hex, bytes })
+    } else {
+        Err(format!(
+            "'{}' is neither a regular file nor a directory",
+            path.display()
+        ))
+    }
+}
+
+fn hash_directory(root: &Path, algorithm: HashAlgorithm) -> Result<PathDigest, String> {
+    let mut files = Vec::new();
+    let mut skipped = 0;
+    collect_files(root, root, &mut files, &mut skipped)?;
+    files.sort();
+
+    let mut manifest = Vec::new();
+    let mut bytes = 0usize;
+    for (rel, full) in &files {
+        let (hex, size) = hash_file(full, algorithm)
+            .map_err(|e| format!("Failed to read file '{}': {e}", full.display()))?;
+        bytes = bytes.saturating_add(size);
+        manifest.extend_from_slice(hex.as_bytes());
+        manifest.extend_from_slice(b"  ");
+        manifest.extend_from_slice(rel);
+        manifest.push(b'\n');
+    }
+    let hex = match algorithm {
+        HashAlgorithm::Sha256 => to_hex(&Sha256::digest(&manifest)),
+        HashAlgorithm::Md5 => to_hex(&Md5::digest(&manifest)),
+    };
+    Ok(PathDigest::Directory {
+        hex,
+        files: files.len(),
+        bytes,
+        skipped,
+    })
+}
+
+/// Recursively gather regular files under `dir`, each as its
+/// `/`-separated path relative to `root` (raw bytes, see
+/// [`name_bytes`]) and its full path. Symlinks and special files bump
+/// `skipped` instead of being followed.
+pub(crate) fn collect_files(
+    root: &Path,
+    dir: &Path,
+    out: &mut Vec<(Vec<u8>, PathBuf)>,
+    skipped: &mut usize,
+) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to list '{}': {e}", dir.display()))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to list '{}': {e}", dir.display()))?;
+        let path = entry.path();
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to stat '{}': {e}", path.display()))?;
+        if file_type.is_dir() {
+            collect_files(root, &path, out, skipped)?;
+        } else if file_type.is_file() {
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| name_bytes(c.as_os_str()))
+                .collect::<Vec<_>>()
+                .join(&b'/');
+            out.push((rel, path));
+        } else {
+            *skipped += 1;
+        }
+    }
+    Ok(())
+}
+
+/// The bytes of a path component as stored on disk. Unix names are
+/// arbitrary bytes; elsewhere names are Unicode, so the UTF-8 form
+/// only differs for unpaired surrogates.
+#[cfg(unix)]
+fn name_bytes(name: &OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    name.as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn name_bytes(name: &OsStr) -> Vec<u8> {
+    name.to_string_lossy().into_owned().into_bytes()
+}
+
+/// Stream `path` through the selected hasher. Returns the lowercase
+/// hex digest and the number of bytes read.
+fn hash_file(path: &Path, algorithm: HashAlgorithm) -> io::Result<(String, usize)> {
+    match algorithm {
+        HashAlgorithm::Sha256 => stream_digest::<Sha256>(path),
+        HashAlgorithm::Md5 => stream_digest::<Md5>(path),
+    }
+}
+
+fn stream_digest<D: Digest>(path: &Path) -> io::Result<(String, usize)> {
+    let mut file = File::open(path)?;
+    let mut hasher = D::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut total = 0usize;
+    loop {
+        let n = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buf[..n]);
+        total = total.saturating_add(n);
+    }
+    Ok((to_hex(&hasher.finalize()), total))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{b:02x}");
+    }
+    out
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use aj_agent::tool::ExecutionMode;
+    use tempfile::TempDir;
+
+    async fn run(path: &Path, algorithm: Option<HashAlgorithm>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        HashTool::default()
+            .execute(
+                &mut ctx,
+                HashInput {
+                    path: path.display().to_string(),
+                    algorithm,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    /// Pull the hex digest (second whitespace-separated field).
+    fn digest_of(outcome: &ToolOutcome) -> String {
+        text(outcome)
+            .split_whitespace()
+            .nth(1)
+            .expect("digest field")
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn file_digest_matches_known_vectors() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("abc.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let sha = run(&file, None).await;
+        assert!(!sha.is_error);
+        assert_eq!(
+            digest_of(&sha),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(text(&sha).starts_with("sha256 "), "{}", text(&sha));
+
+        let md5 = run(&file, Some(HashAlgorithm::Md5)).await;
+        assert_eq!(digest_of(&md5), "900150983cd24fb0d6963f7d28e17f72");
+    }
+
+    #[tokio::test]
+    async fn file_larger_than_one_chunk_streams() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("big.bin");
+        let data = vec![7u8; CHUNK_SIZE * 3 + 17];
+        fs::write(&file, &data).unwrap();
+
+        let outcome = run(&file, None).await;
+        assert_eq!(digest_of(&outcome), to_hex(&Sha256::digest(&data)));
+    }
+
+    #[tokio::test]
+    async fn directory_digest_tracks_content_and_names() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("a.txt"), "one").unwrap();
+        fs::write(dir.path().join("sub/b.txt"), "two").unwrap();
+
+        let first = run(dir.path(), None).await;
+        assert!(!first.is_error);
+        assert!(
+            text(&first).contains("directory, 2 files"),
+            "{}",
+            text(&first)
+        );
+        assert_eq!(digest_of(&run(dir.path(), None).await), digest_of(&first));
+
+        fs::write(dir.path().join("sub/b.txt"), "three").unwrap();
+        let edited = run(dir.path(), None).await;
+        assert_ne!(digest_of(&edited), digest_of(&first));
+
+        fs::rename(dir.path().join("sub/b.txt"), dir.path().join("sub/c.txt")).unwrap();
+        let renamed = run(dir.path(), None).await;
+        assert_ne!(digest_of(&renamed), digest_of(&edited));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn directory_digest_reads_non_utf8_names_as_they_are_on_disk() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // Both names decode lossily to "a\u{FFFD}", so a lossy walk
+        // would fail to reopen them and could not tell them apart.
+        let dir = TempDir::new().unwrap();
+        let names: [&[u8]; 2] = [b"a\xfe", b"a\xff"];
+        for (name, content) in names.iter().zip(["one", "two"]) {
+            let path = dir.path().join(OsStr::from_bytes(name));
+            if fs::write(&path, content).is_err() {
+                // The filesystem only allows UTF-8 names (e.g. APFS);
+                // there is nothing to test.
+                return;
+            }
+        }
+
+        let outcome = run(dir.path(), None).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let mut manifest = Vec::new();
+        for (name, content) in names.iter().zip(["one", "two"]) {
+            manifest.extend_from_slice(to_hex(&Sha256::digest(content)).as_bytes());
+            manifest.extend_from_slice(b"  ");
+            manifest.extend_from_slice(name);
+            manifest.push(b'\n');
+        }
+        assert_eq!(digest_of(&outcome), to_hex(&Sha256::digest(&manifest)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn directory_walk_skips_symlinks() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "one").unwrap();
+        let before = run(dir.path(), None).await;
+
+        std::os::unix::fs::symlink(dir.path().join("a.txt"), dir.path().join("link")).unwrap();
+        let after = run(dir.path(), None).await;
+        assert_eq!(digest_of(&after), digest_of(&before));
+        assert!(
+            text(&after).contains("Skipped 1 symlink"),
+            "{}",
+            text(&after)
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_path_is_a_clear_error() {
+        let outcome = run(Path::new("/nonexistent/path/for/hash"), None).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).starts_with("Path does not exist"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[tokio::test]
+    async fn relative_path_is_rejected() {
+        let outcome = run(Path::new("relative/file"), None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).starts_with("Path must be absolute"));
+    }
+
+    #[test]
+    fn execution_mode_is_parallel() {
+        assert_eq!(
+            HashTool::default().execution_mode(),
+            ExecutionMode::Parallel
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! `help` builtin — a command's usage text, from `--help` or its man page.
+//!
+//! Lets the model look up a CLI's flags before composing a `bash` call
+//! instead of guessing them. The input is a program name, optionally
+//! followed by subcommand words (`git log`); every word must be a plain
+//! identifier, so nothing but `<program> [subcommand...] --help` and
+//! `man <page>` is ever run. The program must be on `PATH`.
+//!
+//! `--help` is tried first. When it fails or prints nothing, the man
+//! page is rendered as plain text (`git log` looks up `git-log`); a
+//! failing `--help` that still printed something is the last resort.
+//! Both run with stdin closed, pagers forced to `cat`, and are killed
+//! after [`HELP_TIMEOUT_SECS`]. Output is capped at [`HELP_MAX_LINES`]
+//! lines; the rest is stashed for `read_more`.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the command. An
+//! unknown program, a rejected command, or no help at all is a
+//! recoverable `is_error: true` outcome.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::tools::run_tests::on_path;
+use crate::truncate::{READ_MAX_BYTES, truncate_head};
+
+const DESCRIPTION: &str = r#"
+Show the usage text of a command-line program: its --help output, or its man
+page when --help gives nothing.
+
+Usage:
+
+- Use this before running an unfamiliar command through bash, instead of
+  guessing its flags
+- command is a program name, optionally followed by subcommands, e.g. "rg",
+  "git log" or "cargo build"; it must not contain flags, paths or shell syntax
+- The program must be installed on PATH
+- Only the help text is produced; the command itself is never run
+- Long help is cut at 400 lines; use read_more with the returned token for the
+  rest
+"#;
+
+/// Seconds `--help` or `man` may run before it is killed.
+const HELP_TIMEOUT_SECS: u64 = 5;
+/// Lines of help text returned before the rest is stashed.
+const HELP_MAX_LINES: usize = 400;
+/// Most words (program plus subcommands) accepted in `command`.
+const COMMAND_WORDS_MAX: usize = 3;
+/// Column width man pages are rendered at.
+const MAN_WIDTH: &str = "100";
+
+#[derive(Clone)]
+pub struct HelpTool;
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct HelpInput {
+    /// Program to look up, optionally followed by subcommands (e.g.
+    /// `git log`).
+    pub command: String,
+}
+
+impl ToolDefinition for HelpTool {
+    type Input = HelpInput;
+
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let words = match parse_command(&input.command) {
+            Ok(words) => words,
+            Err(message) => return Ok(error_outcome(input.command, message)),
+        };
+        let summary = words.join(" ");
+        if !on_path(words[0]) {
+            return Ok(error_outcome(
+                summary,
+                format!("'{}' was not found on PATH.", words[0]),
+            ));
+        }
+
+        let cwd = ctx.working_directory();
+        let cancellation = ctx.cancellation();
+        let mut help_args = words[1..].to_vec();
+        help_args.push("--help");
+        let help = run_quietly(&cancellation, &cwd, words[0], &help_args).await;
+        let text = match help {
+            Run::Output {
+                success: true,
+                text,
+            } if !text.trim().is_empty() => text,
+            Run::Cancelled => return Ok(error_outcome(summary, "Cancelled.".to_string())),
+            help => {
+                let page = words.join("-");
+                match run_quietly(&cancellation, &cwd, "man", &[page.as_str()]).await {
+                    Run::Output {
+                        success: true,
+                        text,
+                    } if !text.trim().is_empty() => strip_overstrike(&text),
+                    Run::Cancelled => {
+                        return Ok(error_outcome(summary, "Cancelled.".to_string()));
+                    }
+                    _ => match help {
+                        Run::Output { text, .. } if !text.trim().is_empty() => text,
+                        Run::TimedOut => {
+                            return Ok(error_outcome(
+                                summary.clone(),
+                                format!(
+                                    "'{summary} --help' did not finish within \
+                                     {HELP_TIMEOUT_SECS} seconds and there is no man page."
+                                ),
+                            ));
+                        }
+                        _ => {
+                            return Ok(error_outcome(
+                                summary.clone(),
+                                format!("No --help output or man page found for '{summary}'."),
+                            ));
+                        }
+                    },
+                }
+            }
+        };
+
+        let text = text.trim_end().to_string();
+        let trunc = truncate_head(&text, HELP_MAX_LINES, READ_MAX_BYTES);
+        let body = if trunc.truncated {
+            let token = ctx.stash_output(text.clone());
+            format!(
+                "{}\n\n[Help truncated: showing {} of {} lines. Use read_more with token \
+                 '{token}' and offset={} to continue.]",
+                trunc.content,
+                trunc.output_lines,
+                trunc.total_lines,
+                trunc.output_lines + 1
+            )
+        } else {
+            text
+        };
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text { summary, body },
+            is_error: false,
+        })
+    }
+}
+
+/// Split `command` into words, refusing anything that isn't a plain
+/// program or subcommand name.
+fn parse_command(command: &str) -> Result<Vec<&str>, String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    if words.is_empty() {
+        return Err("command must name a program, e.g. \"rg\" or \"git log\".".to_string());
+    }
+    if words.len() > COMMAND_WORDS_MAX {
+        return Err(format!(
+            "command takes a program and at most {} subcommands, got '{command}'.",
+            COMMAND_WORDS_MAX - 1
+        ));
+    }
+    let plain = |word: &str| {
+        word.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && word
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '+' | '-'))
+    };
+    if let Some(word) = words.iter().find(|w| !plain(w)) {
+        return Err(format!(
+            "'{word}' is not a plain program or subcommand name. Pass only names (no \
+             flags, paths or shell syntax); the help tool adds --help itself."
+        ));
+    }
+    Ok(words)
+}
+
+/// What running `--help` or `man` produced.
+enum Run {
+    /// The process exited; `text` is stdout, or stderr when stdout was
+    /// empty (plenty of tools print usage to stderr).
+    Output {
+        success: bool,
+        text: String,
+    },
+    /// The process could not be started.
+    Failed,
+    TimedOut,
+    Cancelled,
+}
+
+/// Run `program` with stdin closed and pagers disabled, killing it
+/// after [`HELP_TIMEOUT_SECS`] or when the call is cancelled.
+async fn run_quietly(
+    cancellation: &CancellationToken,
+    cwd: &Path,
+    program: &str,
+    args: &[&str],
+) -> Run {
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .current_dir(cwd)
+        .env("PAGER", "cat")
+        .env("MANPAGER", "cat")
+        .env("GIT_PAGER", "cat")
+        .env("MANWIDTH", MAN_WIDTH)
+        .env("NO_COLOR", "1")
+        .env("TERM", "dumb")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let output = tokio::select! {
+        output = cmd.output() => output,
+        _ = tokio::time::sleep(Duration::from_secs(HELP_TIMEOUT_SECS)) => return Run::TimedOut,
+        _ = cancellation.cancelled() => return Run::Cancelled,
+    };
+    let Ok(output) = output else {
+        return Run::Failed;
+    };
+    let stream = if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    Run::Output {
+        success: output.status.success(),
+        text: String::from_utf8_lossy(&stream).into_owned(),
+    }
+}
+
+/// Drop the `x\x08x` (bold) and `_\x08x` (underline) sequences `man`
+/// still emits when its output isn't a terminal.
+fn strip_overstrike(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\u{8}' {
+            out.pop();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(summary: String, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary,
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+
+    async fn help(command: &str) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        HelpTool
+            .execute(
+                &mut ctx,
+                HelpInput {
+                    command: command.to_string(),
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn only_plain_names_are_accepted() {
+        assert_eq!(parse_command("  git   log ").unwrap(), vec!["git", "log"]);
+        assert_eq!(parse_command("g++").unwrap(), vec!["g++"]);
+        for bad in [
+            "",
+            "rm -rf /",
+            "/bin/ls",
+            "../ls",
+            "ls;reboot",
+            "$(reboot)",
+            "echo `id`",
+            "a b c d",
+        ] {
+            assert!(parse_command(bad).is_err(), "accepted {bad:?}");
+        }
+    }
+
+    #[test]
+    fn overstrike_is_stripped() {
+        assert_eq!(strip_overstrike("N\u{8}NA\u{8}AM\u{8}ME\u{8}E"), "NAME");
+        assert_eq!(strip_overstrike("_\u{8}f_\u{8}i_\u{8}l_\u{8}e"), "file");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn returns_usage_text() {
+        let outcome = help("ls").await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        assert!(
+            text(&outcome).to_lowercase().contains("usage"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[tokio::test]
+    async fn flags_and_unknown_programs_are_errors() {
+        let outcome = help("ls --all").await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not a plain program"),
+            "{}",
+            text(&outcome)
+        );
+
+        let outcome = help("aj-no-such-program-here").await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("not found on PATH"),
+            "{}",
+            text(&outcome)
+        );
+    }
+}
@@ -0,0 +1,548 @@
+//! `hotspots` builtin — where a tree's size, nesting and churn pile up.
+//!
+//! Walks `path` the way `count_lines` does ([`ignore`] rules, the same
+//! `include` glob and `hidden` switch, binary files skipped) and
+//! measures each text file:
+//! its line count and its deepest indentation level. When `path` is in
+//! a git repository, `git log --name-only --relative` over the last
+//! [`CHURN_COMMITS`] commits adds how many of them touched each file.
+//!
+//! The result is a set of ranked tables, each cut to the `top` entries:
+//! the largest files, the most deeply nested, the most often changed,
+//! and — with history — the hotspots, ranked by lines × commits, the
+//! usual "big and busy" measure of where refactoring pays. Churn only
+//! counts files that still exist under the walk, so deleted and
+//! ignored paths never rank.
+//!
+//! Nesting is an indentation heuristic, not a parse: a tab is
+//! [`TAB_WIDTH`] columns, the file's indent unit is its smallest
+//! non-zero indent (clamped to 2..=8 columns so alignment spaces
+//! don't count as levels), and a line's level is its indent divided by
+//! that unit.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the path. The tool
+//! only reads, so it keeps the default parallel execution mode.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git::run_git;
+use crate::roots::AllowedRoots;
+use crate::tools::count_lines::{BINARY_SNIFF_BYTES, Include, walk};
+
+const DESCRIPTION: &str = r#"
+Rank a directory's files by size, nesting depth and git churn, to find where
+refactoring would pay off.
+
+Usage:
+
+- The path parameter must be an absolute path to a directory
+- include is an optional glob: "*.rs" matches file names anywhere, while a
+  pattern containing a slash ("src/**/*.rs") matches paths relative to path
+- top bounds every table (default 10, at most 50)
+- .gitignore rules are respected and binary files are skipped
+- Hidden files and directories (.github, .env, ...) are skipped by default;
+  set hidden to true to rank them too
+- Tables: largest files by lines, deepest indentation nesting, and, inside a git
+  repository, the files changed in the most recent commits and the hotspots
+  ranked by lines x commits
+- Nesting is estimated from indentation, so treat it as a pointer, not a metric
+"#;
+
+/// Entries per table when `top` is omitted.
+const DEFAULT_TOP: usize = 10;
+/// Largest accepted `top`.
+const MAX_TOP: usize = 50;
+/// Most files measured in one call; the walk stops there.
+const FILES_MAX: usize = 50_000;
+/// How far back the churn counts look.
+const CHURN_COMMITS: usize = 500;
+/// Columns a tab counts for when measuring indentation.
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Default)]
+pub struct HotspotsTool {
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl HotspotsTool {
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct HotspotsInput {
+    /// The absolute path to the directory to survey.
+    pub path: String,
+    /// Glob restricting which files are measured (e.g. `*.rs` or
+    /// `src/**/*.ts`). Measures every non-ignored file when omitted.
+    #[serde(default)]
+    pub include: Option<String>,
+    /// Entries per table (default 10, at most 50).
+    #[serde(default)]
+    pub top: Option<usize>,
+    /// Also measure hidden files and directories (names starting with
+    /// `.`). Defaults to false.
+    #[serde(default)]
+    pub hidden: Option<bool>,
+}
+
+impl ToolDefinition for HotspotsTool {
+    type Input = HotspotsInput;
+
+    fn name(&self) -> &'static str {
+        "hotspots"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = PathBuf::from(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(&path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if !path.is_dir() {
+            return Ok(error_outcome(
+                &input.path,
+                if path.exists() {
+                    format!(
+                        "'{}' is not a directory; use count_lines or git_file_history for one file",
+                        input.path
+                    )
+                } else {
+                    format!("Path '{}' does not exist", input.path)
+                },
+            ));
+        }
+        let top = match input.top {
+            Some(0) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    "top must be at least 1".to_string(),
+                ));
+            }
+            Some(top) => top.min(MAX_TOP),
+            None => DEFAULT_TOP,
+        };
+
+        let include = match input.include.as_deref().map(str::trim) {
+            Some(pattern) if !pattern.is_empty() => match Include::new(pattern) {
+                Ok(include) => Some(include),
+                Err(e) => {
+                    return Ok(error_outcome(
+                        &input.path,
+                        format!("Invalid include glob '{pattern}': {e}"),
+                    ));
+                }
+            },
+            _ => None,
+        };
+
+        let root = path.clone();
+        let hidden = input.hidden.unwrap_or(false);
+        let survey =
+            tokio::task::spawn_blocking(move || measure(&root, include.as_ref(), hidden)).await?;
+        let churn = churn(&path).await;
+        let body = render(&survey, churn.as_ref(), &input.path, top);
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text {
+                summary: PathBuf::from(&input.path).display().to_string(),
+                body,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// Measurements of one text file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FileStats {
+    /// Path relative to the walk root, `/`-separated.
+    path: String,
+    lines: usize,
+    /// Deepest indentation level and the 1-based line reaching it.
+    depth: usize,
+    depth_line: usize,
+}
+
+/// Result of a walk.
+#[derive(Default)]
+struct Survey {
+    files: Vec<FileStats>,
+    truncated: bool,
+}
+
+fn measure(root: &Path, include: Option<&Include>, hidden: bool) -> Survey {
+    let mut survey = Survey::default();
+    for entry in walk(root, hidden).flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        if include.is_some_and(|include| !include.matches(root, path)) {
+            continue;
+        }
+        if survey.files.len() == FILES_MAX {
+            survey.truncated = true;
+            break;
+        }
+        let Ok(bytes) = fs::read(path) else {
+            continue;
+        };
+        if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        let (depth, depth_line) = nesting(&text);
+        survey.files.push(FileStats {
+            path: relative(root, path),
+            lines: text.lines().count(),
+            depth,
+            depth_line,
+        });
+    }
+    survey
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The deepest indentation level of `text` and the first line (1-based)
+/// reaching it; `(0, 0)` for a file with no indented lines. See the
+/// module docs for the heuristic.
+fn nesting(text: &str) -> (usize, usize) {
+    let indents: Vec<(usize, usize)> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let columns = line
+                .chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+                .sum();
+            (i + 1, columns)
+        })
+        .collect();
+    let Some(unit) = indents
+        .iter()
+        .map(|(_, columns)| *columns)
+        .filter(|columns| *columns > 0)
+        .min()
+    else {
+        return (0, 0);
+    };
+    let unit = unit.clamp(2, 8);
+    let mut deepest = (0, 0);
+    for (line, columns) in indents {
+        let level = columns / unit;
+        if level > deepest.0 {
+            deepest = (level, line);
+        }
+    }
+    deepest
+}
+
+/// Commits among the last [`CHURN_COMMITS`] touching each path under
+/// `dir`, keyed relative to it. `Err` carries why there is no history.
+async fn churn(dir: &Path) -> Result<HashMap<String, usize>, String> {
+    let max_count = format!("--max-count={CHURN_COMMITS}");
+    let log = run_git(
+        dir,
+        &[
+            "log",
+            &max_count,
+            "--format=",
+            "--name-only",
+            "--relative",
+            "--no-renames",
+            "--",
+            ".",
+        ],
+    )
+    .await?;
+    let mut counts = HashMap::new();
+    for path in log.lines().filter(|line| !line.is_empty()) {
+        *counts.entry(path.to_string()).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Render the ranked tables under a one-line header.
+fn render(
+    survey: &Survey,
+    churn: Result<&HashMap<String, usize>, &String>,
+    path: &str,
+    top: usize,
+) -> String {
+    if survey.files.is_empty() {
+        return format!("No text files to measure under '{path}'.");
+    }
+    let mut out = format!(
+        "Hotspots under '{path}' (top {} of {} files):\n",
+        top.min(survey.files.len()),
+        survey.files.len()
+    );
+
+    let mut largest: Vec<&FileStats> = survey.files.iter().collect();
+    largest.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.path.cmp(&b.path)));
+    table(
+        &mut out,
+        "Largest files (lines)",
+        largest.iter().map(|f| (f.lines, f.path.clone())),
+        top,
+    );
+
+    let mut deepest: Vec<&FileStats> = survey.files.iter().filter(|f| f.depth > 0).collect();
+    deepest.sort_by(|a, b| b.depth.cmp(&a.depth).then_with(|| a.path.cmp(&b.path)));
+    table(
+        &mut out,
+        "Deepest nesting (indent levels)",
+        deepest
+            .iter()
+            .map(|f| (f.depth, format!("{}:{}", f.path, f.depth_line))),
+        top,
+    );
+
+    match churn {
+        Ok(churn) => {
+            let mut changed: Vec<(&FileStats, usize)> = survey
+                .files
+                .iter()
+                .filter_map(|f| churn.get(&f.path).map(|commits| (f, *commits)))
+                .collect();
+            changed.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.path.cmp(&b.0.path)));
+            let title = format!("Most changed (commits, of the last {CHURN_COMMITS})");
+            table(
+                &mut out,
+                &title,
+                changed
+                    .iter()
+                    .map(|(f, commits)| (*commits, f.path.clone())),
+                top,
+            );
+
+            changed.sort_by(|a, b| {
+                (b.0.lines * b.1)
+                    .cmp(&(a.0.lines * a.1))
+                    .then_with(|| a.0.path.cmp(&b.0.path))
+            });
+            table(
+                &mut out,
+                "Hotspots (lines x commits)",
+                changed.iter().map(|(f, commits)| {
+                    (
+                        f.lines * commits,
+                        format!(
+                            "{} ({} lines, {commits} {})",
+                            f.path,
+                            f.lines,
+                            if *commits == 1 { "commit" } else { "commits" }
+                        ),
+                    )
+                }),
+                top,
+            );
+        }
+        Err(reason) => {
+            let _ = write!(out, "\n[No churn counts: {reason}.]\n");
+        }
+    }
+
+    if survey.truncated {
+        let _ = write!(
+            out,
+            "\n[Stopped after {FILES_MAX} files; narrow path or include for complete tables.]\n"
+        );
+    }
+    out
+}
+
+/// One ranked table: a title, then `rank. value  label` rows.
+fn table(out: &mut String, title: &str, rows: impl Iterator<Item = (usize, String)>, top: usize) {
+    let rows: Vec<(usize, String)> = rows.take(top).collect();
+    let _ = write!(out, "\n{title}:\n");
+    if rows.is_empty() {
+        out.push_str("  (none)\n");
+        return;
+    }
+    let width = rows
+        .iter()
+        .map(|(value, _)| value.to_string().len())
+        .max()
+        .unwrap_or(1);
+    for (rank, (value, label)) in rows.iter().enumerate() {
+        let _ = writeln!(out, "  {:>2}. {value:>width$}  {label}", rank + 1);
+    }
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DummyToolContext, git, git_repo};
+    use tempfile::TempDir;
+
+    async fn run(path: &Path, include: Option<&str>, top: Option<usize>) -> ToolOutcome {
+        run_with(path, include, top, None).await
+    }
+
+    async fn run_with(
+        path: &Path,
+        include: Option<&str>,
+        top: Option<usize>,
+        hidden: Option<bool>,
+    ) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        HotspotsTool::default()
+            .execute(
+                &mut ctx,
+                HotspotsInput {
+                    path: path.display().to_string(),
+                    include: include.map(str::to_string),
+                    top,
+                    hidden,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    /// The lines of `body` under the table titled `title`.
+    fn section<'a>(body: &'a str, title: &str) -> Vec<&'a str> {
+        body.lines()
+            .skip_while(|line| !line.starts_with(title))
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn nesting_follows_the_indent_unit() {
+        let text = "fn a() {\n    if x {\n        y();\n    }\n}\n";
+        assert_eq!(nesting(text), (2, 3));
+        assert_eq!(nesting("a:\n\tb:\n\t\tc\n"), (2, 3));
+        // One-space alignment doesn't make every column a level.
+        assert_eq!(nesting("x(a,\n b)\n"), (0, 0));
+        assert_eq!(nesting("flat\n"), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn ranks_size_nesting_and_churn() {
+        let dir = git_repo();
+        fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir_all(dir.path().join("target")).unwrap();
+        fs::write(dir.path().join("target/big.rs"), "x\n".repeat(500)).unwrap();
+        fs::write(dir.path().join("big.rs"), "x\n".repeat(100)).unwrap();
+        fs::write(dir.path().join("deep.rs"), "a\n  b\n    c\n      d\n").unwrap();
+        for round in 0..3 {
+            fs::write(dir.path().join("busy.rs"), "y\n".repeat(40 + round)).unwrap();
+            git(dir.path(), &["add", "-A"]);
+            git(dir.path(), &["commit", "-q", "-m", "change"]);
+        }
+
+        let body = text(&run(dir.path(), Some("*.rs"), Some(2)).await);
+        assert!(body.starts_with("Hotspots under"), "{body}");
+        assert!(body.contains("(top 2 of 3 files)"), "{body}");
+        let largest = section(&body, "Largest files");
+        assert_eq!(largest.len(), 2, "{body}");
+        assert!(largest[0].ends_with("100  big.rs"), "{body}");
+        assert!(section(&body, "Deepest nesting")[0].ends_with("3  deep.rs:4"));
+        assert!(
+            section(&body, "Most changed")[0].ends_with("3  busy.rs"),
+            "{body}"
+        );
+        assert!(
+            section(&body, "Hotspots (lines x commits)")[0]
+                .ends_with("busy.rs (42 lines, 3 commits)"),
+            "{body}"
+        );
+        assert!(!body.contains("target"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn without_git_the_churn_tables_are_left_out() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "x\n").unwrap();
+        let body = text(&run(dir.path(), None, None).await);
+        assert!(body.contains("Largest files"), "{body}");
+        assert!(!body.contains("Most changed"), "{body}");
+        assert!(body.contains("[No churn counts:"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn hidden_files_are_ranked_only_on_request() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "x\n").unwrap();
+        fs::create_dir_all(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/ci.yml"), "a\nb\n").unwrap();
+
+        let body = text(&run(dir.path(), None, None).await);
+        assert!(!body.contains("ci.yml"), "{body}");
+        let body = text(&run_with(dir.path(), None, None, Some(true)).await);
+        assert!(body.contains(".github/ci.yml"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn rejects_files_and_zero_top() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("a.txt");
+        fs::write(&file, "x\n").unwrap();
+        let outcome = run(&file, None, None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("is not a directory"));
+        assert!(run(dir.path(), None, Some(0)).await.is_error);
+    }
+}
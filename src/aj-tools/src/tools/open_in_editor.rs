@@ -0,0 +1,96 @@
+//! `open_in_editor` builtin — jumps the user's editor to a file and line.
+//!
+//! Opening an editor is a front-end concern, so this tool does nothing
+//! by itself: the interactive TUI installs a
+//! [`aj_agent::hooks::BeforeToolCallHook`] that short-circuits the call
+//! and launches the user's editor instead. Everywhere else (print mode,
+//! sub-agents) the call reaches [`OpenInEditorTool::execute`], which
+//! returns a recoverable `is_error: true` outcome telling the model to
+//! cite the location in its reply instead.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DESCRIPTION: &str = r#"
+Open a file in the user's editor, optionally at a specific line.
+
+Usage:
+
+- The path parameter must be an absolute path to an existing file
+- line is 1-based; omit it to open the file at the top
+- Use this when you want the user to look at a specific spot (a bug you
+  found, the code a question is about) rather than pasting the path
+- Only works in the interactive terminal UI; elsewhere nothing is opened
+  and you should cite path:line in your reply instead
+"#;
+
+/// Message returned when no front end is around to open the editor.
+pub const NOT_INTERACTIVE_MESSAGE: &str = "open_in_editor only works in the interactive terminal \
+     UI; nothing was opened. Cite the path and line in your reply instead.";
+
+#[derive(Clone)]
+pub struct OpenInEditorTool;
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct OpenInEditorInput {
+    /// The absolute path to the file to open.
+    pub path: String,
+    /// The 1-based line to jump to.
+    #[serde(default)]
+    pub line: Option<usize>,
+}
+
+impl ToolDefinition for OpenInEditorTool {
+    type Input = OpenInEditorInput;
+
+    fn name(&self) -> &'static str {
+        "open_in_editor"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    async fn execute(
+        &self,
+        _ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(NOT_INTERACTIVE_MESSAGE)],
+            details: ToolDetails::Text {
+                summary: input.path,
+                body: NOT_INTERACTIVE_MESSAGE.to_string(),
+            },
+            is_error: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+
+    #[tokio::test]
+    async fn execute_without_a_front_end_is_a_noop_with_a_clear_message() {
+        let mut ctx = DummyToolContext::default();
+        let outcome = OpenInEditorTool
+            .execute(
+                &mut ctx,
+                OpenInEditorInput {
+                    path: "/tmp/a.rs".to_string(),
+                    line: Some(3),
+                },
+            )
+            .await
+            .expect("execute");
+        assert!(outcome.is_error);
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => assert_eq!(t.text, NOT_INTERACTIVE_MESSAGE),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+}
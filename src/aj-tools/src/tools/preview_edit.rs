@@ -0,0 +1,230 @@
+//! `preview_edit` builtin — `edit_file` without the write.
+//!
+//! Takes exactly `edit_file`'s input and runs the same validation and
+//! replacement in memory (see [`EditFileTool::plan`]), including the
+//! `normalize_on_write`, `respect_editorconfig` and `max_file_size`
+//! settings, so the preview matches what the real edit would do.
+//! Nothing is written to disk.
+//!
+//! Returns a [`ToolOutcome`] whose `details` is [`ToolDetails::Diff`],
+//! so the user sees the proposed change rendered like a real edit, and
+//! whose wire `content` carries a unified diff of the change for the
+//! model. A call that `edit_file` would reject (no match, ambiguous
+//! match, unreadable file) comes back as the same recoverable
+//! `is_error: true` outcome. The tool only reads, so it keeps the
+//! default parallel execution mode.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+
+use crate::roots::AllowedRoots;
+use crate::tools::edit_file::{EditFileInput, EditFileTool, PlannedEdit};
+
+const DESCRIPTION: &str = r#"
+Preview an exact string replacement without applying it.
+
+Usage:
+
+- Takes the same arguments as edit_file and applies the same rules (absolute
+  path, old_string must match exactly once unless replace_all is set)
+- Nothing is written to disk; the result is a unified diff of what edit_file
+  would change
+- Use it to check an edit you are unsure about, then call edit_file with the
+  same arguments to apply it
+"#;
+
+/// Lines of unchanged context around each hunk of the returned diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Clone, Default)]
+pub struct PreviewEditTool {
+    /// The edit tool whose planning the preview runs, carrying the same
+    /// settings as the live `edit_file`.
+    edit: EditFileTool,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl PreviewEditTool {
+    /// Construct with an explicit `normalize_on_write` policy, matching
+    /// [`EditFileTool::with_normalize_on_write`].
+    pub fn with_normalize_on_write(normalize_on_write: bool) -> Self {
+        Self {
+            edit: EditFileTool::with_normalize_on_write(normalize_on_write),
+            allowed_roots: AllowedRoots::default(),
+        }
+    }
+
+    /// Adjust to `.editorconfig` like the live tool, matching
+    /// [`EditFileTool::with_editorconfig`].
+    pub fn with_editorconfig(mut self, editorconfig: bool) -> Self {
+        self.edit = self.edit.with_editorconfig(editorconfig);
+        self
+    }
+
+    /// Replace the file size cap, matching
+    /// [`EditFileTool::with_max_file_size`].
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.edit = self.edit.with_max_file_size(max_file_size);
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`, matching
+    /// [`EditFileTool::with_allowed_roots`].
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+impl ToolDefinition for PreviewEditTool {
+    type Input = EditFileInput;
+
+    fn name(&self) -> &'static str {
+        "preview_edit"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let PlannedEdit {
+            original_content,
+            new_content,
+            normalized,
+            editorconfig_applied,
+        } = match self.edit.plan(&input) {
+            Ok(plan) => plan,
+            Err(message) => return Ok(error_outcome(&input.path, message)),
+        };
+
+        let display_path = display_relative(path, &ctx.working_directory());
+        let diff = TextDiff::from_lines(&original_content, &new_content)
+            .unified_diff()
+            .context_radius(DIFF_CONTEXT_LINES)
+            .header(&display_path, &display_path)
+            .to_string();
+
+        let mut return_value = format!(
+            "Preview only; nothing was written. edit_file with the same arguments would change '{}':\n\n{diff}",
+            input.path
+        );
+        if normalized {
+            return_value
+                .push_str("\nNote: trailing whitespace would be trimmed from the inserted lines.");
+        }
+        if editorconfig_applied {
+            return_value
+                .push_str("\nNote: the content would be adjusted to the project's .editorconfig.");
+        }
+
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(return_value)],
+            details: ToolDetails::Diff {
+                path: display_path,
+                before: original_content,
+                after: new_content,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `edit_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use std::fs;
+    use tempfile::TempDir;
+
+    async fn preview(path: &Path, old: &str, new: &str) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        PreviewEditTool::default()
+            .execute(
+                &mut ctx,
+                EditFileInput {
+                    path: path.display().to_string(),
+                    old_string: old.to_string(),
+                    new_string: new.to_string(),
+                    replace_all: false,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn shows_the_diff_and_leaves_the_file_alone() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, "alpha\nbeta\ngamma\n").unwrap();
+
+        let outcome = preview(&path, "beta", "BETA").await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        assert!(body.starts_with("Preview only"), "{body}");
+        assert!(body.contains("-beta\n+BETA\n"), "{body}");
+        match &outcome.details {
+            ToolDetails::Diff { before, after, .. } => {
+                assert_eq!(before, "alpha\nbeta\ngamma\n");
+                assert_eq!(after, "alpha\nBETA\ngamma\n");
+            }
+            other => panic!("expected Diff details, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "alpha\nbeta\ngamma\n");
+    }
+
+    #[tokio::test]
+    async fn rejects_what_edit_file_would_reject() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, "x x\n").unwrap();
+
+        let outcome = preview(&path, "x", "y").await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("Found 2 occurrences"));
+
+        let outcome = preview(&path, "z", "y").await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("No occurrences"));
+    }
+}
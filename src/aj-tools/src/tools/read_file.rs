@@ -14,6 +14,23 @@
 //! byte budget the body becomes an escape pointing at a
 //! `sed | head -c` fallback.
 //!
+//! `offset` is the 1-based number of the first line returned — the
+//! same number the wire body prefixes it with — and `limit` the line
+//! count. An `offset` past the last line yields a note saying so
+//! rather than an empty body; a `limit` of 0 is refused.
+//!
+//! Gzip-compressed text (a `.gz` extension or the gzip magic bytes) is
+//! decompressed on the fly and then paged like any other text file;
+//! see [`crate::gzip`] for the size cap.
+//!
+//! Files over the `max_file_size` cap (see [`crate::file_size`]) are
+//! refused before anything is read.
+//!
+//! A path that doesn't exist is looked up in the working directory
+//! (see [`crate::fuzzy_path`]): the error lists near-miss candidates,
+//! and with `with_fuzzy_paths(true)` a unique best match is read
+//! instead, behind a note naming the correction.
+//!
 //! For supported image files (PNG, JPEG, GIF, WebP): returns a
 //! [`ToolOutcome`] with [`ToolDetails::Image`]. The `content` is a
 //! short text annotation followed by a [`UserContent::Image`]
@@ -28,23 +45,34 @@ use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::{fs, path::PathBuf};
 
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fuzzy_path;
+use crate::gzip;
 use crate::image::{self, ResizeOptions, ResizedImage};
+use crate::roots::AllowedRoots;
 use crate::truncate::{READ_MAX_BYTES, READ_MAX_LINES, TruncatedBy, format_size, truncate_head};
 
 const DESCRIPTION: &str = r#"
 Read the contents of a file from the local file system. If a file does not exist
-an error will be returned.
+an error will be returned, listing similarly named files in the project.
 
 Usage:
 
 - The path parameter must be an absolute path
 - Supports text files and images (PNG, JPEG, GIF, WebP). Images are returned as
   attachments; the offset/limit parameters do not apply to images.
+- Gzip-compressed files (e.g. *.log.gz) are decompressed transparently;
+  offset/limit count lines of the decompressed text.
 - For text files: results include line numbers, starting at 1. Output is capped
   at 2000 lines or 50KB (whichever fires first). When the cap is hit, the
   result tells you the next offset to continue from.
 - You can specify an offset and a limit but it's usually better to read the
   whole file. Use this for reading very big files
+- offset is the number of the first line to return, exactly as shown in the
+  line prefix of a previous result (1-based); limit is how many lines to
+  return from there. To read lines 40-60, pass offset=40 and limit=21. An
+  offset past the end of the file returns a note saying so; a limit past the
+  end returns the lines that exist.
 "#;
 
 #[derive(Clone)]
@@ -53,19 +81,56 @@ pub struct ReadFileTool {
     /// before attaching them to tool results. When `false`, the
     /// raw source bytes are base64-encoded and attached as-is.
     auto_resize: bool,
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Whether a missing path is replaced by its unique best near-miss
+    /// match rather than only suggesting candidates.
+    fuzzy_paths: bool,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
 }
 
 impl ReadFileTool {
-    /// Construct with the default policy: auto-resize enabled.
+    /// Construct with the default policy: auto-resize enabled and the
+    /// [`DEFAULT_MAX_FILE_SIZE`] cap.
     pub fn new() -> Self {
-        Self { auto_resize: true }
+        Self {
+            auto_resize: true,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            fuzzy_paths: false,
+            allowed_roots: AllowedRoots::default(),
+        }
     }
 
     /// Construct with an explicit resize policy. `false` skips the
     /// inline budget enforcement entirely; see
     /// [`crate::image::passthrough_image`] for the trade-off.
     pub fn with_auto_resize(auto_resize: bool) -> Self {
-        Self { auto_resize }
+        Self {
+            auto_resize,
+            ..Self::new()
+        }
+    }
+
+    /// Replace the file size cap.
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Read the unique best match for a path that doesn't exist,
+    /// instead of failing with a list of candidates. Off by default.
+    pub fn with_fuzzy_paths(mut self, fuzzy_paths: bool) -> Self {
+        self.fuzzy_paths = fuzzy_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
     }
 }
 
@@ -79,10 +144,10 @@ impl Default for ReadFileTool {
 pub struct ReadFileInput {
     /// The absolute path to the file to read.
     path: String,
-    /// The line number to start reading from (1-indexed). If not provided, starts from the beginning.
+    /// The line number to start reading from (1-indexed, as shown in the output's line prefix; 0 is treated as 1). If not provided, starts from the beginning.
     #[serde(default)]
     offset: Option<usize>,
-    /// The number of lines to read. If not provided, reads all lines from offset to end.
+    /// The number of lines to read, at least 1. If not provided, reads all lines from offset to end.
     #[serde(default)]
     limit: Option<usize>,
 }
@@ -98,6 +163,10 @@ impl ToolDefinition for ReadFileTool {
         DESCRIPTION
     }
 
+    fn read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(
         &self,
         ctx: &mut dyn ToolContext,
@@ -111,7 +180,69 @@ impl ToolDefinition for ReadFileTool {
             ));
         }
 
-        let display_path_bare = display_relative(path, &ctx.working_directory());
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if !path.exists() {
+            let wanted = path.to_path_buf();
+            let root = ctx.working_directory();
+            let resolution =
+                tokio::task::spawn_blocking(move || fuzzy_path::resolve(&wanted, &root)).await?;
+            if self.fuzzy_paths
+                && let Some(found) = resolution.unique
+            {
+                let note = format!(
+                    "[File '{}' does not exist; read '{}' instead.]",
+                    input.path,
+                    found.display()
+                );
+                let corrected = ReadFileInput {
+                    path: found.display().to_string(),
+                    ..input
+                };
+                let outcome = self.read(corrected, &ctx.working_directory()).await?;
+                return Ok(with_note(outcome, note));
+            }
+            if !resolution.candidates.is_empty() {
+                let list: Vec<String> = resolution
+                    .candidates
+                    .iter()
+                    .map(|c| format!("- {}", c.display()))
+                    .collect();
+                return Ok(error_outcome(
+                    &input.path,
+                    format!(
+                        "File '{}' does not exist. Did you mean:\n{}",
+                        input.path,
+                        list.join("\n")
+                    ),
+                ));
+            }
+        }
+
+        self.read(input, &ctx.working_directory()).await
+    }
+}
+
+impl ReadFileTool {
+    /// Read `input.path`, already checked to be absolute; `cwd` is
+    /// the working directory display paths are made relative to.
+    async fn read(
+        &self,
+        input: ReadFileInput,
+        cwd: &Path,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        let hint = format!(
+            "Read a range with bash instead, e.g. sed -n '1,200p' {0} or head -c {READ_MAX_BYTES} {0}.",
+            input.path
+        );
+        if let Err(message) = check_file_size(path, self.max_file_size, &hint) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let display_path_bare = display_relative(path, cwd);
         if let Some(source_mime) = image::detect_mime_type_from_file(path) {
             // Non-vision warning omitted: `aj_models::transform` already substitutes
             // a placeholder when the target model can't see images, so the model
@@ -131,7 +262,14 @@ impl ToolDefinition for ReadFileTool {
             .await);
         }
 
-        let content = match fs::read_to_string(&input.path) {
+        if input.limit == Some(0) {
+            return Ok(error_outcome(
+                &display_path_bare,
+                "limit must be at least 1: it is the number of lines to return".to_string(),
+            ));
+        }
+
+        let content = match gzip::read_text_file(path) {
             Ok(content) => content,
             Err(e) => {
                 return Ok(error_outcome(
@@ -144,24 +282,38 @@ impl ToolDefinition for ReadFileTool {
         let lines: Vec<&str> = content.lines().collect();
         let total_file_lines = lines.len();
 
-        // The model's `offset`/`limit` describe a slice over `lines`.
-        // We apply both before truncation so a small explicit limit
-        // wins over our auto-cap.
-        let start_idx = input.offset.map(|o| o.saturating_sub(1)).unwrap_or(0);
+        // The model's `offset`/`limit` describe a slice over `lines`:
+        // `offset` is the 1-based number of the first line (the number
+        // the wire body prefixes it with), `limit` the line count. We
+        // apply both before truncation so a small explicit limit wins
+        // over our auto-cap.
+        let start_idx = input.offset.unwrap_or(1).max(1) - 1;
         let user_limited = input.limit.is_some();
         let user_end_idx = match input.limit {
             Some(limit) => (start_idx + limit).min(lines.len()),
             None => lines.len(),
         };
 
-        // Out-of-range offset: empty body, no line-range suffix, no
-        // footer.
+        // Out-of-range offset: say so instead of returning an empty
+        // body the model could mistake for blank lines. Not an error —
+        // the read worked, there is just nothing there. An empty file
+        // read without an offset stays an empty body.
         if start_idx >= lines.len() {
+            let body = match (input.offset, total_file_lines) {
+                (None, _) => String::new(),
+                (Some(offset), 0) => {
+                    format!("[offset={offset} is past the end of the file: the file is empty.]")
+                }
+                (Some(offset), total) => format!(
+                    "[offset={offset} is past the end of the file, which has {total} lines. \
+                     Use an offset between 1 and {total}.]"
+                ),
+            };
             return Ok(ToolOutcome {
-                content: vec![UserContent::text(String::new())],
+                content: vec![UserContent::text(body.clone())],
                 details: ToolDetails::Text {
                     summary: display_path_bare,
-                    body: String::new(),
+                    body,
                 },
                 is_error: false,
             });
@@ -277,6 +429,16 @@ fn display_relative(path: &Path, cwd: &Path) -> String {
     path.strip_prefix(cwd).unwrap_or(path).display().to_string()
 }
 
+/// Put `note` in front of a successful read's result, for the model as
+/// its own text block and for the user at the top of a text body.
+fn with_note(mut outcome: ToolOutcome, note: String) -> ToolOutcome {
+    if let ToolDetails::Text { body, .. } = &mut outcome.details {
+        *body = format!("{note}\n\n{body}");
+    }
+    outcome.content.insert(0, UserContent::text(note));
+    outcome
+}
+
 /// Build a `ToolOutcome` for a recoverable error. The model gets the
 /// human-readable error string as the tool result and `is_error: true`
 /// so it can correct the call; the user sees the same string in the
@@ -446,6 +608,7 @@ pub fn format_for_display(lines: &[&str]) -> String {
 mod tests {
     use super::*;
     use crate::testing::DummyToolContext;
+    use aj_agent::tool::ExecutionMode;
     use aj_models::types::UserContent;
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -588,6 +751,172 @@ mod tests {
         }
     }
 
+    /// Run a read of a file holding `line 1` ..= `line 10` with the
+    /// given `offset` / `limit`.
+    async fn read_ten_lines(offset: Option<usize>, limit: Option<usize>) -> ToolOutcome {
+        let mut file = NamedTempFile::new().expect("temp file");
+        for i in 1..=10 {
+            writeln!(file, "line {i}").unwrap();
+        }
+        let mut ctx = DummyToolContext::default();
+        ReadFileTool::new()
+            .execute(
+                &mut ctx,
+                ReadFileInput {
+                    path: file.path().display().to_string(),
+                    offset,
+                    limit,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    /// `offset` is the number of the first line returned, as shown in
+    /// the wire prefix, and `limit` the number of lines; a limit past
+    /// the end returns what exists without a continuation footer.
+    #[tokio::test]
+    async fn offset_and_limit_matrix() {
+        // (offset, limit, first line, last line, continuation offset)
+        let cases = [
+            (None, None, 1, 10, None),
+            (Some(1), None, 1, 10, None),
+            (Some(0), Some(2), 1, 2, Some(3)),
+            (Some(4), None, 4, 10, None),
+            (Some(10), None, 10, 10, None),
+            (Some(4), Some(3), 4, 6, Some(7)),
+            (Some(8), Some(3), 8, 10, None),
+            (Some(8), Some(100), 8, 10, None),
+            (None, Some(10), 1, 10, None),
+        ];
+        for (offset, limit, first, last, next) in cases {
+            let outcome = read_ten_lines(offset, limit).await;
+            assert!(!outcome.is_error);
+            let wire = extract_text(&outcome.content);
+            let numbered: Vec<&str> = wire.lines().take_while(|l| !l.is_empty()).collect();
+            let case = format!("offset={offset:?} limit={limit:?}: {wire:?}");
+            assert_eq!(numbered.len(), last - first + 1, "{case}");
+            assert_eq!(numbered[0], format!("{first:>5}: line {first}"), "{case}");
+            assert_eq!(
+                numbered[numbered.len() - 1],
+                format!("{last:>5}: line {last}"),
+                "{case}"
+            );
+            match next {
+                Some(next) => assert!(
+                    wire.ends_with(&format!("offset={next} to continue.]")),
+                    "{case}"
+                ),
+                None => assert!(!wire.contains("to continue"), "{case}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn offset_past_end_of_file_says_so() {
+        for offset in [11, 50] {
+            let outcome = read_ten_lines(Some(offset), None).await;
+            assert!(!outcome.is_error);
+            assert_eq!(
+                extract_text(&outcome.content),
+                format!(
+                    "[offset={offset} is past the end of the file, which has 10 lines. \
+                     Use an offset between 1 and 10.]"
+                )
+            );
+        }
+
+        let file = NamedTempFile::new().expect("temp file");
+        let mut ctx = DummyToolContext::default();
+        let read = |offset| ReadFileInput {
+            path: file.path().display().to_string(),
+            offset,
+            limit: None,
+        };
+        let tool = ReadFileTool::new();
+        let outcome = tool.execute(&mut ctx, read(Some(1))).await.unwrap();
+        assert_eq!(
+            extract_text(&outcome.content),
+            "[offset=1 is past the end of the file: the file is empty.]"
+        );
+        // Reading an empty file whole stays an empty result.
+        let outcome = tool.execute(&mut ctx, read(None)).await.unwrap();
+        assert_eq!(extract_text(&outcome.content), "");
+    }
+
+    #[tokio::test]
+    async fn zero_limit_is_rejected() {
+        let outcome = read_ten_lines(Some(3), Some(0)).await;
+        assert!(outcome.is_error);
+        assert!(
+            extract_text(&outcome.content).contains("limit must be at least 1"),
+            "{:?}",
+            outcome.content
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_reads_gzipped_text_with_offset_and_limit() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let path = dir.path().join("app.log.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        for i in 1..=10 {
+            writeln!(encoder, "line {i}").unwrap();
+        }
+        std::fs::write(&path, encoder.finish().unwrap()).expect("write gz");
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = ReadFileTool::new()
+            .execute(
+                &mut ctx,
+                ReadFileInput {
+                    path: path.display().to_string(),
+                    offset: Some(9),
+                    limit: Some(5),
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        assert!(wire.contains("    9: line 9"), "wire: {wire:?}");
+        assert!(wire.contains("   10: line 10"), "wire: {wire:?}");
+        assert!(!wire.contains("line 8"), "wire: {wire:?}");
+    }
+
+    #[tokio::test]
+    async fn file_over_the_size_cap_points_at_a_range_read() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        writeln!(file, "{}", "x".repeat(99)).unwrap();
+        let path = file.path().display().to_string();
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = ReadFileTool::new()
+            .with_max_file_size(10)
+            .execute(
+                &mut ctx,
+                ReadFileInput {
+                    path: path.clone(),
+                    offset: None,
+                    limit: None,
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(outcome.is_error);
+        let wire = extract_text(&outcome.content);
+        assert!(
+            wire.contains("is too large (100 bytes, limit 10)"),
+            "wire: {wire:?}"
+        );
+        assert!(
+            wire.contains(&format!("sed -n '1,200p' {path}")),
+            "wire: {wire:?}"
+        );
+    }
+
     #[tokio::test]
     async fn relative_path_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
@@ -612,6 +941,52 @@ mod tests {
         }
     }
 
+    /// Neither `..` traversal nor a symlink out of the allowed root
+    /// reads a file beyond it.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn traversal_and_symlink_escapes_are_refused() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let repo = dir.path().join("repo");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret.txt"), "secret\n").unwrap();
+        std::os::unix::fs::symlink(&outside, repo.join("link")).unwrap();
+
+        let tool = ReadFileTool::new().with_allowed_roots(AllowedRoots::new([repo.clone()]));
+        let mut ctx = DummyToolContext {
+            working_directory: repo.clone(),
+            ..DummyToolContext::default()
+        };
+        for escape in [
+            repo.join("../outside/secret.txt"),
+            repo.join("link/secret.txt"),
+        ] {
+            let outcome = tool
+                .execute(
+                    &mut ctx,
+                    ReadFileInput {
+                        path: escape.display().to_string(),
+                        offset: None,
+                        limit: None,
+                    },
+                )
+                .await
+                .expect("execute");
+            assert!(outcome.is_error, "{}", escape.display());
+            match &outcome.details {
+                ToolDetails::Text { body, .. } => {
+                    assert!(
+                        body.contains("outside the allowed directories"),
+                        "body: {body:?}"
+                    );
+                }
+                other => panic!("expected Text details, got {other:?}"),
+            }
+        }
+    }
+
     #[tokio::test]
     async fn missing_file_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
@@ -636,6 +1011,44 @@ mod tests {
         }
     }
 
+    /// A missing path lists near-miss candidates from the working
+    /// directory; with fuzzy paths on, a unique best match is read.
+    #[tokio::test]
+    async fn missing_file_suggests_or_reads_a_near_miss() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src/bin")).unwrap();
+        fs::write(dir.path().join("src/bin/main.rs"), "fn main() {}\n").unwrap();
+        let found = dir.path().join("src/bin/main.rs").display().to_string();
+        let mut ctx = DummyToolContext {
+            working_directory: dir.path().to_path_buf(),
+            ..DummyToolContext::default()
+        };
+        let input = || ReadFileInput {
+            path: dir.path().join("src/main.rs").display().to_string(),
+            offset: None,
+            limit: None,
+        };
+
+        let outcome = ReadFileTool::new()
+            .execute(&mut ctx, input())
+            .await
+            .expect("execute");
+        assert!(outcome.is_error);
+        let text = extract_text(&outcome.content);
+        assert!(text.contains("Did you mean:"), "{text}");
+        assert!(text.contains(&format!("- {found}")), "{text}");
+
+        let outcome = ReadFileTool::new()
+            .with_fuzzy_paths(true)
+            .execute(&mut ctx, input())
+            .await
+            .expect("execute");
+        assert!(!outcome.is_error);
+        let text = extract_text(&outcome.content);
+        assert!(text.contains(&format!("read '{found}' instead")), "{text}");
+        assert!(text.contains("    1: fn main() {}"), "{text}");
+    }
+
     /// A file longer than `READ_MAX_LINES` triggers the line-limited
     /// footer and tells the model the next offset.
     #[tokio::test]
@@ -1104,4 +1517,14 @@ mod tests {
             "passthrough must not emit the omission placeholder: {annotation:?}"
         );
     }
+
+    #[test]
+    fn execution_mode_is_parallel() {
+        // Read-only: several `read_file` calls in one assistant message
+        // run concurrently instead of queueing behind each other.
+        assert_eq!(
+            ReadFileTool::default().execution_mode(),
+            ExecutionMode::Parallel
+        );
+    }
 }
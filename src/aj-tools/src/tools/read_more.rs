@@ -0,0 +1,205 @@
+//! `read_more` builtin — pages through a stashed tool output.
+//!
+//! Tools that cut their result down for the model (e.g. `run_tests`
+//! returning the tail of a log) keep the full text behind a token via
+//! [`ToolContext::stash_output`] and name it in their footer. This tool
+//! reads that text back a page at a time, with `offset` / `limit`
+//! counted in lines exactly like `read_file`, under the same
+//! line/byte budgets.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the token and page
+//! range. An unknown or evicted token is a recoverable
+//! `is_error: true` outcome.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::truncate::{READ_MAX_BYTES, READ_MAX_LINES, truncate_head};
+
+const DESCRIPTION: &str = r#"
+Read more of a tool output that was truncated.
+
+Usage:
+
+- When a tool result says its full output is available through read_more, pass
+  the token it names to read the rest
+- offset is the line to start from (1-indexed, default 1) and limit the number
+  of lines to return; like read_file, a page is capped at 2000 lines or 50KB and
+  ends with the offset to continue from
+- Outputs are kept for a limited time: only the most recent ones can be read
+"#;
+
+#[derive(Clone)]
+pub struct ReadMoreTool;
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct ReadMoreInput {
+    /// The token named by the truncated tool result.
+    pub token: String,
+    /// The line number to start reading from (1-indexed). If not provided, starts from the beginning.
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// The number of lines to read. If not provided, reads up to the page cap.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ToolDefinition for ReadMoreTool {
+    type Input = ReadMoreInput;
+
+    fn name(&self) -> &'static str {
+        "read_more"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let Some(output) = ctx.stashed_output(&input.token) else {
+            return Ok(error_outcome(
+                &input.token,
+                format!(
+                    "No output is stored under token '{}'; it may have been evicted. Re-run the \
+                     original tool call instead.",
+                    input.token
+                ),
+            ));
+        };
+
+        let lines: Vec<&str> = output.lines().collect();
+        let total = lines.len();
+        let start = input.offset.unwrap_or(1).max(1) - 1;
+        if start >= total.max(1) {
+            return Ok(error_outcome(
+                &input.token,
+                format!(
+                    "Offset {} is beyond the end of output '{}' ({total} lines).",
+                    start + 1,
+                    input.token
+                ),
+            ));
+        }
+        let end = match input.limit {
+            Some(limit) => (start + limit.max(1)).min(total),
+            None => total,
+        };
+        let raw = lines[start..end].join("\n");
+        let trunc = truncate_head(&raw, READ_MAX_LINES, READ_MAX_BYTES);
+
+        // A single line over the byte budget: show its head rather
+        // than nothing, and move on to the next line.
+        let (page, shown) = if trunc.first_line_exceeds_limit {
+            let line = lines[start];
+            let mut cut = READ_MAX_BYTES;
+            while !line.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            (format!("{}… [line truncated]", &line[..cut]), 1)
+        } else {
+            (trunc.content, trunc.output_lines.max(1))
+        };
+        let last = start + shown;
+        let mut text = page;
+        if last < total {
+            text.push_str(&format!(
+                "\n\n[Showing lines {}-{last} of {total}. Use read_more with token '{}' and \
+                 offset={} to continue.]",
+                start + 1,
+                input.token,
+                last + 1
+            ));
+        }
+
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(text.clone())],
+            details: ToolDetails::Text {
+                summary: format!("{} {}:{last}", input.token, start + 1),
+                body: text,
+            },
+            is_error: false,
+        })
+    }
+}
+
+fn error_outcome(token: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: token.to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+
+    async fn run(
+        ctx: &mut DummyToolContext,
+        token: &str,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> ToolOutcome {
+        ReadMoreTool
+            .execute(
+                ctx,
+                ReadMoreInput {
+                    token: token.to_string(),
+                    offset,
+                    limit,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pages_through_a_stashed_output() {
+        let mut ctx = DummyToolContext::default();
+        let output: Vec<String> = (1..=10).map(|i| format!("line {i}")).collect();
+        let token = ctx.stash_output(output.join("\n"));
+
+        let first = run(&mut ctx, &token, None, Some(4)).await;
+        assert!(!first.is_error);
+        assert_eq!(
+            text(&first),
+            format!(
+                "line 1\nline 2\nline 3\nline 4\n\n[Showing lines 1-4 of 10. Use read_more with \
+                 token '{token}' and offset=5 to continue.]"
+            )
+        );
+
+        let rest = run(&mut ctx, &token, Some(5), None).await;
+        assert_eq!(text(&rest), output[4..].join("\n"));
+    }
+
+    #[tokio::test]
+    async fn unknown_tokens_and_offsets_past_the_end_are_errors() {
+        let mut ctx = DummyToolContext::default();
+        let missing = run(&mut ctx, "out-7", None, None).await;
+        assert!(missing.is_error);
+        assert!(text(&missing).contains("No output is stored under token 'out-7'"));
+
+        let token = ctx.stash_output("only\nthree\nlines".to_string());
+        let past = run(&mut ctx, &token, Some(4), None).await;
+        assert!(past.is_error);
+        assert!(text(&past).contains("beyond the end"));
+    }
+}
@@ -0,0 +1,378 @@
+//! `read_multi` builtin — line ranges from several text files in one
+//! call.
+//!
+//! Each requested file becomes one section: a `==> path (lines a-b of
+//! N) <==` header followed by its selected lines, numbered with their
+//! original line numbers in `read_file`'s `{:>5}: ` format. A file
+//! that can't be read (missing, not absolute, an image, a bad range)
+//! turns into an error line in its own section rather than failing
+//! the call, so one typo doesn't cost the other files; the outcome is
+//! only `is_error: true` when every file failed.
+//!
+//! Gzip-compressed files are decompressed on the fly, as in
+//! `read_file`, and files over `max_file_size` are refused in their
+//! section before anything is read (see [`crate::file_size`]).
+//!
+//! The combined output shares `read_file`'s line and byte budgets via
+//! `truncate_head`; when they bite, a footer tells the model to narrow
+//! the ranges or split the request.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the file count
+//! (or the single display path) and whose `body` is the sectioned
+//! output.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::gzip;
+use crate::image;
+use crate::roots::AllowedRoots;
+use crate::truncate::{READ_MAX_BYTES, READ_MAX_LINES, format_size, truncate_head};
+
+const DESCRIPTION: &str = r#"
+Read line ranges from several text files in one call.
+
+Usage:
+
+- Each entry's path must be an absolute path to a text file
+- start / end (1-based, inclusive) select a range; omit them to read the
+  whole file
+- Each file is returned under a "==> path (lines a-b of N) <==" header with
+  its original line numbers
+- A file that can't be read gets an error line in its section; the other
+  files are still returned
+- Gzip-compressed files (e.g. *.log.gz) are decompressed transparently
+- At most 20 files per call, and the combined output shares read_file's cap
+  (2000 lines or 50KB). Use read_file for images or for paging through one
+  large file
+"#;
+
+/// Most files accepted in one call.
+const READ_MULTI_MAX_FILES: usize = 20;
+
+#[derive(Clone)]
+pub struct ReadMultiTool {
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl ReadMultiTool {
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+impl Default for ReadMultiTool {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            allowed_roots: AllowedRoots::default(),
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct ReadMultiFile {
+    /// The absolute path to the file to read.
+    pub path: String,
+    /// First line to read (1-based). Defaults to the start of the file.
+    #[serde(default)]
+    pub start: Option<usize>,
+    /// Last line to read (1-based, inclusive). Defaults to the end of
+    /// the file.
+    #[serde(default)]
+    pub end: Option<usize>,
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct ReadMultiInput {
+    /// The files to read, in the order their sections should appear.
+    pub files: Vec<ReadMultiFile>,
+}
+
+impl ToolDefinition for ReadMultiTool {
+    type Input = ReadMultiInput;
+
+    fn name(&self) -> &'static str {
+        "read_multi"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        if input.files.is_empty() {
+            return Ok(error_outcome(
+                "read_multi",
+                "files must list at least one file".to_string(),
+            ));
+        }
+        if input.files.len() > READ_MULTI_MAX_FILES {
+            return Ok(error_outcome(
+                "read_multi",
+                format!(
+                    "At most {READ_MULTI_MAX_FILES} files per call, got {}; split the request",
+                    input.files.len()
+                ),
+            ));
+        }
+
+        let cwd = ctx.working_directory();
+        let mut raw = String::new();
+        let mut failures = 0;
+        for (i, file) in input.files.iter().enumerate() {
+            if i > 0 {
+                raw.push_str("\n\n");
+            }
+            let display = display_relative(Path::new(&file.path), &cwd);
+            match read_section(file, self.max_file_size, &self.allowed_roots, &cwd) {
+                Ok((range, lines)) => {
+                    let _ = write!(raw, "==> {display} ({range}) <==");
+                    for (n, line) in lines {
+                        let _ = write!(raw, "\n{n:>5}: {line}");
+                    }
+                }
+                Err(message) => {
+                    failures += 1;
+                    let _ = write!(raw, "==> {display} <==\n[error: {message}]");
+                }
+            }
+        }
+
+        let trunc = truncate_head(&raw, READ_MAX_LINES, READ_MAX_BYTES);
+        let mut body = trunc.content;
+        if trunc.truncated {
+            let _ = write!(
+                body,
+                "\n\n[Output capped at {READ_MAX_LINES} lines / {}. Narrow the ranges or split the request.]",
+                format_size(READ_MAX_BYTES)
+            );
+        }
+
+        let summary = match &input.files[..] {
+            [only] => display_relative(Path::new(&only.path), &cwd),
+            files => format!("{} files", files.len()),
+        };
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text { summary, body },
+            is_error: failures == input.files.len(),
+        })
+    }
+}
+
+/// Read one entry's selected lines as `(line number, text)` pairs,
+/// plus the `lines a-b of N` label for its header.
+fn read_section(
+    file: &ReadMultiFile,
+    max_file_size: u64,
+    allowed_roots: &AllowedRoots,
+    cwd: &Path,
+) -> Result<(String, Vec<(usize, String)>), String> {
+    let path = Path::new(&file.path);
+    if !path.is_absolute() {
+        return Err(format!("Path must be absolute, got: {}", file.path));
+    }
+    allowed_roots.check(path, cwd)?;
+    if image::detect_mime_type_from_file(path).is_some() {
+        return Err("image files are not supported here; use read_file".to_string());
+    }
+    check_file_size(
+        path,
+        max_file_size,
+        "Read a range of it with bash (e.g. sed -n) instead.",
+    )?;
+    let content = gzip::read_text_file(path)
+        .map_err(|e| format!("Failed to read file '{}': {e}", file.path))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let total = lines.len();
+    if total == 0 && file.start.is_none() {
+        return Ok(("empty file".to_string(), Vec::new()));
+    }
+
+    let start = file.start.unwrap_or(1).max(1);
+    let end = file.end.unwrap_or(total).min(total);
+    if let Some(wanted_end) = file.end
+        && wanted_end < start
+    {
+        return Err(format!("end ({wanted_end}) is before start ({start})"));
+    }
+    if start > total {
+        return Err(format!(
+            "start {start} is past the end of the file ({total} lines)"
+        ));
+    }
+    let selected = lines[start - 1..end]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (start + i, (*line).to_string()))
+        .collect();
+    Ok((format!("lines {start}-{end} of {total}"), selected))
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `read_file`'s
+/// counterpart for the rationale.
+fn error_outcome(summary: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: summary.to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn entry(path: &Path, start: Option<usize>, end: Option<usize>) -> ReadMultiFile {
+        ReadMultiFile {
+            path: path.display().to_string(),
+            start,
+            end,
+        }
+    }
+
+    async fn read(files: Vec<ReadMultiFile>) -> ToolOutcome {
+        read_with(ReadMultiTool::default(), files).await
+    }
+
+    async fn read_with(tool: ReadMultiTool, files: Vec<ReadMultiFile>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        tool.execute(&mut ctx, ReadMultiInput { files })
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_ranges_from_each_file() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        fs::write(&a, "a1\na2\na3\na4\n").unwrap();
+        fs::write(&b, "b1\nb2\n").unwrap();
+
+        let outcome = read(vec![entry(&a, Some(2), Some(3)), entry(&b, None, None)]).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        assert!(
+            body.contains("a.txt (lines 2-3 of 4) <==\n    2: a2\n    3: a3\n"),
+            "{body}"
+        );
+        assert!(
+            body.contains("b.txt (lines 1-2 of 2) <==\n    1: b1\n    2: b2"),
+            "{body}"
+        );
+        assert!(!body.contains("a1") && !body.contains("a4"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn unreadable_files_get_their_own_error_section() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.txt");
+        fs::write(&a, "a1\n").unwrap();
+
+        let outcome = read(vec![
+            entry(&dir.path().join("missing.txt"), None, None),
+            entry(&a, Some(5), None),
+            entry(&a, None, None),
+        ])
+        .await;
+        assert!(!outcome.is_error);
+        let body = text(&outcome);
+        assert!(
+            body.contains("missing.txt <==\n[error: Failed to read"),
+            "{body}"
+        );
+        assert!(
+            body.contains("past the end of the file (1 lines)"),
+            "{body}"
+        );
+        assert!(body.ends_with("    1: a1"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn files_over_the_size_cap_get_an_error_section() {
+        let dir = TempDir::new().unwrap();
+        let big = dir.path().join("big.log");
+        fs::write(&big, "x\n".repeat(50)).unwrap();
+        let small = dir.path().join("small.txt");
+        fs::write(&small, "s1\n").unwrap();
+
+        let tool = ReadMultiTool::default().with_max_file_size(10);
+        let outcome = read_with(
+            tool,
+            vec![entry(&big, Some(1), Some(1)), entry(&small, None, None)],
+        )
+        .await;
+        assert!(!outcome.is_error);
+        let body = text(&outcome);
+        assert!(
+            body.contains("big.log <==\n[error: File ")
+                && body.contains("is too large (100 bytes, limit 10)"),
+            "{body}"
+        );
+        assert!(body.ends_with("    1: s1"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn all_failures_and_bad_requests_are_errors() {
+        let outcome = read(vec![entry(Path::new("relative.txt"), None, None)]).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("must be absolute"));
+
+        assert!(read(Vec::new()).await.is_error);
+
+        let dir = TempDir::new().unwrap();
+        let too_many = (0..=READ_MULTI_MAX_FILES)
+            .map(|_| entry(dir.path(), None, None))
+            .collect();
+        let outcome = read(too_many).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("split the request"));
+    }
+}
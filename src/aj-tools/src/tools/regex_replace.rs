@@ -0,0 +1,544 @@
+//! `regex_replace` builtin — regex replacement with capture groups on
+//! a single file.
+//!
+//! The regex counterpart of `edit_file`: `pattern` is a [`regex`]
+//! expression and `replacement` may reference its capture groups
+//! (`$1`, `${name}`), so matches can be reshaped rather than only
+//! swapped for a literal. At least one match is required, and `count`
+//! caps how many matches (in file order) are rewritten.
+//!
+//! Returns a [`ToolOutcome`] whose `details` is [`ToolDetails::Diff`]
+//! on success, like the other editing tools. The wire `content` is a
+//! summary naming how many matches were replaced plus a short
+//! per-match `line: old -> new` listing so the model can check that
+//! the groups expanded the way it meant.
+//!
+//! Recoverable errors (path-not-absolute, missing file, invalid
+//! regex, no match, read / write failure) come back as
+//! `is_error: true` outcomes carrying [`ToolDetails::Text`].
+//! [`execution_mode`] is [`ExecutionMode::Sequential`] because this
+//! tool mutates the filesystem.
+//!
+//! [`execution_mode`]: ToolDefinition::execution_mode
+
+use aj_agent::tool::{ExecutionMode, ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::editorconfig::{EDITORCONFIG_NOTE, apply_editorconfig};
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fs_retry;
+use crate::normalize::{INSERTED_NORMALIZED_NOTE, ends_line, normalize_inserted};
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+use crate::trash::Trash;
+
+const DESCRIPTION: &str = r#"
+Replace regex matches in a file, with capture-group references in the replacement.
+
+Usage:
+
+- The path parameter must be an absolute path
+- The file must exist
+- pattern is a regular expression (Rust regex syntax; no lookaround or backreferences)
+- replacement may reference capture groups as $1, $2, ... or ${name}. Use ${1}
+  when a group reference is followed by a letter, digit or underscore ($1a means
+  the group named "1a"). Write $$ for a literal $
+- The pattern must match at least once, otherwise the call fails
+- count limits how many matches are replaced, in file order; without it every
+  match is replaced
+- For plain literal replacements prefer edit_file
+"#;
+
+/// How many replacements the wire summary lists individually.
+const LISTED_REPLACEMENTS: usize = 10;
+
+/// Per-side character cap for a listed `old -> new` pair.
+const LISTED_TEXT_CHARS: usize = 80;
+
+#[derive(Clone)]
+pub struct RegexReplaceTool {
+    /// Trim trailing whitespace from the inserted text before writing;
+    /// see [`crate::normalize`]. Off by default.
+    normalize_on_write: bool,
+    /// Adjust the content to the target's `.editorconfig` before
+    /// writing; see [`crate::editorconfig`]. Off by default.
+    editorconfig: bool,
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Paths refused outright, whatever the content; see
+    /// [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+    /// Where the prior version goes before a write, `None` to keep
+    /// nothing; see [`crate::trash`].
+    trash: Option<Trash>,
+}
+
+impl RegexReplaceTool {
+    /// Construct with an explicit `normalize_on_write` policy.
+    pub fn with_normalize_on_write(normalize_on_write: bool) -> Self {
+        Self {
+            normalize_on_write,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse to modify paths matching the `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Copy a file's prior version into `trash` before overwriting it.
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Adjust written content to the target's `.editorconfig`.
+    pub fn with_editorconfig(mut self, editorconfig: bool) -> Self {
+        self.editorconfig = editorconfig;
+        self
+    }
+}
+
+impl Default for RegexReplaceTool {
+    fn default() -> Self {
+        Self {
+            normalize_on_write: false,
+            editorconfig: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: ProtectedPaths::default(),
+            allowed_roots: AllowedRoots::default(),
+            trash: None,
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct RegexReplaceInput {
+    /// The absolute path to the file to modify.
+    pub path: String,
+    /// The regular expression to search for.
+    pub pattern: String,
+    /// The replacement text; may reference capture groups as $1 or ${name}.
+    pub replacement: String,
+    /// Maximum number of matches to replace, in file order. Omit to replace all.
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+impl ToolDefinition for RegexReplaceTool {
+    type Input = RegexReplaceInput;
+
+    fn name(&self) -> &'static str {
+        "regex_replace"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    /// `regex_replace` mutates the filesystem, so it runs in
+    /// `Sequential` mode like the other editing tools.
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if let Err(message) = self.protected_paths.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if input.count == Some(0) {
+            return Ok(error_outcome(
+                &input.path,
+                "count must be at least 1; omit it to replace every match".to_string(),
+            ));
+        }
+
+        let regex = match Regex::new(&input.pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Invalid regex pattern '{}': {e}", input.pattern),
+                ));
+            }
+        };
+
+        if !path.exists() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("File '{}' does not exist", input.path),
+            ));
+        }
+
+        if let Err(message) = check_file_size(
+            path,
+            self.max_file_size,
+            "Edit it with bash (e.g. sed -i) instead.",
+        ) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let original_content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Failed to read file '{}': {}", input.path, e),
+                ));
+            }
+        };
+
+        let total = regex.find_iter(&original_content).count();
+        if total == 0 {
+            return Ok(error_outcome(
+                &input.path,
+                format!(
+                    "Pattern '{}' matched nothing in file '{}'",
+                    input.pattern, input.path
+                ),
+            ));
+        }
+
+        let limit = input.count.unwrap_or(total).min(total);
+        let replaced = replace_matches(
+            &regex,
+            &original_content,
+            &input.replacement,
+            limit,
+            self.normalize_on_write,
+        );
+
+        let display_path = display_relative(path, &ctx.working_directory());
+
+        let normalized = replaced.normalized;
+        let (content, editorconfig_applied) =
+            apply_editorconfig(self.editorconfig, path, replaced.content);
+        if let Some(trash) = &self.trash
+            && let Err(message) = trash.keep(path, &ctx.working_directory())
+        {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if let Err(e) = fs_retry::write(path, &content).await {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Failed to write file '{}': {}", input.path, e),
+            ));
+        }
+
+        let mut return_value = summary(&input.path, total, &replaced.listed, limit);
+        if normalized {
+            return_value.push_str(INSERTED_NORMALIZED_NOTE);
+        }
+        if editorconfig_applied {
+            return_value.push_str(EDITORCONFIG_NOTE);
+        }
+
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(return_value)],
+            details: ToolDetails::Diff {
+                path: display_path,
+                before: original_content,
+                after: content,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// One rewritten match, as listed in the wire summary.
+#[derive(Debug, PartialEq, Eq)]
+struct Replacement {
+    /// 1-based line of the match start in the original file.
+    line: usize,
+    old: String,
+    new: String,
+}
+
+#[derive(Debug)]
+struct Replaced {
+    content: String,
+    /// The first [`LISTED_REPLACEMENTS`] rewrites.
+    listed: Vec<Replacement>,
+    /// Whether `normalize_on_write` trimmed any expanded replacement.
+    normalized: bool,
+}
+
+/// Rewrite the first `limit` matches of `regex` in `content`,
+/// expanding `replacement` against each match's captures. With
+/// `normalize` set each expansion is normalized on its own (see
+/// [`normalize_inserted`]), its last line included where the match
+/// ends a line, leaving the text between matches alone.
+fn replace_matches(
+    regex: &Regex,
+    content: &str,
+    replacement: &str,
+    limit: usize,
+    normalize: bool,
+) -> Replaced {
+    let mut out = String::with_capacity(content.len());
+    let mut listed = Vec::new();
+    let mut normalized = false;
+    let mut last = 0;
+    let mut line = 1;
+    for caps in regex.captures_iter(content).take(limit) {
+        let whole = caps.get(0).expect("group 0 is always present");
+        out.push_str(&content[last..whole.start()]);
+        let mut new = String::new();
+        caps.expand(replacement, &mut new);
+        let (new, trimmed) = normalize_inserted(normalize, &new, ends_line(content, whole.end()));
+        normalized |= trimmed;
+        out.push_str(&new);
+        if listed.len() < LISTED_REPLACEMENTS {
+            line += content[last..whole.start()].matches('\n').count();
+            listed.push(Replacement {
+                line,
+                old: whole.as_str().to_string(),
+                new,
+            });
+            line += whole.as_str().matches('\n').count();
+        }
+        last = whole.end();
+    }
+    out.push_str(&content[last..]);
+    Replaced {
+        content: out,
+        listed,
+        normalized,
+    }
+}
+
+fn summary(path: &str, total: usize, listed: &[Replacement], replaced: usize) -> String {
+    let noun = if replaced == 1 { "match" } else { "matches" };
+    let mut out = if replaced < total {
+        format!("Replaced {replaced} of {total} {noun} in file '{path}' (count limit reached)")
+    } else {
+        format!("Replaced {replaced} {noun} in file '{path}'")
+    };
+    for r in listed {
+        let _ = write!(
+            out,
+            "\n  {:>4} | {} -> {}",
+            r.line,
+            quote(&r.old),
+            quote(&r.new)
+        );
+    }
+    if replaced > listed.len() {
+        let _ = write!(out, "\n  ... and {} more", replaced - listed.len());
+    }
+    out
+}
+
+/// Debug-quote `s` (so newlines and tabs stay visible on one line),
+/// clipped to [`LISTED_TEXT_CHARS`] characters.
+fn quote(s: &str) -> String {
+    let mut chars = s.chars();
+    let head: String = chars.by_ref().take(LISTED_TEXT_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{head:?}…")
+    } else {
+        format!("{head:?}")
+    }
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `edit_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    fn extract_text(content: &[UserContent]) -> String {
+        content
+            .iter()
+            .filter_map(|c| match c {
+                UserContent::Text(t) => Some(t.text.as_str()),
+                UserContent::Image(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("")
+    }
+
+    async fn run(
+        path: &Path,
+        pattern: &str,
+        replacement: &str,
+        count: Option<usize>,
+    ) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        RegexReplaceTool::default()
+            .execute(
+                &mut ctx,
+                RegexReplaceInput {
+                    path: path.display().to_string(),
+                    pattern: pattern.to_string(),
+                    replacement: replacement.to_string(),
+                    count,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    #[tokio::test]
+    async fn capture_groups_reorder_arguments() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("calls.rs");
+        fs::write(&file, "let x = foo(a, b);\nlet y = foo(c, d);\n").unwrap();
+
+        let outcome = run(&file, r"foo\((\w+), (\w+)\)", "bar($2, $1)", None).await;
+        assert!(!outcome.is_error, "{}", extract_text(&outcome.content));
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "let x = bar(b, a);\nlet y = bar(d, c);\n"
+        );
+
+        let text = extract_text(&outcome.content);
+        assert!(text.starts_with("Replaced 2 matches"), "{text}");
+        assert!(
+            text.contains("1 | \"foo(a, b)\" -> \"bar(b, a)\""),
+            "{text}"
+        );
+        assert!(
+            text.contains("2 | \"foo(c, d)\" -> \"bar(d, c)\""),
+            "{text}"
+        );
+        match &outcome.details {
+            ToolDetails::Diff { before, after, .. } => {
+                assert!(before.contains("foo(a, b)"));
+                assert!(after.contains("bar(b, a)"));
+            }
+            other => panic!("expected Diff details, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn count_caps_replacements_in_file_order() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("nums.txt");
+        fs::write(&file, "v1 v2 v3").unwrap();
+
+        let outcome = run(&file, r"v(\d)", "n${1}x", Some(2)).await;
+        assert_eq!(fs::read_to_string(&file).unwrap(), "n1x n2x v3");
+        let text = extract_text(&outcome.content);
+        assert!(text.contains("Replaced 2 of 3 matches"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn invalid_regex_is_rejected_without_touching_the_file() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let outcome = run(&file, "(unclosed", "x", None).await;
+        assert!(outcome.is_error);
+        assert!(extract_text(&outcome.content).starts_with("Invalid regex pattern"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "abc");
+    }
+
+    #[tokio::test]
+    async fn no_match_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let outcome = run(&file, r"\d+", "N", None).await;
+        assert!(outcome.is_error);
+        assert!(extract_text(&outcome.content).contains("matched nothing"));
+    }
+
+    #[tokio::test]
+    async fn zero_count_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let outcome = run(&file, "a", "b", Some(0)).await;
+        assert!(outcome.is_error);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "abc");
+    }
+
+    #[test]
+    fn listing_is_capped() {
+        let regex = Regex::new("x").unwrap();
+        let content = "x\n".repeat(LISTED_REPLACEMENTS + 3);
+        let replaced = replace_matches(&regex, &content, "y", usize::MAX, false);
+        assert_eq!(replaced.content, "y\n".repeat(LISTED_REPLACEMENTS + 3));
+        assert_eq!(replaced.listed.len(), LISTED_REPLACEMENTS);
+        assert_eq!(replaced.listed[3].line, 4);
+        let text = summary(
+            "/f",
+            LISTED_REPLACEMENTS + 3,
+            &replaced.listed,
+            LISTED_REPLACEMENTS + 3,
+        );
+        assert!(text.ends_with("... and 3 more"), "{text}");
+    }
+
+    #[test]
+    fn execution_mode_is_sequential() {
+        assert_eq!(
+            RegexReplaceTool::default().execution_mode(),
+            ExecutionMode::Sequential
+        );
+    }
+}
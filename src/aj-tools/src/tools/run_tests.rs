@@ -0,0 +1,885 @@
+//! `run_tests` builtin — run the project's test suite and summarize it.
+//!
+//! Picks a runner from the build manifest nearest the working
+//! directory (walking up to the git root): `cargo nextest run` when
+//! `cargo-nextest` is on `PATH`, else `cargo test`, for `Cargo.toml`;
+//! `npm test` for `package.json`; `pytest` for the Python markers. The
+//! markers mirror the environment block's project detection in
+//! `aj-conf`, which this crate doesn't depend on.
+//!
+//! The captured output is parsed into pass/fail/skip counts and up to
+//! [`FAILURES_MAX`] failing tests with a short excerpt each, so a red
+//! run costs a few hundred tokens instead of the full log. When the
+//! output can't be parsed (a compile error, an unfamiliar runner) the
+//! tail of the log is returned instead. A failed run also stashes the
+//! full log (see [`ToolContext::stash_output`]) for `read_more`.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the command run.
+//! Failing tests are a normal result; only a missing project, a runner
+//! that can't start, a timeout or cancellation come back as
+//! `is_error: true`. Output is captured as it arrives, so a run stopped
+//! by its timeout still returns the tail of what it printed (and stashes
+//! the rest) next to the timeout error. Like `bash`, the tool runs
+//! `Sequential`.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use aj_agent::tool::{ExecutionMode, ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+use crate::sanitize::sanitize_terminal_output;
+use crate::tools::bash::terminate_process_group;
+use crate::truncate::truncate_tail;
+
+const DESCRIPTION: &str = r#"
+Run the project's test suite and return a compact summary.
+
+Usage:
+
+- The runner is detected from the nearest build manifest: cargo nextest or
+  cargo test for Cargo.toml, npm test for package.json, pytest for Python
+  projects
+- filter narrows the run to matching tests (the cargo test name filter,
+  pytest -k, or an argument passed through to npm test)
+- The result gives pass/fail/skip counts and, for failures, the failing test
+  names with a short error excerpt each instead of the full log
+- When the output can't be parsed (e.g. the build failed) the last lines of
+  the log are returned
+- When the run fails, the full log can be paged with read_more using the token
+  at the end of the result
+- A run that times out returns the last lines it printed before it was stopped
+- Prefer this over running the test command through bash
+"#;
+
+/// Most failing tests reported individually.
+const FAILURES_MAX: usize = 5;
+/// Most lines of each failure's excerpt.
+const EXCERPT_LINES: usize = 15;
+/// Lines and bytes of log returned when the output can't be parsed.
+const TAIL_LINES: usize = 40;
+const TAIL_BYTES: usize = 8 * 1024;
+/// How long a stopped run's output readers may take to drain. A
+/// process that left the group can hold a pipe open forever.
+const DRAIN_GRACE: Duration = Duration::from_secs(2);
+
+/// Python project markers. `pytest.ini`, `tox.ini` and `setup.cfg`
+/// are not in the environment block's list but only ever mean tests.
+const PYTHON_MARKERS: &[&str] = &[
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "pytest.ini",
+    "tox.ini",
+    "requirements.txt",
+];
+
+#[derive(Clone)]
+pub struct RunTestsTool;
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct RunTestsInput {
+    /// Only run tests matching this filter. Omit to run the whole suite.
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Timeout in seconds after which the run is stopped (default: 600).
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+}
+
+fn default_timeout() -> u64 {
+    600
+}
+
+/// The test runners the tool knows how to drive and parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Runner {
+    CargoNextest,
+    CargoTest,
+    Npm,
+    Pytest,
+}
+
+impl Runner {
+    /// The program and arguments to run, with `filter` applied.
+    fn command(self, filter: Option<&str>) -> Vec<String> {
+        let mut args: Vec<&str> = match self {
+            Runner::CargoNextest => vec!["cargo", "nextest", "run", "--no-fail-fast"],
+            Runner::CargoTest => vec!["cargo", "test", "--no-fail-fast"],
+            Runner::Npm => vec!["npm", "test"],
+            Runner::Pytest => vec!["pytest", "-q", "-rfE", "--tb=short"],
+        };
+        if let Some(filter) = filter {
+            match self {
+                Runner::CargoNextest | Runner::CargoTest => args.push(filter),
+                Runner::Npm => args.extend(["--", filter]),
+                Runner::Pytest => args.extend(["-k", filter]),
+            }
+        }
+        args.into_iter().map(str::to_string).collect()
+    }
+
+    fn parse(self, output: &str) -> Option<TestReport> {
+        match self {
+            Runner::CargoNextest => parse_nextest(output),
+            Runner::CargoTest => parse_cargo_test(output),
+            Runner::Npm => parse_npm(output),
+            Runner::Pytest => parse_pytest(output),
+        }
+    }
+}
+
+/// Find the runner for the nearest directory above `working_directory`
+/// (inclusive, stopping at the git root) that holds a known manifest,
+/// and that directory.
+fn detect_runner(working_directory: &Path) -> Option<(Runner, PathBuf)> {
+    for dir in working_directory.ancestors() {
+        let runner = if dir.join("Cargo.toml").is_file() {
+            Some(if on_path("cargo-nextest") {
+                Runner::CargoNextest
+            } else {
+                Runner::CargoTest
+            })
+        } else if dir.join("package.json").is_file() {
+            Some(Runner::Npm)
+        } else if PYTHON_MARKERS.iter().any(|m| dir.join(m).is_file()) {
+            Some(Runner::Pytest)
+        } else {
+            None
+        };
+        if let Some(runner) = runner {
+            return Some((runner, dir.to_path_buf()));
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Whether an executable called `name` is on `PATH`.
+pub(crate) fn on_path(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+}
+
+/// Counts and failures parsed from a runner's output.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TestReport {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    failures: Vec<Failure>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Failure {
+    name: String,
+    /// Leading lines of the test's captured output or error message.
+    excerpt: Vec<String>,
+}
+
+impl TestReport {
+    /// Record `name` as failed once, keeping the first excerpt seen.
+    fn add_failure(&mut self, name: &str, excerpt: Option<&Vec<String>>) {
+        if self.failures.iter().any(|f| f.name == name) {
+            return;
+        }
+        self.failures.push(Failure {
+            name: name.to_string(),
+            excerpt: excerpt.cloned().unwrap_or_default(),
+        });
+    }
+}
+
+/// Collect blocks of lines keyed by the name a `header` line captures.
+/// A block runs until the next header or a line `ends` matches; blocks
+/// sharing a name (a test's stdout and stderr) are joined, then trimmed
+/// of blank edges and capped at [`EXCERPT_LINES`].
+fn collect_blocks(output: &str, header: &Regex, ends: &Regex) -> HashMap<String, Vec<String>> {
+    let mut blocks: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    let mut flush = |current: &mut Option<(String, Vec<String>)>| {
+        if let Some((name, lines)) = current.take() {
+            blocks.entry(name).or_default().extend(lines);
+        }
+    };
+    for line in output.lines() {
+        if let Some(caps) = header.captures(line) {
+            flush(&mut current);
+            current = Some((caps[1].trim().to_string(), Vec::new()));
+        } else if ends.is_match(line) {
+            flush(&mut current);
+        } else if let Some((_, lines)) = &mut current {
+            lines.push(line.to_string());
+        }
+    }
+    flush(&mut current);
+    blocks
+        .into_iter()
+        .map(|(name, lines)| (name, trim_excerpt(lines)))
+        .collect()
+}
+
+fn trim_excerpt(mut lines: Vec<String>) -> Vec<String> {
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let start = lines
+        .iter()
+        .position(|l| !l.trim().is_empty())
+        .unwrap_or(lines.len());
+    lines.drain(..start);
+    lines.truncate(EXCERPT_LINES);
+    // Drop the indentation the lines share, so excerpts nest evenly
+    // under their test name.
+    let indent = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .into_iter()
+        .map(|l| l.get(indent..).unwrap_or("").to_string())
+        .collect()
+}
+
+/// The count in front of `word` in `line` (e.g. `3 passed`), or 0.
+fn count(line: &str, word: &str) -> usize {
+    let re = Regex::new(&format!(r"(\d+) {word}\b")).expect("valid count regex");
+    re.captures(line)
+        .and_then(|caps| caps[1].parse().ok())
+        .unwrap_or(0)
+}
+
+/// `cargo test`: one `test result:` line per test binary, summed;
+/// `test name ... FAILED` lines; `---- name stdout ----` blocks.
+fn parse_cargo_test(output: &str) -> Option<TestReport> {
+    static RESULT: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored").unwrap()
+    });
+    static FAILED: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^test (\S+) \.\.\. FAILED$").unwrap());
+    static HEADER: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^---- (\S+) std(?:out|err) ----$").unwrap());
+    static END: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^failures:$").unwrap());
+
+    let mut report = TestReport::default();
+    let mut seen_result = false;
+    for caps in output.lines().filter_map(|l| RESULT.captures(l)) {
+        seen_result = true;
+        report.passed += caps[1].parse::<usize>().unwrap_or(0);
+        report.failed += caps[2].parse::<usize>().unwrap_or(0);
+        report.skipped += caps[3].parse::<usize>().unwrap_or(0);
+    }
+    if !seen_result {
+        return None;
+    }
+    let blocks = collect_blocks(output, &HEADER, &END);
+    for caps in output.lines().filter_map(|l| FAILED.captures(l)) {
+        report.add_failure(&caps[1], blocks.get(&caps[1]));
+    }
+    Some(report)
+}
+
+/// `cargo nextest run`: a final `Summary [...] N tests run: ...` line;
+/// `FAIL [ 0.1s] crate name` lines (repeated in the final recap);
+/// `--- STDOUT: crate name ---` / `--- STDERR: ... ---` blocks.
+fn parse_nextest(output: &str) -> Option<TestReport> {
+    static FAILED: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\s*(?:FAIL|SIGSEGV|SIGABRT|TIMEOUT) \[[^\]]*\] (.+)$").unwrap()
+    });
+    static HEADER: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*--- STD(?:OUT|ERR):\s+(.+?) ---$").unwrap());
+    static END: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"^\s*(?:(?:PASS|FAIL|SKIP|SIGSEGV|SIGABRT|TIMEOUT) \[|Summary \[|Cancelling|─|-{10})",
+        )
+        .unwrap()
+    });
+
+    let summary = output
+        .lines()
+        .rev()
+        .find(|l| l.trim_start().starts_with("Summary ["))?;
+    let mut report = TestReport {
+        passed: count(summary, "passed"),
+        failed: count(summary, "failed") + count(summary, "timed out"),
+        skipped: count(summary, "skipped"),
+        failures: Vec::new(),
+    };
+    let blocks = collect_blocks(output, &HEADER, &END);
+    for caps in output.lines().filter_map(|l| FAILED.captures(l)) {
+        report.add_failure(caps[1].trim(), blocks.get(caps[1].trim()));
+    }
+    Some(report)
+}
+
+/// `pytest -q -rfE --tb=short`: a final `2 failed, 3 passed in 0.1s`
+/// line; `FAILED path::test - message` summary lines; `___ test ___`
+/// traceback sections.
+fn parse_pytest(output: &str) -> Option<TestReport> {
+    static SUMMARY: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^=*\s*(?:\d+ \w+(?:, )?)+.* in [\d.]+s").unwrap());
+    static FAILED: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(?:FAILED|ERROR) (\S+)(?: - (.*))?$").unwrap());
+    static HEADER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^_{3,} (.+?) _{3,}$").unwrap());
+    static END: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^={3,}").unwrap());
+
+    let summary = output.lines().rev().find(|l| SUMMARY.is_match(l))?;
+    let mut report = TestReport {
+        passed: count(summary, "passed"),
+        failed: count(summary, "failed") + count(summary, "errors?"),
+        skipped: count(summary, "skipped"),
+        failures: Vec::new(),
+    };
+    let blocks = collect_blocks(output, &HEADER, &END);
+    for caps in output.lines().filter_map(|l| FAILED.captures(l)) {
+        let node_id = &caps[1];
+        // Section headers name the test without its file:
+        // `tests/test_x.py::TestA::test_b` is headed `TestA.test_b`.
+        let short = node_id
+            .split_once("::")
+            .map_or_else(|| node_id.to_string(), |(_, rest)| rest.replace("::", "."));
+        let message = caps.get(2).map(|m| vec![m.as_str().to_string()]);
+        report.add_failure(node_id, blocks.get(&short).or(message.as_ref()));
+    }
+    Some(report)
+}
+
+/// `npm test`: whatever the script runs. Understands the count lines of
+/// Jest (`Tests: 1 failed, 4 passed, 5 total`), Vitest
+/// (`Tests  1 failed | 4 passed (5)`) and Mocha (`4 passing`,
+/// `1 failing`), and Jest's `● Suite › test` failure headers.
+fn parse_npm(output: &str) -> Option<TestReport> {
+    static TESTS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*Tests:?\s+\d").unwrap());
+    static MOCHA: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*\d+ (?:passing|failing|pending)\b").unwrap());
+    static HEADER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*● (.+)$").unwrap());
+    static END: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*(?:Test Suites:|Tests:|PASS |FAIL )").unwrap());
+
+    let mut report = if let Some(line) = output.lines().rev().find(|l| TESTS.is_match(l)) {
+        TestReport {
+            passed: count(line, "passed"),
+            failed: count(line, "failed"),
+            skipped: count(line, "skipped") + count(line, "todo"),
+            failures: Vec::new(),
+        }
+    } else {
+        let lines: Vec<&str> = output.lines().filter(|l| MOCHA.is_match(l)).collect();
+        if lines.is_empty() {
+            return None;
+        }
+        let total = |word| lines.iter().map(|l| count(l, word)).sum();
+        TestReport {
+            passed: total("passing"),
+            failed: total("failing"),
+            skipped: total("pending"),
+            failures: Vec::new(),
+        }
+    };
+    let blocks = collect_blocks(output, &HEADER, &END);
+    for caps in output.lines().filter_map(|l| HEADER.captures(l)) {
+        let name = caps[1].trim();
+        if name != "Console" {
+            report.add_failure(name, blocks.get(name));
+        }
+    }
+    Some(report)
+}
+
+/// Render the model-facing summary of a finished run.
+fn render_report(
+    command: &str,
+    exit_code: Option<i32>,
+    report: Option<&TestReport>,
+    log: &str,
+) -> String {
+    let status = match exit_code {
+        Some(code) => format!("exit code {code}"),
+        None => "killed by a signal".to_string(),
+    };
+    let Some(report) = report else {
+        let tail = truncate_tail(log.trim_end(), TAIL_LINES, TAIL_BYTES);
+        return format!(
+            "Ran `{command}` ({status}), but its output had no test results to summarize \
+             (the build may have failed). Last lines of the log:\n\n{}",
+            tail.content
+        );
+    };
+
+    let mut out = format!(
+        "Ran `{command}` ({status}): {} passed, {} failed, {} skipped.",
+        report.passed, report.failed, report.skipped
+    );
+    if report.failed == 0 && report.failures.is_empty() {
+        if exit_code != Some(0) {
+            let tail = truncate_tail(log.trim_end(), TAIL_LINES, TAIL_BYTES);
+            let _ = write!(
+                out,
+                "\nNo test failed, but the run did not succeed. Last lines of the log:\n\n{}",
+                tail.content
+            );
+        }
+        return out;
+    }
+    if report.failures.is_empty() {
+        let tail = truncate_tail(log.trim_end(), TAIL_LINES, TAIL_BYTES);
+        let _ = write!(
+            out,
+            "\nThe failing tests could not be identified. Last lines of the log:\n\n{}",
+            tail.content
+        );
+        return out;
+    }
+    out.push_str("\n\nFailures:");
+    for failure in report.failures.iter().take(FAILURES_MAX) {
+        let _ = write!(out, "\n\n{}", failure.name);
+        for line in &failure.excerpt {
+            let _ = write!(out, "\n    {line}");
+        }
+    }
+    if report.failures.len() > FAILURES_MAX {
+        let _ = write!(
+            out,
+            "\n\n[{} more failing tests not shown. Use filter to run them on their own.]",
+            report.failures.len() - FAILURES_MAX
+        );
+    }
+    out
+}
+
+/// Bytes read so far from one of the runner's streams.
+type Captured = Arc<Mutex<Vec<u8>>>;
+
+/// Copy `stream` into `buf` as it arrives, so a run stopped before it
+/// exits still has what it printed.
+async fn capture(mut stream: impl AsyncRead + Unpin, buf: Captured) {
+    let mut chunk = [0u8; 8192];
+    loop {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => buf
+                .lock()
+                .expect("capture mutex poisoned")
+                .extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+/// The runner's stdout then stderr, lossily decoded and sanitized.
+fn captured_log(stdout: &Captured, stderr: &Captured) -> String {
+    let text = |buf: &Captured| {
+        String::from_utf8_lossy(&buf.lock().expect("capture mutex poisoned")).into_owned()
+    };
+    sanitize_terminal_output(&format!("{}\n{}", text(stdout), text(stderr)))
+}
+
+/// Stash `log` for `read_more` and return the note pointing at it.
+fn full_log_note(ctx: &mut dyn ToolContext, log: &str) -> String {
+    let lines = log.lines().count();
+    let token = ctx.stash_output(log.to_string());
+    format!("[Full log ({lines} lines) available: use read_more with token '{token}'.]")
+}
+
+/// The error for a run stopped by its timeout: the tail of what it
+/// printed, if anything, with the full log stashed.
+fn timeout_message(ctx: &mut dyn ToolContext, command: &str, secs: u64, log: &str) -> String {
+    let mut message = format!(
+        "'{command}' timed out after {secs} seconds. Narrow the run with filter or raise timeout."
+    );
+    let log = log.trim();
+    if log.is_empty() {
+        return message;
+    }
+    let tail = truncate_tail(log, TAIL_LINES, TAIL_BYTES);
+    let _ = write!(
+        message,
+        " Last lines printed before it was stopped:\n\n{}\n\n{}",
+        tail.content,
+        full_log_note(ctx, log)
+    );
+    message
+}
+
+impl ToolDefinition for RunTestsTool {
+    type Input = RunTestsInput;
+
+    fn name(&self) -> &'static str {
+        "run_tests"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let working_directory = ctx.working_directory();
+        let Some((runner, project_dir)) = detect_runner(&working_directory) else {
+            return Ok(error_outcome(
+                "run_tests".to_string(),
+                format!(
+                    "No Cargo.toml, package.json or Python project file found in '{}' or its parents. Run the tests with bash instead.",
+                    working_directory.display()
+                ),
+            ));
+        };
+        let filter = input
+            .filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty());
+        let argv = runner.command(filter);
+        let command = argv.join(" ");
+
+        // A fresh process group lets a timeout or cancel reach the test
+        // binaries the runner spawned, as in `bash`.
+        let mut cmd = Command::new(&argv[0]);
+        cmd.args(&argv[1..])
+            .current_dir(&project_dir)
+            .env("NO_COLOR", "1")
+            .env("TERM", "dumb")
+            .env("CARGO_TERM_COLOR", "never")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        {
+            cmd.process_group(0);
+        }
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(error_outcome(
+                    command.clone(),
+                    format!("Failed to start '{command}': {e}"),
+                ));
+            }
+        };
+        let child_pid: i32 = child
+            .id()
+            .ok_or("child PID unavailable after spawn")?
+            .try_into()
+            .map_err(|e| format!("child PID does not fit in i32: {e}"))?;
+        let stdout: Captured = Arc::default();
+        let stderr: Captured = Arc::default();
+        let mut readers = [
+            tokio::spawn(capture(
+                child.stdout.take().expect("stdout was piped"),
+                Arc::clone(&stdout),
+            )),
+            tokio::spawn(capture(
+                child.stderr.take().expect("stderr was piped"),
+                Arc::clone(&stderr),
+            )),
+        ];
+
+        let cancellation = ctx.cancellation();
+        let status = tokio::select! {
+            status = child.wait() => status?,
+            _ = tokio::time::sleep(Duration::from_secs(input.timeout)) => {
+                terminate_process_group(&mut child, child_pid).await;
+                for reader in &mut readers {
+                    if tokio::time::timeout(DRAIN_GRACE, &mut *reader).await.is_err() {
+                        reader.abort();
+                    }
+                }
+                let log = captured_log(&stdout, &stderr);
+                let message = timeout_message(ctx, &command, input.timeout, &log);
+                return Ok(error_outcome(command, message));
+            }
+            _ = cancellation.cancelled() => {
+                terminate_process_group(&mut child, child_pid).await;
+                return Ok(error_outcome(command.clone(), format!("'{command}' was cancelled")));
+            }
+        };
+
+        for reader in readers {
+            reader.await?;
+        }
+        let log = captured_log(&stdout, &stderr);
+        let report = runner.parse(&log);
+        let mut body = render_report(&command, status.code(), report.as_ref(), &log);
+        // A red run is summarized down to excerpts or a tail; keep the
+        // whole log reachable through `read_more`.
+        let log = log.trim();
+        if !status.success() && !log.is_empty() {
+            let _ = write!(body, "\n\n{}", full_log_note(ctx, log));
+        }
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text {
+                summary: command,
+                body,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(summary: String, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary,
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    const CARGO_OUTPUT: &str = "\
+running 3 tests
+test a::passes ... ok
+test a::breaks ... FAILED
+test a::skipped ... ignored
+
+failures:
+
+---- a::breaks stdout ----
+
+thread 'a::breaks' panicked at src/a.rs:10:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+
+failures:
+    a::breaks
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+
+running 2 tests
+test b::ok ... ok
+test b::ok2 ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out
+";
+
+    #[test]
+    fn parses_cargo_test_counts_and_failures() {
+        let report = parse_cargo_test(CARGO_OUTPUT).expect("parsed");
+        assert_eq!((report.passed, report.failed, report.skipped), (3, 1, 1));
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].name, "a::breaks");
+        assert_eq!(
+            report.failures[0].excerpt[0],
+            "thread 'a::breaks' panicked at src/a.rs:10:5:"
+        );
+        assert_eq!(report.failures[0].excerpt.len(), 4);
+
+        assert_eq!(parse_cargo_test("error[E0425]: cannot find value"), None);
+    }
+
+    #[test]
+    fn parses_nextest_summary_and_failures() {
+        let output = "\
+        PASS [   0.004s] aj-tools a::passes
+        FAIL [   0.005s] aj-tools a::breaks
+--- STDOUT:              aj-tools a::breaks ---
+
+running 1 test
+--- STDERR:              aj-tools a::breaks ---
+thread 'a::breaks' panicked at src/a.rs:10:5:
+boom
+
+------------
+     Summary [   0.010s] 2 tests run: 1 passed, 1 failed, 3 skipped
+        FAIL [   0.005s] aj-tools a::breaks
+error: test run failed
+";
+        let report = parse_nextest(output).expect("parsed");
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 1, 3));
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].name, "aj-tools a::breaks");
+        assert_eq!(
+            report.failures[0].excerpt,
+            [
+                "running 1 test",
+                "thread 'a::breaks' panicked at src/a.rs:10:5:",
+                "boom"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_pytest_summary_and_failures() {
+        let output = "\
+.F.s
+=================================== FAILURES ===================================
+_________________________________ TestA.test_b _________________________________
+tests/test_x.py:5: in test_b
+    assert 1 == 2
+E   assert 1 == 2
+=========================== short test summary info ============================
+FAILED tests/test_x.py::TestA::test_b - assert 1 == 2
+FAILED tests/test_x.py::test_c - ValueError: bad
+1 failed, 2 passed, 1 skipped in 0.03s
+";
+        let report = parse_pytest(output).expect("parsed");
+        assert_eq!((report.passed, report.failed, report.skipped), (2, 1, 1));
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].name, "tests/test_x.py::TestA::test_b");
+        assert_eq!(
+            report.failures[0].excerpt[0],
+            "tests/test_x.py:5: in test_b"
+        );
+        assert_eq!(report.failures[1].excerpt, ["ValueError: bad"]);
+    }
+
+    #[test]
+    fn parses_jest_and_mocha_counts() {
+        let jest = "\
+  ● math › adds
+
+    expect(received).toBe(expected)
+
+Tests:       1 failed, 4 passed, 5 total
+";
+        let report = parse_npm(jest).expect("parsed");
+        assert_eq!((report.passed, report.failed, report.skipped), (4, 1, 0));
+        assert_eq!(report.failures[0].name, "math › adds");
+        assert_eq!(
+            report.failures[0].excerpt,
+            ["expect(received).toBe(expected)"]
+        );
+
+        let mocha = "  4 passing (12ms)\n  1 pending\n";
+        let report = parse_npm(mocha).expect("parsed");
+        assert_eq!((report.passed, report.failed, report.skipped), (4, 0, 1));
+        assert_eq!(parse_npm("> echo no tests"), None);
+    }
+
+    #[test]
+    fn report_lists_capped_failures_or_falls_back_to_the_tail() {
+        let mut report = TestReport {
+            passed: 1,
+            failed: FAILURES_MAX + 1,
+            ..Default::default()
+        };
+        for i in 0..=FAILURES_MAX {
+            report.add_failure(&format!("t{i}"), Some(&vec!["boom".to_string()]));
+        }
+        let out = render_report("cargo test", Some(101), Some(&report), "");
+        assert!(out.starts_with("Ran `cargo test` (exit code 101): 1 passed, 6 failed"));
+        assert!(out.contains("\n\nt0\n    boom"), "{out}");
+        assert!(!out.contains("t5"), "{out}");
+        assert!(out.contains("1 more failing tests not shown"), "{out}");
+
+        let out = render_report("cargo test", Some(101), None, "error: could not compile\n");
+        assert!(out.contains("no test results"), "{out}");
+        assert!(out.ends_with("error: could not compile"), "{out}");
+    }
+
+    #[test]
+    fn detects_the_nearest_manifest() {
+        let dir = TempDir::new().unwrap();
+        let sub = dir.path().join("py");
+        std::fs::create_dir_all(sub.join("tests")).unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(sub.join("pyproject.toml"), "").unwrap();
+
+        assert_eq!(
+            detect_runner(&sub.join("tests")),
+            Some((Runner::Pytest, sub.clone()))
+        );
+        assert_eq!(
+            detect_runner(dir.path()),
+            Some((Runner::Npm, dir.path().to_path_buf()))
+        );
+        assert_eq!(
+            Runner::Pytest.command(Some("slow")),
+            ["pytest", "-q", "-rfE", "--tb=short", "-k", "slow"]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_project_is_a_clear_error() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let mut ctx = DummyToolContext {
+            working_directory: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let outcome = RunTestsTool
+            .execute(
+                &mut ctx,
+                RunTestsInput {
+                    filter: None,
+                    timeout: default_timeout(),
+                },
+            )
+            .await
+            .expect("execute");
+        assert!(outcome.is_error);
+    }
+
+    #[tokio::test]
+    async fn a_timed_out_run_returns_what_it_printed() {
+        if !on_path("npm") {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"test": "echo 'not ok 1 - parses dates' && sleep 30"}}"#,
+        )
+        .unwrap();
+        let mut ctx = DummyToolContext {
+            working_directory: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let outcome = RunTestsTool
+            .execute(
+                &mut ctx,
+                RunTestsInput {
+                    filter: None,
+                    timeout: 2,
+                },
+            )
+            .await
+            .expect("execute");
+        assert!(outcome.is_error);
+        let [UserContent::Text(text)] = &outcome.content[..] else {
+            panic!("expected one text block");
+        };
+        assert!(
+            text.text.contains("timed out after 2 seconds"),
+            "{}",
+            text.text
+        );
+        assert!(
+            text.text.contains("not ok 1 - parses dates"),
+            "{}",
+            text.text
+        );
+        assert!(text.text.contains("use read_more"), "{}", text.text);
+    }
+}
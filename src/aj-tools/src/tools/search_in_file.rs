@@ -0,0 +1,310 @@
+//! `search_in_file` builtin — regex search within one known file.
+//!
+//! For when the model already knows which file it wants to look in:
+//! `pattern` is a [`regex`] expression matched line by line, and each
+//! matching line comes back numbered the way `read_file` numbers
+//! lines, with `context` lines around it. Context lines are marked
+//! with `-` instead of `:`, and non-adjacent groups are separated by
+//! `--`, as in `grep -n -C`.
+//!
+//! Returns [`ToolDetails::Text`]. No match is a normal result, not an
+//! error; a relative or missing path, an invalid regex or an
+//! unreadable file come back as `is_error: true`. Output is capped at
+//! [`MATCHES_MAX`] matches. The tool only reads, so it keeps the
+//! default parallel execution mode.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::roots::AllowedRoots;
+
+const DESCRIPTION: &str = r#"
+Search one file for lines matching a regular expression.
+
+Usage:
+
+- The path parameter must be an absolute path to a file
+- pattern is a regular expression (Rust regex syntax), matched against each
+  line; prefix it with (?i) for a case-insensitive search
+- context adds that many lines before and after each match (default 0)
+- Matching lines are shown as "line: text", context lines as "line- text", and
+  separate groups are divided by "--"
+- Use read_file with an offset to look at a match in more depth
+"#;
+
+/// Most matching lines returned; later ones are counted but not shown.
+const MATCHES_MAX: usize = 200;
+/// Largest `context` honoured.
+const CONTEXT_MAX: usize = 20;
+/// Characters of a single line shown before it is cut.
+const LINE_MAX_CHARS: usize = 500;
+
+#[derive(Clone)]
+pub struct SearchInFileTool {
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl SearchInFileTool {
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+impl Default for SearchInFileTool {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            allowed_roots: AllowedRoots::default(),
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct SearchInFileInput {
+    /// The absolute path to the file to search.
+    pub path: String,
+    /// The regular expression to search for.
+    pub pattern: String,
+    /// Lines of context to show before and after each match (default 0).
+    #[serde(default)]
+    pub context: Option<usize>,
+}
+
+impl ToolDefinition for SearchInFileTool {
+    type Input = SearchInFileInput;
+
+    fn name(&self) -> &'static str {
+        "search_in_file"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let regex = match Regex::new(&input.pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Invalid regex pattern '{}': {e}", input.pattern),
+                ));
+            }
+        };
+
+        if path.is_dir() {
+            return Ok(error_outcome(
+                &input.path,
+                format!(
+                    "'{}' is a directory; search_in_file searches a single file. Use bash with grep -rn to search a tree.",
+                    input.path
+                ),
+            ));
+        }
+        if !path.exists() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("File '{}' does not exist", input.path),
+            ));
+        }
+
+        if let Err(message) = check_file_size(
+            path,
+            self.max_file_size,
+            "Search it with bash (e.g. grep -n) instead.",
+        ) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Failed to read file '{}': {}", input.path, e),
+                ));
+            }
+        };
+
+        let context = input.context.unwrap_or(0).min(CONTEXT_MAX);
+        let body = search(&regex, &content, context, &input.pattern, &input.path);
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(body.clone())],
+            details: ToolDetails::Text {
+                summary: PathBuf::from(&input.path).display().to_string(),
+                body,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// Render the matches of `regex` in `content`, with `context` lines
+/// around each, under a one-line header.
+fn search(regex: &Regex, content: &str, context: usize, pattern: &str, path: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let matches: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(i, _)| i)
+        .collect();
+    if matches.is_empty() {
+        return format!("No lines matching '{pattern}' in '{path}'.");
+    }
+
+    let total = matches.len();
+    let shown = &matches[..total.min(MATCHES_MAX)];
+    let noun = if total == 1 { "line" } else { "lines" };
+    let mut out = format!("{total} matching {noun} in '{path}':\n\n");
+    // One past the last line printed, so overlapping context windows
+    // merge and a gap gets a `--` separator.
+    let mut printed_to: Option<usize> = None;
+    for &hit in shown {
+        let start = hit.saturating_sub(context);
+        let end = (hit + context + 1).min(lines.len());
+        let from = match printed_to {
+            Some(next) if start <= next => next,
+            Some(_) => {
+                out.push_str("--\n");
+                start
+            }
+            None => start,
+        };
+        for (i, line) in lines.iter().enumerate().take(end).skip(from) {
+            let marker = if regex.is_match(line) { ':' } else { '-' };
+            let _ = writeln!(out, "{:>5}{marker} {}", i + 1, clip(line));
+        }
+        printed_to = Some(end.max(printed_to.unwrap_or(0)));
+    }
+    if total > shown.len() {
+        let _ = write!(
+            out,
+            "\n[Showing the first {MATCHES_MAX} of {total} matching lines. Narrow the pattern to see the rest.]\n"
+        );
+    }
+    out
+}
+
+/// `line`, cut to [`LINE_MAX_CHARS`] with a marker when longer.
+fn clip(line: &str) -> String {
+    match line.char_indices().nth(LINE_MAX_CHARS) {
+        Some((cut, _)) => format!("{}… [line truncated]", &line[..cut]),
+        None => line.to_string(),
+    }
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    async fn run(path: &Path, pattern: &str, context: Option<usize>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        SearchInFileTool::default()
+            .execute(
+                &mut ctx,
+                SearchInFileInput {
+                    path: path.display().to_string(),
+                    pattern: pattern.to_string(),
+                    context,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_matches_with_numbers_and_context() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.rs");
+        let lines: Vec<String> = (1..=12).map(|i| format!("line {i}")).collect();
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let outcome = run(&path, r"^line (2|4|11)$", Some(1)).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        let listing = body.split_once("\n\n").expect("header").1;
+        assert_eq!(
+            listing,
+            "    1- line 1\n    2: line 2\n    3- line 3\n    4: line 4\n    5- line 5\n--\n   10- line 10\n   11: line 11\n   12- line 12\n"
+        );
+        assert!(body.starts_with("3 matching lines in"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn no_match_is_not_an_error_but_a_directory_is() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, "alpha\n").unwrap();
+
+        let outcome = run(&path, "beta", None).await;
+        assert!(!outcome.is_error);
+        assert!(text(&outcome).starts_with("No lines matching"));
+
+        let outcome = run(dir.path(), "alpha", None).await;
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("is a directory"));
+    }
+}
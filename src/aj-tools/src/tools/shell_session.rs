@@ -0,0 +1,538 @@
+//! `shell_session` builtin — commands in one long-lived shell.
+//!
+//! Every `bash` call spawns a fresh `bash -c`, so a `cd`, an `export`
+//! or an activated virtualenv is gone by the next call. This tool keeps
+//! one `bash` process per tool catalog (that is, per session; sub-agents
+//! clone the catalog and share it) and feeds it commands over stdin, so
+//! that state carries over.
+//!
+//! Each command is sent as `eval '<command>' </dev/null` followed by a
+//! `printf` of a per-shell sentinel line carrying `$?`. Output is read
+//! until the sentinel shows up; everything before it is the command's
+//! output, with stderr folded into stdout by an `exec 2>&1` when the
+//! shell starts. Running through `eval` keeps a syntax error from
+//! killing the shell, and the `/dev/null` redirect keeps a command that
+//! reads stdin from swallowing the sentinel.
+//!
+//! The shell starts lazily in the session's working directory, in its
+//! own process group as in `bash`. A command that outlives its timeout
+//! or is cancelled can't be interrupted without losing track of the
+//! sentinel, so the whole group is terminated and the next call starts
+//! a fresh shell; the error says so. A command that exits the shell
+//! (`exit`) has the same effect. `reset` drops the shell on request.
+//!
+//! Returns [`ToolDetails::Text`] whose `summary` is the command.
+//! Output beyond [`BASH_MAX_LINES`] / [`BASH_MAX_BYTES`] keeps its tail
+//! and the full text is stashed for `read_more`. Like `bash`, the tool
+//! runs `Sequential`.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aj_agent::tool::{ExecutionMode, ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+use crate::sanitize::sanitize_terminal_output;
+use crate::tools::bash::{DEFAULT_TIMEOUT_SECS, terminate_process_group};
+use crate::truncate::{BASH_MAX_BYTES, BASH_MAX_LINES, truncate_tail};
+
+const DESCRIPTION: &str = r#"
+Run a command in a persistent shell session (bash) that keeps its state between
+calls.
+
+- Unlike bash, the working directory, environment variables, shell variables,
+  functions and activated environments (e.g. `source .venv/bin/activate`)
+  carry over from one call to the next
+- The shell starts in the session's working directory on first use
+- There are no permissions checks or sandboxing, as with bash
+- stdout and stderr are returned together; the exit status is reported when it
+  is not zero
+- Commands have a timeout. Omit `timeout` to use the session default (30s
+  unless the user configured another). A command that times out or is
+  cancelled stops the whole shell: the next call starts a fresh one and the
+  session state is lost
+- Set `reset: true` to discard the session and start over; `command` may be
+  omitted then
+- Commands get no stdin, so interactive programs (editors, pagers, password
+  prompts) won't work; pass non-interactive flags instead
+- Output is truncated to the last 2000 lines or 50KB; the full output can be
+  paged with read_more using the token in the result
+- Use bash for one-off commands and background tasks; use this tool when a
+  sequence of commands depends on shared shell state
+"#;
+
+/// Most bytes of one command's output held in memory; older output is
+/// dropped and the result says so.
+const OUTPUT_CAP_BYTES: usize = 8 * 1024 * 1024;
+
+/// Source of the per-shell sentinel suffix, so two shells in one
+/// process never share one.
+static SHELLS_STARTED: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone)]
+pub struct ShellSessionTool {
+    /// The running shell, if any. Shared by every clone of the tool, so
+    /// one catalog (and the sub-agents cloned from it) use one shell.
+    shell: Arc<Mutex<Option<Shell>>>,
+    /// Timeout for calls that don't pass `timeout`.
+    default_timeout_secs: u64,
+}
+
+impl Default for ShellSessionTool {
+    fn default() -> Self {
+        Self {
+            shell: Arc::default(),
+            default_timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl ShellSessionTool {
+    /// Time out calls that don't pass their own `timeout` after
+    /// `secs` seconds. The per-call value always wins.
+    pub fn with_default_timeout(mut self, secs: u64) -> Self {
+        self.default_timeout_secs = secs;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct ShellSessionInput {
+    /// The command to run in the session's shell. May be omitted when
+    /// `reset` is set.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Timeout in seconds. Omit it to use the session default (30
+    /// unless configured). A timed-out command stops the shell.
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Stop the current shell, if any, before running `command`, so it
+    /// runs in a fresh session.
+    #[serde(default)]
+    pub reset: bool,
+}
+
+/// A live shell and the pipes used to talk to it.
+struct Shell {
+    child: Child,
+    pid: i32,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+    /// Start of the line printed after every command; see [`script`].
+    sentinel: String,
+}
+
+impl Shell {
+    async fn start(working_directory: &Path) -> std::io::Result<Self> {
+        let mut cmd = Command::new("bash");
+        cmd.args(["--noprofile", "--norc"])
+            .current_dir(working_directory)
+            .env("NO_COLOR", "1")
+            .env("TERM", "dumb")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        {
+            cmd.process_group(0);
+        }
+        let mut child = cmd.spawn()?;
+        let pid = child
+            .id()
+            .and_then(|id| i32::try_from(id).ok())
+            .ok_or_else(|| std::io::Error::other("shell PID unavailable after spawn"))?;
+        let mut stdin = child.stdin.take().expect("stdin was piped");
+        let stdout = child.stdout.take().expect("stdout was piped");
+        stdin.write_all(b"exec 2>&1\n").await?;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let n = SHELLS_STARTED.fetch_add(1, Ordering::Relaxed);
+        Ok(Self {
+            child,
+            pid,
+            stdin,
+            stdout,
+            sentinel: format!("__aj_shell_done_{pid}_{n}_{nanos}__"),
+        })
+    }
+
+    /// Terminate the shell's process group and reap it.
+    async fn stop(mut self) {
+        terminate_process_group(&mut self.child, self.pid).await;
+    }
+}
+
+/// The text written to the shell for `command`: the command under
+/// `eval` with stdin closed, then the sentinel line with its status.
+fn script(command: &str, sentinel: &str) -> String {
+    let quoted = command.replace('\'', r"'\''");
+    format!("eval '{quoted}' </dev/null\nprintf '\\n{sentinel} %d\\n' \"$?\"\n")
+}
+
+/// Find the sentinel line in `buf`, looking no earlier than a
+/// sentinel line straddling offset `from` could start: the offset where
+/// the command's output ends and the exit status it reported. `None`
+/// until the whole line has arrived.
+fn finished(buf: &[u8], from: usize, sentinel: &str) -> Option<(usize, i32)> {
+    let needle = format!("\n{sentinel} ");
+    // Room for the needle plus a status line that arrived in part.
+    let from = from.saturating_sub(needle.len() + 16);
+    let start = from
+        + buf[from..]
+            .windows(needle.len())
+            .position(|window| window == needle.as_bytes())?;
+    let rest = &buf[start + needle.len()..];
+    let end = rest.iter().position(|&b| b == b'\n')?;
+    let status = std::str::from_utf8(&rest[..end])
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some((start, status))
+}
+
+/// How a command's run ended.
+enum Ended {
+    /// The sentinel arrived with this exit status.
+    Finished(i32),
+    /// The shell closed its output, e.g. after `exit`.
+    ShellExited,
+    TimedOut,
+    Cancelled,
+}
+
+impl ToolDefinition for ShellSessionTool {
+    type Input = ShellSessionInput;
+
+    fn name(&self) -> &'static str {
+        "shell_session"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    /// Runs arbitrary commands in shared state, so it serializes like
+    /// `bash`.
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let working_directory = ctx.working_directory();
+        let command = input
+            .command
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+        let mut guard = self.shell.lock().await;
+        if input.reset
+            && let Some(shell) = guard.take()
+        {
+            shell.stop().await;
+        }
+        let Some(command) = command else {
+            if input.reset {
+                let body = format!(
+                    "Shell session reset; the next command starts in {}.",
+                    working_directory.display()
+                );
+                return Ok(text_outcome("reset".to_string(), body, false));
+            }
+            return Ok(text_outcome(
+                "shell_session".to_string(),
+                "command is required unless reset is set".to_string(),
+                true,
+            ));
+        };
+
+        let mut notes = Vec::new();
+        if guard.is_none() {
+            match Shell::start(&working_directory).await {
+                Ok(shell) => {
+                    *guard = Some(shell);
+                    notes.push(format!(
+                        "[Started a new shell session in {}.]",
+                        working_directory.display()
+                    ));
+                }
+                Err(e) => {
+                    return Ok(text_outcome(
+                        command,
+                        format!("Failed to start the shell: {e}"),
+                        true,
+                    ));
+                }
+            }
+        }
+        let shell = guard.as_mut().expect("shell started above");
+        let timeout_secs = input.timeout.unwrap_or(self.default_timeout_secs);
+
+        let mut buf = Vec::new();
+        let mut dropped = false;
+        let ended = if shell
+            .stdin
+            .write_all(script(&command, &shell.sentinel).as_bytes())
+            .await
+            .is_err()
+        {
+            Ended::ShellExited
+        } else {
+            let cancellation = ctx.cancellation();
+            let deadline = tokio::time::sleep(Duration::from_secs(timeout_secs));
+            tokio::pin!(deadline);
+            let mut chunk = [0u8; 8192];
+            loop {
+                tokio::select! {
+                    read = shell.stdout.read(&mut chunk) => match read {
+                        Ok(0) | Err(_) => break Ended::ShellExited,
+                        Ok(n) => {
+                            let from = buf.len();
+                            buf.extend_from_slice(&chunk[..n]);
+                            if let Some((end, status)) = finished(&buf, from, &shell.sentinel) {
+                                buf.truncate(end);
+                                break Ended::Finished(status);
+                            }
+                            // Drop the oldest output once past the cap.
+                            if buf.len() > OUTPUT_CAP_BYTES + chunk.len() {
+                                buf.drain(..buf.len() - OUTPUT_CAP_BYTES);
+                                dropped = true;
+                            }
+                        }
+                    },
+                    _ = &mut deadline => break Ended::TimedOut,
+                    _ = cancellation.cancelled() => break Ended::Cancelled,
+                }
+            }
+        };
+
+        let (status_line, is_error) = match ended {
+            Ended::Finished(0) => (None, false),
+            Ended::Finished(code) => (
+                Some(format!("Command failed with exit code: {code}")),
+                false,
+            ),
+            Ended::ShellExited => {
+                let mut shell = guard.take().expect("shell is running");
+                let code = shell.child.wait().await.ok().and_then(|s| s.code());
+                let how =
+                    code.map_or_else(|| "by a signal".to_string(), |c| format!("with status {c}"));
+                (
+                    Some(format!(
+                        "The shell exited {how}; the next call starts a new session."
+                    )),
+                    code != Some(0),
+                )
+            }
+            Ended::TimedOut => {
+                guard.take().expect("shell is running").stop().await;
+                (
+                    Some(format!(
+                        "Command timed out after {timeout_secs} seconds. The shell was stopped; the next call starts a new session, so the working directory and environment are reset."
+                    )),
+                    true,
+                )
+            }
+            Ended::Cancelled => {
+                guard.take().expect("shell is running").stop().await;
+                (
+                    Some(
+                        "Command cancelled. The shell was stopped; the next call starts a new session."
+                            .to_string(),
+                    ),
+                    true,
+                )
+            }
+        };
+        drop(guard);
+
+        let output = sanitize_terminal_output(&String::from_utf8_lossy(&buf));
+        let output = output.trim_end_matches('\n');
+        let mut body = notes.join("\n");
+        if dropped {
+            push_line(
+                &mut body,
+                "[Output exceeded the in-memory limit; only its end was kept.]",
+            );
+        }
+        let tail = truncate_tail(output, BASH_MAX_LINES, BASH_MAX_BYTES);
+        push_line(&mut body, &tail.content);
+        if tail.truncated {
+            let token = ctx.stash_output(output.to_string());
+            push_line(
+                &mut body,
+                &format!(
+                    "[Showing the last {} of {} lines. Full output available: use read_more with token '{token}'.]",
+                    tail.output_lines, tail.total_lines
+                ),
+            );
+        }
+        if let Some(line) = status_line {
+            push_line(&mut body, &line);
+        }
+        Ok(text_outcome(command, body, is_error))
+    }
+}
+
+/// Append `line` to `body` on a line of its own. Empty lines are
+/// skipped.
+fn push_line(body: &mut String, line: &str) {
+    if line.is_empty() {
+        return;
+    }
+    if !body.is_empty() {
+        body.push('\n');
+    }
+    body.push_str(line);
+}
+
+fn text_outcome(summary: String, body: String, is_error: bool) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(body.clone())],
+        details: ToolDetails::Text { summary, body },
+        is_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    async fn run(
+        tool: &ShellSessionTool,
+        ctx: &mut DummyToolContext,
+        command: Option<&str>,
+        timeout: Option<u64>,
+        reset: bool,
+    ) -> ToolOutcome {
+        tool.execute(
+            ctx,
+            ShellSessionInput {
+                command: command.map(str::to_string),
+                timeout,
+                reset,
+            },
+        )
+        .await
+        .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn finds_the_sentinel_line_and_status() {
+        let sentinel = "__done__";
+        assert_eq!(finished(b"out\n\n__done__ 3", 0, sentinel), None);
+        assert_eq!(finished(b"out\n\n__done__ 3\n", 14, sentinel), Some((4, 3)));
+        assert_eq!(finished(b"\n__done__ 0\n", 0, sentinel), Some((0, 0)));
+        // A sentinel split across two reads is still found.
+        assert_eq!(
+            finished(b"abc\n\n__done__ 12\n", 10, sentinel),
+            Some((4, 12))
+        );
+        assert_eq!(
+            script("echo 'a'", sentinel),
+            "eval 'echo '\\''a'\\''' </dev/null\nprintf '\\n__done__ %d\\n' \"$?\"\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn state_carries_over_between_calls() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let mut ctx = DummyToolContext {
+            working_directory: dir.path().to_path_buf(),
+            ..DummyToolContext::default()
+        };
+        let tool = ShellSessionTool::default();
+
+        let first = run(
+            &tool,
+            &mut ctx,
+            Some("cd sub && export GREETING=hi"),
+            None,
+            false,
+        )
+        .await;
+        assert!(!first.is_error, "{}", text(&first));
+        assert!(text(&first).starts_with("[Started a new shell session"));
+
+        let second = run(
+            &tool,
+            &mut ctx,
+            Some("pwd; echo $GREETING >&2"),
+            None,
+            false,
+        )
+        .await;
+        let body = text(&second);
+        assert!(!second.is_error, "{body}");
+        assert!(!body.contains("Started"), "{body}");
+        assert!(body.contains("/sub\nhi"), "{body}");
+
+        let failed = run(&tool, &mut ctx, Some("if then"), None, false).await;
+        assert!(text(&failed).contains("Command failed with exit code: 2"));
+        let still = run(&tool, &mut ctx, Some("echo $GREETING"), None, false).await;
+        assert_eq!(text(&still), "hi");
+    }
+
+    #[tokio::test]
+    async fn reset_timeout_and_exit_start_a_new_session() {
+        let mut ctx = DummyToolContext::default();
+        let tool = ShellSessionTool::default();
+        run(&tool, &mut ctx, Some("X=1"), None, false).await;
+
+        let reset = run(&tool, &mut ctx, None, None, true).await;
+        assert!(!reset.is_error);
+        let fresh = run(&tool, &mut ctx, Some("echo \"[$X]\""), None, false).await;
+        assert!(text(&fresh).ends_with("[]"), "{}", text(&fresh));
+
+        run(&tool, &mut ctx, Some("X=1"), None, false).await;
+        let timed_out = run(
+            &tool,
+            &mut ctx,
+            Some("echo before; sleep 30"),
+            Some(1),
+            false,
+        )
+        .await;
+        let body = text(&timed_out);
+        assert!(timed_out.is_error, "{body}");
+        assert!(
+            body.starts_with("before\nCommand timed out after 1 seconds"),
+            "{body}"
+        );
+        let fresh = run(&tool, &mut ctx, Some("echo \"[$X]\""), None, false).await;
+        assert!(text(&fresh).starts_with("[Started a new shell session"));
+        assert!(text(&fresh).ends_with("[]"));
+
+        let exited = run(&tool, &mut ctx, Some("exit 0"), None, false).await;
+        assert!(!exited.is_error, "{}", text(&exited));
+        assert!(text(&exited).contains("The shell exited with status 0"));
+
+        let missing = run(&tool, &mut ctx, None, None, false).await;
+        assert!(missing.is_error);
+    }
+}
@@ -0,0 +1,577 @@
+//! `structural_search` builtin — tree-sitter queries over source files.
+//!
+//! Regex search can't express "every function returning `Result`" or
+//! "every impl of trait X"; a tree-sitter query can. `query` is written
+//! in tree-sitter's S-expression query syntax and run against the
+//! syntax tree of `path`, or of every supported file under it (walked
+//! with the [`ignore`] crate, so `.gitignore` rules apply and hidden
+//! files are skipped unless `hidden` is set).
+//!
+//! The grammar is picked per file from its extension (see
+//! [`Grammar::for_path`]). The query is compiled once per grammar; a
+//! query that doesn't compile for a grammar (node kinds are
+//! grammar-specific) skips that grammar's files with a note rather
+//! than failing the call, unless no grammar accepts it at all. Files
+//! of unsupported types are skipped and counted.
+//!
+//! Each captured node is reported as `file:line:col @capture  text`,
+//! with the node's first source line as the text. Output stops after
+//! [`MATCHES_MAX`] captures; the walk after [`FILES_MAX`] files.
+//! Returns [`ToolDetails::Text`] whose `summary` is the path. The tool
+//! only reads, so it keeps the default parallel execution mode.
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator};
+
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::roots::AllowedRoots;
+use crate::tools::count_lines::walk;
+
+const DESCRIPTION: &str = r#"
+Search source code by syntax-tree structure using a tree-sitter query.
+
+Usage:
+
+- The path parameter must be an absolute path to a file or directory;
+  directories are searched recursively, respecting .gitignore
+- Hidden files and directories (.github, .cargo, ...) are skipped by default;
+  set hidden to true to search them too
+- query uses tree-sitter query syntax and must capture at least one node with
+  @name, e.g. for Rust:
+    (function_item name: (identifier) @name return_type: (generic_type type: (type_identifier) @ret (#eq? @ret "Result")))
+    (impl_item trait: (type_identifier) @trait (#eq? @trait "Display")) @impl
+- Supported languages (by extension): Rust (.rs), Python (.py), Go (.go),
+  JavaScript (.js, .mjs, .cjs, .jsx), TypeScript (.ts, .mts, .cts, .tsx);
+  other files are skipped
+- Node kinds differ between languages; a query is applied only to the files
+  whose grammar accepts it
+- Each captured node is reported as "file:line:col @capture  first line of the
+  node's text"; use read_file to see the full span
+- Prefer this over regex search for precise structural queries in refactoring
+"#;
+
+/// Most captures reported in one call.
+const MATCHES_MAX: usize = 200;
+/// Most files parsed in one call; the walk stops there.
+const FILES_MAX: usize = 10_000;
+/// Longest snippet of a node's first line shown, in characters.
+const SNIPPET_MAX_CHARS: usize = 160;
+
+#[derive(Clone)]
+pub struct StructuralSearchTool {
+    /// Larger files are skipped (or, for a single named file, refused);
+    /// see [`crate::file_size`].
+    max_file_size: u64,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl Default for StructuralSearchTool {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            allowed_roots: AllowedRoots::default(),
+        }
+    }
+}
+
+impl StructuralSearchTool {
+    /// Skip files larger than `max_file_size` bytes.
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct StructuralSearchInput {
+    /// The absolute path to the file or directory to search.
+    pub path: String,
+    /// The tree-sitter query; it must capture at least one node with
+    /// `@name`.
+    pub query: String,
+    /// Also search hidden files and directories (names starting with
+    /// `.`). Defaults to false.
+    #[serde(default)]
+    pub hidden: Option<bool>,
+}
+
+impl ToolDefinition for StructuralSearchTool {
+    type Input = StructuralSearchInput;
+
+    fn name(&self) -> &'static str {
+        "structural_search"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = PathBuf::from(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(&path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if !path.exists() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path '{}' does not exist", input.path),
+            ));
+        }
+        if path.is_file() {
+            if Grammar::for_path(&path).is_none() {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!(
+                        "Unsupported file type for structural search: {}. Supported: {}",
+                        input.path,
+                        Grammar::SUPPORTED
+                    ),
+                ));
+            }
+            if let Err(message) = check_file_size(
+                &path,
+                self.max_file_size,
+                "Use search_in_file for a regex search instead.",
+            ) {
+                return Ok(error_outcome(&input.path, message));
+            }
+        }
+
+        let max_file_size = self.max_file_size;
+        let query = input.query.clone();
+        let hidden = input.hidden.unwrap_or(false);
+        let result =
+            tokio::task::spawn_blocking(move || search(&path, &query, max_file_size, hidden))
+                .await?;
+        match result {
+            Ok(found) => {
+                let body = found.render(&input.path);
+                Ok(ToolOutcome {
+                    content: vec![UserContent::text(body.clone())],
+                    details: ToolDetails::Text {
+                        summary: PathBuf::from(&input.path).display().to_string(),
+                        body,
+                    },
+                    is_error: false,
+                })
+            }
+            Err(message) => Ok(error_outcome(&input.path, message)),
+        }
+    }
+}
+
+/// A language the tool can parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Grammar {
+    Rust,
+    Python,
+    Go,
+    JavaScript,
+    TypeScript,
+    Tsx,
+}
+
+impl Grammar {
+    /// The supported extensions, for error messages.
+    const SUPPORTED: &str = ".rs, .py, .go, .js, .mjs, .cjs, .jsx, .ts, .mts, .cts, .tsx";
+
+    fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Grammar::Rust),
+            "py" | "pyi" => Some(Grammar::Python),
+            "go" => Some(Grammar::Go),
+            "js" | "mjs" | "cjs" | "jsx" => Some(Grammar::JavaScript),
+            "ts" | "mts" | "cts" => Some(Grammar::TypeScript),
+            "tsx" => Some(Grammar::Tsx),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Grammar::Rust => "Rust",
+            Grammar::Python => "Python",
+            Grammar::Go => "Go",
+            Grammar::JavaScript => "JavaScript",
+            Grammar::TypeScript => "TypeScript",
+            Grammar::Tsx => "TSX",
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Grammar::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Grammar::Python => tree_sitter_python::LANGUAGE.into(),
+            Grammar::Go => tree_sitter_go::LANGUAGE.into(),
+            Grammar::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Grammar::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Grammar::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        }
+    }
+}
+
+/// One captured node.
+struct Capture {
+    path: PathBuf,
+    line: usize,
+    column: usize,
+    name: String,
+    snippet: String,
+}
+
+/// What a search produced, before rendering.
+#[derive(Default)]
+struct Found {
+    captures: Vec<Capture>,
+    /// Captures beyond [`MATCHES_MAX`] that were not kept.
+    more: usize,
+    /// Grammars the query failed to compile for, with the error, in
+    /// the order first met.
+    rejected: Vec<(Grammar, String)>,
+    /// Files skipped because the query didn't compile for them.
+    rejected_files: usize,
+    unsupported: usize,
+    unreadable: usize,
+    too_large: usize,
+    truncated: bool,
+}
+
+impl Found {
+    fn render(&self, path: &str) -> String {
+        let mut out = String::new();
+        if self.captures.is_empty() {
+            let _ = write!(out, "No matches in {path}");
+        }
+        for c in &self.captures {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let _ = write!(
+                out,
+                "{}:{}:{} @{}  {}",
+                c.path.display(),
+                c.line,
+                c.column,
+                c.name,
+                c.snippet
+            );
+        }
+        let mut notes = Vec::new();
+        if self.more > 0 {
+            notes.push(format!(
+                "Stopped after {MATCHES_MAX} captures; {} more not shown. Narrow the path or query.",
+                self.more
+            ));
+        }
+        if self.truncated {
+            notes.push(format!("The walk stopped after {FILES_MAX} files."));
+        }
+        for (grammar, error) in &self.rejected {
+            notes.push(format!(
+                "The query does not compile for {} ({error}); those files were skipped.",
+                grammar.name()
+            ));
+        }
+        if self.unsupported > 0 {
+            notes.push(format!(
+                "Skipped {} file(s) of unsupported types.",
+                self.unsupported
+            ));
+        }
+        if self.too_large > 0 {
+            notes.push(format!(
+                "Skipped {} file(s) over the size limit.",
+                self.too_large
+            ));
+        }
+        if self.unreadable > 0 {
+            notes.push(format!(
+                "Skipped {} unreadable or non-UTF-8 file(s).",
+                self.unreadable
+            ));
+        }
+        if !notes.is_empty() {
+            out.push_str("\n\n");
+            out.push_str(&notes.join("\n"));
+        }
+        out
+    }
+}
+
+/// Run `query` over `root`. Errors (the query compiles for no grammar
+/// met, or captures nothing) are the model-facing message.
+fn search(root: &Path, query: &str, max_file_size: u64, hidden: bool) -> Result<Found, String> {
+    let mut found = Found::default();
+    // `None` marks a grammar the query was rejected for.
+    let mut queries: HashMap<Grammar, Option<Query>> = HashMap::new();
+    let mut parser = Parser::new();
+    let mut files = 0;
+    let mut supported = 0;
+
+    for entry in walk(root, hidden).flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        let Some(grammar) = Grammar::for_path(path) else {
+            found.unsupported += 1;
+            continue;
+        };
+        if files == FILES_MAX {
+            found.truncated = true;
+            break;
+        }
+        files += 1;
+        supported += 1;
+
+        let compiled = queries.entry(grammar).or_insert_with(|| {
+            match Query::new(&grammar.language(), query) {
+                Ok(q) => Some(q),
+                Err(e) => {
+                    found.rejected.push((grammar, e.to_string()));
+                    None
+                }
+            }
+        });
+        let Some(compiled) = compiled else {
+            found.rejected_files += 1;
+            continue;
+        };
+        if compiled.capture_names().is_empty() {
+            return Err(
+                "The query captures nothing; name at least one node with @name, e.g. (function_item) @fn"
+                    .to_string(),
+            );
+        }
+        if entry
+            .metadata()
+            .is_ok_and(|meta| meta.len() > max_file_size)
+        {
+            found.too_large += 1;
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(path) else {
+            found.unreadable += 1;
+            continue;
+        };
+        if parser.set_language(&grammar.language()).is_err() {
+            found.unreadable += 1;
+            continue;
+        }
+        let Some(tree) = parser.parse(&source, None) else {
+            found.unreadable += 1;
+            continue;
+        };
+
+        let names = compiled.capture_names();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(compiled, tree.root_node(), source.as_bytes());
+        while let Some(m) = matches.next() {
+            for capture in m.captures {
+                if found.captures.len() == MATCHES_MAX {
+                    found.more += 1;
+                    continue;
+                }
+                let node = capture.node;
+                let start = node.start_position();
+                let text = source.get(node.byte_range()).unwrap_or("");
+                found.captures.push(Capture {
+                    path: path.to_path_buf(),
+                    line: start.row + 1,
+                    column: start.column + 1,
+                    name: names
+                        .get(usize::try_from(capture.index).unwrap_or(usize::MAX))
+                        .copied()
+                        .unwrap_or("?")
+                        .to_string(),
+                    snippet: snippet(text),
+                });
+            }
+        }
+    }
+
+    if supported == 0 {
+        return Err(format!(
+            "No files of a supported type under {}. Supported: {}",
+            root.display(),
+            Grammar::SUPPORTED
+        ));
+    }
+    if found.rejected_files == supported {
+        let errors: Vec<String> = found
+            .rejected
+            .iter()
+            .map(|(grammar, error)| format!("{}: {error}", grammar.name()))
+            .collect();
+        return Err(format!("Invalid query: {}", errors.join("; ")));
+    }
+    Ok(found)
+}
+
+/// The first line of a node's text, trimmed and capped at
+/// [`SNIPPET_MAX_CHARS`].
+fn snippet(text: &str) -> String {
+    let first = text.lines().next().unwrap_or("").trim();
+    let mut out: String = first.chars().take(SNIPPET_MAX_CHARS).collect();
+    if first.chars().count() > SNIPPET_MAX_CHARS || text.trim_end().contains('\n') {
+        out.push('…');
+    }
+    out
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `write_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    async fn run(path: &Path, query: &str) -> ToolOutcome {
+        run_with(path, query, None).await
+    }
+
+    async fn run_with(path: &Path, query: &str, hidden: Option<bool>) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        StructuralSearchTool::default()
+            .execute(
+                &mut ctx,
+                StructuralSearchInput {
+                    path: path.display().to_string(),
+                    query: query.to_string(),
+                    hidden,
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    const RUST_SRC: &str = "\
+fn plain() {}
+
+fn fallible() -> Result<(), String> {
+    Ok(())
+}
+
+impl std::fmt::Display for Thing {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+}
+";
+
+    #[tokio::test]
+    async fn finds_functions_returning_result() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, RUST_SRC).unwrap();
+
+        let outcome = run(
+            &file,
+            r#"(function_item name: (identifier) @name
+                 return_type: (generic_type type: (type_identifier) @ret)
+                 (#eq? @ret "Result"))"#,
+        )
+        .await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        assert!(body.contains("lib.rs:3:4 @name  fallible"), "{body}");
+        assert!(!body.contains("plain"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn directory_search_skips_other_grammars_and_types() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("lib.rs"), RUST_SRC).unwrap();
+        fs::write(dir.path().join("app.py"), "def f():\n    pass\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "fn not_code() {}\n").unwrap();
+
+        let outcome = run(dir.path(), "(impl_item) @impl").await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        let body = text(&outcome);
+        assert!(
+            body.contains("lib.rs:7:1 @impl  impl std::fmt::Display for Thing {…"),
+            "{body}"
+        );
+        assert!(body.contains("does not compile for Python"), "{body}");
+        assert!(body.contains("Skipped 1 file(s) of unsupported types."));
+    }
+
+    #[tokio::test]
+    async fn hidden_directories_are_searched_only_on_request() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".cargo")).unwrap();
+        fs::write(dir.path().join(".cargo/build.rs"), RUST_SRC).unwrap();
+
+        let body = text(&run(dir.path(), "(impl_item) @impl").await);
+        assert!(!body.contains("build.rs"), "{body}");
+        let body = text(&run_with(dir.path(), "(impl_item) @impl", Some(true)).await);
+        assert!(body.contains(".cargo/build.rs:7:1 @impl"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn invalid_or_uncaptured_queries_and_unsupported_files_error() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, RUST_SRC).unwrap();
+
+        let invalid = run(&file, "(no_such_node) @x").await;
+        assert!(invalid.is_error);
+        assert!(text(&invalid).starts_with("Invalid query: Rust:"));
+
+        let uncaptured = run(&file, "(function_item)").await;
+        assert!(uncaptured.is_error);
+        assert!(text(&uncaptured).contains("captures nothing"));
+
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, "hello").unwrap();
+        let unsupported = run(&notes, "(x) @x").await;
+        assert!(unsupported.is_error);
+        assert!(text(&unsupported).contains("Unsupported file type"));
+    }
+}
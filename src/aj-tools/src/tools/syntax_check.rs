@@ -0,0 +1,516 @@
+//! `syntax_check` builtin — check that one file parses, without a build.
+//!
+//! The checker is picked by extension: `rustfmt` for `.rs` (it parses
+//! the file without resolving `mod` declarations or names), Python's
+//! own `compile()` for `.py`, `node --check` for JavaScript, `bash -n`
+//! for shell scripts, and an in-process parse for `.json`. Nothing is
+//! written: the file's content is piped to the checker where it reads
+//! stdin, so no `__pycache__` or formatted output is left behind.
+//!
+//! Only the errors come back, cut at [`MAX_ERROR_LINES`] lines, with
+//! `is_error: true`; a file that parses gets a one-line confirmation.
+//! An extension without a checker, or a checker that isn't installed,
+//! is not an error: the result says the file wasn't checked. Files over
+//! `max_file_size` are refused before reading (see
+//! [`crate::file_size`]). Being a parse only, a clean result says
+//! nothing about types or names.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use aj_agent::tool::{ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::roots::AllowedRoots;
+use crate::tools::format_file::rust_edition;
+use crate::tools::run_tests::on_path;
+
+const DESCRIPTION: &str = r#"
+Check that a file is syntactically valid, without building the project.
+
+Usage:
+
+- The path parameter must be an absolute path
+- The checker is chosen by extension: rustfmt for .rs, Python's compile() for
+  .py/.pyi, node --check for .js/.mjs/.cjs, bash -n for .sh/.bash, and a JSON
+  parser for .json
+- Only syntax is checked: types, imports and names are not resolved
+- Returns the errors, or a one-line confirmation when the file parses
+- Use it right after an edit to catch obvious breakage cheaply; run the build
+  or tests for anything deeper
+"#;
+
+/// Seconds a checker may run before it is killed.
+const CHECK_TIMEOUT_SECS: u64 = 30;
+/// Lines of checker output returned; the rest is counted, not shown.
+const MAX_ERROR_LINES: usize = 40;
+
+/// Python snippet that compiles stdin under the file name in `argv[1]`
+/// and prints only the error, not a traceback through the snippet.
+const PYTHON_CHECK: &str = "\
+import sys, traceback
+try:
+    compile(sys.stdin.read(), sys.argv[1], 'exec')
+except SyntaxError as e:
+    sys.stderr.write(''.join(traceback.format_exception_only(type(e), e)))
+    sys.exit(1)
+";
+
+#[derive(Clone)]
+pub struct SyntaxCheckTool {
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+}
+
+impl SyntaxCheckTool {
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+}
+
+impl Default for SyntaxCheckTool {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            allowed_roots: AllowedRoots::default(),
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct SyntaxCheckInput {
+    /// The absolute path to the file to check.
+    pub path: String,
+}
+
+/// The checkers the tool knows how to drive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Checker {
+    Rustfmt,
+    Python,
+    Node,
+    Bash,
+    Json,
+}
+
+impl Checker {
+    /// The checker for `path`'s extension, if any.
+    fn for_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension {
+            "rs" => Some(Checker::Rustfmt),
+            "py" | "pyi" => Some(Checker::Python),
+            "js" | "mjs" | "cjs" => Some(Checker::Node),
+            "sh" | "bash" => Some(Checker::Bash),
+            "json" => Some(Checker::Json),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Checker::Rustfmt => "rustfmt",
+            Checker::Python => "python",
+            Checker::Node => "node",
+            Checker::Bash => "bash",
+            Checker::Json => "json",
+        }
+    }
+
+    /// The program to run, or `None` when it isn't installed. `Json`
+    /// never runs one.
+    fn program(self) -> Option<&'static str> {
+        match self {
+            Checker::Rustfmt => on_path("rustfmt").then_some("rustfmt"),
+            Checker::Python => ["python3", "python"].into_iter().find(|name| on_path(name)),
+            Checker::Node => on_path("node").then_some("node"),
+            Checker::Bash => on_path("bash").then_some("bash"),
+            Checker::Json => None,
+        }
+    }
+
+    /// Arguments that make the checker parse `path`'s content from
+    /// stdin, reporting errors against `path`.
+    fn args(self, path: &Path) -> Vec<String> {
+        match self {
+            // `--emit stdout` keeps rustfmt from touching the file; the
+            // formatted copy on stdout is discarded.
+            Checker::Rustfmt => vec![
+                "--emit".to_string(),
+                "stdout".to_string(),
+                "--edition".to_string(),
+                rust_edition(path),
+            ],
+            Checker::Python => vec![
+                "-c".to_string(),
+                PYTHON_CHECK.to_string(),
+                path.display().to_string(),
+            ],
+            // `node --check` only reads from stdin as a script, which
+            // would reject ES module syntax, so it gets the path.
+            Checker::Node => vec!["--check".to_string(), path.display().to_string()],
+            Checker::Bash => vec!["-n".to_string()],
+            Checker::Json => Vec::new(),
+        }
+    }
+
+    /// Whether the checker reads the file itself rather than stdin.
+    fn reads_path(self) -> bool {
+        self == Checker::Node
+    }
+}
+
+/// What a check found: `None` when the file parses, the errors when
+/// it doesn't.
+type CheckResult = Option<String>;
+
+/// Run `program` over `content`, killing it if it outlives
+/// [`CHECK_TIMEOUT_SECS`] or the call is cancelled.
+async fn run_checker(
+    cancellation: CancellationToken,
+    checker: Checker,
+    program: &str,
+    path: &Path,
+    content: &str,
+) -> Result<Result<CheckResult, String>, aj_agent::BoxError> {
+    let name = checker.name();
+    let mut cmd = Command::new(program);
+    cmd.args(checker.args(path))
+        .stdin(if checker.reads_path() {
+            Stdio::null()
+        } else {
+            Stdio::piped()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = path.parent() {
+        cmd.current_dir(dir);
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return Ok(Err(format!("Failed to start {name}: {e}"))),
+    };
+
+    let stdin = child.stdin.take();
+    let mut stderr = child.stderr.take().expect("stderr was piped");
+    let input = content.to_string();
+    let feed = tokio::spawn(async move {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+    });
+    let drain = async {
+        let mut err = Vec::new();
+        let _ = stderr.read_to_end(&mut err).await;
+        let status = child.wait().await;
+        (status, err)
+    };
+
+    let (status, err) = tokio::select! {
+        result = drain => result,
+        _ = tokio::time::sleep(Duration::from_secs(CHECK_TIMEOUT_SECS)) => {
+            return Ok(Err(format!("{name} timed out after {CHECK_TIMEOUT_SECS} seconds.")));
+        }
+        _ = cancellation.cancelled() => {
+            return Ok(Err(format!("{name} was cancelled.")));
+        }
+    };
+    feed.await?;
+
+    if status?.success() {
+        return Ok(Ok(None));
+    }
+    // rustfmt and bash name their input `<stdin>` / `bash`; point the
+    // messages at the file instead.
+    let shown = path.display().to_string();
+    let errors = String::from_utf8_lossy(&err)
+        .replace("<stdin>", &shown)
+        .replace("bash: line ", &format!("{shown}: line "));
+    Ok(Ok(Some(errors)))
+}
+
+/// Parse `content` as JSON, in process.
+fn check_json(content: &str) -> CheckResult {
+    serde_json::from_str::<serde::de::IgnoredAny>(content)
+        .err()
+        .map(|e| format!("{e}\n"))
+}
+
+/// Keep the first [`MAX_ERROR_LINES`] lines of `errors`, noting how
+/// many were dropped.
+fn cap_lines(errors: &str) -> String {
+    let lines: Vec<&str> = errors.trim_end().lines().collect();
+    if lines.len() <= MAX_ERROR_LINES {
+        return lines.join("\n");
+    }
+    format!(
+        "{}\n[... {} more lines]",
+        lines[..MAX_ERROR_LINES].join("\n"),
+        lines.len() - MAX_ERROR_LINES
+    )
+}
+
+impl ToolDefinition for SyntaxCheckTool {
+    type Input = SyntaxCheckInput;
+
+    fn name(&self) -> &'static str {
+        "syntax_check"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+                true,
+            ));
+        }
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(outcome(&input.path, message, true));
+        }
+
+        let Some(checker) = Checker::for_path(path) else {
+            return Ok(outcome(
+                &input.path,
+                format!(
+                    "'{}' was not checked: no syntax checker is known for this file type. syntax_check handles .rs, .py, .js/.mjs/.cjs, .sh and .json.",
+                    input.path
+                ),
+                false,
+            ));
+        };
+
+        let max_file_size = self.max_file_size;
+        let owned = path.to_path_buf();
+        let read = tokio::task::spawn_blocking(move || {
+            check_file_size(
+                &owned,
+                max_file_size,
+                "Check it with the project's own build instead.",
+            )?;
+            std::fs::read_to_string(&owned)
+                .map_err(|e| format!("Failed to read file '{}': {e}", owned.display()))
+        })
+        .await?;
+        let content = match read {
+            Ok(content) => content,
+            Err(message) => return Ok(outcome(&input.path, message, true)),
+        };
+
+        let result = if checker == Checker::Json {
+            check_json(&content)
+        } else {
+            let Some(program) = checker.program() else {
+                return Ok(outcome(
+                    &input.path,
+                    format!(
+                        "'{}' was not checked: {} is not installed.",
+                        input.path,
+                        checker.name()
+                    ),
+                    false,
+                ));
+            };
+            match run_checker(ctx.cancellation(), checker, program, path, &content).await? {
+                Ok(result) => result,
+                Err(message) => return Ok(outcome(&input.path, message, true)),
+            }
+        };
+
+        Ok(match result {
+            None => outcome(
+                &input.path,
+                format!("'{}' parses cleanly ({}).", input.path, checker.name()),
+                false,
+            ),
+            Some(errors) => outcome(
+                &input.path,
+                format!(
+                    "Syntax errors in '{}' ({}):\n\n{}",
+                    input.path,
+                    checker.name(),
+                    cap_lines(&errors)
+                ),
+                true,
+            ),
+        })
+    }
+}
+
+/// A result whose `message` is both the wire content and the detail
+/// body.
+fn outcome(path: &str, message: String, is_error: bool) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(message.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: message,
+        },
+        is_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use std::fs;
+    use tempfile::TempDir;
+
+    async fn check(path: &Path) -> ToolOutcome {
+        check_with(SyntaxCheckTool::default(), path).await
+    }
+
+    async fn check_with(tool: SyntaxCheckTool, path: &Path) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        tool.execute(
+            &mut ctx,
+            SyntaxCheckInput {
+                path: path.display().to_string(),
+            },
+        )
+        .await
+        .expect("execute")
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn picks_the_checker_by_extension() {
+        for (file, expected) in [
+            ("a.rs", Some(Checker::Rustfmt)),
+            ("a.py", Some(Checker::Python)),
+            ("a.mjs", Some(Checker::Node)),
+            ("a.sh", Some(Checker::Bash)),
+            ("a.json", Some(Checker::Json)),
+            ("a.ts", None),
+            ("Makefile", None),
+        ] {
+            assert_eq!(Checker::for_path(Path::new(file)), expected, "{file}");
+        }
+    }
+
+    #[test]
+    fn long_error_output_is_capped() {
+        let errors: String = (0..50).map(|n| format!("error {n}\n")).collect();
+        let capped = cap_lines(&errors);
+        assert!(
+            capped.ends_with("error 39\n[... 10 more lines]"),
+            "{capped}"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_types_are_skipped_and_json_is_parsed_in_process() {
+        let dir = TempDir::new().unwrap();
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, "anything {").unwrap();
+        let outcome = check(&notes).await;
+        assert!(!outcome.is_error);
+        assert!(text(&outcome).contains("was not checked"));
+
+        let json = dir.path().join("a.json");
+        fs::write(&json, "{\"a\": [1, 2,]}").unwrap();
+        let outcome = check(&json).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("line 1 column"),
+            "{}",
+            text(&outcome)
+        );
+
+        fs::write(&json, "{\"a\": [1, 2]}").unwrap();
+        let outcome = check(&json).await;
+        assert!(!outcome.is_error);
+        assert!(text(&outcome).contains("parses cleanly"));
+    }
+
+    #[tokio::test]
+    async fn files_over_the_size_cap_are_refused() {
+        let dir = TempDir::new().unwrap();
+        let json = dir.path().join("big.json");
+        fs::write(&json, format!("[{}0]", "0,".repeat(50))).unwrap();
+        let outcome = check_with(SyntaxCheckTool::default().with_max_file_size(10), &json).await;
+        assert!(outcome.is_error);
+        assert!(
+            text(&outcome).contains("is too large"),
+            "{}",
+            text(&outcome)
+        );
+    }
+
+    #[tokio::test]
+    async fn rust_syntax_errors_name_the_file() {
+        if !on_path("rustfmt") {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "mod elsewhere;\nfn main() { let x = ; }\n").unwrap();
+
+        let outcome = check(&path).await;
+        assert!(outcome.is_error);
+        let shown = text(&outcome);
+        assert!(shown.contains(&format!("{}:2", path.display())), "{shown}");
+        assert!(!shown.contains("<stdin>"), "{shown}");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "mod elsewhere;\nfn main() { let x = ; }\n"
+        );
+
+        fs::write(&path, "mod elsewhere;\nfn main(){let x=1;}\n").unwrap();
+        let outcome = check(&path).await;
+        assert!(!outcome.is_error, "{}", text(&outcome));
+    }
+
+    #[tokio::test]
+    async fn python_errors_come_without_a_traceback() {
+        if Checker::Python.program().is_none() {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.py");
+        fs::write(&path, "def f(:\n    pass\n").unwrap();
+
+        let outcome = check(&path).await;
+        assert!(outcome.is_error);
+        let shown = text(&outcome);
+        assert!(shown.contains("SyntaxError"), "{shown}");
+        assert!(!shown.contains("Traceback"), "{shown}");
+        assert!(!dir.path().join("__pycache__").exists());
+    }
+}
@@ -316,12 +316,12 @@ mod tests {
     /// Start `command` as a background bash task on `ctx`, returning
     /// the task id and spill path.
     async fn start_background(ctx: &mut DummyToolContext, command: &str) -> (TaskId, PathBuf) {
-        let outcome = BashTool
+        let outcome = BashTool::default()
             .execute(
                 ctx,
                 BashInput {
                     command: command.to_string(),
-                    timeout: 30,
+                    timeout: Some(30),
                     description: "test background".to_string(),
                     run_in_background: true,
                 },
@@ -25,6 +25,15 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 
+use crate::editorconfig::{EDITORCONFIG_NOTE, apply_editorconfig};
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fs_retry;
+use crate::listing::write_confirmation;
+use crate::normalize::{NORMALIZED_NOTE, normalize_for_write};
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+use crate::trash::Trash;
+
 const DESCRIPTION: &str = r#"
 Write a file to the local file system.
 
@@ -37,7 +46,91 @@ Usage:
 "#;
 
 #[derive(Clone)]
-pub struct WriteFileTool;
+pub struct WriteFileTool {
+    /// Normalize trailing whitespace and the final newline of the content
+    /// before writing; see [`crate::normalize`]. Off by default.
+    normalize_on_write: bool,
+    /// Adjust the content to the target's `.editorconfig` before
+    /// writing; see [`crate::editorconfig`]. Off by default.
+    editorconfig: bool,
+    /// An existing file larger than this many bytes is refused rather
+    /// than read for the diff; `0` disables the cap. See
+    /// [`crate::file_size`].
+    max_file_size: u64,
+    /// Paths refused outright, whatever the content; see
+    /// [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+    /// Where the prior version goes before a write, `None` to keep
+    /// nothing; see [`crate::trash`].
+    trash: Option<Trash>,
+    /// Append the file's size, mtime and a directory listing to the
+    /// result; see [`crate::listing`]. Off by default.
+    confirm_with_listing: bool,
+}
+
+impl WriteFileTool {
+    /// Construct with an explicit `normalize_on_write` policy.
+    pub fn with_normalize_on_write(normalize_on_write: bool) -> Self {
+        Self {
+            normalize_on_write,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse to modify paths matching the `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Copy a file's prior version into `trash` before overwriting it.
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Adjust written content to the target's `.editorconfig`.
+    pub fn with_editorconfig(mut self, editorconfig: bool) -> Self {
+        self.editorconfig = editorconfig;
+        self
+    }
+
+    /// Confirm each write with the file's on-disk size and its
+    /// directory's listing.
+    pub fn with_confirm_listing(mut self, confirm_with_listing: bool) -> Self {
+        self.confirm_with_listing = confirm_with_listing;
+        self
+    }
+}
+
+impl Default for WriteFileTool {
+    fn default() -> Self {
+        Self {
+            normalize_on_write: false,
+            editorconfig: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: ProtectedPaths::default(),
+            allowed_roots: AllowedRoots::default(),
+            trash: None,
+            confirm_with_listing: false,
+        }
+    }
+}
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
 pub struct WriteFileInput {
@@ -78,6 +171,21 @@ impl ToolDefinition for WriteFileTool {
             ));
         }
 
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if let Err(message) = self.protected_paths.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if let Err(message) = check_file_size(
+            path,
+            self.max_file_size,
+            "Replace it with bash instead, or remove it first.",
+        ) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
         // Snapshot the previous content so the structured `Diff`
         // payload can show a unified diff against the new bytes.
         // Missing files surface as an empty `before`; any other read
@@ -93,7 +201,15 @@ impl ToolDefinition for WriteFileTool {
 
         let display_path = display_relative(path, &ctx.working_directory());
 
-        if let Err(e) = fs::write(path, &input.content) {
+        let (content, normalized) = normalize_for_write(self.normalize_on_write, input.content);
+        let (content, editorconfig_applied) = apply_editorconfig(self.editorconfig, path, content);
+        if let Some(trash) = &self.trash
+            && let Err(message) = trash.keep(path, &ctx.working_directory())
+        {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if let Err(e) = fs_retry::write(path, &content).await {
             return Ok(error_outcome(
                 &input.path,
                 format!("Failed to write file '{}': {}", input.path, e),
@@ -101,14 +217,23 @@ impl ToolDefinition for WriteFileTool {
         }
 
         let action = if file_existed { "overwrote" } else { "created" };
-        let return_value = format!("Successfully {} file '{}'", action, input.path);
+        let mut return_value = format!("Successfully {} file '{}'", action, input.path);
+        if normalized {
+            return_value.push_str(NORMALIZED_NOTE);
+        }
+        if editorconfig_applied {
+            return_value.push_str(EDITORCONFIG_NOTE);
+        }
+        if self.confirm_with_listing {
+            return_value.push_str(&write_confirmation(path));
+        }
 
         Ok(ToolOutcome {
             content: vec![UserContent::text(return_value)],
             details: ToolDetails::Diff {
                 path: display_path,
                 before: original_content.unwrap_or_default(),
-                after: input.content,
+                after: content,
             },
             is_error: false,
         })
@@ -167,7 +292,7 @@ mod tests {
         let target = dir.path().join("new.txt");
 
         let mut ctx = DummyToolContext::default();
-        let outcome = WriteFileTool
+        let outcome = WriteFileTool::default()
             .execute(
                 &mut ctx,
                 WriteFileInput {
@@ -204,6 +329,38 @@ mod tests {
         assert_eq!(on_disk, "hello\nworld\n");
     }
 
+    /// With `confirm_with_listing` the result reports the file as read
+    /// back from disk and lists its directory; without it, neither.
+    #[tokio::test]
+    async fn confirm_with_listing_appends_size_and_directory() {
+        let dir = TempDir::new().expect("temp dir");
+        fs::write(dir.path().join("other.txt"), "x").expect("seed");
+        let target = dir.path().join("new.txt");
+        let input = WriteFileInput {
+            path: target.display().to_string(),
+            content: "hello\n".to_string(),
+        };
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = WriteFileTool::default()
+            .execute(&mut ctx, input.clone())
+            .await
+            .expect("execute");
+        assert!(!extract_text(&outcome.content).contains("On disk"));
+
+        let outcome = WriteFileTool::default()
+            .with_confirm_listing(true)
+            .execute(&mut ctx, input)
+            .await
+            .expect("execute");
+        let wire = extract_text(&outcome.content);
+        assert!(wire.contains("\nOn disk: 6B, modified "), "wire: {wire:?}");
+        assert!(
+            wire.contains("(2 entries):\n  new.txt  6B\n  other.txt  1B"),
+            "wire: {wire:?}"
+        );
+    }
+
     /// Overwrites an existing file. The wire content reports an
     /// "overwrote" action; the structured `Diff` carries the prior
     /// bytes as `before` and the new bytes as `after` so the renderer
@@ -216,7 +373,7 @@ mod tests {
         let path = file.path().to_path_buf();
 
         let mut ctx = DummyToolContext::default();
-        let outcome = WriteFileTool
+        let outcome = WriteFileTool::default()
             .execute(
                 &mut ctx,
                 WriteFileInput {
@@ -253,7 +410,7 @@ mod tests {
     #[tokio::test]
     async fn relative_path_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
-        let outcome = WriteFileTool
+        let outcome = WriteFileTool::default()
             .execute(
                 &mut ctx,
                 WriteFileInput {
@@ -278,7 +435,7 @@ mod tests {
     #[tokio::test]
     async fn write_failure_returns_error_outcome() {
         let mut ctx = DummyToolContext::default();
-        let outcome = WriteFileTool
+        let outcome = WriteFileTool::default()
             .execute(
                 &mut ctx,
                 WriteFileInput {
@@ -298,10 +455,141 @@ mod tests {
         }
     }
 
+    /// With `normalize_on_write`, the written bytes and the `Diff`'s
+    /// `after` are the normalized content, and the wire reply says so.
+    #[tokio::test]
+    async fn normalize_on_write_trims_and_reports() {
+        let dir = TempDir::new().expect("temp dir");
+        let target = dir.path().join("ws.txt");
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = WriteFileTool::with_normalize_on_write(true)
+            .execute(
+                &mut ctx,
+                WriteFileInput {
+                    path: target.display().to_string(),
+                    content: "a  \nb\t\n\n".to_string(),
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        assert!(extract_text(&outcome.content).contains("trailing whitespace was trimmed"));
+        match &outcome.details {
+            ToolDetails::Diff { after, .. } => assert_eq!(after, "a\nb\n"),
+            other => panic!("expected Diff details, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(&target).expect("read back"), "a\nb\n");
+    }
+
+    /// With `respect_editorconfig`, the project's `.editorconfig` shapes
+    /// the written bytes and the `Diff`'s `after`, and the reply says so.
+    #[tokio::test]
+    async fn editorconfig_is_applied_and_reported() {
+        let dir = TempDir::new().expect("temp dir");
+        fs::write(
+            dir.path().join(".editorconfig"),
+            "root = true\n[*.py]\nindent_style = space\nindent_size = 4\ninsert_final_newline = true\n",
+        )
+        .unwrap();
+        let target = dir.path().join("a.py");
+
+        let mut ctx = DummyToolContext::default();
+        let outcome = WriteFileTool::default()
+            .with_editorconfig(true)
+            .execute(
+                &mut ctx,
+                WriteFileInput {
+                    path: target.display().to_string(),
+                    content: "def f():\n\treturn 1".to_string(),
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        assert!(extract_text(&outcome.content).contains(".editorconfig"));
+        let expected = "def f():\n    return 1\n";
+        match &outcome.details {
+            ToolDetails::Diff { after, .. } => assert_eq!(after, expected),
+            other => panic!("expected Diff details, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(&target).expect("read back"), expected);
+    }
+
     /// Locks in `Sequential` execution mode — the agent's batching
     /// logic relies on this to serialize filesystem mutations.
     #[test]
     fn execution_mode_is_sequential() {
-        assert_eq!(WriteFileTool.execution_mode(), ExecutionMode::Sequential);
+        assert_eq!(
+            WriteFileTool::default().execution_mode(),
+            ExecutionMode::Sequential
+        );
+    }
+
+    /// With a trash configured, overwriting keeps the prior content
+    /// under the trash directory before the new bytes land.
+    #[tokio::test]
+    async fn overwrite_keeps_the_prior_version_in_the_trash() {
+        let dir = TempDir::new().expect("temp dir");
+        let target = dir.path().join("a.txt");
+        fs::write(&target, "before\n").unwrap();
+
+        let mut ctx = DummyToolContext {
+            working_directory: dir.path().to_path_buf(),
+            ..DummyToolContext::default()
+        };
+        let trash = Trash::default();
+        let outcome = WriteFileTool::default()
+            .with_trash(Some(trash.clone()))
+            .execute(
+                &mut ctx,
+                WriteFileInput {
+                    path: target.display().to_string(),
+                    content: "after\n".to_string(),
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(!outcome.is_error);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "after\n");
+        let versions = trash.list(dir.path(), 10);
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].original, target);
+        assert_eq!(
+            fs::read_to_string(&versions[0].trashed).unwrap(),
+            "before\n"
+        );
+    }
+
+    /// A path that escapes the allowed roots through `..` is refused
+    /// and nothing is written.
+    #[tokio::test]
+    async fn paths_outside_the_allowed_roots_are_refused() {
+        let dir = TempDir::new().expect("temp dir");
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        let escaped = repo.join("../outside.txt");
+
+        let mut ctx = DummyToolContext {
+            working_directory: repo.clone(),
+            ..DummyToolContext::default()
+        };
+        let outcome = WriteFileTool::default()
+            .with_allowed_roots(AllowedRoots::new([repo.clone()]))
+            .execute(
+                &mut ctx,
+                WriteFileInput {
+                    path: escaped.display().to_string(),
+                    content: "x".to_string(),
+                },
+            )
+            .await
+            .expect("execute");
+
+        assert!(outcome.is_error);
+        assert!(!dir.path().join("outside.txt").exists());
     }
 }
@@ -0,0 +1,548 @@
+//! `write_range` builtin — replace a line range of an existing file.
+//!
+//! The middle ground between `edit_file`, which needs a unique anchor
+//! string, and `write_file`, which resends the whole file: lines
+//! `start_line..=end_line` (1-based, inclusive) are replaced with
+//! `content` and everything else is kept byte for byte. `end_line =
+//! start_line - 1` selects an empty range, so the same call inserts
+//! before `start_line` (or appends, with `start_line` one past the last
+//! line); an empty `content` deletes the range. The range is checked
+//! against the file's line count before anything is written.
+//!
+//! `content` gets the file's line ending appended when it lacks one and
+//! lines follow it, so the next line never gets glued on; at the end
+//! of the file the original trailing-newline state is kept. See
+//! [`replace_lines`], which the edit-approval preview shares.
+//!
+//! Returns a [`ToolOutcome`] whose `details` is [`ToolDetails::Diff`]
+//! on success, like the other editing tools. The wire `content` names
+//! the range, the new line count and how far the following lines
+//! moved, so the model can keep addressing the file by line number.
+//!
+//! Recoverable errors (path-not-absolute, missing file, bad range,
+//! read / write failure) come back as `is_error: true` outcomes
+//! carrying [`ToolDetails::Text`]. [`execution_mode`] is
+//! [`ExecutionMode::Sequential`] because this tool mutates the
+//! filesystem.
+//!
+//! [`execution_mode`]: ToolDefinition::execution_mode
+
+use aj_agent::tool::{ExecutionMode, ToolContext, ToolDefinition, ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::editorconfig::{EDITORCONFIG_NOTE, apply_editorconfig};
+use crate::file_size::{DEFAULT_MAX_FILE_SIZE, check_file_size};
+use crate::fs_retry;
+use crate::normalize::{INSERTED_NORMALIZED_NOTE, normalize_inserted};
+use crate::protected::ProtectedPaths;
+use crate::roots::AllowedRoots;
+use crate::trash::Trash;
+
+const DESCRIPTION: &str = r#"
+Replace a range of lines in an existing file, leaving the rest untouched.
+
+Usage:
+
+- The path parameter must be an absolute path
+- The file must exist; use write_file to create one
+- start_line and end_line are 1-based and inclusive, as shown by read_file
+- Lines start_line through end_line are replaced with content; an empty content
+  deletes them
+- To insert without replacing, set end_line to start_line - 1: the content goes
+  before start_line (or at the end of the file when start_line is one past the
+  last line)
+- end_line must not be past the last line of the file
+- A line ending is added to content when it lacks one and more lines follow
+- Read the range first: line numbers shift after every edit that changes the
+  line count, and the result says by how much
+- Prefer edit_file when a unique anchor string is at hand
+"#;
+
+#[derive(Clone)]
+pub struct WriteRangeTool {
+    /// Trim trailing whitespace from the inserted lines before writing;
+    /// see [`crate::normalize`]. Off by default.
+    normalize_on_write: bool,
+    /// Adjust the content to the target's `.editorconfig` before
+    /// writing; see [`crate::editorconfig`]. Off by default.
+    editorconfig: bool,
+    /// Files larger than this many bytes are refused before reading;
+    /// `0` disables the cap. See [`crate::file_size`].
+    max_file_size: u64,
+    /// Paths refused outright, whatever the content; see
+    /// [`crate::protected`].
+    protected_paths: ProtectedPaths,
+    /// Directories every path must resolve inside; see
+    /// [`crate::roots`].
+    allowed_roots: AllowedRoots,
+    /// Where the prior version goes before a write, `None` to keep
+    /// nothing; see [`crate::trash`].
+    trash: Option<Trash>,
+}
+
+impl WriteRangeTool {
+    /// Construct with an explicit `normalize_on_write` policy.
+    pub fn with_normalize_on_write(normalize_on_write: bool) -> Self {
+        Self {
+            normalize_on_write,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the file size cap (default [`DEFAULT_MAX_FILE_SIZE`]).
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Refuse to modify paths matching the `protected_paths` patterns.
+    pub fn with_protected_paths(mut self, protected_paths: ProtectedPaths) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// Refuse paths outside `allowed_roots`.
+    pub fn with_allowed_roots(mut self, allowed_roots: AllowedRoots) -> Self {
+        self.allowed_roots = allowed_roots;
+        self
+    }
+
+    /// Copy a file's prior version into `trash` before overwriting it.
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Adjust written content to the target's `.editorconfig`.
+    pub fn with_editorconfig(mut self, editorconfig: bool) -> Self {
+        self.editorconfig = editorconfig;
+        self
+    }
+}
+
+impl Default for WriteRangeTool {
+    fn default() -> Self {
+        Self {
+            normalize_on_write: false,
+            editorconfig: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            protected_paths: ProtectedPaths::default(),
+            allowed_roots: AllowedRoots::default(),
+            trash: None,
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+pub struct WriteRangeInput {
+    /// The absolute path to the file to modify.
+    pub path: String,
+    /// First line to replace, 1-based.
+    pub start_line: usize,
+    /// Last line to replace, inclusive. Use start_line - 1 to insert before start_line.
+    pub end_line: usize,
+    /// The new text for the range. Empty to delete the lines.
+    pub content: String,
+}
+
+impl ToolDefinition for WriteRangeTool {
+    type Input = WriteRangeInput;
+
+    fn name(&self) -> &'static str {
+        "write_range"
+    }
+
+    fn description(&self) -> &'static str {
+        DESCRIPTION
+    }
+
+    /// `write_range` mutates the filesystem, so it runs in
+    /// `Sequential` mode like the other editing tools.
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut dyn ToolContext,
+        input: Self::Input,
+    ) -> Result<ToolOutcome, aj_agent::BoxError> {
+        let path = Path::new(&input.path);
+        if !path.is_absolute() {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Path must be absolute, got: {}", input.path),
+            ));
+        }
+
+        if let Err(message) = self.allowed_roots.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+        if let Err(message) = self.protected_paths.check(path, &ctx.working_directory()) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if !path.exists() {
+            return Ok(error_outcome(
+                &input.path,
+                format!(
+                    "File '{}' does not exist; use write_file to create it",
+                    input.path
+                ),
+            ));
+        }
+
+        if let Err(message) = check_file_size(
+            path,
+            self.max_file_size,
+            "Edit it with bash (e.g. sed -i) instead.",
+        ) {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        let original_content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(error_outcome(
+                    &input.path,
+                    format!("Failed to read file '{}': {}", input.path, e),
+                ));
+            }
+        };
+
+        // The range is whole lines, so its last line is trimmed too;
+        // the lines around it are left as they are.
+        let (range_content, normalized) =
+            normalize_inserted(self.normalize_on_write, &input.content, true);
+        let replaced = match replace_lines(
+            &original_content,
+            input.start_line,
+            input.end_line,
+            &range_content,
+        ) {
+            Ok(replaced) => replaced,
+            Err(message) => return Ok(error_outcome(&input.path, message)),
+        };
+
+        let display_path = display_relative(path, &ctx.working_directory());
+
+        let (content, editorconfig_applied) = apply_editorconfig(self.editorconfig, path, replaced);
+        if let Some(trash) = &self.trash
+            && let Err(message) = trash.keep(path, &ctx.working_directory())
+        {
+            return Ok(error_outcome(&input.path, message));
+        }
+
+        if let Err(e) = fs_retry::write(path, &content).await {
+            return Ok(error_outcome(
+                &input.path,
+                format!("Failed to write file '{}': {}", input.path, e),
+            ));
+        }
+
+        let mut return_value = summary(
+            &input.path,
+            input.start_line,
+            input.end_line,
+            input.content.lines().count(),
+            original_content.lines().count(),
+            content.lines().count(),
+        );
+        if normalized {
+            return_value.push_str(INSERTED_NORMALIZED_NOTE);
+        }
+        if editorconfig_applied {
+            return_value.push_str(EDITORCONFIG_NOTE);
+        }
+
+        Ok(ToolOutcome {
+            content: vec![UserContent::text(return_value)],
+            details: ToolDetails::Diff {
+                path: display_path,
+                before: original_content,
+                after: content,
+            },
+            is_error: false,
+        })
+    }
+}
+
+/// Replace lines `start..=end` (1-based) of `content` with
+/// `replacement`, or explain why the range doesn't fit the file.
+/// `end == start - 1` inserts before `start` without replacing.
+///
+/// A non-empty `replacement` without a final newline gets the file's
+/// line ending (`\r\n` when the file uses it) when lines follow the
+/// range, or when the range reaches the end of a file that ended with
+/// one.
+pub fn replace_lines(
+    content: &str,
+    start: usize,
+    end: usize,
+    replacement: &str,
+) -> Result<String, String> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let total = lines.len();
+    if start == 0 {
+        return Err("start_line must be at least 1 (line numbers are 1-based)".to_string());
+    }
+    if end + 1 < start {
+        return Err(format!(
+            "end_line={end} is before start_line={start}; use end_line={} to insert \
+             before line {start} without replacing anything",
+            start - 1
+        ));
+    }
+    if end > total {
+        return Err(match total {
+            0 => format!(
+                "end_line={end} is past the end of the file, which is empty. \
+                 Use start_line=1 and end_line=0 to insert."
+            ),
+            _ => format!(
+                "end_line={end} is past the end of the file, which has {total} lines. \
+                 Use an end_line of at most {total}."
+            ),
+        });
+    }
+
+    let eol = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut out = lines[..start - 1].concat();
+    let suffix = lines[end..].concat();
+    if !replacement.is_empty() && !out.is_empty() && !out.ends_with('\n') {
+        // Appending after a last line that had no line ending.
+        out.push_str(eol);
+    }
+    out.push_str(replacement);
+    let needs_eol = if suffix.is_empty() {
+        content.ends_with('\n')
+    } else {
+        true
+    };
+    if !replacement.is_empty() && !replacement.ends_with('\n') && needs_eol {
+        out.push_str(eol);
+    }
+    out.push_str(&suffix);
+    Ok(out)
+}
+
+/// The wire result: what happened to which lines, and how the lines
+/// after the range moved. `before` and `now` are the file's line
+/// counts around the write.
+fn summary(
+    path: &str,
+    start: usize,
+    end: usize,
+    written: usize,
+    before: usize,
+    now: usize,
+) -> String {
+    let replaced = end + 1 - start;
+    let action = match (replaced, written) {
+        (0, _) => format!(
+            "Inserted {written} {} before line {start} of file '{path}'",
+            plural(written)
+        ),
+        (_, 0) => format!("Deleted {} of file '{path}'", range(start, end)),
+        _ => format!(
+            "Replaced {} of file '{path}' with {written} {}",
+            range(start, end),
+            plural(written)
+        ),
+    };
+    let shift = if written == replaced || end >= before {
+        // Nothing moved, or nothing followed the range.
+        String::new()
+    } else if written > replaced {
+        format!("; later lines moved down by {}", written - replaced)
+    } else {
+        format!("; later lines moved up by {}", replaced - written)
+    };
+    format!("{action}. The file now has {now} {}{shift}.", plural(now))
+}
+
+fn range(start: usize, end: usize) -> String {
+    if start == end {
+        format!("line {start}")
+    } else {
+        format!("lines {start}-{end}")
+    }
+}
+
+fn plural(lines: usize) -> &'static str {
+    if lines == 1 { "line" } else { "lines" }
+}
+
+/// Resolve `path` against `cwd` for display, falling back to the raw
+/// path when stripping fails (e.g. the file lives outside the cwd).
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Build a recoverable error [`ToolOutcome`]; see `edit_file`'s
+/// counterpart for the rationale.
+fn error_outcome(path: &str, error: String) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(error.clone())],
+        details: ToolDetails::Text {
+            summary: PathBuf::from(path).display().to_string(),
+            body: error,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DummyToolContext;
+    use tempfile::TempDir;
+
+    fn extract_text(content: &[UserContent]) -> String {
+        content
+            .iter()
+            .filter_map(|c| match c {
+                UserContent::Text(t) => Some(t.text.as_str()),
+                UserContent::Image(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("")
+    }
+
+    async fn run(path: &Path, start_line: usize, end_line: usize, content: &str) -> ToolOutcome {
+        let mut ctx = DummyToolContext::default();
+        WriteRangeTool::default()
+            .execute(
+                &mut ctx,
+                WriteRangeInput {
+                    path: path.display().to_string(),
+                    start_line,
+                    end_line,
+                    content: content.to_string(),
+                },
+            )
+            .await
+            .expect("execute")
+    }
+
+    #[test]
+    fn replaces_inserts_appends_and_deletes() {
+        let file = "a\nb\nc\n";
+        assert_eq!(replace_lines(file, 2, 2, "B").unwrap(), "a\nB\nc\n");
+        assert_eq!(
+            replace_lines(file, 2, 3, "x\ny\nz\n").unwrap(),
+            "a\nx\ny\nz\n"
+        );
+        assert_eq!(replace_lines(file, 1, 0, "top").unwrap(), "top\na\nb\nc\n");
+        assert_eq!(replace_lines(file, 4, 3, "end").unwrap(), "a\nb\nc\nend\n");
+        assert_eq!(replace_lines(file, 1, 2, "").unwrap(), "c\n");
+        // The missing final newline is kept at the end, and an append
+        // after such a line doesn't glue onto it.
+        assert_eq!(replace_lines("a\nb", 2, 2, "B").unwrap(), "a\nB");
+        assert_eq!(replace_lines("a\nb", 3, 2, "c").unwrap(), "a\nb\nc");
+        assert_eq!(
+            replace_lines("a\r\nb\r\n", 1, 1, "A").unwrap(),
+            "A\r\nb\r\n"
+        );
+        assert_eq!(replace_lines("", 1, 0, "new\n").unwrap(), "new\n");
+    }
+
+    #[test]
+    fn ranges_outside_the_file_are_rejected() {
+        let file = "a\nb\nc\n";
+        assert!(
+            replace_lines(file, 0, 1, "x")
+                .unwrap_err()
+                .contains("1-based")
+        );
+        assert!(
+            replace_lines(file, 3, 1, "x")
+                .unwrap_err()
+                .contains("use end_line=2")
+        );
+        assert_eq!(
+            replace_lines(file, 2, 4, "x").unwrap_err(),
+            "end_line=4 is past the end of the file, which has 3 lines. \
+             Use an end_line of at most 3."
+        );
+        assert!(replace_lines("", 1, 1, "x").unwrap_err().contains("empty"));
+    }
+
+    #[test]
+    fn summary_names_the_range_and_the_shift() {
+        assert_eq!(
+            summary("/f", 2, 3, 3, 10, 11),
+            "Replaced lines 2-3 of file '/f' with 3 lines. The file now has 11 lines; \
+             later lines moved down by 1."
+        );
+        assert_eq!(
+            summary("/f", 5, 5, 0, 10, 9),
+            "Deleted line 5 of file '/f'. The file now has 9 lines; later lines moved up by 1."
+        );
+        assert_eq!(
+            summary("/f", 11, 10, 2, 10, 12),
+            "Inserted 2 lines before line 11 of file '/f'. The file now has 12 lines."
+        );
+    }
+
+    #[tokio::test]
+    async fn rewrites_only_the_range_and_reports_a_diff() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("big.txt");
+        let original: String = (1..=100).map(|n| format!("line {n}\n")).collect();
+        fs::write(&file, &original).unwrap();
+
+        let outcome = run(&file, 40, 42, "replaced").await;
+        assert!(!outcome.is_error, "{}", extract_text(&outcome.content));
+        let written = fs::read_to_string(&file).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 98);
+        assert_eq!(lines[38..41], ["line 39", "replaced", "line 43"]);
+        assert!(
+            extract_text(&outcome.content).ends_with("later lines moved up by 2."),
+            "{}",
+            extract_text(&outcome.content)
+        );
+        match &outcome.details {
+            ToolDetails::Diff { before, after, .. } => {
+                assert_eq!(before, &original);
+                assert_eq!(after, &written);
+            }
+            other => panic!("expected Diff details, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn bad_range_or_missing_file_leaves_disk_alone() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("f.txt");
+        fs::write(&file, "a\nb\n").unwrap();
+
+        let outcome = run(&file, 1, 5, "x").await;
+        assert!(outcome.is_error);
+        assert!(extract_text(&outcome.content).contains("which has 2 lines"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\nb\n");
+
+        let missing = dir.path().join("missing.txt");
+        let outcome = run(&missing, 1, 0, "x").await;
+        assert!(outcome.is_error);
+        assert!(extract_text(&outcome.content).contains("use write_file"));
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn execution_mode_is_sequential() {
+        assert_eq!(
+            WriteRangeTool::default().execution_mode(),
+            ExecutionMode::Sequential
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Prior versions of overwritten files (`trash_writes`).
+//!
+//! With the option on, `write_file`, `edit_file`, `edit_file_multi`,
+//! `write_range`, `regex_replace` and `format_file` call
+//! [`Trash::keep`] right before writing, copying the file's current
+//! content to `<trash dir>/<timestamp>/<absolute path>`. A failed copy
+//! fails the call, so nothing is overwritten without its backup.
+//! Creating a file keeps nothing.
+//!
+//! The trash directory (`.aj-trash` under the working directory by
+//! default) gets a `.gitignore` and an `.ignore` matching everything,
+//! so git and the ignore-aware walkers (`count_lines`, fuzzy path
+//! lookup, ripgrep) skip it even under a non-hidden name.
+//! [`Trash::list`] and [`Trash::restore`] back the interactive
+//! `restore` command.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Default trash directory, relative to the working directory.
+pub const DEFAULT_TRASH_DIR: &str = ".aj-trash";
+
+/// Where trashed versions go. A relative directory is resolved
+/// against the working directory.
+#[derive(Clone, Debug)]
+pub struct Trash {
+    dir: PathBuf,
+}
+
+/// One kept version of a file, as found by [`Trash::list`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrashedVersion {
+    /// The path the content was written over.
+    pub original: PathBuf,
+    /// The copy inside the trash directory.
+    pub trashed: PathBuf,
+    /// The timestamp directory name, e.g. `20261017-120301-123`.
+    pub stamp: String,
+    pub size: u64,
+}
+
+impl Trash {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The trash directory for `working_directory`.
+    pub fn dir(&self, working_directory: &Path) -> PathBuf {
+        working_directory.join(&self.dir)
+    }
+
+    /// Copy the current content of `path` into a fresh timestamp
+    /// directory. Returns the copy, or `None` when there was nothing
+    /// to keep (the file doesn't exist yet, or lives in the trash).
+    pub(crate) fn keep(
+        &self,
+        path: &Path,
+        working_directory: &Path,
+    ) -> Result<Option<PathBuf>, String> {
+        let dir = self.dir(working_directory);
+        let absolute = working_directory.join(path);
+        if !absolute.is_file() || absolute.starts_with(&dir) {
+            return Ok(None);
+        }
+        let failed = |e: std::io::Error| {
+            format!(
+                "Failed to keep the previous version of '{}' in {}: {e}. Nothing was written.",
+                path.display(),
+                dir.display()
+            )
+        };
+        ensure_ignored(&dir).map_err(failed)?;
+
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+        let relative: PathBuf = absolute
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect();
+        // Two writes of one file within a millisecond get `-1`, `-2`, ...
+        let mut target = dir.join(&stamp).join(&relative);
+        let mut n = 0;
+        while target.exists() {
+            n += 1;
+            target = dir.join(format!("{stamp}-{n}")).join(&relative);
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(failed)?;
+        }
+        fs::copy(&absolute, &target).map_err(failed)?;
+        Ok(Some(target))
+    }
+
+    /// Kept versions under the trash directory, newest first, at most
+    /// `limit` of them. A missing trash directory lists nothing.
+    pub fn list(&self, working_directory: &Path, limit: usize) -> Vec<TrashedVersion> {
+        let dir = self.dir(working_directory);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+        let mut stamps: Vec<(String, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|e| Some((e.file_name().into_string().ok()?, e.path())))
+            .collect();
+        // Stamps sort chronologically as strings; `-N` suffixes sort
+        // after their base stamp.
+        stamps.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let mut versions = Vec::new();
+        for (stamp, stamp_dir) in stamps {
+            let mut files = Vec::new();
+            collect_files(&stamp_dir, &mut files);
+            files.sort();
+            for trashed in files {
+                let Ok(relative) = trashed.strip_prefix(&stamp_dir) else {
+                    continue;
+                };
+                let size = fs::metadata(&trashed).map(|m| m.len()).unwrap_or(0);
+                versions.push(TrashedVersion {
+                    original: Path::new(&Component::RootDir).join(relative),
+                    trashed: trashed.clone(),
+                    stamp: stamp.clone(),
+                    size,
+                });
+                if versions.len() >= limit {
+                    return versions;
+                }
+            }
+        }
+        versions
+    }
+
+    /// Put `version` back at its original path, keeping the content
+    /// it replaces in the trash first.
+    pub fn restore(
+        &self,
+        version: &TrashedVersion,
+        working_directory: &Path,
+    ) -> Result<(), String> {
+        self.keep(&version.original, working_directory)?;
+        if let Some(parent) = version.original.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        fs::copy(&version.trashed, &version.original)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to restore {}: {e}", version.original.display()))
+    }
+}
+
+impl Default for Trash {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRASH_DIR)
+    }
+}
+
+/// Create `dir` with ignore files matching everything, so searches
+/// and git leave the trash alone.
+fn ensure_ignored(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for name in [".gitignore", ".ignore"] {
+        let file = dir.join(name);
+        if !file.exists() {
+            fs::write(file, "*\n")?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => collect_files(&entry.path(), out),
+            Ok(t) if t.is_file() => out.push(entry.path()),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_prior_versions_and_lists_them_newest_first() {
+        let root = tempfile::tempdir().unwrap();
+        let cwd = root.path();
+        let file = cwd.join("src/a.txt");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let trash = Trash::default();
+
+        // Creating a file keeps nothing.
+        assert_eq!(trash.keep(&file, cwd).unwrap(), None);
+
+        fs::write(&file, "one").unwrap();
+        let first = trash.keep(&file, cwd).unwrap().unwrap();
+        fs::write(&file, "two").unwrap();
+        let second = trash.keep(&file, cwd).unwrap().unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "one");
+
+        let dir = cwd.join(DEFAULT_TRASH_DIR);
+        assert_eq!(fs::read_to_string(dir.join(".gitignore")).unwrap(), "*\n");
+        assert!(dir.join(".ignore").is_file());
+
+        let versions = trash.list(cwd, 10);
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].trashed, second);
+        assert_eq!(versions[0].original, file);
+        assert_eq!(versions[0].size, 3);
+        assert_eq!(trash.list(cwd, 1).len(), 1);
+    }
+
+    #[test]
+    fn restore_puts_a_version_back_and_keeps_the_current_one() {
+        let root = tempfile::tempdir().unwrap();
+        let cwd = root.path();
+        let file = cwd.join("a.txt");
+        let trash = Trash::new(cwd.join("bin"));
+
+        fs::write(&file, "old").unwrap();
+        trash.keep(&file, cwd).unwrap();
+        fs::write(&file, "new").unwrap();
+
+        let old = trash.list(cwd, 10).remove(0);
+        trash.restore(&old, cwd).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old");
+        let newest = trash.list(cwd, 1).remove(0);
+        assert_eq!(fs::read_to_string(&newest.trashed).unwrap(), "new");
+    }
+}
@@ -4,12 +4,16 @@
 //! The CLI surface is split into [`args`] (the [`clap`]-derived `Args`
 //! struct + dispatch enums) and [`file_args`] (turning `@path`
 //! arguments into `<file>`-wrapped text and image attachments).
+//! [`mentions`] does the same for `@path` tokens inside a prompt typed
+//! in the interactive editor. [`preload`] reads the `--preload` file.
 //! [`initial_input`] ties them together: it interprets the positional
 //! arguments as a mix of `@file` attachments and free-form messages and
 //! produces the content to auto-submit as the launch turn.
 
 pub mod args;
 pub mod file_args;
+pub mod mentions;
+pub mod preload;
 
 use std::path::Path;
 
@@ -50,10 +50,19 @@ pub struct Args {
 
     /// Output format for print mode. `text` (default) renders
     /// human-readable lines; `json` writes one JSONL event per
-    /// line. Implies `--print` when set.
+    /// line; `ndjson` writes a compact per-event summary stream keyed
+    /// by `"event"`; `raw-json` writes each finalized message as the
+    /// model SDK serializes it. Implies `--print` when set.
     #[arg(long, value_enum, default_value_t = PrintFormat::Text)]
     pub format: PrintFormat,
 
+    /// Show each tool call's full, pretty-printed JSON input above its
+    /// result in the interactive TUI, for this run only. The same as
+    /// setting `show_tool_input = true` in `~/.aj/config.toml`. Print
+    /// mode's `--format json` already carries the full input.
+    #[arg(long)]
+    pub verbose: bool,
+
     /// Free-form launch input. Each positional argument is either a
     /// `@file` attachment (its contents are wrapped in a `<file>` block
     /// and images are attached inline) or a message; the messages are
@@ -62,6 +71,14 @@ pub struct Args {
     /// [`crate::cli::initial_input`] for the full rules.
     pub prompt: Vec<String>,
 
+    /// JSON file of context and tool results to place in the transcript
+    /// before the first turn, without an API round trip. The file holds
+    /// an array whose entries are either a string (context text) or
+    /// `{"tool": ..., "input": {...}, "output": ...}` (a result for one
+    /// of the session's tools). See [`crate::cli::preload`].
+    #[arg(long, value_name = "FILE")]
+    pub preload: Option<String>,
+
     /// Replace the live model with a scripted fake that replays a
     /// canned
     /// [`AssistantMessageEvent`](aj_models::streaming::AssistantMessageEvent)
@@ -79,6 +96,13 @@ pub struct Args {
     #[arg(long)]
     pub scripted: Option<String>,
 
+    /// Validate the builtin tools' names, descriptions and input
+    /// schemas, print any problems and exit non-zero if there were
+    /// any. A maintenance check (see [`aj_tools::schema_check`]), so
+    /// hidden from `--help`.
+    #[arg(long, hide = true)]
+    pub check_tools: bool,
+
     /// Subcommand selector for the non-conversational utilities
     /// (`list-sessions`, `continue`, `update-models`).
     #[command(subcommand)]
@@ -96,6 +120,16 @@ pub enum PrintFormat {
     /// One JSONL [`aj_agent::events::AgentEvent`] per line. Stable
     /// shape suitable for piping into another process.
     Json,
+    /// One compact JSON record per line for the events a consumer
+    /// usually wants (assistant text, thinking, tool use, tool results,
+    /// usage), discriminated by an `"event"` field. Flushed per line,
+    /// so `jq` can follow a long run as it happens.
+    Ndjson,
+    /// One `{"agent", "message"}` object per finalized message, the
+    /// message being the full [`aj_models::types::Message`] (usage,
+    /// stop reason, every content block) in its wire serialization.
+    #[value(name = "raw-json")]
+    RawJson,
 }
 
 /// Non-conversational subcommands.
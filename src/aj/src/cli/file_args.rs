@@ -57,19 +57,31 @@ pub fn process_file_args(file_args: &[String], cwd: &Path) -> Result<ResolvedFil
             continue;
         }
 
-        match image::detect_mime_type_from_file(&path) {
-            Some(mime) => append_image(&path, &display, mime, &mut text, &mut images)?,
-            None => {
-                let content = std::fs::read_to_string(&path)
-                    .with_context(|| format!("could not read @file as text: {display}"))?;
-                text.push_str(&format!("<file name=\"{display}\">\n{content}\n</file>\n"));
-            }
-        }
+        append_file(&path, &display, &mut text, &mut images)?;
     }
 
     Ok(ResolvedFiles { text, images })
 }
 
+/// Append one existing, non-empty file: a `<file>` block on `text` for
+/// a text file, or an image attachment (see [`append_image`]).
+pub(crate) fn append_file(
+    path: &Path,
+    display: &str,
+    text: &mut String,
+    images: &mut Vec<UserContent>,
+) -> Result<()> {
+    match image::detect_mime_type_from_file(path) {
+        Some(mime) => append_image(path, display, mime, text, images),
+        None => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("could not read @file as text: {display}"))?;
+            text.push_str(&format!("<file name=\"{display}\">\n{content}\n</file>\n"));
+            Ok(())
+        }
+    }
+}
+
 /// Read, resize, and attach an image file. The resized payload rides on
 /// `images` as a [`UserContent::Image`]; a `<file>` text reference
 /// (carrying the dimension note when the image was scaled) is appended
@@ -104,7 +116,7 @@ fn append_image(
 /// symlinks are left intact and the path is normalized without touching
 /// the filesystem, so the `<file name>` we show matches what the user
 /// typed.
-fn resolve_path(arg: &str, cwd: &Path) -> PathBuf {
+pub(crate) fn resolve_path(arg: &str, cwd: &Path) -> PathBuf {
     let expanded = expand_tilde(arg);
     let joined = if expanded.is_absolute() {
         expanded
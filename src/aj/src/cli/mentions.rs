@@ -0,0 +1,204 @@
+//! Inline `@path` mentions in a typed prompt.
+//!
+//! The editor's autocomplete inserts `@path` (or `@"path with spaces"`)
+//! tokens; on submit, [`expand_mentions`] finds them and attaches the
+//! files the way the launch `@file` arguments are attached (see
+//! [`crate::cli::file_args`]): text files as `<file name="ABS">…</file>`
+//! blocks, images as image attachments. Each mention in the prompt is
+//! replaced by the file's absolute path so the sentence still reads and
+//! points at the matching `<file>` block.
+//!
+//! A mention is an `@` at the start of the prompt or after whitespace,
+//! so `user@host` is left alone. Unlike the launch arguments,
+//! resolution is lenient: a mention that can't be attached (missing
+//! file, directory, unreadable) stays in the prompt as typed and yields
+//! a warning instead of failing the submit. A token that doesn't look
+//! like a path at all (`@someone`) is left alone without a warning.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use aj_models::types::UserContent;
+
+use crate::cli::file_args::{append_file, resolve_path};
+
+/// Trailing punctuation dropped from an unquoted mention when the token
+/// as typed doesn't name a file (`see @src/lib.rs.`).
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '\'', '"'];
+
+/// A typed prompt with its `@path` mentions resolved.
+#[derive(Debug, Default)]
+pub struct ExpandedPrompt {
+    /// The prompt with each attached mention replaced by its path.
+    pub text: String,
+    /// `<file>` blocks for the attached files, in mention order.
+    pub files: String,
+    /// Image attachments, in mention order.
+    pub images: Vec<UserContent>,
+    /// One line per mention that couldn't be attached.
+    pub warnings: Vec<String>,
+}
+
+impl ExpandedPrompt {
+    /// Whether any mention was attached. When not, the prompt can be
+    /// sent as the plain text the user typed.
+    pub fn has_attachments(&self) -> bool {
+        !self.files.is_empty() || !self.images.is_empty()
+    }
+
+    /// Content blocks for the user message: the prompt, the `<file>`
+    /// blocks, then the images.
+    pub fn into_content(self) -> Vec<UserContent> {
+        let mut content = vec![UserContent::text(self.text)];
+        if !self.files.is_empty() {
+            content.push(UserContent::text(self.files));
+        }
+        content.extend(self.images);
+        content
+    }
+}
+
+/// Resolve the `@path` mentions in `prompt`, relative to `cwd`.
+pub fn expand_mentions(prompt: &str, cwd: &Path) -> ExpandedPrompt {
+    let mut expanded = ExpandedPrompt::default();
+    let mut attached = HashSet::new();
+    let mut rest = prompt;
+    let mut at_boundary = true;
+
+    while let Some(c) = rest.chars().next() {
+        let mention = if c == '@' && at_boundary {
+            parse_mention(&rest[1..])
+        } else {
+            None
+        };
+        let Some((raw, quoted, len)) = mention else {
+            expanded.text.push(c);
+            at_boundary = c.is_whitespace();
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        let token = &rest[..1 + len];
+        rest = &rest[1 + len..];
+        at_boundary = false;
+
+        // An unquoted token may carry sentence punctuation; prefer the
+        // token as typed when it names something, else the trimmed one.
+        let mut path_arg = raw;
+        let mut suffix = "";
+        if !quoted && !resolve_path(raw, cwd).exists() {
+            let trimmed = raw.trim_end_matches(TRAILING_PUNCTUATION);
+            if !trimmed.is_empty() {
+                path_arg = trimmed;
+                suffix = &raw[trimmed.len()..];
+            }
+        }
+
+        let path = resolve_path(path_arg, cwd);
+        let display = path.display().to_string();
+        match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => {
+                expanded
+                    .warnings
+                    .push(format!("@{path_arg} is a directory; it was not attached."));
+                expanded.text.push_str(token);
+            }
+            Ok(metadata) => {
+                // Empty files and repeat mentions are referenced
+                // without another `<file>` block.
+                let mut ok = true;
+                if metadata.len() > 0
+                    && attached.insert(display.clone())
+                    && let Err(e) =
+                        append_file(&path, &display, &mut expanded.files, &mut expanded.images)
+                {
+                    expanded.warnings.push(format!("@{path_arg}: {e:#}"));
+                    ok = false;
+                }
+                if ok {
+                    expanded.text.push_str(&display);
+                    expanded.text.push_str(suffix);
+                } else {
+                    expanded.text.push_str(token);
+                }
+            }
+            Err(_) => {
+                if looks_like_path(path_arg) {
+                    expanded
+                        .warnings
+                        .push(format!("@{path_arg}: file not found; it was not attached."));
+                }
+                expanded.text.push_str(token);
+            }
+        }
+    }
+    expanded
+}
+
+/// Parse the path after an `@`: a `"quoted path"` or a run of
+/// non-whitespace. Returns the path, whether it was quoted, and the
+/// byte length consumed after the `@`.
+fn parse_mention(after_at: &str) -> Option<(&str, bool, usize)> {
+    if let Some(quoted) = after_at.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        let path = &quoted[..end];
+        return (!path.is_empty()).then_some((path, true, end + 2));
+    }
+    let end = after_at.find(char::is_whitespace).unwrap_or(after_at.len());
+    let path = &after_at[..end];
+    (!path.is_empty()).then_some((path, false, end))
+}
+
+/// Whether a mention that named nothing was plausibly meant as a path,
+/// so its absence is worth a warning.
+fn looks_like_path(arg: &str) -> bool {
+    arg.contains('/') || arg.contains('.') || arg.starts_with('~')
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn attaches_mentioned_files_and_references_them() {
+        let dir = tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("src")).expect("mkdir");
+        std::fs::write(dir.path().join("src/lib.rs"), "fn a() {}").expect("write");
+        std::fs::write(dir.path().join("my notes.md"), "todo").expect("write");
+        let lib = dir.path().join("src/lib.rs").display().to_string();
+        let notes = dir.path().join("my notes.md").display().to_string();
+
+        let expanded = expand_mentions(
+            "explain @src/lib.rs, then @\"my notes.md\" and @src/lib.rs again",
+            dir.path(),
+        );
+        assert!(expanded.warnings.is_empty(), "{:?}", expanded.warnings);
+        assert_eq!(
+            expanded.text,
+            format!("explain {lib}, then {notes} and {lib} again")
+        );
+        assert_eq!(
+            expanded.files,
+            format!(
+                "<file name=\"{lib}\">\nfn a() {{}}\n</file>\n<file name=\"{notes}\">\ntodo\n</file>\n"
+            )
+        );
+        assert!(expanded.has_attachments());
+        assert_eq!(expanded.into_content().len(), 2);
+    }
+
+    #[test]
+    fn unattachable_mentions_stay_as_typed_with_a_warning() {
+        let dir = tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("src")).expect("mkdir");
+
+        let prompt = "ask @someone about @missing.rs and @src, mail me@example.com";
+        let expanded = expand_mentions(prompt, dir.path());
+        assert_eq!(expanded.text, prompt);
+        assert!(!expanded.has_attachments());
+        assert_eq!(expanded.warnings.len(), 2, "{:?}", expanded.warnings);
+        assert!(expanded.warnings[0].contains("@missing.rs: file not found"));
+        assert!(expanded.warnings[1].contains("@src is a directory"));
+    }
+}
@@ -0,0 +1,69 @@
+//! Loading of the `--preload` file.
+//!
+//! The file is a JSON array of [`Preload`] entries: a string is context
+//! text, an object `{"tool": ..., "input": {...}, "output": ...}` is a
+//! result for one of the session's tools. Its path resolves like an
+//! `@file` argument (relative to the working directory, `~` expanded).
+//! Both modes hand the entries to [`aj_agent::Agent::preload`] before
+//! the first turn of a session.
+//!
+//! Loading is strict: a missing or malformed file aborts startup, as a
+//! missing `@file` does.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use aj_agent::preload::Preload;
+
+use crate::cli::file_args::resolve_path;
+
+/// Read and parse the `--preload` file at `arg`, relative to `cwd`.
+pub fn load_preload(arg: &str, cwd: &Path) -> Result<Vec<Preload>> {
+    let path = resolve_path(arg, cwd);
+    let display = path.display();
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("cannot read preload file {display}"))?;
+    serde_json::from_str(&text).with_context(|| {
+        format!(
+            "preload file {display} must be a JSON array of strings or \
+             {{\"tool\", \"input\", \"output\"}} objects"
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_context_and_tool_results() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("preload.json"),
+            r#"["some context", {"tool": "bash", "input": {"command": "ls"}, "output": "a\nb"}]"#,
+        )
+        .unwrap();
+        let entries = load_preload("preload.json", dir.path()).unwrap();
+        assert_eq!(entries[0], Preload::Context("some context".to_string()));
+        let Preload::ToolResult {
+            tool,
+            input,
+            output,
+        } = &entries[1]
+        else {
+            panic!("expected a tool result: {entries:?}");
+        };
+        assert_eq!(tool, "bash");
+        assert_eq!(input["command"], "ls");
+        assert_eq!(output, "a\nb");
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("bad.json"), r#"[{"tool": "bash"}]"#).unwrap();
+        assert!(load_preload("bad.json", dir.path()).is_err());
+        assert!(load_preload("missing.json", dir.path()).is_err());
+    }
+}
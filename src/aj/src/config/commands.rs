@@ -134,6 +134,14 @@ pub const COMMANDS: &[Command] = &[
         action_id: None,
         action: CommandAction::ExportHtml,
     },
+    Command {
+        name: "debug-request",
+        title: "debug request",
+        category: "session",
+        description: "Write the next request payload to a JSON file, secrets redacted.",
+        action_id: None,
+        action: CommandAction::DebugNextRequest,
+    },
     Command {
         name: "compact",
         title: "compact",
@@ -142,6 +150,30 @@ pub const COMMANDS: &[Command] = &[
         action_id: None,
         action: CommandAction::Compact,
     },
+    Command {
+        name: "context",
+        title: "messages",
+        category: "session",
+        description: "List the messages in the model's context with their estimated size.",
+        action_id: None,
+        action: CommandAction::OpenContextList,
+    },
+    Command {
+        name: "breakdown",
+        title: "token breakdown",
+        category: "session",
+        description: "Estimate how the context splits across tool results, thinking, text, and the system prompt.",
+        action_id: None,
+        action: CommandAction::OpenContextBreakdown,
+    },
+    Command {
+        name: "drop",
+        title: "drop message",
+        category: "session",
+        description: "Remove the message whose number is in the editor from the model's context.",
+        action_id: None,
+        action: CommandAction::DropMessage,
+    },
     Command {
         name: "history",
         title: "history",
@@ -150,6 +182,14 @@ pub const COMMANDS: &[Command] = &[
         action_id: Some(crate::config::keybindings::ACTION_HISTORY_OPEN),
         action: CommandAction::OpenPromptHistory,
     },
+    Command {
+        name: "focus",
+        title: "next-turn context",
+        category: "prompt",
+        description: "Use the editor's text as extra system context for the next turn only.",
+        action_id: None,
+        action: CommandAction::SetTurnContext,
+    },
     Command {
         name: "agents",
         title: "switch",
@@ -158,6 +198,54 @@ pub const COMMANDS: &[Command] = &[
         action_id: Some(crate::config::keybindings::ACTION_AGENT_PICKER),
         action: CommandAction::OpenAgentPicker,
     },
+    Command {
+        name: "tools",
+        title: "tools",
+        category: "agent",
+        description: "Switch individual tools off or back on for this session.",
+        action_id: None,
+        action: CommandAction::OpenTools,
+    },
+    Command {
+        name: "restore",
+        title: "restore",
+        category: "session",
+        description: "Put back a file version kept by trash_writes.",
+        action_id: None,
+        action: CommandAction::OpenRestore,
+    },
+    Command {
+        name: "summary",
+        title: "session summary",
+        category: "session",
+        description: "List the files this session changed and count its bash commands.",
+        action_id: None,
+        action: CommandAction::ShowChangeSummary,
+    },
+    Command {
+        name: "approvals",
+        title: "remembered approvals",
+        category: "session",
+        description: "List the files whose edits apply without asking (\"Always apply\").",
+        action_id: None,
+        action: CommandAction::ListApprovals,
+    },
+    Command {
+        name: "forget-approvals",
+        title: "forget approvals",
+        category: "session",
+        description: "Ask again before editing files approved with \"Always apply\".",
+        action_id: None,
+        action: CommandAction::ForgetApprovals,
+    },
+    Command {
+        name: "permissions",
+        title: "permissions",
+        category: "session",
+        description: "Review edit approval, remembered approvals, disabled tools and protected paths.",
+        action_id: None,
+        action: CommandAction::OpenPermissions,
+    },
     Command {
         name: "settings",
         title: "settings",
@@ -273,6 +361,11 @@ pub enum CommandAction {
     /// Open the prompt-history search overlay. `Enter` recalls the
     /// chosen prompt into the editor; `Esc` cancels.
     OpenPromptHistory,
+    /// Take the editor's text as a transient system-prompt addendum
+    /// for the viewed agent's next prompted turn (see
+    /// `Agent::set_turn_system_addendum`) and clear the editor. With
+    /// an empty editor, drops any addendum still pending.
+    SetTurnContext,
     /// Open the agent picker overlay. `Enter` switches the chat view
     /// to the chosen agent's transcript; `Esc` cancels.
     OpenAgentPicker,
@@ -293,10 +386,45 @@ pub enum CommandAction {
     /// Toggles persist to the `disabled_skills` config option as the
     /// user makes them; `Esc` closes.
     OpenSkills,
+    /// Open the tools window overlay listing the session's tools.
+    /// Toggles apply to the running session from the next inference
+    /// and are not persisted (the `disabled_tools` config option is
+    /// the durable switch); `Esc` closes. Safe mid-turn.
+    OpenTools,
+    /// Open the restore selector listing the most recent file
+    /// versions `trash_writes` kept; confirming one copies it back
+    /// over the file, keeping the replaced content in the trash.
+    /// `Esc` cancels.
+    OpenRestore,
+    /// Surface a notice listing the files the session's tools created
+    /// or edited and the number of `bash` commands it ran. Reads the
+    /// agent, so it's refused mid-turn.
+    ShowChangeSummary,
+    /// Surface a notice listing the files remembered by the edit
+    /// approval overlay's "Always apply" for this session. Read-only,
+    /// so it's safe mid-turn.
+    ListApprovals,
+    /// Forget every remembered edit approval so the overlay asks again
+    /// for those files. Safe mid-turn: a batch already waiting keeps
+    /// its overlay.
+    ForgetApprovals,
+    /// Open the permissions window: the edit-approval switch, the
+    /// remembered approvals, disabled tools, protected paths and path
+    /// confinement on one page. Toggling the switch applies to the
+    /// session and persists `confirm_edit_batches`; the remembered row
+    /// forgets them. The rest is read-only. Safe mid-turn. `Esc`
+    /// closes.
+    OpenPermissions,
     /// Render the current session to a self-contained HTML file under
     /// `~/.aj/exports/` and surface the path as a notice. Read-only, so
     /// it's safe mid-turn.
     ExportHtml,
+    /// Arm a one-shot capture of the main agent's next inference
+    /// request: the assembled body is pretty-printed, with secret-named
+    /// fields redacted, to a file under `~/.aj/debug/`, and the path is
+    /// surfaced as a notice right away. Takes effect from the next turn,
+    /// so it's safe mid-turn.
+    DebugNextRequest,
     /// Start a fresh session. The current session is preserved on
     /// disk; the host creates a new [`ConversationLog`], swaps it
     /// in, seeds the agent's transcript empty, and clears the
@@ -308,6 +436,21 @@ pub enum CommandAction {
     /// owns the turn machinery `handle_command` lacks), so the
     /// `handle_command` arm for it is a no-op.
     Compact,
+    /// Open the read-only context overlay: the main conversation's
+    /// projected messages, numbered, with role, block kinds, and an
+    /// estimated size. Reads the log under its lock, so it's safe
+    /// mid-turn.
+    OpenContextList,
+    /// Open the read-only token-breakdown overlay: the main
+    /// conversation's estimated context split by content type, with
+    /// tool results per tool. Reads the log under its lock, so it's
+    /// safe mid-turn.
+    OpenContextBreakdown,
+    /// Take the editor's text as a message number from the context
+    /// overlay and prune that message from the main conversation's
+    /// context (see `aj_session::plan_drop`), then reseed the agent.
+    /// Refused mid-turn.
+    DropMessage,
     /// Show the command reference. The host opens the help overlay
     /// listing every entry in [`COMMANDS`].
     Help,
@@ -0,0 +1,210 @@
+//! One-shot dump of the next outgoing inference request.
+//!
+//! `/debug-request` arms a capture on the main agent's stream options.
+//! The provider hands the fully assembled request body (system blocks,
+//! cache-control placement, tools, thinking config, messages) to the
+//! [`OnPayload`] hook just before sending it; the first body that
+//! arrives is pretty-printed to a JSON file under `~/.aj/debug/` and
+//! every later one is ignored, so arming it costs nothing once fired.
+//!
+//! Credentials travel in request headers, never the body, but the dump
+//! is meant to be pasted into bug reports, so any field named like a
+//! credential (`api_key`, `authorization`, ...) is still blanked before
+//! anything touches disk. The tool definitions and the messages are
+//! left exactly as sent: a tool's `token` parameter or argument is data
+//! the dump exists to show, not a secret.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use aj_conf::Config;
+use aj_models::types::OnPayload;
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// Placeholder written in place of a redacted value.
+const REDACTED: &str = "[REDACTED]";
+
+/// Lowercased names of credential-bearing fields. Matched whole after
+/// stripping `-` and `_`, so `x-api-key`, `apiKey`, and `access_token`
+/// all hit while `max_tokens` or a bare `token` don't.
+const SECRET_KEYS: &[&str] = &[
+    "apikey",
+    "xapikey",
+    "authorization",
+    "accesstoken",
+    "refreshtoken",
+    "idtoken",
+    "clientsecret",
+    "secret",
+    "password",
+    "credentials",
+];
+
+/// Top-level request fields copied into the dump unredacted: the tool
+/// definitions and the conversation replayed to the model.
+const VERBATIM_FIELDS: &[&str] = &["tools", "messages"];
+
+/// Where the next captured request for `session_id` is written:
+/// `~/.aj/debug/aj-request-<session>-<timestamp>.json`. The timestamp
+/// keeps repeated captures in one session from overwriting each other.
+pub fn request_dump_path(session_id: &str) -> Result<PathBuf> {
+    let dir = Config::get_config_dir()
+        .context("failed to resolve ~/.aj")?
+        .join("debug");
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    Ok(dir.join(format!("aj-request-{session_id}-{stamp}.json")))
+}
+
+/// Build an [`OnPayload`] hook that writes the first request body it
+/// sees to `path` and ignores every call after that. Write failures are
+/// logged rather than surfaced: the hook runs inside the provider's
+/// send path, which has no channel back to the UI.
+pub fn capture_next(path: PathBuf) -> OnPayload {
+    let fired = Arc::new(AtomicBool::new(false));
+    OnPayload::new(move |payload| {
+        if fired.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = write_dump(&path, payload) {
+            tracing::warn!("failed to write request dump: {e:#}");
+        }
+    })
+}
+
+fn write_dump(path: &Path, payload: &Value) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, render(payload))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Pretty-print `payload` with credential-named fields redacted outside
+/// of [`VERBATIM_FIELDS`].
+pub fn render(payload: &Value) -> String {
+    let mut payload = payload.clone();
+    match &mut payload {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if VERBATIM_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                if is_secret_key(key) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        other => redact(other),
+    }
+    let mut out = serde_json::to_string_pretty(&payload).unwrap_or_else(|_| payload.to_string());
+    out.push('\n');
+    out
+}
+
+/// Replace the value of every object key that names a credential with
+/// [`REDACTED`], recursing through nested objects and arrays.
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_secret_key(key) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect();
+    SECRET_KEYS.contains(&normalized.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_secret_named_keys_at_any_depth() {
+        let payload = json!({
+            "model": "claude",
+            "api_key": "sk-ant-123",
+            "metadata": { "Authorization": "Bearer abc", "user_id": "u1" },
+            "extra": [{ "x-api-key": "sk-1", "refresh_token": "r" }],
+        });
+        let out: Value = serde_json::from_str(&render(&payload)).unwrap();
+        assert_eq!(out["api_key"], REDACTED);
+        assert_eq!(out["metadata"]["Authorization"], REDACTED);
+        assert_eq!(out["metadata"]["user_id"], "u1");
+        assert_eq!(out["extra"][0]["x-api-key"], REDACTED);
+        assert_eq!(out["extra"][0]["refresh_token"], REDACTED);
+        assert_eq!(out["model"], "claude");
+    }
+
+    #[test]
+    fn keeps_token_budgets_and_request_shape() {
+        let payload = json!({
+            "max_tokens": 1024,
+            "thinking": { "type": "enabled", "budget_tokens": 512 },
+            "system": [{
+                "type": "text",
+                "text": "be brief",
+                "cache_control": { "type": "ephemeral" },
+            }],
+            "tools": [{ "name": "bash", "input_schema": { "type": "object" } }],
+        });
+        let out: Value = serde_json::from_str(&render(&payload)).unwrap();
+        assert_eq!(out, payload);
+    }
+
+    #[test]
+    fn leaves_tool_definitions_and_messages_verbatim() {
+        let payload = json!({
+            "metadata": { "token": "t1", "access_token": "a1" },
+            "tools": [{
+                "name": "read_more",
+                "input_schema": { "properties": { "token": { "type": "string" } } },
+            }],
+            "messages": [{
+                "role": "assistant",
+                "content": [{
+                    "type": "tool_use",
+                    "name": "read_more",
+                    "input": { "token": "out-1", "password": "hunter2" },
+                }],
+            }],
+        });
+        let out: Value = serde_json::from_str(&render(&payload)).unwrap();
+        assert_eq!(out["metadata"]["token"], "t1");
+        assert_eq!(out["metadata"]["access_token"], REDACTED);
+        assert_eq!(out["tools"], payload["tools"]);
+        assert_eq!(out["messages"], payload["messages"]);
+    }
+
+    #[test]
+    fn capture_writes_only_the_first_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("req.json");
+        let hook = capture_next(path.clone());
+        (hook.0)(&json!({ "n": 1, "authorization": "secret" }));
+        (hook.0)(&json!({ "n": 2 }));
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("\"n\": 1"));
+        assert!(!written.contains("\"secret\""));
+        assert!(written.contains(REDACTED));
+    }
+}
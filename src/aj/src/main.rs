@@ -1,6 +1,7 @@
 //! Binary entry point for the `aj` CLI.
 //!
-//! Loads `~/.aj/.env`, parses CLI args (see
+//! Loads the `.env` layers (`~/.aj/.env`, then the git root's and the
+//! working directory's), parses CLI args (see
 //! [`aj::cli::args::Args`]), and dispatches to either
 //! [`aj::modes::print`] or [`aj::modes::interactive`].
 //! Subcommands (`list-sessions`, `continue`, `update-models`)
@@ -34,19 +35,14 @@ async fn main() -> Result<()> {
         None => builder.with_ansi(true).init(),
     }
 
-    // `~/.aj/.env` first (highest priority for env-driven config),
-    // then a project-local `.env` if present. CLI flags layered on
-    // top via clap's `env = ...` per-arg attribute.
-    if let Ok(dotenv_path) = Config::get_dotenv_file_path() {
-        tracing::info!("loading .env from {:?}", dotenv_path);
-        dotenv::from_path(dotenv_path).ok();
-    } else {
-        tracing::info!("no .env in config directory");
-    }
-    dotenv::dotenv().ok();
+    load_dotenv_layers();
 
     let args = Args::parse();
 
+    if args.check_tools {
+        return handle_check_tools();
+    }
+
     match args.command {
         Some(Command::UpdateModels) => handle_update_models_command().await,
         Some(Command::ListSessions) => handle_list_sessions(),
@@ -63,6 +59,26 @@ async fn main() -> Result<()> {
     }
 }
 
+/// Load the `.env` layers from [`Config::dotenv_file_paths`]:
+/// `~/.aj/.env` < `<git-root>/.env` < `<cwd>/.env`.
+///
+/// `dotenv` never overwrites a variable that is already set, so the
+/// layers are loaded most specific first: a project file beats the
+/// global one, and the real process environment beats every file. CLI
+/// flags sit on top via clap's `env = ...` per-arg attribute. Missing
+/// files are skipped silently; a file that fails to parse is logged.
+fn load_dotenv_layers() {
+    for path in Config::dotenv_file_paths().iter().rev() {
+        if !path.is_file() {
+            continue;
+        }
+        match dotenv::from_path(path) {
+            Ok(()) => tracing::info!("loaded .env from {:?}", path),
+            Err(e) => tracing::warn!("failed to load .env from {:?}: {e}", path),
+        }
+    }
+}
+
 /// Dispatch to the interactive or print mode based on `--print`.
 ///
 /// The same binary serves both; the only difference is which
@@ -103,6 +119,21 @@ fn handle_list_sessions() -> Result<()> {
     Ok(())
 }
 
+/// `aj --check-tools`: run [`aj_tools::schema_check`] over the builtin
+/// tools and report each problem on stderr.
+fn handle_check_tools() -> Result<()> {
+    let tools = aj_tools::get_builtin_tools(&aj_tools::BuiltinToolOptions::default());
+    let problems = aj_tools::schema_check::check_tool_schemas(&tools);
+    if problems.is_empty() {
+        println!("{} tools OK", tools.len());
+        return Ok(());
+    }
+    for problem in &problems {
+        eprintln!("{problem}");
+    }
+    anyhow::bail!("{} problem(s) across {} tools", problems.len(), tools.len())
+}
+
 /// `aj update-models`: refresh the on-disk model catalog at
 /// `~/.aj/models.json` from `models.dev`. The `/model` selector
 /// overlay reads that catalog at startup, so running this command
@@ -21,17 +21,22 @@
 //! (e.g. so the user can log in later) and lets a mid-session login
 //! take effect on the next turn without a restart.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
+use aj_agent::FallbackModel;
 use aj_conf::{Config, ConfigThinkingDisplay, ConfigThinkingLevel, ConfigVerbosity};
 use aj_models::ThinkingConfig;
+use aj_models::anthropic::{RateLimitTracker, RateLimits};
 use aj_models::auth::{AuthStorage, find_env_keys};
 use aj_models::provider::{Provider, provider_for};
 use aj_models::registry::{ModelInfo, ModelRegistry};
 use aj_models::types::{
     ApiKeyResolver, ReasoningSummary, Speed, StreamOptions, ThinkingDisplay, Verbosity,
 };
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use tokio::sync::Semaphore;
 
 use crate::cli::args::Args;
 
@@ -52,6 +57,53 @@ pub const DEFAULT_PROVIDER_ID: &str = "anthropic";
 /// gracefully instead of erroring.
 const PREFERRED_DEFAULT_MODELS: &[(&str, &str)] = &[("anthropic", "claude-opus-4-8")];
 
+/// `user-agent` sent when `user_agent` isn't configured.
+const DEFAULT_USER_AGENT: &str = concat!("aj/", env!("CARGO_PKG_VERSION"));
+
+/// Process-wide request settings from `config.toml`, stamped onto
+/// every bundle [`from_model_info`] builds. Set once by
+/// [`init_request_defaults`].
+static REQUEST_DEFAULTS: OnceLock<RequestDefaults> = OnceLock::new();
+
+/// See [`REQUEST_DEFAULTS`].
+#[derive(Debug)]
+struct RequestDefaults {
+    /// Cap on in-flight requests (`max_concurrent_requests`); `None`
+    /// means unlimited.
+    limiter: Option<Arc<Semaphore>>,
+    /// `user_agent`, or [`DEFAULT_USER_AGENT`].
+    user_agent: String,
+    /// `metadata_user_id`, sent as the request's `metadata.user_id`.
+    metadata_user_id: Option<String>,
+    /// Latest Anthropic rate-limit headers, throttling per
+    /// `rate_limit_throttle`.
+    rate_limits: RateLimitTracker,
+}
+
+impl RequestDefaults {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            limiter: request_limiter(config.max_concurrent_requests),
+            user_agent: config
+                .user_agent
+                .clone()
+                .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            metadata_user_id: config.metadata_user_id.clone(),
+            rate_limits: RateLimitTracker::new(config.rate_limit_throttle),
+        }
+    }
+
+    fn apply(&self, options: &mut StreamOptions) {
+        options.request_limiter = self.limiter.clone();
+        options.rate_limits = Some(self.rate_limits.clone());
+        options.user_agent = Some(self.user_agent.clone());
+        options.metadata = self
+            .metadata_user_id
+            .as_ref()
+            .map(|id| HashMap::from([("user_id".to_string(), Value::String(id.clone()))]));
+    }
+}
+
 /// The model-selection triple after applying CLI > env > config
 /// precedence. [`merge`](ModelSelection::merge) is the single place
 /// that overlay lives. The fields are the post-merge `(api, name,
@@ -170,6 +222,9 @@ pub fn from_model_info(
     let mut stream_options = StreamOptions::default();
     install_api_key_resolver(&mut stream_options, auth, &model_info.provider);
     stream_options.speed = speed;
+    if let Some(defaults) = REQUEST_DEFAULTS.get() {
+        defaults.apply(&mut stream_options);
+    }
 
     Ok(ResolvedModel {
         provider: Arc::from(provider),
@@ -178,6 +233,75 @@ pub fn from_model_info(
     })
 }
 
+/// Resolve `config.fallback_models` into the agent's fallback chain.
+///
+/// An entry is `provider/model`, or a bare model id looked up under
+/// `primary_provider`. Each model gets its own bundle (provider impl,
+/// credential resolver, `speed`) plus the configured thinking display
+/// and verbosity, like the primary's. An entry missing from the
+/// registry is an error naming it, as it is for the primary model.
+pub fn resolve_fallbacks(
+    registry: &ModelRegistry,
+    auth: &AuthStorage,
+    config: &Config,
+    primary_provider: &str,
+    speed: Option<Speed>,
+) -> Result<Vec<FallbackModel>> {
+    config
+        .fallback_models
+        .iter()
+        .map(|entry| {
+            let (provider_id, model_id) = entry
+                .split_once('/')
+                .unwrap_or((primary_provider, entry.as_str()));
+            let model_info = pick_model(registry, provider_id, Some(model_id))
+                .with_context(|| format!("fallback_models entry {entry:?}"))?;
+            let ResolvedModel {
+                provider,
+                model_info,
+                mut stream_options,
+            } = from_model_info(auth, model_info, speed)?;
+            apply_thinking_display(&mut stream_options, config.thinking_display);
+            apply_verbosity(&mut stream_options, config.verbosity);
+            Ok(FallbackModel {
+                provider,
+                model_info,
+                stream_options,
+            })
+        })
+        .collect()
+}
+
+/// Set the process-wide request settings from `config`: the
+/// `max_concurrent_requests` limit, the `user_agent` header, the
+/// `metadata_user_id` and the rate-limit tracker
+/// (`rate_limit_throttle`). Every bundle built afterwards by
+/// [`from_model_info`] carries them; the limit is one shared semaphore,
+/// and sub-agents clone their parent's stream options, so the whole
+/// process draws from one pool of slots. Only the first call takes
+/// effect (the limit can't change under requests already holding
+/// permits), so a new value needs a restart.
+pub fn init_request_defaults(config: &Config) {
+    REQUEST_DEFAULTS.get_or_init(|| RequestDefaults::from_config(config));
+}
+
+/// The budgets reported by the most recent Anthropic API response of
+/// this process, for display. `None` before the first response, when
+/// every request went through OAuth (which reports plan limits
+/// instead), or before [`init_request_defaults`].
+pub fn latest_rate_limits() -> Option<RateLimits> {
+    REQUEST_DEFAULTS.get()?.rate_limits.latest()
+}
+
+/// A semaphore with `max_concurrent_requests` permits, or `None` for
+/// `0` (unlimited).
+fn request_limiter(max_concurrent_requests: u64) -> Option<Arc<Semaphore>> {
+    let permits = usize::try_from(max_concurrent_requests)
+        .unwrap_or(usize::MAX)
+        .min(Semaphore::MAX_PERMITS);
+    (permits > 0).then(|| Arc::new(Semaphore::new(permits)))
+}
+
 /// Install an [`ApiKeyResolver`] on `options` that resolves
 /// `provider_id`'s bearer token through [`AuthStorage`] on every
 /// inference.
@@ -513,6 +637,32 @@ mod tests {
         assert!(opts.verbosity.is_none());
     }
 
+    #[test]
+    fn request_limiter_zero_is_unlimited() {
+        assert!(request_limiter(0).is_none());
+        let limiter = request_limiter(3).expect("a limit");
+        assert_eq!(limiter.available_permits(), 3);
+    }
+
+    #[test]
+    fn request_defaults_stamp_user_agent_and_metadata() {
+        let mut opts = StreamOptions::default();
+        RequestDefaults::from_config(&Config::default()).apply(&mut opts);
+        assert_eq!(opts.user_agent.as_deref(), Some(DEFAULT_USER_AGENT));
+        assert!(opts.metadata.is_none());
+        assert!(opts.request_limiter.is_none());
+
+        let config = Config {
+            user_agent: Some("acme-bot/2".into()),
+            metadata_user_id: Some("user-42".into()),
+            ..Config::default()
+        };
+        RequestDefaults::from_config(&config).apply(&mut opts);
+        assert_eq!(opts.user_agent.as_deref(), Some("acme-bot/2"));
+        let metadata = opts.metadata.expect("metadata set");
+        assert_eq!(metadata["user_id"], "user-42");
+    }
+
     #[test]
     fn model_selection_cli_overrides_config() {
         use clap::Parser;
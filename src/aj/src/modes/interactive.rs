@@ -15,28 +15,31 @@
 //! [`AgentEvent`]: aj_agent::events::AgentEvent
 
 pub mod components;
+pub mod edit_approval;
 pub mod editor_ext;
 pub mod event_pump;
 pub mod footer_data;
 pub mod layout;
+pub mod open_in_editor;
 pub mod render_settings;
 pub mod session;
 pub mod shutdown;
 #[cfg(test)]
 pub(crate) mod test_support;
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use aj_agent::events::{AgentEvent, AgentId};
+use aj_agent::preload::Preload;
 use aj_agent::queue::MessageQueues;
 use aj_agent::types::UsageSummary;
 use aj_agent::{Agent, SharedAgent, SubAgentRegistry, TurnError, sub_agent_session_id};
 use aj_conf::{
-    AgentEnv, Config, ConfigLayer, ConfigSpeed, ConfigThinkingDisplay, ConfigThinkingLevel,
-    ConfigVerbosity, Severity, SystemPromptSource, display_path,
+    AgentEnv, Config, ConfigLayer, ConfigSpeed, ConfigStyleTerseness, ConfigThinkingDisplay,
+    ConfigThinkingLevel, ConfigVerbosity, Severity, SystemPromptSource, display_path,
 };
 use aj_models::auth::AuthStorage;
 use aj_models::provider::Provider;
@@ -46,7 +49,8 @@ use aj_models::{
     ThinkingConfig, speed_from_name, speed_name, thinking_config_from_name, verbosity_name,
 };
 use aj_session::{ConversationPersistence, ThreadFilter};
-use aj_tools::{BuiltinToolOptions, get_builtin_tools};
+use aj_tools::trash::Trash;
+use aj_tools::{BuiltinToolOptions, get_builtin_tools, sanitize_terminal_output};
 use aj_tui::EditorComponent;
 use aj_tui::components::editor::Editor;
 use aj_tui::terminal::ProcessTerminal;
@@ -71,7 +75,14 @@ use crate::modes::interactive::components::auth_picker::{
     AuthPickerComponent, AuthProviderItem, OutcomeHandle as AuthPickerOutcomeHandle,
 };
 use crate::modes::interactive::components::auth_status::AuthStatusOutcomeHandle;
+use crate::modes::interactive::components::batch_approval::{
+    BatchApprovalComponent, BatchApprovalOutcome, ChangePreview,
+    OutcomeHandle as BatchApprovalOutcomeHandle,
+};
 use crate::modes::interactive::components::command_palette::CommandPaletteOutcomeHandle;
+use crate::modes::interactive::components::context_breakdown::ContextBreakdownOutcomeHandle;
+use crate::modes::interactive::components::context_list::ContextListOutcomeHandle;
+use crate::modes::interactive::components::diff::render_unified_diff;
 use crate::modes::interactive::components::footer::Footer;
 use crate::modes::interactive::components::login_dialog::{
     LoginDialogComponent, LoginDialogState, LoginLine, TuiOAuthCallbacks,
@@ -80,13 +91,22 @@ use crate::modes::interactive::components::model_selector::{
     ModelIdentityRef, ModelSelectorComponent, ModelSelectorOutcome,
     OutcomeHandle as ModelOutcomeHandle,
 };
+use crate::modes::interactive::components::permissions_window::{
+    CONFIRM_EDITS_ROW, ChangesHandle as PermissionsChangesHandle,
+    OutcomeHandle as PermissionsOutcomeHandle, PermissionsSnapshot, PermissionsWindowComponent,
+    PermissionsWindowOutcome, REMEMBERED_ROW,
+};
 use crate::modes::interactive::components::prompt_history::{
     PromptHistoryOutcome, PromptHistoryOutcomeHandle, PromptHistorySearchComponent,
     all_workspaces_history_streaming, workspace_history_streaming,
 };
+use crate::modes::interactive::components::restore_selector::{
+    OutcomeHandle as RestoreOutcomeHandle, RestoreSelectorComponent, RestoreSelectorOutcome,
+};
 use crate::modes::interactive::components::session_info::SessionInfoOutcomeHandle;
 use crate::modes::interactive::components::session_selector::{
     OutcomeHandle as SessionOutcomeHandle, SessionSelectorComponent, SessionSelectorOutcome,
+    resume_offer,
 };
 use crate::modes::interactive::components::settings_window::{
     ChangesHandle as SettingsChangesHandle, ClearsHandle as SettingsClearsHandle,
@@ -104,9 +124,14 @@ use crate::modes::interactive::components::task_output::{
 use crate::modes::interactive::components::thinking_selector::{
     OutcomeHandle as ThinkingOutcomeHandle, ThinkingSelectorComponent, ThinkingSelectorOutcome,
 };
+use crate::modes::interactive::components::tools_window::{
+    ChangesHandle as ToolsChangesHandle, OutcomeHandle as ToolsOutcomeHandle, ToolRow,
+    ToolsWindowComponent, ToolsWindowOutcome,
+};
 use crate::modes::interactive::components::usage_status::{
     UsageStatusComponent, UsageStatusOutcomeHandle,
 };
+use crate::modes::interactive::edit_approval::{EditApprovalRequest, EditDecision};
 use crate::modes::interactive::editor_ext::{DEFAULT_MAX_ENTRIES, PromptHistory};
 use crate::modes::interactive::event_pump::{
     EventPump, set_editor_submit_enabled, take_submitted_prompt,
@@ -115,7 +140,8 @@ use crate::modes::interactive::layout::{SlotIndex, build_layout};
 use crate::modes::interactive::render_settings::RenderSettings;
 use crate::modes::interactive::session::{SessionEntry, SessionSpec, SessionWorld};
 use crate::modes::interactive::shutdown::{
-    print_resume_hint, print_session_usage, print_usage_summary,
+    format_change_summary, print_change_summary, print_resume_hint, print_session_usage,
+    print_usage_summary,
 };
 use crate::session_setup::{RestoreContext, RunConfigSnapshot, build_initial_run_config};
 use crate::turn::{TurnPolicy, TurnStart};
@@ -155,6 +181,37 @@ fn session_busy_notice(what: &str) -> String {
     format!("Can't {what} while a turn is running — press {cancel} to cancel it first.")
 }
 
+/// Prune message `index` (1-based, as the context overlay numbers
+/// it) from the main conversation: record the drop in the log, then
+/// reseed the agent from the new projection. Returns how many message
+/// entries were dropped; errors are user-facing.
+async fn drop_message(world: &SessionWorld, index: usize) -> Result<usize, String> {
+    let mut log = world.log.lock().await;
+    let head = log
+        .latest_leaf(ThreadFilter::USER)
+        .ok_or_else(|| "The conversation has no messages yet.".to_string())?;
+    let ids = aj_session::plan_drop(&log.linearize(&head, ThreadFilter::USER), index)?;
+    let count = ids.len();
+    log.append_prune(ThreadFilter::USER, ids)
+        .map_err(|e| format!("Failed to drop message #{index}: {e}"))?;
+    let head = log
+        .latest_leaf(ThreadFilter::USER)
+        .expect("head exists after append");
+    let messages = log.linearize(&head, ThreadFilter::USER).agent_messages();
+    world.agent.lock().await.reseed_transcript(messages);
+    Ok(count)
+}
+
+/// Forget the world's remembered edit approvals, for the
+/// `forget-approvals` command and the permissions window.
+fn forget_approvals(world: &SessionWorld) -> String {
+    match world.remembered_approvals.clear() {
+        0 => "No remembered edit approvals to forget.".to_string(),
+        1 => "Forgot 1 remembered edit approval.".to_string(),
+        n => format!("Forgot {n} remembered edit approvals."),
+    }
+}
+
 /// Counts of running work a quit would tear down, for the Ctrl+C
 /// quit-arming guard: (agents, bash tasks).
 ///
@@ -330,9 +387,13 @@ impl InteractiveMode {
         // process working directory — where the user typed the command.
         // Consumed by the first `run_session` call via `mem::take`, so a
         // later in-process session switch starts clean.
-        let mut launch_content = {
-            let cwd = std::env::current_dir().unwrap_or_default();
-            crate::cli::initial_input(&self.args, &cwd)?.into_content()
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let mut launch_content = crate::cli::initial_input(&self.args, &cwd)?.into_content();
+        // `--preload` entries go in ahead of the launch turn, on the
+        // same first-session-only terms.
+        let mut preload = match &self.args.preload {
+            Some(path) => crate::cli::preload::load_preload(path, &cwd)?,
+            None => Vec::new(),
         };
 
         let spec = match self.args.command {
@@ -363,6 +424,22 @@ impl InteractiveMode {
             },
         };
 
+        // Sessions are saved as they run, so a crash or an accidental
+        // quit loses nothing; on a fresh start without a launch prompt,
+        // point at the most recent one (`offer_resume`). Looked up
+        // before the new session exists, though it only reaches disk
+        // on its first prompt anyway.
+        let resume_notice = if config.offer_resume
+            && launch_content.is_empty()
+            && matches!(spec, SessionSpec::Create { .. })
+        {
+            conversation_persistence
+                .latest_session_preview()
+                .map(|preview| resume_offer(&preview, chrono::Utc::now()))
+        } else {
+            None
+        };
+
         // ---- Theme ----------------------------------------------------
         // Loaded once at startup from `config.theme` (default `light`).
         // The handle is reused everywhere a component needs theme
@@ -388,6 +465,9 @@ impl InteractiveMode {
             false,
             config.image_show_in_terminal,
         );
+        // `--verbose` turns the full tool-input view on for this run
+        // without touching the config file.
+        render_settings.set_show_tool_input(config.show_tool_input || self.args.verbose);
         let mut world = SessionWorld::build(
             &config,
             &run_config,
@@ -513,21 +593,32 @@ impl InteractiveMode {
             };
             world.pump.handle(&mut tui, &event);
         }
+        if config.show_startup_summary {
+            let model_key = run_config
+                .lock()
+                .expect("run config mutex poisoned")
+                .model_key
+                .clone();
+            let summary =
+                build_startup_summary(&model_key, &world.env, config.confirm_edit_batches);
+            world.pump.handle(&mut tui, &notice_event(&summary));
+        }
         // The context notice only applies to fresh sessions: a
         // resumed session keeps the assembled prompt persisted in
         // its log, so the freshly-loaded env the notice describes
-        // doesn't govern what's actually sent. Skill-discovery
-        // warnings ride along under the same rule.
+        // doesn't govern what's actually sent. Truncated-context and
+        // skill-discovery warnings ride along under the same rule.
         if matches!(spec, SessionSpec::Create { .. }) {
             let env = &world.env;
-            let skill_warnings: Vec<String> = env
-                .skill_diagnostics
+            let env_warnings: Vec<String> = env
+                .context_files
                 .iter()
-                .map(|d| d.to_string())
+                .filter_map(|f| f.truncation_warning())
+                .chain(env.skill_diagnostics.iter().map(|d| d.to_string()))
                 .collect();
             let context_notice = build_context_notice(env);
             world.pump.handle(&mut tui, &notice_event(&context_notice));
-            for warning in &skill_warnings {
+            for warning in &env_warnings {
                 world.pump.handle(&mut tui, &warning_event(warning));
             }
         }
@@ -546,6 +637,9 @@ impl InteractiveMode {
         for notice in std::mem::take(&mut world.restore_notices) {
             world.pump.handle(&mut tui, &notice_event(&notice));
         }
+        if let Some(notice) = &resume_notice {
+            world.pump.handle(&mut tui, &notice_event(notice));
+        }
 
         // Shared, mutable view of the on-disk config. Selector
         // outcomes (model / thinking / the settings window) mutate
@@ -602,6 +696,7 @@ impl InteractiveMode {
                 &mut theme_watch,
                 &mut prompt_history_rx,
                 std::mem::take(&mut launch_content),
+                std::mem::take(&mut preload),
             )
             .await
             {
@@ -674,11 +769,11 @@ impl InteractiveMode {
 
         shell.tui.stop();
 
-        // End-of-process banner: token-usage breakdown plus a resume
-        // hint pointing at the live session id. Printed *after*
-        // [`Tui::stop`] so the bytes land in the user's regular
-        // shell scrollback rather than the alternate-screen TUI
-        // buffer that gets cleared on exit.
+        // End-of-process banner: token-usage breakdown, the live
+        // session's file changes, plus a resume hint pointing at the
+        // live session id. Printed *after* [`Tui::stop`] so the bytes
+        // land in the user's regular shell scrollback rather than the
+        // alternate-screen TUI buffer that gets cleared on exit.
         //
         // Reading the agent + log behind their `TokioMutex` is safe
         // here: in-flight turns were shut down before `run_session`
@@ -702,6 +797,9 @@ impl InteractiveMode {
                     }
                     print_session_usage(&world.session_id, &summary);
                 }
+                // What the live session did on disk; completed sessions
+                // aren't itemized, their worlds are gone.
+                print_change_summary(&world.change_log().await);
 
                 // Resume hint is gated on "the session is worth resuming",
                 // i.e. it has at least one persisted user-thread leaf.
@@ -988,6 +1086,7 @@ async fn run_session(
     theme_watch: &mut ThemeWatch,
     prompt_history_rx: &mut Option<UnboundedReceiver<PromptHistory>>,
     launch_content: Vec<UserContent>,
+    preload: Vec<Preload>,
 ) -> Result<SessionExit> {
     // ---- Main event loop ------------------------------------------
     // In-flight turns keyed by the agent running them. `JoinSet`
@@ -1001,6 +1100,10 @@ async fn run_session(
     // viewed agent is idle but other agents or background tasks are
     // still running.
     let mut quit_armed = false;
+    // The prompt `guard_repeated_prompts` last held back, per agent;
+    // submitting the same text again sends it. See
+    // `hold_repeated_prompt`.
+    let mut repeat_armed: Option<(AgentId, String)> = None;
 
     // A command like the thinking selector opens an overlay
     // selector. While an overlay is up the editor is not focused, but
@@ -1021,6 +1124,36 @@ async fn run_session(
     let mut login_task: Option<tokio::task::JoinHandle<Result<(), aj_models::auth::AuthError>>> =
         None;
 
+    // Idle-quit deadline from `idle_timeout_secs` (`0` disables it, in
+    // which case the arm pends forever). Re-armed on every input event
+    // and every turn completion, so it only fires after a stretch in
+    // which the user typed nothing and no turn finished.
+    let idle_timeout = {
+        let secs = shell
+            .config
+            .lock()
+            .expect("config mutex poisoned")
+            .idle_timeout_secs;
+        (secs > 0).then(|| std::time::Duration::from_secs(secs))
+    };
+    let mut idle_deadline = idle_timeout.map(|t| tokio::time::Instant::now() + t);
+
+    // Place the `--preload` entries in the transcript before anything
+    // else runs. Their events render through the pump like a turn's.
+    if !preload.is_empty() {
+        let result = world.agent.lock().await.preload(preload).await;
+        match result {
+            Ok(()) | Err(TurnError::Aborted) => {}
+            Err(TurnError::Recoverable(err)) => {
+                world.pump.handle(
+                    &mut shell.tui,
+                    &error_event(&format!("Preload skipped: {err}")),
+                );
+            }
+            Err(TurnError::Fatal(err)) => return Err(anyhow::Error::msg(err)),
+        }
+    }
+
     // Auto-submit the launch prompt (`aj <msg>` / `aj @file ...`) as the
     // first turn. Empty for any in-process session switch after the first.
     if !launch_content.is_empty() {
@@ -1036,6 +1169,38 @@ async fn run_session(
         sync_editor_enabled(&mut shell.tui);
     }
 
+    // `prewarm_cache`: on a fresh, idle start, prime the provider's
+    // prompt cache in the background. The task holds the agent while
+    // the request runs, so a prompt submitted meanwhile waits for it
+    // and then reads the cached prefix.
+    let prewarm_cancel = CancellationToken::new();
+    let prewarm = shell
+        .config
+        .lock()
+        .expect("config mutex poisoned")
+        .prewarm_cache;
+    if prewarm && turns.is_empty() && world.agent.lock().await.messages().is_empty() {
+        let agent = Arc::clone(&world.agent);
+        let cancel = prewarm_cancel.clone();
+        tokio::spawn(async move {
+            let agent = agent.lock().await;
+            match agent.prewarm_cache(cancel).await {
+                Ok(_) | Err(TurnError::Aborted) => {}
+                Err(TurnError::Recoverable(err)) => {
+                    let _ = agent
+                        .emit_event(AgentEvent::Warning {
+                            agent_id: AgentId::Main,
+                            text: format!("Prompt cache prewarm failed: {err}"),
+                        })
+                        .await;
+                }
+                Err(TurnError::Fatal(err)) => {
+                    tracing::warn!(%err, "prompt cache prewarm failed");
+                }
+            }
+        });
+    }
+
     let exit: Result<SessionExit> = loop {
         tokio::select! {
             biased;
@@ -1045,6 +1210,7 @@ async fn run_session(
                 match joined {
                     Ok((id, result)) => {
                         turn_cancels.remove(&id);
+                        idle_deadline = idle_timeout.map(|t| tokio::time::Instant::now() + t);
                         world.pump.mark_idle(&mut shell.tui, id);
                         // Main-turn completion bounds every nested
                         // initial spawn it started. Drain any sub
@@ -1172,6 +1338,7 @@ async fn run_session(
                 match event {
                     TuiEvent::Render => shell.tui.render(),
                     TuiEvent::Input(input) => {
+                        idle_deadline = idle_timeout.map(|t| tokio::time::Instant::now() + t);
                         // Ctrl+C semantics, in priority order.
                         // A visible overlay always wins: a Ctrl+C
                         // aimed at a modal dismisses the modal and
@@ -1193,6 +1360,14 @@ async fn run_session(
                         //    aborts the task.
                         // 3. Otherwise act on the agent you are
                         //    *viewing*:
+                        //    - Viewing the main agent while it runs
+                        //      a tool: fire the session's
+                        //      `ToolInterrupt`. Only the in-flight
+                        //      tool calls stop; each reports an
+                        //      "interrupted" result and the turn
+                        //      carries on. A second Ctrl+C (once
+                        //      the model streams again) cancels
+                        //      the turn as below.
                         //    - Viewed agent has a binary-driven
                         //      turn (`turn_cancels`): cancel just
                         //      it. The cancel handle is the
@@ -1235,6 +1410,13 @@ async fn run_session(
                             } else {
                                 // Per-view Ctrl+C: act on the agent you're viewing.
                                 let active = world.pump.active_view(&mut shell.tui);
+                                if active == AgentId::Main && world.pump.has_running_tools(active) {
+                                    // Stop just the running tool; the
+                                    // turn goes on with its result.
+                                    world.tool_interrupt.interrupt();
+                                    quit_armed = false;
+                                    continue;
+                                }
                                 if let Some(token) = turn_cancels.get(&active) {
                                     // Viewed agent has a binary-driven turn: cancel just it.
                                     token.cancel();
@@ -1401,7 +1583,16 @@ async fn run_session(
                                         }
                                     }
                                     world.pump.sync_pending(&mut shell.tui);
-                                } else if !text.is_empty() {
+                                } else if !text.is_empty()
+                                    && !hold_repeated_prompt(
+                                        &mut shell.tui,
+                                        world,
+                                        &shell.config,
+                                        &mut repeat_armed,
+                                        target,
+                                        &text,
+                                    )
+                                {
                                     if spawn_prompt_turn(
                                         &mut shell.tui,
                                         world,
@@ -1902,6 +2093,17 @@ async fn run_session(
                                 continue;
                             }
 
+                            if hold_repeated_prompt(
+                                &mut shell.tui,
+                                world,
+                                &shell.config,
+                                &mut repeat_armed,
+                                target,
+                                &trimmed,
+                            ) {
+                                continue;
+                            }
+
                             // Idle: start a turn. `spawn_prompt_turn`
                             // clears the editor, records history, mints
                             // the per-turn cancel token (kept in
@@ -2016,9 +2218,42 @@ async fn run_session(
                 }
                 *prompt_history_rx = None;
             }
+
+            // --- Edit batch awaiting approval ---
+            // The main agent's batch hook is parked on `reply`. The
+            // prompt stacks over whatever overlay is open, so the answer
+            // is never blocked behind another window.
+            request = recv_edit_approval(world.edit_approvals.as_mut()) => {
+                match request {
+                    Some(request) => {
+                        let selector = open_edit_approval(&mut shell.tui, &shell.theme, request);
+                        selectors.push(&mut shell.tui, selector);
+                        shell.tui.request_render();
+                    }
+                    None => world.edit_approvals = None,
+                }
+            }
+
+            // --- Idle timeout ---
+            // Only quits a quiet session: while a turn, a nested
+            // sub-agent run, or a login is still going, the deadline is
+            // pushed out instead so unattended work is never cut short.
+            () = sleep_until_or_pending(idle_deadline) => {
+                let busy = !turn_cancels.is_empty()
+                    || !world.pump.running_agents().is_empty()
+                    || login_task.is_some();
+                if busy {
+                    idle_deadline = idle_timeout.map(|t| tokio::time::Instant::now() + t);
+                    continue;
+                }
+                tracing::info!(?idle_timeout, "idle timeout elapsed, quitting");
+                break Ok(SessionExit::Quit);
+            }
         }
     };
 
+    prewarm_cancel.cancel();
+
     // Kill the background-task tree before tearing down turns:
     // cancelling the registry root makes every detached driver
     // SIGKILL its process group promptly; the bounded quiesce makes
@@ -2078,6 +2313,7 @@ fn apply_turn_config(
                 Arc::clone(&cfg.model_info),
                 stream_options,
             );
+            agent.set_fallback_models(cfg.fallback_chain());
             agent.set_default_thinking(cfg.thinking.clone());
             agent.set_speed(cfg.speed);
         }
@@ -2166,11 +2402,33 @@ fn spawn_turn(
     };
     let run_config_for_turn = Arc::clone(run_config);
     let sub_overrides_for_turn = Arc::clone(&world.sub_overrides);
+    let disabled_tools_for_turn = Arc::clone(&world.disabled_tools);
     let log = Arc::clone(&world.log);
+    // A `focus` addendum staged for this agent rides along with its
+    // next user prompt only; wakes and compactions leave it pending.
+    let turn_context = match start {
+        TurnStart::Prompt(_) | TurnStart::Content(_) => {
+            let mut staged = world
+                .turn_context
+                .lock()
+                .expect("turn context mutex poisoned");
+            match staged.take() {
+                Some((id, text)) if id == target => Some(text),
+                other => {
+                    *staged = other;
+                    None
+                }
+            }
+        }
+        TurnStart::Wake | TurnStart::Compact { .. } => None,
+    };
     let turn_cancel = CancellationToken::new();
     turn_cancels.insert(target, turn_cancel.clone());
     turns.spawn(async move {
         let mut a = handle.lock().await;
+        if turn_context.is_some() {
+            a.set_turn_system_addendum(turn_context);
+        }
         let result = crate::turn::drive_turn(
             &mut a,
             &log,
@@ -2178,6 +2436,12 @@ fn spawn_turn(
             start,
             |agent: &mut Agent| {
                 apply_turn_config(target, agent, &run_config_for_turn, &sub_overrides_for_turn);
+                agent.set_session_disabled_tools(
+                    disabled_tools_for_turn
+                        .lock()
+                        .expect("disabled tools mutex poisoned")
+                        .clone(),
+                );
             },
             turn_cancel,
         )
@@ -2221,10 +2485,12 @@ fn spawn_wake_turn(
 /// leaves the editor intact on a miss (returning `false`) so the caller
 /// can surface a notice and the user keeps their text; otherwise clears
 /// the editor, records history, and dispatches a [`TurnStart::Prompt`]
-/// sequence.
+/// sequence — or [`TurnStart::Content`] when the prompt `@`-mentions
+/// files (see [`crate::cli::mentions`]), warning about any mention that
+/// couldn't be attached.
 fn spawn_prompt_turn(
     tui: &mut Tui,
-    world: &SessionWorld,
+    world: &mut SessionWorld,
     run_config: &Arc<std::sync::Mutex<RunConfigSnapshot>>,
     target: AgentId,
     text: String,
@@ -2239,17 +2505,66 @@ fn spawn_prompt_turn(
         editor.set_text("");
         editor.add_to_history(&text);
     }
+    let expanded = crate::cli::mentions::expand_mentions(&text, &world.env.working_directory);
+    for warning in &expanded.warnings {
+        world.pump.handle(tui, &warning_event(warning));
+    }
+    let start = if expanded.has_attachments() {
+        TurnStart::Content(expanded.into_content())
+    } else {
+        TurnStart::Prompt(text)
+    };
     spawn_turn(
         world,
         run_config,
         target,
-        TurnStart::Prompt(text),
+        start,
         policy,
         turns,
         turn_cancels,
     )
 }
 
+/// The `guard_repeated_prompts` check ahead of an idle submit. Returns
+/// `true` when `text` repeats `target`'s previous user message and
+/// wasn't just held back already: the text goes back into the editor
+/// with a notice, and `armed` remembers it so submitting the same text
+/// again sends it. Any other submit disarms. An agent that can't be
+/// inspected right now (no handle, or locked) never holds a prompt.
+fn hold_repeated_prompt(
+    tui: &mut Tui,
+    world: &mut SessionWorld,
+    config: &Arc<std::sync::Mutex<Config>>,
+    armed: &mut Option<(AgentId, String)>,
+    target: AgentId,
+    text: &str,
+) -> bool {
+    let confirmed = armed
+        .take()
+        .is_some_and(|(agent, held)| agent == target && held == text);
+    let guard = config
+        .lock()
+        .expect("config mutex poisoned")
+        .guard_repeated_prompts;
+    if !guard || confirmed {
+        return false;
+    }
+    let repeats = resolve_agent(target, &world.agent, &world.registry)
+        .is_some_and(|agent| agent.try_lock().is_ok_and(|a| a.repeats_last_prompt(text)));
+    if !repeats {
+        return false;
+    }
+    if let Some(editor) = tui.get_mut_as::<Editor>(SlotIndex::Editor.idx()) {
+        editor.set_text(text);
+    }
+    *armed = Some((target, text.to_string()));
+    world.pump.handle(
+        tui,
+        &notice_event("Same as your previous message. Submit it again to send it anyway."),
+    );
+    true
+}
+
 /// prepending it to whatever is currently typed (blank-line joined),
 /// and repaint the pending box. Returns whether anything was yanked.
 /// Used by the dequeue chord, the empty-editor Up/Ctrl+P yank, and the
@@ -2302,6 +2617,15 @@ async fn join_next_or_pending(
     }
 }
 
+/// Sleep until `deadline`, or pend forever when there is none (the
+/// idle timeout is disabled), so the idle `select!` arm is a no-op.
+async fn sleep_until_or_pending(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Pull one [`Theme`] off the theme-watcher channel. Mirrors the
 /// shape of [`recv_event`] but accepts an `Option<&mut
 /// UnboundedReceiver<Theme>>` so the `tokio::select!` arm in
@@ -2347,6 +2671,62 @@ async fn recv_prompt_history(
     }
 }
 
+/// Await the next edit-batch approval request. Mirrors
+/// [`recv_prompt_history`]: with approvals off (no receiver) the arm
+/// pends forever.
+async fn recv_edit_approval(
+    rx: Option<&mut UnboundedReceiver<EditApprovalRequest>>,
+) -> Option<EditApprovalRequest> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Show the approval overlay for `request` and wrap it for the
+/// selector stack.
+fn open_edit_approval(
+    tui: &mut Tui,
+    theme: &ThemeHandle,
+    request: EditApprovalRequest,
+) -> OpenSelector {
+    let EditApprovalRequest { changes, reply } = request;
+    let previews = changes
+        .into_iter()
+        .map(|change| ChangePreview {
+            row: change.row,
+            diff: change.preview.map(|(before, after)| {
+                // The change row already names the file, so drop the
+                // `--- a/` / `+++ b/` header lines.
+                let header = usize::from(!before.is_empty()) + usize::from(!after.is_empty());
+                render_unified_diff(
+                    &sanitize_terminal_output(&change.path),
+                    &sanitize_terminal_output(&before),
+                    &sanitize_terminal_output(&after),
+                )
+                .into_iter()
+                .skip(header)
+                .collect()
+            }),
+        })
+        .collect();
+    let inner = BatchApprovalComponent::new(select_list_theme(theme), previews);
+    let outcome = inner.outcome_handle();
+    let window = aj_tui::components::overlay_window::OverlayWindow::new(
+        "Apply file edits?",
+        Box::new(inner),
+        crate::config::theme::overlay_window_theme(theme),
+        PALETTE_OVERLAY_INNER_ROWS,
+    )
+    .with_subtitle(&subtitle_confirm_close());
+    let handle = tui.show_overlay(Box::new(window), palette_overlay_options());
+    OpenSelector::EditApproval {
+        handle,
+        outcome,
+        reply: std::sync::Mutex::new(Some(reply)),
+    }
+}
+
 /// An overlay the host is tracking on the [`SelectorStack`].
 ///
 /// Each variant pairs the overlay's [`OverlayHandle`] (so the host
@@ -2421,6 +2801,16 @@ enum OpenSelector {
         handle: OverlayHandle,
         outcome: SessionInfoOutcomeHandle,
     },
+    /// Read-only context overlay. Both Esc and Enter close it.
+    ContextList {
+        handle: OverlayHandle,
+        outcome: ContextListOutcomeHandle,
+    },
+    /// Read-only token-breakdown overlay. Both Esc and Enter close it.
+    ContextBreakdown {
+        handle: OverlayHandle,
+        outcome: ContextBreakdownOutcomeHandle,
+    },
     /// Read-only usage overlay. Both Esc and Enter close it. The
     /// usage reports stream in from a background fetch after the
     /// overlay opens; closing early just drops the fetch's receiver.
@@ -2452,6 +2842,41 @@ enum OpenSelector {
         outcome: SkillsOutcomeHandle,
         changes: SkillsChangesHandle,
     },
+    /// Tools window. Stays open across changes: the host drains
+    /// `changes` after every input event into the world's
+    /// session-only disabled-tool set; `outcome` only ever reports the
+    /// close.
+    Tools {
+        handle: OverlayHandle,
+        outcome: ToolsOutcomeHandle,
+        changes: ToolsChangesHandle,
+    },
+    /// Permissions window. Stays open across changes: the host drains
+    /// `changes` after every input event, flipping the world's
+    /// edit-approval switch (and persisting it) or forgetting the
+    /// remembered approvals; `outcome` only ever reports the close.
+    Permissions {
+        handle: OverlayHandle,
+        outcome: PermissionsOutcomeHandle,
+        changes: PermissionsChangesHandle,
+    },
+    /// The `/restore` selector. `trash` is the directory the listed
+    /// versions came from, captured at open time.
+    Restore {
+        handle: OverlayHandle,
+        outcome: RestoreOutcomeHandle,
+        trash: Trash,
+    },
+    /// Approval prompt for a batch of file edits the main agent is
+    /// parked on. Either answer is sent through `reply` and pops back
+    /// to whatever was open beneath; dropping the entry unanswered
+    /// (close-all, session exit) drops the sender, which the agent
+    /// reads as a denial.
+    EditApproval {
+        handle: OverlayHandle,
+        outcome: BatchApprovalOutcomeHandle,
+        reply: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<EditDecision>>>,
+    },
 }
 
 impl OpenSelector {
@@ -2470,9 +2895,15 @@ impl OpenSelector {
             | OpenSelector::AuthPicker { handle, .. }
             | OpenSelector::AuthStatus { handle, .. }
             | OpenSelector::SessionInfo { handle, .. }
+            | OpenSelector::ContextList { handle, .. }
+            | OpenSelector::ContextBreakdown { handle, .. }
             | OpenSelector::UsageStatus { handle, .. }
             | OpenSelector::Settings { handle, .. }
-            | OpenSelector::Skills { handle, .. } => *handle,
+            | OpenSelector::Skills { handle, .. }
+            | OpenSelector::Tools { handle, .. }
+            | OpenSelector::Permissions { handle, .. }
+            | OpenSelector::Restore { handle, .. }
+            | OpenSelector::EditApproval { handle, .. } => *handle,
         }
     }
 }
@@ -2724,11 +3155,48 @@ fn settings_values_from_config(config: &Config, catalog: &[ModelInfo]) -> Settin
         hide_thinking_block: config.hide_thinking_block,
         image_auto_resize: config.image_auto_resize,
         image_show_in_terminal: config.image_show_in_terminal,
+        show_tool_input: config.show_tool_input,
+        turn_separator: config.turn_separator,
+        turn_timestamps: config.turn_timestamps,
         image_block: config.image_block,
         syntax_highlighting: config.syntax_highlighting,
         auto_compact: config.auto_compact,
         compact_threshold: config.compact_threshold.to_string(),
         compact_keep_recent: config.compact_keep_recent.to_string(),
+        idle_timeout_secs: config.idle_timeout_secs.to_string(),
+        bash_preserve_ansi: config.bash_preserve_ansi,
+        bash_timeout_secs: config.bash_timeout_secs.to_string(),
+        env_tool_values: config.env_tool_values,
+        confirm_edit_batches: config.confirm_edit_batches,
+        normalize_on_write: config.normalize_on_write,
+        respect_editorconfig: config.respect_editorconfig,
+        confirm_with_listing: config.confirm_with_listing,
+        nudge_empty_responses: config.nudge_empty_responses,
+        retry_partial_streams: config.retry_partial_streams,
+        guard_repeated_prompts: config.guard_repeated_prompts,
+        fallback_models: config.fallback_models.join(", "),
+        max_file_size: config.max_file_size.to_string(),
+        protected_paths: config.protected_paths.join(", "),
+        confine_paths: config.confine_paths,
+        allowed_roots: config.allowed_roots.join(", "),
+        offer_resume: config.offer_resume,
+        prewarm_cache: config.prewarm_cache,
+        read_fuzzy_paths: config.read_fuzzy_paths,
+        max_concurrent_requests: config.max_concurrent_requests.to_string(),
+        rate_limit_throttle: config.rate_limit_throttle,
+        tool_result_warn_tokens: config.tool_result_warn_tokens.to_string(),
+        max_context_file_bytes: config.max_context_file_bytes.to_string(),
+        editor_command: config.editor_command.clone(),
+        trash_writes: config.trash_writes,
+        trash_dir: config.trash_dir.clone(),
+        tool_audit_log: config.tool_audit_log.clone(),
+        greeting: config.greeting.clone(),
+        show_startup_summary: config.show_startup_summary,
+        user_agent: config.user_agent.clone(),
+        metadata_user_id: config.metadata_user_id.clone(),
+        style_terseness: config.style_terseness.map(|t| t.to_string()),
+        style_explain_first: config.style_explain_first,
+        style_language: config.style_language.clone(),
     }
 }
 
@@ -2766,6 +3234,28 @@ impl ThemeWatch {
     }
 }
 
+/// The `show_startup_summary` line: model, working directory, how
+/// many instruction files (AGENTS.md / CLAUDE.md) were loaded, and
+/// whether edit batches wait for approval.
+fn build_startup_summary(
+    model_key: &(String, String),
+    env: &AgentEnv,
+    confirm_edit_batches: bool,
+) -> String {
+    let instructions = match env.context_files.len() {
+        0 => "none".to_string(),
+        1 => "1 file".to_string(),
+        n => format!("{n} files"),
+    };
+    format!(
+        "Model {}/{} · cwd {} · instructions: {instructions} · edit approval: {}",
+        model_key.0,
+        model_key.1,
+        display_path(&env.working_directory),
+        if confirm_edit_batches { "on" } else { "off" }
+    )
+}
+
 /// Build the chat-scrollback "Context:" notice listing everything
 /// stitched into the agent's system prompt: the base prompt (builtin
 /// or override file), every agents.md-style instruction file, and
@@ -3062,7 +3552,10 @@ fn persist_setting(
 /// stay at least `COMMANDS.len() + 3`. The content-heavy overlays
 /// (session switcher, prompt history) size their rows dynamically
 /// instead. See [`large_overlay_inner_rows`].
-const PALETTE_OVERLAY_INNER_ROWS: usize = 22;
+const PALETTE_OVERLAY_INNER_ROWS: usize = 33;
+
+/// Most kept versions the `/restore` selector lists.
+const RESTORE_LIST_LIMIT: usize = 50;
 
 /// Sizing/anchor used by the command palette and the compact pickers
 /// (model / thinking / help). Centered, fills ~75% of the terminal
@@ -3676,6 +4169,94 @@ async fn handle_command(
                 notice: None,
             }
         }
+        CommandAction::OpenContextList => {
+            // Read-only, like `OpenSessionInfo`: the guard drops at the
+            // end of the statement.
+            let rows = {
+                let log = world.log.lock().await;
+                log.latest_leaf(ThreadFilter::USER)
+                    .map(|head| aj_session::message_rows(&log.linearize(&head, ThreadFilter::USER)))
+                    .unwrap_or_default()
+            };
+            let inner = crate::modes::interactive::components::context_list::build_overlay(
+                select_list_theme(theme),
+                &rows,
+            );
+            let outcome = inner.outcome_handle();
+            let window = aj_tui::components::overlay_window::OverlayWindow::new(
+                "Context",
+                Box::new(inner),
+                crate::config::theme::overlay_window_theme(theme),
+                PALETTE_OVERLAY_INNER_ROWS,
+            )
+            .with_subtitle(&subtitle_close());
+            let handle = tui.show_overlay(Box::new(window), palette_overlay_options());
+            CommandOutcome::Continue {
+                selector: Some(OpenSelector::ContextList { handle, outcome }),
+                notice: None,
+            }
+        }
+        CommandAction::OpenContextBreakdown => {
+            // Read-only, like `OpenContextList`.
+            let breakdown = {
+                let log = world.log.lock().await;
+                log.latest_leaf(ThreadFilter::USER)
+                    .map(|head| {
+                        aj_session::context_breakdown(
+                            &log.linearize(&head, ThreadFilter::USER),
+                            log.system_prompt(),
+                        )
+                    })
+                    .unwrap_or_default()
+            };
+            let inner = crate::modes::interactive::components::context_breakdown::build_overlay(
+                select_list_theme(theme),
+                &breakdown,
+            );
+            let outcome = inner.outcome_handle();
+            let window = aj_tui::components::overlay_window::OverlayWindow::new(
+                "Token breakdown",
+                Box::new(inner),
+                crate::config::theme::overlay_window_theme(theme),
+                PALETTE_OVERLAY_INNER_ROWS,
+            )
+            .with_subtitle(&subtitle_close());
+            let handle = tui.show_overlay(Box::new(window), palette_overlay_options());
+            CommandOutcome::Continue {
+                selector: Some(OpenSelector::ContextBreakdown { handle, outcome }),
+                notice: None,
+            }
+        }
+        // Reseeding swaps the agent's transcript, which a running turn
+        // is still extending.
+        CommandAction::DropMessage if turn_running => CommandOutcome::Continue {
+            selector: None,
+            notice: Some(session_busy_notice("drop a message")),
+        },
+        CommandAction::DropMessage => {
+            let text = tui
+                .get_mut_as::<Editor>(SlotIndex::Editor.idx())
+                .map(|editor| editor.get_text())
+                .unwrap_or_default();
+            let notice = match text.trim().trim_start_matches('#').parse::<usize>() {
+                Err(_) => "Type the number of the message to drop (see the context list) in \
+                           the editor first, then run this command."
+                    .to_string(),
+                Ok(index) => match drop_message(world, index).await {
+                    Ok(count) => {
+                        if let Some(editor) = tui.get_mut_as::<Editor>(SlotIndex::Editor.idx()) {
+                            editor.set_text("");
+                        }
+                        aj_session::drop_notice(count)
+                    }
+                    Err(message) => message,
+                },
+            };
+            CommandOutcome::Continue {
+                selector: None,
+                notice: Some(notice),
+            }
+        }
         CommandAction::ExportHtml => {
             // Render under the lock (read-only, so it can't deadlock a
             // turn) as a string, then write the file with the guard
@@ -3692,6 +4273,32 @@ async fn handle_command(
                 notice: Some(notice),
             }
         }
+        CommandAction::DebugNextRequest => {
+            // Installed on the run-config snapshot rather than the live
+            // agent, so it's picked up by `apply_turn_config` at the
+            // next turn and never races an in-flight one. The hook
+            // fires once; a model swap before then rebuilds the stream
+            // options and drops it, which just disarms the capture.
+            let notice = match crate::debug_request::request_dump_path(&world.session_id) {
+                Ok(path) => {
+                    let notice = format!(
+                        "The next request will be written to {}",
+                        display_path(&path)
+                    );
+                    run_config
+                        .lock()
+                        .expect("run config mutex poisoned")
+                        .stream_options
+                        .on_payload = Some(crate::debug_request::capture_next(path));
+                    notice
+                }
+                Err(e) => format!("Request capture failed: {e}"),
+            };
+            CommandOutcome::Continue {
+                selector: None,
+                notice: Some(notice),
+            }
+        }
         CommandAction::OpenUsageStatus => {
             // The fetch hits the network, so it runs detached: the
             // overlay opens immediately in its loading state and the
@@ -3883,6 +4490,115 @@ async fn handle_command(
             notice: Some(session_busy_notice("start a new session")),
         },
         CommandAction::NewSession => CommandOutcome::SessionChange(SessionRequest::New),
+        CommandAction::SetTurnContext => {
+            let target = world.pump.active_view(tui);
+            let text = tui
+                .get_mut_as::<Editor>(SlotIndex::Editor.idx())
+                .map(|editor| editor.get_text())
+                .unwrap_or_default();
+            let text = text.trim().to_string();
+            let mut staged = world
+                .turn_context
+                .lock()
+                .expect("turn context mutex poisoned");
+            let notice = if text.is_empty() {
+                match staged.take() {
+                    Some(_) => "Next-turn context cleared.".to_string(),
+                    None => {
+                        "Type the context in the editor first, then run this command.".to_string()
+                    }
+                }
+            } else {
+                if let Some(editor) = tui.get_mut_as::<Editor>(SlotIndex::Editor.idx()) {
+                    editor.set_text("");
+                }
+                *staged = Some((target, text));
+                "Context set for the next turn; it won't persist beyond it.".to_string()
+            };
+            CommandOutcome::Continue {
+                selector: None,
+                notice: Some(notice),
+            }
+        }
+        // The running turn holds the agent lock until it ends.
+        CommandAction::ShowChangeSummary if turn_running => CommandOutcome::Continue {
+            selector: None,
+            notice: Some(session_busy_notice("summarize the session")),
+        },
+        CommandAction::ShowChangeSummary => {
+            let log = world.change_log().await;
+            CommandOutcome::Continue {
+                selector: None,
+                notice: Some(format_change_summary(&log)),
+            }
+        }
+        CommandAction::ListApprovals => {
+            let paths = world.remembered_approvals.list();
+            let notice = if paths.is_empty() {
+                "No remembered edit approvals in this session.".to_string()
+            } else {
+                let mut notice = "Edits to these files apply without asking:".to_string();
+                for path in &paths {
+                    notice.push_str("\n  ");
+                    notice.push_str(&sanitize_terminal_output(path));
+                }
+                notice
+            };
+            CommandOutcome::Continue {
+                selector: None,
+                notice: Some(notice),
+            }
+        }
+        CommandAction::ForgetApprovals => CommandOutcome::Continue {
+            selector: None,
+            notice: Some(forget_approvals(world)),
+        },
+        CommandAction::OpenPermissions => {
+            let snapshot = {
+                let cfg = config.lock().expect("config mutex poisoned");
+                // The configured switch-offs never reach the session's
+                // toolset; the session set holds `/tools` toggles.
+                let mut disabled_tools: BTreeSet<String> =
+                    cfg.disabled_tools.iter().cloned().collect();
+                disabled_tools.extend(
+                    world
+                        .disabled_tools
+                        .lock()
+                        .expect("disabled tools mutex poisoned")
+                        .iter()
+                        .cloned(),
+                );
+                PermissionsSnapshot {
+                    confirm_edits: world.confirm_edits.is_on(),
+                    remembered: world.remembered_approvals.list(),
+                    disabled_tools: disabled_tools.into_iter().collect(),
+                    protected_paths: cfg.protected_paths.clone(),
+                    confine_paths: cfg.confine_paths,
+                    allowed_roots: cfg.allowed_roots.clone(),
+                }
+            };
+            let inner = PermissionsWindowComponent::new(settings_list_theme(theme), snapshot);
+            let outcome = inner.outcome_handle();
+            let changes = inner.changes_handle();
+            let initial_inner_rows = large_overlay_inner_rows(usize::from(tui.terminal().rows()));
+            let window = aj_tui::components::overlay_window::OverlayWindow::new(
+                "Permissions",
+                Box::new(inner),
+                crate::config::theme::overlay_window_theme(theme),
+                initial_inner_rows,
+            )
+            .with_dynamic_height(tui.handle(), large_overlay_inner_rows)
+            .with_subtitle(subtitle_change_close("change"));
+            let handle = tui.show_overlay(Box::new(window), large_overlay_options());
+            CommandOutcome::Continue {
+                selector: Some(OpenSelector::Permissions {
+                    handle,
+                    outcome,
+                    changes,
+                }),
+                notice: None,
+            }
+        }
         // The interactive loop intercepts `Compact` before reaching
         // `handle_command` (it needs the turn machinery this function
         // doesn't have), so this arm only exists for exhaustiveness.
@@ -3914,11 +4630,48 @@ async fn handle_command(
                     hide_thinking_block: render_settings.hide_thinking_block(),
                     image_auto_resize: cfg.image_auto_resize,
                     image_show_in_terminal: render_settings.show_image_in_terminal(),
+                    show_tool_input: render_settings.show_tool_input(),
+                    turn_separator: cfg.turn_separator,
+                    turn_timestamps: cfg.turn_timestamps,
                     image_block: cfg.image_block,
                     syntax_highlighting: cfg.syntax_highlighting,
                     auto_compact: cfg.auto_compact,
                     compact_threshold: cfg.compact_threshold.to_string(),
                     compact_keep_recent: cfg.compact_keep_recent.to_string(),
+                    idle_timeout_secs: cfg.idle_timeout_secs.to_string(),
+                    bash_preserve_ansi: cfg.bash_preserve_ansi,
+                    bash_timeout_secs: cfg.bash_timeout_secs.to_string(),
+                    env_tool_values: cfg.env_tool_values,
+                    confirm_edit_batches: cfg.confirm_edit_batches,
+                    normalize_on_write: cfg.normalize_on_write,
+                    respect_editorconfig: cfg.respect_editorconfig,
+                    confirm_with_listing: cfg.confirm_with_listing,
+                    nudge_empty_responses: cfg.nudge_empty_responses,
+                    retry_partial_streams: cfg.retry_partial_streams,
+                    guard_repeated_prompts: cfg.guard_repeated_prompts,
+                    fallback_models: cfg.fallback_models.join(", "),
+                    max_file_size: cfg.max_file_size.to_string(),
+                    protected_paths: cfg.protected_paths.join(", "),
+                    confine_paths: cfg.confine_paths,
+                    allowed_roots: cfg.allowed_roots.join(", "),
+                    offer_resume: cfg.offer_resume,
+                    prewarm_cache: cfg.prewarm_cache,
+                    read_fuzzy_paths: cfg.read_fuzzy_paths,
+                    max_concurrent_requests: cfg.max_concurrent_requests.to_string(),
+                    rate_limit_throttle: cfg.rate_limit_throttle,
+                    tool_result_warn_tokens: cfg.tool_result_warn_tokens.to_string(),
+                    max_context_file_bytes: cfg.max_context_file_bytes.to_string(),
+                    editor_command: cfg.editor_command.clone(),
+                    trash_writes: cfg.trash_writes,
+                    trash_dir: cfg.trash_dir.clone(),
+                    tool_audit_log: cfg.tool_audit_log.clone(),
+                    greeting: cfg.greeting.clone(),
+                    show_startup_summary: cfg.show_startup_summary,
+                    user_agent: cfg.user_agent.clone(),
+                    metadata_user_id: cfg.metadata_user_id.clone(),
+                    style_terseness: cfg.style_terseness.map(|t| t.to_string()),
+                    style_explain_first: cfg.style_explain_first,
+                    style_language: cfg.style_language.clone(),
                 }
             };
             // Builtin tool names for the disabled-tools toggle list.
@@ -4100,6 +4853,82 @@ async fn handle_command(
                 }
             }
         }
+        CommandAction::OpenTools => {
+            // Listed from the world's build-time snapshot rather than
+            // the agent, which a running turn holds locked.
+            let rows: Vec<ToolRow> = {
+                let disabled = world
+                    .disabled_tools
+                    .lock()
+                    .expect("disabled tools mutex poisoned");
+                world
+                    .tool_names
+                    .iter()
+                    .map(|name| ToolRow {
+                        name: name.clone(),
+                        enabled: !disabled.contains(name),
+                    })
+                    .collect()
+            };
+            let inner = ToolsWindowComponent::new(settings_list_theme(theme), rows);
+            let outcome = inner.outcome_handle();
+            let changes = inner.changes_handle();
+            let initial_inner_rows = large_overlay_inner_rows(usize::from(tui.terminal().rows()));
+            let window = aj_tui::components::overlay_window::OverlayWindow::new(
+                "Tools",
+                Box::new(inner),
+                crate::config::theme::overlay_window_theme(theme),
+                initial_inner_rows,
+            )
+            .with_dynamic_height(tui.handle(), large_overlay_inner_rows)
+            .with_subtitle(subtitle_change_close("toggle"));
+            let handle = tui.show_overlay(Box::new(window), large_overlay_options());
+            CommandOutcome::Continue {
+                selector: Some(OpenSelector::Tools {
+                    handle,
+                    outcome,
+                    changes,
+                }),
+                notice: None,
+            }
+        }
+        CommandAction::OpenRestore => {
+            let trash = crate::session_setup::trash(&config.lock().expect("config mutex poisoned"));
+            let cwd = &world.env.working_directory;
+            let versions = trash.list(cwd, RESTORE_LIST_LIMIT);
+            if versions.is_empty() {
+                return CommandOutcome::Continue {
+                    selector: None,
+                    notice: Some(format!(
+                        "No kept file versions in {}. Turn on trash_writes to keep them.",
+                        trash.dir(cwd).display()
+                    )),
+                };
+            }
+            let inner = RestoreSelectorComponent::new(
+                select_list_theme(theme),
+                versions,
+                cwd,
+                PALETTE_OVERLAY_INNER_ROWS,
+            );
+            let outcome = inner.outcome_handle();
+            let window = aj_tui::components::overlay_window::OverlayWindow::new(
+                "Restore a file version",
+                Box::new(inner),
+                crate::config::theme::overlay_window_theme(theme),
+                PALETTE_OVERLAY_INNER_ROWS,
+            )
+            .with_subtitle(&subtitle_confirm_close());
+            let handle = tui.show_overlay(Box::new(window), palette_overlay_options());
+            CommandOutcome::Continue {
+                selector: Some(OpenSelector::Restore {
+                    handle,
+                    outcome,
+                    trash,
+                }),
+                notice: None,
+            }
+        }
         CommandAction::Help => {
             let inner = crate::modes::interactive::components::help_overlay::build_overlay(
                 select_list_theme(theme),
@@ -4698,6 +5527,23 @@ async fn apply_setting_change(
                 save_note,
             ))
         }
+        "show_tool_input" => {
+            let show = value == "true";
+            render_settings.set_show_tool_input(show);
+            tui.request_render();
+            let save_note = persist_setting(
+                layers,
+                config,
+                persist,
+                "show_tool_input",
+                Some(value),
+                |c| c.show_tool_input = show,
+            );
+            Some(join_notice(
+                format!("show_tool_input set to {show}."),
+                save_note,
+            ))
+        }
         "image_auto_resize" => {
             let on = value == "true";
             let save_note = persist_setting(
@@ -4754,6 +5600,135 @@ async fn apply_setting_change(
                 save_note,
             ))
         }
+        "user_agent" | "metadata_user_id" => {
+            let new_value = (!value.is_empty()).then(|| value.to_string());
+            let save_note =
+                persist_setting(layers, config, persist, id, new_value.as_deref(), |c| {
+                    if id == "user_agent" {
+                        c.user_agent = new_value.clone();
+                    } else {
+                        c.metadata_user_id = new_value.clone();
+                    }
+                });
+            let what = match &new_value {
+                Some(v) => format!("set to {v}"),
+                None => "unset".to_string(),
+            };
+            Some(join_notice(
+                format!("{id} {what}. Takes effect on restart."),
+                save_note,
+            ))
+        }
+        "editor_command" => {
+            let command = (!value.is_empty()).then(|| value.to_string());
+            let save_note = persist_setting(
+                layers,
+                config,
+                persist,
+                "editor_command",
+                command.as_deref(),
+                |c| c.editor_command = command.clone(),
+            );
+            let what = match &command {
+                Some(c) => format!("set to {c}"),
+                None => "unset".to_string(),
+            };
+            Some(join_notice(
+                format!("editor_command {what}. Takes effect for new sessions."),
+                save_note,
+            ))
+        }
+        "greeting" => {
+            let greeting = (!value.is_empty()).then(|| value.to_string());
+            let save_note = persist_setting(
+                layers,
+                config,
+                persist,
+                "greeting",
+                greeting.as_deref(),
+                |c| c.greeting = greeting.clone(),
+            );
+            let what = match &greeting {
+                Some(g) => format!("set to {g}"),
+                None => "unset".to_string(),
+            };
+            Some(join_notice(
+                format!("greeting {what}. Takes effect on restart."),
+                save_note,
+            ))
+        }
+        "style_terseness" => {
+            let terseness = if value == UNSET_VALUE {
+                None
+            } else {
+                match value.parse::<ConfigStyleTerseness>() {
+                    Ok(t) => Some(t),
+                    Err(err) => return Some(format!("Can't set style_terseness: {err}")),
+                }
+            };
+            let value_opt = (value != UNSET_VALUE).then_some(value);
+            let save_note =
+                persist_setting(layers, config, persist, "style_terseness", value_opt, |c| {
+                    c.style_terseness = terseness
+                });
+            Some(join_notice(
+                format!("style_terseness set to {value}. Takes effect for new sessions."),
+                save_note,
+            ))
+        }
+        "style_language" => {
+            let language = (!value.is_empty()).then(|| value.to_string());
+            let save_note = persist_setting(
+                layers,
+                config,
+                persist,
+                "style_language",
+                language.as_deref(),
+                |c| c.style_language = language.clone(),
+            );
+            let what = match &language {
+                Some(l) => format!("set to {l}"),
+                None => "unset".to_string(),
+            };
+            Some(join_notice(
+                format!("style_language {what}. Takes effect for new sessions."),
+                save_note,
+            ))
+        }
+        "trash_dir" => {
+            let dir = (!value.is_empty()).then(|| value.to_string());
+            let save_note =
+                persist_setting(layers, config, persist, "trash_dir", dir.as_deref(), |c| {
+                    c.trash_dir = dir.clone()
+                });
+            let what = match &dir {
+                Some(d) => format!("set to {d}"),
+                None => "unset".to_string(),
+            };
+            Some(join_notice(
+                format!("trash_dir {what}. Takes effect for new sessions."),
+                save_note,
+            ))
+        }
+        "tool_audit_log" => {
+            let path = (!value.is_empty()).then(|| value.to_string());
+            let save_note = persist_setting(
+                layers,
+                config,
+                persist,
+                "tool_audit_log",
+                path.as_deref(),
+                |c| c.tool_audit_log = path.clone(),
+            );
+            let what = match &path {
+                Some(p) => format!("set to {p}"),
+                None => "unset".to_string(),
+            };
+            Some(join_notice(
+                format!("tool_audit_log {what}. Takes effect for new sessions."),
+                save_note,
+            ))
+        }
         "disabled_tools" => {
             let tools: Vec<String> = value
                 .split(',')
@@ -5163,6 +6138,14 @@ async fn handle_selector_outcome(
             None => SelectorTransition::Stay,
             Some(()) => SelectorTransition::Back,
         },
+        OpenSelector::ContextList { outcome, .. } => match outcome.take() {
+            None => SelectorTransition::Stay,
+            Some(()) => SelectorTransition::Back,
+        },
+        OpenSelector::ContextBreakdown { outcome, .. } => match outcome.take() {
+            None => SelectorTransition::Stay,
+            Some(()) => SelectorTransition::Back,
+        },
         OpenSelector::UsageStatus { outcome, .. } => {
             use crate::modes::interactive::components::usage_status::UsageStatusOutcome;
             match outcome.take() {
@@ -5325,6 +6308,93 @@ async fn handle_selector_outcome(
                 Some(SkillsWindowOutcome::Closed) => SelectorTransition::Back,
             }
         }
+        OpenSelector::Tools {
+            outcome, changes, ..
+        } => {
+            let drained: Vec<(String, String)> =
+                std::mem::take(&mut *changes.lock().expect("tools changes poisoned"));
+            for (name, value) in drained {
+                {
+                    let mut disabled = world
+                        .disabled_tools
+                        .lock()
+                        .expect("disabled tools mutex poisoned");
+                    if value == "disabled" {
+                        disabled.insert(name.clone());
+                    } else {
+                        disabled.remove(&name);
+                    }
+                }
+                let notice =
+                    format!("Tool {name} {value} for this session. Takes effect next inference.");
+                world.pump.handle(tui, &notice_event(&notice));
+            }
+            let outcome_value = outcome.lock().expect("tools outcome poisoned").take();
+            match outcome_value {
+                None => SelectorTransition::Stay,
+                Some(ToolsWindowOutcome::Closed) => SelectorTransition::Back,
+            }
+        }
+        OpenSelector::Permissions {
+            outcome, changes, ..
+        } => {
+            let drained: Vec<(String, String)> =
+                std::mem::take(&mut *changes.lock().expect("permissions changes poisoned"));
+            for (id, value) in drained {
+                let notice = match id.as_str() {
+                    CONFIRM_EDITS_ROW => {
+                        let on = value == "on";
+                        world.confirm_edits.set(on);
+                        let save_note =
+                            persist_user(config_layers, &config, |c| c.confirm_edit_batches = on);
+                        let state = if on {
+                            "Edit batches now wait for approval."
+                        } else {
+                            "Edit batches now apply without asking."
+                        };
+                        join_notice(state.to_string(), save_note)
+                    }
+                    REMEMBERED_ROW => forget_approvals(world),
+                    _ => continue,
+                };
+                world.pump.handle(tui, &notice_event(&notice));
+            }
+            let outcome_value = outcome.lock().expect("permissions outcome poisoned").take();
+            match outcome_value {
+                None => SelectorTransition::Stay,
+                Some(PermissionsWindowOutcome::Closed) => SelectorTransition::Back,
+            }
+        }
+        OpenSelector::Restore { outcome, trash, .. } => match outcome.take() {
+            None => SelectorTransition::Stay,
+            Some(RestoreSelectorOutcome::Cancelled) => SelectorTransition::Back,
+            Some(RestoreSelectorOutcome::Confirmed(version)) => {
+                let notice = match trash.restore(&version, &world.env.working_directory) {
+                    Ok(()) => format!(
+                        "Restored {} from {}. The replaced content was kept in the trash.",
+                        version.original.display(),
+                        version.stamp
+                    ),
+                    Err(e) => e,
+                };
+                SelectorTransition::Close(CloseEffects::notice(notice))
+            }
+        },
+        OpenSelector::EditApproval { outcome, reply, .. } => match outcome.take() {
+            None => SelectorTransition::Stay,
+            Some(answer) => {
+                let decision = match answer {
+                    BatchApprovalOutcome::Approved => EditDecision::Apply,
+                    BatchApprovalOutcome::ApprovedAlways => EditDecision::ApplyAlways,
+                    BatchApprovalOutcome::Denied { reason } => EditDecision::Deny { reason },
+                };
+                if let Some(reply) = reply.lock().expect("edit approval reply poisoned").take() {
+                    // The turn may have ended meanwhile; nothing to tell.
+                    let _ = reply.send(decision);
+                }
+                SelectorTransition::Back
+            }
+        },
         OpenSelector::Palette { outcome, .. } => {
             use crate::modes::interactive::components::command_palette::CommandPaletteOutcome;
             match outcome.take() {
@@ -5346,7 +6416,9 @@ async fn handle_selector_outcome(
 mod tests {
     use std::path::PathBuf;
 
-    use aj_conf::{AgentEnv, ContextFile, ContextFileKind, SystemPrompt, SystemPromptSource};
+    use aj_conf::{
+        AgentEnv, ContextFile, ContextFileKind, PromptStyle, SystemPrompt, SystemPromptSource,
+    };
     use tempfile::TempDir;
 
     use super::*;
@@ -5363,6 +6435,7 @@ mod tests {
         AgentEnv {
             working_directory: PathBuf::from("/tmp"),
             git_root_directory: None,
+            project_types: Vec::new(),
             operating_system: "linux".to_string(),
             today_date: "2025-01-01".to_string(),
             system_prompt: SystemPrompt {
@@ -5372,6 +6445,7 @@ mod tests {
             context_files,
             skills: Vec::new(),
             skill_diagnostics: Vec::new(),
+            style: PromptStyle::default(),
         }
     }
 
@@ -5506,6 +6580,27 @@ mod tests {
         assert_eq!(resolve_theme_name(Some("solarized")), "solarized");
     }
 
+    #[test]
+    fn build_startup_summary_names_model_cwd_instructions_and_approval() {
+        let mut env = env_with(Vec::new());
+        env.working_directory = PathBuf::from("/var/project");
+        let key = ("anthropic".to_string(), "claude-test".to_string());
+        assert_eq!(
+            build_startup_summary(&key, &env, false),
+            "Model anthropic/claude-test · cwd /var/project · instructions: none · edit approval: off"
+        );
+        env.context_files = vec![ContextFile {
+            path: PathBuf::from("/var/project/AGENTS.md"),
+            kind: ContextFileKind::ProjectInstructions,
+            content: String::new(),
+            truncated_from: None,
+        }];
+        assert!(
+            build_startup_summary(&key, &env, true)
+                .ends_with("instructions: 1 file · edit approval: on")
+        );
+    }
+
     #[test]
     fn build_context_notice_without_files_lists_only_the_system_prompt() {
         let env = env_with(Vec::new());
@@ -5528,11 +6623,13 @@ mod tests {
                 path: user_path,
                 kind: ContextFileKind::UserInstructions,
                 content: String::new(),
+                truncated_from: None,
             },
             ContextFile {
                 path: project_path,
                 kind: ContextFileKind::ProjectInstructions,
                 content: String::new(),
+                truncated_from: None,
             },
         ]);
 
@@ -6842,7 +7939,7 @@ mod tests {
         let dir = TempDir::new().expect("tempdir");
         let persistence = ConversationPersistence::new(dir.path().to_path_buf());
         let run_config = scripted_run_config(vec![finalized_text_message("hi back")]);
-        let world =
+        let mut world =
             build_test_world(&persistence, &run_config, &create_spec()).expect("create world");
         let mut tui = Tui::new(Box::new(StubTerminal));
         build_layout(&mut tui, &ThemeHandle::new(Theme::bundled_dark()), true);
@@ -6854,7 +7951,7 @@ mod tests {
         let mut turn_cancels: HashMap<AgentId, CancellationToken> = HashMap::new();
         let spawned = spawn_prompt_turn(
             &mut tui,
-            &world,
+            &mut world,
             &run_config,
             AgentId::Main,
             "do the thing".to_string(),
@@ -6890,7 +7987,7 @@ mod tests {
         let dir = TempDir::new().expect("tempdir");
         let persistence = ConversationPersistence::new(dir.path().to_path_buf());
         let run_config = scripted_run_config(Vec::new());
-        let world =
+        let mut world =
             build_test_world(&persistence, &run_config, &create_spec()).expect("create world");
         let mut tui = Tui::new(Box::new(StubTerminal));
         build_layout(&mut tui, &ThemeHandle::new(Theme::bundled_dark()), true);
@@ -6902,7 +7999,7 @@ mod tests {
         let mut turn_cancels: HashMap<AgentId, CancellationToken> = HashMap::new();
         let spawned = spawn_prompt_turn(
             &mut tui,
-            &world,
+            &mut world,
             &run_config,
             AgentId::Sub(99),
             "x".to_string(),
@@ -7071,6 +8168,7 @@ mod run_loop_tests {
                 &mut theme_watch,
                 &mut history_rx,
                 launch,
+                Vec::new(),
             )
             .await
             .expect("run_session returns Ok")
@@ -7113,6 +8211,7 @@ mod run_loop_tests {
             speed: None,
             model_key: ("scripted".to_string(), "scripted".to_string()),
             session_id: None,
+            fallback_models: Vec::new(),
         }))
     }
 
@@ -7250,6 +8349,104 @@ mod run_loop_tests {
         assert!(matches!(exit, SessionExit::Quit));
     }
 
+    /// With `idle_timeout_secs` set, a session left alone quits on its
+    /// own once the launch turn has finished, and never before: the
+    /// scripted reply still lands in the chat.
+    #[tokio::test(start_paused = true)]
+    #[serial_test::serial]
+    async fn idle_timeout_quits_after_the_turn_settles() {
+        let run_config = scripted_run_config(vec![finalized_text_message("reply before idle")]);
+        let mut h = build_harness(run_config).await;
+        h.shell
+            .config
+            .lock()
+            .expect("config mutex poisoned")
+            .idle_timeout_secs = 60;
+
+        let exit = h.run(vec![UserContent::text("hello agent")]).await;
+
+        assert!(matches!(exit, SessionExit::Quit));
+        let chat = h.chat_text();
+        assert!(
+            chat.contains("reply before idle"),
+            "the turn finished before the idle quit:\n{chat}"
+        );
+    }
+
+    /// With edit-batch approval on, a `write_file` call parks the turn
+    /// on the approval overlay; Esc denies it, the file is never
+    /// written, and the turn carries on to the model's next reply.
+    #[tokio::test(start_paused = true)]
+    #[serial_test::serial]
+    async fn denied_edit_batch_is_not_applied() {
+        use aj_models::types::{AssistantContent, StopReason, ToolCall};
+
+        use crate::modes::interactive::edit_approval::{
+            ConfirmEdits, EditProjection, edit_approval_hook,
+        };
+
+        let dir = TempDir::new().expect("target tempdir");
+        let target = dir.path().join("denied.txt");
+        let write_call = AssistantMessage {
+            content: vec![AssistantContent::ToolCall(ToolCall {
+                id: "tu-1".to_string(),
+                name: "write_file".to_string(),
+                arguments: serde_json::json!({
+                    "path": target.display().to_string(),
+                    "content": "should not land",
+                }),
+            })],
+            api: "scripted".to_string(),
+            provider: "scripted".to_string(),
+            model: "scripted".to_string(),
+            response_id: Some("test-write-msg".to_string()),
+            usage: Default::default(),
+            stop_reason: StopReason::ToolUse,
+            error: None,
+            timestamp: 0,
+        };
+        let run_config = scripted_run_config(vec![
+            write_call,
+            finalized_text_message("understood, not written"),
+        ]);
+        let mut h = build_harness(run_config).await;
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        h.world
+            .agent
+            .lock()
+            .await
+            .set_before_tool_batch(Some(edit_approval_hook(
+                tx,
+                h.world.remembered_approvals.clone(),
+                ConfirmEdits::new(true),
+                EditProjection::default(),
+            )));
+        h.world.edit_approvals = Some(rx);
+
+        // First sleep fires once the turn is parked on the approval
+        // overlay; the second once the follow-up reply has rendered.
+        // Kept short: the busy spinner keeps ticking while the turn is
+        // parked, so the paused clock advances one frame at a time.
+        let input = h.input.clone();
+        let feeder = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            let _ = input.send(Key::escape());
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            let _ = input.send(Key::ctrl('c'));
+        });
+
+        let exit = h.run(vec![UserContent::text("write it")]).await;
+        feeder.abort();
+
+        assert!(matches!(exit, SessionExit::Quit));
+        assert!(!target.exists(), "a denied write must not touch disk");
+        let chat = h.chat_text();
+        assert!(
+            chat.contains("understood, not written"),
+            "the turn continued after the denial:\n{chat}"
+        );
+    }
+
     /// A mid-turn Ctrl+C cancels the in-flight turn without freezing
     /// the loop (the R2/A3 lost-cancellation regression): the turn
     /// aborts, the "Turn cancelled." notice renders, the scripted
@@ -16,9 +16,12 @@ pub mod assistant_message;
 pub mod auth_picker;
 pub mod auth_status;
 pub mod bash_execution;
+pub mod batch_approval;
 pub mod chat_view;
 pub mod command_palette;
 pub mod compaction_summary;
+pub mod context_breakdown;
+pub mod context_list;
 pub mod diff;
 pub mod footer;
 pub mod header;
@@ -28,8 +31,10 @@ pub mod login_dialog;
 pub mod model_selector;
 pub mod outcome;
 pub mod pending_message;
+pub mod permissions_window;
 pub mod prompt_history;
 pub mod read_only_list;
+pub mod restore_selector;
 pub mod session_info;
 pub mod session_selector;
 pub mod settings_window;
@@ -39,5 +44,7 @@ pub mod subagent_box;
 pub mod task_output;
 pub mod thinking_selector;
 pub mod tool_execution;
+pub mod tools_window;
+pub mod turn_separator;
 pub mod usage_status;
 pub mod user_message;
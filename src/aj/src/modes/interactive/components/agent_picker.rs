@@ -320,7 +320,7 @@ fn command_tail(command: &str) -> String {
 }
 
 /// Compact `1m 23s`-style runtime formatter for task rows.
-fn format_runtime(d: Duration) -> String {
+pub(crate) fn format_runtime(d: Duration) -> String {
     let secs = d.as_secs();
     if secs < 60 {
         format!("{secs}s")
@@ -0,0 +1,344 @@
+//! Edit-batch approval overlay (`confirm_edit_batches`).
+//!
+//! Shown when the model requests one or more file-editing tool calls
+//! in a single assistant message and the user opted into reviewing
+//! them first. The overlay lists every pending change (operation and
+//! path, followed by its proposed diff) above a four-row
+//! [`SelectList`] — apply, always apply (the batch's files stop asking
+//! for the rest of the session), deny, or deny with a reason. The last
+//! swaps the list for a one-line [`TextInput`] whose text travels back
+//! to the model with the denial. `Esc` on the list counts as a plain
+//! denial, so the waiting turn is never left hanging. See
+//! `crate::modes::interactive::edit_approval` for the agent-side hook
+//! that raises the request.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use aj_tui::ansi::truncate_to_width;
+use aj_tui::component::Component;
+use aj_tui::components::select_list::{SelectItem, SelectList, SelectListLayout, SelectListTheme};
+use aj_tui::components::text_input::TextInput;
+use aj_tui::keybindings;
+use aj_tui::keys::InputEvent;
+use aj_tui::style;
+
+use crate::modes::interactive::components::outcome::OutcomeSlot;
+
+/// Most diff lines shown across the whole batch, keeping the choice
+/// rows on screen. Every change's label is listed regardless; a diff
+/// past the budget collapses into a "... and N more lines" line.
+const MAX_PREVIEW_DIFF_LINES: usize = 16;
+
+/// One pending change as the overlay shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangePreview {
+    /// Operation and path.
+    pub row: String,
+    /// Styled diff lines, or `None` when the change couldn't be
+    /// previewed.
+    pub diff: Option<Vec<String>>,
+}
+
+/// Outcome of the overlay: apply the whole batch, or deny all of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchApprovalOutcome {
+    Approved,
+    /// Approved, and remember the batch's files for the session.
+    ApprovedAlways,
+    Denied {
+        reason: Option<String>,
+    },
+}
+
+/// Cheap-to-clone handle pointing at the overlay's outcome slot.
+pub type OutcomeHandle = OutcomeSlot<BatchApprovalOutcome>;
+
+/// The overlay's top-level component: the change previews plus the
+/// apply / deny [`SelectList`], or the reason input once the user
+/// picked "Deny with reason".
+pub struct BatchApprovalComponent {
+    changes: Vec<ChangePreview>,
+    inner: SelectList,
+    /// Set by the list's `on_select` when the reason row is chosen.
+    wants_reason: Rc<Cell<bool>>,
+    reason: Option<TextInput>,
+    outcome: OutcomeHandle,
+}
+
+impl BatchApprovalComponent {
+    /// Build the overlay for `changes`, in call order. "Apply" starts
+    /// highlighted.
+    pub fn new(theme: SelectListTheme, changes: Vec<ChangePreview>) -> Self {
+        let noun = if changes.len() == 1 {
+            "change"
+        } else {
+            "changes"
+        };
+        let items = vec![
+            SelectItem::new("apply", &format!("Apply {} {noun}", changes.len())),
+            SelectItem::new("always", "Always apply")
+                .with_description("don't ask again for these files this session"),
+            SelectItem::new("deny", "Deny all").with_description("nothing is applied"),
+            SelectItem::new("reason", "Deny with reason").with_description("tell the model why"),
+        ];
+        let mut inner = SelectList::new(items, 4, theme, SelectListLayout::default());
+
+        let outcome = OutcomeHandle::new();
+        let wants_reason = Rc::new(Cell::new(false));
+        let confirm_outcome = outcome.clone();
+        let confirm_reason = Rc::clone(&wants_reason);
+        inner.on_select = Some(Box::new(move |item| match item.value.as_str() {
+            "apply" => confirm_outcome.set(BatchApprovalOutcome::Approved),
+            "always" => confirm_outcome.set(BatchApprovalOutcome::ApprovedAlways),
+            "reason" => confirm_reason.set(true),
+            _ => confirm_outcome.set(BatchApprovalOutcome::Denied { reason: None }),
+        }));
+        let cancel_outcome = outcome.clone();
+        inner.on_cancel = Some(Box::new(move || {
+            cancel_outcome.set(BatchApprovalOutcome::Denied { reason: None });
+        }));
+
+        Self {
+            changes,
+            inner,
+            wants_reason,
+            reason: None,
+            outcome,
+        }
+    }
+
+    /// Hand the host a clone of the outcome slot.
+    pub fn outcome_handle(&self) -> OutcomeHandle {
+        self.outcome.clone()
+    }
+
+    /// The preview block: every change's label, each followed by as
+    /// much of its diff as the [`MAX_PREVIEW_DIFF_LINES`] budget left
+    /// by earlier changes allows.
+    fn preview_rows(&self, width: usize) -> Vec<aj_tui::Line> {
+        let mut rows: Vec<String> = Vec::new();
+        let mut budget = MAX_PREVIEW_DIFF_LINES;
+        for change in &self.changes {
+            rows.push(format!("  {}", change.row));
+            match &change.diff {
+                Some(diff) => {
+                    let shown = diff.len().min(budget);
+                    budget -= shown;
+                    rows.extend(diff[..shown].iter().map(|l| format!("    {l}")));
+                    let hidden = diff.len() - shown;
+                    if hidden > 0 {
+                        rows.push(format!("    ... and {hidden} more lines"));
+                    }
+                }
+                None => rows.push(format!("    {}", style::dim("(no preview)"))),
+            }
+        }
+        rows.iter()
+            .map(|row| truncate_to_width(row, width, "…", false).into())
+            .collect()
+    }
+}
+
+impl Component for BatchApprovalComponent {
+    aj_tui::impl_component_any!();
+
+    fn render(&mut self, width: usize) -> Vec<aj_tui::Line> {
+        let mut lines = self.preview_rows(width);
+        lines.push(String::new().into());
+        match &mut self.reason {
+            Some(input) => {
+                lines.push("  Reason for denying (Enter to deny, Esc to go back):".into());
+                lines.extend(input.render(width));
+            }
+            None => lines.extend(self.inner.render(width)),
+        }
+        lines
+    }
+
+    fn handle_input(&mut self, event: &InputEvent) -> bool {
+        if let Some(input) = &mut self.reason {
+            let kb = keybindings::get();
+            if kb.matches(event, "tui.select.cancel") {
+                self.reason = None;
+                self.inner.set_focused(true);
+                return true;
+            }
+            if kb.matches(event, "tui.input.submit") {
+                let reason = input.value().trim();
+                self.outcome.set(BatchApprovalOutcome::Denied {
+                    reason: (!reason.is_empty()).then(|| reason.to_string()),
+                });
+                return true;
+            }
+            return input.handle_input(event);
+        }
+        let handled = self.inner.handle_input(event);
+        if self.wants_reason.replace(false) {
+            let mut input = TextInput::new("> ");
+            input.set_focused(true);
+            self.reason = Some(input);
+        }
+        handled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        match &mut self.reason {
+            Some(input) => input.set_focused(focused),
+            None => self.inner.set_focused(focused),
+        }
+    }
+
+    fn is_focused(&self) -> bool {
+        match &self.reason {
+            Some(input) => input.is_focused(),
+            None => self.inner.is_focused(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use aj_tui::keys::Key;
+
+    use super::*;
+
+    fn identity_theme() -> SelectListTheme {
+        SelectListTheme {
+            selected_prefix: Arc::new(|s| s.to_string()),
+            selected_text: Arc::new(|s| s.to_string()),
+            description: Arc::new(|s| s.to_string()),
+            scroll_info: Arc::new(|s| s.to_string()),
+            no_match: Arc::new(|s| s.to_string()),
+            prefix: Arc::new(|s| s.to_string()),
+            shortcut: Arc::new(|s| s.to_string()),
+        }
+    }
+
+    fn change(row: &str, diff: &[&str]) -> ChangePreview {
+        ChangePreview {
+            row: row.to_string(),
+            diff: Some(diff.iter().map(|l| l.to_string()).collect()),
+        }
+    }
+
+    fn body(c: &mut BatchApprovalComponent) -> String {
+        c.render(80)
+            .iter()
+            .map(|l| l.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn previews_changes_and_defaults_to_apply() {
+        let mut c = BatchApprovalComponent::new(
+            identity_theme(),
+            vec![
+                change("edit  src/a.rs", &["-old", "+new"]),
+                ChangePreview {
+                    row: "regex replace  src/b.rs".to_string(),
+                    diff: None,
+                },
+            ],
+        );
+        let text = body(&mut c);
+        assert!(text.contains("edit  src/a.rs"), "{text}");
+        assert!(text.contains("    +new"), "{text}");
+        assert!(text.contains("(no preview)"), "{text}");
+        assert!(text.contains("Apply 2 changes"), "{text}");
+
+        let outcome = c.outcome_handle();
+        c.handle_input(&Key::enter());
+        assert_eq!(outcome.take(), Some(BatchApprovalOutcome::Approved));
+    }
+
+    #[test]
+    fn deny_row_and_esc_both_deny() {
+        let denied = Some(BatchApprovalOutcome::Denied { reason: None });
+        let mut c = BatchApprovalComponent::new(identity_theme(), vec![change("x", &[])]);
+        let outcome = c.outcome_handle();
+        c.handle_input(&Key::down());
+        c.handle_input(&Key::down());
+        c.handle_input(&Key::enter());
+        assert_eq!(outcome.take(), denied);
+
+        let mut c = BatchApprovalComponent::new(identity_theme(), vec![change("x", &[])]);
+        let outcome = c.outcome_handle();
+        c.handle_input(&Key::escape());
+        assert_eq!(outcome.take(), denied);
+    }
+
+    #[test]
+    fn always_row_approves_and_remembers() {
+        let mut c = BatchApprovalComponent::new(identity_theme(), vec![change("x", &[])]);
+        assert!(body(&mut c).contains("Always apply"));
+        let outcome = c.outcome_handle();
+        c.handle_input(&Key::down());
+        c.handle_input(&Key::enter());
+        assert_eq!(outcome.take(), Some(BatchApprovalOutcome::ApprovedAlways));
+    }
+
+    #[test]
+    fn reason_row_collects_text_for_the_denial() {
+        let mut c = BatchApprovalComponent::new(identity_theme(), vec![change("x", &[])]);
+        let outcome = c.outcome_handle();
+        c.handle_input(&Key::down());
+        c.handle_input(&Key::down());
+        c.handle_input(&Key::down());
+        c.handle_input(&Key::enter());
+        assert_eq!(outcome.take(), None);
+        assert!(body(&mut c).contains("Reason for denying"));
+
+        // Esc backs out to the list without deciding.
+        c.handle_input(&Key::escape());
+        assert_eq!(outcome.take(), None);
+        assert!(body(&mut c).contains("Deny with reason"));
+
+        c.handle_input(&Key::enter());
+        for ch in "wrong file".chars() {
+            c.handle_input(&Key::char(ch));
+        }
+        c.handle_input(&Key::enter());
+        assert_eq!(
+            outcome.take(),
+            Some(BatchApprovalOutcome::Denied {
+                reason: Some("wrong file".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn long_previews_collapse_the_tail() {
+        let diff: Vec<String> = (0..MAX_PREVIEW_DIFF_LINES + 2)
+            .map(|i| format!("+{i}"))
+            .collect();
+        let diff: Vec<&str> = diff.iter().map(String::as_str).collect();
+        let mut c = BatchApprovalComponent::new(identity_theme(), vec![change("f.rs", &diff)]);
+        assert!(body(&mut c).contains("... and 2 more lines"));
+    }
+
+    #[test]
+    fn every_change_is_listed_past_the_diff_budget() {
+        let diff: Vec<String> = (0..MAX_PREVIEW_DIFF_LINES)
+            .map(|i| format!("+{i}"))
+            .collect();
+        let diff: Vec<&str> = diff.iter().map(String::as_str).collect();
+        let changes = (0..5)
+            .map(|i| change(&format!("edit  f{i}.rs"), &diff))
+            .collect();
+        let mut c = BatchApprovalComponent::new(identity_theme(), changes);
+        let text = body(&mut c);
+        for i in 0..5 {
+            assert!(text.contains(&format!("edit  f{i}.rs")), "{text}");
+        }
+        assert_eq!(
+            text.matches(&format!("... and {MAX_PREVIEW_DIFF_LINES} more lines"))
+                .count(),
+            4,
+            "{text}"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Read-only token-breakdown overlay (`breakdown`, shown as "token
+//! breakdown" in the palette).
+//!
+//! Shows how the main conversation's context splits across content
+//! types, each with its estimated size and share of the total, then
+//! the tool results totalled per tool, largest first. The list and
+//! close-key mechanics are the shared [`ReadOnlyListOverlay`]; the
+//! numbers come from [`aj_session::context_breakdown`].
+
+use aj_session::ContextBreakdown;
+use aj_tui::components::select_list::{SelectItem, SelectList, SelectListLayout, SelectListTheme};
+
+use crate::modes::interactive::components::footer::format_tokens;
+use crate::modes::interactive::components::read_only_list::{
+    ReadOnlyCloseHandle, ReadOnlyListOverlay,
+};
+
+/// Cheap-to-clone handle the host polls to learn the overlay was closed.
+pub type ContextBreakdownOutcomeHandle = ReadOnlyCloseHandle;
+
+/// Build the overlay from a computed breakdown.
+pub fn build_overlay(
+    list_theme: SelectListTheme,
+    breakdown: &ContextBreakdown,
+) -> ReadOnlyListOverlay {
+    let layout = SelectListLayout {
+        show_selection_indicator: false,
+        ..Default::default()
+    };
+    let items = build_items(breakdown);
+    let visible = items.len().max(1);
+    let scroll_info = std::sync::Arc::clone(&list_theme.scroll_info);
+    let list = SelectList::new(items, visible, list_theme, layout);
+    ReadOnlyListOverlay::new(list, scroll_info)
+}
+
+fn build_items(breakdown: &ContextBreakdown) -> Vec<SelectItem> {
+    let total = breakdown.total();
+    if total == 0 {
+        return vec![SelectItem::new("", "Nothing in the context yet.")];
+    }
+    let categories = [
+        ("system prompt", breakdown.system_prompt),
+        ("summary", breakdown.summary),
+        ("user text", breakdown.user_text),
+        ("assistant text", breakdown.assistant_text),
+        ("thinking", breakdown.thinking),
+        ("tool calls", breakdown.tool_calls),
+        ("tool results", breakdown.tool_results),
+        ("images", breakdown.images),
+    ];
+    let tool_rows: Vec<(String, u64)> = breakdown
+        .by_tool
+        .iter()
+        .map(|share| {
+            let noun = if share.results == 1 {
+                "result"
+            } else {
+                "results"
+            };
+            (
+                format!("{} ({} {noun})", share.tool_name, share.results),
+                share.tokens,
+            )
+        })
+        .collect();
+    let label_width = categories
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .chain(tool_rows.iter().map(|(label, _)| label.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let size_width = categories
+        .iter()
+        .map(|(_, tokens)| *tokens)
+        .chain(std::iter::once(total))
+        .map(|tokens| size_label(tokens).chars().count())
+        .max()
+        .unwrap_or(0);
+    let row = |label: &str, tokens: u64| {
+        let line = format!(
+            "  {label:<label_width$}  {:>size_width$}  {:>3}%",
+            size_label(tokens),
+            percent(tokens, total)
+        );
+        SelectItem::new("", &line)
+    };
+
+    let mut items = vec![SelectItem::new("", "Context")];
+    items.extend(categories.iter().map(|(label, tokens)| row(label, *tokens)));
+    items.push(row("total", total));
+    if !tool_rows.is_empty() {
+        items.push(SelectItem::new("", ""));
+        items.push(SelectItem::new("", "Tool results by tool"));
+        items.extend(tool_rows.iter().map(|(label, tokens)| row(label, *tokens)));
+    }
+    items.push(SelectItem::new("", ""));
+    items.push(SelectItem::new(
+        "",
+        "Estimated at ~4 characters per token. Run `context` to list messages and `drop` to remove one.",
+    ));
+    items
+}
+
+fn size_label(tokens: u64) -> String {
+    format!("~{} tok", format_tokens(tokens))
+}
+
+/// `part` as a whole-number percentage of `total`, rounded.
+#[allow(clippy::as_conversions)]
+fn percent(part: u64, total: u64) -> u64 {
+    ((part as f64) * 100.0 / (total as f64)).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use aj_session::ToolResultShare;
+    use aj_tui::component::Component;
+
+    use super::*;
+
+    fn identity_theme() -> SelectListTheme {
+        SelectListTheme {
+            selected_prefix: Arc::new(|s| s.to_string()),
+            selected_text: Arc::new(|s| s.to_string()),
+            description: Arc::new(|s| s.to_string()),
+            scroll_info: Arc::new(|s| s.to_string()),
+            no_match: Arc::new(|s| s.to_string()),
+            prefix: Arc::new(|s| s.to_string()),
+            shortcut: Arc::new(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn lists_categories_with_shares_and_tools() {
+        let breakdown = ContextBreakdown {
+            system_prompt: 1_000,
+            user_text: 500,
+            tool_results: 8_500,
+            by_tool: vec![
+                ToolResultShare {
+                    tool_name: "read_file".to_string(),
+                    results: 3,
+                    tokens: 8_000,
+                },
+                ToolResultShare {
+                    tool_name: "bash".to_string(),
+                    results: 1,
+                    tokens: 500,
+                },
+            ],
+            ..Default::default()
+        };
+        let mut c = build_overlay(identity_theme(), &breakdown);
+        let body = c
+            .render(120)
+            .iter()
+            .map(|l| l.trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(body.contains("tool results"), "{body}");
+        assert!(body.contains(" 85%"), "{body}");
+        assert!(body.contains(" 10%"), "{body}");
+        assert!(body.contains("read_file (3 results)"), "{body}");
+        assert!(body.contains("bash (1 result)"), "{body}");
+    }
+
+    #[test]
+    fn empty_context_says_so() {
+        let mut c = build_overlay(identity_theme(), &ContextBreakdown::default());
+        let body = c
+            .render(80)
+            .iter()
+            .map(|l| l.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(body.contains("Nothing in the context yet."), "{body}");
+    }
+}
@@ -0,0 +1,132 @@
+//! Read-only context overlay (`context`, shown as "messages" in the
+//! palette).
+//!
+//! Lists every message the model currently receives, numbered the way
+//! the `drop` command takes them: role, block kinds, estimated size,
+//! and the start of the text. A footer row totals the estimate and
+//! points at `drop`. The list and close-key mechanics are the shared
+//! [`ReadOnlyListOverlay`]; the rows come from
+//! [`aj_session::message_rows`].
+
+use aj_session::MessageRow;
+use aj_tui::components::select_list::{SelectItem, SelectList, SelectListLayout, SelectListTheme};
+
+use crate::modes::interactive::components::footer::format_tokens;
+use crate::modes::interactive::components::read_only_list::{
+    ReadOnlyCloseHandle, ReadOnlyListOverlay,
+};
+
+/// Cheap-to-clone handle the host polls to learn the overlay was closed.
+pub type ContextListOutcomeHandle = ReadOnlyCloseHandle;
+
+/// Build the overlay from the projected message rows.
+pub fn build_overlay(list_theme: SelectListTheme, rows: &[MessageRow]) -> ReadOnlyListOverlay {
+    let layout = SelectListLayout {
+        show_selection_indicator: false,
+        ..Default::default()
+    };
+    let items = build_items(rows);
+    let visible = items.len().max(1);
+    let scroll_info = std::sync::Arc::clone(&list_theme.scroll_info);
+    let list = SelectList::new(items, visible, list_theme, layout);
+    ReadOnlyListOverlay::new(list, scroll_info)
+}
+
+fn build_items(rows: &[MessageRow]) -> Vec<SelectItem> {
+    if rows.is_empty() {
+        return vec![SelectItem::new("", "No messages in the context yet.")];
+    }
+    let index_width = rows.len().to_string().len();
+    let role_width = rows
+        .iter()
+        .map(|r| r.role.chars().count())
+        .max()
+        .unwrap_or(0);
+    let size_width = rows
+        .iter()
+        .map(|r| size_label(r.tokens).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut items: Vec<SelectItem> = rows
+        .iter()
+        .map(|r| {
+            let label = format!(
+                "#{:<index_width$}  {:<role_width$}  {:>size_width$}  {}  {}",
+                r.index,
+                r.role,
+                size_label(r.tokens),
+                r.blocks,
+                r.preview
+            );
+            SelectItem::new("", label.trim_end())
+        })
+        .collect();
+    let total: u64 = rows.iter().map(|r| r.tokens).sum();
+    items.push(SelectItem::new("", ""));
+    items.push(SelectItem::new(
+        "",
+        &format!(
+            "{} messages, {}. Type a number in the editor and run `drop` to remove one.",
+            rows.len(),
+            size_label(total)
+        ),
+    ));
+    items
+}
+
+fn size_label(tokens: u64) -> String {
+    format!("~{} tok", format_tokens(tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use aj_tui::component::Component;
+
+    use super::*;
+
+    fn identity_theme() -> SelectListTheme {
+        SelectListTheme {
+            selected_prefix: Arc::new(|s| s.to_string()),
+            selected_text: Arc::new(|s| s.to_string()),
+            description: Arc::new(|s| s.to_string()),
+            scroll_info: Arc::new(|s| s.to_string()),
+            no_match: Arc::new(|s| s.to_string()),
+            prefix: Arc::new(|s| s.to_string()),
+            shortcut: Arc::new(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn lists_numbered_rows_and_a_total() {
+        let rows = vec![
+            MessageRow {
+                index: 1,
+                role: "user".to_string(),
+                blocks: "text".to_string(),
+                tokens: 12,
+                preview: "list files".to_string(),
+            },
+            MessageRow {
+                index: 2,
+                role: "tool_result (bash)".to_string(),
+                blocks: "text".to_string(),
+                tokens: 4_000,
+                preview: "a.txt b.txt".to_string(),
+            },
+        ];
+        let mut c = build_overlay(identity_theme(), &rows);
+        let body = c
+            .render(120)
+            .iter()
+            .map(|l| l.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(body.contains("#1  user"), "{body}");
+        assert!(body.contains("tool_result (bash)"), "{body}");
+        assert!(body.contains("a.txt b.txt"), "{body}");
+        assert!(body.contains("2 messages"), "{body}");
+    }
+}
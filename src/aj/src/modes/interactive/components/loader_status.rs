@@ -13,8 +13,15 @@
 //! per-phase status of an in-flight compaction — and restores the
 //! default with [`LoaderStatus::reset_message`] when that activity
 //! ends.
+//!
+//! While the viewed agent has a tool call in flight the pump also
+//! hands over that tool via [`LoaderStatus::set_tool_status`]; the
+//! message then reads "Running <tool>… 12s (Ctrl+C to stop the tool)"
+//! with the elapsed time refreshed on every frame, and falls back to
+//! the base message once the tool finishes.
 
 use std::any::Any;
+use std::time::Instant;
 
 use aj_tui::component::Component;
 use aj_tui::components::loader::Loader;
@@ -22,6 +29,8 @@ use aj_tui::keys::InputEvent;
 use aj_tui::style;
 use aj_tui::tui::RenderHandle;
 
+use super::agent_picker::format_runtime;
+
 /// Default working message. The "(Ctrl+C to cancel)" suffix surfaces
 /// the cancellation affordance so users can discover it without
 /// consulting docs.
@@ -48,6 +57,14 @@ pub struct LoaderStatus {
     /// [`Loader`] tracks this independently, but mirroring it on
     /// the wrapper keeps `render` cheap and self-contained.
     active: bool,
+    /// Message set through [`Self::set_message`]; shown whenever no
+    /// tool status overrides it.
+    base_message: String,
+    /// Label and start time of the viewed agent's running tool(s).
+    tool_status: Option<(String, Instant)>,
+    /// Text last handed to the inner loader, so `render` only
+    /// relabels it when the elapsed seconds actually change.
+    shown: String,
 }
 
 impl LoaderStatus {
@@ -72,6 +89,9 @@ impl LoaderStatus {
         Self {
             loader,
             active: false,
+            base_message: default_message(),
+            tool_status: None,
+            shown: default_message(),
         }
     }
 
@@ -90,7 +110,8 @@ impl LoaderStatus {
     /// Replace the displayed status message. Triggers an
     /// immediate repaint via the underlying loader.
     pub fn set_message(&mut self, message: &str) {
-        self.loader.set_message(message);
+        self.base_message = message.to_string();
+        self.refresh_message();
     }
 
     /// Reset the message to [`default_message`].
@@ -98,6 +119,35 @@ impl LoaderStatus {
         self.set_message(&default_message());
     }
 
+    /// Show `Some((label, started))` as the running tool, or go back
+    /// to the base message with `None`.
+    pub fn set_tool_status(&mut self, status: Option<(String, Instant)>) {
+        self.tool_status = status;
+        self.refresh_message();
+    }
+
+    /// The text the loader should currently display.
+    fn current_message(&self) -> String {
+        match &self.tool_status {
+            Some((label, started)) => format!(
+                "Running {label}… {} ({} to stop the tool)",
+                format_runtime(started.elapsed()),
+                crate::config::keybindings::fixed_keys::CTRL_C
+            ),
+            None => self.base_message.clone(),
+        }
+    }
+
+    /// Push [`Self::current_message`] to the inner loader if it
+    /// differs from what is already shown.
+    fn refresh_message(&mut self) {
+        let message = self.current_message();
+        if message != self.shown {
+            self.loader.set_message(&message);
+            self.shown = message;
+        }
+    }
+
     /// Whether the loader is currently animating.
     pub fn is_active(&self) -> bool {
         self.active
@@ -114,6 +164,7 @@ impl Component for LoaderStatus {
             // scrollback sits flush against the editor.
             return Vec::new();
         }
+        self.refresh_message();
         self.loader.render(width)
     }
 
@@ -152,6 +203,21 @@ mod tests {
         assert!(!lines.is_empty(), "active loader should produce a row");
     }
 
+    #[test]
+    fn tool_status_overrides_the_message_until_cleared() {
+        let mut s = LoaderStatus::new(RenderHandle::detached());
+        s.start();
+        s.set_tool_status(Some(("bash".to_string(), Instant::now())));
+        assert!(s.shown.starts_with("Running bash… 0s"), "{}", s.shown);
+        assert!(s.shown.contains("to stop the tool"), "{}", s.shown);
+
+        s.set_message("Compacting…");
+        assert!(s.shown.starts_with("Running bash"), "{}", s.shown);
+
+        s.set_tool_status(None);
+        assert_eq!(s.shown, "Compacting…");
+    }
+
     #[test]
     fn stop_clears_active_state() {
         let mut s = LoaderStatus::new(RenderHandle::detached());
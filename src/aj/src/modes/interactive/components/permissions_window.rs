@@ -0,0 +1,309 @@
+//! Permissions window overlay (`/permissions`).
+//!
+//! One page for the session's safety state: whether edit batches wait
+//! for approval, the files remembered with "Always apply", the tools
+//! switched off, the protected paths, and path confinement. Two rows
+//! act on `Enter`: the approval switch toggles between `on` and `off`,
+//! and the remembered-approvals row forgets every remembered file. The
+//! rest are read-only, their descriptions naming where each is edited.
+//!
+//! Like the tools window, the overlay stays open across changes: each
+//! action is pushed onto a shared queue ([`ChangesHandle`]) that the
+//! host drains, and `Esc` closes the window via the outcome slot.
+
+use std::sync::{Arc, Mutex};
+
+use aj_tui::component::Component;
+use aj_tui::components::settings_list::{
+    SettingItem, SettingsList, SettingsListOptions, SettingsListTheme,
+};
+use aj_tui::keys::InputEvent;
+
+/// Row id of the edit-approval switch; its values are `on` / `off`.
+pub const CONFIRM_EDITS_ROW: &str = "confirm_edit_batches";
+
+/// Row id of the remembered approvals; a change means "forget them".
+pub const REMEMBERED_ROW: &str = "remembered_approvals";
+
+/// What the window shows, snapshotted by the host at open time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionsSnapshot {
+    pub confirm_edits: bool,
+    pub remembered: Vec<String>,
+    pub disabled_tools: Vec<String>,
+    pub protected_paths: Vec<String>,
+    pub confine_paths: bool,
+    pub allowed_roots: Vec<String>,
+}
+
+/// Outcome of a window session. The window only ever closes;
+/// individual actions flow through [`ChangesHandle`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionsWindowOutcome {
+    Closed,
+}
+
+/// Cheap-to-clone handle pointing at the same outcome slot the overlay
+/// component writes into.
+pub type OutcomeHandle = Arc<Mutex<Option<PermissionsWindowOutcome>>>;
+
+/// Queue of `(row id, new value)` actions, in the order the user made
+/// them. The host drains it after every input event.
+pub type ChangesHandle = Arc<Mutex<Vec<(String, String)>>>;
+
+/// The overlay's top-level component. See the module docs for the
+/// changes flow.
+pub struct PermissionsWindowComponent {
+    inner: SettingsList,
+    outcome: OutcomeHandle,
+    changes: ChangesHandle,
+}
+
+impl PermissionsWindowComponent {
+    pub fn new(theme: SettingsListTheme, snapshot: PermissionsSnapshot) -> Self {
+        let items = items(snapshot);
+        let count = items.len();
+
+        let outcome: OutcomeHandle = Arc::new(Mutex::new(None));
+        let changes: ChangesHandle = Arc::new(Mutex::new(Vec::new()));
+
+        let changes_for_cb = Arc::clone(&changes);
+        let outcome_for_cb = Arc::clone(&outcome);
+        let inner = SettingsList::new(
+            items,
+            // Pre-push default; the surrounding overlay window pushes
+            // its real budget via `set_available_height`.
+            count,
+            theme,
+            move |id: &str, value: &str| {
+                changes_for_cb
+                    .lock()
+                    .expect("changes mutex poisoned")
+                    .push((id.to_string(), value.to_string()));
+            },
+            move || {
+                *outcome_for_cb.lock().expect("outcome mutex poisoned") =
+                    Some(PermissionsWindowOutcome::Closed);
+            },
+            SettingsListOptions {
+                enable_search: false,
+            },
+        );
+
+        Self {
+            inner,
+            outcome,
+            changes,
+        }
+    }
+
+    /// Hand the host a clone of the outcome slot, polled after each
+    /// input event; `Some(Closed)` means hide the overlay.
+    pub fn outcome_handle(&self) -> OutcomeHandle {
+        Arc::clone(&self.outcome)
+    }
+
+    /// Hand the host a clone of the changes queue to drain after each
+    /// input event.
+    pub fn changes_handle(&self) -> ChangesHandle {
+        Arc::clone(&self.changes)
+    }
+}
+
+fn items(snapshot: PermissionsSnapshot) -> Vec<SettingItem> {
+    let mut confirm = SettingItem::cycleable(
+        CONFIRM_EDITS_ROW,
+        "Confirm edit batches",
+        on_off(snapshot.confirm_edits),
+        vec!["on".to_string(), "off".to_string()],
+    );
+    confirm.description = Some(
+        "Preview and approve each batch of file edits before it is applied. \
+         Applies now and is saved as confirm_edit_batches."
+            .to_string(),
+    );
+
+    // A single value: activating the row always lands on "none", which
+    // the host reads as "forget them".
+    let mut remembered = SettingItem::cycleable(
+        REMEMBERED_ROW,
+        "Remembered approvals",
+        count(snapshot.remembered.len(), "file", "files"),
+        vec!["none".to_string()],
+    );
+    remembered.description = Some(if snapshot.remembered.is_empty() {
+        "Files approved with \"Always apply\" edit without asking. None this session.".to_string()
+    } else {
+        format!(
+            "Edits to these files apply without asking; Enter forgets them: {}",
+            snapshot.remembered.join(", ")
+        )
+    });
+
+    let disabled = info(
+        "disabled_tools",
+        "Disabled tools",
+        list_or_none(&snapshot.disabled_tools),
+        "Tools hidden from the model this session. Toggle them in /tools; \
+         disabled_tools in /settings is the durable switch.",
+    );
+    let protected = info(
+        "protected_paths",
+        "Protected paths",
+        list_or_none(&snapshot.protected_paths),
+        "Paths the file-writing tools refuse, whatever is approved. Edit in /settings.",
+    );
+    let roots = info(
+        "confine_paths",
+        "Path confinement",
+        if snapshot.confine_paths {
+            match snapshot.allowed_roots.as_slice() {
+                [] => "working directory".to_string(),
+                roots => format!("working directory, {}", roots.join(", ")),
+            }
+        } else {
+            "off".to_string()
+        },
+        "Directories the file tools may touch when confine_paths is on \
+         (allowed_roots adds to the working directory). Edit in /settings.",
+    );
+
+    vec![confirm, remembered, disabled, protected, roots]
+}
+
+/// A row without values: `Enter` does nothing.
+fn info(id: &str, label: &str, value: String, description: &str) -> SettingItem {
+    SettingItem {
+        id: id.to_string(),
+        label: label.to_string(),
+        description: Some(description.to_string()),
+        current_value: value,
+        empty_placeholder: None,
+        inherited: false,
+        values: None,
+        submenu: None,
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+fn count(n: usize, one: &str, many: &str) -> String {
+    match n {
+        0 => "none".to_string(),
+        1 => format!("1 {one}"),
+        n => format!("{n} {many}"),
+    }
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+impl Component for PermissionsWindowComponent {
+    aj_tui::impl_component_any!();
+
+    fn render(&mut self, width: usize) -> Vec<aj_tui::Line> {
+        self.inner.render(width)
+    }
+
+    fn handle_input(&mut self, event: &InputEvent) -> bool {
+        self.inner.handle_input(event)
+    }
+
+    fn invalidate(&mut self) {
+        self.inner.invalidate();
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.inner.set_focused(focused);
+    }
+
+    fn set_available_height(&mut self, rows: usize) {
+        self.inner.set_available_height(rows);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.inner.is_focused()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aj_tui::keys::Key;
+
+    use super::*;
+
+    fn identity_theme() -> SettingsListTheme {
+        SettingsListTheme {
+            label: Arc::new(|s, _| s.to_string()),
+            value: Arc::new(|s, _| s.to_string()),
+            description: Arc::new(|s| s.to_string()),
+            hint: Arc::new(|s| s.to_string()),
+            marker: Arc::new(|s| s.to_string()),
+            cursor: "→ ".to_string(),
+        }
+    }
+
+    fn snapshot() -> PermissionsSnapshot {
+        PermissionsSnapshot {
+            confirm_edits: true,
+            remembered: vec!["/a.rs".to_string(), "/b.rs".to_string()],
+            disabled_tools: vec!["bash".to_string()],
+            protected_paths: vec![".env".to_string()],
+            confine_paths: true,
+            allowed_roots: vec!["/tmp".to_string()],
+        }
+    }
+
+    #[test]
+    fn shows_every_setting() {
+        let mut component = PermissionsWindowComponent::new(identity_theme(), snapshot());
+        let text = component
+            .render(120)
+            .iter()
+            .map(|l| l.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        for expected in [
+            "Confirm edit batches",
+            "2 files",
+            "bash",
+            ".env",
+            "working directory, /tmp",
+        ] {
+            assert!(text.contains(expected), "missing {expected:?} in:\n{text}");
+        }
+    }
+
+    #[test]
+    fn actions_queue_changes_and_info_rows_do_nothing() {
+        let mut component = PermissionsWindowComponent::new(identity_theme(), snapshot());
+        let changes = component.changes_handle();
+        component.handle_input(&Key::enter());
+        component.handle_input(&Key::down());
+        component.handle_input(&Key::enter());
+        component.handle_input(&Key::down());
+        component.handle_input(&Key::enter());
+        let queued = std::mem::take(&mut *changes.lock().unwrap());
+        assert_eq!(
+            queued,
+            vec![
+                (CONFIRM_EDITS_ROW.to_string(), "off".to_string()),
+                (REMEMBERED_ROW.to_string(), "none".to_string()),
+            ]
+        );
+
+        let outcome = component.outcome_handle();
+        component.handle_input(&Key::escape());
+        assert_eq!(
+            outcome.lock().unwrap().take(),
+            Some(PermissionsWindowOutcome::Closed)
+        );
+    }
+}
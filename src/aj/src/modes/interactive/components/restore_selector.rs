@@ -0,0 +1,201 @@
+//! Restore selector overlay (`/restore`).
+//!
+//! Lists the most recent file versions `trash_writes` kept, newest
+//! first, as a [`SelectList`]: the path (relative to the working
+//! directory where possible) with the time it was replaced and its
+//! size. `Enter` confirms the highlighted version for the host to put
+//! back; `Esc` cancels.
+
+use std::path::Path;
+
+use aj_tools::trash::TrashedVersion;
+use aj_tui::components::select_list::{SelectItem, SelectList, SelectListLayout, SelectListTheme};
+
+use crate::modes::interactive::components::outcome::OutcomeSlot;
+
+/// Outcome of a single overlay session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreSelectorOutcome {
+    Confirmed(TrashedVersion),
+    Cancelled,
+}
+
+/// Cheap-to-clone handle pointing at the same outcome slot the
+/// overlay component writes into.
+pub type OutcomeHandle = OutcomeSlot<RestoreSelectorOutcome>;
+
+pub struct RestoreSelectorComponent {
+    inner: SelectList,
+    outcome: OutcomeHandle,
+}
+
+impl RestoreSelectorComponent {
+    /// Build a selector over `versions` (already newest first). Paths
+    /// under `working_directory` are shown relative to it.
+    pub fn new(
+        theme: SelectListTheme,
+        versions: Vec<TrashedVersion>,
+        working_directory: &Path,
+        max_visible: usize,
+    ) -> Self {
+        let items: Vec<SelectItem> = versions
+            .iter()
+            .enumerate()
+            .map(|(i, version)| {
+                let path = version
+                    .original
+                    .strip_prefix(working_directory)
+                    .unwrap_or(&version.original)
+                    .display()
+                    .to_string();
+                SelectItem::new(&i.to_string(), &path).with_description(&format!(
+                    "{} · {} bytes",
+                    display_stamp(&version.stamp),
+                    version.size
+                ))
+            })
+            .collect();
+
+        let outcome = OutcomeHandle::new();
+        let mut inner = SelectList::new(items, max_visible, theme, SelectListLayout::default());
+
+        let confirm_outcome = outcome.clone();
+        inner.on_select = Some(Box::new(move |item| {
+            if let Some(version) = item
+                .value
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| versions.get(i))
+            {
+                confirm_outcome.set(RestoreSelectorOutcome::Confirmed(version.clone()));
+            }
+        }));
+        let cancel_outcome = outcome.clone();
+        inner.on_cancel = Some(Box::new(move || {
+            cancel_outcome.set(RestoreSelectorOutcome::Cancelled);
+        }));
+
+        Self { inner, outcome }
+    }
+
+    /// Hand the host a clone of the outcome slot, polled after each
+    /// input event.
+    pub fn outcome_handle(&self) -> OutcomeHandle {
+        self.outcome.clone()
+    }
+}
+
+/// `20261017-120301-123` (optionally with a `-N` suffix) as
+/// `2026-10-17 12:03:01`; anything else verbatim.
+fn display_stamp(stamp: &str) -> String {
+    let b = stamp.as_bytes();
+    if b.len() >= 15 && b[8] == b'-' && stamp.is_char_boundary(15) {
+        format!(
+            "{}-{}-{} {}:{}:{}",
+            &stamp[0..4],
+            &stamp[4..6],
+            &stamp[6..8],
+            &stamp[9..11],
+            &stamp[11..13],
+            &stamp[13..15]
+        )
+    } else {
+        stamp.to_string()
+    }
+}
+
+impl aj_tui::component::Component for RestoreSelectorComponent {
+    aj_tui::impl_component_any!();
+
+    fn render(&mut self, width: usize) -> Vec<aj_tui::Line> {
+        self.inner.render(width)
+    }
+
+    fn handle_input(&mut self, event: &aj_tui::keys::InputEvent) -> bool {
+        self.inner.handle_input(event)
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.inner.set_focused(focused);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.inner.is_focused()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use aj_tui::component::Component;
+    use aj_tui::keys::Key;
+
+    use super::*;
+
+    fn identity_theme() -> SelectListTheme {
+        SelectListTheme {
+            selected_prefix: Arc::new(|s| s.to_string()),
+            selected_text: Arc::new(|s| s.to_string()),
+            description: Arc::new(|s| s.to_string()),
+            scroll_info: Arc::new(|s| s.to_string()),
+            no_match: Arc::new(|s| s.to_string()),
+            prefix: Arc::new(|s| s.to_string()),
+            shortcut: Arc::new(|s| s.to_string()),
+        }
+    }
+
+    fn version(path: &str, stamp: &str) -> TrashedVersion {
+        TrashedVersion {
+            original: PathBuf::from(path),
+            trashed: PathBuf::from("/repo/.aj-trash").join(stamp),
+            stamp: stamp.to_string(),
+            size: 12,
+        }
+    }
+
+    #[test]
+    fn lists_relative_paths_and_confirms_the_highlighted_version() {
+        let versions = vec![
+            version("/repo/src/a.rs", "20261017-120301-123"),
+            version("/elsewhere/b.rs", "20261016-090000-000"),
+        ];
+        let mut sel = RestoreSelectorComponent::new(
+            identity_theme(),
+            versions.clone(),
+            Path::new("/repo"),
+            10,
+        );
+        let body = sel
+            .render(100)
+            .iter()
+            .map(|l| l.as_str().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(body.contains("src/a.rs"), "got: {body}");
+        assert!(body.contains("2026-10-17 12:03:01"), "got: {body}");
+        assert!(body.contains("/elsewhere/b.rs"), "got: {body}");
+
+        let outcome = sel.outcome_handle();
+        sel.handle_input(&Key::down());
+        sel.handle_input(&Key::enter());
+        assert_eq!(
+            outcome.take(),
+            Some(RestoreSelectorOutcome::Confirmed(versions[1].clone()))
+        );
+    }
+
+    #[test]
+    fn esc_cancels() {
+        let mut sel = RestoreSelectorComponent::new(
+            identity_theme(),
+            vec![version("/repo/a.rs", "20261017-120301-123")],
+            Path::new("/repo"),
+            10,
+        );
+        let outcome = sel.outcome_handle();
+        sel.handle_input(&Key::escape());
+        assert_eq!(outcome.take(), Some(RestoreSelectorOutcome::Cancelled));
+    }
+}
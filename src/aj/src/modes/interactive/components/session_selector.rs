@@ -247,6 +247,19 @@ fn format_primary(preview: &SessionPreview, is_current: bool) -> String {
     }
 }
 
+/// The startup notice pointing at the project's most recent session
+/// (`offer_resume`), labelled the way the picker labels its row.
+pub fn resume_offer(preview: &SessionPreview, now: DateTime<Utc>) -> String {
+    let age = match format_age(now, preview.last_message_at).as_str() {
+        "now" => "just now".to_string(),
+        age => format!("{age} ago"),
+    };
+    format!(
+        "Last session here: \"{}\" ({age}). Run `resume` to pick it up, or start with `aj continue`.",
+        format_primary(preview, false)
+    )
+}
+
 /// Build the secondary (right / description) column for one row.
 /// Carries session metadata as a triplet: message count, creation
 /// date (adaptive absolute), and time since the last message
@@ -650,6 +663,27 @@ mod tests {
         assert_eq!(format_age(now, now - Duration::days(800)), "2y");
     }
 
+    #[test]
+    fn resume_offer_names_the_session_and_its_age() {
+        let preview = make_preview(
+            "2025-05-10",
+            Some("refactor the agent loop\nand more"),
+            4,
+            Duration::hours(3),
+        );
+        let now = Utc::now();
+        assert_eq!(
+            resume_offer(&preview, now),
+            "Last session here: \"refactor the agent loop\" (3h ago). Run `resume` to pick it up, or start with `aj continue`."
+        );
+        let fresh = make_preview("2025-05-11", None, 0, Duration::seconds(5));
+        assert!(
+            resume_offer(&fresh, now).contains("(no user message yet)\" (just now)"),
+            "{}",
+            resume_offer(&fresh, now)
+        );
+    }
+
     #[test]
     fn format_created_uses_clock_for_same_day() {
         // A timestamp earlier the same calendar day should render
@@ -117,6 +117,9 @@ pub struct SettingsCurrentValues {
     pub hide_thinking_block: bool,
     pub image_auto_resize: bool,
     pub image_show_in_terminal: bool,
+    pub show_tool_input: bool,
+    pub turn_separator: bool,
+    pub turn_timestamps: bool,
     pub image_block: bool,
     pub syntax_highlighting: bool,
     pub auto_compact: bool,
@@ -126,6 +129,48 @@ pub struct SettingsCurrentValues {
     /// Recent-tail token budget kept after compaction, formatted for
     /// display/editing (e.g. `"20000"`).
     pub compact_keep_recent: String,
+    /// Idle-quit timeout in seconds, formatted for display/editing
+    /// (`"0"` when disabled).
+    pub idle_timeout_secs: String,
+    /// Keep ANSI escapes in `bash` output instead of stripping them.
+    pub bash_preserve_ansi: bool,
+    pub bash_timeout_secs: String,
+    pub env_tool_values: bool,
+    /// Ask before applying each batch of file edits.
+    pub confirm_edit_batches: bool,
+    pub normalize_on_write: bool,
+    pub respect_editorconfig: bool,
+    pub confirm_with_listing: bool,
+    pub nudge_empty_responses: bool,
+    pub retry_partial_streams: bool,
+    pub guard_repeated_prompts: bool,
+    /// `fallback_models`, comma-joined for the text editor.
+    pub fallback_models: String,
+    pub max_file_size: String,
+    /// `protected_paths`, comma-joined for the text editor.
+    pub protected_paths: String,
+    pub confine_paths: bool,
+    /// `allowed_roots`, comma-joined for the text editor.
+    pub allowed_roots: String,
+    pub offer_resume: bool,
+    pub prewarm_cache: bool,
+    pub read_fuzzy_paths: bool,
+    pub max_concurrent_requests: String,
+    pub rate_limit_throttle: bool,
+    pub tool_result_warn_tokens: String,
+    pub user_agent: Option<String>,
+    pub metadata_user_id: Option<String>,
+    pub max_context_file_bytes: String,
+    pub editor_command: Option<String>,
+    pub trash_writes: bool,
+    pub trash_dir: Option<String>,
+    pub tool_audit_log: Option<String>,
+    pub greeting: Option<String>,
+    pub show_startup_summary: bool,
+    /// Canonical terseness name, `None` when unset.
+    pub style_terseness: Option<String>,
+    pub style_explain_first: bool,
+    pub style_language: Option<String>,
 }
 
 /// The overlay's top-level component. See the module docs for the
@@ -527,6 +572,23 @@ fn build_items(
             "image_show_in_terminal" => {
                 items.push(bool_item(option, current.image_show_in_terminal, None));
             }
+            "show_tool_input" => {
+                items.push(bool_item(option, current.show_tool_input, None));
+            }
+            "turn_separator" => {
+                items.push(bool_item(
+                    option,
+                    current.turn_separator,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "turn_timestamps" => {
+                items.push(bool_item(
+                    option,
+                    current.turn_timestamps,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
             "image_block" => {
                 items.push(bool_item(
                     option,
@@ -564,6 +626,329 @@ fn build_items(
                 item.description = Some(describe(option, "A positive number of tokens."));
                 items.push(item);
             }
+            "bash_preserve_ansi" => {
+                items.push(bool_item(
+                    option,
+                    current.bash_preserve_ansi,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "env_tool_values" => {
+                items.push(bool_item(
+                    option,
+                    current.env_tool_values,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "confirm_edit_batches" => {
+                items.push(bool_item(
+                    option,
+                    current.confirm_edit_batches,
+                    Some("Takes effect for new sessions; /permissions switches the current one."),
+                ));
+            }
+            "normalize_on_write" => {
+                items.push(bool_item(
+                    option,
+                    current.normalize_on_write,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "respect_editorconfig" => {
+                items.push(bool_item(
+                    option,
+                    current.respect_editorconfig,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "confirm_with_listing" => {
+                items.push(bool_item(
+                    option,
+                    current.confirm_with_listing,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "nudge_empty_responses" => {
+                items.push(bool_item(
+                    option,
+                    current.nudge_empty_responses,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "retry_partial_streams" => {
+                items.push(bool_item(
+                    option,
+                    current.retry_partial_streams,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "guard_repeated_prompts" => {
+                items.push(bool_item(option, current.guard_repeated_prompts, None));
+            }
+            "fallback_models" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.fallback_models.clone(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(none)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Comma-separated. Takes effect for new sessions.",
+                ));
+                items.push(item);
+            }
+            "max_file_size" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.max_file_size.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(option, "Takes effect for new sessions."));
+                items.push(item);
+            }
+            "protected_paths" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.protected_paths.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(
+                    option,
+                    "Comma-separated. Takes effect for new sessions.",
+                ));
+                items.push(item);
+            }
+            "confine_paths" => {
+                items.push(bool_item(
+                    option,
+                    current.confine_paths,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "allowed_roots" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.allowed_roots.clone(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(default)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Comma-separated. Takes effect for new sessions.",
+                ));
+                items.push(item);
+            }
+            "offer_resume" => {
+                items.push(bool_item(
+                    option,
+                    current.offer_resume,
+                    Some("Takes effect on restart."),
+                ));
+            }
+            "prewarm_cache" => {
+                items.push(bool_item(
+                    option,
+                    current.prewarm_cache,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "greeting" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.greeting.clone().unwrap_or_default(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(default)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Takes effect on restart. Submit an empty value to unset.",
+                ));
+                items.push(item);
+            }
+            "show_startup_summary" => {
+                items.push(bool_item(
+                    option,
+                    current.show_startup_summary,
+                    Some("Takes effect on restart."),
+                ));
+            }
+            "read_fuzzy_paths" => {
+                items.push(bool_item(
+                    option,
+                    current.read_fuzzy_paths,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "idle_timeout_secs" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.idle_timeout_secs.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(option, "Takes effect for new sessions."));
+                items.push(item);
+            }
+            "max_concurrent_requests" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.max_concurrent_requests.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(option, "Takes effect on restart."));
+                items.push(item);
+            }
+            "rate_limit_throttle" => {
+                items.push(bool_item(
+                    option,
+                    current.rate_limit_throttle,
+                    Some("Takes effect on restart."),
+                ));
+            }
+            "user_agent" | "metadata_user_id" => {
+                let value = if option.name == "user_agent" {
+                    &current.user_agent
+                } else {
+                    &current.metadata_user_id
+                };
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    value.clone().unwrap_or_default(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(default)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Takes effect on restart. Submit an empty value to unset.",
+                ));
+                items.push(item);
+            }
+            "bash_timeout_secs" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.bash_timeout_secs.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(option, "Takes effect for new sessions."));
+                items.push(item);
+            }
+            "tool_result_warn_tokens" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.tool_result_warn_tokens.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(option, "Takes effect for new sessions."));
+                items.push(item);
+            }
+            "editor_command" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.editor_command.clone().unwrap_or_default(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(none)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Takes effect for new sessions. Submit an empty value to unset.",
+                ));
+                items.push(item);
+            }
+            "style_terseness" => {
+                let mut values = vec![UNSET_VALUE.to_string()];
+                values.extend(enum_values(option));
+                let mut item = SettingItem::cycleable(
+                    option.name,
+                    option.name,
+                    current
+                        .style_terseness
+                        .clone()
+                        .unwrap_or_else(|| UNSET_VALUE.to_string()),
+                    values,
+                );
+                item.description = Some(describe(
+                    option,
+                    "\"default\" adds nothing to the prompt. Takes effect for new sessions.",
+                ));
+                items.push(item);
+            }
+            "style_explain_first" => {
+                items.push(bool_item(
+                    option,
+                    current.style_explain_first,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "style_language" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.style_language.clone().unwrap_or_default(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(default)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Takes effect for new sessions. Submit an empty value to unset.",
+                ));
+                items.push(item);
+            }
+            "trash_writes" => {
+                items.push(bool_item(
+                    option,
+                    current.trash_writes,
+                    Some("Takes effect for new sessions."),
+                ));
+            }
+            "trash_dir" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.trash_dir.clone().unwrap_or_default(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(default)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Takes effect for new sessions. Submit an empty value to unset.",
+                ));
+                items.push(item);
+            }
+            "tool_audit_log" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.tool_audit_log.clone().unwrap_or_default(),
+                    text_submenu_factory(),
+                );
+                item.empty_placeholder = Some("(none)".to_string());
+                item.description = Some(describe(
+                    option,
+                    "Takes effect for new sessions. Submit an empty value to unset.",
+                ));
+                items.push(item);
+            }
+            "max_context_file_bytes" => {
+                let mut item = SettingItem::with_submenu(
+                    option.name,
+                    option.name,
+                    current.max_context_file_bytes.clone(),
+                    text_submenu_factory(),
+                );
+                item.description = Some(describe(option, "Takes effect for new sessions."));
+                items.push(item);
+            }
             other => {
                 tracing::warn!(option = other, "config option has no settings-window row");
             }
@@ -955,11 +1340,48 @@ mod tests {
             hide_thinking_block: false,
             image_auto_resize: true,
             image_show_in_terminal: true,
+            show_tool_input: false,
+            turn_separator: false,
+            turn_timestamps: false,
             image_block: false,
             syntax_highlighting: false,
             auto_compact: true,
             compact_threshold: "0.85".to_string(),
             compact_keep_recent: "20000".to_string(),
+            idle_timeout_secs: "0".to_string(),
+            bash_preserve_ansi: false,
+            bash_timeout_secs: "30".to_string(),
+            env_tool_values: false,
+            confirm_edit_batches: false,
+            normalize_on_write: false,
+            respect_editorconfig: false,
+            confirm_with_listing: false,
+            nudge_empty_responses: false,
+            retry_partial_streams: false,
+            guard_repeated_prompts: false,
+            fallback_models: String::new(),
+            max_file_size: "10485760".to_string(),
+            protected_paths: String::new(),
+            confine_paths: false,
+            allowed_roots: String::new(),
+            offer_resume: true,
+            prewarm_cache: false,
+            read_fuzzy_paths: false,
+            max_concurrent_requests: "0".to_string(),
+            rate_limit_throttle: false,
+            tool_result_warn_tokens: "10000".to_string(),
+            user_agent: None,
+            metadata_user_id: None,
+            max_context_file_bytes: "65536".to_string(),
+            editor_command: None,
+            trash_writes: false,
+            trash_dir: None,
+            tool_audit_log: None,
+            greeting: None,
+            show_startup_summary: false,
+            style_terseness: None,
+            style_explain_first: false,
+            style_language: None,
         }
     }
 
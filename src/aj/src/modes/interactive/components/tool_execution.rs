@@ -185,6 +185,9 @@ pub struct ToolExecutionComponent {
     /// JSON-encoded arguments, rendered inside the parens after
     /// the name. Stored as a string so we can re-render cheaply.
     args_pretty: String,
+    /// The full arguments as pretty-printed JSON, shown under the
+    /// header when [`Self::show_input`] is on.
+    args_full: String,
     /// Current execution status. Drives the header glyph and the
     /// bubble background tint.
     status: Status,
@@ -274,6 +277,11 @@ pub struct ToolExecutionComponent {
     /// [`Self::reconcile_settings`]; sourced from the
     /// `image_show_in_terminal` config key.
     show_image_in_terminal: bool,
+    /// Last-applied tool-input mode — whether [`Self::args_full`]
+    /// renders between the header and the body. Kept in step with
+    /// `settings.show_tool_input()` by [`Self::reconcile_settings`];
+    /// sourced from the `show_tool_input` config key or `--verbose`.
+    show_input: bool,
     /// Terminal status of the background task this cell launched,
     /// set by [`Self::finish_task`] when the task's `TaskEnd`
     /// arrives. Renders into the header's task badge and freezes
@@ -332,10 +340,12 @@ impl ToolExecutionComponent {
     ) -> Self {
         let expanded = settings.tools_expanded();
         let show_image_in_terminal = settings.show_image_in_terminal();
+        let show_input = settings.show_tool_input();
         let last_generation = settings.generation();
         let mut me = Self {
             tool_name,
             args_pretty: format_args(args),
+            args_full: format_args_full(args),
             status: Status::Started,
             body: Vec::new(),
             last_details: None,
@@ -351,6 +361,7 @@ impl ToolExecutionComponent {
             cell_pixel_size,
             image_payload: None,
             show_image_in_terminal,
+            show_input,
             task_status: None,
         };
         me.bubble.set_bg_fn(me.make_bg_box());
@@ -416,7 +427,7 @@ impl ToolExecutionComponent {
     /// last-applied fields and snapshot the generation.
     ///
     /// Returns `true` when a setting that affects the rendered body
-    /// or children — tool expansion or inline-image mode — actually
+    /// or children — tool expansion, inline-image or tool-input mode — actually
     /// changed, so the caller knows to rebuild those caches. A no-op
     /// for the caches when nothing relevant changed, so an unrelated
     /// toggle (e.g. thinking fold, which shares the generation
@@ -435,6 +446,11 @@ impl ToolExecutionComponent {
             self.show_image_in_terminal = show_image;
             changed = true;
         }
+        let show_input = self.settings.show_tool_input();
+        if self.show_input != show_input {
+            self.show_input = show_input;
+            changed = true;
+        }
         changed
     }
 
@@ -484,6 +500,18 @@ impl ToolExecutionComponent {
         line
     }
 
+    /// The full-input block shown under the header when
+    /// [`Self::show_input`] is on: an `input:` label and the
+    /// pretty-printed JSON, dimmed. `None` when the mode is off.
+    fn input_lines(&self) -> Option<Vec<String>> {
+        if !self.show_input {
+            return None;
+        }
+        let mut lines = vec![style::dim("input:")];
+        lines.extend(self.args_full.lines().map(style::dim));
+        Some(lines)
+    }
+
     /// The header's background-task badge: `[task #N]` while the
     /// task runs (or for a resumed launch cell, where the terminal
     /// status is unknown), `[task #N · exited 0]` etc. once
@@ -541,6 +569,11 @@ impl ToolExecutionComponent {
         let header_text = Text::new(&self.header_line(), 0, 0);
         self.bubble.add_child(Box::new(header_text));
 
+        if let Some(input) = self.input_lines() {
+            self.bubble
+                .add_child(Box::new(Text::new(&input.join("\n"), 0, 0)));
+        }
+
         // Body. Joining with `\n` lets `wrap_text_with_ansi`
         // (called inside `Text::render`) see the body as one
         // multi-line input and track its ANSI state across the
@@ -629,7 +662,8 @@ impl Component for ToolExecutionComponent {
         if width < MIN_BUBBLE_WIDTH {
             let mut out: Vec<aj_tui::Line> = Vec::with_capacity(self.body.len() + 1);
             out.push(self.header_line().into());
-            for line in &self.body {
+            let input = self.input_lines().unwrap_or_default();
+            for line in input.iter().chain(&self.body) {
                 out.extend(
                     wrap_text_with_ansi(line, width.max(1))
                         .into_iter()
@@ -695,6 +729,13 @@ fn derive_status(details: &ToolDetails, is_error: bool) -> Status {
     Status::Succeeded
 }
 
+/// Pretty-print the tool's input JSON in full for the
+/// `show_tool_input` view, with control characters in string values
+/// left escaped so the block can't drive the terminal.
+fn format_args_full(args: &Value) -> String {
+    serde_json::to_string_pretty(args).unwrap_or_else(|_| args.to_string())
+}
+
 /// Build a single-line argument summary from the tool's input
 /// JSON. The goal is a compact `command(arg1=val1, arg2=val2)`
 /// preview that fits on one line; if the JSON is too verbose for
@@ -985,6 +1026,35 @@ mod tests {
         assert!(header_trimmed.contains("path=\"/tmp/foo.txt\""));
     }
 
+    #[test]
+    fn show_tool_input_renders_the_full_json_under_the_header() {
+        let args = serde_json::json!({"path": "/tmp/foo.txt", "edits": [{"old": "a"}]});
+        let settings = settings(true);
+        let mut c = ToolExecutionComponent::new(
+            "edit_file_multi".to_string(),
+            &args,
+            &theme(),
+            settings.clone(),
+        );
+        let plain = |c: &mut ToolExecutionComponent| -> Vec<String> {
+            c.render(80)
+                .iter()
+                .map(|l| strip_ansi(l).trim().to_string())
+                .collect()
+        };
+        assert!(!plain(&mut c).iter().any(|l| l == "input:"));
+
+        // Flipping the shared setting reaches the existing component.
+        settings.set_show_tool_input(true);
+        let lines = plain(&mut c);
+        let at = lines
+            .iter()
+            .position(|l| l == "input:")
+            .expect("input block");
+        assert_eq!(lines[at + 1], "{");
+        assert!(lines.iter().any(|l| l == "\"old\": \"a\""), "{lines:?}");
+    }
+
     #[test]
     fn header_only_renders_just_the_header_without_bubble_or_body() {
         let args = serde_json::json!({"path": "/tmp/foo.txt"});
@@ -0,0 +1,201 @@
+//! Tools window overlay (`/tools`).
+//!
+//! Lists the running session's tools; `Enter` toggles the highlighted
+//! tool between enabled and disabled. Like the skills window, the
+//! overlay stays open across changes: each toggle is pushed onto a
+//! shared queue ([`ChangesHandle`]) that the host drains into the
+//! session's disabled-tool set. `Esc` closes the window via the
+//! outcome slot.
+//!
+//! Toggles are session-only — nothing is persisted; the
+//! `disabled_tools` config option is the durable switch — and apply
+//! from the agent's next inference, so a tool can be switched off
+//! while a turn is running.
+
+use std::sync::{Arc, Mutex};
+
+use aj_tui::component::Component;
+use aj_tui::components::settings_list::{
+    SettingItem, SettingsList, SettingsListOptions, SettingsListTheme,
+};
+use aj_tui::keys::InputEvent;
+
+/// One tool row, precomputed by the host from the session's toolset
+/// and its current disabled-tool set.
+pub struct ToolRow {
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// Outcome of a window session. The window only ever closes;
+/// individual toggles flow through [`ChangesHandle`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolsWindowOutcome {
+    Closed,
+}
+
+/// Cheap-to-clone handle pointing at the same outcome slot the overlay
+/// component writes into.
+pub type OutcomeHandle = Arc<Mutex<Option<ToolsWindowOutcome>>>;
+
+/// Queue of `(tool name, "enabled" | "disabled")` toggles, in the
+/// order the user made them. The host drains it after every input
+/// event.
+pub type ChangesHandle = Arc<Mutex<Vec<(String, String)>>>;
+
+/// The overlay's top-level component. See the module docs for the
+/// changes flow.
+pub struct ToolsWindowComponent {
+    inner: SettingsList,
+    outcome: OutcomeHandle,
+    changes: ChangesHandle,
+}
+
+impl ToolsWindowComponent {
+    pub fn new(theme: SettingsListTheme, rows: Vec<ToolRow>) -> Self {
+        let count = rows.len().max(1);
+        let items: Vec<SettingItem> = rows
+            .into_iter()
+            .map(|row| {
+                let value = if row.enabled { "enabled" } else { "disabled" };
+                SettingItem::cycleable(
+                    row.name.clone(),
+                    row.name,
+                    value,
+                    vec!["enabled".to_string(), "disabled".to_string()],
+                )
+            })
+            .collect();
+
+        let outcome: OutcomeHandle = Arc::new(Mutex::new(None));
+        let changes: ChangesHandle = Arc::new(Mutex::new(Vec::new()));
+
+        let changes_for_cb = Arc::clone(&changes);
+        let outcome_for_cb = Arc::clone(&outcome);
+        let inner = SettingsList::new(
+            items,
+            // Pre-push default; the surrounding overlay window pushes
+            // its real budget via `set_available_height`.
+            count,
+            theme,
+            move |id: &str, value: &str| {
+                changes_for_cb
+                    .lock()
+                    .expect("changes mutex poisoned")
+                    .push((id.to_string(), value.to_string()));
+            },
+            move || {
+                *outcome_for_cb.lock().expect("outcome mutex poisoned") =
+                    Some(ToolsWindowOutcome::Closed);
+            },
+            SettingsListOptions {
+                enable_search: true,
+            },
+        );
+
+        Self {
+            inner,
+            outcome,
+            changes,
+        }
+    }
+
+    /// Hand the host a clone of the outcome slot, polled after each
+    /// input event; `Some(Closed)` means hide the overlay.
+    pub fn outcome_handle(&self) -> OutcomeHandle {
+        Arc::clone(&self.outcome)
+    }
+
+    /// Hand the host a clone of the changes queue to drain after each
+    /// input event.
+    pub fn changes_handle(&self) -> ChangesHandle {
+        Arc::clone(&self.changes)
+    }
+}
+
+impl Component for ToolsWindowComponent {
+    aj_tui::impl_component_any!();
+
+    fn render(&mut self, width: usize) -> Vec<aj_tui::Line> {
+        self.inner.render(width)
+    }
+
+    fn handle_input(&mut self, event: &InputEvent) -> bool {
+        self.inner.handle_input(event)
+    }
+
+    fn invalidate(&mut self) {
+        self.inner.invalidate();
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.inner.set_focused(focused);
+    }
+
+    fn set_available_height(&mut self, rows: usize) {
+        self.inner.set_available_height(rows);
+    }
+
+    fn is_focused(&self) -> bool {
+        self.inner.is_focused()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aj_tui::keys::Key;
+
+    use super::*;
+
+    fn identity_theme() -> SettingsListTheme {
+        SettingsListTheme {
+            label: Arc::new(|s, _| s.to_string()),
+            value: Arc::new(|s, _| s.to_string()),
+            description: Arc::new(|s| s.to_string()),
+            hint: Arc::new(|s| s.to_string()),
+            marker: Arc::new(|s| s.to_string()),
+            cursor: "→ ".to_string(),
+        }
+    }
+
+    fn rows() -> Vec<ToolRow> {
+        vec![
+            ToolRow {
+                name: "bash".to_string(),
+                enabled: true,
+            },
+            ToolRow {
+                name: "edit_file".to_string(),
+                enabled: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn toggling_rows_queues_changes_in_order() {
+        let mut component = ToolsWindowComponent::new(identity_theme(), rows());
+        let changes = component.changes_handle();
+        component.handle_input(&Key::enter());
+        component.handle_input(&Key::down());
+        component.handle_input(&Key::enter());
+        let queued = std::mem::take(&mut *changes.lock().unwrap());
+        assert_eq!(
+            queued,
+            vec![
+                ("bash".to_string(), "disabled".to_string()),
+                ("edit_file".to_string(), "enabled".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape_closes_the_window() {
+        let mut component = ToolsWindowComponent::new(identity_theme(), rows());
+        let outcome = component.outcome_handle();
+        component.handle_input(&Key::escape());
+        assert_eq!(
+            outcome.lock().unwrap().take(),
+            Some(ToolsWindowOutcome::Closed)
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Turn separator (`turn_separator`).
+//!
+//! A dim horizontal rule the event pump puts above each typed prompt,
+//! labelled with the turn number and, with `turn_timestamps`, the time
+//! the prompt was sent: `── turn 3 · 14:02 ─────…`. It gives long
+//! transcripts a visual break between turns when scrolling back. The
+//! rule spans the render width, so it is a component rather than a
+//! fixed [`aj_tui::components::text::Text`] line.
+
+use aj_tui::component::Component;
+use aj_tui::keys::InputEvent;
+
+/// Rule glyph; matches the box-drawing style of the other chat rows.
+const RULE: char = '─';
+/// Rule characters before the label.
+const LEAD: usize = 2;
+
+pub struct TurnSeparatorComponent {
+    label: String,
+}
+
+impl TurnSeparatorComponent {
+    /// Separator for turn `turn` (1-based), with `time` (already
+    /// formatted) appended when given.
+    pub fn new(turn: usize, time: Option<&str>) -> Self {
+        let label = match time {
+            Some(time) => format!("turn {turn} · {time}"),
+            None => format!("turn {turn}"),
+        };
+        Self { label }
+    }
+}
+
+impl Component for TurnSeparatorComponent {
+    aj_tui::impl_component_any!();
+
+    fn render(&mut self, width: usize) -> Vec<aj_tui::Line> {
+        let head = format!("{} {} ", RULE.to_string().repeat(LEAD), self.label);
+        let used = head.chars().count();
+        let line: String = if used >= width {
+            head.chars().take(width).collect()
+        } else {
+            head + &RULE.to_string().repeat(width - used)
+        };
+        vec![aj_tui::Line::from(aj_tui::style::dim(&line))]
+    }
+
+    fn handle_input(&mut self, _event: &InputEvent) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(c: &mut TurnSeparatorComponent, width: usize) -> String {
+        let lines = c.render(width);
+        assert_eq!(lines.len(), 1);
+        aj_tui::ansi::strip_ansi(lines[0].as_str())
+    }
+
+    #[test]
+    fn rule_spans_the_width_and_carries_the_label() {
+        let mut c = TurnSeparatorComponent::new(3, Some("14:02"));
+        let line = plain(&mut c, 30);
+        assert_eq!(line.chars().count(), 30);
+        assert!(line.starts_with("── turn 3 · 14:02 ─"), "{line}");
+
+        let mut bare = TurnSeparatorComponent::new(1, None);
+        assert_eq!(plain(&mut bare, 8), "── turn ");
+    }
+}
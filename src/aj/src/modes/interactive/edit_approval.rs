@@ -0,0 +1,691 @@
+//! Batch approval for file edits (`confirm_edit_batches`).
+//!
+//! The session's main agent carries a
+//! [`aj_agent::hooks::BeforeToolBatchHook`], shared with every
+//! sub-agent it spawns, that, while the session's
+//! [`ConfirmEdits`] switch is on, for any assistant message requesting
+//! file-editing tools sends an
+//! [`EditApprovalRequest`] to the main loop and waits for the user's
+//! answer. Each pending change carries the file's projected before /
+//! after contents (see [`propose_change`]), with the same
+//! `normalize_on_write` and `.editorconfig` adjustments the tools make
+//! and read under the same `max_file_size` cap, so the
+//! [`crate::modes::interactive::components::batch_approval`] overlay
+//! can preview the diff before anything touches disk. The loop replies
+//! through the request's oneshot. Approval runs the batch unchanged;
+//! denial (including a dropped reply, e.g. the overlay was torn down or
+//! the session ended) short-circuits every editing call with an error
+//! result — carrying the user's reason when one was given — so the
+//! model knows none of them were applied. Other tools in the same batch
+//! run either way.
+//!
+//! "Always apply" approves the batch and adds its files to the
+//! session's [`RememberedApprovals`]; later batches touching only
+//! remembered files run without asking. The set lives as long as the
+//! session world and is listed and cleared through the `approvals` and
+//! `forget-approvals` commands.
+//!
+//! The switch starts from `confirm_edit_batches` and is flipped for
+//! the running session by the `/permissions` window, so the hook is
+//! installed either way and checks it per batch.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use aj_agent::hooks::{BeforeToolBatchHook, BeforeToolBatchOutcome, ToolBatchCall};
+use aj_agent::tool::{ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use aj_tools::editorconfig::apply_editorconfig;
+use aj_tools::file_size::check_file_size;
+use aj_tools::normalize::{normalize_for_write, normalize_inserted, replace_normalized};
+use aj_tools::tools::write_range::replace_lines;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+
+/// Builtin tools that write files and therefore need approval.
+/// `git_stash` only needs it for the actions that rewrite the working
+/// tree; see [`needs_approval`].
+const FILE_EDIT_TOOLS: &[&str] = &[
+    "edit_file",
+    "edit_file_multi",
+    "format_file",
+    "git_stash",
+    "regex_replace",
+    "write_file",
+    "write_range",
+];
+
+/// What a `git_stash` call is listed under in place of a file path: a
+/// push or pop rewrites the whole working tree.
+const WORKING_TREE: &str = "<working tree>";
+
+/// The write-time settings the file tools were built with, so a
+/// preview projects what they would actually write.
+#[derive(Debug, Clone, Copy)]
+pub struct EditProjection {
+    /// Files over this many bytes aren't read for a preview; `0`
+    /// disables the cap.
+    pub max_file_size: u64,
+    pub normalize_on_write: bool,
+    pub respect_editorconfig: bool,
+}
+
+impl Default for EditProjection {
+    fn default() -> Self {
+        Self {
+            max_file_size: aj_tools::file_size::DEFAULT_MAX_FILE_SIZE,
+            normalize_on_write: false,
+            respect_editorconfig: false,
+        }
+    }
+}
+
+/// One editing call awaiting approval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposedChange {
+    /// Operation and path (see [`describe_call`]).
+    pub row: String,
+    /// The path the call writes, as the model gave it.
+    pub path: String,
+    /// The file's contents before and after the call, when they can be
+    /// projected without running the tool. `None` for calls that would
+    /// fail anyway (no match, bad line range, unreadable or oversized
+    /// file) and for `regex_replace` and `format_file`.
+    pub preview: Option<(String, String)>,
+}
+
+/// The user's answer to an [`EditApprovalRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditDecision {
+    Apply,
+    /// Apply, and stop asking about the batch's files for the rest of
+    /// the session.
+    ApplyAlways,
+    Deny {
+        reason: Option<String>,
+    },
+}
+
+/// A pending batch awaiting the user's decision.
+pub struct EditApprovalRequest {
+    /// One entry per editing call, in call order.
+    pub changes: Vec<ProposedChange>,
+    /// The decision; a dropped sender counts as a denial.
+    pub reply: oneshot::Sender<EditDecision>,
+}
+
+/// Whether edit batches wait for approval; the live form of
+/// `confirm_edit_batches`. Cheap to clone; clones share the switch.
+#[derive(Clone, Default)]
+pub struct ConfirmEdits(Arc<AtomicBool>);
+
+impl ConfirmEdits {
+    pub fn new(on: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(on)))
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Flip the switch; the next batch sees the new value.
+    pub fn set(&self, on: bool) {
+        self.0.store(on, Ordering::Relaxed);
+    }
+}
+
+/// Files whose edits the user approved for the rest of the session.
+/// Cheap to clone; clones share the set.
+#[derive(Clone, Default)]
+pub struct RememberedApprovals {
+    paths: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl RememberedApprovals {
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeSet<String>> {
+        self.paths.lock().expect("remembered approvals poisoned")
+    }
+
+    /// Whether every path in `paths` is remembered.
+    fn covers<'a>(&self, mut paths: impl Iterator<Item = &'a str>) -> bool {
+        let remembered = self.lock();
+        paths.all(|path| remembered.contains(path))
+    }
+
+    fn remember<'a>(&self, paths: impl Iterator<Item = &'a str>) {
+        self.lock().extend(paths.map(str::to_string));
+    }
+
+    /// The remembered paths, sorted.
+    pub fn list(&self) -> Vec<String> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Forget every remembered path, returning how many there were.
+    pub fn clear(&self) -> usize {
+        let mut remembered = self.lock();
+        let count = remembered.len();
+        remembered.clear();
+        count
+    }
+}
+
+/// Build the batch hook that routes editing batches to `requests`
+/// while `confirm` is on, skipping batches whose files are all in
+/// `remembered`. Previews are projected with `projection`.
+pub fn edit_approval_hook(
+    requests: UnboundedSender<EditApprovalRequest>,
+    remembered: RememberedApprovals,
+    confirm: ConfirmEdits,
+    projection: EditProjection,
+) -> BeforeToolBatchHook {
+    Arc::new(move |calls: &[ToolBatchCall<'_>]| {
+        let confirming = confirm.is_on();
+        let edits: Vec<(String, String, Value)> = calls
+            .iter()
+            .filter(|c| confirming && needs_approval(c.tool_name, c.args))
+            .map(|c| {
+                (
+                    c.call_id.to_string(),
+                    c.tool_name.to_string(),
+                    c.args.clone(),
+                )
+            })
+            .collect();
+        let requests = requests.clone();
+        let remembered = remembered.clone();
+        Box::pin(async move {
+            let paths = || edits.iter().map(|(_, tool, args)| call_path(tool, args));
+            if edits.is_empty() || remembered.covers(paths()) {
+                return BeforeToolBatchOutcome::Proceed;
+            }
+            // Projected contents per path, so two calls on one file
+            // preview against each other rather than against disk.
+            // Reading the files blocks, so it runs off the async thread.
+            let calls: Vec<(String, Value)> = edits
+                .iter()
+                .map(|(_, tool, args)| (tool.clone(), args.clone()))
+                .collect();
+            let changes = tokio::task::spawn_blocking(move || {
+                let mut projected = HashMap::new();
+                calls
+                    .iter()
+                    .map(|(tool, args)| propose_change(tool, args, &projection, &mut projected))
+                    .collect::<Vec<_>>()
+            })
+            .await;
+            // A panicked projection still asks, just without previews.
+            let changes = changes.unwrap_or_else(|_| {
+                edits
+                    .iter()
+                    .map(|(_, tool, args)| ProposedChange {
+                        row: describe_call(tool, args),
+                        path: call_path(tool, args).to_string(),
+                        preview: None,
+                    })
+                    .collect()
+            });
+            let (reply, answer) = oneshot::channel();
+            let decision = if requests
+                .send(EditApprovalRequest { changes, reply })
+                .is_ok()
+            {
+                answer.await.unwrap_or(EditDecision::Deny { reason: None })
+            } else {
+                EditDecision::Deny { reason: None }
+            };
+            let reason = match decision {
+                EditDecision::Apply => return BeforeToolBatchOutcome::Proceed,
+                EditDecision::ApplyAlways => {
+                    // A stash rewrites the whole tree, so it's never
+                    // remembered; the next one asks again.
+                    remembered.remember(paths().filter(|path| *path != WORKING_TREE));
+                    return BeforeToolBatchOutcome::Proceed;
+                }
+                EditDecision::Deny { reason } => reason,
+            };
+            let outcomes: HashMap<String, ToolOutcome> = edits
+                .into_iter()
+                .map(|(call_id, tool, args)| {
+                    let summary = format!("{tool}: {}", call_path(&tool, &args));
+                    (call_id, denied_outcome(summary, reason.as_deref()))
+                })
+                .collect();
+            BeforeToolBatchOutcome::ShortCircuit { outcomes }
+        })
+    })
+}
+
+/// Describe one editing call and project its effect the way the tool
+/// would write it under `projection`. `projected` carries the contents
+/// earlier calls in the batch would leave behind.
+fn propose_change(
+    tool_name: &str,
+    args: &Value,
+    projection: &EditProjection,
+    projected: &mut HashMap<String, String>,
+) -> ProposedChange {
+    let path = call_path(tool_name, args).to_string();
+    let before = projected.get(&path).cloned().or_else(|| {
+        check_file_size(Path::new(&path), projection.max_file_size, "").ok()?;
+        std::fs::read_to_string(&path).ok()
+    });
+    let normalize = projection.normalize_on_write;
+    let after = match tool_name {
+        "write_file" => args
+            .get("content")
+            .and_then(Value::as_str)
+            .map(|content| normalize_for_write(normalize, content.to_string()).0),
+        "edit_file" => before
+            .as_deref()
+            .and_then(|b| apply_edit(b, args, normalize)),
+        "edit_file_multi" => before.as_deref().and_then(|b| {
+            let edits = args.get("edits").and_then(Value::as_array)?;
+            edits.iter().try_fold(b.to_string(), |content, edit| {
+                apply_edit(&content, edit, normalize)
+            })
+        }),
+        "write_range" => before.as_deref().and_then(|b| {
+            let line = |key| {
+                args.get(key)
+                    .and_then(Value::as_u64)
+                    .and_then(|n| usize::try_from(n).ok())
+            };
+            let content = args.get("content").and_then(Value::as_str)?;
+            let (content, _) = normalize_inserted(normalize, content, true);
+            replace_lines(b, line("start_line")?, line("end_line")?, &content).ok()
+        }),
+        _ => None,
+    };
+    let after = after.map(|after| {
+        apply_editorconfig(projection.respect_editorconfig, Path::new(&path), after).0
+    });
+    let preview = after.map(|after| {
+        projected.insert(path.clone(), after.clone());
+        (before.unwrap_or_default(), after)
+    });
+    if preview.is_none() {
+        // Later calls on this path can't be projected either.
+        projected.remove(&path);
+    }
+    ProposedChange {
+        row: describe_call(tool_name, args),
+        path,
+        preview,
+    }
+}
+
+/// Apply one `{old_string, new_string, replace_all}` edit the way
+/// `edit_file` does, normalizing the inserted text when `normalize`,
+/// or `None` where the tool would reject it.
+fn apply_edit(content: &str, edit: &Value, normalize: bool) -> Option<String> {
+    let old = edit.get("old_string").and_then(Value::as_str)?;
+    let new = edit.get("new_string").and_then(Value::as_str)?;
+    let replace_all = edit
+        .get("replace_all")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if old.is_empty() {
+        return None;
+    }
+    match content.matches(old).count() {
+        0 => None,
+        1 => Some(replace_normalized(normalize, content, old, new).0),
+        _ if replace_all => Some(replace_normalized(normalize, content, old, new).0),
+        _ => None,
+    }
+}
+
+/// One overlay row for an editing call: operation and path, plus the
+/// edit count for the multi-edit tool and the line range for
+/// `write_range`.
+fn describe_call(tool_name: &str, args: &Value) -> String {
+    let path = call_path(tool_name, args);
+    let op = match tool_name {
+        "write_file" => "write".to_string(),
+        "edit_file" => "edit".to_string(),
+        "edit_file_multi" => match args.get("edits").and_then(Value::as_array).map(Vec::len) {
+            Some(1) => "edit (1 edit)".to_string(),
+            Some(n) => format!("edit ({n} edits)"),
+            None => "edit".to_string(),
+        },
+        "write_range" => match (args.get("start_line"), args.get("end_line")) {
+            (Some(start), Some(end)) => format!("write {start}-{end}"),
+            _ => "write range".to_string(),
+        },
+        "regex_replace" => "regex replace".to_string(),
+        "format_file" => "format".to_string(),
+        "git_stash" => match args.get("action").and_then(Value::as_str) {
+            Some(action) => format!("stash {action}"),
+            None => "stash".to_string(),
+        },
+        other => other.to_string(),
+    };
+    format!("{op:<14} {path}")
+}
+
+/// Whether a call to `tool_name` with `args` writes files. A
+/// `git_stash` `list` only reads.
+fn needs_approval(tool_name: &str, args: &Value) -> bool {
+    FILE_EDIT_TOOLS.contains(&tool_name)
+        && !(tool_name == "git_stash" && args.get("action").and_then(Value::as_str) == Some("list"))
+}
+
+fn call_path<'a>(tool_name: &str, args: &'a Value) -> &'a str {
+    if tool_name == "git_stash" {
+        return WORKING_TREE;
+    }
+    args.get("path")
+        .and_then(Value::as_str)
+        .unwrap_or("<no path>")
+}
+
+/// The result a denied call lands with. `summary` is the collapsed
+/// tool-row label (`"<tool>: <path>"`); `reason` is what the user
+/// typed when denying, if anything.
+fn denied_outcome(summary: String, reason: Option<&str>) -> ToolOutcome {
+    let denied = "The user denied this batch of file edits; this change was not applied.";
+    let text = match reason {
+        Some(reason) => format!("{denied}\nReason: {reason}"),
+        None => denied.to_string(),
+    };
+    ToolOutcome {
+        content: vec![UserContent::text(text.clone())],
+        details: ToolDetails::Text {
+            summary,
+            body: text,
+        },
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn call<'a>(id: &'a str, tool: &'a str, args: &'a Value) -> ToolBatchCall<'a> {
+        ToolBatchCall {
+            call_id: id,
+            tool_name: tool,
+            args,
+        }
+    }
+
+    #[test]
+    fn rows_name_the_operation_and_path() {
+        assert_eq!(
+            describe_call("write_file", &json!({"path": "/a.rs"})),
+            "write          /a.rs"
+        );
+        assert_eq!(
+            describe_call(
+                "edit_file_multi",
+                &json!({"path": "/b.rs", "edits": [{}, {}]})
+            ),
+            "edit (2 edits) /b.rs"
+        );
+        assert_eq!(
+            describe_call(
+                "write_range",
+                &json!({"path": "/c.rs", "start_line": 3, "end_line": 5})
+            ),
+            "write 3-5      /c.rs"
+        );
+    }
+
+    #[test]
+    fn previews_chain_edits_to_the_same_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "one\ntwo\n").unwrap();
+        let path = file.display().to_string();
+        let mut projected = HashMap::new();
+
+        let first = propose_change(
+            "edit_file",
+            &json!({"path": path, "old_string": "one", "new_string": "uno"}),
+            &EditProjection::default(),
+            &mut projected,
+        );
+        assert_eq!(
+            first.preview,
+            Some(("one\ntwo\n".to_string(), "uno\ntwo\n".to_string()))
+        );
+        let second = propose_change(
+            "edit_file_multi",
+            &json!({"path": path, "edits": [{"old_string": "two", "new_string": "dos"}]}),
+            &EditProjection::default(),
+            &mut projected,
+        );
+        assert_eq!(
+            second.preview,
+            Some(("uno\ntwo\n".to_string(), "uno\ndos\n".to_string()))
+        );
+        let third = propose_change(
+            "write_range",
+            &json!({"path": path, "start_line": 2, "end_line": 1, "content": "mid"}),
+            &EditProjection::default(),
+            &mut projected,
+        );
+        assert_eq!(
+            third.preview,
+            Some(("uno\ndos\n".to_string(), "uno\nmid\ndos\n".to_string()))
+        );
+        let missing = propose_change(
+            "edit_file",
+            &json!({"path": path, "old_string": "three", "new_string": "x"}),
+            &EditProjection::default(),
+            &mut projected,
+        );
+        assert_eq!(missing.preview, None);
+    }
+
+    #[test]
+    fn previews_follow_the_tools_write_settings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "one\ntwo\n").unwrap();
+        let path = file.display().to_string();
+        let normalizing = EditProjection {
+            normalize_on_write: true,
+            ..EditProjection::default()
+        };
+
+        let edit = propose_change(
+            "edit_file",
+            &json!({"path": path, "old_string": "one", "new_string": "uno  "}),
+            &normalizing,
+            &mut HashMap::new(),
+        );
+        assert_eq!(
+            edit.preview,
+            Some(("one\ntwo\n".to_string(), "uno\ntwo\n".to_string()))
+        );
+        let write = propose_change(
+            "write_file",
+            &json!({"path": path, "content": "new \n\n\n"}),
+            &normalizing,
+            &mut HashMap::new(),
+        );
+        assert_eq!(
+            write.preview.map(|(_, after)| after),
+            Some("new\n".to_string())
+        );
+
+        let capped = EditProjection {
+            max_file_size: 4,
+            ..EditProjection::default()
+        };
+        let oversized = propose_change(
+            "edit_file",
+            &json!({"path": path, "old_string": "one", "new_string": "uno"}),
+            &capped,
+            &mut HashMap::new(),
+        );
+        assert_eq!(oversized.preview, None);
+    }
+
+    #[tokio::test]
+    async fn batches_without_edits_never_ask() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let hook = edit_approval_hook(
+            tx,
+            RememberedApprovals::default(),
+            ConfirmEdits::new(true),
+            EditProjection::default(),
+        );
+        let args = json!({"command": "ls"});
+        let outcome = hook(&[call("c0", "bash", &args)]).await;
+        assert!(matches!(outcome, BeforeToolBatchOutcome::Proceed));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn denial_short_circuits_only_the_edits() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<EditApprovalRequest>();
+        let hook = edit_approval_hook(
+            tx,
+            RememberedApprovals::default(),
+            ConfirmEdits::new(true),
+            EditProjection::default(),
+        );
+        let edit = json!({"path": "/a.rs"});
+        let bash = json!({"command": "ls"});
+        let answer = tokio::spawn(async move {
+            let request = rx.recv().await.expect("request");
+            assert_eq!(request.changes.len(), 1);
+            request
+                .reply
+                .send(EditDecision::Deny {
+                    reason: Some("wrong file".to_string()),
+                })
+                .unwrap();
+        });
+        let outcome = hook(&[call("c0", "edit_file", &edit), call("c1", "bash", &bash)]).await;
+        answer.await.unwrap();
+        match outcome {
+            BeforeToolBatchOutcome::ShortCircuit { outcomes } => {
+                assert_eq!(outcomes.len(), 1);
+                assert!(outcomes["c0"].is_error);
+                let ToolDetails::Text { body, .. } = &outcomes["c0"].details else {
+                    panic!("expected text details");
+                };
+                assert!(body.ends_with("Reason: wrong file"), "{body}");
+            }
+            BeforeToolBatchOutcome::Proceed => panic!("expected a denial"),
+        }
+    }
+
+    #[tokio::test]
+    async fn always_apply_stops_asking_about_those_files() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<EditApprovalRequest>();
+        let remembered = RememberedApprovals::default();
+        let hook = edit_approval_hook(
+            tx,
+            remembered.clone(),
+            ConfirmEdits::new(true),
+            EditProjection::default(),
+        );
+        let a = json!({"path": "/a.rs"});
+        let b = json!({"path": "/b.rs"});
+        let answer = tokio::spawn(async move {
+            let request = rx.recv().await.expect("request");
+            request.reply.send(EditDecision::ApplyAlways).unwrap();
+            rx
+        });
+        let outcome = hook(&[call("c0", "edit_file", &a)]).await;
+        let mut rx = answer.await.unwrap();
+        assert!(matches!(outcome, BeforeToolBatchOutcome::Proceed));
+        assert_eq!(remembered.list(), vec!["/a.rs".to_string()]);
+
+        let outcome = hook(&[call("c1", "write_file", &a)]).await;
+        assert!(matches!(outcome, BeforeToolBatchOutcome::Proceed));
+        assert!(rx.try_recv().is_err(), "a remembered file asked again");
+
+        // A batch that also touches an unremembered file still asks.
+        let pending = tokio::spawn(async move {
+            let request = rx.recv().await.expect("request");
+            assert_eq!(request.changes.len(), 2);
+        });
+        drop(hook(&[call("c2", "edit_file", &a), call("c3", "edit_file", &b)]).await);
+        pending.await.unwrap();
+
+        assert_eq!(remembered.clear(), 1);
+        assert!(remembered.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stash_push_asks_but_list_does_not() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<EditApprovalRequest>();
+        let remembered = RememberedApprovals::default();
+        let hook = edit_approval_hook(
+            tx,
+            remembered.clone(),
+            ConfirmEdits::new(true),
+            EditProjection::default(),
+        );
+        let list = json!({"action": "list"});
+        let outcome = hook(&[call("c0", "git_stash", &list)]).await;
+        assert!(matches!(outcome, BeforeToolBatchOutcome::Proceed));
+        assert!(rx.try_recv().is_err(), "a stash list asked");
+
+        let push = json!({"action": "push"});
+        let answer = tokio::spawn(async move {
+            let request = rx.recv().await.expect("request");
+            assert_eq!(request.changes[0].row, "stash push     <working tree>");
+            request.reply.send(EditDecision::ApplyAlways).unwrap();
+        });
+        let outcome = hook(&[call("c1", "git_stash", &push)]).await;
+        answer.await.unwrap();
+        assert!(matches!(outcome, BeforeToolBatchOutcome::Proceed));
+        assert!(remembered.list().is_empty(), "a stash was remembered");
+    }
+
+    #[tokio::test]
+    async fn switching_confirmation_off_stops_asking() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<EditApprovalRequest>();
+        let confirm = ConfirmEdits::new(false);
+        let hook = edit_approval_hook(
+            tx,
+            RememberedApprovals::default(),
+            confirm.clone(),
+            EditProjection::default(),
+        );
+        let edit = json!({"path": "/a.rs"});
+        let outcome = hook(&[call("c0", "edit_file", &edit)]).await;
+        assert!(matches!(outcome, BeforeToolBatchOutcome::Proceed));
+        assert!(rx.try_recv().is_err());
+
+        confirm.set(true);
+        let pending = tokio::spawn(async move { rx.recv().await.is_some() });
+        drop(hook(&[call("c1", "edit_file", &edit)]).await);
+        assert!(pending.await.unwrap(), "the switched-on hook didn't ask");
+    }
+
+    #[tokio::test]
+    async fn dropped_reply_counts_as_denial() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<EditApprovalRequest>();
+        let hook = edit_approval_hook(
+            tx,
+            RememberedApprovals::default(),
+            ConfirmEdits::new(true),
+            EditProjection::default(),
+        );
+        let edit = json!({"path": "/a.rs"});
+        let dropper = tokio::spawn(async move {
+            drop(rx.recv().await);
+        });
+        let outcome = hook(&[call("c0", "write_file", &edit)]).await;
+        dropper.await.unwrap();
+        assert!(matches!(
+            outcome,
+            BeforeToolBatchOutcome::ShortCircuit { .. }
+        ));
+    }
+}
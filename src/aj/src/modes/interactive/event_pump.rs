@@ -50,6 +50,7 @@ use crate::modes::interactive::components::loader_status::LoaderStatus;
 use crate::modes::interactive::components::pending_message::PendingMessage;
 use crate::modes::interactive::components::subagent_box::SubAgentStatus;
 use crate::modes::interactive::components::tool_execution::ToolExecutionComponent;
+use crate::modes::interactive::components::turn_separator::TurnSeparatorComponent;
 use crate::modes::interactive::components::user_message::UserMessageComponent;
 use crate::modes::interactive::footer_data::AgentFooters;
 use crate::modes::interactive::layout::SlotIndex;
@@ -73,6 +74,8 @@ struct AgentRender {
     /// Map of `tool_use_id` → index inside this agent's container of
     /// the matching [`ToolExecutionComponent`].
     tool_index: HashMap<String, usize>,
+    /// Typed prompts rendered so far, numbering the turn separators.
+    turns: usize,
 }
 
 /// One background task tracked from [`AgentEvent::TaskStart`] /
@@ -105,6 +108,13 @@ struct TaskInfo {
     cell: Option<usize>,
 }
 
+/// One in-flight tool call, for the loader's status line.
+struct RunningTool {
+    call_id: String,
+    tool: String,
+    started: Instant,
+}
+
 /// Translates [`AgentEvent`]s into TUI mutations.
 ///
 /// The pump owns no view state of its own — every component lives
@@ -154,6 +164,15 @@ pub struct EventPump {
     /// when it is in *either* set, which is how the spinner animates
     /// during the multi-second summarizer call.
     compacting: HashSet<AgentId>,
+    /// Tool calls per agent that emitted
+    /// [`AgentEvent::ToolExecutionStart`] without a matching
+    /// [`AgentEvent::ToolExecutionEnd`] yet. Feeds the loader's
+    /// "Running <tool>" status ([`Self::sync_loader`]) and lets the
+    /// binary's Ctrl+C tell a running tool from a streaming model
+    /// ([`Self::has_running_tools`]). The `agent` tool is left out:
+    /// its sub-agent box carries its own status. Cleared with the
+    /// agent's `AgentEnd`, so an interrupted batch can't leak entries.
+    running_tools: HashMap<AgentId, Vec<RunningTool>>,
     /// Background tasks observed via [`AgentEvent::TaskStart`],
     /// keyed by task id. Entries are kept (with their terminal
     /// status) after `TaskEnd` so the picker's "all" scope can list
@@ -192,6 +211,16 @@ pub struct EventPump {
     /// sequence on every call, so without this gate every sub-agent
     /// end would needlessly clear an already-off indicator.
     progress_active: bool,
+    /// Estimated size, in tokens, above which a finished tool result
+    /// gets a warning line under its cell (`tool_result_warn_tokens`).
+    /// `0` disables the warning.
+    tool_result_warn_tokens: u64,
+    /// Put a [`TurnSeparatorComponent`] above each typed prompt
+    /// (`turn_separator`).
+    turn_separator: bool,
+    /// Show the prompt's send time in the separator
+    /// (`turn_timestamps`).
+    turn_timestamps: bool,
 }
 
 impl EventPump {
@@ -222,15 +251,32 @@ impl EventPump {
             agents: HashMap::new(),
             running_agents: HashSet::new(),
             compacting: HashSet::new(),
+            running_tools: HashMap::new(),
             tasks: BTreeMap::new(),
             render_settings,
             footer_data: AgentFooters::new(main_settings, main_context_window),
             catalog,
             message_queues,
             progress_active: false,
+            tool_result_warn_tokens: 0,
+            turn_separator: false,
+            turn_timestamps: false,
         }
     }
 
+    /// Set the [`Self::tool_result_warn_tokens`] threshold (`0`
+    /// disables the large-result warning).
+    pub fn set_tool_result_warn_tokens(&mut self, tokens: u64) {
+        self.tool_result_warn_tokens = tokens;
+    }
+
+    /// Turn the per-prompt [`TurnSeparatorComponent`] on or off, and
+    /// whether it carries the prompt's send time.
+    pub fn set_turn_separator(&mut self, enabled: bool, timestamps: bool) {
+        self.turn_separator = enabled;
+        self.turn_timestamps = timestamps;
+    }
+
     /// Push the active view's footer state — model line and
     /// context usage — into the [`Footer`] component. Called after
     /// every mutation that affects the rendered state so the row
@@ -496,6 +542,11 @@ impl EventPump {
         self.running_agents.contains(&id)
     }
 
+    /// Whether `id` has a tool call (other than `agent`) in flight.
+    pub fn has_running_tools(&self, id: AgentId) -> bool {
+        self.running_tools.get(&id).is_some_and(|t| !t.is_empty())
+    }
+
     /// Snapshot of every agent currently in the running set. The
     /// binary iterates this to reconcile leaked nested sub-agents
     /// on main-turn completion; order is unspecified.
@@ -510,6 +561,7 @@ impl EventPump {
     /// drain a leaked sub-agent whose `AgentEnd` never arrived.
     pub fn mark_idle(&mut self, tui: &mut Tui, id: AgentId) {
         self.running_agents.remove(&id);
+        self.running_tools.remove(&id);
         if let AgentId::Sub(n) = id
             && let Some(chat) = tui.get_mut_as::<ChatView>(SlotIndex::Chat.idx())
             && let Some(b) = chat.sub_box_mut(n)
@@ -554,6 +606,7 @@ impl EventPump {
             }
             AgentEvent::AgentEnd { agent_id, .. } => {
                 self.running_agents.remove(agent_id);
+                self.running_tools.remove(agent_id);
                 // Each agent owns its streaming bookkeeping, so an
                 // agent's end clears only its own entry; the main
                 // agent's pending `agent` tool call (whose body is a
@@ -614,6 +667,15 @@ impl EventPump {
             } => {
                 if tool != "agent" {
                     self.append_tool_execution(tui, *agent_id, call_id, tool, args);
+                    self.running_tools
+                        .entry(*agent_id)
+                        .or_default()
+                        .push(RunningTool {
+                            call_id: call_id.clone(),
+                            tool: tool.clone(),
+                            started: Instant::now(),
+                        });
+                    self.sync_loader(tui);
                 }
             }
             AgentEvent::ToolExecutionUpdate {
@@ -640,6 +702,15 @@ impl EventPump {
                     self.update_tool_execution_result(
                         tui, *agent_id, call_id, tool, result, content, *is_error,
                     );
+                    if let Some(warning) =
+                        large_tool_result_warning(tool, content, self.tool_result_warn_tokens)
+                    {
+                        self.append_styled_notice(tui, *agent_id, &warning, aj_tui::style::yellow);
+                    }
+                    if let Some(running) = self.running_tools.get_mut(agent_id) {
+                        running.retain(|t| t.call_id != *call_id);
+                    }
+                    self.sync_loader(tui);
                 }
             }
 
@@ -876,14 +947,31 @@ impl EventPump {
     /// activity: active iff the active view's agent is in
     /// `running_agents`. Only toggles on a genuine edge —
     /// `Loader::start` resets the frame clock, so calling it on
-    /// every event would jitter the animation.
+    /// every event would jitter the animation. Also hands the loader
+    /// the viewed agent's running tools (name, or a count when several
+    /// run at once, and the oldest start time) for its status line.
     fn sync_loader(&self, tui: &mut Tui) {
         let active = self.active_view(tui);
         let should_run = self.running_agents.contains(&active) || self.compacting.contains(&active);
-        self.with_loader(tui, |l| match (should_run, l.is_active()) {
-            (true, false) => l.start(),
-            (false, true) => l.stop(),
-            _ => {}
+        let tool_status = self
+            .running_tools
+            .get(&active)
+            .and_then(|running| match &running[..] {
+                [] => None,
+                [only] => Some((only.tool.clone(), only.started)),
+                several => several
+                    .iter()
+                    .map(|t| t.started)
+                    .min()
+                    .map(|started| (format!("{} tools", several.len()), started)),
+            });
+        self.with_loader(tui, |l| {
+            match (should_run, l.is_active()) {
+                (true, false) => l.start(),
+                (false, true) => l.stop(),
+                _ => {}
+            }
+            l.set_tool_status(tool_status);
         });
     }
 
@@ -927,8 +1015,17 @@ impl EventPump {
     /// event) and the resume path (via the same event synthesized
     /// by `aj_session::replay`). Multiple [`UserContent::Text`]
     /// blocks are joined with `\n` so legacy multi-block user
-    /// messages collapse into one rendered component.
-    fn append_user_message(&self, tui: &mut Tui, agent_id: AgentId, content: &[UserContent]) {
+    /// messages collapse into one rendered component. With
+    /// `turn_separator` on, a typed prompt is preceded by a rule
+    /// numbering the turn; `timestamp` (Unix ms) is the send time it
+    /// shows with `turn_timestamps`.
+    fn append_user_message(
+        &mut self,
+        tui: &mut Tui,
+        agent_id: AgentId,
+        content: &[UserContent],
+        timestamp: i64,
+    ) {
         let text = content
             .iter()
             .filter_map(|b| match b {
@@ -954,6 +1051,25 @@ impl EventPump {
                     self.render_settings.clone(),
                 ))
             } else {
+                if self.turn_separator {
+                    let render = self.agents.entry(agent_id).or_default();
+                    render.turns += 1;
+                    let turn = render.turns;
+                    let time = self
+                        .turn_timestamps
+                        .then(|| chrono::DateTime::from_timestamp_millis(timestamp))
+                        .flatten()
+                        .map(|t| {
+                            t.with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M")
+                                .to_string()
+                        });
+                    self.push_chat_child(
+                        tui,
+                        agent_id,
+                        Box::new(TurnSeparatorComponent::new(turn, time.as_deref())),
+                    );
+                }
                 Box::new(UserMessageComponent::new(&text, &self.theme))
             };
         self.push_chat_child(tui, agent_id, component);
@@ -1065,7 +1181,7 @@ impl EventPump {
     fn handle_message_end(&mut self, tui: &mut Tui, agent_id: AgentId, message: &AgentMessage) {
         match &message.kind {
             AgentMessageKind::Wire(Message::User(u)) => {
-                self.append_user_message(tui, agent_id, &u.content);
+                self.append_user_message(tui, agent_id, &u.content, u.timestamp);
             }
             AgentMessageKind::Wire(Message::Assistant(a)) => {
                 // Two cases share this arm:
@@ -1446,6 +1562,26 @@ fn format_turn_usage_line(agent_id: AgentId, usage: &TokenUsage) -> String {
     }
 }
 
+/// The warning line for a tool result whose estimated size exceeds
+/// `threshold` tokens, or `None` when it doesn't (or `threshold` is
+/// `0`). Uses the compaction heuristic, so the figure is approximate.
+fn large_tool_result_warning(
+    tool: &str,
+    content: &[UserContent],
+    threshold: u64,
+) -> Option<String> {
+    if threshold == 0 {
+        return None;
+    }
+    let tokens = aj_session::compaction::estimate_content_tokens(content);
+    (tokens > threshold).then(|| {
+        format!(
+            "tool_result from {tool} is ~{} tokens",
+            crate::modes::interactive::components::footer::format_tokens(tokens)
+        )
+    })
+}
+
 /// Pull and clear the editor's submitted text. Returns `Some` at
 /// most once per editor submission; the host's main loop calls
 /// this after every input event so a freshly-submitted prompt
@@ -1571,6 +1707,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn large_tool_result_warning_fires_above_the_threshold() {
+        let content = [UserContent::text("x".repeat(32_800))];
+        assert_eq!(
+            large_tool_result_warning("grep", &content, 5_000).as_deref(),
+            Some("tool_result from grep is ~8.2k tokens"),
+        );
+        assert_eq!(large_tool_result_warning("grep", &content, 10_000), None);
+        // `0` turns the warning off.
+        assert_eq!(large_tool_result_warning("grep", &content, 0), None);
+    }
+
     #[test]
     fn format_turn_usage_line_prefixes_sub_agent_id() {
         // Sub-agents share the parent's bus, so their per-turn
@@ -2453,6 +2601,29 @@ mod tests {
             .len()
     }
 
+    #[test]
+    fn turn_separator_numbers_each_typed_prompt() {
+        let (mut tui, mut pump, _theme) = fresh_tui_with_layout();
+        pump.handle(&mut tui, &user_message_end_event("no rule yet"));
+        let before = chat_child_count(&mut tui);
+
+        pump.set_turn_separator(true, false);
+        pump.handle(&mut tui, &user_message_end_event("first"));
+        pump.handle(&mut tui, &user_message_end_event("second"));
+        // Each prompt adds a spacer, the rule, a spacer and the message.
+        assert_eq!(chat_child_count(&mut tui), before + 8);
+
+        let chat = tui
+            .get_mut_as::<ChatView>(SlotIndex::Chat.idx())
+            .expect("chat slot")
+            .container_mut();
+        let rule = chat
+            .get_mut_as::<TurnSeparatorComponent>(before + 5)
+            .expect("second rule");
+        let line = aj_tui::ansi::strip_ansi(rule.render(40)[0].as_str());
+        assert!(line.starts_with("── turn 2 ─"), "{line}");
+    }
+
     /// Build an `AgentEvent::MessageEnd` for an assistant turn that
     /// terminated with `error` and `stop`.
     fn errored_assistant_message_end(
@@ -0,0 +1,264 @@
+//! Front-end side of the `open_in_editor` tool.
+//!
+//! The builtin tool is a no-op (see
+//! [`aj_tools::tools::open_in_editor`]); the interactive session's main
+//! agent carries the [`aj_agent::hooks::BeforeToolCallHook`] built by
+//! [`open_in_editor_hook`], which short-circuits each `open_in_editor`
+//! call and launches the user's editor instead. The command is
+//! `editor_command` from the config; there is no `$VISUAL` / `$EDITOR`
+//! fallback, since a terminal editor can't share the terminal with the
+//! TUI. It runs through `sh -c` detached from the terminal with the
+//! path and line passed as positional parameters, so neither is ever
+//! interpolated into the script. Sub-agents don't get the hook and
+//! keep the no-op.
+
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+use aj_agent::hooks::{BeforeToolCallHook, BeforeToolCallOutcome, ToolCallContext};
+use aj_agent::tool::{ToolDetails, ToolOutcome};
+use aj_models::types::UserContent;
+use aj_tools::tools::open_in_editor::OpenInEditorInput;
+use serde_json::Value;
+
+/// How long to wait for the editor command before leaving it running
+/// in the background. Launchers (`code -g`, `tmux new-window`) exit
+/// well within this, so a failing command is still reported to the
+/// model.
+const LAUNCH_GRACE: Duration = Duration::from_millis(500);
+
+/// Build the tool-call hook that opens `open_in_editor` targets with
+/// `editor_command` (the config value; `None` answers each call with
+/// an error). Other tools proceed untouched.
+pub fn open_in_editor_hook(editor_command: Option<String>) -> BeforeToolCallHook {
+    Arc::new(move |ctx: ToolCallContext<'_>, args: Value| {
+        let editor_command = editor_command.clone();
+        Box::pin(async move {
+            if ctx.tool_name != "open_in_editor" {
+                return BeforeToolCallOutcome::Proceed { args };
+            }
+            // Malformed arguments fall through so the agent reports the
+            // usual input error.
+            let Ok(input) = serde_json::from_value::<OpenInEditorInput>(args.clone()) else {
+                return BeforeToolCallOutcome::Proceed { args };
+            };
+            let outcome = open(editor_command.as_deref(), &input).await;
+            BeforeToolCallOutcome::ShortCircuit { outcome }
+        })
+    })
+}
+
+/// The `sh -c` script that opens `$1` at line `$2`: `editor_command`
+/// with its placeholders replaced. Blank commands count as unset.
+fn editor_script(editor_command: Option<&str>) -> Option<String> {
+    editor_command
+        .filter(|command| !command.trim().is_empty())
+        .map(|command| {
+            command
+                .replace("{path}", "\"$1\"")
+                .replace("{line}", "\"$2\"")
+        })
+}
+
+async fn open(editor_command: Option<&str>, input: &OpenInEditorInput) -> ToolOutcome {
+    let line = input.line.unwrap_or(1).max(1);
+    let location = match input.line {
+        Some(_) => format!("{}:{line}", input.path),
+        None => input.path.clone(),
+    };
+    let path = Path::new(&input.path);
+    if !path.is_absolute() {
+        return outcome(
+            location,
+            format!("Path must be absolute, got: {}", input.path),
+            true,
+        );
+    }
+    if !path.is_file() {
+        return outcome(location, format!("No such file: {}", input.path), true);
+    }
+    let Some(script) = editor_script(editor_command) else {
+        return outcome(
+            location,
+            "No editor configured: the user hasn't set editor_command in ~/.aj/config.toml. \
+             Nothing was opened; cite the path and line in your reply instead."
+                .to_string(),
+            true,
+        );
+    };
+    match launch(&script, &input.path, line).await {
+        Ok(None) => outcome(
+            location.clone(),
+            format!("Opened {location} in the user's editor."),
+            false,
+        ),
+        Ok(Some(status)) => outcome(
+            location,
+            format!("The editor command failed ({status}); nothing was opened."),
+            true,
+        ),
+        Err(e) => outcome(
+            location,
+            format!("Could not run the editor command: {e}"),
+            true,
+        ),
+    }
+}
+
+/// Spawn `script` detached and give it [`LAUNCH_GRACE`] to fail.
+/// Returns the exit status of a command that failed within the grace
+/// period; one still running is reaped in the background.
+async fn launch(script: &str, path: &str, line: usize) -> std::io::Result<Option<ExitStatus>> {
+    let mut child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(script)
+        .arg("aj")
+        .arg(path)
+        .arg(line.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    match tokio::time::timeout(LAUNCH_GRACE, child.wait()).await {
+        Ok(status) => {
+            let status = status?;
+            Ok((!status.success()).then_some(status))
+        }
+        Err(_) => {
+            tokio::spawn(async move {
+                let _ = child.wait().await;
+            });
+            Ok(None)
+        }
+    }
+}
+
+fn outcome(summary: String, text: String, is_error: bool) -> ToolOutcome {
+    ToolOutcome {
+        content: vec![UserContent::text(text.clone())],
+        details: ToolDetails::Text {
+            summary,
+            body: text,
+        },
+        is_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn ctx(tool: &str) -> ToolCallContext<'_> {
+        ToolCallContext {
+            call_id: "tu_1",
+            tool_name: tool,
+        }
+    }
+
+    fn text(outcome: &ToolOutcome) -> String {
+        match &outcome.content[..] {
+            [UserContent::Text(t)] => t.text.clone(),
+            other => panic!("expected one text block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn editor_script_fills_in_the_configured_command() {
+        assert_eq!(
+            editor_script(Some("code -g {path}:{line}")).as_deref(),
+            Some("code -g \"$1\":\"$2\"")
+        );
+        assert_eq!(editor_script(Some("  ")), None);
+        assert_eq!(editor_script(None), None);
+    }
+
+    #[tokio::test]
+    async fn hook_leaves_other_tools_alone() {
+        let hook = open_in_editor_hook(Some("false".to_string()));
+        let args = json!({"path": "/tmp/a.rs"});
+        match hook(ctx("read_file"), args.clone()).await {
+            BeforeToolCallOutcome::Proceed { args: got } => assert_eq!(got, args),
+            BeforeToolCallOutcome::ShortCircuit { .. } => panic!("expected Proceed"),
+        }
+    }
+
+    #[tokio::test]
+    async fn hook_runs_the_editor_command_with_path_and_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("it's a file.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+        let out = dir.path().join("out");
+        let command = format!("printf '%s:%s' {{path}} {{line}} > '{}'", out.display());
+        let hook = open_in_editor_hook(Some(command));
+
+        let args = json!({"path": file.display().to_string(), "line": 7});
+        let BeforeToolCallOutcome::ShortCircuit { outcome } =
+            hook(ctx("open_in_editor"), args).await
+        else {
+            panic!("expected ShortCircuit");
+        };
+        assert!(!outcome.is_error, "{}", text(&outcome));
+        assert!(text(&outcome).starts_with("Opened "));
+        // The path reaches the command verbatim, quote and space included.
+        let mut written = String::new();
+        for _ in 0..50 {
+            written = std::fs::read_to_string(&out).unwrap_or_default();
+            if !written.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(written, format!("{}:7", file.display()));
+    }
+
+    #[tokio::test]
+    async fn hook_reports_missing_files_and_failing_commands() {
+        let hook = open_in_editor_hook(Some("exit 3".to_string()));
+        let BeforeToolCallOutcome::ShortCircuit { outcome } = hook(
+            ctx("open_in_editor"),
+            json!({"path": "/no/such/file.rs", "line": 1}),
+        )
+        .await
+        else {
+            panic!("expected ShortCircuit");
+        };
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("No such file"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "").unwrap();
+        let BeforeToolCallOutcome::ShortCircuit { outcome } = hook(
+            ctx("open_in_editor"),
+            json!({"path": file.display().to_string()}),
+        )
+        .await
+        else {
+            panic!("expected ShortCircuit");
+        };
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("editor command failed"));
+    }
+
+    #[tokio::test]
+    async fn hook_without_an_editor_command_opens_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "").unwrap();
+        let hook = open_in_editor_hook(None);
+        let BeforeToolCallOutcome::ShortCircuit { outcome } = hook(
+            ctx("open_in_editor"),
+            json!({"path": file.display().to_string(), "line": 2}),
+        )
+        .await
+        else {
+            panic!("expected ShortCircuit");
+        };
+        assert!(outcome.is_error);
+        assert!(text(&outcome).contains("No editor configured"));
+    }
+}
@@ -3,7 +3,8 @@
 //! The interactive transcript has a handful of global "how should
 //! everything render" toggles — whether tool bodies show in full or
 //! compact form, whether assistant thinking blocks are folded to a
-//! placeholder, and whether tool image attachments render inline.
+//! placeholder, whether tool image attachments render inline, and
+//! whether each tool call shows its full JSON input.
 //! Every [`AssistantMessageComponent`] and [`ToolExecutionComponent`]
 //! in the transcript (and inside every sub-agent box) has to honour
 //! the same values.
@@ -48,6 +49,10 @@ struct Inner {
     /// has no runtime toggle today, but is session-wide so it lives
     /// here alongside the others.
     show_image_in_terminal: Cell<bool>,
+    /// Show each tool call's full, pretty-printed JSON input between
+    /// its header and its result. Off unless `show_tool_input` or
+    /// `--verbose` turns it on (see [`RenderSettings::set_show_tool_input`]).
+    show_tool_input: Cell<bool>,
     /// Bumped on every value change. Components compare it against
     /// the generation they last reconciled to decide whether to
     /// rebuild their derived caches.
@@ -68,6 +73,7 @@ impl RenderSettings {
             tools_expanded: Cell::new(tools_expanded),
             hide_thinking_block: Cell::new(hide_thinking_block),
             show_image_in_terminal: Cell::new(show_image_in_terminal),
+            show_tool_input: Cell::new(false),
             generation: Cell::new(0),
         }))
     }
@@ -91,6 +97,10 @@ impl RenderSettings {
         self.0.show_image_in_terminal.get()
     }
 
+    pub fn show_tool_input(&self) -> bool {
+        self.0.show_tool_input.get()
+    }
+
     pub fn set_tools_expanded(&self, expanded: bool) {
         self.set(&self.0.tools_expanded, expanded);
     }
@@ -103,6 +113,10 @@ impl RenderSettings {
         self.set(&self.0.show_image_in_terminal, show);
     }
 
+    pub fn set_show_tool_input(&self, show: bool) {
+        self.set(&self.0.show_tool_input, show);
+    }
+
     /// Write `value` into `cell`, bumping the generation only on an
     /// actual change so a redundant toggle doesn't make every
     /// component re-reconcile.
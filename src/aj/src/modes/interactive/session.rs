@@ -12,8 +12,10 @@
 use std::sync::Arc;
 
 use aj_agent::bus::SubscriptionHandle;
+use aj_agent::changes::ChangeLog;
 use aj_agent::events::{AgentEvent, AgentId};
 use aj_agent::queue::MessageQueues;
+use aj_agent::tool::ToolInterrupt;
 use aj_agent::types::UsageSummary;
 use aj_agent::{Agent, SubAgentRegistry, TaskRegistry};
 use aj_conf::{AgentEnv, Config};
@@ -32,8 +34,12 @@ use crate::modes::interactive::SubAgentOverrides;
 use crate::modes::interactive::apply_editor_agent_marker;
 use crate::modes::interactive::components::chat_view::ChatView;
 use crate::modes::interactive::components::header::Header;
+use crate::modes::interactive::edit_approval::{
+    ConfirmEdits, EditApprovalRequest, EditProjection, RememberedApprovals, edit_approval_hook,
+};
 use crate::modes::interactive::event_pump::EventPump;
 use crate::modes::interactive::layout::SlotIndex;
+use crate::modes::interactive::open_in_editor::open_in_editor_hook;
 use crate::modes::interactive::render_settings::RenderSettings;
 use crate::modes::interactive::shutdown::build_usage_summary;
 use crate::session_setup::{
@@ -66,15 +72,19 @@ const APP_TITLE: &str = "AJ";
 
 /// The header notice announced when a world is installed. The
 /// wording depends on both how the log was obtained (create vs.
-/// resume) and whether this is the process's first session.
-fn header_notice(spec: &SessionSpec) -> String {
+/// resume) and whether this is the process's first session. A fresh
+/// startup session shows `greeting` (the config value) when set.
+fn header_notice(spec: &SessionSpec, greeting: Option<&str>) -> String {
     match spec {
         SessionSpec::Create {
             entry: SessionEntry::Startup,
-        } => format!(
-            "Chat with {APP_TITLE} — {} to quit",
-            crate::config::keybindings::fixed_keys::CTRL_C
-        ),
+        } => match greeting {
+            Some(greeting) => greeting.to_string(),
+            None => format!(
+                "Chat with {APP_TITLE} — {} to quit",
+                crate::config::keybindings::fixed_keys::CTRL_C
+            ),
+        },
         SessionSpec::Create {
             entry: SessionEntry::Switch,
         } => "Fresh session".to_string(),
@@ -126,6 +136,40 @@ pub struct SessionWorld {
     /// runs with whatever it already holds (spawn-time inheritance).
     pub(crate) sub_overrides:
         Arc<std::sync::Mutex<std::collections::HashMap<usize, SubAgentOverrides>>>,
+    /// Transient system context staged by the `focus` command for the
+    /// given agent's next prompted turn. The turn task hands it to
+    /// `Agent::set_turn_system_addendum`, which scopes it to that one
+    /// run; per-world, so a session switch drops it.
+    pub(crate) turn_context: Arc<std::sync::Mutex<Option<(AgentId, String)>>>,
+    /// Names of the tools `agent` was built with, captured at build
+    /// so the `tools` window can list them without locking the agent
+    /// mid-turn.
+    pub(crate) tool_names: Vec<String>,
+    /// Tools the user switched off with the `tools` command. The turn
+    /// task re-applies the set to whichever agent it drives before
+    /// every inference, so a toggle lands at the next inference
+    /// even mid-turn; per-world, so a session switch turns every tool
+    /// back on.
+    pub(crate) disabled_tools: Arc<std::sync::Mutex<std::collections::BTreeSet<String>>>,
+    /// Edit batches the main agent is waiting on the user to approve,
+    /// raised by its batch hook while `confirm_edits` is on; `None`
+    /// once the hook's sender is gone. The main loop opens the approval
+    /// overlay for each request.
+    pub(crate) edit_approvals: Option<UnboundedReceiver<EditApprovalRequest>>,
+    /// Whether the batch hook asks at all. Starts from
+    /// `confirm_edit_batches`; `/permissions` flips it for this world.
+    pub(crate) confirm_edits: ConfirmEdits,
+    /// Files the user chose "Always apply" for in the approval
+    /// overlay; the batch hook skips asking about them. Per-world, so
+    /// a session switch starts asking again.
+    pub(crate) remembered_approvals: RememberedApprovals,
+    /// Handle injected into `agent` that stops just its in-flight tool
+    /// calls. Ctrl+C fires it while a tool runs so the turn carries on
+    /// with an "interrupted" result instead of being cancelled whole.
+    pub(crate) tool_interrupt: ToolInterrupt,
+    /// The `greeting` config value, shown in the header of the
+    /// process's first fresh session in place of the default.
+    pub(crate) greeting: Option<String>,
     /// The session's on-disk conversation log, shared with the
     /// persistence listener.
     pub log: Arc<TokioMutex<ConversationLog>>,
@@ -251,6 +295,35 @@ impl SessionWorld {
         let message_queues = MessageQueues::default();
         agent.set_message_queues(message_queues.clone());
 
+        // Stops only the running tool calls; the main loop's Ctrl+C
+        // reaches for it before cancelling the whole turn.
+        let tool_interrupt = ToolInterrupt::new();
+        agent.set_tool_interrupt(tool_interrupt.clone());
+
+        // Opt-in review of file-edit batches: the hook parks the turn
+        // on a oneshot the main loop answers from the approval
+        // overlay. Installed even when off, since `/permissions` can
+        // switch it on mid-session.
+        let remembered_approvals = RememberedApprovals::default();
+        let confirm_edits = ConfirmEdits::new(config.confirm_edit_batches);
+        let (approval_tx, approval_rx) = tokio::sync::mpsc::unbounded_channel();
+        agent.set_before_tool_batch(Some(edit_approval_hook(
+            approval_tx,
+            remembered_approvals.clone(),
+            confirm_edits.clone(),
+            EditProjection {
+                max_file_size: config.max_file_size,
+                normalize_on_write: config.normalize_on_write,
+                respect_editorconfig: config.respect_editorconfig,
+            },
+        )));
+        let edit_approvals = Some(approval_rx);
+
+        // `open_in_editor` only does something here, where a user sits
+        // at the terminal; the hook launches their editor in place of
+        // the builtin's no-op.
+        agent.set_before_tool_call(Some(open_in_editor_hook(config.editor_command.clone())));
+
         // Bus subscriptions: the channel forwarder feeds the pump in
         // the main loop; the persistence listener writes events into
         // the log. Seeding never emits bus events, so subscription
@@ -268,7 +341,7 @@ impl SessionWorld {
             verbosity: verbosity_name(verbosity).to_string(),
         };
         let context_window = agent.model_info().context_window;
-        let pump = EventPump::new(
+        let mut pump = EventPump::new(
             chat_theme(theme, config.syntax_highlighting),
             render_settings.clone(),
             main_settings,
@@ -276,6 +349,10 @@ impl SessionWorld {
             catalog,
             message_queues.clone(),
         );
+        pump.set_tool_result_warn_tokens(config.tool_result_warn_tokens);
+        pump.set_turn_separator(config.turn_separator, config.turn_timestamps);
+
+        let tool_names = agent.tool_names();
 
         Ok(SessionWorld {
             agent: Arc::new(TokioMutex::new(agent)),
@@ -284,6 +361,14 @@ impl SessionWorld {
             task_registry,
             message_queues,
             sub_overrides: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            turn_context: Arc::new(std::sync::Mutex::new(None)),
+            tool_names,
+            disabled_tools: Arc::new(std::sync::Mutex::new(std::collections::BTreeSet::new())),
+            edit_approvals,
+            confirm_edits,
+            remembered_approvals,
+            tool_interrupt,
+            greeting: config.greeting.clone(),
             log,
             session_id,
             pump,
@@ -322,7 +407,7 @@ impl SessionWorld {
         }
         if let Some(header) = tui.get_mut_as::<Header>(SlotIndex::Header.idx()) {
             header.set_session_id(Some(self.session_id.clone()));
-            header.set_notice(Some(header_notice(spec)));
+            header.set_notice(Some(header_notice(spec, self.greeting.as_deref())));
         }
         tui.terminal_mut().set_title(&self.window_title());
         tui.request_render();
@@ -396,6 +481,14 @@ impl SessionWorld {
         let agent = self.agent.lock().await;
         build_usage_summary(&agent)
     }
+
+    /// Snapshot the files this world's agent (and its sub-agents)
+    /// changed and the `bash` commands they ran, for `/summary` and
+    /// the shutdown banner. Locks the agent, so call only while no
+    /// turn is in flight.
+    pub async fn change_log(&self) -> ChangeLog {
+        self.agent.lock().await.change_log()
+    }
 }
 
 #[cfg(test)]
@@ -430,29 +523,50 @@ mod tests {
     #[test]
     fn header_notice_picks_wording_per_entry_and_kind() {
         assert_eq!(
-            header_notice(&SessionSpec::Create {
-                entry: SessionEntry::Startup
-            }),
+            header_notice(
+                &SessionSpec::Create {
+                    entry: SessionEntry::Startup
+                },
+                None
+            ),
             "Chat with AJ — Ctrl+C to quit"
         );
         assert_eq!(
-            header_notice(&SessionSpec::Resume {
-                session_id: "s".to_string(),
-                entry: SessionEntry::Startup
-            }),
+            header_notice(
+                &SessionSpec::Create {
+                    entry: SessionEntry::Startup
+                },
+                Some("Ready when you are")
+            ),
+            "Ready when you are"
+        );
+        assert_eq!(
+            header_notice(
+                &SessionSpec::Resume {
+                    session_id: "s".to_string(),
+                    entry: SessionEntry::Startup
+                },
+                Some("ignored")
+            ),
             "Resuming conversation"
         );
         assert_eq!(
-            header_notice(&SessionSpec::Resume {
-                session_id: "s".to_string(),
-                entry: SessionEntry::Switch
-            }),
+            header_notice(
+                &SessionSpec::Resume {
+                    session_id: "s".to_string(),
+                    entry: SessionEntry::Switch
+                },
+                Some("ignored")
+            ),
             "Resumed conversation"
         );
         assert_eq!(
-            header_notice(&SessionSpec::Create {
-                entry: SessionEntry::Switch
-            }),
+            header_notice(
+                &SessionSpec::Create {
+                    entry: SessionEntry::Switch
+                },
+                Some("ignored")
+            ),
             "Fresh session"
         );
     }
@@ -992,6 +1106,7 @@ mod tests {
                 speed: None,
                 model_key: ("anthropic".to_string(), "claude-x".to_string()),
                 session_id: None,
+                fallback_models: Vec::new(),
             }))
         };
 
@@ -1,5 +1,5 @@
-//! End-of-session shutdown banner: token usage summary plus
-//! resume hint.
+//! End-of-session shutdown banner: token usage summary, the files
+//! the session changed, plus resume hint.
 //!
 //! Prints a per-agent token-usage breakdown (main + each
 //! sub-agent + grand total) followed by a `Session: <id> (resume
@@ -16,8 +16,10 @@
 use std::collections::HashMap;
 
 use aj_agent::Agent;
+use aj_agent::changes::ChangeLog;
 use aj_agent::types::{SubAgentUsage, UsageSummary};
 use aj_models::types::Usage;
+use aj_tools::sanitize_terminal_output;
 use aj_tui::style;
 
 /// Compute the structured end-of-session token-usage summary from
@@ -184,6 +186,42 @@ fn print_usage_block(header: Option<&str>, summary: &UsageSummary) {
     println!();
 }
 
+/// Format a session's [`ChangeLog`]: a `Files changed:` header with
+/// one indented `<path>: <what happened>` row per file, then the
+/// number of `bash` commands run. Shared by the `/summary` notice and
+/// the shutdown banner. No trailing newline.
+pub fn format_change_summary(log: &ChangeLog) -> String {
+    let mut out = String::new();
+    let files = log.by_file();
+    if files.is_empty() {
+        out.push_str("No files changed.");
+    } else {
+        out.push_str("Files changed:");
+        for file in &files {
+            out.push_str(&format!(
+                "\n  {}: {}",
+                sanitize_terminal_output(&file.path),
+                file.describe()
+            ));
+        }
+    }
+    out.push_str(&format!("\nBash commands run: {}", log.bash_commands));
+    out
+}
+
+/// Print the session's change summary, dimmed and indented like the
+/// usage block above it, followed by a blank row. Prints nothing when
+/// the session neither wrote a file nor ran a command.
+pub fn print_change_summary(log: &ChangeLog) {
+    if log.is_empty() {
+        return;
+    }
+    for line in format_change_summary(log).lines() {
+        println!(" {}", style::dim(line));
+    }
+    println!();
+}
+
 /// Print the resume hint to stdout, dimmed and indented. Called
 /// only when the session has at least one persisted user message
 /// (otherwise the hint points at an effectively-empty session and
@@ -321,6 +359,36 @@ mod tests {
         assert_eq!(format_usage_summary(&summary), expected);
     }
 
+    #[test]
+    fn format_change_summary_lists_files_then_bash_count() {
+        use aj_agent::changes::{FileChange, FileOperation};
+
+        let change = |path: &str, operation, lines_before, lines_after| FileChange {
+            path: path.to_string(),
+            operation,
+            tool: "edit_file".to_string(),
+            lines_before,
+            lines_after,
+        };
+        let log = ChangeLog {
+            files: vec![
+                change("src/new.rs", FileOperation::Created, 0, 12),
+                change("src/lib.rs", FileOperation::Edited, 100, 104),
+                change("src/lib.rs", FileOperation::Edited, 104, 110),
+            ],
+            bash_commands: 3,
+        };
+        assert_eq!(
+            format_change_summary(&log),
+            "Files changed:\n  src/new.rs: created (12 lines)\n  \
+             src/lib.rs: edited 2 times (100 -> 110 lines)\nBash commands run: 3"
+        );
+        assert_eq!(
+            format_change_summary(&ChangeLog::default()),
+            "No files changed.\nBash commands run: 0"
+        );
+    }
+
     #[test]
     fn format_session_usage_header_round_trips_session_id() {
         assert_eq!(format_session_usage_header("abc123"), "Session: abc123");
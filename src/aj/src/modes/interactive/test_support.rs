@@ -106,6 +106,7 @@ pub(crate) fn scripted_run_config(
         speed: None,
         model_key: ("scripted".to_string(), "scripted".to_string()),
         session_id: None,
+        fallback_models: Vec::new(),
     }))
 }
 
@@ -129,6 +130,7 @@ pub(crate) fn scripted_run_config_with_window(
         speed: None,
         model_key: ("scripted".to_string(), "scripted".to_string()),
         session_id: None,
+        fallback_models: Vec::new(),
     }))
 }
 
@@ -6,7 +6,7 @@
 //! `Agent::prompt` returns. Same code path lets callers script the
 //! agent or embed it in a parent process.
 //!
-//! Two output formats:
+//! Four output formats:
 //!
 //! - **Text** ([`PrintFormat::Text`]). Best-effort human-readable
 //!   output. Once the prompt completes, the last assistant message's
@@ -23,6 +23,23 @@
 //!   can rely on stable discriminator keys (`"type"`, `"kind"`) and
 //!   `snake_case` variant names. Persistence runs alongside the JSONL
 //!   writer; both observe the same event sequence.
+//! - **NDJSON** ([`PrintFormat::Ndjson`]). A compact digest of the
+//!   same event sequence: one object per line with an `"event"`
+//!   discriminator — `assistant_text`, `thinking`, `tool_use`,
+//!   `tool_result` (with `is_error`) and `usage` — and an `"agent"`
+//!   field in the [`AgentId`](aj_agent::events::AgentId) shape. Other
+//!   events are dropped. Each line is flushed as it is written, so a
+//!   consumer can process a long tool-heavy run incrementally.
+//! - **Raw JSON** ([`PrintFormat::RawJson`]). One line per finalized
+//!   message, user prompts and tool results included:
+//!   `{"agent": <AgentId>, "message": <Message>}`, where `message` is
+//!   the [`aj_models::types::Message`] exactly as it is persisted and
+//!   sent to providers (`"role"` discriminator, content blocks with
+//!   their own `"type"`, and on assistant messages the provider,
+//!   model, `usage` and `stop_reason`). No streaming or tool progress
+//!   events. The shape is the session file's message shape, so it
+//!   only changes in backwards-compatible ways (new optional fields,
+//!   new block types) that existing session files must survive too.
 //!
 //! Print mode opens (or for `continue`, resumes) a
 //! [`ConversationLog`](aj_session::ConversationLog) the same way
@@ -33,9 +50,9 @@
 //! session id), the resume flow does the same disk handshake as the
 //! interactive resume: open the session, reuse the persisted system
 //! prompt, repair any interrupted tool calls, then seed the agent's
-//! in-memory transcript from the linearized user thread. In JSON
-//! output mode the persisted history is streamed through the JSONL
-//! sink in [`aj_session::replay`] order *before* the new prompt
+//! in-memory transcript from the linearized user thread. In the JSON
+//! and NDJSON output modes the persisted history is streamed through
+//! the sink in [`aj_session::replay`] order *before* the new prompt
 //! runs, so consumers see the full event trace (historical and
 //! live) in emit order; in text mode the historical events are
 //! suppressed and only the new assistant turn's visible text is
@@ -57,7 +74,7 @@ use aj_agent::events::AgentEvent;
 use aj_agent::{Agent, TaskRegistry, TurnError};
 use aj_conf::{Config, ConfigSpeed, Severity};
 use aj_models::auth::AuthStorage;
-use aj_models::types::Speed;
+use aj_models::types::{Speed, UserContent};
 use aj_session::{ConversationPersistence, ThreadFilter, persistence_listener, replay};
 use anyhow::{Context, Result, anyhow, bail};
 use tokio::sync::Mutex as TokioMutex;
@@ -184,6 +201,10 @@ async fn run_inner<W: Write + Send + 'static>(
         }
         input.into_content()
     };
+    let preload = match &args.preload {
+        Some(path) => crate::cli::preload::load_preload(path, &cwd)?,
+        None => Vec::new(),
+    };
 
     // Speed selection follows the same precedence as the model:
     // CLI flag > config.toml > default. `--speed` is parsed here; the
@@ -258,20 +279,19 @@ async fn run_inner<W: Write + Send + 'static>(
         eprintln!("aj: {notice}");
     }
 
-    // JSON mode: replay the persisted history through the JSON sink
-    // **before** subscribing any listeners, so the consumer sees the
+    // JSON / NDJSON mode: replay the persisted history through the
+    // sink **before** subscribing any listeners, so the consumer sees the
     // full historical trace in emit order without double-firing the
     // persistence listener (events already on disk would otherwise be
     // re-written). Text mode skips the historical events: callers
     // piping the binary's stdout into another process want a clean
     // final answer, not the prior conversation re-stamped.
     if matches!(source, SessionSource::Resume { .. })
-        && matches!(args.format, PrintFormat::Json)
         && log.latest_leaf(ThreadFilter::USER).is_some()
+        && let Some(sink) = structured_listener(args.format, &out)
     {
-        let json = json_event_listener(Arc::clone(&out));
         for event in replay(&log) {
-            json(&event).await.map_err(|e| {
+            sink(&event).await.map_err(|e| {
                 anyhow::Error::msg(e)
                     .context("failed to write replayed event to stdout during print-mode resume")
             })?;
@@ -294,6 +314,10 @@ async fn run_inner<W: Write + Send + 'static>(
             cfg.model_key.clone(),
         )
     };
+    let fallback_models = run_config
+        .lock()
+        .expect("run config mutex poisoned")
+        .fallback_chain();
     let BuiltAgent {
         mut agent,
         env,
@@ -306,6 +330,17 @@ async fn run_inner<W: Write + Send + 'static>(
         thinking.clone(),
         agent_speed,
     );
+    agent.set_fallback_models(fallback_models);
+    // Nobody is there to confirm a repeated prompt (a script resuming
+    // with the prompt it just sent), so the agent drops it instead.
+    agent.set_skip_repeated_prompts(config.guard_repeated_prompts);
+    for warning in env
+        .context_files
+        .iter()
+        .filter_map(|f| f.truncation_warning())
+    {
+        eprintln!("aj: warning: {warning}");
+    }
     for d in &env.skill_diagnostics {
         eprintln!("aj: warning: {d}");
     }
@@ -356,13 +391,17 @@ async fn run_inner<W: Write + Send + 'static>(
     // happens after `prompt` returns when we walk
     // `agent.messages()`. The listener is therefore essentially a
     // no-op in text mode but keeps the structure symmetrical.
-    let _stream_handle = match args.format {
-        PrintFormat::Json => Some(agent.subscribe(json_event_listener(Arc::clone(&out)))),
-        PrintFormat::Text => None,
-    };
+    let _stream_handle = structured_listener(args.format, &out).map(|l| agent.subscribe(l));
 
     let _persistence_handle = agent.subscribe(persistence_listener(Arc::clone(&log)));
 
+    // `--preload` entries land after the listeners are up so they are
+    // persisted and streamed like the turn that follows them.
+    agent
+        .preload(preload)
+        .await
+        .map_err(|err| anyhow!("--preload failed: {err}"))?;
+
     // Drive a single prompt, then map its outcome to the process exit
     // status (`finish_result` below documents the error buckets).
     //
@@ -384,6 +423,17 @@ async fn run_inner<W: Write + Send + 'static>(
     // and queued-work paths don't apply to a single headless turn.
     // Recovery runs before the background-task teardown below so the
     // retried turn can still use tools.
+    // The agent drops a repeated prompt on its own (see
+    // `set_skip_repeated_prompts` above); check up front too, so text
+    // mode can say so on stderr and doesn't print the previous answer
+    // as if it were a new one.
+    let repeat_skipped = config.guard_repeated_prompts
+        && matches!(content.first(), Some(UserContent::Text(t)) if agent.repeats_last_prompt(&t.text));
+    if repeat_skipped {
+        eprintln!(
+            "aj: warning: the prompt repeats the previous message; skipped (guard_repeated_prompts)"
+        );
+    }
     let policy = crate::turn::TurnPolicy {
         recover_overflow: config.auto_compact,
         auto_threshold: None,
@@ -410,8 +460,9 @@ async fn run_inner<W: Write + Send + 'static>(
     finish_result(prompt_result)?;
 
     // Text mode: print the final assistant message's visible text.
-    // JSON mode already streamed every event; nothing else to do.
-    if matches!(args.format, PrintFormat::Text) {
+    // The structured modes already streamed every event; nothing else
+    // to do.
+    if matches!(args.format, PrintFormat::Text) && !repeat_skipped {
         print_final_assistant_text(&agent, &out)?;
     }
 
@@ -442,6 +493,20 @@ fn finish_result(prompt_result: Result<(), TurnError>) -> Result<()> {
     }
 }
 
+/// The stdout listener for a structured output `format`, or `None` for
+/// text mode (which renders after the run instead).
+fn structured_listener<W: Write + Send + 'static>(
+    format: PrintFormat,
+    out: &Arc<Mutex<W>>,
+) -> Option<Listener> {
+    match format {
+        PrintFormat::Text => None,
+        PrintFormat::Json => Some(json_event_listener(Arc::clone(out))),
+        PrintFormat::Ndjson => Some(ndjson_event_listener(Arc::clone(out))),
+        PrintFormat::RawJson => Some(raw_json_listener(Arc::clone(out))),
+    }
+}
+
 /// Build a [`Listener`] that writes each event as one JSONL line into
 /// `out`. The listener is synchronous (`listener_from_sync`), and the
 /// bus awaits it inline, so events appear in the sink in the same order
@@ -464,12 +529,7 @@ fn json_event_listener<W: Write + Send + 'static>(out: Arc<Mutex<W>>) -> Listene
             return;
         }
         match serde_json::to_string(event) {
-            Ok(line) => {
-                let mut w = out.lock().expect("print sink mutex poisoned");
-                if let Err(e) = writeln!(w, "{line}") {
-                    eprintln!("aj: failed to write event to stdout: {e}");
-                }
-            }
+            Ok(line) => write_line(&out, &line),
             Err(e) => {
                 // A non-serializable payload would land here. Surface
                 // enough detail to debug but don't kill the run.
@@ -479,6 +539,136 @@ fn json_event_listener<W: Write + Send + 'static>(out: Arc<Mutex<W>>) -> Listene
     })
 }
 
+/// Build a [`Listener`] that writes the NDJSON digest of each event
+/// (see [`ndjson_records`]) into `out`, one compact object per line.
+/// Same ordering and log-and-continue error policy as
+/// [`json_event_listener`].
+fn ndjson_event_listener<W: Write + Send + 'static>(out: Arc<Mutex<W>>) -> Listener {
+    listener_from_sync(move |event: &AgentEvent| {
+        for record in ndjson_records(event) {
+            write_line(&out, &record.to_string());
+        }
+    })
+}
+
+/// The NDJSON records for one event: zero for events the format
+/// doesn't carry, one per visible text or thinking block for a
+/// finalized assistant message, and one otherwise.
+fn ndjson_records(event: &AgentEvent) -> Vec<serde_json::Value> {
+    use aj_models::types::{AssistantContent, Message, UserContent};
+    use serde_json::json;
+
+    match event {
+        AgentEvent::MessageEnd { agent_id, message } => {
+            let Some(Message::Assistant(message)) = message.as_wire() else {
+                return Vec::new();
+            };
+            message
+                .content
+                .iter()
+                .filter_map(|block| match block {
+                    AssistantContent::Text(t) if !t.text.is_empty() => Some(json!({
+                        "event": "assistant_text",
+                        "agent": agent_id,
+                        "text": t.text,
+                    })),
+                    AssistantContent::Thinking(t) if !t.redacted && !t.thinking.is_empty() => {
+                        Some(json!({
+                            "event": "thinking",
+                            "agent": agent_id,
+                            "text": t.thinking,
+                        }))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+        AgentEvent::ToolExecutionStart {
+            agent_id,
+            call_id,
+            tool,
+            args,
+        } => vec![json!({
+            "event": "tool_use",
+            "agent": agent_id,
+            "call_id": call_id,
+            "tool": tool,
+            "input": args,
+        })],
+        AgentEvent::ToolExecutionEnd {
+            agent_id,
+            call_id,
+            tool,
+            content,
+            is_error,
+            ..
+        } => {
+            let output = content
+                .iter()
+                .filter_map(|c| match c {
+                    UserContent::Text(t) => Some(t.text.as_str()),
+                    UserContent::Image(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            vec![json!({
+                "event": "tool_result",
+                "agent": agent_id,
+                "call_id": call_id,
+                "tool": tool,
+                "is_error": is_error,
+                "output": output,
+            })]
+        }
+        AgentEvent::UsageUpdate { agent_id, usage } => vec![json!({
+            "event": "usage",
+            "agent": agent_id,
+            "usage": usage,
+        })],
+        _ => Vec::new(),
+    }
+}
+
+/// Build a [`Listener`] that writes each finalized message as one
+/// `{"agent", "message"}` line into `out` (see the module docs). Same
+/// ordering and log-and-continue error policy as
+/// [`json_event_listener`].
+fn raw_json_listener<W: Write + Send + 'static>(out: Arc<Mutex<W>>) -> Listener {
+    listener_from_sync(move |event: &AgentEvent| {
+        let AgentEvent::MessageEnd { agent_id, message } = event else {
+            return;
+        };
+        let Some(message) = message.as_wire() else {
+            return;
+        };
+        let record = RawJsonRecord {
+            agent: agent_id,
+            message,
+        };
+        match serde_json::to_string(&record) {
+            Ok(line) => write_line(&out, &line),
+            Err(e) => eprintln!("aj: failed to serialize message: {e}"),
+        }
+    })
+}
+
+/// One line of [`PrintFormat::RawJson`] output.
+#[derive(serde::Serialize)]
+struct RawJsonRecord<'a> {
+    agent: &'a aj_agent::events::AgentId,
+    message: &'a aj_models::types::Message,
+}
+
+/// Write `line` plus a newline to `out` and flush, so a consumer
+/// reading a pipe sees each event as soon as it fires. Write errors
+/// (e.g. a closed pipe) are reported on stderr, not propagated.
+fn write_line<W: Write>(out: &Mutex<W>, line: &str) {
+    let mut w = out.lock().expect("print sink mutex poisoned");
+    if let Err(e) = writeln!(w, "{line}").and_then(|()| w.flush()) {
+        eprintln!("aj: failed to write event to stdout: {e}");
+    }
+}
+
 /// Walk back through the agent's transcript to find the most recent
 /// assistant message, then write every visible text block on its own
 /// line into `out`. Callers piping the output into another process get
@@ -710,6 +900,97 @@ mod tests {
         );
     }
 
+    /// NDJSON mode writes only the digest records, each a compact
+    /// object with an `"event"` discriminator, in emit order: the
+    /// assistant text and usage of each turn, and the tool call with
+    /// its (here failed) result in between.
+    #[tokio::test]
+    async fn ndjson_mode_streams_event_records_for_a_tool_turn() {
+        let (out, _persistence, _sessions) = drive(&[
+            "--print",
+            "--format",
+            "ndjson",
+            "--scripted",
+            "tool-use-parse-error",
+            "hello",
+        ])
+        .await;
+
+        let records: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| {
+                serde_json::from_str(line)
+                    .unwrap_or_else(|e| panic!("each line is a JSON object ({e}): {line:?}"))
+            })
+            .collect();
+        let events: Vec<&str> = records
+            .iter()
+            .map(|r| r["event"].as_str().expect("every record has an event"))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                "assistant_text",
+                "usage",
+                "tool_use",
+                "tool_result",
+                "assistant_text",
+                "usage",
+            ],
+            "records in emit order:\n{out}"
+        );
+        assert_eq!(records[0]["agent"], "main");
+        assert_eq!(records[2]["tool"], "bash");
+        assert_eq!(records[3]["call_id"], records[2]["call_id"]);
+        assert_eq!(records[3]["is_error"], true);
+    }
+
+    /// Raw JSON mode writes one record per finalized message — the
+    /// prompt, each assistant reply and the tool result — carrying the
+    /// full wire message, usage and stop reason included.
+    #[tokio::test]
+    async fn raw_json_mode_streams_finalized_wire_messages() {
+        let (out, _persistence, _sessions) = drive(&[
+            "--print",
+            "--format",
+            "raw-json",
+            "--scripted",
+            "tool-use-parse-error",
+            "hello",
+        ])
+        .await;
+
+        let records: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| {
+                serde_json::from_str(line)
+                    .unwrap_or_else(|e| panic!("each line is a JSON object ({e}): {line:?}"))
+            })
+            .collect();
+        let roles: Vec<&str> = records
+            .iter()
+            .map(|r| {
+                r["message"]["role"]
+                    .as_str()
+                    .expect("every record has a role")
+            })
+            .collect();
+        assert_eq!(
+            roles,
+            ["user", "assistant", "tool_result", "assistant"],
+            "records in emit order:\n{out}"
+        );
+        assert!(records.iter().all(|r| r["agent"] == "main"));
+        let reply = &records[1]["message"];
+        assert!(reply["usage"].is_object(), "{reply}");
+        assert!(reply["stop_reason"].is_string(), "{reply}");
+        assert!(reply["content"].is_array(), "{reply}");
+        // The message round-trips into the SDK type unchanged.
+        let parsed: aj_models::types::Message =
+            serde_json::from_value(reply.clone()).expect("a wire message");
+        assert_eq!(serde_json::to_value(parsed).unwrap(), *reply);
+    }
+
     /// Resuming a session in JSON mode drains the persisted history
     /// through the JSON sink before the new turn's events, so a consumer
     /// sees the full trace in emit order. This is print's most intricate
@@ -11,11 +11,13 @@
 //! registry, bus subscriptions, and event pump. Print mode adds the
 //! JSONL / persistence listeners and the one-shot turn.
 
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex as StdMutex};
 
+use aj_agent::audit::ToolAuditLog;
 use aj_agent::message::AgentMessage;
-use aj_agent::{Agent, AgentSeed};
-use aj_conf::{AgentEnv, Config, ConfigSpeed};
+use aj_agent::{Agent, AgentSeed, FallbackModel};
+use aj_conf::{AgentEnv, Config, ConfigSpeed, PromptStyle};
 use aj_models::auth::AuthStorage;
 use aj_models::provider::Provider;
 use aj_models::registry::{ModelInfo, ModelRegistry, validate_thinking_level};
@@ -27,6 +29,9 @@ use aj_models::{
 use aj_session::{
     ConversationLog, ConversationPersistence, ThreadFilter, repair_interrupted_tool_uses,
 };
+use aj_tools::protected::ProtectedPaths;
+use aj_tools::roots::AllowedRoots;
+use aj_tools::trash::Trash;
 use aj_tools::{BuiltinToolOptions, builtin_tools};
 use anyhow::{Context, Result};
 
@@ -82,6 +87,26 @@ pub(crate) struct RunConfigSnapshot {
     /// `stream_options` from registry defaults, which would otherwise
     /// drop it. `None` until the log is opened in [`prepare_log`].
     pub(crate) session_id: Option<String>,
+    /// Models the main agent falls back to when its model stays
+    /// overloaded, resolved from `fallback_models`. Empty on the
+    /// scripted path.
+    pub(crate) fallback_models: Vec<FallbackModel>,
+}
+
+impl RunConfigSnapshot {
+    /// The fallback chain minus the model the snapshot runs on, which
+    /// a `/model` pick or a resumed session may have made one of the
+    /// entries.
+    pub(crate) fn fallback_chain(&self) -> Vec<FallbackModel> {
+        self.fallback_models
+            .iter()
+            .filter(|m| {
+                m.model_info.provider != self.model_info.provider
+                    || m.model_info.id != self.model_info.id
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 /// Dependencies for resume-time settings restoration: the model
@@ -117,6 +142,7 @@ fn build_run_config(
         // Filled in by `prepare_log` once the log (and thus the session
         // id) exists; the initial resolve runs before then.
         session_id: None,
+        fallback_models: Vec::new(),
     }
 }
 
@@ -136,6 +162,7 @@ pub(crate) fn build_initial_run_config(
     speed: Option<Speed>,
 ) -> Result<(RunConfigSnapshot, Option<RestoreContext>)> {
     let selection = ModelSelection::merge(args, config);
+    crate::model::init_request_defaults(config);
     if let Some(name) = &args.scripted {
         let crate::scripted::ResolvedScriptedModel {
             provider,
@@ -159,8 +186,10 @@ pub(crate) fn build_initial_run_config(
             stream_options,
         } = crate::model::resolve(&registry, auth, &selection, speed)
             .context("failed to resolve model from registry")?;
+        let fallback_models =
+            crate::model::resolve_fallbacks(&registry, auth, config, &model_info.provider, speed)?;
         let model_key = (model_info.provider.clone(), model_info.id.clone());
-        let run_config = build_run_config(
+        let mut run_config = build_run_config(
             config,
             provider,
             model_info,
@@ -168,6 +197,7 @@ pub(crate) fn build_initial_run_config(
             model_key,
             speed,
         );
+        run_config.fallback_models = fallback_models;
         let restore = RestoreContext {
             registry: Arc::new(registry),
             auth: auth.clone(),
@@ -335,6 +365,42 @@ pub(crate) struct BuiltAgent {
     pub(crate) include_skills: bool,
 }
 
+/// The trash directory from `trash_dir`, whether or not
+/// `trash_writes` is on: versions kept earlier stay restorable after
+/// the option is switched off.
+pub(crate) fn trash(config: &Config) -> Trash {
+    config
+        .trash_dir
+        .as_deref()
+        .map_or_else(Trash::default, Trash::new)
+}
+
+/// The directories `confine_paths` keeps tools inside: `allowed_roots`
+/// resolved against the working directory, or the working directory
+/// and its git root when none are configured, plus the directory of
+/// every enabled skill so its files stay readable. Unrestricted when
+/// `confine_paths` is off.
+fn allowed_roots(config: &Config, env: &AgentEnv) -> AllowedRoots {
+    if !config.confine_paths {
+        return AllowedRoots::default();
+    }
+    let cwd = &env.working_directory;
+    let mut roots: Vec<PathBuf> = if config.allowed_roots.is_empty() {
+        std::iter::once(cwd.clone())
+            .chain(env.git_root_directory.clone())
+            .collect()
+    } else {
+        config.allowed_roots.iter().map(|r| cwd.join(r)).collect()
+    };
+    roots.extend(
+        env.skills
+            .iter()
+            .filter(|skill| skill.enabled)
+            .filter_map(|skill| skill.path.parent().map(Path::to_path_buf)),
+    );
+    AllowedRoots::new(roots)
+}
+
 /// Construct a fresh, not-yet-shared [`Agent`] from the persisted
 /// config and a resolved provider bundle.
 ///
@@ -352,14 +418,27 @@ pub(crate) fn build_agent(
     thinking: Option<ThinkingConfig>,
     speed: Option<Speed>,
 ) -> BuiltAgent {
+    let mut env = AgentEnv::new(SYSTEM_PROMPT, &config.disabled_skills);
+    env.cap_context_files(config.max_context_file_bytes);
+    env.style = PromptStyle::from_config(config);
     let tools = builtin_tools(
         &BuiltinToolOptions {
             image_auto_resize: config.image_auto_resize,
+            bash_preserve_ansi: config.bash_preserve_ansi,
+            bash_timeout_secs: config.bash_timeout_secs,
+            env_tool_values: config.env_tool_values,
+            normalize_on_write: config.normalize_on_write,
+            respect_editorconfig: config.respect_editorconfig,
+            confirm_with_listing: config.confirm_with_listing,
+            max_file_size: config.max_file_size,
+            protected_paths: ProtectedPaths::new(&config.protected_paths),
+            read_fuzzy_paths: config.read_fuzzy_paths,
+            trash: config.trash_writes.then(|| trash(config)),
+            allowed_roots: allowed_roots(config, &env),
         },
         &config.disabled_tools,
     );
     let include_skills = tools.iter().any(|tool| tool.name == "read_file");
-    let env = AgentEnv::new(SYSTEM_PROMPT, &config.disabled_skills);
     let mut agent = Agent::with_provider(
         env.working_directory.clone(),
         tools,
@@ -372,6 +451,14 @@ pub(crate) fn build_agent(
         None,
     );
     agent.set_block_images(config.image_block);
+    agent.set_tool_audit_log(
+        config
+            .tool_audit_log
+            .as_deref()
+            .map(|path| ToolAuditLog::new(env.working_directory.join(path))),
+    );
+    agent.set_nudge_empty_responses(config.nudge_empty_responses);
+    agent.set_retry_partial_streams(config.retry_partial_streams);
     agent.set_default_thinking(thinking);
     agent.set_speed(speed);
     BuiltAgent {
@@ -3,14 +3,15 @@
 //! The `aj-agent` runtime takes a finished system-prompt string and
 //! never reaches for the host's configuration or filesystem. The
 //! binary owns the [`AgentEnv`] (base prompt, AGENTS.md/CLAUDE.md
-//! context files, discovered skills, environment summary) and turns
-//! it into that string here, once, before seeding the agent.
+//! context files, style preferences, discovered skills, environment
+//! summary) and turns it into that string here, once, before seeding
+//! the agent.
 
-use aj_conf::AgentEnv;
+use aj_conf::{AgentEnv, ConfigStyleTerseness, PromptStyle};
 
 /// Assemble the full system prompt: the base prompt, the stitched
-/// context files, the optional skills listing, and the trailing
-/// environment block.
+/// context files, the optional `<style>` block, the optional skills
+/// listing, and the trailing environment block.
 ///
 /// `include_skills` gates the skills listing. Skills are progressive
 /// disclosure reachable only with a `read_file` tool, so the caller
@@ -30,6 +31,11 @@ pub fn assemble_system_prompt(env: &AgentEnv, include_skills: bool) -> String {
         ));
     }
 
+    if let Some(block) = style_block(&env.style) {
+        text.push_str("\n\n");
+        text.push_str(&block);
+    }
+
     if include_skills {
         if let Some(block) = aj_conf::skills::format_skills_for_prompt(&env.skills) {
             text.push_str("\n\n");
@@ -44,11 +50,48 @@ pub fn assemble_system_prompt(env: &AgentEnv, include_skills: bool) -> String {
     text
 }
 
+/// Render the `style_*` config values as a `<style>` block, one
+/// instruction per line, or `None` when none is set.
+fn style_block(style: &PromptStyle) -> Option<String> {
+    let mut lines = Vec::new();
+    match style.terseness {
+        Some(ConfigStyleTerseness::Terse) => lines.push(
+            "- Keep answers short: lead with the result, skip preamble and recaps.".to_string(),
+        ),
+        Some(ConfigStyleTerseness::Detailed) => lines.push(
+            "- Give detailed answers: explain your reasoning and the trade-offs you weighed."
+                .to_string(),
+        ),
+        None => {}
+    }
+    if style.explain_first {
+        lines.push(
+            "- Before calling tools, say in a sentence what you are about to do and why."
+                .to_string(),
+        );
+    }
+    if let Some(language) = style.language.as_deref().map(str::trim)
+        && !language.is_empty()
+    {
+        lines.push(format!(
+            "- Write your answers in {language}. Code, identifiers and commit messages stay as \
+             they are."
+        ));
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "The user's preferences for how you communicate:\n<style>\n{}\n</style>",
+        lines.join("\n")
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use aj_conf::{AgentEnv, SystemPrompt, SystemPromptSource};
+    use aj_conf::{AgentEnv, ConfigStyleTerseness, PromptStyle, SystemPrompt, SystemPromptSource};
 
     use super::assemble_system_prompt;
 
@@ -56,6 +99,7 @@ mod tests {
         AgentEnv {
             working_directory: PathBuf::from("/tmp"),
             git_root_directory: None,
+            project_types: Vec::new(),
             operating_system: "test".to_string(),
             today_date: "2024-01-01".to_string(),
             system_prompt: SystemPrompt {
@@ -65,6 +109,7 @@ mod tests {
             context_files: Vec::new(),
             skills,
             skill_diagnostics: Vec::new(),
+            style: PromptStyle::default(),
         }
     }
 
@@ -99,4 +144,25 @@ mod tests {
         let prompt = assemble_system_prompt(&env, false);
         assert!(!prompt.contains("<available_skills>"));
     }
+
+    #[test]
+    fn style_block_is_rendered_only_when_set() {
+        let mut env = env_with_skills(Vec::new());
+        assert!(!assemble_system_prompt(&env, true).contains("<style>"));
+
+        env.style = PromptStyle {
+            terseness: Some(ConfigStyleTerseness::Terse),
+            explain_first: true,
+            language: Some("German".to_string()),
+        };
+        let prompt = assemble_system_prompt(&env, true);
+        let block = &prompt[prompt.find("<style>").unwrap()..prompt.find("</style>").unwrap()];
+        assert!(block.contains("Keep answers short"), "{block}");
+        assert!(block.contains("Before calling tools"), "{block}");
+        assert!(block.contains("in German"), "{block}");
+        assert!(
+            prompt.find("</style>").unwrap() < prompt.find("<env>").unwrap(),
+            "style block must come before the env block"
+        );
+    }
 }
@@ -3,9 +3,10 @@
 //! The fetching machinery (the [`UsageSource`] trait and its
 //! implementations) lives in `aj-models`; this module holds the
 //! binary's UX around it: [`collect_usage`] turns every registered
-//! source into render-ready rows for the `/usage` overlay, and the
-//! formatting helpers render utilization and reset times the way the
-//! overlay shows them.
+//! source into render-ready rows for the `/usage` overlay (adding the
+//! Anthropic API rate limits the process last saw, see
+//! [`add_api_rate_limits`]), and the formatting helpers render
+//! utilization and reset times the way the overlay shows them.
 //!
 //! [`UsageSource`]: aj_models::usage::UsageSource
 
@@ -13,8 +14,9 @@ use std::sync::Arc;
 
 use chrono::{Datelike, Local, TimeZone, Utc};
 
+use aj_models::anthropic::RateLimits;
 use aj_models::auth::AuthStorage;
-use aj_models::usage::{ProviderUsage, UsageReport, default_usage_sources};
+use aj_models::usage::{ProviderUsage, UsageReport, UsageWindow, default_usage_sources};
 
 /// Per-source timeout. The Anthropic source's HTTP request already
 /// caps itself at 5 s; this outer bound also covers credential
@@ -92,10 +94,43 @@ pub async fn collect_usage(auth: &AuthStorage) -> Vec<ProviderUsageStatus> {
         }
     }
 
+    if let Some(limits) = crate::model::latest_rate_limits() {
+        add_api_rate_limits(&mut statuses, &limits);
+    }
+
     statuses.sort_by(|a, b| a.provider_id.cmp(&b.provider_id));
     statuses
 }
 
+/// Append the API rate-limit budgets from the last Anthropic response
+/// to the `anthropic` rows, one window per bucket labeled with what is
+/// left, e.g. `"Requests/min (12 of 50 left)"`. An API-key login can't
+/// report plan usage, so its "not available" row gives way to these
+/// windows; a failed fetch keeps its error row.
+fn add_api_rate_limits(statuses: &mut [ProviderUsageStatus], limits: &RateLimits) {
+    let windows: Vec<UsageWindow> = limits
+        .buckets()
+        .map(|(label, bucket)| UsageWindow {
+            label: format!("{label} ({} of {} left)", bucket.remaining, bucket.limit),
+            used: bucket.used(),
+            resets_at: bucket.reset_at_ms,
+        })
+        .collect();
+    let Some(status) = statuses.iter_mut().find(|s| s.provider_id == "anthropic") else {
+        return;
+    };
+    match &mut status.outcome {
+        UsageOutcome::Usage(usage) => usage.windows.extend(windows),
+        UsageOutcome::Error(_) => {}
+        outcome => {
+            *outcome = UsageOutcome::Usage(ProviderUsage {
+                windows,
+                notes: Vec::new(),
+            });
+        }
+    }
+}
+
 /// Render a window's status, e.g.
 /// `"12% used · resets 17:00 (Europe/Berlin)"`.
 pub fn format_window_status(used: f64, resets_at: Option<i64>, now_ms: i64) -> String {
@@ -202,6 +237,43 @@ mod tests {
         local_tz_label(dt.offset().local_minus_utc())
     }
 
+    #[test]
+    fn api_rate_limits_replace_the_unsupported_row() {
+        use aj_models::anthropic::RateLimitBucket;
+
+        let limits = RateLimits {
+            requests: Some(RateLimitBucket {
+                limit: 50,
+                remaining: 12,
+                reset_at_ms: Some(1_000),
+            }),
+            tokens: None,
+            input_tokens: None,
+            output_tokens: None,
+        };
+        let mut statuses = vec![
+            ProviderUsageStatus {
+                provider_id: "anthropic".into(),
+                outcome: UsageOutcome::Unsupported {
+                    reason: "subscription only".into(),
+                },
+            },
+            ProviderUsageStatus {
+                provider_id: "openai".into(),
+                outcome: UsageOutcome::NoSource,
+            },
+        ];
+        add_api_rate_limits(&mut statuses, &limits);
+        let UsageOutcome::Usage(usage) = &statuses[0].outcome else {
+            panic!("expected usage: {:?}", statuses[0].outcome);
+        };
+        assert_eq!(usage.windows.len(), 1);
+        assert_eq!(usage.windows[0].label, "Requests/min (12 of 50 left)");
+        assert_eq!(usage.windows[0].used, 0.76);
+        assert_eq!(usage.windows[0].resets_at, Some(1_000));
+        assert!(matches!(statuses[1].outcome, UsageOutcome::NoSource));
+    }
+
     #[test]
     fn utc_offset_labels() {
         assert_eq!(utc_offset_label(0), "UTC");
@@ -375,7 +375,7 @@ async fn replay_renders_bash_tool_identically_to_live() {
     });
     assert_live_matches_replay(
         "bash",
-        BashTool.into(),
+        BashTool::default().into(),
         "tu-bash",
         "bash",
         input,
@@ -405,7 +405,7 @@ async fn replay_renders_edit_file_tool_identically_to_live() {
     let (session_id, live_events) = drive_live_turn(
         sessions_dir.path(),
         working_dir.path(),
-        EditFileTool.into(),
+        EditFileTool::default().into(),
         "tu-edit",
         "edit_file",
         input,
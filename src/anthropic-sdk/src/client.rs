@@ -1,12 +1,15 @@
 use std::pin::Pin;
+use std::sync::Arc;
 use std::time::Duration;
 
 use eventsource_stream::Eventsource;
 use futures::{Stream, StreamExt};
 use reqwest::Client as ReqwestClient;
 use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 use crate::messages::{ApiError, ApiErrorResponse, Message, Messages, ServerSentEvent};
+use crate::rate_limit::RateLimitTracker;
 use crate::stealth::{
     CLAUDE_CODE_VERSION, apply_request_transformations, collect_caller_tool_names,
     reverse_map_event, reverse_map_message,
@@ -51,6 +54,14 @@ pub struct Client {
     /// header. The provider sets this when reasoning is enabled and
     /// the model is non-adaptive (adaptive models reject the header).
     interleaved_thinking: bool,
+    /// Shared cap on in-flight requests; see
+    /// [`Self::with_concurrency_limit`]. `None` means unlimited.
+    concurrency_limit: Option<Arc<Semaphore>>,
+    /// `user-agent` sent in API-key mode; see [`Self::with_user_agent`].
+    user_agent: Option<String>,
+    /// Shared record of the latest rate-limit headers; see
+    /// [`Self::with_rate_limit_tracker`].
+    rate_limits: Option<RateLimitTracker>,
 }
 
 impl Client {
@@ -70,6 +81,9 @@ impl Client {
             base_url,
             beta_headers: Vec::new(),
             interleaved_thinking: false,
+            concurrency_limit: None,
+            user_agent: None,
+            rate_limits: None,
         }
     }
 
@@ -110,10 +124,65 @@ impl Client {
         self.interleaved_thinking = enabled;
     }
 
+    /// Cap in-flight `/v1/messages` requests with `limit`: each
+    /// request waits for a permit before it is sent and holds it until
+    /// the response (for a stream, the whole event stream) is done.
+    /// The semaphore is shared, so clients built for separate calls
+    /// (the main agent and its sub-agents) draw from one pool.
+    pub fn with_concurrency_limit(mut self, limit: Arc<Semaphore>) -> Self {
+        self.concurrency_limit = Some(limit);
+        self
+    }
+
+    /// Send `user_agent` as the `user-agent` header. Applies in
+    /// API-key mode only: OAuth requests keep the Claude Code CLI
+    /// identity the subscription endpoint expects.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Record the `anthropic-ratelimit-*` headers of every
+    /// `/v1/messages` response in `tracker`, and, if the tracker
+    /// throttles, hold each request until nearly spent budgets reset.
+    /// Like the concurrency limit, the tracker is shared across
+    /// clients.
+    pub fn with_rate_limit_tracker(mut self, tracker: RateLimitTracker) -> Self {
+        self.rate_limits = Some(tracker);
+        self
+    }
+
     pub fn base_url(&self) -> String {
         self.base_url.clone()
     }
 
+    /// Wait for a slot under [`Self::with_concurrency_limit`]. `None`
+    /// when no limit is set.
+    async fn acquire_permit(&self) -> Option<OwnedSemaphorePermit> {
+        let limit = self.concurrency_limit.clone()?;
+        limit.acquire_owned().await.ok()
+    }
+
+    /// Sleep out a throttling [`RateLimitTracker`]'s delay, if any.
+    /// Runs before [`Self::acquire_permit`] so a held request doesn't
+    /// occupy a concurrency slot while it waits.
+    async fn wait_for_rate_limits(&self) {
+        let Some(delay) = self.rate_limits.as_ref().and_then(RateLimitTracker::delay) else {
+            return;
+        };
+        tracing::info!(
+            delay_ms = delay.as_millis(),
+            "anthropic-sdk: rate-limit budget nearly spent, delaying request"
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    fn observe_rate_limits(&self, response: &reqwest::Response) {
+        if let Some(tracker) = &self.rate_limits {
+            tracker.observe(response.headers());
+        }
+    }
+
     /// Compute the full set of beta headers for this request, in
     /// declaration order: caller-configured first, then the always-on
     /// `fine-grained-tool-streaming` beta, then OAuth-required betas
@@ -153,6 +222,9 @@ impl Client {
         match self.auth_mode {
             AuthMode::ApiKey => {
                 builder = builder.header("x-api-key", self.api_key.clone());
+                if let Some(user_agent) = &self.user_agent {
+                    builder = builder.header("user-agent", user_agent.clone());
+                }
             }
             AuthMode::OAuth => {
                 builder = builder
@@ -216,7 +288,10 @@ impl Client {
         self.debug_log_request(&messages);
         let request_builder = self.build_request().json(&messages);
 
+        self.wait_for_rate_limits().await;
+        let _permit = self.acquire_permit().await;
         let response = request_builder.send().await?;
+        self.observe_rate_limits(&response);
 
         let status = response.status();
         if status.is_success() {
@@ -265,7 +340,10 @@ impl Client {
         self.debug_log_request(&messages);
         let request_builder = self.build_request().json(&messages);
 
+        self.wait_for_rate_limits().await;
+        let permit = self.acquire_permit().await;
         let response = request_builder.send().await?;
+        self.observe_rate_limits(&response);
 
         let status = response.status();
         if status.is_success() {
@@ -303,7 +381,7 @@ impl Client {
                     }
                 }
             });
-            return Ok(stream.boxed());
+            return Ok(hold_permit(stream, permit).boxed());
         }
 
         // Capture status + Retry-After before consuming the response
@@ -368,6 +446,18 @@ impl Client {
     }
 }
 
+/// Keep `permit` alive for as long as `stream` is, so a streamed
+/// response occupies its concurrency slot until the consumer drops it.
+fn hold_permit<S: Stream>(
+    stream: S,
+    permit: Option<OwnedSemaphorePermit>,
+) -> impl Stream<Item = S::Item> {
+    stream.map(move |item| {
+        let _held = &permit;
+        item
+    })
+}
+
 /// Extract the raw `Retry-After` header value, if present and printable.
 fn retry_after_header(response: &reqwest::Response) -> Option<String> {
     response
@@ -562,4 +652,39 @@ mod tests {
         assert_eq!(err.http_status(), Some(500));
         assert!(err.to_string().contains("upstream boom"), "got: {err}");
     }
+
+    #[tokio::test]
+    async fn hold_permit_releases_the_slot_when_the_stream_is_dropped() {
+        let limit = Arc::new(Semaphore::new(1));
+        let client = Client::new(None, "sk-ant-12345".to_string())
+            .with_concurrency_limit(Arc::clone(&limit));
+        let permit = client.acquire_permit().await;
+        assert!(permit.is_some());
+
+        let mut stream = hold_permit(futures::stream::iter([1, 2]), permit).boxed();
+        assert_eq!(stream.next().await, Some(1));
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.next().await, None);
+        // Exhausted but not dropped: the slot is still taken.
+        assert_eq!(limit.available_permits(), 0);
+        drop(stream);
+        assert_eq!(limit.available_permits(), 1);
+    }
+
+    #[test]
+    fn user_agent_applies_in_api_key_mode_only() {
+        let user_agent = |client: Client| {
+            let request = client.build_request().build().expect("request builds");
+            request
+                .headers()
+                .get("user-agent")
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+        let api_key = Client::new(None, "sk-ant-12345".to_string()).with_user_agent("aj/1.0");
+        assert_eq!(user_agent(api_key).as_deref(), Some("aj/1.0"));
+
+        let oauth = Client::new(None, "sk-ant-oat01-12345".to_string()).with_user_agent("aj/1.0");
+        let header = user_agent(oauth).expect("oauth sends a user-agent");
+        assert!(header.starts_with("claude-cli/"), "got {header}");
+    }
 }
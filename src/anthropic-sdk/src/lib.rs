@@ -11,5 +11,6 @@
 
 pub mod client;
 pub mod messages;
+pub mod rate_limit;
 mod stealth;
 pub mod usage;
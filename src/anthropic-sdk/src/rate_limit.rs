@@ -0,0 +1,244 @@
+//! Rate-limit state reported by the API's `anthropic-ratelimit-*`
+//! response headers.
+//!
+//! Every response (success or error) carries the organization's
+//! current request and token budgets: a limit, what remains of it and
+//! when it refills, per bucket. A [`RateLimitTracker`] handed to
+//! [`crate::client::Client::with_rate_limit_tracker`] keeps the most
+//! recent snapshot so a UI can show it, and, when built with
+//! `throttle`, makes the client hold the next request until the reset
+//! of any bucket that is nearly spent instead of running into a 429.
+//!
+//! OAuth (subscription) responses report unified plan limits instead
+//! of these headers; for them the tracker simply stays empty.
+
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+
+/// Longest the client waits for a bucket to refill. A longer reset is
+/// left to the caller's 429 retry, which can honor `Retry-After` and
+/// be cancelled.
+pub const MAX_THROTTLE_DELAY: Duration = Duration::from_secs(60);
+
+/// One budget: how much of `limit` is left until `reset_at_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitBucket {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the bucket is fully replenished, unix milliseconds.
+    pub reset_at_ms: Option<i64>,
+}
+
+impl RateLimitBucket {
+    /// Whether at most 5% of the budget is left.
+    pub fn is_nearly_exhausted(&self) -> bool {
+        self.remaining.saturating_mul(20) <= self.limit
+    }
+
+    /// Fraction of the budget used, `0.0..=1.0`.
+    pub fn used(&self) -> f64 {
+        if self.limit == 0 {
+            return 1.0;
+        }
+        let spent = self.limit.saturating_sub(self.remaining);
+        let permille = spent.saturating_mul(1000) / self.limit;
+        f64::from(u32::try_from(permille).unwrap_or(1000)) / 1000.0
+    }
+}
+
+/// The budgets reported by one response. A bucket is `None` when its
+/// headers were absent or unparseable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Requests per minute.
+    pub requests: Option<RateLimitBucket>,
+    /// The most restrictive token limit currently in effect.
+    pub tokens: Option<RateLimitBucket>,
+    /// Input tokens per minute.
+    pub input_tokens: Option<RateLimitBucket>,
+    /// Output tokens per minute.
+    pub output_tokens: Option<RateLimitBucket>,
+}
+
+impl RateLimits {
+    /// Parse the `anthropic-ratelimit-*` headers; `None` when the
+    /// response carried none of them.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let limits = Self {
+            requests: bucket(headers, "requests"),
+            tokens: bucket(headers, "tokens"),
+            input_tokens: bucket(headers, "input-tokens"),
+            output_tokens: bucket(headers, "output-tokens"),
+        };
+        limits.buckets().next().is_some().then_some(limits)
+    }
+
+    /// The reported buckets with a display label, in the order above.
+    pub fn buckets(&self) -> impl Iterator<Item = (&'static str, &RateLimitBucket)> {
+        [
+            ("Requests/min", &self.requests),
+            ("Tokens/min", &self.tokens),
+            ("Input tokens/min", &self.input_tokens),
+            ("Output tokens/min", &self.output_tokens),
+        ]
+        .into_iter()
+        .filter_map(|(label, bucket)| bucket.as_ref().map(|b| (label, b)))
+    }
+
+    /// How long to hold the next request at `now_ms`: until the latest
+    /// reset among the nearly exhausted buckets, capped at
+    /// [`MAX_THROTTLE_DELAY`]. `None` when no bucket needs it.
+    pub fn throttle_delay(&self, now_ms: i64) -> Option<Duration> {
+        let wait_ms = self
+            .buckets()
+            .filter(|(_, bucket)| bucket.is_nearly_exhausted())
+            .filter_map(|(_, bucket)| bucket.reset_at_ms)
+            .map(|reset| reset - now_ms)
+            .max()?;
+        let wait = Duration::from_millis(u64::try_from(wait_ms).ok().filter(|ms| *ms > 0)?);
+        Some(wait.min(MAX_THROTTLE_DELAY))
+    }
+}
+
+fn bucket(headers: &HeaderMap, name: &str) -> Option<RateLimitBucket> {
+    let header = |field: &str| {
+        headers
+            .get(format!("anthropic-ratelimit-{name}-{field}"))
+            .and_then(|value| value.to_str().ok())
+    };
+    Some(RateLimitBucket {
+        limit: header("limit")?.trim().parse().ok()?,
+        remaining: header("remaining")?.trim().parse().ok()?,
+        reset_at_ms: header("reset")
+            .and_then(|reset| chrono::DateTime::parse_from_rfc3339(reset.trim()).ok())
+            .map(|reset| reset.timestamp_millis()),
+    })
+}
+
+/// The latest [`RateLimits`] seen by every client sharing this
+/// tracker. Cheap to clone; clones share the snapshot, so one tracker
+/// can serve a session and its sub-agents.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitTracker {
+    latest: Arc<StdMutex<Option<RateLimits>>>,
+    throttle: bool,
+}
+
+impl RateLimitTracker {
+    /// A tracker with no snapshot yet. With `throttle`, clients delay
+    /// requests per [`RateLimits::throttle_delay`].
+    pub fn new(throttle: bool) -> Self {
+        Self {
+            latest: Arc::default(),
+            throttle,
+        }
+    }
+
+    /// The most recent snapshot, if any response reported one.
+    pub fn latest(&self) -> Option<RateLimits> {
+        *self.lock()
+    }
+
+    pub(crate) fn observe(&self, headers: &HeaderMap) {
+        if let Some(limits) = RateLimits::from_headers(headers) {
+            *self.lock() = Some(limits);
+        }
+    }
+
+    /// How long to hold the next request; always `None` unless
+    /// throttling.
+    pub(crate) fn delay(&self) -> Option<Duration> {
+        if !self.throttle {
+            return None;
+        }
+        self.latest()?
+            .throttle_delay(chrono::Utc::now().timestamp_millis())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<RateLimits>> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn parses_the_reported_buckets() {
+        let limits = RateLimits::from_headers(&headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "49"),
+            ("anthropic-ratelimit-requests-reset", "2026-01-01T00:00:30Z"),
+            ("anthropic-ratelimit-input-tokens-limit", "40000"),
+            ("anthropic-ratelimit-input-tokens-remaining", "1000"),
+            // Half a bucket is no bucket.
+            ("anthropic-ratelimit-output-tokens-limit", "8000"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            limits.requests,
+            Some(RateLimitBucket {
+                limit: 50,
+                remaining: 49,
+                reset_at_ms: Some(1_767_225_630_000),
+            })
+        );
+        assert_eq!(limits.input_tokens.unwrap().reset_at_ms, None);
+        assert_eq!(limits.output_tokens, None);
+        let labels: Vec<_> = limits.buckets().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["Requests/min", "Input tokens/min"]);
+        assert_eq!(RateLimits::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn throttles_only_for_nearly_exhausted_buckets_until_reset() {
+        let bucket = |remaining, reset_at_ms| {
+            Some(RateLimitBucket {
+                limit: 100,
+                remaining,
+                reset_at_ms: Some(reset_at_ms),
+            })
+        };
+        let limits = RateLimits {
+            requests: bucket(50, 90_000),
+            tokens: bucket(5, 4_000),
+            input_tokens: bucket(0, 3_000),
+            output_tokens: None,
+        };
+        assert_eq!(limits.throttle_delay(1_000), Some(Duration::from_secs(3)));
+        // Resets already past: nothing to wait for.
+        assert_eq!(limits.throttle_delay(5_000), None);
+
+        let far = RateLimits {
+            requests: bucket(0, 600_000),
+            ..limits
+        };
+        assert_eq!(far.throttle_delay(0), Some(MAX_THROTTLE_DELAY));
+
+        let tracker = RateLimitTracker::new(false);
+        tracker.observe(&headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "0"),
+            ("anthropic-ratelimit-requests-reset", "2999-01-01T00:00:00Z"),
+        ]));
+        assert_eq!(tracker.latest().unwrap().requests.unwrap().remaining, 0);
+        // Tracking without throttling never delays.
+        assert_eq!(tracker.delay(), None);
+    }
+}
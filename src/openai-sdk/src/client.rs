@@ -1,9 +1,11 @@
 use std::pin::Pin;
+use std::sync::Arc;
 
 use eventsource_stream::Eventsource;
 use futures::{Stream, StreamExt};
 use reqwest::{Client as ReqwestClient, StatusCode};
 use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 use crate::types::chat_completions::{
     CreateChatCompletionRequest, CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
@@ -43,6 +45,9 @@ pub struct Client {
     /// Used by the Responses provider for session-correlation
     /// headers (`session_id`, `x-client-request-id`).
     extra_headers: Vec<(String, String)>,
+    /// Shared cap on in-flight requests; see
+    /// [`Self::with_concurrency_limit`]. `None` means unlimited.
+    concurrency_limit: Option<Arc<Semaphore>>,
 }
 
 impl Client {
@@ -54,6 +59,7 @@ impl Client {
             api_key,
             base_url,
             extra_headers: Vec::new(),
+            concurrency_limit: None,
         }
     }
 
@@ -63,6 +69,22 @@ impl Client {
         self
     }
 
+    /// Cap in-flight requests with `limit`: each request waits for a
+    /// permit before it is sent and holds it until the response (for a
+    /// stream, the whole event stream) is done. The semaphore is
+    /// shared, so clients built for separate calls draw from one pool.
+    pub fn with_concurrency_limit(mut self, limit: Arc<Semaphore>) -> Self {
+        self.concurrency_limit = Some(limit);
+        self
+    }
+
+    /// Wait for a slot under [`Self::with_concurrency_limit`]. `None`
+    /// when no limit is set.
+    async fn acquire_permit(&self) -> Option<OwnedSemaphorePermit> {
+        let limit = self.concurrency_limit.clone()?;
+        limit.acquire_owned().await.ok()
+    }
+
     fn apply_extra_headers(&self, mut builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         for (name, value) in &self.extra_headers {
             builder = builder.header(name.as_str(), value.as_str());
@@ -87,6 +109,7 @@ impl Client {
                 .json(&request),
         );
 
+        let _permit = self.acquire_permit().await;
         let response = request_builder.send().await?;
 
         let status = response.status();
@@ -127,12 +150,13 @@ impl Client {
                 .json(&request),
         );
 
+        let permit = self.acquire_permit().await;
         let response = request_builder.send().await?;
 
         let status = response.status();
         if status == StatusCode::OK {
             return Ok(parse_sse_stream::<CreateChatCompletionStreamResponse>(
-                response,
+                response, permit,
             ));
         }
 
@@ -160,6 +184,7 @@ impl Client {
                 .json(&request),
         );
 
+        let _permit = self.acquire_permit().await;
         let response = request_builder.send().await?;
 
         let status = response.status();
@@ -234,11 +259,12 @@ impl Client {
                 .json(&request),
         );
 
+        let permit = self.acquire_permit().await;
         let response = request_builder.send().await?;
 
         let status = response.status();
         if status == StatusCode::OK {
-            return Ok(parse_sse_stream::<ResponseStreamEvent>(response));
+            return Ok(parse_sse_stream::<ResponseStreamEvent>(response, permit));
         }
 
         let http_status = status.as_u16();
@@ -262,8 +288,12 @@ impl Client {
 /// - a protocol-level `error` event is not a transport error. It
 ///   deserializes into the event type (e.g. `ResponseStreamEvent::Error`)
 ///   and is yielded as `Ok` for the consumer to classify.
+///
+/// `permit` is the request's concurrency slot, if any; it is held until
+/// the returned stream is dropped.
 fn parse_sse_stream<T>(
     response: reqwest::Response,
+    permit: Option<OwnedSemaphorePermit>,
 ) -> Pin<Box<dyn Stream<Item = Result<T, ClientError>> + Send>>
 where
     T: serde::de::DeserializeOwned + Send + 'static,
@@ -277,6 +307,10 @@ where
                 Err(e) => Some(Err(ClientError::InternalError(e.to_string()))),
             }
         })
+        .map(move |item| {
+            let _held = &permit;
+            item
+        })
         .boxed()
 }
 